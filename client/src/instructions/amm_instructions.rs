@@ -9,7 +9,10 @@ use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program, sysva
 use raydium_cp_swap::accounts as raydium_cp_accounts;
 use raydium_cp_swap::instruction as raydium_cp_instructions;
 use raydium_cp_swap::{
-    states::{AMM_CONFIG_SEED, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED},
+    states::{
+        AMM_CONFIG_SEED, LP_MINT_REGISTRY_SEED, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED,
+        POOL_VAULT_SEED,
+    },
     AUTH_SEED,
 };
 use std::rc::Rc;
@@ -29,6 +32,9 @@ pub fn initialize_pool_instr(
     init_amount_0: u64,
     init_amount_1: u64,
     open_time: u64,
+    curve_type: u8,
+    amp: u64,
+    accept_freeze_risk: bool,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
@@ -88,6 +94,13 @@ pub fn initialize_pool_instr(
         ],
         &program.id(),
     );
+    let (lp_mint_registry_key, __bump) = Pubkey::find_program_address(
+        &[
+            LP_MINT_REGISTRY_SEED.as_bytes(),
+            lp_mint_key.to_bytes().as_ref(),
+        ],
+        &program.id(),
+    );
 
     let mut instructions = program
         .request()
@@ -109,17 +122,34 @@ pub fn initialize_pool_instr(
             token_1_vault,
             create_pool_fee,
             observation_state: observation_key,
+            lp_mint_registry: lp_mint_registry_key,
             token_program: spl_token::id(),
             token_0_program,
             token_1_program,
             associated_token_program: spl_associated_token_account::id(),
             system_program: system_program::id(),
             rent: sysvar::rent::id(),
+            create_pool_fee_mint: None,
+            creator_fee_token: None,
+            create_pool_fee_token_program: None,
+            protocol_stats: None,
+            mint_policy: None,
+            token_program_2022: None,
+            token_0_badge: None,
+            token_1_badge: None,
         })
         .args(raydium_cp_instructions::Initialize {
             init_amount_0,
             init_amount_1,
             open_time,
+            authority_type: 0,
+            custom_authority: None,
+            curve_type,
+            amp,
+            launch_mode: 0,
+            launch_duration: 0,
+            lp_mint_metadata: None,
+            accept_freeze_risk,
         })
         .instructions()?;
     if random_pool_id.is_some() {
@@ -173,11 +203,17 @@ pub fn deposit_instr(
             vault_0_mint: token_0_mint,
             vault_1_mint: token_1_mint,
             lp_mint: token_lp_mint,
+            system_program: Some(system_program::id()),
+            deposit_receipt: None,
+            associated_token_program: Some(spl_associated_token_account::id()),
         })
         .args(raydium_cp_instructions::Deposit {
             lp_token_amount,
             maximum_token_0_amount,
             maximum_token_1_amount,
+            wrap_sol: false,
+            deadline: None,
+            price_slippage: None,
         })
         .instructions()?;
     Ok(instructions)
@@ -223,11 +259,15 @@ pub fn withdraw_instr(
             vault_1_mint: token_1_mint,
             lp_mint: token_lp_mint,
             memo_program: spl_memo::id(),
+            deposit_receipt: None,
         })
         .args(raydium_cp_instructions::Withdraw {
             lp_token_amount,
             minimum_token_0_amount,
             minimum_token_1_amount,
+            wrap_sol: false,
+            deadline: None,
+            price_slippage: None,
         })
         .instructions()?;
     Ok(instructions)
@@ -273,10 +313,26 @@ pub fn swap_base_input_instr(
             input_token_mint,
             output_token_mint,
             observation_state: observation_account,
+            referrer_token_account: None,
+            system_program: None,
+            launch_whitelist: None,
+            trader_entry: None,
+            fee_discount_token_account: None,
+            protocol_stats: None,
+            fee_exemption_list: None,
+            price_oracle: None,
+            trader_stats: None,
+            pool_snapshot: None,
+            lp_mint: None,
+            payer_lp_token: None,
+            lp_token_program: None,
         })
         .args(raydium_cp_instructions::SwapBaseInput {
             amount_in,
             minimum_amount_out,
+            wrap_sol: false,
+            deadline: None,
+            sqrt_price_limit_x64: None,
         })
         .instructions()?;
     Ok(instructions)
@@ -322,10 +378,25 @@ pub fn swap_base_output_instr(
             input_token_mint,
             output_token_mint,
             observation_state: observation_account,
+            referrer_token_account: None,
+            system_program: None,
+            launch_whitelist: None,
+            trader_entry: None,
+            fee_discount_token_account: None,
+            protocol_stats: None,
+            fee_exemption_list: None,
+            price_oracle: None,
+            trader_stats: None,
+            pool_snapshot: None,
+            lp_mint: None,
+            payer_lp_token: None,
+            lp_token_program: None,
         })
         .args(raydium_cp_instructions::SwapBaseOutput {
             max_amount_in,
             amount_out,
+            wrap_sol: false,
+            deadline: None,
         })
         .instructions()?;
     Ok(instructions)
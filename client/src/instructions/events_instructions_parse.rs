@@ -6,6 +6,7 @@ use colorful::Colorful;
 use raydium_cp_swap::instruction;
 use raydium_cp_swap::states::*;
 use regex::Regex;
+use solana_sdk::pubkey::Pubkey;
 use solana_transaction_status::{
     option_serializer::OptionSerializer, EncodedTransaction, UiTransactionStatusMeta,
 };
@@ -145,6 +146,81 @@ pub fn handle_program_log(
             LpChangeEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<LpChangeEvent>(&mut slice)?);
             }
+            DepositEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<DepositEvent>(&mut slice)?);
+            }
+            WithdrawEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<WithdrawEvent>(&mut slice)?);
+            }
+            FeeCollectedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FeeCollectedEvent>(&mut slice)?);
+            }
+            ConfigUpdatedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<ConfigUpdatedEvent>(&mut slice)?);
+            }
+            SyncReservesEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<SyncReservesEvent>(&mut slice)?);
+            }
+            LaunchFinalizedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LaunchFinalizedEvent>(&mut slice)?);
+            }
+            LimitOrderFilledEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LimitOrderFilledEvent>(&mut slice)?);
+            }
+            BuybackEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<BuybackEvent>(&mut slice)?);
+            }
+            ClosePoolEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<ClosePoolEvent>(&mut slice)?);
+            }
+            CircuitBreakerTrippedEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<CircuitBreakerTrippedEvent>(&mut slice)?
+                );
+            }
+            FeeDiscountUpdatedEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<FeeDiscountUpdatedEvent>(&mut slice)?
+                );
+            }
+            HarvestWithheldFeesEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<HarvestWithheldFeesEvent>(&mut slice)?
+                );
+            }
+            StrategyBorrowEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<StrategyBorrowEvent>(&mut slice)?);
+            }
+            StrategyRepayEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<StrategyRepayEvent>(&mut slice)?);
+            }
+            FeeRebateClaimedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FeeRebateClaimedEvent>(&mut slice)?);
+            }
+            PoolRebalanceEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PoolRebalanceEvent>(&mut slice)?);
+            }
+            SwapRequestedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<SwapRequestedEvent>(&mut slice)?);
+            }
+            SwapRequestExecutedEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<SwapRequestExecutedEvent>(&mut slice)?
+                );
+            }
+            CustomAuthorityChangedEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<CustomAuthorityChangedEvent>(&mut slice)?
+                );
+            }
+            MintPolicyUpdatedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<MintPolicyUpdatedEvent>(&mut slice)?);
+            }
             _ => {
                 println!("unknow event: {}", l);
             }
@@ -358,6 +434,10 @@ pub fn handle_program_instruction(
                 pub init_amount_0: u64,
                 pub init_amount_1: u64,
                 pub open_time: u64,
+                pub authority_type: u8,
+                pub custom_authority: Option<Pubkey>,
+                pub curve_type: u8,
+                pub amp: u64,
             }
             impl From<instruction::Initialize> for Initialize {
                 fn from(instr: instruction::Initialize) -> Initialize {
@@ -365,6 +445,10 @@ pub fn handle_program_instruction(
                         init_amount_0: instr.init_amount_0,
                         init_amount_1: instr.init_amount_1,
                         open_time: instr.open_time,
+                        authority_type: instr.authority_type,
+                        custom_authority: instr.custom_authority,
+                        curve_type: instr.curve_type,
+                        amp: instr.amp,
                     }
                 }
             }
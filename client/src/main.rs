@@ -98,6 +98,12 @@ pub enum RaydiumCpCommands {
         open_time: u64,
         #[clap(short, long, action)]
         random_pool: bool,
+        #[arg(long, default_value_t = 0)]
+        curve_type: u8,
+        #[arg(long, default_value_t = 0)]
+        amp: u64,
+        #[clap(long, action)]
+        accept_freeze_risk: bool,
     },
     Deposit {
         pool_id: Pubkey,
@@ -155,6 +161,9 @@ fn main() -> Result<()> {
             init_amount_1,
             open_time,
             random_pool,
+            curve_type,
+            amp,
+            accept_freeze_risk,
         } => {
             let (mint0, mint1, init_amount_0, init_amount_1) = if mint0 > mint1 {
                 (mint1, mint0, init_amount_1, init_amount_0)
@@ -199,6 +208,9 @@ fn main() -> Result<()> {
                 init_amount_0,
                 init_amount_1,
                 open_time,
+                curve_type,
+                amp,
+                accept_freeze_risk,
             )?;
 
             let recent_hash = rpc_client.get_latest_blockhash()?;
@@ -245,7 +257,6 @@ fn main() -> Result<()> {
                 u128::from(total_token_1_amount),
                 raydium_cp_swap::curve::RoundDirection::Ceiling,
             )
-            .ok_or(raydium_cp_swap::error::ErrorCode::ZeroTradingTokens)
             .unwrap();
             println!(
                 "amount_0:{}, amount_1:{}, lp_token_amount:{}",
@@ -349,7 +360,6 @@ fn main() -> Result<()> {
                 u128::from(total_token_1_amount),
                 raydium_cp_swap::curve::RoundDirection::Ceiling,
             )
-            .ok_or(raydium_cp_swap::error::ErrorCode::ZeroTradingTokens)
             .unwrap();
             println!(
                 "amount_0:{}, amount_1:{}, lp_token_amount:{}",
@@ -450,6 +460,10 @@ fn main() -> Result<()> {
             ];
             let rsps = rpc_client.get_multiple_accounts(&load_pubkeys)?;
             let epoch = rpc_client.get_epoch_info().unwrap().epoch;
+            // the amplification coefficient of a ramping pool depends on the time
+            let block_timestamp = rpc_client
+                .get_block_time(rpc_client.get_slot().unwrap())
+                .unwrap() as u64;
             let [pool_account, amm_config_account, token_0_vault_account, token_1_vault_account, token_0_mint_account, token_1_mint_account, user_input_token_account] =
                 array_ref![rsps, 0, 7];
             // docode account
@@ -525,15 +539,22 @@ fn main() -> Result<()> {
             };
             // Take transfer fees into account for actual amount transferred in
             let actual_amount_in = user_input_amount.saturating_sub(transfer_fee);
-            let result = raydium_cp_swap::curve::CurveCalculator::swap_base_input(
+            let result = raydium_cp_swap::curve::CurveCalculator::swap_scaled(
+                raydium_cp_swap::curve::SwapParams {
+                    curve_type: pool_state.curve(block_timestamp)?.oriented(trade_direction),
+                    amount_scale: raydium_cp_swap::curve::AmountScale::ONE,
+                    fee_rates: raydium_cp_swap::curve::FeeRates {
+                        trade_fee_rate: amm_config_state.trade_fee_rate,
+                        protocol_fee_rate: amm_config_state.protocol_fee_rate,
+                        fund_fee_rate: amm_config_state.fund_fee_rate,
+                    },
+                    fee_on_output: amm_config_state.fee_on_output,
+                },
+                raydium_cp_swap::curve::SwapSide::ExactInput,
                 u128::from(actual_amount_in),
                 u128::from(total_input_token_amount),
                 u128::from(total_output_token_amount),
-                amm_config_state.trade_fee_rate,
-                amm_config_state.protocol_fee_rate,
-                amm_config_state.fund_fee_rate,
             )
-            .ok_or(raydium_cp_swap::error::ErrorCode::ZeroTradingTokens)
             .unwrap();
             let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
             let transfer_fee = match trade_direction {
@@ -604,6 +625,10 @@ fn main() -> Result<()> {
             ];
             let rsps = rpc_client.get_multiple_accounts(&load_pubkeys)?;
             let epoch = rpc_client.get_epoch_info().unwrap().epoch;
+            // the amplification coefficient of a ramping pool depends on the time
+            let block_timestamp = rpc_client
+                .get_block_time(rpc_client.get_slot().unwrap())
+                .unwrap() as u64;
             let [pool_account, amm_config_account, token_0_vault_account, token_1_vault_account, token_0_mint_account, token_1_mint_account, user_input_token_account] =
                 array_ref![rsps, 0, 7];
             // docode account
@@ -679,15 +704,22 @@ fn main() -> Result<()> {
             };
             let actual_amount_out = amount_out_less_fee.checked_add(out_transfer_fee).unwrap();
 
-            let result = raydium_cp_swap::curve::CurveCalculator::swap_base_output(
+            let result = raydium_cp_swap::curve::CurveCalculator::swap_scaled(
+                raydium_cp_swap::curve::SwapParams {
+                    curve_type: pool_state.curve(block_timestamp)?.oriented(trade_direction),
+                    amount_scale: raydium_cp_swap::curve::AmountScale::ONE,
+                    fee_rates: raydium_cp_swap::curve::FeeRates {
+                        trade_fee_rate: amm_config_state.trade_fee_rate,
+                        protocol_fee_rate: amm_config_state.protocol_fee_rate,
+                        fund_fee_rate: amm_config_state.fund_fee_rate,
+                    },
+                    fee_on_output: amm_config_state.fee_on_output,
+                },
+                raydium_cp_swap::curve::SwapSide::ExactOutput,
                 u128::from(actual_amount_out),
                 u128::from(total_input_token_amount),
                 u128::from(total_output_token_amount),
-                amm_config_state.trade_fee_rate,
-                amm_config_state.protocol_fee_rate,
-                amm_config_state.fund_fee_rate,
             )
-            .ok_or(raydium_cp_swap::error::ErrorCode::ZeroTradingTokens)
             .unwrap();
 
             let source_amount_swapped = u64::try_from(result.source_amount_swapped).unwrap();
@@ -0,0 +1,68 @@
+//! Converts arbitrary trading token and pool token amounts and checks that deposits and
+//! withdrawals round in favour of the pool, so a deposit followed by a withdrawal never
+//! gives free tokens
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use raydium_cp_swap::curve::*;
+
+#[derive(Arbitrary, Debug)]
+struct LpSharesInput {
+    token_0_amount: u64,
+    token_1_amount: u64,
+    lp_token_supply: u64,
+    swap_token_0_amount: u64,
+    swap_token_1_amount: u64,
+}
+
+fuzz_target!(|input: LpSharesInput| {
+    let lp_token_supply = u128::from(input.lp_token_supply);
+    let swap_token_0_amount = u128::from(input.swap_token_0_amount);
+    let swap_token_1_amount = u128::from(input.swap_token_1_amount);
+    let withdraw = |lp_token_amount: u128, round_direction| {
+        CurveCalculator::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            lp_token_supply,
+            swap_token_0_amount,
+            swap_token_1_amount,
+            round_direction,
+        )
+        .ok()
+    };
+    let token_0_amount = u128::from(input.token_0_amount);
+    let token_1_amount = u128::from(input.token_1_amount);
+
+    if let Ok(lp_token_amount) = CurveCalculator::trading_tokens_to_lp_tokens(
+        token_0_amount,
+        token_1_amount,
+        lp_token_supply,
+        swap_token_0_amount,
+        swap_token_1_amount,
+    ) {
+        if let Some(withdrawn) = withdraw(lp_token_amount, RoundDirection::Floor) {
+            assert!(withdrawn.token_0_amount <= token_0_amount);
+            assert!(withdrawn.token_1_amount <= token_1_amount);
+        }
+        if let (Some(deposited), Some(withdrawn)) = (
+            withdraw(lp_token_amount, RoundDirection::Ceiling),
+            withdraw(lp_token_amount, RoundDirection::Floor),
+        ) {
+            assert!(deposited.token_0_amount >= withdrawn.token_0_amount);
+            assert!(deposited.token_1_amount >= withdrawn.token_1_amount);
+        }
+    }
+
+    if let Ok(lp_token_amount) = CurveCalculator::trading_tokens_to_lp_tokens_ceiling(
+        token_0_amount,
+        token_1_amount,
+        lp_token_supply,
+        swap_token_0_amount,
+        swap_token_1_amount,
+    ) {
+        if let Some(withdrawn) = withdraw(lp_token_amount, RoundDirection::Floor) {
+            assert!(withdrawn.token_0_amount >= token_0_amount);
+            assert!(withdrawn.token_1_amount >= token_1_amount);
+        }
+    }
+});
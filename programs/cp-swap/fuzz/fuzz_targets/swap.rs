@@ -0,0 +1,127 @@
+//! Swaps arbitrary amounts on arbitrary reserves and checks the invariants the swap
+//! instructions rely on: the curve invariant never decreases, an exact output swap
+//! never costs more than the exact input swap it inverts, a round trip gives no free
+//! tokens and the math errors instead of panicking up to u64::MAX reserves
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use raydium_cp_swap::curve::*;
+
+#[derive(Arbitrary, Debug)]
+struct SwapInput {
+    amount_specified: u64,
+    swap_source_amount: u64,
+    swap_destination_amount: u64,
+    trade_fee_rate: u16,
+    protocol_fee_rate: u32,
+    fund_fee_rate: u32,
+    amp: u64,
+    stable: bool,
+    exact_output: bool,
+    fee_on_output: bool,
+    source_decimals_below_destination: u8,
+}
+
+fuzz_target!(|input: SwapInput| {
+    let curve_type = if input.stable {
+        CurveType::StableSwap {
+            amp: input.amp.clamp(MIN_AMP, MAX_AMP),
+        }
+    } else {
+        CurveType::ConstantProduct
+    };
+    let amount_scale = AmountScale {
+        source_multiplier_x64: 10u128.pow(u32::from(input.source_decimals_below_destination % 10))
+            * AMOUNT_MULTIPLIER_ONE_X64,
+        destination_multiplier_x64: AMOUNT_MULTIPLIER_ONE_X64,
+    };
+    // the fee rates an amm config accepts
+    let trade_fee_rate = u64::from(input.trade_fee_rate) % FEE_RATE_DENOMINATOR_VALUE;
+    let protocol_fee_rate = u64::from(input.protocol_fee_rate) % (FEE_RATE_DENOMINATOR_VALUE / 2);
+    let fund_fee_rate = u64::from(input.fund_fee_rate) % (FEE_RATE_DENOMINATOR_VALUE / 2);
+    let swap_source_amount = u128::from(input.swap_source_amount);
+    let swap_destination_amount = u128::from(input.swap_destination_amount);
+    let swap = |side, amount_specified, swap_source_amount, swap_destination_amount| {
+        CurveCalculator::swap_scaled(
+            SwapParams {
+                curve_type,
+                amount_scale,
+                fee_rates: FeeRates {
+                    trade_fee_rate,
+                    protocol_fee_rate,
+                    fund_fee_rate,
+                },
+                fee_on_output: input.fee_on_output,
+            },
+            side,
+            amount_specified,
+            swap_source_amount,
+            swap_destination_amount,
+        )
+        .ok()
+    };
+    let side = if input.exact_output {
+        SwapSide::ExactOutput
+    } else {
+        SwapSide::ExactInput
+    };
+    let Some(result) = swap(
+        side,
+        u128::from(input.amount_specified),
+        swap_source_amount,
+        swap_destination_amount,
+    ) else {
+        return;
+    };
+    assert!(result.destination_amount_swapped < swap_destination_amount);
+    assert!(result.protocol_fee + result.fund_fee <= result.trade_fee);
+
+    let Ok((new_swap_source_amount, new_swap_destination_amount)) =
+        result.new_swap_amounts_without_fee()
+    else {
+        return;
+    };
+    if let (Ok(invariant_before), Ok(invariant_after)) = (
+        CurveCalculator::invariant_scaled(
+            curve_type,
+            amount_scale,
+            swap_source_amount,
+            swap_destination_amount,
+        ),
+        CurveCalculator::invariant_scaled(
+            curve_type,
+            amount_scale,
+            new_swap_source_amount,
+            new_swap_destination_amount,
+        ),
+    ) {
+        assert!(invariant_after >= invariant_before);
+    }
+    if result.destination_amount_swapped == 0 {
+        return;
+    }
+
+    if side == SwapSide::ExactInput {
+        if let Some(inverse) = swap(
+            SwapSide::ExactOutput,
+            result.destination_amount_swapped,
+            swap_source_amount,
+            swap_destination_amount,
+        ) {
+            assert!(inverse.source_amount_swapped <= result.source_amount_swapped);
+        }
+    }
+
+    // swapping the output back never returns more than the input
+    if amount_scale.is_neutral(curve_type) {
+        if let Some(back) = swap(
+            SwapSide::ExactInput,
+            result.destination_amount_swapped,
+            new_swap_destination_amount,
+            new_swap_source_amount,
+        ) {
+            assert!(back.destination_amount_swapped <= result.source_amount_swapped);
+        }
+    }
+});
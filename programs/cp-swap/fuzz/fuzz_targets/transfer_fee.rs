@@ -0,0 +1,72 @@
+//! Adjusts arbitrary swaps by arbitrary transfer fees the way the swap instructions do
+//! and checks that the pool vault always receives the source amount the swap priced
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use raydium_cp_swap::curve::*;
+use spl_token_2022::extension::transfer_fee::{TransferFee, MAX_FEE_BASIS_POINTS};
+
+#[derive(Arbitrary, Debug)]
+struct TransferFeeInput {
+    amount_specified: u64,
+    swap_source_amount: u64,
+    swap_destination_amount: u64,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+    exact_output: bool,
+}
+
+fuzz_target!(|input: TransferFeeInput| {
+    let transfer_fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: input.maximum_fee.into(),
+        transfer_fee_basis_points: (input.transfer_fee_basis_points % MAX_FEE_BASIS_POINTS).into(),
+    };
+    let swap = |side, amount_specified| {
+        CurveCalculator::swap_scaled(
+            SwapParams {
+                curve_type: CurveType::ConstantProduct,
+                amount_scale: AmountScale::ONE,
+                fee_rates: FeeRates {
+                    trade_fee_rate: 2500,
+                    protocol_fee_rate: 120000,
+                    fund_fee_rate: 40000,
+                },
+                fee_on_output: false,
+            },
+            side,
+            amount_specified,
+            u128::from(input.swap_source_amount),
+            u128::from(input.swap_destination_amount),
+        )
+        .ok()
+    };
+
+    if input.exact_output {
+        let Some(result) = swap(SwapSide::ExactOutput, u128::from(input.amount_specified)) else {
+            return;
+        };
+        let Ok(source_amount) = u64::try_from(result.source_amount_swapped) else {
+            return;
+        };
+        // the trader pays the inverse transfer fee on top of the swapped source amount
+        let Some(amount_in) = transfer_fee
+            .calculate_inverse_fee(source_amount)
+            .and_then(|fee| source_amount.checked_add(fee))
+        else {
+            return;
+        };
+        let received = amount_in - transfer_fee.calculate_fee(amount_in).unwrap();
+        assert!(received >= source_amount);
+    } else {
+        // the pool only swaps what its vault receives
+        let fee = transfer_fee.calculate_fee(input.amount_specified).unwrap();
+        assert!(fee <= input.amount_specified);
+        assert!(fee <= input.maximum_fee);
+        let actual_amount_in = input.amount_specified - fee;
+        if let Some(result) = swap(SwapSide::ExactInput, u128::from(actual_amount_in)) {
+            assert_eq!(result.source_amount_swapped, u128::from(actual_amount_in));
+        }
+    }
+});
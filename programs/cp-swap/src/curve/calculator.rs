@@ -1,6 +1,12 @@
 //! Swap calculations
 
-use crate::curve::{constant_product::ConstantProductCurve, fees::Fees};
+use crate::curve::{
+    constant_product::ConstantProductCurve,
+    fees::{FeeRates, Fees},
+    stable_swap::{StableSwapCurve, MAX_AMP, MIN_AMP},
+    weighted::{WeightedCurve, MAX_WEIGHT, MIN_WEIGHT, WEIGHT_DENOMINATOR},
+};
+use crate::utils::U256;
 use anchor_lang::prelude::*;
 use {crate::error::ErrorCode, std::fmt::Debug};
 
@@ -23,6 +29,68 @@ pub enum TradeDirection {
     OneForZero,
 }
 
+/// The invariant a pool trades on, selected at pool creation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CurveType {
+    /// Uniswap style x * y = k
+    ConstantProduct,
+    /// Curve style StableSwap with the amplification coefficient `amp`
+    StableSwap { amp: u64 },
+    /// Balancer style weighted constant mean, with the weights of the source and
+    /// destination tokens out of WEIGHT_DENOMINATOR
+    Weighted {
+        source_weight: u64,
+        destination_weight: u64,
+    },
+}
+
+impl CurveType {
+    /// Raw value of `PoolState::curve_type` for a constant product pool
+    pub const CONSTANT_PRODUCT: u8 = 0;
+    /// Raw value of `PoolState::curve_type` for a StableSwap pool
+    pub const STABLE_SWAP: u8 = 1;
+    /// Raw value of `PoolState::curve_type` for a weighted pool
+    pub const WEIGHTED: u8 = 2;
+
+    /// Build the curve from the raw values stored on the pool, returns None
+    /// for unknown curve types or an out of range amplification coefficient or weight.
+    /// `amp` holds the token_0 weight of a weighted pool, which is the source token of
+    /// the curve until it is `oriented`
+    pub fn from_raw(curve_type: u8, amp: u64) -> Option<CurveType> {
+        match curve_type {
+            Self::CONSTANT_PRODUCT => Some(CurveType::ConstantProduct),
+            Self::STABLE_SWAP if (MIN_AMP..=MAX_AMP).contains(&amp) => {
+                Some(CurveType::StableSwap { amp })
+            }
+            Self::WEIGHTED if (MIN_WEIGHT..=MAX_WEIGHT).contains(&amp) => {
+                Some(CurveType::Weighted {
+                    source_weight: amp,
+                    destination_weight: WEIGHT_DENOMINATOR - amp,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Orient the curve of the pool from token_0 to token_1 along the trade direction,
+    /// only the weights of a weighted pool depend on it
+    pub fn oriented(self, trade_direction: TradeDirection) -> CurveType {
+        match (self, trade_direction) {
+            (
+                CurveType::Weighted {
+                    source_weight,
+                    destination_weight,
+                },
+                TradeDirection::OneForZero,
+            ) => CurveType::Weighted {
+                source_weight: destination_weight,
+                destination_weight: source_weight,
+            },
+            _ => self,
+        }
+    }
+}
+
 /// The direction to round.  Used for pool token to trading token conversions to
 /// avoid losing value on any deposit or withdrawal.
 #[repr(C)]
@@ -34,6 +102,16 @@ pub enum RoundDirection {
     Ceiling,
 }
 
+/// The side of a swap whose amount is fixed by the trader, the curve calculates the
+/// amount of the other side
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwapSide {
+    /// The source amount is specified, the destination amount is rounded down
+    ExactInput,
+    /// The destination amount is specified, the source amount is rounded up
+    ExactOutput,
+}
+
 impl TradeDirection {
     /// Given a trade direction, gives the opposite direction of the trade, so
     /// A to B becomes B to A, and vice versa
@@ -65,14 +143,178 @@ pub struct SwapResult {
     pub source_amount_swapped: u128,
     /// Amount of destination token swapped
     pub destination_amount_swapped: u128,
-    /// Amount of source tokens going to pool holders
+    /// Amount of fee tokens going to pool holders
     pub trade_fee: u128,
-    /// Amount of source tokens going to protocol
+    /// Amount of fee tokens going to protocol
     pub protocol_fee: u128,
-    /// Amount of source tokens going to protocol team
+    /// Amount of fee tokens going to protocol team
     pub fund_fee: u128,
+    /// Whether the fees are paid in destination tokens, they are paid in source tokens
+    /// otherwise
+    pub fee_on_output: bool,
+}
+
+impl SwapResult {
+    /// The trade direction whose input token pays the fees
+    pub fn fee_direction(&self, trade_direction: TradeDirection) -> TradeDirection {
+        if self.fee_on_output {
+            trade_direction.opposite()
+        } else {
+            trade_direction
+        }
+    }
+
+    /// The new source and destination amounts without the trade fee, the curve invariant
+    /// of the swap is checked on them
+    pub fn new_swap_amounts_without_fee(&self) -> Result<(u128, u128)> {
+        if self.fee_on_output {
+            Ok((
+                self.new_swap_source_amount,
+                self.new_swap_destination_amount
+                    .checked_sub(self.trade_fee)
+                    .ok_or(ErrorCode::MathUnderflow)?,
+            ))
+        } else {
+            Ok((
+                self.new_swap_source_amount
+                    .checked_sub(self.trade_fee)
+                    .ok_or(ErrorCode::MathUnderflow)?,
+                self.new_swap_destination_amount,
+            ))
+        }
+    }
+}
+
+/// Encodes all results of depositing a single token, part of which is swapped for
+/// the other token first so the rest is deposited in the pool ratio
+#[derive(Debug, PartialEq)]
+pub struct SingleTokenDepositResult {
+    /// Results of the implicit swap, its source and destination amounts are the
+    /// pool amounts before the deposit
+    pub swap_result: SwapResult,
+    /// Amount of source token deposited without swap
+    pub source_amount_deposited: u128,
+    /// Amount of pool tokens to mint
+    pub lp_token_amount: u128,
+}
+
+/// Encodes all results of withdrawing to a single token, the withdrawn source
+/// token is swapped through the pool for the destination token
+#[derive(Debug, PartialEq)]
+pub struct SingleTokenWithdrawResult {
+    /// Results of the implicit swap, its source and destination amounts are the
+    /// pool amounts after the withdrawal
+    pub swap_result: SwapResult,
+    /// Amount of destination token withdrawn without swap
+    pub destination_amount_withdrawn: u128,
 }
 
+impl SingleTokenWithdrawResult {
+    /// Total amount of destination token leaving the pool
+    pub fn destination_amount(&self) -> Result<u128> {
+        Ok(self
+            .destination_amount_withdrawn
+            .checked_add(self.swap_result.destination_amount_swapped)
+            .ok_or(ErrorCode::MathOverflow)?)
+    }
+}
+
+/// Q64.64 multiplier of an amount scale that leaves the amounts unchanged
+pub const AMOUNT_MULTIPLIER_ONE_X64: u128 = 1 << 64;
+
+/// Multipliers from the raw amounts of the source and destination tokens to their UI
+/// amounts, Q64.64. Only the StableSwap curve depends on them since it pegs the amounts
+/// of both tokens, the constant product and weighted curves swap the same raw amounts at
+/// any scale
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AmountScale {
+    pub source_multiplier_x64: u128,
+    pub destination_multiplier_x64: u128,
+}
+
+impl AmountScale {
+    pub const ONE: AmountScale = AmountScale {
+        source_multiplier_x64: AMOUNT_MULTIPLIER_ONE_X64,
+        destination_multiplier_x64: AMOUNT_MULTIPLIER_ONE_X64,
+    };
+
+    /// Whether the curve gives the same raw amounts with and without the scale
+    pub fn is_neutral(&self, curve_type: CurveType) -> bool {
+        matches!(
+            curve_type,
+            CurveType::ConstantProduct | CurveType::Weighted { .. }
+        ) || self.source_multiplier_x64 == self.destination_multiplier_x64
+    }
+
+    fn scale(amount: u128, multiplier_x64: u128, round_direction: RoundDirection) -> Result<u128> {
+        let mut scaled = U256::from(amount)
+            .checked_mul(U256::from(multiplier_x64))
+            .ok_or(ErrorCode::AmountScaleOverflow)?;
+        if round_direction == RoundDirection::Ceiling {
+            scaled = scaled
+                .checked_add(U256::from(u64::MAX))
+                .ok_or(ErrorCode::AmountScaleOverflow)?;
+        }
+        u128::try_from(scaled >> 64).map_err(|_| error!(ErrorCode::AmountScaleOverflow))
+    }
+
+    fn unscale(
+        amount: u128,
+        multiplier_x64: u128,
+        round_direction: RoundDirection,
+    ) -> Result<u128> {
+        let numerator = U256::from(amount) << 64;
+        let multiplier_x64 = U256::from(multiplier_x64);
+        let unscaled = match round_direction {
+            RoundDirection::Floor => numerator
+                .checked_div(multiplier_x64)
+                .ok_or(ErrorCode::DivisionByZero)?,
+            RoundDirection::Ceiling => numerator
+                .checked_add(
+                    multiplier_x64
+                        .checked_sub(U256::from(1u8))
+                        .ok_or(ErrorCode::DivisionByZero)?,
+                )
+                .ok_or(ErrorCode::AmountScaleOverflow)?
+                .checked_div(multiplier_x64)
+                .ok_or(ErrorCode::DivisionByZero)?,
+        };
+        u128::try_from(unscaled).map_err(|_| error!(ErrorCode::AmountScaleOverflow))
+    }
+
+    pub fn scale_source(&self, amount: u128) -> Result<u128> {
+        Self::scale(amount, self.source_multiplier_x64, RoundDirection::Floor)
+    }
+
+    pub fn scale_destination(&self, amount: u128) -> Result<u128> {
+        Self::scale(
+            amount,
+            self.destination_multiplier_x64,
+            RoundDirection::Floor,
+        )
+    }
+}
+
+/// The curve, the UI amount scale and the fee rates a swap is calculated with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwapParams {
+    /// The curve of the pool oriented along the trade direction
+    pub curve_type: CurveType,
+    pub amount_scale: AmountScale,
+    pub fee_rates: FeeRates,
+    /// Whether the trade fee is charged on the destination token
+    pub fee_on_output: bool,
+}
+
+/// Denominator of price impacts in basis points
+pub const PRICE_IMPACT_BPS_DENOMINATOR: u64 = 10_000;
+
+/// Denominator of virtual reserve multipliers, 10_000 trades on the real reserves only
+pub const VIRTUAL_RESERVE_MULTIPLIER_DENOMINATOR: u64 = 10_000;
+
+/// Number of bisection steps searching the amount to swap of a single token deposit
+const SINGLE_TOKEN_DEPOSIT_ITERATIONS: u32 = 20;
+
 /// Concrete struct to wrap around the trait object which performs calculation.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct CurveCalculator {}
@@ -88,73 +330,697 @@ impl CurveCalculator {
         Ok(())
     }
 
+    /// Get the lp tokens of the initial deposit of a pool, the geometric mean of the
+    /// amounts weighted by the token weights of a weighted pool
+    pub fn initial_lp_amount(
+        curve_type: CurveType,
+        token_0_amount: u64,
+        token_1_amount: u64,
+    ) -> Result<u64> {
+        let lp_amount = match curve_type {
+            CurveType::Weighted {
+                source_weight,
+                destination_weight,
+            } => WeightedCurve::value(
+                source_weight,
+                destination_weight,
+                u128::from(token_0_amount),
+                u128::from(token_1_amount),
+            )?,
+            _ => (U256::from(token_0_amount) * U256::from(token_1_amount))
+                .integer_sqrt()
+                .as_u128(),
+        };
+        u64::try_from(lp_amount).map_err(|_| error!(ErrorCode::SqrtPriceOverflow))
+    }
+
+    /// Calculate the invariant of the curve, the swap instructions check that
+    /// it never decreases from a trade.
+    ///
+    /// The constant product implementation returns x * y, the StableSwap
+    /// implementation returns D and the weighted implementation the weighted sum of the
+    /// logarithms of the amounts.
+    pub fn invariant(
+        curve_type: CurveType,
+        swap_token_0_amount: u128,
+        swap_token_1_amount: u128,
+    ) -> Result<u128> {
+        match curve_type {
+            CurveType::ConstantProduct => Ok(swap_token_0_amount
+                .checked_mul(swap_token_1_amount)
+                .ok_or(ErrorCode::MathOverflow)?),
+            CurveType::StableSwap { amp } => {
+                StableSwapCurve::compute_d(amp, swap_token_0_amount, swap_token_1_amount)
+            }
+            CurveType::Weighted {
+                source_weight,
+                destination_weight,
+            } => WeightedCurve::compute_invariant(
+                source_weight,
+                destination_weight,
+                swap_token_0_amount,
+                swap_token_1_amount,
+            ),
+        }
+    }
+
+    /// Calculate the invariant of the curve on the UI amounts of the tokens
+    pub fn invariant_scaled(
+        curve_type: CurveType,
+        amount_scale: AmountScale,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<u128> {
+        if amount_scale.is_neutral(curve_type) {
+            return Self::invariant(curve_type, swap_source_amount, swap_destination_amount);
+        }
+        Self::invariant(
+            curve_type,
+            amount_scale.scale_source(swap_source_amount)?,
+            amount_scale.scale_destination(swap_destination_amount)?,
+        )
+    }
+
+    /// Swap the specified amount of the `side` of the swap with the curve and fee rates of
+    /// `params` on the UI amounts of the tokens, the single entry point of
+    /// `swap_base_input_scaled` and `swap_base_output_scaled`
+    pub fn swap_scaled(
+        params: SwapParams,
+        side: SwapSide,
+        amount_specified: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<SwapResult> {
+        if params.fee_on_output {
+            return Self::swap_scaled_fee_on_output(
+                params,
+                side,
+                amount_specified,
+                swap_source_amount,
+                swap_destination_amount,
+            );
+        }
+        match side {
+            SwapSide::ExactInput => Self::swap_base_input_scaled(
+                params.curve_type,
+                params.amount_scale,
+                amount_specified,
+                swap_source_amount,
+                swap_destination_amount,
+                params.fee_rates,
+            ),
+            SwapSide::ExactOutput => Self::swap_base_output_scaled(
+                params.curve_type,
+                params.amount_scale,
+                amount_specified,
+                swap_source_amount,
+                swap_destination_amount,
+                params.fee_rates,
+            ),
+        }
+    }
+
+    /// Same as `swap_scaled` with the fees paid in the destination token. The curve swaps
+    /// the amounts without fees, then the trade fee is taken from the destination amount
+    /// the curve gives and stays in the destination vault
+    fn swap_scaled_fee_on_output(
+        params: SwapParams,
+        side: SwapSide,
+        amount_specified: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<SwapResult> {
+        let SwapParams {
+            curve_type,
+            amount_scale,
+            fee_rates,
+            ..
+        } = params;
+        let (result_without_fees, trade_fee) = match side {
+            SwapSide::ExactInput => {
+                let result = Self::swap_base_input_scaled(
+                    curve_type,
+                    amount_scale,
+                    amount_specified,
+                    swap_source_amount,
+                    swap_destination_amount,
+                    FeeRates::default(),
+                )?;
+                let trade_fee =
+                    Fees::trading_fee(result.destination_amount_swapped, fee_rates.trade_fee_rate)?;
+                (result, trade_fee)
+            }
+            SwapSide::ExactOutput => {
+                let destination_amount =
+                    Fees::calculate_pre_fee_amount(amount_specified, fee_rates.trade_fee_rate)?;
+                let result = Self::swap_base_output_scaled(
+                    curve_type,
+                    amount_scale,
+                    destination_amount,
+                    swap_source_amount,
+                    swap_destination_amount,
+                    FeeRates::default(),
+                )?;
+                (result, destination_amount - amount_specified)
+            }
+        };
+        let destination_amount_swapped = result_without_fees
+            .destination_amount_swapped
+            .checked_sub(trade_fee)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        Ok(SwapResult {
+            new_swap_source_amount: result_without_fees.new_swap_source_amount,
+            new_swap_destination_amount: swap_destination_amount
+                .checked_sub(destination_amount_swapped)
+                .ok_or(ErrorCode::SwapExceedsReserves)?,
+            source_amount_swapped: result_without_fees.source_amount_swapped,
+            destination_amount_swapped,
+            trade_fee,
+            protocol_fee: Fees::protocol_fee(trade_fee, fee_rates.protocol_fee_rate)?,
+            fund_fee: Fees::fund_fee(trade_fee, fee_rates.fund_fee_rate)?,
+            fee_on_output: true,
+        })
+    }
+
+    /// Same as `swap_base_input` with the curve calculated on the UI amounts of the
+    /// tokens, the results are raw amounts and the destination amount is rounded down
+    pub fn swap_base_input_scaled(
+        curve_type: CurveType,
+        amount_scale: AmountScale,
+        source_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        fee_rates: FeeRates,
+    ) -> Result<SwapResult> {
+        if amount_scale.is_neutral(curve_type) {
+            return Self::swap_base_input(
+                curve_type,
+                source_amount,
+                swap_source_amount,
+                swap_destination_amount,
+                fee_rates,
+            );
+        }
+        let scaled_result = Self::swap_base_input(
+            curve_type,
+            amount_scale.scale_source(source_amount)?,
+            amount_scale.scale_source(swap_source_amount)?,
+            amount_scale.scale_destination(swap_destination_amount)?,
+            fee_rates,
+        )?;
+        let destination_amount_swapped = AmountScale::unscale(
+            scaled_result.destination_amount_swapped,
+            amount_scale.destination_multiplier_x64,
+            RoundDirection::Floor,
+        )?;
+        // the fee rounded down keeps the invariant of the scaled amounts from decreasing
+        let trade_fee = AmountScale::unscale(
+            scaled_result.trade_fee,
+            amount_scale.source_multiplier_x64,
+            RoundDirection::Floor,
+        )?;
+        let protocol_fee = Fees::protocol_fee(trade_fee, fee_rates.protocol_fee_rate)?;
+        let fund_fee = Fees::fund_fee(trade_fee, fee_rates.fund_fee_rate)?;
+
+        Ok(SwapResult {
+            new_swap_source_amount: swap_source_amount
+                .checked_add(source_amount)
+                .ok_or(ErrorCode::MathOverflow)?,
+            new_swap_destination_amount: swap_destination_amount
+                .checked_sub(destination_amount_swapped)
+                .ok_or(ErrorCode::SwapExceedsReserves)?,
+            source_amount_swapped: source_amount,
+            destination_amount_swapped,
+            trade_fee,
+            protocol_fee,
+            fund_fee,
+            fee_on_output: false,
+        })
+    }
+
+    /// Same as `swap_base_output` with the curve calculated on the UI amounts of the
+    /// tokens, the results are raw amounts and the source amount is rounded up
+    pub fn swap_base_output_scaled(
+        curve_type: CurveType,
+        amount_scale: AmountScale,
+        destinsation_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        fee_rates: FeeRates,
+    ) -> Result<SwapResult> {
+        if amount_scale.is_neutral(curve_type) {
+            return Self::swap_base_output(
+                curve_type,
+                destinsation_amount,
+                swap_source_amount,
+                swap_destination_amount,
+                fee_rates,
+            );
+        }
+        // round the scaled destination amount up so the pool is never paid less
+        let scaled_destination_amount = AmountScale::scale(
+            destinsation_amount,
+            amount_scale.destination_multiplier_x64,
+            RoundDirection::Ceiling,
+        )?;
+        let scaled_result = Self::swap_base_output(
+            curve_type,
+            scaled_destination_amount,
+            amount_scale.scale_source(swap_source_amount)?,
+            amount_scale.scale_destination(swap_destination_amount)?,
+            fee_rates,
+        )?;
+        let source_amount = AmountScale::unscale(
+            scaled_result.source_amount_swapped,
+            amount_scale.source_multiplier_x64,
+            RoundDirection::Ceiling,
+        )?;
+        let trade_fee = AmountScale::unscale(
+            scaled_result.trade_fee,
+            amount_scale.source_multiplier_x64,
+            RoundDirection::Floor,
+        )?;
+        let protocol_fee = Fees::protocol_fee(trade_fee, fee_rates.protocol_fee_rate)?;
+        let fund_fee = Fees::fund_fee(trade_fee, fee_rates.fund_fee_rate)?;
+
+        Ok(SwapResult {
+            new_swap_source_amount: swap_source_amount
+                .checked_add(source_amount)
+                .ok_or(ErrorCode::MathOverflow)?,
+            new_swap_destination_amount: swap_destination_amount
+                .checked_sub(destinsation_amount)
+                .ok_or(ErrorCode::SwapExceedsReserves)?,
+            source_amount_swapped: source_amount,
+            destination_amount_swapped: destinsation_amount,
+            trade_fee,
+            protocol_fee,
+            fund_fee,
+            fee_on_output: false,
+        })
+    }
+
     /// Subtract fees and calculate how much destination token will be provided
     /// given an amount of source token.
     pub fn swap_base_input(
+        curve_type: CurveType,
         source_amount: u128,
         swap_source_amount: u128,
         swap_destination_amount: u128,
-        trade_fee_rate: u64,
-        protocol_fee_rate: u64,
-        fund_fee_rate: u64,
-    ) -> Option<SwapResult> {
+        fee_rates: FeeRates,
+    ) -> Result<SwapResult> {
         // debit the fee to calculate the amount swapped
-        let trade_fee = Fees::trading_fee(source_amount, trade_fee_rate)?;
-        let protocol_fee = Fees::protocol_fee(trade_fee, protocol_fee_rate)?;
-        let fund_fee = Fees::fund_fee(trade_fee, fund_fee_rate)?;
+        let trade_fee = Fees::trading_fee(source_amount, fee_rates.trade_fee_rate)?;
+        let protocol_fee = Fees::protocol_fee(trade_fee, fee_rates.protocol_fee_rate)?;
+        let fund_fee = Fees::fund_fee(trade_fee, fee_rates.fund_fee_rate)?;
 
-        let source_amount_less_fees = source_amount.checked_sub(trade_fee)?;
+        let source_amount_less_fees = source_amount
+            .checked_sub(trade_fee)
+            .ok_or(ErrorCode::MathUnderflow)?;
 
-        let destination_amount_swapped = ConstantProductCurve::swap_base_input_without_fees(
-            source_amount_less_fees,
-            swap_source_amount,
-            swap_destination_amount,
-        );
+        let destination_amount_swapped = match curve_type {
+            CurveType::ConstantProduct => ConstantProductCurve::swap_base_input_without_fees(
+                source_amount_less_fees,
+                swap_source_amount,
+                swap_destination_amount,
+            )?,
+            CurveType::StableSwap { amp } => StableSwapCurve::swap_base_input_without_fees(
+                amp,
+                source_amount_less_fees,
+                swap_source_amount,
+                swap_destination_amount,
+            )?,
+            CurveType::Weighted {
+                source_weight,
+                destination_weight,
+            } => WeightedCurve::swap_base_input_without_fees(
+                source_weight,
+                destination_weight,
+                source_amount_less_fees,
+                swap_source_amount,
+                swap_destination_amount,
+            )?,
+        };
 
-        Some(SwapResult {
-            new_swap_source_amount: swap_source_amount.checked_add(source_amount)?,
+        Ok(SwapResult {
+            new_swap_source_amount: swap_source_amount
+                .checked_add(source_amount)
+                .ok_or(ErrorCode::MathOverflow)?,
             new_swap_destination_amount: swap_destination_amount
-                .checked_sub(destination_amount_swapped)?,
+                .checked_sub(destination_amount_swapped)
+                .ok_or(ErrorCode::SwapExceedsReserves)?,
             source_amount_swapped: source_amount,
             destination_amount_swapped,
             trade_fee,
             protocol_fee,
             fund_fee,
+            fee_on_output: false,
         })
     }
 
     pub fn swap_base_output(
+        curve_type: CurveType,
         destinsation_amount: u128,
         swap_source_amount: u128,
         swap_destination_amount: u128,
-        trade_fee_rate: u64,
-        protocol_fee_rate: u64,
-        fund_fee_rate: u64,
-    ) -> Option<SwapResult> {
-        let source_amount_swapped = ConstantProductCurve::swap_base_output_without_fees(
-            destinsation_amount,
-            swap_source_amount,
-            swap_destination_amount,
-        );
+        fee_rates: FeeRates,
+    ) -> Result<SwapResult> {
+        let source_amount_swapped = match curve_type {
+            CurveType::ConstantProduct => ConstantProductCurve::swap_base_output_without_fees(
+                destinsation_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )?,
+            CurveType::StableSwap { amp } => StableSwapCurve::swap_base_output_without_fees(
+                amp,
+                destinsation_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )?,
+            CurveType::Weighted {
+                source_weight,
+                destination_weight,
+            } => WeightedCurve::swap_base_output_without_fees(
+                source_weight,
+                destination_weight,
+                destinsation_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )?,
+        };
 
         let source_amount =
-            Fees::calculate_pre_fee_amount(source_amount_swapped, trade_fee_rate).unwrap();
-        let trade_fee = Fees::trading_fee(source_amount, trade_fee_rate)?;
-        let protocol_fee = Fees::protocol_fee(trade_fee, protocol_fee_rate)?;
-        let fund_fee = Fees::fund_fee(trade_fee, fund_fee_rate)?;
+            Fees::calculate_pre_fee_amount(source_amount_swapped, fee_rates.trade_fee_rate)?;
+        let trade_fee = Fees::trading_fee(source_amount, fee_rates.trade_fee_rate)?;
+        let protocol_fee = Fees::protocol_fee(trade_fee, fee_rates.protocol_fee_rate)?;
+        let fund_fee = Fees::fund_fee(trade_fee, fee_rates.fund_fee_rate)?;
 
-        Some(SwapResult {
-            new_swap_source_amount: swap_source_amount.checked_add(source_amount)?,
+        Ok(SwapResult {
+            new_swap_source_amount: swap_source_amount
+                .checked_add(source_amount)
+                .ok_or(ErrorCode::MathOverflow)?,
             new_swap_destination_amount: swap_destination_amount
-                .checked_sub(destinsation_amount)?,
+                .checked_sub(destinsation_amount)
+                .ok_or(ErrorCode::SwapExceedsReserves)?,
             source_amount_swapped: source_amount,
             destination_amount_swapped: destinsation_amount,
             trade_fee,
             protocol_fee,
             fund_fee,
+            fee_on_output: false,
         })
     }
 
+    /// Calculate the pool tokens minted for depositing only the source token.
+    ///
+    /// The source amount is split by bisection so that, after swapping part of it
+    /// through the pool, the remaining source amount and the swapped destination
+    /// amount match the new pool ratio. Pool tokens are rounded down, any
+    /// imbalance left by the search stays with the pool.
+    pub fn deposit_single_token(
+        curve_type: CurveType,
+        source_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        lp_token_supply: u128,
+        fee_rates: FeeRates,
+    ) -> Result<SingleTokenDepositResult> {
+        // Returns the swap results and whether too little of the source was swapped
+        let split = |amount_swapped: u128| -> Result<(SwapResult, bool)> {
+            let result = Self::swap_base_input(
+                curve_type,
+                amount_swapped,
+                swap_source_amount,
+                swap_destination_amount,
+                fee_rates,
+            )?;
+            let new_swap_source_amount = result
+                .new_swap_source_amount
+                .checked_sub(result.protocol_fee)
+                .and_then(|amount| amount.checked_sub(result.fund_fee))
+                .ok_or(ErrorCode::MathUnderflow)?;
+            let source_left = source_amount
+                .checked_sub(amount_swapped)
+                .ok_or(ErrorCode::MathUnderflow)?;
+            let under_swapped = source_left
+                .checked_mul(result.new_swap_destination_amount)
+                .ok_or(ErrorCode::MathOverflow)?
+                >= result
+                    .destination_amount_swapped
+                    .checked_mul(new_swap_source_amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            Ok((result, under_swapped))
+        };
+
+        let mut low = 0u128;
+        let mut high = source_amount;
+        for _ in 0..SINGLE_TOKEN_DEPOSIT_ITERATIONS {
+            if high - low <= 1 {
+                break;
+            }
+            let mid = low + (high - low) / 2;
+            if split(mid)?.1 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        let (swap_result, _) = split(low)?;
+
+        let source_amount_deposited = source_amount
+            .checked_sub(low)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        let new_swap_source_amount = swap_result
+            .new_swap_source_amount
+            .checked_sub(swap_result.protocol_fee)
+            .and_then(|amount| amount.checked_sub(swap_result.fund_fee))
+            .ok_or(ErrorCode::MathUnderflow)?;
+        let lp_from_source = source_amount_deposited
+            .checked_mul(lp_token_supply)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(new_swap_source_amount)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        let lp_from_destination = swap_result
+            .destination_amount_swapped
+            .checked_mul(lp_token_supply)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(swap_result.new_swap_destination_amount)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        Ok(SingleTokenDepositResult {
+            swap_result,
+            source_amount_deposited,
+            lp_token_amount: lp_from_source.min(lp_from_destination),
+        })
+    }
+
+    /// Calculate the destination token received for burning pool tokens.
+    ///
+    /// The pool tokens are redeemed for both tokens rounding down, then the
+    /// redeemed source amount is swapped for the destination token on the pool
+    /// amounts left after the withdrawal.
+    pub fn withdraw_single_token(
+        curve_type: CurveType,
+        lp_token_amount: u128,
+        lp_token_supply: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        fee_rates: FeeRates,
+    ) -> Result<SingleTokenWithdrawResult> {
+        let withdraw_result = Self::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            lp_token_supply,
+            swap_source_amount,
+            swap_destination_amount,
+            RoundDirection::Floor,
+        )?;
+        let swap_result = Self::swap_base_input(
+            curve_type,
+            withdraw_result.token_0_amount,
+            swap_source_amount
+                .checked_sub(withdraw_result.token_0_amount)
+                .ok_or(ErrorCode::SwapExceedsReserves)?,
+            swap_destination_amount
+                .checked_sub(withdraw_result.token_1_amount)
+                .ok_or(ErrorCode::SwapExceedsReserves)?,
+            fee_rates,
+        )?;
+        Ok(SingleTokenWithdrawResult {
+            swap_result,
+            destination_amount_withdrawn: withdraw_result.token_1_amount,
+        })
+    }
+
+    /// Calculate the price impact of a swap in basis points, the pool price of the
+    /// source token, in destination tokens, drops from `swap_destination_amount /
+    /// swap_source_amount` to `new_swap_destination_amount / new_swap_source_amount`.
+    pub fn price_impact_bps(
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        new_swap_source_amount: u128,
+        new_swap_destination_amount: u128,
+    ) -> Result<u64> {
+        let price_before = U256::from(swap_destination_amount)
+            .checked_mul(U256::from(new_swap_source_amount))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let price_after = U256::from(new_swap_destination_amount)
+            .checked_mul(U256::from(swap_source_amount))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let price_impact = price_before
+            .saturating_sub(price_after)
+            .checked_mul(U256::from(PRICE_IMPACT_BPS_DENOMINATOR))
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(price_before)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        Ok(price_impact.as_u64())
+    }
+
+    /// Whether the invariant backing each lp token did not decrease from
+    /// `invariant_before` over `lp_supply_before` to `invariant_after` over
+    /// `lp_supply_after`. The constant product grows with the square of the reserves and
+    /// the stable swap D linearly, so they are compared against the square and the lp
+    /// supply respectively. The weighted invariant is a logarithm, see
+    /// `WeightedCurve::invariant_per_lp_token_preserved`.
+    pub fn invariant_per_lp_token_preserved(
+        curve_type: CurveType,
+        invariant_before: u128,
+        lp_supply_before: u64,
+        invariant_after: u128,
+        lp_supply_after: u64,
+    ) -> Result<bool> {
+        let (lp_supply_before, lp_supply_after) = match curve_type {
+            CurveType::Weighted {
+                source_weight,
+                destination_weight,
+            } => {
+                return WeightedCurve::invariant_per_lp_token_preserved(
+                    source_weight + destination_weight,
+                    invariant_before,
+                    lp_supply_before,
+                    invariant_after,
+                    lp_supply_after,
+                )
+            }
+            CurveType::ConstantProduct => (
+                U256::from(lp_supply_before) * U256::from(lp_supply_before),
+                U256::from(lp_supply_after) * U256::from(lp_supply_after),
+            ),
+            CurveType::StableSwap { .. } => {
+                (U256::from(lp_supply_before), U256::from(lp_supply_after))
+            }
+        };
+        let value_before = U256::from(invariant_before)
+            .checked_mul(lp_supply_after)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let value_after = U256::from(invariant_after)
+            .checked_mul(lp_supply_before)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(value_after >= value_before)
+    }
+
+    /// Get the virtual reserves to add to the real reserves so the curve is `multiplier`
+    /// / VIRTUAL_RESERVE_MULTIPLIER_DENOMINATOR times deeper than the real reserves, at
+    /// the price of the current real plus virtual reserves. The side of the larger value
+    /// is amplified by the multiplier and the other side follows the price, so both real
+    /// reserves are amplified by at least the multiplier. Once a real reserve is drained
+    /// the price has left the amplified range, and getting the virtual reserves again
+    /// re-centers the range around the current price.
+    pub fn virtual_reserves(
+        multiplier: u64,
+        token_0_amount: u128,
+        token_1_amount: u128,
+        virtual_token_0_amount: u128,
+        virtual_token_1_amount: u128,
+    ) -> Result<(u128, u128)> {
+        if multiplier < VIRTUAL_RESERVE_MULTIPLIER_DENOMINATOR {
+            return err!(ErrorCode::InvalidInput);
+        }
+        let curve_token_0_amount = U256::from(
+            map_zero_to_none(
+                token_0_amount
+                    .checked_add(virtual_token_0_amount)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::DivisionByZero)?,
+        );
+        let curve_token_1_amount = U256::from(
+            map_zero_to_none(
+                token_1_amount
+                    .checked_add(virtual_token_1_amount)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::DivisionByZero)?,
+        );
+        // the value of the real token_0 reserve in token_1 at the curve price
+        let token_0_value = U256::from(token_0_amount)
+            .checked_mul(curve_token_1_amount)
+            .ok_or(ErrorCode::MathOverflow)?
+            / curve_token_0_amount;
+        let new_curve_token_1_amount = token_0_value
+            .max(U256::from(token_1_amount))
+            .checked_mul(U256::from(multiplier))
+            .ok_or(ErrorCode::MathOverflow)?
+            / U256::from(VIRTUAL_RESERVE_MULTIPLIER_DENOMINATOR);
+        let new_curve_token_0_amount = new_curve_token_1_amount
+            .checked_mul(curve_token_0_amount)
+            .ok_or(ErrorCode::MathOverflow)?
+            / curve_token_1_amount;
+        let new_curve_token_0_amount = u128::try_from(new_curve_token_0_amount)
+            .map_err(|_| error!(ErrorCode::MathOverflow))?;
+        let new_curve_token_1_amount = u128::try_from(new_curve_token_1_amount)
+            .map_err(|_| error!(ErrorCode::MathOverflow))?;
+        Ok((
+            new_curve_token_0_amount.saturating_sub(token_0_amount),
+            new_curve_token_1_amount.saturating_sub(token_1_amount),
+        ))
+    }
+
+    /// Get the most pool tokens a balanced deposit of at most the given trading tokens
+    /// mints, provided the total trading tokens and supply of pool tokens.
+    pub fn trading_tokens_to_lp_tokens(
+        token_0_amount: u128,
+        token_1_amount: u128,
+        lp_token_supply: u128,
+        swap_token_0_amount: u128,
+        swap_token_1_amount: u128,
+    ) -> Result<u128> {
+        let lp_token_0_amount = token_0_amount
+            .checked_mul(lp_token_supply)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(swap_token_0_amount)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        let lp_token_1_amount = token_1_amount
+            .checked_mul(lp_token_supply)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(swap_token_1_amount)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        Ok(lp_token_0_amount.min(lp_token_1_amount))
+    }
+
+    /// Get the fewest pool tokens a balanced withdrawal of at least the given trading tokens
+    /// burns, provided the total trading tokens and supply of pool tokens.
+    pub fn trading_tokens_to_lp_tokens_ceiling(
+        token_0_amount: u128,
+        token_1_amount: u128,
+        lp_token_supply: u128,
+        swap_token_0_amount: u128,
+        swap_token_1_amount: u128,
+    ) -> Result<u128> {
+        let ceil_div = |amount: u128, swap_amount: u128| -> Result<u128> {
+            let numerator = amount
+                .checked_mul(lp_token_supply)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let round_up = swap_amount
+                .checked_sub(1)
+                .ok_or(ErrorCode::DivisionByZero)?;
+            Ok(numerator
+                .checked_add(round_up)
+                .ok_or(ErrorCode::MathOverflow)?
+                / swap_amount)
+        };
+        let lp_token_0_amount = ceil_div(token_0_amount, swap_token_0_amount)?;
+        let lp_token_1_amount = ceil_div(token_1_amount, swap_token_1_amount)?;
+        Ok(lp_token_0_amount.max(lp_token_1_amount))
+    }
+
     /// Get the amount of trading tokens for the given amount of pool tokens,
     /// provided the total trading tokens and supply of pool tokens.
     pub fn lp_tokens_to_trading_tokens(
@@ -163,7 +1029,7 @@ impl CurveCalculator {
         swap_token_0_amount: u128,
         swap_token_1_amount: u128,
         round_direction: RoundDirection,
-    ) -> Option<TradingTokenResult> {
+    ) -> Result<TradingTokenResult> {
         ConstantProductCurve::lp_tokens_to_trading_tokens(
             lp_token_amount,
             lp_token_supply,
@@ -221,7 +1087,8 @@ pub mod test {
             source_token_amount,
             swap_source_amount,
             swap_destination_amount,
-        );
+        )
+        .unwrap();
 
         let (swap_token_0_amount, swap_token_1_amount) = match trade_direction {
             TradeDirection::ZeroForOne => (swap_source_amount, swap_destination_amount),
@@ -343,4 +1210,502 @@ pub mod test {
            (total, intermediate)
        }
     }
+
+    #[test]
+    fn deposit_single_token_close_to_balanced_deposit() {
+        let swap_source_amount = 1_000_000_000u128;
+        let swap_destination_amount = 1_000_000_000u128;
+        let lp_token_supply = 1_000_000_000u128;
+        let source_amount = 10_000_000u128;
+        for curve_type in [
+            CurveType::ConstantProduct,
+            CurveType::StableSwap { amp: 100 },
+        ] {
+            let result = CurveCalculator::deposit_single_token(
+                curve_type,
+                source_amount,
+                swap_source_amount,
+                swap_destination_amount,
+                lp_token_supply,
+                FeeRates {
+                    trade_fee_rate: 2500,
+                    protocol_fee_rate: 0,
+                    fund_fee_rate: 0,
+                },
+            )
+            .unwrap();
+            // a balanced deposit of the same value mints about half of the source share
+            let balanced_lp_token_amount = source_amount * lp_token_supply / swap_source_amount / 2;
+            assert!(result.lp_token_amount < balanced_lp_token_amount);
+            assert!(result.lp_token_amount > balanced_lp_token_amount * 99 / 100);
+            assert_eq!(
+                result.source_amount_deposited + result.swap_result.source_amount_swapped,
+                source_amount
+            );
+        }
+    }
+
+    #[test]
+    fn withdraw_single_token_without_fees() {
+        let result = CurveCalculator::withdraw_single_token(
+            CurveType::ConstantProduct,
+            10_000,
+            1_000_000,
+            1_000_000,
+            1_000_000,
+            FeeRates::default(),
+        )
+        .unwrap();
+        assert_eq!(result.destination_amount_withdrawn, 10_000);
+        // 10_000 swapped on 990_000 / 990_000 after the withdrawal
+        assert_eq!(result.swap_result.destination_amount_swapped, 9_900);
+        assert_eq!(result.destination_amount().unwrap(), 19_900);
+    }
+
+    #[test]
+    fn price_impact_of_constant_product_swap() {
+        // 1% of the reserves in moves the price by about 2%
+        let result = CurveCalculator::swap_base_input(
+            CurveType::ConstantProduct,
+            10_000,
+            1_000_000,
+            1_000_000,
+            FeeRates::default(),
+        )
+        .unwrap();
+        let price_impact_bps = CurveCalculator::price_impact_bps(
+            1_000_000,
+            1_000_000,
+            result.new_swap_source_amount,
+            result.new_swap_destination_amount,
+        )
+        .unwrap();
+        assert_eq!(price_impact_bps, 197);
+        assert_eq!(
+            CurveCalculator::price_impact_bps(1_000_000, 1_000_000, 1_000_000, 1_000_000).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn trading_tokens_to_lp_tokens_fits_amounts() {
+        let lp_token_amount =
+            CurveCalculator::trading_tokens_to_lp_tokens(1_000, 5_000, 10_000, 20_000, 40_000)
+                .unwrap();
+        // token_0 limits the deposit
+        assert_eq!(lp_token_amount, 500);
+        let results = CurveCalculator::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            10_000,
+            20_000,
+            40_000,
+            RoundDirection::Ceiling,
+        )
+        .unwrap();
+        assert!(results.token_0_amount <= 1_000 && results.token_1_amount <= 5_000);
+        assert_eq!(
+            CurveCalculator::trading_tokens_to_lp_tokens(1_000, 5_000, 10_000, 0, 40_000)
+                .unwrap_err(),
+            ErrorCode::DivisionByZero.into()
+        );
+    }
+
+    #[test]
+    fn trading_tokens_to_lp_tokens_ceiling_covers_amounts() {
+        let lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens_ceiling(
+            1_001, 5_001, 10_000, 20_000, 40_000,
+        )
+        .unwrap();
+        // token_1 requires the most pool tokens
+        assert_eq!(lp_token_amount, 1_251);
+        let results = CurveCalculator::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            10_000,
+            20_000,
+            40_000,
+            RoundDirection::Floor,
+        )
+        .unwrap();
+        assert!(results.token_0_amount >= 1_001 && results.token_1_amount >= 5_001);
+        assert_eq!(
+            CurveCalculator::trading_tokens_to_lp_tokens_ceiling(1_000, 5_000, 10_000, 0, 40_000)
+                .unwrap_err(),
+            ErrorCode::DivisionByZero.into()
+        );
+    }
+
+    #[test]
+    fn locked_lp_tokens_defeat_share_inflation() {
+        let locked_lp_amount = u128::from(crate::states::LOCKED_LP_AMOUNT);
+        // the attacker creates the pool with the least liquidity, keeping a single lp
+        // token, then donates to the vaults to inflate the lp token value
+        let donation = 10_000_000;
+        let mut lp_token_supply = locked_lp_amount + 1;
+        let mut swap_token_amount = lp_token_supply + donation;
+
+        let lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens(
+            5_000_000,
+            5_000_000,
+            lp_token_supply,
+            swap_token_amount,
+            swap_token_amount,
+        )
+        .unwrap();
+        let deposited = CurveCalculator::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            lp_token_supply,
+            swap_token_amount,
+            swap_token_amount,
+            RoundDirection::Ceiling,
+        )
+        .unwrap()
+        .token_0_amount;
+        lp_token_supply += lp_token_amount;
+        swap_token_amount += deposited;
+        let withdrawn = CurveCalculator::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            lp_token_supply,
+            swap_token_amount,
+            swap_token_amount,
+            RoundDirection::Floor,
+        )
+        .unwrap()
+        .token_0_amount;
+        lp_token_supply -= lp_token_amount;
+        swap_token_amount -= withdrawn;
+        // the victim loses less than the value of an lp token to the rounding
+        assert!(lp_token_amount > 0);
+        assert!(deposited - withdrawn <= swap_token_amount / lp_token_supply + 1);
+
+        // the donation mostly went to the locked lp tokens
+        let attacker_withdrawn = CurveCalculator::lp_tokens_to_trading_tokens(
+            1,
+            lp_token_supply,
+            swap_token_amount,
+            swap_token_amount,
+            RoundDirection::Floor,
+        )
+        .unwrap()
+        .token_0_amount;
+        assert!(attacker_withdrawn * locked_lp_amount < donation + deposited - withdrawn);
+    }
+
+    #[test]
+    fn virtual_reserves_amplify_real_reserves() {
+        // 5x the liquidity of a fresh pool, at the price of the real reserves
+        assert_eq!(
+            CurveCalculator::virtual_reserves(50_000, 1_000, 4_000, 0, 0).unwrap(),
+            (4_000, 16_000)
+        );
+        assert_eq!(
+            CurveCalculator::virtual_reserves(10_000, 1_000, 4_000, 0, 0).unwrap(),
+            (0, 0)
+        );
+        assert_eq!(
+            CurveCalculator::virtual_reserves(9_999, 1_000, 4_000, 0, 0).unwrap_err(),
+            ErrorCode::InvalidInput.into()
+        );
+        assert_eq!(
+            CurveCalculator::virtual_reserves(50_000, 0, 0, 0, 0).unwrap_err(),
+            ErrorCode::DivisionByZero.into()
+        );
+    }
+
+    #[test]
+    fn virtual_reserves_recenter_drained_range() {
+        // the price moved up until token_0 is nearly drained
+        let (virtual_token_0_amount, virtual_token_1_amount) =
+            CurveCalculator::virtual_reserves(20_000, 10, 9_000, 1_000, 1_000).unwrap();
+        assert_eq!(
+            (virtual_token_0_amount, virtual_token_1_amount),
+            (1_808, 9_000)
+        );
+        // the price of the curve is unchanged
+        let price_before: u128 = 10_000 * 1_000_000 / 1_010;
+        let price_after =
+            (9_000 + virtual_token_1_amount) * 1_000_000 / (10 + virtual_token_0_amount);
+        assert!(price_before.abs_diff(price_after) * 1_000 < price_before);
+    }
+
+    #[test]
+    fn invariant_per_lp_token_preserved_test() {
+        let curve_type = CurveType::ConstantProduct;
+        // a proportional deposit grows k with the square of the lp supply
+        let k_before = 1_000u128 * 4_000;
+        let k_after = 1_500u128 * 6_000;
+        assert!(CurveCalculator::invariant_per_lp_token_preserved(
+            curve_type, k_before, 2_000, k_after, 3_000
+        )
+        .unwrap());
+        // minting one more lp token than the deposit is worth dilutes the lp holders
+        assert!(!CurveCalculator::invariant_per_lp_token_preserved(
+            curve_type, k_before, 2_000, k_after, 3_001
+        )
+        .unwrap());
+
+        // the stable swap D grows linearly with the lp supply
+        let curve_type = CurveType::StableSwap { amp: 100 };
+        let d_before = CurveCalculator::invariant(curve_type, 1_000, 4_000).unwrap();
+        let d_after = CurveCalculator::invariant(curve_type, 1_500, 6_000).unwrap();
+        assert!(CurveCalculator::invariant_per_lp_token_preserved(
+            curve_type, d_before, 2_000, d_after, 3_000
+        )
+        .unwrap());
+        assert!(!CurveCalculator::invariant_per_lp_token_preserved(
+            curve_type, d_before, 2_000, d_after, 3_010
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn swap_scaled_charges_fee_on_output() {
+        let swap = |side, amount_specified| {
+            CurveCalculator::swap_scaled(
+                SwapParams {
+                    curve_type: CurveType::ConstantProduct,
+                    amount_scale: AmountScale::ONE,
+                    fee_rates: FeeRates {
+                        trade_fee_rate: 2500,
+                        protocol_fee_rate: 120000,
+                        fund_fee_rate: 40000,
+                    },
+                    fee_on_output: true,
+                },
+                side,
+                amount_specified,
+                1_000_000,
+                1_000_000,
+            )
+            .unwrap()
+        };
+        let result = swap(SwapSide::ExactInput, 10_000);
+        // the curve gives 9_900 destination tokens, the trade fee is taken from them
+        assert_eq!(result.trade_fee, 25);
+        assert_eq!(result.protocol_fee, 3);
+        assert_eq!(result.fund_fee, 1);
+        assert_eq!(result.source_amount_swapped, 10_000);
+        assert_eq!(result.destination_amount_swapped, 9_875);
+        assert_eq!(result.new_swap_source_amount, 1_010_000);
+        assert_eq!(result.new_swap_destination_amount, 990_125);
+        assert_eq!(
+            result.new_swap_amounts_without_fee().unwrap(),
+            (1_010_000, 990_100)
+        );
+        assert_eq!(
+            result.fee_direction(TradeDirection::ZeroForOne),
+            TradeDirection::OneForZero
+        );
+
+        let result = swap(SwapSide::ExactOutput, 9_875);
+        assert_eq!(result.trade_fee, 25);
+        assert_eq!(result.destination_amount_swapped, 9_875);
+        assert!(result.source_amount_swapped <= 10_000);
+    }
+
+    #[test]
+    fn swap_scaled_prices_stable_swap_on_ui_amounts() {
+        let curve_type = CurveType::StableSwap { amp: 100 };
+        let raw = CurveCalculator::swap_base_input(
+            curve_type,
+            1_000,
+            1_000_000_000,
+            1_000_000_000,
+            FeeRates::default(),
+        )
+        .unwrap();
+        let neutral = CurveCalculator::swap_base_input_scaled(
+            curve_type,
+            AmountScale::ONE,
+            1_000,
+            1_000_000_000,
+            1_000_000_000,
+            FeeRates::default(),
+        )
+        .unwrap();
+        assert_eq!(raw, neutral);
+
+        // a raw source token is worth 2 UI tokens, so the balanced pool holds half as many
+        let amount_scale = AmountScale {
+            source_multiplier_x64: 2 * AMOUNT_MULTIPLIER_ONE_X64,
+            destination_multiplier_x64: AMOUNT_MULTIPLIER_ONE_X64,
+        };
+        let result = CurveCalculator::swap_base_input_scaled(
+            curve_type,
+            amount_scale,
+            1_000,
+            500_000_000,
+            1_000_000_000,
+            FeeRates::default(),
+        )
+        .unwrap();
+        assert!(result.destination_amount_swapped.abs_diff(2_000) <= 1);
+        assert!(
+            CurveCalculator::invariant_scaled(
+                curve_type,
+                amount_scale,
+                result.new_swap_source_amount,
+                result.new_swap_destination_amount,
+            )
+            .unwrap()
+                >= CurveCalculator::invariant_scaled(
+                    curve_type,
+                    amount_scale,
+                    500_000_000,
+                    1_000_000_000,
+                )
+                .unwrap()
+        );
+
+        let result = CurveCalculator::swap_base_output_scaled(
+            curve_type,
+            amount_scale,
+            2_000,
+            500_000_000,
+            1_000_000_000,
+            FeeRates::default(),
+        )
+        .unwrap();
+        assert!(result.source_amount_swapped.abs_diff(1_000) <= 1);
+    }
+
+    proptest! {
+        #[test]
+        fn deposit_single_token_does_not_dilute_pool_tokens(
+            source_amount in 1..u32::MAX as u64,
+            swap_source_amount in 1_000..u32::MAX as u64,
+            swap_destination_amount in 1_000..u32::MAX as u64,
+            lp_token_supply in 1_000..u32::MAX as u64,
+        ) {
+            let result = CurveCalculator::deposit_single_token(
+                CurveType::ConstantProduct,
+                source_amount as u128,
+                swap_source_amount as u128,
+                swap_destination_amount as u128,
+                lp_token_supply as u128,
+                FeeRates {
+                    trade_fee_rate: 2500,
+                    protocol_fee_rate: 120000,
+                    fund_fee_rate: 40000,
+                },
+            )
+            .unwrap();
+            let swap_result = result.swap_result;
+            let new_swap_source_amount = swap_source_amount as u128 + source_amount as u128
+                - swap_result.protocol_fee
+                - swap_result.fund_fee;
+            let value = normalized_value(swap_source_amount as u128, swap_destination_amount as u128)
+                .unwrap();
+            let new_value = normalized_value(new_swap_source_amount, swap_destination_amount as u128)
+                .unwrap();
+            // new_value / new_lp_token_supply >= value / lp_token_supply
+            let new_lp_token_supply =
+                PreciseNumber::new(lp_token_supply as u128 + result.lp_token_amount).unwrap();
+            let lp_token_supply = PreciseNumber::new(lp_token_supply as u128).unwrap();
+            prop_assert!(new_value
+                .checked_mul(&lp_token_supply)
+                .unwrap()
+                .greater_than_or_equal(&value.checked_mul(&new_lp_token_supply).unwrap()));
+        }
+
+        #[test]
+        fn withdraw_single_token_does_not_dilute_pool_tokens(
+            (lp_token_supply, lp_token_amount) in total_and_intermediate(u32::MAX as u64),
+            swap_source_amount in 1_000..u32::MAX as u64,
+            swap_destination_amount in 1_000..u32::MAX as u64,
+        ) {
+            let result = CurveCalculator::withdraw_single_token(
+                CurveType::ConstantProduct,
+                lp_token_amount as u128,
+                lp_token_supply as u128,
+                swap_source_amount as u128,
+                swap_destination_amount as u128,
+                FeeRates {
+                    trade_fee_rate: 2500,
+                    protocol_fee_rate: 120000,
+                    fund_fee_rate: 40000,
+                },
+            )
+            .unwrap();
+            let swap_result = &result.swap_result;
+            let new_swap_source_amount = swap_source_amount as u128
+                - swap_result.protocol_fee
+                - swap_result.fund_fee;
+            let new_swap_destination_amount =
+                swap_destination_amount as u128 - result.destination_amount().unwrap();
+            prop_assert_eq!(new_swap_destination_amount, swap_result.new_swap_destination_amount);
+            let value = normalized_value(swap_source_amount as u128, swap_destination_amount as u128)
+                .unwrap();
+            let new_value = normalized_value(new_swap_source_amount, new_swap_destination_amount)
+                .unwrap();
+            // new_value / new_lp_token_supply >= value / lp_token_supply
+            let new_lp_token_supply =
+                PreciseNumber::new((lp_token_supply - lp_token_amount) as u128).unwrap();
+            let lp_token_supply = PreciseNumber::new(lp_token_supply as u128).unwrap();
+            prop_assert!(new_value
+                .checked_mul(&lp_token_supply)
+                .unwrap()
+                .greater_than_or_equal(&value.checked_mul(&new_lp_token_supply).unwrap()));
+        }
+
+        #[test]
+        fn swap_exact_output_inverts_exact_input(
+            source_amount in 1..u32::MAX as u64,
+            swap_source_amount in 1_000..u32::MAX as u64,
+            swap_destination_amount in 1_000..u32::MAX as u64,
+            amp in MIN_AMP..=MAX_AMP,
+            stable in proptest::bool::ANY,
+            scaled in proptest::bool::ANY,
+            fee_on_output in proptest::bool::ANY,
+        ) {
+            let curve_type = if stable {
+                CurveType::StableSwap { amp }
+            } else {
+                CurveType::ConstantProduct
+            };
+            // a source token with one decimal less than the destination token
+            let amount_scale = if scaled {
+                AmountScale {
+                    source_multiplier_x64: 10 * AMOUNT_MULTIPLIER_ONE_X64,
+                    destination_multiplier_x64: AMOUNT_MULTIPLIER_ONE_X64,
+                }
+            } else {
+                AmountScale::ONE
+            };
+            let swap = |side, amount_specified| {
+                CurveCalculator::swap_scaled(
+                    SwapParams {
+                        curve_type,
+                        amount_scale,
+                        fee_rates: FeeRates {
+                            trade_fee_rate: 2500,
+                            protocol_fee_rate: 120000,
+                            fund_fee_rate: 40000,
+                        },
+                        fee_on_output,
+                    },
+                    side,
+                    amount_specified,
+                    swap_source_amount as u128,
+                    swap_destination_amount as u128,
+                )
+            };
+            let exact_input = swap(SwapSide::ExactInput, source_amount as u128).unwrap();
+            prop_assume!(exact_input.destination_amount_swapped > 0);
+            // buying back the output of an exact input swap never costs more than its input
+            let exact_output = swap(
+                SwapSide::ExactOutput,
+                exact_input.destination_amount_swapped,
+            )
+            .unwrap();
+            prop_assert!(exact_output.source_amount_swapped <= exact_input.source_amount_swapped);
+            // and the input an exact output swap asks for buys at least its output
+            let round_trip = swap(SwapSide::ExactInput, exact_output.source_amount_swapped).unwrap();
+            prop_assert!(
+                round_trip.destination_amount_swapped >= exact_output.destination_amount_swapped
+            );
+            prop_assert!(
+                round_trip.destination_amount_swapped <= exact_input.destination_amount_swapped
+            );
+        }
+    }
 }
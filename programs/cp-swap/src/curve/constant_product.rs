@@ -1,9 +1,11 @@
 //! The Uniswap invariantConstantProductCurve::
 
 use crate::{
-    curve::calculator::{RoundDirection, TradingTokenResult},
-    utils::CheckedCeilDiv,
+    curve::calculator::{RoundDirection, TradeDirection, TradingTokenResult},
+    error::ErrorCode,
+    utils::{CheckedCeilDiv, U256},
 };
+use anchor_lang::prelude::*;
 
 /// ConstantProductCurve struct implementing CurveCalculator
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -20,28 +22,67 @@ impl ConstantProductCurve {
         source_amount: u128,
         swap_source_amount: u128,
         swap_destination_amount: u128,
-    ) -> u128 {
+    ) -> Result<u128> {
         // (x + delta_x) * (y - delta_y) = x * y
         // delta_y = (delta_x * y) / (x + delta_x)
-        let numerator = source_amount.checked_mul(swap_destination_amount).unwrap();
-        let denominator = swap_source_amount.checked_add(source_amount).unwrap();
-        let destinsation_amount_swapped = numerator.checked_div(denominator).unwrap();
-        destinsation_amount_swapped
+        let numerator = source_amount
+            .checked_mul(swap_destination_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let denominator = swap_source_amount
+            .checked_add(source_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let destinsation_amount_swapped = numerator
+            .checked_div(denominator)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        Ok(destinsation_amount_swapped)
     }
 
     pub fn swap_base_output_without_fees(
         destinsation_amount: u128,
         swap_source_amount: u128,
         swap_destination_amount: u128,
-    ) -> u128 {
+    ) -> Result<u128> {
         // (x + delta_x) * (y - delta_y) = x * y
         // delta_x = (x * delta_y) / (y - delta_y)
-        let numerator = swap_source_amount.checked_mul(destinsation_amount).unwrap();
+        let numerator = swap_source_amount
+            .checked_mul(destinsation_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
         let denominator = swap_destination_amount
             .checked_sub(destinsation_amount)
-            .unwrap();
-        let (source_amount_swapped, _) = numerator.checked_ceil_div(denominator).unwrap();
-        source_amount_swapped
+            .ok_or(ErrorCode::SwapExceedsReserves)?;
+        let (source_amount_swapped, _) = numerator.checked_ceil_div(denominator)?;
+        Ok(source_amount_swapped)
+    }
+
+    /// Get the source amount, without fees, moving the square root of the token_0 price in
+    /// token_1 to `target_sqrt_price_x64`, a Q64.64.
+    ///
+    /// The target reserves keep x * y = k with y / x = target_price:
+    ///  - x' = sqrt(k) / target_sqrt_price
+    ///  - y' = sqrt(k) * target_sqrt_price
+    ///
+    /// Returns None when the price is already at the target
+    pub fn source_amount_to_sqrt_price(
+        swap_token_0_amount: u128,
+        swap_token_1_amount: u128,
+        target_sqrt_price_x64: u128,
+    ) -> Result<Option<(TradeDirection, u128)>> {
+        require_gt!(target_sqrt_price_x64, 0, ErrorCode::InvalidInput);
+        let sqrt_invariant =
+            (U256::from(swap_token_0_amount) * U256::from(swap_token_1_amount)).integer_sqrt();
+        let target_token_1_amount = (sqrt_invariant * U256::from(target_sqrt_price_x64)) >> 64;
+        if target_token_1_amount > U256::from(swap_token_1_amount) {
+            let source_amount = u128::try_from(target_token_1_amount - swap_token_1_amount)
+                .map_err(|_| ErrorCode::MathOverflow)?;
+            return Ok(Some((TradeDirection::OneForZero, source_amount)));
+        }
+        let target_token_0_amount = (sqrt_invariant << 64) / U256::from(target_sqrt_price_x64);
+        if target_token_0_amount > U256::from(swap_token_0_amount) {
+            let source_amount = u128::try_from(target_token_0_amount - swap_token_0_amount)
+                .map_err(|_| ErrorCode::MathOverflow)?;
+            return Ok(Some((TradeDirection::ZeroForOne, source_amount)));
+        }
+        Ok(None)
     }
 
     /// Get the amount of trading tokens for the given amount of pool tokens,
@@ -55,19 +96,25 @@ impl ConstantProductCurve {
         swap_token_0_amount: u128,
         swap_token_1_amount: u128,
         round_direction: RoundDirection,
-    ) -> Option<TradingTokenResult> {
+    ) -> Result<TradingTokenResult> {
         let mut token_0_amount = lp_token_amount
-            .checked_mul(swap_token_0_amount)?
-            .checked_div(lp_token_supply)?;
+            .checked_mul(swap_token_0_amount)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(lp_token_supply)
+            .ok_or(ErrorCode::DivisionByZero)?;
         let mut token_1_amount = lp_token_amount
-            .checked_mul(swap_token_1_amount)?
-            .checked_div(lp_token_supply)?;
+            .checked_mul(swap_token_1_amount)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(lp_token_supply)
+            .ok_or(ErrorCode::DivisionByZero)?;
         let (token_0_amount, token_1_amount) = match round_direction {
             RoundDirection::Floor => (token_0_amount, token_1_amount),
             RoundDirection::Ceiling => {
                 let token_0_remainder = lp_token_amount
-                    .checked_mul(swap_token_0_amount)?
-                    .checked_rem(lp_token_supply)?;
+                    .checked_mul(swap_token_0_amount)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_rem(lp_token_supply)
+                    .ok_or(ErrorCode::DivisionByZero)?;
                 // Also check for 0 token A and B amount to avoid taking too much
                 // for tiny amounts of pool tokens.  For example, if someone asks
                 // for 1 pool token, which is worth 0.01 token A, we avoid the
@@ -77,15 +124,17 @@ impl ConstantProductCurve {
                     token_0_amount += 1;
                 }
                 let token_1_remainder = lp_token_amount
-                    .checked_mul(swap_token_1_amount)?
-                    .checked_rem(lp_token_supply)?;
+                    .checked_mul(swap_token_1_amount)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_rem(lp_token_supply)
+                    .ok_or(ErrorCode::DivisionByZero)?;
                 if token_1_remainder > 0 && token_1_amount > 0 {
                     token_1_amount += 1;
                 }
                 (token_0_amount, token_1_amount)
             }
         };
-        Some(TradingTokenResult {
+        Ok(TradingTokenResult {
             token_0_amount,
             token_1_amount,
         })
@@ -142,7 +191,7 @@ mod tests {
             0,
             RoundDirection::Floor,
         );
-        assert!(results.is_none());
+        assert_eq!(results.unwrap_err(), ErrorCode::MathOverflow.into());
         let results = ConstantProductCurve::lp_tokens_to_trading_tokens(
             5,
             10,
@@ -150,7 +199,44 @@ mod tests {
             u128::MAX,
             RoundDirection::Floor,
         );
-        assert!(results.is_none());
+        assert_eq!(results.unwrap_err(), ErrorCode::MathOverflow.into());
+    }
+
+    #[test]
+    fn source_amount_to_sqrt_price() {
+        let sqrt_price_x64 = |sqrt_price: u128| sqrt_price << 64;
+        // price 4, the target price 16 buys token_0 with token_1
+        assert_eq!(
+            ConstantProductCurve::source_amount_to_sqrt_price(
+                1_000_000,
+                4_000_000,
+                sqrt_price_x64(4)
+            )
+            .unwrap(),
+            Some((TradeDirection::OneForZero, 4_000_000))
+        );
+        // the target price 1 sells token_0 for token_1
+        assert_eq!(
+            ConstantProductCurve::source_amount_to_sqrt_price(
+                1_000_000,
+                4_000_000,
+                sqrt_price_x64(1)
+            )
+            .unwrap(),
+            Some((TradeDirection::ZeroForOne, 1_000_000))
+        );
+        assert_eq!(
+            ConstantProductCurve::source_amount_to_sqrt_price(
+                1_000_000,
+                4_000_000,
+                sqrt_price_x64(2)
+            )
+            .unwrap(),
+            None
+        );
+        assert!(
+            ConstantProductCurve::source_amount_to_sqrt_price(1_000_000, 4_000_000, 0).is_err()
+        );
     }
 
     fn test_truncation(
@@ -165,7 +251,8 @@ mod tests {
             source_amount,
             swap_source_amount,
             swap_destination_amount,
-        );
+        )
+        .unwrap();
         assert_eq!(source_amount, expected_source_amount_swapped);
         assert_eq!(
             destination_amount_swapped,
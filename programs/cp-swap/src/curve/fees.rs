@@ -1,30 +1,45 @@
 //! All fee information, to be used for validation currently
 
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
 pub const FEE_RATE_DENOMINATOR_VALUE: u64 = 1_000_000;
 
 pub struct Fees {}
 
-fn ceil_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
-    token_amount
-        .checked_mul(u128::from(fee_numerator))
-        .unwrap()
-        .checked_add(fee_denominator)?
-        .checked_sub(1)?
+/// The fee rates of a swap out of `FEE_RATE_DENOMINATOR_VALUE`, the protocol and fund
+/// rates are shares of the trade fee
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeRates {
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+}
+
+fn ceil_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Result<u128> {
+    Ok(token_amount
+        .checked_mul(fee_numerator)
+        .ok_or(ErrorCode::FeeCalculationOverflow)?
+        .checked_add(fee_denominator)
+        .ok_or(ErrorCode::FeeCalculationOverflow)?
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?
         .checked_div(fee_denominator)
+        .ok_or(ErrorCode::DivisionByZero)?)
 }
 
 /// Helper function for calculating swap fee
-pub fn floor_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
-    Some(
-        token_amount
-            .checked_mul(fee_numerator)?
-            .checked_div(fee_denominator)?,
-    )
+pub fn floor_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Result<u128> {
+    Ok(token_amount
+        .checked_mul(fee_numerator)
+        .ok_or(ErrorCode::FeeCalculationOverflow)?
+        .checked_div(fee_denominator)
+        .ok_or(ErrorCode::DivisionByZero)?)
 }
 
 impl Fees {
     /// Calculate the trading fee in trading tokens
-    pub fn trading_fee(amount: u128, trade_fee_rate: u64) -> Option<u128> {
+    pub fn trading_fee(amount: u128, trade_fee_rate: u64) -> Result<u128> {
         ceil_div(
             amount,
             u128::from(trade_fee_rate),
@@ -33,7 +48,7 @@ impl Fees {
     }
 
     /// Calculate the owner protocol fee in trading tokens
-    pub fn protocol_fee(amount: u128, protocol_fee_rate: u64) -> Option<u128> {
+    pub fn protocol_fee(amount: u128, protocol_fee_rate: u64) -> Result<u128> {
         floor_div(
             amount,
             u128::from(protocol_fee_rate),
@@ -42,7 +57,7 @@ impl Fees {
     }
 
     /// Calculate the owner fund fee in trading tokens
-    pub fn fund_fee(amount: u128, fund_fee_rate: u64) -> Option<u128> {
+    pub fn fund_fee(amount: u128, fund_fee_rate: u64) -> Result<u128> {
         floor_div(
             amount,
             u128::from(fund_fee_rate),
@@ -50,18 +65,89 @@ impl Fees {
         )
     }
 
-    pub fn calculate_pre_fee_amount(post_fee_amount: u128, trade_fee_rate: u64) -> Option<u128> {
+    /// Calculate the referrer share of the trade fee in trading tokens
+    pub fn referral_fee(amount: u128, referral_fee_rate: u64) -> Result<u128> {
+        floor_div(
+            amount,
+            u128::from(referral_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the pool creator share of the trade fee in trading tokens
+    pub fn creator_fee(amount: u128, creator_fee_rate: u64) -> Result<u128> {
+        floor_div(
+            amount,
+            u128::from(creator_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the share of the liquidity provider fee rebated to the trader in trading tokens
+    pub fn lp_fee_rebate(amount: u128, lp_fee_rebate_rate: u64) -> Result<u128> {
+        floor_div(
+            amount,
+            u128::from(lp_fee_rebate_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the bounty paid to the fee crank caller in swept tokens
+    pub fn crank_bounty(amount: u128, fee_crank_bounty_rate: u64) -> Result<u128> {
+        floor_div(
+            amount,
+            u128::from(fee_crank_bounty_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the flash loan fee in borrowed tokens
+    pub fn flash_loan_fee(amount: u128, flash_loan_fee_rate: u64) -> Result<u128> {
+        ceil_div(
+            amount,
+            u128::from(flash_loan_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the trade fee rate of the dynamic fee mode, the static trade fee rate
+    /// increases in proportion to the volatility and is bounded by the min and max rates
+    pub fn dynamic_trade_fee_rate(
+        trade_fee_rate: u64,
+        volatility: u64,
+        volatility_factor: u64,
+        min_rate: u64,
+        max_rate: u64,
+    ) -> u64 {
+        let volatility_fee = u128::from(volatility)
+            .saturating_mul(u128::from(volatility_factor))
+            .checked_div(u128::from(FEE_RATE_DENOMINATOR_VALUE))
+            .unwrap();
+        let fee_rate = u128::from(trade_fee_rate)
+            .saturating_add(volatility_fee)
+            .max(u128::from(min_rate))
+            .min(u128::from(max_rate));
+        fee_rate as u64
+    }
+
+    pub fn calculate_pre_fee_amount(post_fee_amount: u128, trade_fee_rate: u64) -> Result<u128> {
         if trade_fee_rate == 0 {
-            Some(post_fee_amount)
+            Ok(post_fee_amount)
         } else {
-            let numerator = post_fee_amount.checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))?;
-            let denominator =
-                u128::from(FEE_RATE_DENOMINATOR_VALUE).checked_sub(u128::from(trade_fee_rate))?;
+            let numerator = post_fee_amount
+                .checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))
+                .ok_or(ErrorCode::FeeCalculationOverflow)?;
+            let denominator = u128::from(FEE_RATE_DENOMINATOR_VALUE)
+                .checked_sub(u128::from(trade_fee_rate))
+                .ok_or(ErrorCode::MathUnderflow)?;
 
-            numerator
-                .checked_add(denominator)?
-                .checked_sub(1)?
+            Ok(numerator
+                .checked_add(denominator)
+                .ok_or(ErrorCode::FeeCalculationOverflow)?
+                .checked_sub(1)
+                .ok_or(ErrorCode::MathUnderflow)?
                 .checked_div(denominator)
+                .ok_or(ErrorCode::DivisionByZero)?)
         }
     }
 }
@@ -3,7 +3,16 @@
 pub mod calculator;
 pub mod constant_product;
 pub mod fees;
+pub mod multi_stable_swap;
+pub mod stable_swap;
+pub mod weighted;
+
+#[cfg(test)]
+mod property_test;
 
 pub use calculator::*;
 pub use constant_product::*;
 pub use fees::*;
+pub use multi_stable_swap::*;
+pub use stable_swap::*;
+pub use weighted::*;
@@ -0,0 +1,302 @@
+//! The StableSwap invariant over the balances of 2 to 4 coins, for the multi token pools
+
+use crate::error::ErrorCode;
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+/// Maximum number of iterations for the newton method
+const ITERATIONS: u16 = 256;
+
+/// MultiStableSwapCurve generalizing StableSwapCurve to more than 2 coins
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MultiStableSwapCurve;
+
+/// Compute the ceiling of a / b for U256
+fn checked_ceil_div(a: U256, b: U256) -> Option<U256> {
+    let quotient = a.checked_div(b)?;
+    if a.checked_rem(b)?.is_zero() {
+        Some(quotient)
+    } else {
+        quotient.checked_add(U256::one())
+    }
+}
+
+impl MultiStableSwapCurve {
+    /// Returns the leverage of the curve, `A * n`
+    fn leverage(amp: u64, n_coins: usize) -> Option<u64> {
+        amp.checked_mul(u64::try_from(n_coins).ok()?)
+    }
+
+    /// Compute the stable swap invariant (D) of the coin balances
+    ///
+    /// Equation:
+    ///
+    /// A * sum(x_i) * n**n + D = A * D * n**n + D**(n+1) / (n**n * prod(x_i))
+    pub fn compute_d(amp: u64, amounts: &[u128]) -> Result<u128> {
+        let n_coins = U256::from(amounts.len());
+        let leverage =
+            U256::from(Self::leverage(amp, amounts.len()).ok_or(ErrorCode::MathOverflow)?);
+        let mut sum_x = 0u128;
+        for amount in amounts {
+            sum_x = sum_x.checked_add(*amount).ok_or(ErrorCode::MathOverflow)?;
+        }
+        if sum_x == 0 {
+            return Ok(0);
+        }
+        let sum_x = U256::from(sum_x);
+        let mut d_previous: U256;
+        let mut d = sum_x;
+
+        // Newton's method to approximate D
+        for _ in 0..ITERATIONS {
+            let mut d_product = d;
+            for amount in amounts {
+                d_product = d_product
+                    .checked_mul(d)
+                    .ok_or(ErrorCode::StableSwapCalculationFailed)?
+                    .checked_div(U256::from(*amount) * n_coins)
+                    .ok_or(ErrorCode::DivisionByZero)?;
+            }
+            d_previous = d;
+            // d = (leverage * sum_x + d_product * n) * d / ((leverage - 1) * d + (n + 1) * d_product)
+            d = leverage
+                .checked_mul(sum_x)
+                .and_then(|l_val| l_val.checked_add(d_product.checked_mul(n_coins)?))
+                .and_then(|l_val| l_val.checked_mul(d))
+                .and_then(|l_val| {
+                    let r_val = d
+                        .checked_mul(leverage.checked_sub(U256::one())?)?
+                        .checked_add(d_product.checked_mul(n_coins + U256::one())?)?;
+                    l_val.checked_div(r_val)
+                })
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+            // Equality with the precision of 1
+            if d == d_previous {
+                break;
+            }
+        }
+        u128::try_from(d).map_err(|_| error!(ErrorCode::StableSwapCalculationFailed))
+    }
+
+    /// Compute the balance of coin `index` keeping the invariant D given the balances of
+    /// the other coins, `amounts[index]` is ignored. Rounded up in favor of the pool
+    ///
+    /// Solve for y:
+    /// y**2 + y * (sum' - (A*n**n - 1) * D / (A * n**n)) = D ** (n + 1) / (n ** (2 * n) * prod' * A)
+    /// y**2 + b*y = c
+    pub fn compute_y(amp: u64, amounts: &[u128], index: usize, d_val: u128) -> Result<u128> {
+        let n_coins = U256::from(amounts.len());
+        let leverage =
+            U256::from(Self::leverage(amp, amounts.len()).ok_or(ErrorCode::MathOverflow)?);
+        let d_val = U256::from(d_val);
+
+        // c = D ** (n + 1) / (n ** n * prod' * A * n), computed one coin at a time
+        let mut c = d_val;
+        let mut sum_x = U256::zero();
+        for (_, amount) in amounts.iter().enumerate().filter(|(i, _)| *i != index) {
+            let amount = U256::from(*amount);
+            sum_x = sum_x
+                .checked_add(amount)
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+            c = c
+                .checked_mul(d_val)
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?
+                .checked_div(amount * n_coins)
+                .ok_or(ErrorCode::DivisionByZero)?;
+        }
+        c = c
+            .checked_mul(d_val)
+            .ok_or(ErrorCode::StableSwapCalculationFailed)?
+            .checked_div(leverage * n_coins)
+            .ok_or(ErrorCode::DivisionByZero)?;
+
+        // b = sum' + D / (A * n)
+        let b = sum_x
+            .checked_add(
+                d_val
+                    .checked_div(leverage)
+                    .ok_or(ErrorCode::DivisionByZero)?,
+            )
+            .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+
+        // Solve for y by approximating: y**2 + b*y = c
+        let mut y = d_val;
+        for _ in 0..ITERATIONS {
+            let numerator = y
+                .checked_mul(y)
+                .and_then(|y_squared| y_squared.checked_add(c))
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+            let denominator = y
+                .checked_mul(U256::from(2))
+                .and_then(|y_double| y_double.checked_add(b))
+                .and_then(|y_double| y_double.checked_sub(d_val))
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+            let y_new =
+                checked_ceil_div(numerator, denominator).ok_or(ErrorCode::DivisionByZero)?;
+            if y_new == y {
+                break;
+            } else {
+                y = y_new;
+            }
+        }
+        u128::try_from(y).map_err(|_| error!(ErrorCode::StableSwapCalculationFailed))
+    }
+
+    /// Swap `source_amount` of coin `source_index` for coin `destination_index` keeping D
+    /// constant, the destination amount swapped is rounded down in favor of the pool
+    pub fn swap_base_input_without_fees(
+        amp: u64,
+        amounts: &[u128],
+        source_index: usize,
+        destination_index: usize,
+        source_amount: u128,
+    ) -> Result<u128> {
+        require!(
+            source_index != destination_index
+                && source_index < amounts.len()
+                && destination_index < amounts.len(),
+            ErrorCode::InvalidInput
+        );
+        let d = Self::compute_d(amp, amounts)?;
+        let mut new_amounts = amounts.to_vec();
+        new_amounts[source_index] = amounts[source_index]
+            .checked_add(source_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let new_destination_amount = Self::compute_y(amp, &new_amounts, destination_index, d)?;
+        Ok(amounts[destination_index].saturating_sub(new_destination_amount))
+    }
+
+    /// The amount of coin `source_index` required to receive `destination_amount` of coin
+    /// `destination_index` is rounded up in favor of the pool
+    pub fn swap_base_output_without_fees(
+        amp: u64,
+        amounts: &[u128],
+        source_index: usize,
+        destination_index: usize,
+        destination_amount: u128,
+    ) -> Result<u128> {
+        require!(
+            source_index != destination_index
+                && source_index < amounts.len()
+                && destination_index < amounts.len(),
+            ErrorCode::InvalidInput
+        );
+        let d = Self::compute_d(amp, amounts)?;
+        let mut new_amounts = amounts.to_vec();
+        new_amounts[destination_index] = amounts[destination_index]
+            .checked_sub(destination_amount)
+            .ok_or(ErrorCode::SwapExceedsReserves)?;
+        if new_amounts[destination_index] == 0 {
+            return err!(ErrorCode::SwapExceedsReserves);
+        }
+        let new_source_amount = Self::compute_y(amp, &new_amounts, source_index, d)?;
+        Ok(new_source_amount
+            .checked_sub(amounts[source_index])
+            .ok_or(ErrorCode::MathUnderflow)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::curve::{StableSwapCurve, MAX_AMP, MIN_AMP},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    fn compute_d_balanced_pool() {
+        // A balanced pool has D == sum(x_i) for any amplification
+        for amp in [MIN_AMP, 10, 100, 1_000, MAX_AMP] {
+            assert_eq!(
+                MultiStableSwapCurve::compute_d(amp, &[1_000_000; 3]).unwrap(),
+                3_000_000
+            );
+            assert_eq!(
+                MultiStableSwapCurve::compute_d(amp, &[1_000_000; 4]).unwrap(),
+                4_000_000
+            );
+        }
+        assert_eq!(MultiStableSwapCurve::compute_d(100, &[0; 3]).unwrap(), 0);
+    }
+
+    #[test]
+    fn tri_pool_swap_near_peg() {
+        let amounts = [1_000_000_000u128, 1_000_000_000, 1_000_000_000];
+        let destination_amount_swapped =
+            MultiStableSwapCurve::swap_base_input_without_fees(100, &amounts, 0, 2, 10_000_000)
+                .unwrap();
+        assert!(destination_amount_swapped <= 10_000_000);
+        assert!(destination_amount_swapped > 9_990_000);
+        assert_eq!(
+            MultiStableSwapCurve::swap_base_input_without_fees(100, &amounts, 1, 1, 10)
+                .unwrap_err(),
+            ErrorCode::InvalidInput.into()
+        );
+        assert_eq!(
+            MultiStableSwapCurve::swap_base_output_without_fees(100, &amounts, 0, 1, 1_000_000_000)
+                .unwrap_err(),
+            ErrorCode::SwapExceedsReserves.into()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn two_coins_match_stable_swap_curve(
+            amp in MIN_AMP..=MAX_AMP,
+            source_token_amount in 1..u32::MAX as u64,
+            swap_source_amount in 1_000..u64::MAX / 2,
+            swap_destination_amount in 1_000..u64::MAX / 2,
+        ) {
+            let amounts = [swap_source_amount as u128, swap_destination_amount as u128];
+            prop_assert_eq!(
+                MultiStableSwapCurve::compute_d(amp, &amounts).unwrap(),
+                StableSwapCurve::compute_d(amp, amounts[0], amounts[1]).unwrap()
+            );
+            let multi = MultiStableSwapCurve::swap_base_input_without_fees(
+                amp,
+                &amounts,
+                0,
+                1,
+                source_token_amount as u128,
+            )
+            .unwrap();
+            let stable = StableSwapCurve::swap_base_input_without_fees(
+                amp,
+                source_token_amount as u128,
+                amounts[0],
+                amounts[1],
+            )
+            .unwrap();
+            // c is divided one coin at a time, which may round y up by one more
+            prop_assert!(multi <= stable && multi + 1 >= stable);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn curve_value_does_not_decrease_from_swap(
+            amp in MIN_AMP..=MAX_AMP,
+            source_token_amount in 1..u64::MAX,
+            amounts in proptest::collection::vec(1..u64::MAX, 3..=4),
+            destination_index in 1..3usize,
+        ) {
+            let amounts: Vec<u128> = amounts.into_iter().map(u128::from).collect();
+            let d_before = MultiStableSwapCurve::compute_d(amp, &amounts).unwrap();
+            let destination_amount_swapped = MultiStableSwapCurve::swap_base_input_without_fees(
+                amp,
+                &amounts,
+                0,
+                destination_index,
+                source_token_amount as u128,
+            )
+            .unwrap();
+            let mut new_amounts = amounts.clone();
+            new_amounts[0] += source_token_amount as u128;
+            new_amounts[destination_index] -= destination_amount_swapped;
+            prop_assume!(new_amounts[destination_index] > 0);
+            let d_after = MultiStableSwapCurve::compute_d(amp, &new_amounts).unwrap();
+            prop_assert!(d_after >= d_before);
+        }
+    }
+}
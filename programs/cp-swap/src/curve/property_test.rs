@@ -0,0 +1,541 @@
+//! Property tests of the curve, fee and pool token math shared by all the instructions.
+//!
+//! The same properties are exercised with coverage guided inputs by the targets of the
+//! `fuzz` crate, a failing fuzz input belongs here as a regression test.
+
+use super::*;
+use anchor_lang::prelude::Result;
+use proptest::prelude::*;
+use spl_token_2022::extension::transfer_fee::TransferFee;
+
+/// The fee rates of the default amm config, 0.25% trade fee with 12% protocol and
+/// 4% fund shares
+const TRADE_FEE_RATE: u64 = 2500;
+const PROTOCOL_FEE_RATE: u64 = 120000;
+const FUND_FEE_RATE: u64 = 40000;
+
+fn curve_type(stable: bool, amp: u64) -> CurveType {
+    if stable {
+        CurveType::StableSwap { amp }
+    } else {
+        CurveType::ConstantProduct
+    }
+}
+
+/// A source token with one decimal less than the destination token when `scaled`
+fn amount_scale(scaled: bool) -> AmountScale {
+    if scaled {
+        AmountScale {
+            source_multiplier_x64: 10 * AMOUNT_MULTIPLIER_ONE_X64,
+            destination_multiplier_x64: AMOUNT_MULTIPLIER_ONE_X64,
+        }
+    } else {
+        AmountScale::ONE
+    }
+}
+
+/// Any curve a pool is created with, weighted pools with weights summing to
+/// WEIGHT_DENOMINATOR
+fn any_curve_type() -> impl Strategy<Value = CurveType> {
+    prop_oneof![
+        Just(CurveType::ConstantProduct),
+        (MIN_AMP..=MAX_AMP).prop_map(|amp| CurveType::StableSwap { amp }),
+        (MIN_WEIGHT..=MAX_WEIGHT).prop_map(|source_weight| CurveType::Weighted {
+            source_weight,
+            destination_weight: WEIGHT_DENOMINATOR - source_weight,
+        }),
+    ]
+}
+
+fn swap_side(exact_output: bool) -> SwapSide {
+    if exact_output {
+        SwapSide::ExactOutput
+    } else {
+        SwapSide::ExactInput
+    }
+}
+
+fn swap(
+    curve_type: CurveType,
+    amount_scale: AmountScale,
+    side: SwapSide,
+    amount_specified: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+    fee_on_output: bool,
+) -> Result<SwapResult> {
+    CurveCalculator::swap_scaled(
+        SwapParams {
+            curve_type,
+            amount_scale,
+            fee_rates: FeeRates {
+                trade_fee_rate: TRADE_FEE_RATE,
+                protocol_fee_rate: PROTOCOL_FEE_RATE,
+                fund_fee_rate: FUND_FEE_RATE,
+            },
+            fee_on_output,
+        },
+        side,
+        amount_specified,
+        swap_source_amount,
+        swap_destination_amount,
+    )
+}
+
+fn transfer_fee(transfer_fee_basis_points: u16, maximum_fee: u64) -> TransferFee {
+    TransferFee {
+        epoch: 0.into(),
+        maximum_fee: maximum_fee.into(),
+        transfer_fee_basis_points: transfer_fee_basis_points.into(),
+    }
+}
+
+proptest! {
+    #[test]
+    fn swap_output_is_monotonic_in_input(
+        source_amount in 1..u32::MAX as u64,
+        extra_amount in 1..u32::MAX as u64,
+        swap_source_amount in 1_000..u32::MAX as u64,
+        swap_destination_amount in 1_000..u32::MAX as u64,
+        amp in MIN_AMP..=MAX_AMP,
+        stable in proptest::bool::ANY,
+        scaled in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let curve_type = curve_type(stable, amp);
+        let amount_scale = amount_scale(scaled);
+        let exact_input = |amount: u64| {
+            swap(
+                curve_type,
+                amount_scale,
+                SwapSide::ExactInput,
+                u128::from(amount),
+                u128::from(swap_source_amount),
+                u128::from(swap_destination_amount),
+                fee_on_output,
+            )
+            .unwrap()
+        };
+        let smaller = exact_input(source_amount);
+        let larger = exact_input(source_amount + extra_amount);
+        prop_assert!(smaller.destination_amount_swapped <= larger.destination_amount_swapped);
+        prop_assert!(smaller.trade_fee <= larger.trade_fee);
+    }
+
+    #[test]
+    fn swap_input_is_monotonic_in_output(
+        destination_amount in 1..u16::MAX as u64,
+        extra_amount in 1..u16::MAX as u64,
+        swap_source_amount in 1_000..u32::MAX as u64,
+        swap_destination_amount in 1_000_000..u32::MAX as u64,
+        amp in MIN_AMP..=MAX_AMP,
+        stable in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let curve_type = curve_type(stable, amp);
+        let exact_output = |amount: u64| {
+            swap(
+                curve_type,
+                AmountScale::ONE,
+                SwapSide::ExactOutput,
+                u128::from(amount),
+                u128::from(swap_source_amount),
+                u128::from(swap_destination_amount),
+                fee_on_output,
+            )
+        };
+        let (Ok(smaller), Ok(larger)) = (
+            exact_output(destination_amount),
+            exact_output(destination_amount + extra_amount),
+        ) else {
+            // outputs close to the destination reserve are rejected
+            return Ok(());
+        };
+        prop_assert!(smaller.source_amount_swapped <= larger.source_amount_swapped);
+    }
+
+    #[test]
+    fn swap_never_reduces_the_invariant(
+        source_amount in 1..u32::MAX as u64,
+        swap_source_amount in 1_000..u32::MAX as u64,
+        swap_destination_amount in 1_000..u32::MAX as u64,
+        amp in MIN_AMP..=MAX_AMP,
+        stable in proptest::bool::ANY,
+        scaled in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let curve_type = curve_type(stable, amp);
+        let amount_scale = amount_scale(scaled);
+        let result = swap(
+            curve_type,
+            amount_scale,
+            SwapSide::ExactInput,
+            u128::from(source_amount),
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+            fee_on_output,
+        )
+        .unwrap();
+        let (new_swap_source_amount, new_swap_destination_amount) =
+            result.new_swap_amounts_without_fee().unwrap();
+        let invariant_before = CurveCalculator::invariant_scaled(
+            curve_type,
+            amount_scale,
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+        )
+        .unwrap();
+        let invariant_after = CurveCalculator::invariant_scaled(
+            curve_type,
+            amount_scale,
+            new_swap_source_amount,
+            new_swap_destination_amount,
+        )
+        .unwrap();
+        prop_assert!(invariant_after >= invariant_before);
+    }
+
+    #[test]
+    fn swap_never_reduces_the_invariant_of_any_curve(
+        amount_specified in 1..u16::MAX as u64,
+        swap_source_amount in 1_000_000..u32::MAX as u64,
+        swap_destination_amount in 1_000_000..u32::MAX as u64,
+        curve_type in any_curve_type(),
+        scaled in proptest::bool::ANY,
+        exact_output in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let amount_scale = amount_scale(scaled);
+        let Ok(result) = swap(
+            curve_type,
+            amount_scale,
+            swap_side(exact_output),
+            u128::from(amount_specified),
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+            fee_on_output,
+        ) else {
+            // outputs close to the destination reserve are rejected
+            return Ok(());
+        };
+        let (new_swap_source_amount, new_swap_destination_amount) =
+            result.new_swap_amounts_without_fee().unwrap();
+        let invariant_before = CurveCalculator::invariant_scaled(
+            curve_type,
+            amount_scale,
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+        )
+        .unwrap();
+        let invariant_after = CurveCalculator::invariant_scaled(
+            curve_type,
+            amount_scale,
+            new_swap_source_amount,
+            new_swap_destination_amount,
+        )
+        .unwrap();
+        prop_assert!(invariant_after >= invariant_before);
+    }
+
+    #[test]
+    fn swap_rounds_in_favor_of_the_pool_on_any_curve(
+        source_amount in 1..u32::MAX as u64,
+        swap_source_amount in 1_000_000..u32::MAX as u64,
+        swap_destination_amount in 1_000_000..u32::MAX as u64,
+        curve_type in any_curve_type(),
+        scaled in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let amount_scale = amount_scale(scaled);
+        let swap = |side, amount_specified| {
+            swap(
+                curve_type,
+                amount_scale,
+                side,
+                amount_specified,
+                u128::from(swap_source_amount),
+                u128::from(swap_destination_amount),
+                fee_on_output,
+            )
+        };
+        let exact_input = swap(SwapSide::ExactInput, u128::from(source_amount)).unwrap();
+        prop_assume!(exact_input.destination_amount_swapped > 0);
+        // the output of an exact input swap is rounded down and the input of an exact
+        // output swap up, buying that output back never costs more than the input
+        let Ok(exact_output) = swap(SwapSide::ExactOutput, exact_input.destination_amount_swapped)
+        else {
+            return Ok(());
+        };
+        prop_assert!(exact_output.source_amount_swapped <= exact_input.source_amount_swapped);
+        // and is always enough to buy it
+        let round_trip = swap(SwapSide::ExactInput, exact_output.source_amount_swapped).unwrap();
+        prop_assert!(
+            round_trip.destination_amount_swapped >= exact_output.destination_amount_swapped
+        );
+    }
+
+    #[test]
+    fn swap_fee_split_stays_within_the_trade_fee_on_any_curve(
+        amount_specified in 1..u32::MAX as u64,
+        swap_source_amount in 1_000_000..u32::MAX as u64,
+        swap_destination_amount in 1_000_000..u32::MAX as u64,
+        curve_type in any_curve_type(),
+        trade_fee_rate in 0..FEE_RATE_DENOMINATOR_VALUE / 10,
+        protocol_fee_rate in 0..=FEE_RATE_DENOMINATOR_VALUE / 2,
+        fund_fee_rate in 0..=FEE_RATE_DENOMINATOR_VALUE / 2,
+        exact_output in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let params = SwapParams {
+            curve_type,
+            amount_scale: AmountScale::ONE,
+            fee_rates: FeeRates {
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+            },
+            fee_on_output,
+        };
+        let Ok(result) = CurveCalculator::swap_scaled(
+            params,
+            swap_side(exact_output),
+            u128::from(amount_specified),
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+        ) else {
+            return Ok(());
+        };
+        prop_assert_eq!(result.fee_on_output, fee_on_output);
+        prop_assert_eq!(
+            result.protocol_fee,
+            Fees::protocol_fee(result.trade_fee, protocol_fee_rate).unwrap()
+        );
+        prop_assert_eq!(
+            result.fund_fee,
+            Fees::fund_fee(result.trade_fee, fund_fee_rate).unwrap()
+        );
+        // the protocol and fund fees are shares of the trade fee, the rest goes to the
+        // liquidity providers
+        prop_assert!(result.protocol_fee + result.fund_fee <= result.trade_fee);
+        // the trade fee is taken on the amount traded on the curve, rounded up
+        let fee_amount = if fee_on_output {
+            result.destination_amount_swapped + result.trade_fee
+        } else {
+            result.source_amount_swapped
+        };
+        let trade_fee = Fees::trading_fee(fee_amount, trade_fee_rate).unwrap();
+        if exact_output {
+            // the pre fee amount of an exact output swap rounds the fee up by at most one
+            prop_assert!(result.trade_fee >= trade_fee.saturating_sub(1));
+            prop_assert!(result.trade_fee <= trade_fee + 1);
+        } else {
+            prop_assert_eq!(result.trade_fee, trade_fee);
+        }
+    }
+
+    #[test]
+    fn swap_round_trip_gives_no_free_tokens(
+        source_amount in 1..u32::MAX as u64,
+        swap_source_amount in 1_000..u32::MAX as u64,
+        swap_destination_amount in 1_000..u32::MAX as u64,
+        amp in MIN_AMP..=MAX_AMP,
+        stable in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let curve_type = curve_type(stable, amp);
+        let there = swap(
+            curve_type,
+            AmountScale::ONE,
+            SwapSide::ExactInput,
+            u128::from(source_amount),
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+            fee_on_output,
+        )
+        .unwrap();
+        prop_assume!(there.destination_amount_swapped > 0);
+        let (new_swap_source_amount, new_swap_destination_amount) =
+            there.new_swap_amounts_without_fee().unwrap();
+        // swapping the output back through the pool in the opposite direction
+        let back = swap(
+            curve_type,
+            AmountScale::ONE,
+            SwapSide::ExactInput,
+            there.destination_amount_swapped,
+            new_swap_destination_amount,
+            new_swap_source_amount,
+            fee_on_output,
+        )
+        .unwrap();
+        prop_assert!(back.destination_amount_swapped <= u128::from(source_amount));
+    }
+
+    #[test]
+    fn swap_at_u64_max_reserves_does_not_overflow(
+        amount_specified in 1..=u64::MAX,
+        swap_source_amount in u64::MAX - u32::MAX as u64..=u64::MAX,
+        swap_destination_amount in u64::MAX - u32::MAX as u64..=u64::MAX,
+        amp in MIN_AMP..=MAX_AMP,
+        stable in proptest::bool::ANY,
+        exact_output in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let side = if exact_output {
+            SwapSide::ExactOutput
+        } else {
+            SwapSide::ExactInput
+        };
+        // the math either errors or stays within the reserves, it never panics
+        if let Ok(result) = swap(
+            curve_type(stable, amp),
+            AmountScale::ONE,
+            side,
+            u128::from(amount_specified),
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+            fee_on_output,
+        ) {
+            prop_assert!(result.destination_amount_swapped < u128::from(swap_destination_amount));
+            prop_assert!(result.new_swap_destination_amount <= u128::from(swap_destination_amount));
+            prop_assert!(result.trade_fee <= result.source_amount_swapped.max(result.destination_amount_swapped));
+        }
+    }
+
+    #[test]
+    fn lp_share_round_trip_gives_no_free_tokens(
+        token_0_amount in 1..u64::MAX,
+        token_1_amount in 1..u64::MAX,
+        lp_token_supply in 1..u64::MAX,
+        swap_token_0_amount in 1..u64::MAX,
+        swap_token_1_amount in 1..u64::MAX,
+    ) {
+        let lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens(
+            u128::from(token_0_amount),
+            u128::from(token_1_amount),
+            u128::from(lp_token_supply),
+            u128::from(swap_token_0_amount),
+            u128::from(swap_token_1_amount),
+        )
+        .unwrap();
+        // the pool tokens of a deposit have to fit the supply of the lp mint
+        prop_assume!(lp_token_amount <= u128::from(u64::MAX));
+        // withdrawing the pool tokens minted for a deposit never returns more than it
+        let withdrawn = CurveCalculator::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            u128::from(lp_token_supply),
+            u128::from(swap_token_0_amount),
+            u128::from(swap_token_1_amount),
+            RoundDirection::Floor,
+        )
+        .unwrap();
+        prop_assert!(withdrawn.token_0_amount <= u128::from(token_0_amount));
+        prop_assert!(withdrawn.token_1_amount <= u128::from(token_1_amount));
+
+        // and the pool tokens burned for a withdrawal always cover it
+        let lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens_ceiling(
+            u128::from(token_0_amount),
+            u128::from(token_1_amount),
+            u128::from(lp_token_supply),
+            u128::from(swap_token_0_amount),
+            u128::from(swap_token_1_amount),
+        )
+        .unwrap();
+        prop_assume!(lp_token_amount <= u128::from(u64::MAX));
+        let withdrawn = CurveCalculator::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            u128::from(lp_token_supply),
+            u128::from(swap_token_0_amount),
+            u128::from(swap_token_1_amount),
+            RoundDirection::Floor,
+        )
+        .unwrap();
+        prop_assert!(withdrawn.token_0_amount >= u128::from(token_0_amount));
+        prop_assert!(withdrawn.token_1_amount >= u128::from(token_1_amount));
+    }
+
+    #[test]
+    fn lp_share_deposit_ceiling_covers_floor_withdrawal(
+        lp_token_amount in 1..u64::MAX,
+        lp_token_supply in 1..u64::MAX,
+        swap_token_0_amount in 1..u64::MAX,
+        swap_token_1_amount in 1..u64::MAX,
+    ) {
+        let convert = |round_direction| {
+            CurveCalculator::lp_tokens_to_trading_tokens(
+                u128::from(lp_token_amount),
+                u128::from(lp_token_supply),
+                u128::from(swap_token_0_amount),
+                u128::from(swap_token_1_amount),
+                round_direction,
+            )
+            .unwrap()
+        };
+        let deposited = convert(RoundDirection::Ceiling);
+        let withdrawn = convert(RoundDirection::Floor);
+        prop_assert!(deposited.token_0_amount >= withdrawn.token_0_amount);
+        prop_assert!(deposited.token_1_amount >= withdrawn.token_1_amount);
+        prop_assert!(deposited.token_0_amount - withdrawn.token_0_amount <= 1);
+        prop_assert!(deposited.token_1_amount - withdrawn.token_1_amount <= 1);
+    }
+
+    #[test]
+    fn transfer_fee_adjusted_input_covers_exact_output(
+        destination_amount in 1..u16::MAX as u64,
+        swap_source_amount in 1_000..u32::MAX as u64,
+        swap_destination_amount in 1_000_000..u32::MAX as u64,
+        transfer_fee_basis_points in 0..10_000u16,
+        maximum_fee in 0..u32::MAX as u64,
+        amp in MIN_AMP..=MAX_AMP,
+        stable in proptest::bool::ANY,
+        fee_on_output in proptest::bool::ANY,
+    ) {
+        let transfer_fee = transfer_fee(transfer_fee_basis_points, maximum_fee);
+        let result = swap(
+            curve_type(stable, amp),
+            AmountScale::ONE,
+            SwapSide::ExactOutput,
+            u128::from(destination_amount),
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+            fee_on_output,
+        )
+        .unwrap();
+        let source_amount = u64::try_from(result.source_amount_swapped).unwrap();
+        // the trader pays the inverse transfer fee on top of the swapped source amount
+        let inverse_fee = transfer_fee.calculate_inverse_fee(source_amount).unwrap();
+        let amount_in = source_amount + inverse_fee;
+        let received = amount_in - transfer_fee.calculate_fee(amount_in).unwrap();
+        prop_assert!(received >= source_amount);
+    }
+
+    #[test]
+    fn transfer_fee_adjusted_input_is_never_swapped_twice(
+        amount_in in 1..u64::MAX,
+        swap_source_amount in 1_000..u32::MAX as u64,
+        swap_destination_amount in 1_000..u32::MAX as u64,
+        transfer_fee_basis_points in 0..=10_000u16,
+        maximum_fee in 0..=u64::MAX,
+        amp in MIN_AMP..=MAX_AMP,
+        stable in proptest::bool::ANY,
+    ) {
+        let transfer_fee = transfer_fee(transfer_fee_basis_points, maximum_fee);
+        let fee = transfer_fee.calculate_fee(amount_in).unwrap();
+        prop_assert!(fee <= amount_in);
+        prop_assert!(fee <= maximum_fee);
+        let actual_amount_in = amount_in - fee;
+        prop_assume!(actual_amount_in > 0);
+        // the pool only ever swaps what its vault receives
+        if let Ok(result) = swap(
+            curve_type(stable, amp),
+            AmountScale::ONE,
+            SwapSide::ExactInput,
+            u128::from(actual_amount_in),
+            u128::from(swap_source_amount),
+            u128::from(swap_destination_amount),
+            false,
+        ) {
+            prop_assert_eq!(result.source_amount_swapped, u128::from(actual_amount_in));
+            prop_assert!(result.destination_amount_swapped < u128::from(swap_destination_amount));
+        }
+    }
+}
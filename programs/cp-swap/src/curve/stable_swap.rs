@@ -0,0 +1,319 @@
+//! The StableSwap invariant, as popularized by Curve
+
+use crate::error::ErrorCode;
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+/// Number of coins in a pool
+const N_COINS: u8 = 2;
+/// Number of coins squared
+const N_COINS_SQUARED: u8 = 4;
+/// Maximum number of iterations for the newton method
+const ITERATIONS: u16 = 256;
+
+/// Minimum amplification coefficient
+pub const MIN_AMP: u64 = 1;
+/// Maximum amplification coefficient
+pub const MAX_AMP: u64 = 1_000_000;
+
+/// StableSwapCurve struct implementing CurveCalculator
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StableSwapCurve;
+
+/// Compute a ** b for U256
+fn checked_u8_power(a: &U256, b: u8) -> Option<U256> {
+    let mut result = *a;
+    for _ in 1..b {
+        result = result.checked_mul(*a)?;
+    }
+    Some(result)
+}
+
+/// Compute a * b for U256
+fn checked_u8_mul(a: &U256, b: u8) -> Option<U256> {
+    a.checked_mul(U256::from(b))
+}
+
+/// Compute the ceiling of a / b for U256
+fn checked_ceil_div(a: U256, b: U256) -> Option<U256> {
+    let quotient = a.checked_div(b)?;
+    if a.checked_rem(b)?.is_zero() {
+        Some(quotient)
+    } else {
+        quotient.checked_add(U256::one())
+    }
+}
+
+impl StableSwapCurve {
+    /// Returns the leverage of the curve, `A * n`
+    fn leverage(amp: u64) -> Option<u64> {
+        amp.checked_mul(u64::from(N_COINS))
+    }
+
+    /// d = (leverage * sum_x + d_product * n_coins) * initial_d / ((leverage - 1) * initial_d + (n_coins + 1) * d_product)
+    fn calculate_step(
+        initial_d: &U256,
+        leverage: u64,
+        sum_x: u128,
+        d_product: &U256,
+    ) -> Option<U256> {
+        let leverage_mul = U256::from(leverage).checked_mul(sum_x.into())?;
+        let d_p_mul = checked_u8_mul(d_product, N_COINS)?;
+
+        let l_val = leverage_mul.checked_add(d_p_mul)?.checked_mul(*initial_d)?;
+
+        let leverage_sub = initial_d.checked_mul((leverage.checked_sub(1)?).into())?;
+        let n_coins_sum = checked_u8_mul(d_product, N_COINS.checked_add(1)?)?;
+
+        let r_val = leverage_sub.checked_add(n_coins_sum)?;
+
+        l_val.checked_div(r_val)
+    }
+
+    /// Compute the stable swap invariant (D)
+    ///
+    /// Equation:
+    ///
+    /// A * sum(x_i) * n**n + D = A * D * n**n + D**(n+1) / (n**n * prod(x_i))
+    pub fn compute_d(amp: u64, amount_0: u128, amount_1: u128) -> Result<u128> {
+        let leverage = Self::leverage(amp).ok_or(ErrorCode::MathOverflow)?;
+        let amount_0_times_coins = checked_u8_mul(&U256::from(amount_0), N_COINS)
+            .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+        let amount_1_times_coins = checked_u8_mul(&U256::from(amount_1), N_COINS)
+            .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+        let sum_x = amount_0
+            .checked_add(amount_1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if sum_x == 0 {
+            return Ok(0);
+        }
+        let mut d_previous: U256;
+        let mut d: U256 = sum_x.into();
+
+        // Newton's method to approximate D
+        for _ in 0..ITERATIONS {
+            let mut d_product = d;
+            d_product = d_product
+                .checked_mul(d)
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?
+                .checked_div(amount_0_times_coins)
+                .ok_or(ErrorCode::DivisionByZero)?;
+            d_product = d_product
+                .checked_mul(d)
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?
+                .checked_div(amount_1_times_coins)
+                .ok_or(ErrorCode::DivisionByZero)?;
+            d_previous = d;
+            d = Self::calculate_step(&d, leverage, sum_x, &d_product)
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+            // Equality with the precision of 1
+            if d == d_previous {
+                break;
+            }
+        }
+        u128::try_from(d).map_err(|_| error!(ErrorCode::StableSwapCalculationFailed))
+    }
+
+    /// Compute the new balance of one side of the pool given the new balance
+    /// of the other side and the invariant D, rounded up in favor of the pool
+    ///
+    /// Solve for y:
+    /// y**2 + y * (sum' - (A*n**n - 1) * D / (A * n**n)) = D ** (n + 1) / (n ** (2 * n) * prod' * A)
+    /// y**2 + b*y = c
+    pub fn compute_y(amp: u64, new_source_amount: u128, d_val: u128) -> Result<u128> {
+        let leverage: U256 = Self::leverage(amp).ok_or(ErrorCode::MathOverflow)?.into();
+        let new_source_amount: U256 = new_source_amount.into();
+        let d_val: U256 = d_val.into();
+
+        // sum' = prod' = x
+        // c =  D ** (n + 1) / (n ** (2 * n) * prod' * A)
+        let c = checked_u8_power(&d_val, N_COINS + 1)
+            .ok_or(ErrorCode::StableSwapCalculationFailed)?
+            .checked_div(
+                checked_u8_mul(&new_source_amount, N_COINS_SQUARED)
+                    .and_then(|x| x.checked_mul(leverage))
+                    .ok_or(ErrorCode::StableSwapCalculationFailed)?,
+            )
+            .ok_or(ErrorCode::DivisionByZero)?;
+
+        // b = sum' - (A*n**n - 1) * D / (A * n**n)
+        let b = new_source_amount
+            .checked_add(
+                d_val
+                    .checked_div(leverage)
+                    .ok_or(ErrorCode::DivisionByZero)?,
+            )
+            .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+
+        // Solve for y by approximating: y**2 + b*y = c
+        let mut y = d_val;
+        for _ in 0..ITERATIONS {
+            let numerator = checked_u8_power(&y, 2)
+                .and_then(|y_squared| y_squared.checked_add(c))
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+            let denominator = checked_u8_mul(&y, 2)
+                .and_then(|y_double| y_double.checked_add(b))
+                .and_then(|y_double| y_double.checked_sub(d_val))
+                .ok_or(ErrorCode::StableSwapCalculationFailed)?;
+            let y_new =
+                checked_ceil_div(numerator, denominator).ok_or(ErrorCode::DivisionByZero)?;
+            if y_new == y {
+                break;
+            } else {
+                y = y_new;
+            }
+        }
+        u128::try_from(y).map_err(|_| error!(ErrorCode::StableSwapCalculationFailed))
+    }
+
+    /// StableSwap swap ensures D is kept constant
+    ///
+    /// The destination amount swapped is rounded down in favor of the pool.
+    pub fn swap_base_input_without_fees(
+        amp: u64,
+        source_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<u128> {
+        let d = Self::compute_d(amp, swap_source_amount, swap_destination_amount)?;
+        let new_swap_source_amount = swap_source_amount
+            .checked_add(source_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let new_swap_destination_amount = Self::compute_y(amp, new_swap_source_amount, d)?;
+        Ok(swap_destination_amount.saturating_sub(new_swap_destination_amount))
+    }
+
+    /// The source amount required to receive `destinsation_amount` is rounded
+    /// up in favor of the pool.
+    pub fn swap_base_output_without_fees(
+        amp: u64,
+        destinsation_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<u128> {
+        let d = Self::compute_d(amp, swap_source_amount, swap_destination_amount)?;
+        let new_swap_destination_amount = swap_destination_amount
+            .checked_sub(destinsation_amount)
+            .ok_or(ErrorCode::SwapExceedsReserves)?;
+        if new_swap_destination_amount == 0 {
+            return err!(ErrorCode::SwapExceedsReserves);
+        }
+        let new_swap_source_amount = Self::compute_y(amp, new_swap_destination_amount, d)?;
+        Ok(new_swap_source_amount
+            .checked_sub(swap_source_amount)
+            .ok_or(ErrorCode::MathUnderflow)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, proptest::prelude::*};
+
+    #[test]
+    fn compute_d_balanced_pool() {
+        // A balanced pool has D == x + y for any amplification
+        for amp in [MIN_AMP, 10, 100, 1_000, MAX_AMP] {
+            assert_eq!(
+                StableSwapCurve::compute_d(amp, 1_000_000, 1_000_000).unwrap(),
+                2_000_000
+            );
+        }
+        assert_eq!(StableSwapCurve::compute_d(100, 0, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn stable_swap_better_than_constant_product_near_peg() {
+        let swap_source_amount = 1_000_000_000u128;
+        let swap_destination_amount = 1_000_000_000u128;
+        let source_amount = 10_000_000u128;
+        let stable = StableSwapCurve::swap_base_input_without_fees(
+            100,
+            source_amount,
+            swap_source_amount,
+            swap_destination_amount,
+        )
+        .unwrap();
+        let constant_product = crate::curve::ConstantProductCurve::swap_base_input_without_fees(
+            source_amount,
+            swap_source_amount,
+            swap_destination_amount,
+        )
+        .unwrap();
+        assert!(stable > constant_product);
+        assert!(stable <= source_amount);
+    }
+
+    #[test]
+    fn fail_swap_base_output_drains_pool() {
+        assert_eq!(
+            StableSwapCurve::swap_base_output_without_fees(100, 1_000, 1_000, 1_000).unwrap_err(),
+            ErrorCode::SwapExceedsReserves.into()
+        );
+        assert_eq!(
+            StableSwapCurve::swap_base_output_without_fees(100, 1_001, 1_000, 1_000).unwrap_err(),
+            ErrorCode::SwapExceedsReserves.into()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn curve_value_does_not_decrease_from_swap(
+            amp in MIN_AMP..=MAX_AMP,
+            source_token_amount in 1..u64::MAX,
+            swap_source_amount in 1..u64::MAX,
+            swap_destination_amount in 1..u64::MAX,
+        ) {
+            let source_token_amount = source_token_amount as u128;
+            let swap_source_amount = swap_source_amount as u128;
+            let swap_destination_amount = swap_destination_amount as u128;
+            let d_before = StableSwapCurve::compute_d(
+                amp,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap();
+            let destination_amount_swapped = StableSwapCurve::swap_base_input_without_fees(
+                amp,
+                source_token_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap();
+            let d_after = StableSwapCurve::compute_d(
+                amp,
+                swap_source_amount + source_token_amount,
+                swap_destination_amount - destination_amount_swapped,
+            )
+            .unwrap();
+            prop_assert!(d_after >= d_before);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn swap_base_output_covers_swap_base_input(
+            amp in MIN_AMP..=MAX_AMP,
+            source_token_amount in 1..u32::MAX as u64,
+            swap_source_amount in 1_000..u64::MAX / 2,
+            swap_destination_amount in 1_000..u64::MAX / 2,
+        ) {
+            let destination_amount_swapped = StableSwapCurve::swap_base_input_without_fees(
+                amp,
+                source_token_amount as u128,
+                swap_source_amount as u128,
+                swap_destination_amount as u128,
+            )
+            .unwrap();
+            prop_assume!(destination_amount_swapped > 0);
+            let source_amount_required = StableSwapCurve::swap_base_output_without_fees(
+                amp,
+                destination_amount_swapped,
+                swap_source_amount as u128,
+                swap_destination_amount as u128,
+            )
+            .unwrap();
+            // Asking for the same output never costs more than the input that produced it
+            prop_assert!(source_amount_required <= source_token_amount as u128 + 1);
+        }
+    }
+}
@@ -0,0 +1,522 @@
+//! The weighted constant mean invariant, as popularized by Balancer
+
+use crate::error::ErrorCode;
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+/// Denominator of the token weights, the weights of both tokens of a pool sum to it
+pub const WEIGHT_DENOMINATOR: u64 = 10_000;
+/// Minimum weight of a token, 2%
+pub const MIN_WEIGHT: u64 = 200;
+/// Maximum weight of a token, 98%
+pub const MAX_WEIGHT: u64 = WEIGHT_DENOMINATOR - MIN_WEIGHT;
+/// Upper bound of the rounding error of `log2_x64`, Q64.64
+const LOG2_ERROR_X64: u128 = 1 << 6;
+/// Maximum number of steps raising an amount rounded by the logarithm to the invariant
+const ITERATIONS: u16 = 128;
+
+/// 2 ** (2 ** -i) for i in 1..=64, Q64.64 rounded up
+const EXP2_FACTORS_X64: [u128; 64] = [
+    0x16a09e667f3bcc909,
+    0x1306fe0a31b7152df,
+    0x1172b83c7d517adce,
+    0x10b5586cf9890f62a,
+    0x1059b0d31585743af,
+    0x102c9a3e778060ee7,
+    0x10163da9fb33356d9,
+    0x100b1afa5abcbed62,
+    0x10058c86da1c09ea2,
+    0x1002c605e2e8cec51,
+    0x100162f3904051fa2,
+    0x1000b175effdc76bb,
+    0x100058ba01fb9f96e,
+    0x10002c5cc37da9492,
+    0x1000162e525ee0548,
+    0x10000b17255775c05,
+    0x1000058b91b5bc9af,
+    0x100002c5c89d5ec6d,
+    0x10000162e43f4f832,
+    0x100000b1721bcfc9a,
+    0x10000058b90cf1e6e,
+    0x1000002c5c863b740,
+    0x100000162e430e5a2,
+    0x1000000b172183552,
+    0x100000058b90c0b49,
+    0x10000002c5c8601cd,
+    0x1000000162e42fff1,
+    0x10000000b17217fbb,
+    0x1000000058b90bfce,
+    0x100000002c5c85fe4,
+    0x10000000162e42ff1,
+    0x100000000b17217f9,
+    0x10000000058b90bfc,
+    0x1000000002c5c85fe,
+    0x100000000162e42ff,
+    0x1000000000b172180,
+    0x100000000058b90c0,
+    0x10000000002c5c860,
+    0x1000000000162e430,
+    0x10000000000b17218,
+    0x1000000000058b90c,
+    0x100000000002c5c86,
+    0x10000000000162e43,
+    0x100000000000b1722,
+    0x10000000000058b91,
+    0x1000000000002c5c9,
+    0x100000000000162e5,
+    0x1000000000000b173,
+    0x100000000000058ba,
+    0x10000000000002c5d,
+    0x1000000000000162f,
+    0x10000000000000b18,
+    0x1000000000000058c,
+    0x100000000000002c6,
+    0x10000000000000163,
+    0x100000000000000b2,
+    0x10000000000000059,
+    0x1000000000000002d,
+    0x10000000000000017,
+    0x1000000000000000c,
+    0x10000000000000006,
+    0x10000000000000003,
+    0x10000000000000002,
+    0x10000000000000001,
+];
+
+/// WeightedCurve struct implementing CurveCalculator
+///
+/// The pool trades on `x ** w_x * y ** w_y = k`, so the token of weight `w_x` holds
+/// `w_x / (w_x + w_y)` of the pool value at any price. The invariant is evaluated in the
+/// log domain, `w_x * log2(x) + w_y * log2(y)`, and the swaps are solved on the same
+/// logarithm so they never decrease it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WeightedCurve;
+
+/// Compute log2(x) for x > 0, Q64.64 rounded down
+pub(crate) fn log2_x64(x: u128) -> u128 {
+    let msb = 127 - x.leading_zeros();
+    // the mantissa in [1, 2), Q1.63
+    let mut mantissa = if msb >= 63 {
+        x >> (msb - 63)
+    } else {
+        x << (63 - msb)
+    };
+    let mut result = u128::from(msb) << 64;
+    for bit in (0..64).rev() {
+        mantissa = (mantissa * mantissa) >> 63;
+        if mantissa >= 1 << 64 {
+            mantissa >>= 1;
+            result |= 1 << bit;
+        }
+    }
+    result
+}
+
+/// Compute 2 ** (y / 2 ** 64), rounded up to an integer
+pub(crate) fn exp2_ceil(y_x64: u128) -> Option<u128> {
+    let integer = u32::try_from(y_x64 >> 64).ok()?;
+    if integer >= 128 {
+        return None;
+    }
+    let one_x64 = U256::one() << 64;
+    let mut result_x64 = one_x64;
+    for (i, factor_x64) in EXP2_FACTORS_X64.iter().enumerate() {
+        if y_x64 & (1u128 << (63 - i)) != 0 {
+            result_x64 = (result_x64 * U256::from(*factor_x64) + (one_x64 - 1)) >> 64;
+        }
+    }
+    let result = ((result_x64 << integer) + (one_x64 - 1)) >> 64;
+    u128::try_from(result).ok()
+}
+
+/// Compute the ceiling of a / b for u128
+fn ceil_div(a: u128, b: u128) -> Option<u128> {
+    a.checked_add(b.checked_sub(1)?)?.checked_div(b)
+}
+
+impl WeightedCurve {
+    /// Compute the invariant of the weighted pool in the log domain,
+    /// `w_source * log2(source) + w_destination * log2(destination)`, Q64.64. It is zero
+    /// once a token is drained
+    pub fn compute_invariant(
+        source_weight: u64,
+        destination_weight: u64,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<u128> {
+        if swap_source_amount == 0 || swap_destination_amount == 0 {
+            return Ok(0);
+        }
+        log2_x64(swap_source_amount)
+            .checked_mul(u128::from(source_weight))
+            .and_then(|source_log| {
+                source_log.checked_add(
+                    log2_x64(swap_destination_amount)
+                        .checked_mul(u128::from(destination_weight))?,
+                )
+            })
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// Solve the amount of the token of weight `weight` restoring `invariant` given the
+    /// weighted logarithm `other_log_x64` of the other token, rounded up in favor of the pool
+    fn solve_amount(
+        invariant: u128,
+        weight: u64,
+        other_log_x64: u128,
+        invariant_of: impl Fn(u128) -> Result<u128>,
+    ) -> Result<u128> {
+        let mut amount = match invariant.checked_sub(other_log_x64) {
+            Some(log_x64) if log_x64 > 0 => {
+                exp2_ceil(ceil_div(log_x64, u128::from(weight)).ok_or(ErrorCode::DivisionByZero)?)
+                    .ok_or(ErrorCode::MathOverflow)?
+            }
+            _ => 1,
+        };
+        // raise the amount until the logarithm rounded down reaches the invariant
+        let mut step = 1u128;
+        for _ in 0..ITERATIONS {
+            if invariant_of(amount)? >= invariant {
+                return Ok(amount);
+            }
+            amount = amount.checked_add(step).ok_or(ErrorCode::MathOverflow)?;
+            step = step.saturating_mul(2);
+        }
+        err!(ErrorCode::WeightedCalculationFailed)
+    }
+
+    /// Swap `source_amount` of the token of weight `source_weight` for the token of weight
+    /// `destination_weight`, the destination amount swapped is rounded down in favor of the
+    /// pool
+    pub fn swap_base_input_without_fees(
+        source_weight: u64,
+        destination_weight: u64,
+        source_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<u128> {
+        if source_amount == 0 {
+            return Ok(0);
+        }
+        let invariant = Self::compute_invariant(
+            source_weight,
+            destination_weight,
+            swap_source_amount,
+            swap_destination_amount,
+        )?;
+        let new_swap_source_amount = swap_source_amount
+            .checked_add(source_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let source_log_x64 = log2_x64(new_swap_source_amount)
+            .checked_mul(u128::from(source_weight))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let new_swap_destination_amount =
+            Self::solve_amount(invariant, destination_weight, source_log_x64, |amount| {
+                Self::compute_invariant(
+                    source_weight,
+                    destination_weight,
+                    new_swap_source_amount,
+                    amount,
+                )
+            })?;
+        Ok(swap_destination_amount.saturating_sub(new_swap_destination_amount))
+    }
+
+    /// Get the source amount required to receive `destination_amount`, rounded up in favor
+    /// of the pool
+    pub fn swap_base_output_without_fees(
+        source_weight: u64,
+        destination_weight: u64,
+        destination_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+    ) -> Result<u128> {
+        let new_swap_destination_amount = swap_destination_amount
+            .checked_sub(destination_amount)
+            .ok_or(ErrorCode::SwapExceedsReserves)?;
+        if new_swap_destination_amount == 0 {
+            return err!(ErrorCode::SwapExceedsReserves);
+        }
+        let invariant = Self::compute_invariant(
+            source_weight,
+            destination_weight,
+            swap_source_amount,
+            swap_destination_amount,
+        )?;
+        let destination_log_x64 = log2_x64(new_swap_destination_amount)
+            .checked_mul(u128::from(destination_weight))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let new_swap_source_amount =
+            Self::solve_amount(invariant, source_weight, destination_log_x64, |amount| {
+                Self::compute_invariant(
+                    source_weight,
+                    destination_weight,
+                    amount,
+                    new_swap_destination_amount,
+                )
+            })?;
+        // a positive output always costs at least one source token
+        Ok(new_swap_source_amount
+            .saturating_sub(swap_source_amount)
+            .max(u128::from(destination_amount > 0)))
+    }
+
+    /// Get the weighted geometric mean of the token amounts, `x ** w_x * y ** w_y` with
+    /// weights normalized to one, rounded up. It scales linearly with the amounts like the
+    /// lp supply
+    pub fn value(
+        token_0_weight: u64,
+        token_1_weight: u64,
+        token_0_amount: u128,
+        token_1_amount: u128,
+    ) -> Result<u128> {
+        let invariant = Self::compute_invariant(
+            token_0_weight,
+            token_1_weight,
+            token_0_amount,
+            token_1_amount,
+        )?;
+        if invariant == 0 {
+            return Ok(0);
+        }
+        exp2_ceil(invariant / u128::from(token_0_weight + token_1_weight))
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// Whether the invariant backing each lp token did not decrease, up to the rounding of
+    /// the logarithms. The invariant is the log of a value of degree one in the amounts, so
+    /// the log of the lp supply weighted by the sum of the weights is subtracted from it
+    pub fn invariant_per_lp_token_preserved(
+        weight_sum: u64,
+        invariant_before: u128,
+        lp_supply_before: u64,
+        invariant_after: u128,
+        lp_supply_after: u64,
+    ) -> Result<bool> {
+        if lp_supply_after == 0 {
+            return Ok(true);
+        }
+        let weighted_log =
+            |lp_supply: u64| log2_x64(u128::from(lp_supply)).checked_mul(u128::from(weight_sum));
+        let tolerance_x64 = LOG2_ERROR_X64 * 3 * u128::from(weight_sum);
+        let value_before = invariant_before
+            .checked_add(weighted_log(lp_supply_after).ok_or(ErrorCode::MathOverflow)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let value_after = invariant_after
+            .checked_add(weighted_log(lp_supply_before).ok_or(ErrorCode::MathOverflow)?)
+            .and_then(|value| value.checked_add(tolerance_x64))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(value_after >= value_before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::curve::{CurveCalculator, CurveType},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    fn log2_and_exp2_are_accurate() {
+        for exponent in 0..128u32 {
+            assert_eq!(log2_x64(1u128 << exponent), u128::from(exponent) << 64);
+            assert_eq!(
+                exp2_ceil(u128::from(exponent) << 64).unwrap(),
+                1u128 << exponent
+            );
+        }
+        for x in [3u128, 10, 1_000_003, u64::MAX as u128, u128::MAX] {
+            let log2 = log2_x64(x) as f64 / 2f64.powi(64);
+            assert!((log2 - (x as f64).log2()).abs() < 1e-12);
+        }
+        // 2 ** 1.5
+        assert_eq!(exp2_ceil(3 << 63).unwrap(), 3);
+        assert_eq!(exp2_ceil((1_000_000u128 << 64) / 1_000), None);
+        // 1_000_000 ** 0.8 * 10_000 ** 0.2 = 10 ** 5.6
+        let value = WeightedCurve::value(8_000, 2_000, 1_000_000, 10_000).unwrap();
+        assert!((value as f64 - 10f64.powf(5.6)).abs() <= 1.0);
+    }
+
+    #[test]
+    fn weighted_swap_prices_the_weights() {
+        // 80/20 pool priced at 1 token_0 for 1 token_1 holds 4 times more token_0
+        let swap_source_amount = 4_000_000_000u128;
+        let swap_destination_amount = 1_000_000_000u128;
+        let destination_amount_swapped = WeightedCurve::swap_base_input_without_fees(
+            8_000,
+            2_000,
+            1_000,
+            swap_source_amount,
+            swap_destination_amount,
+        )
+        .unwrap();
+        assert!(destination_amount_swapped <= 1_000 && destination_amount_swapped >= 998);
+        // the same trade on the 20 side moves 4 times more of the price
+        let destination_amount_swapped = WeightedCurve::swap_base_input_without_fees(
+            2_000,
+            8_000,
+            1_000_000,
+            swap_destination_amount,
+            swap_source_amount,
+        )
+        .unwrap();
+        let exact = 4e9 * (1.0 - (1e9f64 / 1.001e9).powf(0.25));
+        assert!(destination_amount_swapped as f64 <= exact);
+        assert!(destination_amount_swapped as f64 >= exact - 2.0);
+        assert_eq!(
+            WeightedCurve::swap_base_output_without_fees(
+                8_000,
+                2_000,
+                swap_destination_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap_err(),
+            ErrorCode::SwapExceedsReserves.into()
+        );
+    }
+
+    #[test]
+    fn weighted_curve_type_orients_the_weights() {
+        let curve_type = CurveType::from_raw(CurveType::WEIGHTED, 8_000).unwrap();
+        assert_eq!(
+            curve_type,
+            CurveType::Weighted {
+                source_weight: 8_000,
+                destination_weight: 2_000
+            }
+        );
+        assert_eq!(
+            curve_type.oriented(crate::curve::TradeDirection::OneForZero),
+            CurveType::Weighted {
+                source_weight: 2_000,
+                destination_weight: 8_000
+            }
+        );
+        assert_eq!(
+            CurveType::from_raw(CurveType::WEIGHTED, MAX_WEIGHT + 1),
+            None
+        );
+        assert!(CurveCalculator::invariant_per_lp_token_preserved(
+            curve_type,
+            CurveCalculator::invariant(curve_type, 1_000, 4_000).unwrap(),
+            2_000,
+            CurveCalculator::invariant(curve_type, 1_500, 6_000).unwrap(),
+            3_000,
+        )
+        .unwrap());
+        assert!(!CurveCalculator::invariant_per_lp_token_preserved(
+            curve_type,
+            CurveCalculator::invariant(curve_type, 1_000, 4_000).unwrap(),
+            2_000,
+            CurveCalculator::invariant(curve_type, 1_500, 6_000).unwrap(),
+            3_001,
+        )
+        .unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn fifty_fifty_matches_constant_product(
+            source_amount in 1..u32::MAX as u128,
+            swap_source_amount in 1_000..u64::MAX as u128,
+            swap_destination_amount in 1_000..u64::MAX as u128,
+        ) {
+            let weighted = WeightedCurve::swap_base_input_without_fees(
+                5_000,
+                5_000,
+                source_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap();
+            let constant_product = crate::curve::ConstantProductCurve::swap_base_input_without_fees(
+                source_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap();
+            // the logarithms round the new destination reserve up by about 2^-58 of it
+            prop_assert!(weighted <= constant_product);
+            prop_assert!(weighted + 2 + swap_destination_amount / (1 << 56) >= constant_product);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn invariant_does_not_decrease_from_swap(
+            source_weight in MIN_WEIGHT..=MAX_WEIGHT,
+            source_amount in 1..u64::MAX as u128,
+            swap_source_amount in 1..u64::MAX as u128,
+            swap_destination_amount in 1..u64::MAX as u128,
+        ) {
+            let destination_weight = WEIGHT_DENOMINATOR - source_weight;
+            let invariant_before = WeightedCurve::compute_invariant(
+                source_weight,
+                destination_weight,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap();
+            let destination_amount_swapped = WeightedCurve::swap_base_input_without_fees(
+                source_weight,
+                destination_weight,
+                source_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap();
+            prop_assert!(destination_amount_swapped < swap_destination_amount);
+            let invariant_after = WeightedCurve::compute_invariant(
+                source_weight,
+                destination_weight,
+                swap_source_amount + source_amount,
+                swap_destination_amount - destination_amount_swapped,
+            )
+            .unwrap();
+            prop_assert!(invariant_after >= invariant_before);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn invariant_does_not_decrease_from_swap_base_output(
+            source_weight in MIN_WEIGHT..=MAX_WEIGHT,
+            swap_source_amount in 1..u64::MAX as u128,
+            swap_destination_amount in 2..u64::MAX as u128,
+            destination_amount in 1..u64::MAX as u128,
+        ) {
+            let destination_weight = WEIGHT_DENOMINATOR - source_weight;
+            prop_assume!(destination_amount < swap_destination_amount);
+            let invariant_before = WeightedCurve::compute_invariant(
+                source_weight,
+                destination_weight,
+                swap_source_amount,
+                swap_destination_amount,
+            )
+            .unwrap();
+            let source_amount = match WeightedCurve::swap_base_output_without_fees(
+                source_weight,
+                destination_weight,
+                destination_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            ) {
+                Ok(source_amount) => source_amount,
+                // the source reserve required overflows
+                Err(_) => return Ok(()),
+            };
+            prop_assert!(source_amount > 0);
+            let invariant_after = WeightedCurve::compute_invariant(
+                source_weight,
+                destination_weight,
+                swap_source_amount + source_amount,
+                swap_destination_amount - destination_amount,
+            )
+            .unwrap();
+            prop_assert!(invariant_after >= invariant_before);
+        }
+    }
+}
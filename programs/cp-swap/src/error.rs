@@ -28,10 +28,164 @@ pub enum ErrorCode {
     NotSupportMint,
     #[msg("invaild vault")]
     InvalidVault,
-    #[msg("Init lp amount is too less(Because 100 amount lp will be locked)")]
+    #[msg("Init lp amount is too less(Because 1000 amount lp will be locked)")]
     InitLpAmountTooLess,
     #[msg("TransferFee calculate not match")]
     TransferFeeCalculateNotMatch,
     #[msg("Invalid authority configuration")]
     InvalidAuthority,
+    #[msg("Invalid curve type or amplification coefficient")]
+    InvalidCurveType,
+    #[msg("A flash loan is in progress")]
+    FlashLoanInProgress,
+    #[msg("Flash loan must be invoked directly and repaid in the same transaction")]
+    InvalidFlashLoan,
+    #[msg("Not enough observations to cover the requested window")]
+    ObservationNotEnough,
+    #[msg("Pool creator is not allowed by the amm config")]
+    NotAllowedPoolCreator,
+    #[msg("The timelock of the config change has not expired")]
+    ConfigChangeTimelocked,
+    #[msg("The pool is already registered")]
+    PoolAlreadyRegistered,
+    #[msg("The pool registry of the mint pair is full")]
+    PoolRegistryFull,
+    #[msg("Trade fee rate is out of the bounds of the amm config")]
+    InvalidTradeFeeRate,
+    #[msg("Swap moves the pool price beyond the max price impact")]
+    ExceededPriceImpact,
+    #[msg("Invalid observation cardinality")]
+    InvalidObservationCardinality,
+    #[msg("Swap moves the price out of the virtual reserve range")]
+    ExceededVirtualReserveRange,
+    #[msg("Trade is not allowed in the launch phase")]
+    LaunchTradeNotAllowed,
+    #[msg("Launch phase has not ended")]
+    LaunchNotEnded,
+    #[msg("Invalid ed25519 signature of the order")]
+    InvalidSignature,
+    #[msg("Order expired")]
+    OrderExpired,
+    #[msg("Invalid order nonce")]
+    InvalidOrderNonce,
+    #[msg("Pool price has not reached the limit price of the order")]
+    LimitPriceNotReached,
+    #[msg("No fund fees left to buy back in the current epoch")]
+    BuybackAmountZero,
+    #[msg("The pool still has lp supply or tokens in its vaults")]
+    PoolNotEmpty,
+    #[msg("Swaps are paused by the circuit breaker of the pool")]
+    CircuitBreakerTripped,
+    #[msg("Transaction deadline exceeded")]
+    DeadlineExceeded,
+    #[msg("Trade moves the pool price out of its price band")]
+    PriceOutsideBand,
+    #[msg("The pool is already in use by the instruction calling back into it")]
+    ReentrancyDetected,
+    #[msg("Strategy borrow exceeds the share of the reserves it can borrow")]
+    StrategyBorrowLimitExceeded,
+    #[msg("Reserves borrowed by the strategy are not repaid")]
+    StrategyBorrowOutstanding,
+    #[msg("Protocol, fund and referral fee rates exceed the trade fee")]
+    InvalidFeeSplit,
+    #[msg("Invalid or stale oracle price")]
+    InvalidOracle,
+    #[msg("Swap price deviates too far from the oracle price")]
+    OracleDeviationExceeded,
+    #[msg("Pool state is already at the current version")]
+    InvalidPoolStateVersion,
+    #[msg("Swap reaches the auction size threshold and must be requested first")]
+    AuctionRequired,
+    #[msg("Only the filler can execute the swap request during the auction window")]
+    AuctionWindowActive,
+    #[msg("The auction window of the swap request is over")]
+    AuctionWindowClosed,
+    #[msg("Filler output is below the pool output")]
+    AuctionPriceNotImproved,
+    #[msg("Pool is in position mode and does not mint lp tokens")]
+    LpTokensDisabled,
+    #[msg("Math operation overflow")]
+    MathOverflow,
+    #[msg("Math operation underflow")]
+    MathUnderflow,
+    #[msg("Division by zero")]
+    DivisionByZero,
+    #[msg("Fee calculation overflow")]
+    FeeCalculationOverflow,
+    #[msg("Amount overflows when converted to or from its UI amount")]
+    AmountScaleOverflow,
+    #[msg("Swap takes all or more than the reserves of the pool")]
+    SwapExceedsReserves,
+    #[msg("Trade decreases the curve invariant")]
+    CurveInvariantViolated,
+    #[msg("StableSwap invariant calculation overflows")]
+    StableSwapCalculationFailed,
+    #[msg("Square root of the reserves product overflows")]
+    SqrtPriceOverflow,
+    #[msg("Lp token amount exceeds the lp supply")]
+    LpSupplyUnderflow,
+    #[msg("Transfer fee consumes the whole input amount")]
+    TransferFeeExceedsInput,
+    #[msg("Session is expired")]
+    SessionExpired,
+    #[msg("Lp token amount exceeds the remaining limit of the session")]
+    SessionLimitExceeded,
+    #[msg("Pool vaults or lp supply do not match the pool accounting")]
+    PoolAccountingViolated,
+    #[msg("Ramp target or duration out of bounds")]
+    InvalidRamp,
+    #[msg("Mint is not allowed by the mint policy of the amm config")]
+    MintNotAllowed,
+    #[msg("Mint policy lists the maximum number of mints")]
+    MintPolicyFull,
+    #[msg("Trade fee charged on the output token is not supported by this instruction")]
+    FeeOnOutputNotSupported,
+    #[msg("Pool rewards use the maximum number of reward mints")]
+    RewardsFull,
+    #[msg("Reward accounts are required by pools with rewards")]
+    MissingRewardAccounts,
+    #[msg("Pool price is already at the target price")]
+    TargetPriceReached,
+    #[msg("Trader is not registered in the trader registry of the pool")]
+    TraderNotRegistered,
+    #[msg("Fee exemption list holds the maximum number of traders")]
+    FeeExemptionsFull,
+    #[msg("Next tranche of the DCA order is not due yet")]
+    DcaTrancheNotDue,
+    #[msg("Pool price is below the minimum price of the DCA order")]
+    DcaMinPriceNotReached,
+    #[msg("Swap trades less or more token_0 than the trade size limits of the pool")]
+    TradeSizeOutOfRange,
+    #[msg("Liquidity of a pool with a withdrawal lockup is only withdrawn by withdraw")]
+    WithdrawalLockup,
+    #[msg("Weighted invariant calculation does not converge")]
+    WeightedCalculationFailed,
+    #[msg("Accrued protocol and fund fees of the pool must be collected first")]
+    PoolFeesNotSettled,
+    #[msg("Pool price is already beyond the price limit of the swap")]
+    SqrtPriceLimitReached,
+    #[msg("Receipt tree holds the maximum number of receipts")]
+    ReceiptTreeFull,
+    #[msg("Proof does not match a recent root of the receipt tree")]
+    InvalidReceiptProof,
+    #[msg("Transfer fee of the mint exceeds the maximum of the amm config")]
+    TransferFeeTooHigh,
+    #[msg("Pool is outside of its trading hours")]
+    OutsideTradingHours,
+    #[msg("Weights of the liquidity bootstrapping pool have not reached 50/50")]
+    LbpNotEnded,
+    #[msg("Pool mint has a freeze authority, its risk must be accepted")]
+    FreezeRiskNotAccepted,
+    #[msg("Vault is not frozen")]
+    VaultNotFrozen,
+    #[msg("Custom authority is transferred through propose_new_admin and accept_admin")]
+    TransferNotProposed,
+    #[msg("Dynamic fee min rate exceeds its max rate")]
+    InvalidDynamicFeeBounds,
+    #[msg("Liquidity of a pool with a withdrawal lockup is only deposited by deposit with a deposit receipt")]
+    DepositReceiptRequired,
+    #[msg("Buyback price is below the TWAP of the pool by more than the amm config allows")]
+    BuybackPriceDeviation,
+    #[msg("Vaults hold less than the output, the rest of the reserves is lent to the strategy")]
+    StrategyReservesLent,
 }
@@ -0,0 +1,91 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::AccountLoad;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(role: u8)]
+pub struct AcceptAdmin<'info> {
+    /// The proposed admin accepting the role
+    #[account(address = pending_admin.pending_admin @ ErrorCode::InvalidOwner)]
+    pub new_admin: Signer<'info>,
+
+    /// CHECK: The amm config or pool state the role belongs to, checked by the pending admin seeds
+    #[account(mut)]
+    pub target: UncheckedAccount<'info>,
+
+    /// The proposal to accept, closed once the role is transferred
+    #[account(
+        mut,
+        seeds = [
+            PENDING_ADMIN_SEED.as_bytes(),
+            target.key().as_ref(),
+            &[role],
+        ],
+        bump = pending_admin.bump,
+        close = proposer
+    )]
+    pub pending_admin: Account<'info, PendingAdmin>,
+
+    /// CHECK: Receives the rent of the proposal
+    #[account(mut, address = pending_admin.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>, role: u8) -> Result<()> {
+    let new_admin = ctx.accounts.new_admin.key();
+    // the proposal is stale once its proposer no longer holds the role
+    let proposer = ctx.accounts.proposer.key();
+    let target_info = ctx.accounts.target.to_account_info();
+    require_keys_eq!(*target_info.owner, crate::id(), ErrorCode::InvalidOwner);
+    match AdminRole::from_u8(role).ok_or(ErrorCode::InvalidInput)? {
+        AdminRole::ProtocolOwner => {
+            let mut amm_config =
+                AmmConfig::try_deserialize(&mut &target_info.try_borrow_data()?[..])?;
+            require!(
+                proposer == amm_config.protocol_owner || proposer == crate::admin::ID,
+                ErrorCode::InvalidOwner
+            );
+            #[cfg(feature = "enable-log")]
+            msg!(
+                "amm_config, old_protocol_owner:{}, new_owner:{}",
+                amm_config.protocol_owner.to_string(),
+                new_admin.to_string()
+            );
+            amm_config.protocol_owner = new_admin;
+            amm_config.try_serialize(&mut &mut target_info.try_borrow_mut_data()?[..])?;
+        }
+        AdminRole::FundOwner => {
+            let mut amm_config =
+                AmmConfig::try_deserialize(&mut &target_info.try_borrow_data()?[..])?;
+            require!(
+                proposer == amm_config.fund_owner || proposer == crate::admin::ID,
+                ErrorCode::InvalidOwner
+            );
+            #[cfg(feature = "enable-log")]
+            msg!(
+                "amm_config, old_fund_owner:{}, new_fund_owner:{}",
+                amm_config.fund_owner.to_string(),
+                new_admin.to_string()
+            );
+            amm_config.fund_owner = new_admin;
+            amm_config.try_serialize(&mut &mut target_info.try_borrow_mut_data()?[..])?;
+        }
+        AdminRole::PoolCustomAuthority => {
+            let pool_state_loader = AccountLoad::<PoolState>::try_from(&target_info)?;
+            let mut pool_state = pool_state_loader.load_mut()?;
+            require!(
+                pool_state.is_custom_authority() && proposer == pool_state.custom_authority,
+                ErrorCode::InvalidAuthority
+            );
+            #[cfg(feature = "enable-log")]
+            msg!(
+                "pool_state, old_custom_authority:{}, new_custom_authority:{}",
+                { pool_state.custom_authority }.to_string(),
+                new_admin.to_string()
+            );
+            pool_state.custom_authority = new_admin;
+        }
+    }
+    Ok(())
+}
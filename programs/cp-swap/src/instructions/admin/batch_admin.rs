@@ -0,0 +1,73 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// The most operations of a batch
+pub const MAX_BATCH_ADMIN_OPS: usize = 32;
+
+#[derive(Accounts)]
+pub struct BatchAdmin<'info> {
+    /// Only the admin applies a batch
+    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub authority: Signer<'info>,
+}
+
+/// Apply `ops` in order to the pools passed as remaining accounts, followed by the amm
+/// configs of the pools whose trade fee rate is set. Any failing operation fails the
+/// whole batch, so no pool is left half updated
+pub fn batch_admin<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, BatchAdmin<'info>>,
+    ops: Vec<BatchAdminOp>,
+) -> Result<()> {
+    require!(
+        !ops.is_empty() && ops.len() <= MAX_BATCH_ADMIN_OPS,
+        ErrorCode::InvalidInput
+    );
+    let epoch = Clock::get()?.epoch;
+    for (index, batch_op) in ops.iter().enumerate() {
+        let pool_account = ctx
+            .remaining_accounts
+            .get(usize::from(batch_op.pool_index))
+            .ok_or(ErrorCode::InvalidInput)?;
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(pool_account)?;
+        let mut pool_state = pool_state_loader.load_mut()?;
+        let amm_config = match batch_op.op {
+            PoolAdminOp::SetTradeFeeRate {
+                trade_fee_rate: Some(_),
+            } => {
+                let amm_config_info = ctx
+                    .remaining_accounts
+                    .iter()
+                    .find(|account| account.key == &pool_state.amm_config)
+                    .ok_or(ErrorCode::InvalidInput)?;
+                Some(Account::<AmmConfig>::try_from(amm_config_info)?)
+            }
+            _ => None,
+        };
+        let old_authority = pool_state.custom_authority;
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "pool_state:{}, batch admin op {}: {:?}",
+            pool_account.key,
+            index,
+            batch_op.op
+        );
+        batch_op.op.apply(&mut pool_state, amm_config.as_deref())?;
+        pool_state.recent_epoch = epoch;
+
+        if let PoolAdminOp::SetCustomAuthority { new_authority } = batch_op.op {
+            emit!(CustomAuthorityChangedEvent {
+                pool_id: pool_account.key(),
+                old_authority,
+                new_authority,
+                by_admin: true,
+            });
+        }
+        emit!(PoolAdminOpEvent {
+            pool_id: pool_account.key(),
+            index: index as u16,
+            op: batch_op.op,
+        });
+    }
+    Ok(())
+}
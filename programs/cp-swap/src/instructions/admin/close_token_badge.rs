@@ -0,0 +1,26 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseTokenBadge<'info> {
+    /// Only admin can revoke a token badge, receives its rent
+    #[account(mut, address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The badge to revoke, the pools already created with the mint are not affected
+    #[account(
+        mut,
+        seeds = [
+            TOKEN_BADGE_SEED.as_bytes(),
+            token_badge.mint.as_ref(),
+        ],
+        bump = token_badge.bump,
+        close = owner
+    )]
+    pub token_badge: Box<Account<'info, TokenBadge>>,
+}
+
+pub fn close_token_badge(_ctx: Context<CloseTokenBadge>) -> Result<()> {
+    Ok(())
+}
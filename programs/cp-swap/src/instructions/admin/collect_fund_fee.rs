@@ -1,6 +1,7 @@
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::token::*;
+use crate::utils::PoolVaults;
 use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::Mint;
@@ -8,8 +9,9 @@ use anchor_spl::token_interface::Token2022;
 use anchor_spl::token_interface::TokenAccount;
 #[derive(Accounts)]
 pub struct CollectFundFee<'info> {
-    /// Only admin, fund owner, or custom authority can collect fee
-    #[account(constraint = (owner.key() == amm_config.fund_owner || owner.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && owner.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidOwner)]
+    /// Only admin, fund owner, or custom authority can collect fee,
+    /// anyone can when the fees are collected to the fund fee destination
+    #[account(constraint = (amm_config.is_fund_fee_destination_set() || owner.key() == amm_config.fund_owner || owner.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && owner.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -55,12 +57,20 @@ pub struct CollectFundFee<'info> {
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// The address that receives the collected token_0 fund fees
-    #[account(mut)]
+    /// The address that receives the collected token_0 fund fees,
+    /// must be owned by the fund fee destination when it is set
+    #[account(
+        mut,
+        constraint = !amm_config.is_fund_fee_destination_set() || recipient_token_0_account.owner == amm_config.fund_fee_destination @ ErrorCode::InvalidOwner
+    )]
     pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The address that receives the collected token_1 fund fees
-    #[account(mut)]
+    /// The address that receives the collected token_1 fund fees,
+    /// must be owned by the fund fee destination when it is set
+    #[account(
+        mut,
+        constraint = !amm_config.is_fund_fee_destination_set() || recipient_token_1_account.owner == amm_config.fund_fee_destination @ ErrorCode::InvalidOwner
+    )]
     pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// The SPL program to perform token transfers
@@ -68,10 +78,94 @@ pub struct CollectFundFee<'info> {
 
     /// The SPL program 2022 to perform token transfers
     pub token_program_2022: Program<'info, Token2022>,
+
+    /// The insurance fund of the amm config, required when the amm config sends a share
+    /// of the fund fees to it
+    #[account(
+        seeds = [
+            INSURANCE_FUND_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump = insurance_fund.bump,
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    /// The token_0 account of the insurance fund receiving its share of the fund fees
+    #[account(mut, token::mint = vault_0_mint)]
+    pub insurance_fund_token_0: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The token_1 account of the insurance fund receiving its share of the fund fees
+    #[account(mut, token::mint = vault_1_mint)]
+    pub insurance_fund_token_1: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 }
 
-pub fn collect_fund_fee(
-    ctx: Context<CollectFundFee>,
+impl<'info> CollectFundFee<'info> {
+    fn token_program(&self, mint: &InterfaceAccount<'info, Mint>) -> AccountInfo<'info> {
+        if mint.to_account_info().owner == self.token_program.key {
+            self.token_program.to_account_info()
+        } else {
+            self.token_program_2022.to_account_info()
+        }
+    }
+
+    /// Send the share of the collected fund fees to the token accounts of the insurance fund
+    fn send_insurance_fund_share(
+        &self,
+        amount_0: u64,
+        amount_1: u64,
+        auth_bump: u8,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let insurance_fund = self
+            .insurance_fund
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?;
+        for (vault, mint, insurance_fund_token, amount) in [
+            (
+                &self.token_0_vault,
+                &self.vault_0_mint,
+                &self.insurance_fund_token_0,
+                amount_0,
+            ),
+            (
+                &self.token_1_vault,
+                &self.vault_1_mint,
+                &self.insurance_fund_token_1,
+                amount_1,
+            ),
+        ] {
+            let insurance_fund_token = insurance_fund_token
+                .as_ref()
+                .ok_or(ErrorCode::InvalidInput)?;
+            require_keys_eq!(
+                insurance_fund_token.owner,
+                insurance_fund.key(),
+                ErrorCode::InvalidOwner
+            );
+            transfer_from_pool_vault_to_user(
+                self.authority.to_account_info(),
+                vault.to_account_info(),
+                insurance_fund_token.to_account_info(),
+                mint.to_account_info(),
+                self.token_program(mint),
+                amount,
+                mint.decimals,
+                &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+                remaining_accounts,
+            )?;
+        }
+        emit!(InsuranceFundDepositEvent {
+            amm_config: self.amm_config.key(),
+            pool_id: self.pool_state.key(),
+            token_0_amount: amount_0,
+            token_1_amount: amount_1,
+        });
+        Ok(())
+    }
+}
+
+pub fn collect_fund_fee<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CollectFundFee<'info>>,
     amount_0_requested: u64,
     amount_1_requested: u64,
 ) -> Result<()> {
@@ -80,6 +174,13 @@ pub fn collect_fund_fee(
     let auth_bump: u8;
     {
         let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        // the fund fees of buyback pools are spent by `execute_buyback`
+        require!(
+            !ctx.accounts
+                .amm_config
+                .is_buyback_pool(&pool_state.token_0_mint, &pool_state.token_1_mint),
+            ErrorCode::NotApproved
+        );
         amount_0 = amount_0_requested.min(pool_state.fund_fees_token_0);
         amount_1 = amount_1_requested.min(pool_state.fund_fees_token_1);
 
@@ -87,20 +188,38 @@ pub fn collect_fund_fee(
         pool_state.fund_fees_token_1 = pool_state.fund_fees_token_1.checked_sub(amount_1).unwrap();
         auth_bump = pool_state.auth_bump;
         pool_state.recent_epoch = Clock::get()?.epoch;
+
+        emit!(FeeCollectedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            fee_type: 1,
+            token_0_amount: amount_0,
+            token_1_amount: amount_1,
+            token_0_fees_remaining: pool_state.fund_fees_token_0,
+            token_1_fees_remaining: pool_state.fund_fees_token_1,
+        });
+    }
+    // the insurance fund of the config takes its share of the fund fees first
+    let insurance_amount_0 = ctx.accounts.amm_config.insurance_fund_share(amount_0);
+    let insurance_amount_1 = ctx.accounts.amm_config.insurance_fund_share(amount_1);
+    if ctx.accounts.amm_config.is_insurance_fund_enabled() {
+        ctx.accounts.send_insurance_fund_share(
+            insurance_amount_0,
+            insurance_amount_1,
+            auth_bump,
+            ctx.remaining_accounts,
+        )?;
     }
+
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.token_0_vault.to_account_info(),
         ctx.accounts.recipient_token_0_account.to_account_info(),
         ctx.accounts.vault_0_mint.to_account_info(),
-        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
-            ctx.accounts.token_program.to_account_info()
-        } else {
-            ctx.accounts.token_program_2022.to_account_info()
-        },
-        amount_0,
+        ctx.accounts.token_program(&ctx.accounts.vault_0_mint),
+        amount_0.checked_sub(insurance_amount_0).unwrap(),
         ctx.accounts.vault_0_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_pool_vault_to_user(
@@ -108,15 +227,19 @@ pub fn collect_fund_fee(
         ctx.accounts.token_1_vault.to_account_info(),
         ctx.accounts.recipient_token_1_account.to_account_info(),
         ctx.accounts.vault_1_mint.to_account_info(),
-        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
-            ctx.accounts.token_program.to_account_info()
-        } else {
-            ctx.accounts.token_program_2022.to_account_info()
-        },
-        amount_1,
+        ctx.accounts.token_program(&ctx.accounts.vault_1_mint),
+        amount_1.checked_sub(insurance_amount_1).unwrap(),
         ctx.accounts.vault_1_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    )
+    .check(&*ctx.accounts.pool_state.load()?, None, None)?;
     Ok(())
 }
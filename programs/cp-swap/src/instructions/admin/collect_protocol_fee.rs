@@ -2,6 +2,11 @@ use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::*;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::Mint;
 use anchor_spl::token_interface::Token2022;
@@ -9,8 +14,9 @@ use anchor_spl::token_interface::TokenAccount;
 
 #[derive(Accounts)]
 pub struct CollectProtocolFee<'info> {
-    /// Only admin, protocol owner, or custom authority can collect fee
-    #[account(constraint = (owner.key() == amm_config.protocol_owner || owner.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && owner.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidOwner)]
+    /// Only admin, protocol owner, or custom authority can collect fee,
+    /// anyone can when the fees are collected to the protocol fee destination
+    #[account(constraint = (amm_config.is_protocol_fee_destination_set() || owner.key() == amm_config.protocol_owner || owner.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && owner.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -56,12 +62,20 @@ pub struct CollectProtocolFee<'info> {
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// The address that receives the collected token_0 protocol fees
-    #[account(mut)]
+    /// The address that receives the collected token_0 protocol fees,
+    /// must be owned by the protocol fee destination when it is set
+    #[account(
+        mut,
+        constraint = !amm_config.is_protocol_fee_destination_set() || recipient_token_0_account.owner == amm_config.protocol_fee_destination @ ErrorCode::InvalidOwner
+    )]
     pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The address that receives the collected token_1 protocol fees
-    #[account(mut)]
+    /// The address that receives the collected token_1 protocol fees,
+    /// must be owned by the protocol fee destination when it is set
+    #[account(
+        mut,
+        constraint = !amm_config.is_protocol_fee_destination_set() || recipient_token_1_account.owner == amm_config.protocol_fee_destination @ ErrorCode::InvalidOwner
+    )]
     pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// The SPL program to perform token transfers
@@ -69,13 +83,77 @@ pub struct CollectProtocolFee<'info> {
 
     /// The SPL program 2022 to perform token transfers
     pub token_program_2022: Program<'info, Token2022>,
+
+    /// CHECK: the revenue distribution program of the amm config, required when it is set.
+    /// The collected fees are then deposited to it through its `deposit_revenue` instruction
+    #[account(address = amm_config.revenue_program @ ErrorCode::InvalidInput)]
+    pub revenue_program: Option<UncheckedAccount<'info>>,
 }
 
-pub fn collect_protocol_fee(
-    ctx: Context<CollectProtocolFee>,
+/// The instruction of the revenue distribution program the collected protocol fees are
+/// deposited with, its data is the anchor discriminator and the token_0 and token_1 amounts
+pub const REVENUE_DEPOSIT_INSTRUCTION: &str = "global:deposit_revenue";
+
+impl<'info> CollectProtocolFee<'info> {
+    /// Notify the revenue distribution program of the fees transferred to the recipient
+    /// accounts, signed by the amm config so it can only be called by this program. The
+    /// accounts of `deposit_revenue` are the amm config, the pool state, the recipient token
+    /// accounts, the vault mints and the remaining accounts
+    fn deposit_revenue(
+        &self,
+        revenue_program: &AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<()> {
+        let mut data = hash(REVENUE_DEPOSIT_INSTRUCTION.as_bytes()).to_bytes()[..8].to_vec();
+        data.extend_from_slice(&amount_0.to_le_bytes());
+        data.extend_from_slice(&amount_1.to_le_bytes());
+        let mut account_infos = vec![
+            self.amm_config.to_account_info(),
+            self.pool_state.to_account_info(),
+            self.recipient_token_0_account.to_account_info(),
+            self.recipient_token_1_account.to_account_info(),
+            self.vault_0_mint.to_account_info(),
+            self.vault_1_mint.to_account_info(),
+        ];
+        account_infos.extend_from_slice(remaining_accounts);
+        let mut accounts = vec![AccountMeta::new_readonly(self.amm_config.key(), true)];
+        accounts.extend(account_infos[1..].iter().map(|account| {
+            if account.is_writable {
+                AccountMeta::new(account.key(), account.is_signer)
+            } else {
+                AccountMeta::new_readonly(account.key(), account.is_signer)
+            }
+        }));
+        account_infos.push(revenue_program.clone());
+        invoke_signed(
+            &Instruction {
+                program_id: revenue_program.key(),
+                accounts,
+                data,
+            },
+            &account_infos,
+            &[&[
+                AMM_CONFIG_SEED.as_bytes(),
+                &self.amm_config.index.to_be_bytes(),
+                &[self.amm_config.bump],
+            ]],
+        )?;
+        Ok(())
+    }
+}
+
+pub fn collect_protocol_fee<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CollectProtocolFee<'info>>,
     amount_0_requested: u64,
     amount_1_requested: u64,
 ) -> Result<()> {
+    // the fees of a config with a revenue program are only collected to it
+    require!(
+        ctx.accounts.amm_config.is_revenue_program_set() == ctx.accounts.revenue_program.is_some(),
+        ErrorCode::InvalidInput
+    );
     let amount_0: u64;
     let amount_1: u64;
     let auth_bump: u8;
@@ -96,6 +174,15 @@ pub fn collect_protocol_fee(
 
         auth_bump = pool_state.auth_bump;
         pool_state.recent_epoch = Clock::get()?.epoch;
+
+        emit!(FeeCollectedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            fee_type: 0,
+            token_0_amount: amount_0,
+            token_1_amount: amount_1,
+            token_0_fees_remaining: pool_state.protocol_fees_token_0,
+            token_1_fees_remaining: pool_state.protocol_fees_token_1,
+        });
     }
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
@@ -110,6 +197,7 @@ pub fn collect_protocol_fee(
         amount_0,
         ctx.accounts.vault_0_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_pool_vault_to_user(
@@ -125,7 +213,24 @@ pub fn collect_protocol_fee(
         amount_1,
         ctx.accounts.vault_1_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    )
+    .check(&*ctx.accounts.pool_state.load()?, None, None)?;
+
+    if let Some(revenue_program) = &ctx.accounts.revenue_program {
+        ctx.accounts.deposit_revenue(
+            &revenue_program.to_account_info(),
+            ctx.remaining_accounts,
+            amount_0,
+            amount_1,
+        )?;
+    }
     Ok(())
 }
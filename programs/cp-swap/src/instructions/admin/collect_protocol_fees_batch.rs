@@ -0,0 +1,148 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// Number of remaining accounts of each pool of the batch
+pub const PROTOCOL_FEES_BATCH_POOL_ACCOUNTS: usize = 7;
+
+#[derive(Accounts)]
+pub struct CollectProtocolFeesBatch<'info> {
+    /// Only admin or protocol owner can collect fee,
+    /// anyone can when the fees are collected to the protocol fee destination
+    #[account(constraint = (amm_config.is_protocol_fee_destination_set() || owner.key() == amm_config.protocol_owner || owner.key() == crate::admin::ID) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Amm config account of all the pools of the batch
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn collect_protocol_fees_batch<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CollectProtocolFeesBatch<'info>>,
+) -> Result<()> {
+    let batch = ctx
+        .remaining_accounts
+        .chunks_exact(PROTOCOL_FEES_BATCH_POOL_ACCOUNTS);
+    require!(
+        !ctx.remaining_accounts.is_empty() && batch.remainder().is_empty(),
+        ErrorCode::InvalidInput
+    );
+    // the protocol fees of a config with a revenue program are only deposited to it
+    require!(
+        !ctx.accounts.amm_config.is_revenue_program_set(),
+        ErrorCode::NotApproved
+    );
+    for pool_accounts in batch {
+        collect_pool_protocol_fees(&ctx, pool_accounts)?;
+    }
+    Ok(())
+}
+
+/// Collect all the protocol fees of a pool of the batch, its accounts are the pool state, the
+/// token_0 and token_1 vaults, their mints and the token_0 and token_1 recipient token accounts
+fn collect_pool_protocol_fees<'info>(
+    ctx: &Context<'_, '_, '_, 'info, CollectProtocolFeesBatch<'info>>,
+    pool_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let pool_state_loader = AccountLoader::<PoolState>::try_from(&pool_accounts[0])?;
+    let (token_0_vault, token_1_vault) = (&pool_accounts[1], &pool_accounts[2]);
+    let vault_0_mint = InterfaceAccount::<Mint>::try_from(&pool_accounts[3])?;
+    let vault_1_mint = InterfaceAccount::<Mint>::try_from(&pool_accounts[4])?;
+    let recipient_token_0_account = InterfaceAccount::<TokenAccount>::try_from(&pool_accounts[5])?;
+    let recipient_token_1_account = InterfaceAccount::<TokenAccount>::try_from(&pool_accounts[6])?;
+    let amm_config = &ctx.accounts.amm_config;
+    if amm_config.is_protocol_fee_destination_set() {
+        require_keys_eq!(
+            recipient_token_0_account.owner,
+            amm_config.protocol_fee_destination,
+            ErrorCode::InvalidOwner
+        );
+        require_keys_eq!(
+            recipient_token_1_account.owner,
+            amm_config.protocol_fee_destination,
+            ErrorCode::InvalidOwner
+        );
+    }
+
+    let (amount_0, amount_1) = {
+        let mut pool_state = pool_state_loader.load_mut()?;
+        require_keys_eq!(pool_state.amm_config, amm_config.key());
+        require_keys_eq!(token_0_vault.key(), pool_state.token_0_vault);
+        require_keys_eq!(token_1_vault.key(), pool_state.token_1_vault);
+        require_keys_eq!(vault_0_mint.key(), pool_state.token_0_mint);
+        require_keys_eq!(vault_1_mint.key(), pool_state.token_1_mint);
+
+        let amounts = (
+            pool_state.protocol_fees_token_0,
+            pool_state.protocol_fees_token_1,
+        );
+        pool_state.protocol_fees_token_0 = 0;
+        pool_state.protocol_fees_token_1 = 0;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+        amounts
+    };
+    emit!(FeeCollectedEvent {
+        pool_id: pool_state_loader.key(),
+        fee_type: 0,
+        token_0_amount: amount_0,
+        token_1_amount: amount_1,
+        token_0_fees_remaining: 0,
+        token_1_fees_remaining: 0,
+    });
+
+    for (vault, mint, recipient, amount) in [
+        (
+            token_0_vault,
+            &vault_0_mint,
+            &recipient_token_0_account,
+            amount_0,
+        ),
+        (
+            token_1_vault,
+            &vault_1_mint,
+            &recipient_token_1_account,
+            amount_1,
+        ),
+    ] {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            vault.to_account_info(),
+            recipient.to_account_info(),
+            mint.to_account_info(),
+            if mint.to_account_info().owner == ctx.accounts.token_program.key {
+                ctx.accounts.token_program.to_account_info()
+            } else {
+                ctx.accounts.token_program_2022.to_account_info()
+            },
+            amount,
+            mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+            &[],
+        )?;
+    }
+    PoolVaults::new(
+        token_0_vault.clone(),
+        token_1_vault.clone(),
+        vault_0_mint.to_account_info(),
+        vault_1_mint.to_account_info(),
+    )
+    .check(&*pool_state_loader.load()?, None, None)?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CoverShortfall<'info> {
+    /// Only admin can cover a shortfall
+    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The insurance fund paying the shortfall
+    #[account(
+        mut,
+        seeds = [
+            INSURANCE_FUND_SEED.as_bytes(),
+            insurance_fund.amm_config.as_ref(),
+        ],
+        bump = insurance_fund.bump,
+    )]
+    pub insurance_fund: Box<Account<'info, InsuranceFund>>,
+
+    /// The pool with the shortfall, of the amm config of the insurance fund
+    #[account(
+        constraint = pool_state.load()?.amm_config == insurance_fund.amm_config @ ErrorCode::InvalidInput
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The token account of the insurance fund sending the tokens
+    #[account(
+        mut,
+        token::mint = vault_mint,
+        token::authority = insurance_fund,
+    )]
+    pub insurance_fund_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_0 or token_1 vault of the pool receiving the tokens
+    #[account(
+        mut,
+        constraint = vault.key() == pool_state.load()?.token_0_vault
+            || vault.key() == pool_state.load()?.token_1_vault @ ErrorCode::InvalidVault
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the vault
+    #[account(address = vault.mint)]
+    pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token program of the vault mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn cover_shortfall<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CoverShortfall<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require_gt!(amount, 0);
+    let vault_amount_before = ctx.accounts.vault.amount;
+    let insurance_fund = &ctx.accounts.insurance_fund;
+    transfer_from_pool_vault_to_user(
+        insurance_fund.to_account_info(),
+        ctx.accounts.insurance_fund_token.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.vault_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.vault_mint.decimals,
+        &[&[
+            INSURANCE_FUND_SEED.as_bytes(),
+            insurance_fund.amm_config.as_ref(),
+            &[insurance_fund.bump],
+        ]],
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.vault.reload()?;
+
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.shortfall_count = insurance_fund.shortfall_count.checked_add(1).unwrap();
+    emit!(ShortfallCoveredEvent {
+        amm_config: insurance_fund.amm_config,
+        pool_id: ctx.accounts.pool_state.key(),
+        vault: ctx.accounts.vault.key(),
+        mint: ctx.accounts.vault_mint.key(),
+        amount,
+        vault_amount_before,
+        vault_amount_after: ctx.accounts.vault.amount,
+    });
+    Ok(())
+}
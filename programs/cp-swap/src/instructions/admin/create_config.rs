@@ -1,6 +1,7 @@
 use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
 use std::ops::DerefMut;
 
 #[derive(Accounts)]
@@ -47,5 +48,7 @@ pub fn create_amm_config(
     amm_config.fund_fee_rate = fund_fee_rate;
     amm_config.create_pool_fee = create_pool_fee;
     amm_config.fund_owner = ctx.accounts.owner.key();
+    amm_config.create_pool_fee_mint = spl_token::native_mint::id();
+    amm_config.create_pool_fee_receiver = crate::create_pool_fee_reveiver::ID;
     Ok(())
 }
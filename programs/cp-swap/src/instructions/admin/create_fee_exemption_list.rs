@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateFeeExemptionList<'info> {
+    /// Only admin can create the fee exemption list of an amm config
+    #[account(mut, address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The amm config whose swaps the exemptions apply to
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The fee exemption list of the amm config
+    #[account(
+        init,
+        seeds = [
+            FEE_EXEMPTION_LIST_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = FeeExemptionList::LEN
+    )]
+    pub fee_exemption_list: Box<Account<'info, FeeExemptionList>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_fee_exemption_list(ctx: Context<CreateFeeExemptionList>) -> Result<()> {
+    let fee_exemption_list = &mut ctx.accounts.fee_exemption_list;
+    fee_exemption_list.bump = ctx.bumps.fee_exemption_list;
+    fee_exemption_list.amm_config = ctx.accounts.amm_config.key();
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config:{}, fee_exemption_list:{}",
+        fee_exemption_list.amm_config.to_string(),
+        fee_exemption_list.key().to_string()
+    );
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateInsuranceFund<'info> {
+    /// Only admin can create an insurance fund
+    #[account(mut, address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The amm config whose pools the fund covers
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The insurance fund of the amm config
+    #[account(
+        init,
+        seeds = [
+            INSURANCE_FUND_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = InsuranceFund::LEN
+    )]
+    pub insurance_fund: Box<Account<'info, InsuranceFund>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_insurance_fund(ctx: Context<CreateInsuranceFund>) -> Result<()> {
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.bump = ctx.bumps.insurance_fund;
+    insurance_fund.amm_config = ctx.accounts.amm_config.key();
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "insurance_fund, amm_config:{}",
+        insurance_fund.amm_config.to_string()
+    );
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateMintPolicy<'info> {
+    /// Only admin can create the mint policy of an amm config
+    #[account(mut, address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The amm config whose new pools are checked against the policy
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The mint policy of the amm config
+    #[account(
+        init,
+        seeds = [
+            MINT_POLICY_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = MintPolicy::LEN
+    )]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_mint_policy(ctx: Context<CreateMintPolicy>, mode: u8) -> Result<()> {
+    MintPolicyMode::from_u8(mode).ok_or(ErrorCode::InvalidInput)?;
+    let mint_policy = &mut ctx.accounts.mint_policy;
+    mint_policy.bump = ctx.bumps.mint_policy;
+    mint_policy.amm_config = ctx.accounts.amm_config.key();
+    mint_policy.mode = mode;
+    // an empty denylist allows every mint until mints are banned
+    ctx.accounts.amm_config.mint_policy = mint_policy.key();
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, mint_policy:{}, mode:{}",
+        mint_policy.key().to_string(),
+        mode
+    );
+    Ok(())
+}
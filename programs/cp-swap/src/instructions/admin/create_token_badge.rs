@@ -0,0 +1,48 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::get_transfer_hook_program_id;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct CreateTokenBadge<'info> {
+    /// Only admin can issue a token badge
+    #[account(mut, address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The vetted mint, only token program 2022 mints have risky extensions
+    #[account(mint::token_program = Token2022::id())]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The badge of the mint
+    #[account(
+        init,
+        seeds = [
+            TOKEN_BADGE_SEED.as_bytes(),
+            token_mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = TokenBadge::LEN
+    )]
+    pub token_badge: Box<Account<'info, TokenBadge>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_token_badge(ctx: Context<CreateTokenBadge>) -> Result<()> {
+    let token_badge = &mut ctx.accounts.token_badge;
+    token_badge.bump = ctx.bumps.token_badge;
+    token_badge.mint = ctx.accounts.token_mint.key();
+    token_badge.transfer_hook_program =
+        get_transfer_hook_program_id(&ctx.accounts.token_mint.to_account_info())?
+            .unwrap_or_default();
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "token_badge, mint:{}, transfer_hook_program:{}",
+        token_badge.mint.to_string(),
+        token_badge.transfer_hook_program.to_string()
+    );
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::error::ErrorCode;
+use crate::instructions::{emit_config_updated, update_fee_rate};
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The queued change, closed once it is applied
+    #[account(
+        mut,
+        seeds = [
+            PENDING_CONFIG_CHANGE_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            &[pending_config_change.param],
+        ],
+        bump = pending_config_change.bump,
+        has_one = amm_config,
+        close = payer
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+
+    /// CHECK: Receives the rent of the queued change
+    #[account(mut, address = pending_config_change.payer)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let pending_config_change = &ctx.accounts.pending_config_change;
+    require_gte!(
+        Clock::get()?.unix_timestamp as u64,
+        pending_config_change.effective_timestamp,
+        ErrorCode::ConfigChangeTimelocked
+    );
+    update_fee_rate(
+        &mut ctx.accounts.amm_config,
+        pending_config_change.param,
+        pending_config_change.value,
+    )?;
+    emit_config_updated(
+        &ctx.accounts.amm_config,
+        pending_config_change.param,
+        pending_config_change.value,
+    );
+    Ok(())
+}
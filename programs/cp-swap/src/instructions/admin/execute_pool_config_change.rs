@@ -0,0 +1,57 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecutePoolConfigChange<'info> {
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config the pool is reassigned to
+    #[account(address = pending_pool_config_change.new_amm_config @ ErrorCode::InvalidInput)]
+    pub new_amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The queued reassignment, closed once it is applied
+    #[account(
+        mut,
+        seeds = [
+            PENDING_POOL_CONFIG_CHANGE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = pending_pool_config_change.bump,
+        close = payer
+    )]
+    pub pending_pool_config_change: Account<'info, PendingPoolConfigChange>,
+
+    /// CHECK: Receives the rent of the queued reassignment
+    #[account(mut, address = pending_pool_config_change.payer)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn execute_pool_config_change(ctx: Context<ExecutePoolConfigChange>) -> Result<()> {
+    require_gte!(
+        Clock::get()?.unix_timestamp as u64,
+        ctx.accounts.pending_pool_config_change.effective_timestamp,
+        ErrorCode::ConfigChangeTimelocked
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // the accrued protocol and fund fees belong to the owners of the old config
+    require!(
+        pool_state.protocol_fees_token_0 == 0
+            && pool_state.protocol_fees_token_1 == 0
+            && pool_state.fund_fees_token_0 == 0
+            && pool_state.fund_fees_token_1 == 0,
+        ErrorCode::PoolFeesNotSettled
+    );
+    // the orders opened since the reassignment was queued must still execute
+    pool_state.check_amm_config_reassignment(&ctx.accounts.new_amm_config)?;
+    let old_amm_config = pool_state.amm_config;
+    pool_state.amm_config = ctx.accounts.new_amm_config.key();
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    emit!(PoolConfigChangedEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        old_amm_config,
+        new_amm_config: pool_state.amm_config,
+    });
+    Ok(())
+}
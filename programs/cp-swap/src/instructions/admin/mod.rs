@@ -12,3 +12,114 @@ pub use collect_protocol_fee::*;
 
 pub mod collect_fund_fee;
 pub use collect_fund_fee::*;
+
+pub mod collect_protocol_fees_batch;
+pub use collect_protocol_fees_batch::*;
+
+pub mod propose_new_admin;
+pub use propose_new_admin::*;
+
+pub mod accept_admin;
+pub use accept_admin::*;
+
+pub mod execute_config_change;
+pub use execute_config_change::*;
+
+pub mod update_fee_discount;
+pub use update_fee_discount::*;
+
+pub mod update_pool_fee_rate;
+pub use update_pool_fee_rate::*;
+
+pub mod set_pool_fee_split;
+pub use set_pool_fee_split::*;
+
+pub mod update_pool_max_price_impact;
+pub use update_pool_max_price_impact::*;
+
+pub mod update_pool_trade_size_limits;
+pub use update_pool_trade_size_limits::*;
+
+pub mod update_pool_trading_schedule;
+pub use update_pool_trading_schedule::*;
+
+pub mod update_pool_lockup;
+pub use update_pool_lockup::*;
+
+pub mod update_multi_pool_status;
+pub use update_multi_pool_status::*;
+
+pub mod reassign_pool_config;
+pub use reassign_pool_config::*;
+
+pub mod execute_pool_config_change;
+pub use execute_pool_config_change::*;
+
+pub mod update_pool_virtual_reserves;
+pub use update_pool_virtual_reserves::*;
+
+pub mod update_pool_circuit_breaker;
+pub use update_pool_circuit_breaker::*;
+
+pub mod update_pool_price_band;
+pub use update_pool_price_band::*;
+
+pub mod update_pool_strategy;
+pub use update_pool_strategy::*;
+
+pub mod update_pool_oracle;
+pub use update_pool_oracle::*;
+
+pub mod update_pool_fee_rebate;
+pub use update_pool_fee_rebate::*;
+
+pub mod rebalance_between_pools;
+pub use rebalance_between_pools::*;
+
+pub mod update_pool_auction;
+pub use update_pool_auction::*;
+
+pub mod update_pool_creator_fee;
+pub use update_pool_creator_fee::*;
+
+pub mod update_pool_position_mode;
+pub use update_pool_position_mode::*;
+
+pub mod set_pool_custom_authority;
+pub use set_pool_custom_authority::*;
+
+pub mod ramp_pool_parameter;
+pub use ramp_pool_parameter::*;
+
+pub mod create_mint_policy;
+pub use create_mint_policy::*;
+
+pub mod create_token_badge;
+pub use create_token_badge::*;
+
+pub mod close_token_badge;
+pub use close_token_badge::*;
+
+pub mod create_insurance_fund;
+pub use create_insurance_fund::*;
+
+pub mod cover_shortfall;
+pub use cover_shortfall::*;
+
+pub mod update_mint_policy;
+pub use update_mint_policy::*;
+
+pub mod update_pool_trader_registry;
+pub use update_pool_trader_registry::*;
+
+pub mod create_fee_exemption_list;
+pub use create_fee_exemption_list::*;
+
+pub mod update_fee_exemptions;
+pub use update_fee_exemptions::*;
+
+pub mod pause_pools;
+pub use pause_pools::*;
+
+pub mod batch_admin;
+pub use batch_admin::*;
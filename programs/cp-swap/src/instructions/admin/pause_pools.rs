@@ -0,0 +1,54 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PausePools<'info> {
+    /// The admin or the pause guardian of the amm config
+    #[account(
+        constraint = (authority.key() == crate::admin::ID || amm_config.is_pause_guardian(&authority.key())) @ ErrorCode::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    /// Amm config account of all the paused pools
+    #[account(mut)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+}
+
+/// Disable every operation of the pools passed as remaining accounts, and the creation of
+/// new pools when `disable_create_pool` is set. Only the admin resumes them
+pub fn pause_pools<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, PausePools<'info>>,
+    disable_create_pool: bool,
+) -> Result<()> {
+    let epoch = Clock::get()?.epoch;
+    let amm_config = &mut ctx.accounts.amm_config;
+    require!(
+        disable_create_pool || !ctx.remaining_accounts.is_empty(),
+        ErrorCode::InvalidInput
+    );
+    let mut pools = Vec::with_capacity(ctx.remaining_accounts.len());
+    for pool_account in ctx.remaining_accounts.iter() {
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(pool_account)?;
+        let mut pool_state = pool_state_loader.load_mut()?;
+        require_keys_eq!(
+            pool_state.amm_config,
+            amm_config.key(),
+            ErrorCode::InvalidInput
+        );
+        pool_state.pause();
+        pool_state.recent_epoch = epoch;
+        pools.push(pool_account.key());
+    }
+    if disable_create_pool {
+        amm_config.disable_create_pool = true;
+    }
+
+    emit!(PoolsPausedEvent {
+        amm_config: amm_config.key(),
+        authority: ctx.accounts.authority.key(),
+        pools,
+        disable_create_pool,
+    });
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::AccountLoad;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(role: u8)]
+pub struct ProposeNewAdmin<'info> {
+    /// The current admin of the role, or the program admin for amm config roles
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    /// CHECK: The amm config or pool state the role belongs to, checked by the role
+    pub target: UncheckedAccount<'info>,
+
+    /// Stores the proposed admin until it is accepted
+    #[account(
+        init_if_needed,
+        seeds = [
+            PENDING_ADMIN_SEED.as_bytes(),
+            target.key().as_ref(),
+            &[role],
+        ],
+        bump,
+        payer = proposer,
+        space = PendingAdmin::LEN
+    )]
+    pub pending_admin: Account<'info, PendingAdmin>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_new_admin(ctx: Context<ProposeNewAdmin>, role: u8, new_admin: Pubkey) -> Result<()> {
+    require_keys_neq!(new_admin, Pubkey::default());
+    let proposer = ctx.accounts.proposer.key();
+    let target_info = ctx.accounts.target.to_account_info();
+    require_keys_eq!(*target_info.owner, crate::id(), ErrorCode::InvalidOwner);
+    match AdminRole::from_u8(role).ok_or(ErrorCode::InvalidInput)? {
+        AdminRole::ProtocolOwner => {
+            let amm_config = AmmConfig::try_deserialize(&mut &target_info.try_borrow_data()?[..])?;
+            require!(
+                proposer == amm_config.protocol_owner || proposer == crate::admin::ID,
+                ErrorCode::InvalidOwner
+            );
+        }
+        AdminRole::FundOwner => {
+            let amm_config = AmmConfig::try_deserialize(&mut &target_info.try_borrow_data()?[..])?;
+            require!(
+                proposer == amm_config.fund_owner || proposer == crate::admin::ID,
+                ErrorCode::InvalidOwner
+            );
+        }
+        AdminRole::PoolCustomAuthority => {
+            let pool_state_loader = AccountLoad::<PoolState>::try_from(&target_info)?;
+            let pool_state = pool_state_loader.load()?;
+            require!(
+                pool_state.is_custom_authority() && proposer == pool_state.custom_authority,
+                ErrorCode::InvalidAuthority
+            );
+        }
+    }
+
+    let pending_admin = &mut ctx.accounts.pending_admin;
+    pending_admin.bump = ctx.bumps.pending_admin;
+    pending_admin.role = role;
+    pending_admin.target = target_info.key();
+    pending_admin.pending_admin = new_admin;
+    pending_admin.proposer = proposer;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "propose new admin, role:{}, target:{}, pending_admin:{}",
+        role,
+        pending_admin.target.to_string(),
+        new_admin.to_string()
+    );
+    Ok(())
+}
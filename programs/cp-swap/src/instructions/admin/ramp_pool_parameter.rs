@@ -0,0 +1,73 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RampPoolParameter<'info> {
+    /// Only admin or the custom authority of the pool can ramp the amplification coefficient,
+    /// only the custom authority can ramp the trade fee rate
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores the trade fee bounds
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn ramp_pool_parameter(
+    ctx: Context<RampPoolParameter>,
+    parameter: RampParameter,
+    target_value: u64,
+    ramp_duration: u32,
+) -> Result<()> {
+    let block_timestamp = oracle::block_timestamp();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    match parameter {
+        RampParameter::Amp => {
+            #[cfg(feature = "enable-log")]
+            msg!(
+                "pool_state, amp:{}, target_amp:{}, ramp_duration:{}",
+                pool_state.amp_at(block_timestamp),
+                target_value,
+                ramp_duration
+            );
+            pool_state.ramp_amp(target_value, block_timestamp, ramp_duration)?;
+        }
+        RampParameter::TradeFeeRate => {
+            require!(
+                pool_state.is_custom_authority()
+                    && ctx.accounts.authority.key() == pool_state.custom_authority,
+                ErrorCode::InvalidAuthority
+            );
+            require!(
+                ctx.accounts
+                    .amm_config
+                    .is_valid_pool_trade_fee_rate(target_value),
+                ErrorCode::InvalidTradeFeeRate
+            );
+            // the ramp starts from the rate the swaps currently pay
+            let trade_fee_rate = ctx
+                .accounts
+                .amm_config
+                .pool_trade_fee_rate(pool_state.trade_fee_rate_override(block_timestamp));
+            #[cfg(feature = "enable-log")]
+            msg!(
+                "pool_state, trade_fee_rate:{}, target_trade_fee_rate:{}, ramp_duration:{}",
+                trade_fee_rate,
+                target_value,
+                ramp_duration
+            );
+            pool_state.ramp_trade_fee_rate(
+                trade_fee_rate,
+                target_value,
+                block_timestamp,
+                ramp_duration,
+            )?;
+        }
+    }
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
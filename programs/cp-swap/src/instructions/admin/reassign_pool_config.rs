@@ -0,0 +1,59 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ReassignPoolConfig<'info> {
+    /// Only the admin can reassign a pool, pays for the queued reassignment
+    #[account(mut, address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config the pool is reassigned to
+    pub new_amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Stores the reassignment until its timelock expires
+    #[account(
+        init_if_needed,
+        seeds = [
+            PENDING_POOL_CONFIG_CHANGE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PendingPoolConfigChange::LEN
+    )]
+    pub pending_pool_config_change: Account<'info, PendingPoolConfigChange>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn reassign_pool_config(ctx: Context<ReassignPoolConfig>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let amm_config = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        pool_state.check_amm_config_reassignment(&ctx.accounts.new_amm_config)?;
+        pool_state.amm_config
+    };
+    let new_amm_config = ctx.accounts.new_amm_config.key();
+    require_keys_neq!(new_amm_config, amm_config, ErrorCode::InvalidInput);
+
+    // a queued reassignment of the pool is replaced and its timelock restarts
+    let effective_timestamp = (Clock::get()?.unix_timestamp as u64)
+        .checked_add(CONFIG_CHANGE_DELAY)
+        .unwrap();
+    let pending_pool_config_change = &mut ctx.accounts.pending_pool_config_change;
+    pending_pool_config_change.bump = ctx.bumps.pending_pool_config_change;
+    pending_pool_config_change.pool_id = pool_id;
+    pending_pool_config_change.new_amm_config = new_amm_config;
+    pending_pool_config_change.effective_timestamp = effective_timestamp;
+    pending_pool_config_change.payer = ctx.accounts.owner.key();
+    emit!(PoolConfigChangeQueuedEvent {
+        pool_id,
+        amm_config,
+        new_amm_config,
+        effective_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,392 @@
+use crate::curve::CurveCalculator;
+use crate::curve::RoundDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct RebalanceBetweenPools<'info> {
+    /// Only admin can move its liquidity between pools without trading
+    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the liquidity is withdrawn from
+    #[account(mut)]
+    pub from_pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool of the same mint pair the liquidity is deposited to
+    #[account(
+        mut,
+        constraint = to_pool_state.key() != from_pool_state.key() @ ErrorCode::InvalidInput,
+        constraint = to_pool_state.load()?.token_0_mint == from_pool_state.load()?.token_0_mint @ ErrorCode::InvalidInput,
+        constraint = to_pool_state.load()?.token_1_mint == from_pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput,
+    )]
+    pub to_pool_state: AccountLoader<'info, PoolState>,
+
+    /// Owner lp token account of the pool the liquidity is withdrawn from
+    #[account(
+        mut,
+        token::mint = from_lp_mint,
+        token::authority = owner
+    )]
+    pub owner_from_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner lp token account of the pool the liquidity is deposited to
+    #[account(
+        mut,
+        token::mint = to_lp_mint,
+    )]
+    pub owner_to_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account receiving the token_0 left over by the ratio of the pools
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account receiving the token_1 left over by the ratio of the pools
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_0 vault of the pool the liquidity is withdrawn from
+    #[account(
+        mut,
+        constraint = from_token_0_vault.key() == from_pool_state.load()?.token_0_vault
+    )]
+    pub from_token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 vault of the pool the liquidity is withdrawn from
+    #[account(
+        mut,
+        constraint = from_token_1_vault.key() == from_pool_state.load()?.token_1_vault
+    )]
+    pub from_token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_0 vault of the pool the liquidity is deposited to
+    #[account(
+        mut,
+        constraint = to_token_0_vault.key() == to_pool_state.load()?.token_0_vault
+    )]
+    pub to_token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 vault of the pool the liquidity is deposited to
+    #[account(
+        mut,
+        constraint = to_token_1_vault.key() == to_pool_state.load()?.token_1_vault
+    )]
+    pub to_token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vaults
+    #[account(
+        address = from_token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vaults
+    #[account(
+        address = from_token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Lp token mint of the pool the liquidity is withdrawn from
+    #[account(
+        mut,
+        address = from_pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint
+    )]
+    pub from_lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Lp token mint of the pool the liquidity is deposited to
+    #[account(
+        mut,
+        address = to_pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint
+    )]
+    pub to_lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+impl<'info> RebalanceBetweenPools<'info> {
+    fn mint_token_program(&self, mint: &InterfaceAccount<'info, Mint>) -> AccountInfo<'info> {
+        if mint.to_account_info().owner == self.token_program.key {
+            self.token_program.to_account_info()
+        } else {
+            self.token_program_2022.to_account_info()
+        }
+    }
+}
+
+pub fn rebalance_between_pools<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, RebalanceBetweenPools<'info>>,
+    lp_token_amount: u64,
+    minimum_lp_token_amount_out: u64,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let from_pool_id = ctx.accounts.from_pool_state.key();
+    let to_pool_id = ctx.accounts.to_pool_state.key();
+    let from_pool_state = &mut ctx.accounts.from_pool_state.load_mut()?;
+    let to_pool_state = &mut ctx.accounts.to_pool_state.load_mut()?;
+    if !from_pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw)
+        || !to_pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !from_pool_state.is_flash_loan_active() && !to_pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    // the lp tokens of the pools with a withdrawal lockup only move through deposit receipts
+    from_pool_state.check_withdraw_without_receipt()?;
+    to_pool_state.check_deposit_without_receipt()?;
+    // the liquidity of custom authority pools is only provided by their custom authority
+    require!(
+        !from_pool_state.is_custom_authority() && !to_pool_state.is_custom_authority(),
+        ErrorCode::InvalidAuthority
+    );
+    require!(
+        !to_pool_state.is_position_mode(),
+        ErrorCode::LpTokensDisabled
+    );
+    from_pool_state.lock()?;
+    to_pool_state.lock()?;
+    let from_pool_vaults = PoolVaults::new(
+        ctx.accounts.from_token_0_vault.to_account_info(),
+        ctx.accounts.from_token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let from_pool_snapshot = from_pool_vaults.snapshot(from_pool_state)?;
+    let to_pool_vaults = PoolVaults::new(
+        ctx.accounts.to_token_0_vault.to_account_info(),
+        ctx.accounts.to_token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let to_pool_snapshot = to_pool_vaults.snapshot(to_pool_state)?;
+
+    // withdraw from the first pool at its ratio
+    let (from_total_token_0_amount, from_total_token_1_amount) = from_pool_state
+        .vault_amount_without_fee(
+            ctx.accounts.from_token_0_vault.amount,
+            ctx.accounts.from_token_1_vault.amount,
+        );
+    let withdrawn = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(from_pool_state.lp_supply),
+        u128::from(from_total_token_0_amount),
+        u128::from(from_total_token_1_amount),
+        RoundDirection::Floor,
+    )?;
+    let withdrawn_token_0_amount = u64::try_from(withdrawn.token_0_amount)
+        .unwrap()
+        .min(from_total_token_0_amount);
+    let withdrawn_token_1_amount = u64::try_from(withdrawn.token_1_amount)
+        .unwrap()
+        .min(from_total_token_1_amount);
+    from_pool_state.check_vault_outflow(
+        ctx.accounts.from_token_0_vault.amount,
+        ctx.accounts.from_token_1_vault.amount,
+        withdrawn_token_0_amount,
+        withdrawn_token_1_amount,
+    )?;
+
+    // deposit the most the withdrawn tokens cover into the second pool at its ratio
+    let (to_total_token_0_amount, to_total_token_1_amount) = to_pool_state
+        .vault_amount_without_fee(
+            ctx.accounts.to_token_0_vault.amount,
+            ctx.accounts.to_token_1_vault.amount,
+        );
+    let netted_token_0_amount = withdrawn_token_0_amount.saturating_sub(get_transfer_fee(
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        withdrawn_token_0_amount,
+    )?);
+    let netted_token_1_amount = withdrawn_token_1_amount.saturating_sub(get_transfer_fee(
+        &ctx.accounts.vault_1_mint.to_account_info(),
+        withdrawn_token_1_amount,
+    )?);
+    let to_lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens(
+        u128::from(netted_token_0_amount),
+        u128::from(netted_token_1_amount),
+        u128::from(to_pool_state.lp_supply),
+        u128::from(to_total_token_0_amount),
+        u128::from(to_total_token_1_amount),
+    )
+    .and_then(|lp_token_amount| {
+        u64::try_from(lp_token_amount).map_err(|_| error!(ErrorCode::MathOverflow))
+    })?;
+    require_gt!(to_lp_token_amount, 0, ErrorCode::ZeroTradingTokens);
+    if to_lp_token_amount < minimum_lp_token_amount_out {
+        return Err(ErrorCode::ExceededSlippage.into());
+    }
+    let deposited = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(to_lp_token_amount),
+        u128::from(to_pool_state.lp_supply),
+        u128::from(to_total_token_0_amount),
+        u128::from(to_total_token_1_amount),
+        RoundDirection::Ceiling,
+    )?;
+    let deposited_token_0_amount = u64::try_from(deposited.token_0_amount).unwrap();
+    let deposited_token_1_amount = u64::try_from(deposited.token_1_amount).unwrap();
+    let transfer_token_0_fee = get_transfer_inverse_fee(
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        deposited_token_0_amount,
+    )?;
+    let transfer_token_1_fee = get_transfer_inverse_fee(
+        &ctx.accounts.vault_1_mint.to_account_info(),
+        deposited_token_1_amount,
+    )?;
+    let transfer_token_0_amount = deposited_token_0_amount
+        .checked_add(transfer_token_0_fee)
+        .unwrap();
+    let transfer_token_1_amount = deposited_token_1_amount
+        .checked_add(transfer_token_1_fee)
+        .unwrap();
+    let refund_token_0_amount = withdrawn_token_0_amount
+        .checked_sub(transfer_token_0_amount)
+        .ok_or(ErrorCode::ExceededSlippage)?;
+    let refund_token_1_amount = withdrawn_token_1_amount
+        .checked_sub(transfer_token_1_amount)
+        .ok_or(ErrorCode::ExceededSlippage)?;
+
+    emit!(LpChangeEvent {
+        pool_id: from_pool_id,
+        lp_amount_before: from_pool_state.lp_supply,
+        token_0_vault_before: from_total_token_0_amount,
+        token_1_vault_before: from_total_token_1_amount,
+        token_0_amount: withdrawn_token_0_amount,
+        token_1_amount: withdrawn_token_1_amount,
+        token_0_transfer_fee: 0,
+        token_1_transfer_fee: 0,
+        change_type: 1
+    });
+    emit!(LpChangeEvent {
+        pool_id: to_pool_id,
+        lp_amount_before: to_pool_state.lp_supply,
+        token_0_vault_before: to_total_token_0_amount,
+        token_1_vault_before: to_total_token_1_amount,
+        token_0_amount: deposited_token_0_amount,
+        token_1_amount: deposited_token_1_amount,
+        token_0_transfer_fee: transfer_token_0_fee,
+        token_1_transfer_fee: transfer_token_1_fee,
+        change_type: 0
+    });
+    emit!(PoolRebalanceEvent {
+        from_pool_id,
+        to_pool_id,
+        from_lp_amount: lp_token_amount,
+        to_lp_amount: to_lp_token_amount,
+        token_0_amount: transfer_token_0_amount,
+        token_1_amount: transfer_token_1_amount,
+        token_0_refund: refund_token_0_amount,
+        token_1_refund: refund_token_1_amount,
+    });
+
+    let from_lp_supply = from_pool_state
+        .lp_supply
+        .checked_sub(lp_token_amount)
+        .unwrap();
+    from_pool_state.set_lp_supply(from_lp_supply);
+    let to_lp_supply = to_pool_state
+        .lp_supply
+        .checked_add(to_lp_token_amount)
+        .unwrap();
+    to_pool_state.set_lp_supply(to_lp_supply);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[from_pool_state.auth_bump]]];
+    token_burn(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.mint_token_program(&ctx.accounts.from_lp_mint),
+        ctx.accounts.from_lp_mint.to_account_info(),
+        ctx.accounts.owner_from_lp_token.to_account_info(),
+        lp_token_amount,
+        signer_seeds,
+    )?;
+    for (from_vault, to_vault, token_account, mint, transfer_amount, refund_amount) in [
+        (
+            &ctx.accounts.from_token_0_vault,
+            &ctx.accounts.to_token_0_vault,
+            &ctx.accounts.token_0_account,
+            &ctx.accounts.vault_0_mint,
+            transfer_token_0_amount,
+            refund_token_0_amount,
+        ),
+        (
+            &ctx.accounts.from_token_1_vault,
+            &ctx.accounts.to_token_1_vault,
+            &ctx.accounts.token_1_account,
+            &ctx.accounts.vault_1_mint,
+            transfer_token_1_amount,
+            refund_token_1_amount,
+        ),
+    ] {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            from_vault.to_account_info(),
+            to_vault.to_account_info(),
+            mint.to_account_info(),
+            ctx.accounts.mint_token_program(mint),
+            transfer_amount,
+            mint.decimals,
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            from_vault.to_account_info(),
+            token_account.to_account_info(),
+            mint.to_account_info(),
+            ctx.accounts.mint_token_program(mint),
+            refund_amount,
+            mint.decimals,
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+    }
+    token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.mint_token_program(&ctx.accounts.to_lp_mint),
+        ctx.accounts.to_lp_mint.to_account_info(),
+        ctx.accounts.owner_to_lp_token.to_account_info(),
+        to_lp_token_amount,
+        signer_seeds,
+    )?;
+
+    let epoch = Clock::get()?.epoch;
+    from_pool_state.recent_epoch = epoch;
+    to_pool_state.recent_epoch = epoch;
+    from_pool_vaults.check(
+        from_pool_state,
+        Some(&ctx.accounts.from_lp_mint.to_account_info()),
+        Some(&from_pool_snapshot),
+    )?;
+    to_pool_vaults.check(
+        to_pool_state,
+        Some(&ctx.accounts.to_lp_mint.to_account_info()),
+        Some(&to_pool_snapshot),
+    )?;
+    from_pool_state.unlock();
+    to_pool_state.unlock();
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::validate_custom_authority_signer;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolCustomAuthority<'info> {
+    /// The current custom authority of the pool, or the admin as break-glass
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_pool_custom_authority(
+    ctx: Context<SetPoolCustomAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // a renounced custom authority cannot be set again
+    require!(pool_state.is_custom_authority(), ErrorCode::NotApproved);
+    let by_admin = ctx.accounts.authority.key() == crate::admin::ID;
+    if !by_admin {
+        validate_custom_authority_signer(&pool_state, &ctx.accounts.authority)?;
+        // the custom authority only renounces directly, its transfers are proposed and
+        // accepted so a mistyped key never takes the pool
+        require_keys_eq!(
+            new_authority,
+            Pubkey::default(),
+            ErrorCode::TransferNotProposed
+        );
+    }
+    let old_authority = pool_state.custom_authority;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_custom_authority:{}, new_custom_authority:{}",
+        old_authority,
+        new_authority
+    );
+    pool_state.set_custom_authority(new_authority);
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    emit!(CustomAuthorityChangedEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        old_authority,
+        new_authority,
+        by_admin,
+    });
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolFeeSplit<'info> {
+    /// Only admin can set the fee split of a pool
+    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores the referral fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn set_pool_fee_split(
+    ctx: Context<SetPoolFeeSplit>,
+    protocol_fee_rate: Option<u64>,
+    fund_fee_rate: Option<u64>,
+) -> Result<()> {
+    let fee_split = match (protocol_fee_rate, fund_fee_rate) {
+        (Some(protocol_fee_rate), Some(fund_fee_rate)) => {
+            // the referral share of the amm config and the creator share of the pool are
+            // taken from the same trade fee
+            let creator_fee_rate = ctx.accounts.pool_state.load()?.creator_fee_rate;
+            require!(
+                protocol_fee_rate
+                    .checked_add(fund_fee_rate)
+                    .and_then(|rate| rate.checked_add(ctx.accounts.amm_config.referral_fee_rate))
+                    .and_then(|rate| rate.checked_add(creator_fee_rate))
+                    .is_some_and(|rate| rate <= FEE_RATE_DENOMINATOR_VALUE),
+                ErrorCode::InvalidFeeSplit
+            );
+            Some((protocol_fee_rate, fund_fee_rate))
+        }
+        (None, None) => None,
+        _ => return err!(ErrorCode::InvalidInput),
+    };
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_fee_split:{:?}, new_fee_split:{:?}",
+        pool_state.fee_split(&ctx.accounts.amm_config),
+        fee_split
+    );
+    pool_state.set_fee_split_override(fee_split);
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
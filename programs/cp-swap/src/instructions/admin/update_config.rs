@@ -1,79 +1,423 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
 use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::utils::token::create_or_allocate_account;
 use anchor_lang::prelude::*;
+use spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS;
 
 #[derive(Accounts)]
 pub struct UpdateAmmConfig<'info> {
-    /// The amm config owner or admin
-    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    /// The amm config owner or admin, pays for the queued fee rate change
+    #[account(mut, address = crate::admin::ID @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// Amm config account to be changed
     #[account(mut)]
     pub amm_config: Account<'info, AmmConfig>,
+
+    /// CHECK: Stores the queued change, only required for fee rate changes,
+    /// seeds are checked when the change is queued
+    #[account(mut)]
+    pub pending_config_change: Option<UncheckedAccount<'info>>,
+
+    /// Only required for fee rate changes
+    pub system_program: Option<Program<'info, System>>,
 }
 
 pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
+    if is_fee_rate_param(param) {
+        return queue_config_change(ctx, param, value);
+    }
     let amm_config = &mut ctx.accounts.amm_config;
     let match_param = Some(param);
     match match_param {
-        Some(0) => update_trade_fee_rate(amm_config, value),
-        Some(1) => update_protocol_fee_rate(amm_config, value),
-        Some(2) => update_fund_fee_rate(amm_config, value),
-        Some(3) => {
-            let new_procotol_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
-            set_new_protocol_owner(amm_config, new_procotol_owner)?;
-        }
-        Some(4) => {
-            let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
-            set_new_fund_owner(amm_config, new_fund_owner)?;
-        }
         Some(5) => amm_config.create_pool_fee = value,
         Some(6) => amm_config.disable_create_pool = if value == 0 { false } else { true },
+        Some(11) => {
+            let pool_creation_authority = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .map(|account| *account.key)
+                .unwrap_or_default();
+            set_pool_creation_authority(amm_config, pool_creation_authority);
+        }
+        Some(12) => update_protocol_fee_compound_rate(amm_config, value),
+        Some(14) => update_fee_crank_bounty_rate(amm_config, value),
+        Some(15) => update_min_trade_fee_rate(amm_config, value),
+        Some(16) => update_max_trade_fee_rate(amm_config, value),
+        Some(17) => {
+            let buyback_mint = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .map(|account| *account.key)
+                .unwrap_or_default();
+            set_buyback_mint(amm_config, buyback_mint);
+        }
+        Some(18) => amm_config.buyback_epoch_cap = value,
+        Some(19) | Some(20) => {
+            let fee_destination = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .map(|account| *account.key)
+                .unwrap_or_default();
+            set_fee_destination(amm_config, param, fee_destination);
+        }
+        Some(21) | Some(22) => {
+            let account_key = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .map(|account| *account.key)
+                .ok_or(ErrorCode::InvalidInput)?;
+            set_create_pool_fee_account(amm_config, param, account_key);
+        }
+        Some(23) => amm_config.withheld_fees_to_protocol = value != 0,
+        Some(24) => amm_config.observation_update_duration = value,
+        Some(25) => {
+            let mint_policy = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .map(|account| *account.key)
+                .unwrap_or_default();
+            set_mint_policy(amm_config, mint_policy)?;
+        }
+        Some(26) => amm_config.fee_on_output = value != 0,
+        Some(27) => amm_config.allow_salted_pools = value != 0,
+        Some(28) => {
+            let pause_guardian = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .map(|account| *account.key)
+                .unwrap_or_default();
+            set_pause_guardian(amm_config, pause_guardian);
+        }
+        Some(29) => {
+            let revenue_program = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .map(|account| *account.key)
+                .unwrap_or_default();
+            set_revenue_program(amm_config, revenue_program);
+        }
+        Some(30) => update_insurance_fund_rate(amm_config, value),
+        Some(31) => update_max_transfer_fee_bps(amm_config, value),
+        Some(33) => amm_config.lp_fee_rebate_epoch_cap = value,
+        Some(34) => update_buyback_max_deviation_bps(amm_config, value),
+        Some(35) => amm_config.buyback_twap_window = u16::try_from(value).unwrap(),
+        _ => return err!(ErrorCode::InvalidInput),
+    }
+
+    emit_config_updated(amm_config, param, value);
+    Ok(())
+}
+
+/// Emit the amm config fee rates after a param is changed
+pub fn emit_config_updated(amm_config: &Account<AmmConfig>, param: u8, value: u64) {
+    emit!(ConfigUpdatedEvent {
+        amm_config: amm_config.key(),
+        param,
+        value,
+        trade_fee_rate: amm_config.trade_fee_rate,
+        protocol_fee_rate: amm_config.protocol_fee_rate,
+        fund_fee_rate: amm_config.fund_fee_rate,
+        referral_fee_rate: amm_config.referral_fee_rate,
+        flash_loan_fee_rate: amm_config.flash_loan_fee_rate,
+    });
+}
+
+/// Fee rate changes are timelocked so traders and LPs have advance notice
+pub fn is_fee_rate_param(param: u8) -> bool {
+    matches!(param, 0 | 1 | 2 | 7 | 8 | 9 | 10 | 13 | 32)
+}
+
+/// Apply a fee rate change to the amm config
+pub fn update_fee_rate(amm_config: &mut AmmConfig, param: u8, value: u64) -> Result<()> {
+    match param {
+        0 => update_trade_fee_rate(amm_config, value),
+        1 => update_protocol_fee_rate(amm_config, value),
+        2 => update_fund_fee_rate(amm_config, value),
+        7 => update_flash_loan_fee_rate(amm_config, value),
+        8 => update_dynamic_fee_min_rate(amm_config, value)?,
+        9 => update_dynamic_fee_max_rate(amm_config, value)?,
+        10 => amm_config.dynamic_fee_volatility_factor = value,
+        13 => update_referral_fee_rate(amm_config, value),
+        32 => update_lp_fee_rebate_rate(amm_config, value),
         _ => return err!(ErrorCode::InvalidInput),
     }
+    Ok(())
+}
+
+fn queue_config_change(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
+    // validate the new fee rate against the current config
+    update_fee_rate(&mut (*ctx.accounts.amm_config).clone(), param, value)?;
 
+    let pending_config_change_info = ctx
+        .accounts
+        .pending_config_change
+        .as_ref()
+        .ok_or(ErrorCode::InvalidInput)?
+        .to_account_info();
+    let amm_config_key = ctx.accounts.amm_config.key();
+    let (expected_key, bump) = Pubkey::find_program_address(
+        &[
+            PENDING_CONFIG_CHANGE_SEED.as_bytes(),
+            amm_config_key.as_ref(),
+            &[param],
+        ],
+        &crate::id(),
+    );
+    require_keys_eq!(pending_config_change_info.key(), expected_key);
+    // a queued change of the same param is replaced and its timelock restarts
+    if pending_config_change_info.owner != &crate::id() {
+        let system_program = ctx
+            .accounts
+            .system_program
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?;
+        create_or_allocate_account(
+            &crate::id(),
+            ctx.accounts.owner.to_account_info(),
+            system_program.to_account_info(),
+            pending_config_change_info.clone(),
+            &[
+                PENDING_CONFIG_CHANGE_SEED.as_bytes(),
+                amm_config_key.as_ref(),
+                &[param],
+                &[bump],
+            ],
+            PendingConfigChange::LEN,
+        )?;
+    }
+
+    let effective_timestamp = (Clock::get()?.unix_timestamp as u64)
+        .checked_add(CONFIG_CHANGE_DELAY)
+        .unwrap();
+    let pending_config_change = PendingConfigChange {
+        bump,
+        param,
+        amm_config: amm_config_key,
+        value,
+        effective_timestamp,
+        payer: ctx.accounts.owner.key(),
+    };
+    pending_config_change
+        .try_serialize(&mut &mut pending_config_change_info.try_borrow_mut_data()?[..])?;
+    emit!(ConfigChangeQueuedEvent {
+        amm_config: amm_config_key,
+        param,
+        value,
+        effective_timestamp,
+    });
     Ok(())
 }
 
-fn update_protocol_fee_rate(amm_config: &mut Account<AmmConfig>, protocol_fee_rate: u64) {
+fn update_protocol_fee_rate(amm_config: &mut AmmConfig, protocol_fee_rate: u64) {
     assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(protocol_fee_rate + amm_config.fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    assert!(
+        protocol_fee_rate + amm_config.fund_fee_rate + amm_config.referral_fee_rate
+            <= FEE_RATE_DENOMINATOR_VALUE
+    );
     amm_config.protocol_fee_rate = protocol_fee_rate;
 }
 
-fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u64) {
+fn update_trade_fee_rate(amm_config: &mut AmmConfig, trade_fee_rate: u64) {
     assert!(trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
     amm_config.trade_fee_rate = trade_fee_rate;
 }
 
-fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u64) {
+fn update_fund_fee_rate(amm_config: &mut AmmConfig, fund_fee_rate: u64) {
     assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(fund_fee_rate + amm_config.protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    assert!(
+        fund_fee_rate + amm_config.protocol_fee_rate + amm_config.referral_fee_rate
+            <= FEE_RATE_DENOMINATOR_VALUE
+    );
     amm_config.fund_fee_rate = fund_fee_rate;
 }
 
-fn set_new_protocol_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) -> Result<()> {
-    require_keys_neq!(new_owner, Pubkey::default());
+fn update_referral_fee_rate(amm_config: &mut AmmConfig, referral_fee_rate: u64) {
+    assert!(referral_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    assert!(
+        referral_fee_rate + amm_config.protocol_fee_rate + amm_config.fund_fee_rate
+            <= FEE_RATE_DENOMINATOR_VALUE
+    );
+    amm_config.referral_fee_rate = referral_fee_rate;
+}
+
+fn update_flash_loan_fee_rate(amm_config: &mut AmmConfig, flash_loan_fee_rate: u64) {
+    assert!(flash_loan_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.flash_loan_fee_rate = flash_loan_fee_rate;
+}
+
+fn update_dynamic_fee_min_rate(
+    amm_config: &mut AmmConfig,
+    dynamic_fee_min_rate: u64,
+) -> Result<()> {
+    assert!(dynamic_fee_min_rate < FEE_RATE_DENOMINATOR_VALUE);
+    // the bounds are only ordered while the dynamic fee mode is enabled
+    require!(
+        !amm_config.is_dynamic_fee_enabled()
+            || dynamic_fee_min_rate <= amm_config.dynamic_fee_max_rate,
+        ErrorCode::InvalidDynamicFeeBounds
+    );
+    amm_config.dynamic_fee_min_rate = dynamic_fee_min_rate;
+    Ok(())
+}
+
+fn update_dynamic_fee_max_rate(
+    amm_config: &mut AmmConfig,
+    dynamic_fee_max_rate: u64,
+) -> Result<()> {
+    assert!(dynamic_fee_max_rate < FEE_RATE_DENOMINATOR_VALUE);
+    // 0 disables the dynamic fee mode
+    require!(
+        dynamic_fee_max_rate == 0 || dynamic_fee_max_rate >= amm_config.dynamic_fee_min_rate,
+        ErrorCode::InvalidDynamicFeeBounds
+    );
+    amm_config.dynamic_fee_max_rate = dynamic_fee_max_rate;
+    Ok(())
+}
+
+fn update_protocol_fee_compound_rate(amm_config: &mut AmmConfig, protocol_fee_compound_rate: u64) {
+    assert!(protocol_fee_compound_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.protocol_fee_compound_rate = protocol_fee_compound_rate;
+}
+
+fn update_fee_crank_bounty_rate(amm_config: &mut AmmConfig, fee_crank_bounty_rate: u64) {
+    assert!(fee_crank_bounty_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.fee_crank_bounty_rate = fee_crank_bounty_rate;
+}
+
+fn update_insurance_fund_rate(amm_config: &mut AmmConfig, insurance_fund_rate: u64) {
+    assert!(insurance_fund_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.insurance_fund_rate = insurance_fund_rate;
+}
+
+fn update_lp_fee_rebate_rate(amm_config: &mut AmmConfig, lp_fee_rebate_rate: u64) {
+    assert!(lp_fee_rebate_rate <= FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.lp_fee_rebate_rate = lp_fee_rebate_rate;
+}
+
+fn update_max_transfer_fee_bps(amm_config: &mut AmmConfig, max_transfer_fee_bps: u64) {
+    assert!(max_transfer_fee_bps <= u64::from(MAX_FEE_BASIS_POINTS));
+    amm_config.max_transfer_fee_bps = max_transfer_fee_bps as u16;
+}
+
+fn update_buyback_max_deviation_bps(amm_config: &mut AmmConfig, buyback_max_deviation_bps: u64) {
+    assert!(buyback_max_deviation_bps <= PRICE_IMPACT_BPS_DENOMINATOR);
+    amm_config.buyback_max_deviation_bps = buyback_max_deviation_bps as u16;
+}
+
+fn update_min_trade_fee_rate(amm_config: &mut AmmConfig, min_trade_fee_rate: u64) {
+    assert!(min_trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.min_trade_fee_rate = min_trade_fee_rate;
+}
+
+fn update_max_trade_fee_rate(amm_config: &mut AmmConfig, max_trade_fee_rate: u64) {
+    assert!(max_trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
+    amm_config.max_trade_fee_rate = max_trade_fee_rate;
+}
+
+fn set_pool_creation_authority(amm_config: &mut AmmConfig, pool_creation_authority: Pubkey) {
     #[cfg(feature = "enable-log")]
     msg!(
-        "amm_config, old_protocol_owner:{}, new_owner:{}",
-        amm_config.protocol_owner.to_string(),
-        new_owner.key().to_string()
+        "amm_config, old_pool_creation_authority:{}, new_pool_creation_authority:{}",
+        amm_config.pool_creation_authority.to_string(),
+        pool_creation_authority.to_string()
     );
-    amm_config.protocol_owner = new_owner;
-    Ok(())
+    amm_config.pool_creation_authority = pool_creation_authority;
 }
 
-fn set_new_fund_owner(amm_config: &mut Account<AmmConfig>, new_fund_owner: Pubkey) -> Result<()> {
-    require_keys_neq!(new_fund_owner, Pubkey::default());
+fn set_pause_guardian(amm_config: &mut AmmConfig, pause_guardian: Pubkey) {
     #[cfg(feature = "enable-log")]
     msg!(
-        "amm_config, old_fund_owner:{}, new_fund_owner:{}",
-        amm_config.fund_owner.to_string(),
-        new_fund_owner.key().to_string()
+        "amm_config, old_pause_guardian:{}, new_pause_guardian:{}",
+        amm_config.pause_guardian.to_string(),
+        pause_guardian.to_string()
     );
-    amm_config.fund_owner = new_fund_owner;
+    amm_config.pause_guardian = pause_guardian;
+}
+
+fn set_revenue_program(amm_config: &mut AmmConfig, revenue_program: Pubkey) {
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, old_revenue_program:{}, new_revenue_program:{}",
+        amm_config.revenue_program.to_string(),
+        revenue_program.to_string()
+    );
+    amm_config.revenue_program = revenue_program;
+}
+
+fn set_buyback_mint(amm_config: &mut AmmConfig, buyback_mint: Pubkey) {
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, old_buyback_mint:{}, new_buyback_mint:{}",
+        amm_config.buyback_mint.to_string(),
+        buyback_mint.to_string()
+    );
+    amm_config.buyback_mint = buyback_mint;
+}
+
+fn set_fee_destination(amm_config: &mut AmmConfig, param: u8, fee_destination: Pubkey) {
+    if param == 19 {
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "amm_config, old_protocol_fee_destination:{}, new_protocol_fee_destination:{}",
+            amm_config.protocol_fee_destination.to_string(),
+            fee_destination.to_string()
+        );
+        amm_config.protocol_fee_destination = fee_destination;
+    } else {
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "amm_config, old_fund_fee_destination:{}, new_fund_fee_destination:{}",
+            amm_config.fund_fee_destination.to_string(),
+            fee_destination.to_string()
+        );
+        amm_config.fund_fee_destination = fee_destination;
+    }
+}
+
+fn set_mint_policy(amm_config: &mut Account<AmmConfig>, mint_policy: Pubkey) -> Result<()> {
+    if mint_policy != Pubkey::default() {
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[MINT_POLICY_SEED.as_bytes(), amm_config.key().as_ref()],
+            &crate::id(),
+        );
+        require_keys_eq!(mint_policy, expected_key);
+    }
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, old_mint_policy:{}, new_mint_policy:{}",
+        amm_config.mint_policy.to_string(),
+        mint_policy.to_string()
+    );
+    amm_config.mint_policy = mint_policy;
     Ok(())
 }
+
+fn set_create_pool_fee_account(amm_config: &mut AmmConfig, param: u8, account_key: Pubkey) {
+    if param == 21 {
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "amm_config, old_create_pool_fee_mint:{}, new_create_pool_fee_mint:{}",
+            amm_config.create_pool_fee_mint.to_string(),
+            account_key.to_string()
+        );
+        amm_config.create_pool_fee_mint = account_key;
+    } else {
+        #[cfg(feature = "enable-log")]
+        msg!(
+            "amm_config, old_create_pool_fee_receiver:{}, new_create_pool_fee_receiver:{}",
+            amm_config.create_pool_fee_receiver.to_string(),
+            account_key.to_string()
+        );
+        amm_config.create_pool_fee_receiver = account_key;
+    }
+}
@@ -0,0 +1,35 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeDiscount<'info> {
+    /// Only admin can set the fee discount
+    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn update_fee_discount(
+    ctx: Context<UpdateFeeDiscount>,
+    fee_discount_mint: Pubkey,
+    tiers: Vec<FeeDiscountTier>,
+) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.set_fee_discount(fee_discount_mint, &tiers)?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, fee_discount_mint:{}, tiers:{:?}",
+        fee_discount_mint,
+        tiers
+    );
+    emit!(FeeDiscountUpdatedEvent {
+        amm_config: amm_config.key(),
+        fee_discount_mint,
+        tiers,
+    });
+    Ok(())
+}
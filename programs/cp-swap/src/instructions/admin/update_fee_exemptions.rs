@@ -0,0 +1,48 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeExemptions<'info> {
+    /// Only admin can update the fee exemptions
+    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The fee exemption list to be changed
+    #[account(mut)]
+    pub fee_exemption_list: Box<Account<'info, FeeExemptionList>>,
+}
+
+pub fn update_fee_exemptions(
+    ctx: Context<UpdateFeeExemptions>,
+    traders: Vec<Pubkey>,
+    exemption_bps: u16,
+) -> Result<()> {
+    require_gte!(
+        PRICE_IMPACT_BPS_DENOMINATOR,
+        u64::from(exemption_bps),
+        ErrorCode::InvalidInput
+    );
+    let fee_exemption_list = &mut ctx.accounts.fee_exemption_list;
+    for trader in traders.iter() {
+        require!(
+            fee_exemption_list.set_exemption(*trader, exemption_bps),
+            ErrorCode::FeeExemptionsFull
+        );
+    }
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "fee_exemption_list, traders:{:?}, exemption_bps:{}, exemption_count:{}",
+        traders,
+        exemption_bps,
+        fee_exemption_list.exemption_count
+    );
+    emit!(FeeExemptionsUpdatedEvent {
+        amm_config: fee_exemption_list.amm_config,
+        fee_exemption_list: fee_exemption_list.key(),
+        traders,
+        exemption_bps,
+    });
+    Ok(())
+}
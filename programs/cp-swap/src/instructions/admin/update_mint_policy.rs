@@ -0,0 +1,51 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMintPolicy<'info> {
+    /// Only admin can update the mint policy
+    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// The mint policy to be changed
+    #[account(mut)]
+    pub mint_policy: Box<Account<'info, MintPolicy>>,
+}
+
+pub fn update_mint_policy(
+    ctx: Context<UpdateMintPolicy>,
+    mode: u8,
+    mints: Vec<Pubkey>,
+    listed: bool,
+) -> Result<()> {
+    MintPolicyMode::from_u8(mode).ok_or(ErrorCode::InvalidInput)?;
+    let mint_policy = &mut ctx.accounts.mint_policy;
+    mint_policy.mode = mode;
+    for mint in mints.iter() {
+        if listed {
+            require!(
+                mint_policy.is_listed(mint) || mint_policy.add_mint(*mint),
+                ErrorCode::MintPolicyFull
+            );
+        } else {
+            mint_policy.remove_mint(mint);
+        }
+    }
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "mint_policy, mode:{}, mints:{:?}, listed:{}, mint_count:{}",
+        mode,
+        mints,
+        listed,
+        mint_policy.mint_count
+    );
+    emit!(MintPolicyUpdatedEvent {
+        amm_config: mint_policy.amm_config,
+        mint_policy: mint_policy.key(),
+        mode,
+        mints,
+        listed,
+    });
+    Ok(())
+}
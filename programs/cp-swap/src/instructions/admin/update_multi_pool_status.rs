@@ -0,0 +1,25 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMultiPoolStatus<'info> {
+    #[account(
+        address = crate::admin::ID @ ErrorCode::InvalidOwner
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, MultiPoolState>>,
+}
+
+pub fn update_multi_pool_status(ctx: Context<UpdateMultiPoolStatus>, status: u8) -> Result<()> {
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "multi_pool_state, old_status:{}, new_status:{}",
+        ctx.accounts.pool_state.status,
+        status
+    );
+    ctx.accounts.pool_state.status = status;
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolAuction<'info> {
+    /// Only admin or the custom authority of the pool can set the auction
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_auction(
+    ctx: Context<UpdatePoolAuction>,
+    filler: Pubkey,
+    size_threshold: u64,
+    window_slots: u64,
+) -> Result<()> {
+    if size_threshold > 0 {
+        require!(filler != Pubkey::default(), ErrorCode::InvalidInput);
+        require_gt!(window_slots, 0, ErrorCode::InvalidInput);
+    }
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_auction_filler:{}, old_auction_size_threshold:{}, old_auction_window_slots:{}, new_auction_filler:{}, new_auction_size_threshold:{}, new_auction_window_slots:{}",
+        { pool_state.auction_filler },
+        { pool_state.auction_size_threshold },
+        { pool_state.auction_window_slots },
+        filler,
+        size_threshold,
+        window_slots
+    );
+    pool_state.auction_filler = filler;
+    pool_state.auction_size_threshold = size_threshold;
+    pool_state.auction_window_slots = window_slots;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolCircuitBreaker<'info> {
+    /// Only admin or the custom authority of the pool can set or unpause the circuit breaker
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_circuit_breaker(
+    ctx: Context<UpdatePoolCircuitBreaker>,
+    threshold_bps: u16,
+    window: u32,
+) -> Result<()> {
+    require_gte!(
+        PRICE_IMPACT_BPS_DENOMINATOR,
+        u64::from(threshold_bps),
+        ErrorCode::InvalidInput
+    );
+    require!(threshold_bps == 0 || window > 0, ErrorCode::InvalidInput);
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_threshold_bps:{}, old_window:{}, new_threshold_bps:{}, new_window:{}",
+        { pool_state.circuit_breaker_threshold_bps },
+        { pool_state.circuit_breaker_window },
+        threshold_bps,
+        window
+    );
+    pool_state.circuit_breaker_threshold_bps = threshold_bps;
+    pool_state.circuit_breaker_window = window;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
+
+pub fn unpause_pool(ctx: Context<UpdatePoolCircuitBreaker>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        pool_state.is_circuit_breaker_tripped(),
+        ErrorCode::InvalidInput
+    );
+    pool_state.unpause_circuit_breaker(oracle::block_timestamp());
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
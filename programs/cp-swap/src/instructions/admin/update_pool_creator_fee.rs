@@ -0,0 +1,44 @@
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolCreatorFee<'info> {
+    /// Only admin or the custom authority of the pool can set the creator fee
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores the fee split and the referral fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn update_pool_creator_fee(
+    ctx: Context<UpdatePoolCreatorFee>,
+    creator_fee_rate: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // the protocol, fund and referral shares are taken from the same trade fee
+    let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&ctx.accounts.amm_config);
+    require!(
+        protocol_fee_rate
+            .checked_add(fund_fee_rate)
+            .and_then(|rate| rate.checked_add(ctx.accounts.amm_config.referral_fee_rate))
+            .and_then(|rate| rate.checked_add(creator_fee_rate))
+            .is_some_and(|rate| rate <= FEE_RATE_DENOMINATOR_VALUE),
+        ErrorCode::InvalidFeeSplit
+    );
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_creator_fee_rate:{}, new_creator_fee_rate:{}",
+        { pool_state.creator_fee_rate },
+        creator_fee_rate
+    );
+    pool_state.creator_fee_rate = creator_fee_rate;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
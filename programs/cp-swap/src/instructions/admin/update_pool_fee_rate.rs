@@ -0,0 +1,45 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolFeeRate<'info> {
+    /// The custom authority of the pool
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores the trade fee bounds
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn update_pool_fee_rate(
+    ctx: Context<UpdatePoolFeeRate>,
+    trade_fee_rate: Option<u64>,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        pool_state.is_custom_authority()
+            && ctx.accounts.authority.key() == pool_state.custom_authority,
+        ErrorCode::InvalidAuthority
+    );
+    if let Some(trade_fee_rate) = trade_fee_rate {
+        require!(
+            ctx.accounts
+                .amm_config
+                .is_valid_pool_trade_fee_rate(trade_fee_rate),
+            ErrorCode::InvalidTradeFeeRate
+        );
+    }
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_trade_fee_rate:{:?}, new_trade_fee_rate:{:?}",
+        pool_state.trade_fee_rate_override(oracle::block_timestamp()),
+        trade_fee_rate
+    );
+    pool_state.set_trade_fee_rate_override(trade_fee_rate);
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
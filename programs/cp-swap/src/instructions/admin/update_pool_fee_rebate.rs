@@ -0,0 +1,39 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolFeeRebate<'info> {
+    /// Only admin or the custom authority of the pool can set the fee rebates
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_fee_rebate(
+    ctx: Context<UpdatePoolFeeRebate>,
+    volume_threshold: u64,
+    rebate_bps: u16,
+) -> Result<()> {
+    require_gte!(
+        PRICE_IMPACT_BPS_DENOMINATOR,
+        u64::from(rebate_bps),
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_volume_threshold:{}, old_rebate_bps:{}, new_volume_threshold:{}, new_rebate_bps:{}",
+        { pool_state.fee_rebate_volume_threshold },
+        { pool_state.fee_rebate_bps },
+        volume_threshold,
+        rebate_bps
+    );
+    pool_state.fee_rebate_volume_threshold = volume_threshold;
+    pool_state.fee_rebate_bps = rebate_bps;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolLockup<'info> {
+    /// Only admin or the custom authority of the pool can set the withdrawal lockup
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_lockup(
+    ctx: Context<UpdatePoolLockup>,
+    min_lock_duration: u32,
+    early_exit_fee_bps: u16,
+) -> Result<()> {
+    require_gt!(
+        PRICE_IMPACT_BPS_DENOMINATOR,
+        u64::from(early_exit_fee_bps),
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_min_lock_duration:{}, old_early_exit_fee_bps:{}, new_min_lock_duration:{}, new_early_exit_fee_bps:{}",
+        { pool_state.min_lock_duration },
+        { pool_state.early_exit_fee_bps },
+        min_lock_duration,
+        early_exit_fee_bps
+    );
+    pool_state.min_lock_duration = min_lock_duration;
+    pool_state.early_exit_fee_bps = early_exit_fee_bps;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
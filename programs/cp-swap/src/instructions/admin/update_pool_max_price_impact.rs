@@ -0,0 +1,35 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolMaxPriceImpact<'info> {
+    /// Only admin or the custom authority of the pool can set the max price impact
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_max_price_impact(
+    ctx: Context<UpdatePoolMaxPriceImpact>,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    require_gte!(
+        PRICE_IMPACT_BPS_DENOMINATOR,
+        u64::from(max_price_impact_bps),
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_max_price_impact_bps:{}, new_max_price_impact_bps:{}",
+        { pool_state.max_price_impact_bps },
+        max_price_impact_bps
+    );
+    pool_state.max_price_impact_bps = max_price_impact_bps;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
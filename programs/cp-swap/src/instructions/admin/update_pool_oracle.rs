@@ -0,0 +1,45 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolOracle<'info> {
+    /// Only admin or the custom authority of the pool can set the oracle
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_oracle(
+    ctx: Context<UpdatePoolOracle>,
+    price_oracle: Pubkey,
+    max_deviation_bps: u16,
+    max_age: u32,
+) -> Result<()> {
+    if price_oracle != Pubkey::default() {
+        require!(
+            max_deviation_bps > 0 && u64::from(max_deviation_bps) <= PRICE_IMPACT_BPS_DENOMINATOR,
+            ErrorCode::InvalidInput
+        );
+        require_gt!(max_age, 0, ErrorCode::InvalidInput);
+    }
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_price_oracle:{}, old_max_deviation_bps:{}, old_max_age:{}, new_price_oracle:{}, new_max_deviation_bps:{}, new_max_age:{}",
+        { pool_state.price_oracle },
+        { pool_state.oracle_max_deviation_bps },
+        { pool_state.oracle_max_age },
+        price_oracle,
+        max_deviation_bps,
+        max_age
+    );
+    pool_state.price_oracle = price_oracle;
+    pool_state.oracle_max_deviation_bps = max_deviation_bps;
+    pool_state.oracle_max_age = max_age;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolPositionMode<'info> {
+    /// Only admin or the custom authority of the pool can set the position mode
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_position_mode(
+    ctx: Context<UpdatePoolPositionMode>,
+    position_mode: bool,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_position_mode:{}, new_position_mode:{}",
+        pool_state.position_mode,
+        position_mode
+    );
+    pool_state.position_mode = u8::from(position_mode);
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolPriceBand<'info> {
+    /// Only admin or the custom authority of the pool can set the price band
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_price_band(
+    ctx: Context<UpdatePoolPriceBand>,
+    min_price_x64: u128,
+    max_price_x64: u128,
+) -> Result<()> {
+    require!(
+        max_price_x64 == 0 || min_price_x64 <= max_price_x64,
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_min_price_x64:{}, old_max_price_x64:{}, new_min_price_x64:{}, new_max_price_x64:{}",
+        { pool_state.min_price_x64 },
+        { pool_state.max_price_x64 },
+        min_price_x64,
+        max_price_x64
+    );
+    pool_state.min_price_x64 = min_price_x64;
+    pool_state.max_price_x64 = max_price_x64;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
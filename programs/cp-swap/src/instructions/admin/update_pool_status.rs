@@ -1,21 +1,53 @@
+use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct UpdatePoolStatus<'info> {
+    /// The admin, or the pause guardian of the amm config which can only disable operations
     #[account(
-        address = crate::admin::ID
+        constraint = (authority.key() == crate::admin::ID || amm_config.as_ref().is_some_and(|amm_config| amm_config.is_pause_guardian(&authority.key()))) @ ErrorCode::InvalidAuthority
     )]
     pub authority: Signer<'info>,
 
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config of the pool, only required when signed by its pause guardian
+    #[account(address = pool_state.load()?.amm_config @ ErrorCode::InvalidInput)]
+    pub amm_config: Option<Box<Account<'info, AmmConfig>>>,
 }
 
 pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
     require_gte!(255, status);
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // only the admin resumes operations
+    if ctx.accounts.authority.key() != crate::admin::ID {
+        require!(
+            pool_state.is_pause_only(status),
+            ErrorCode::InvalidAuthority
+        );
+    }
     pool_state.set_status(status);
     pool_state.recent_epoch = Clock::get()?.epoch;
     Ok(())
 }
+
+pub fn update_pool_status_v2(ctx: Context<UpdatePoolStatus>, bit: u8, disable: bool) -> Result<()> {
+    let bit = PoolStatusBitIndex::from_u8(bit).ok_or(ErrorCode::InvalidInput)?;
+    // only the admin resumes operations
+    if ctx.accounts.authority.key() != crate::admin::ID {
+        require!(disable, ErrorCode::InvalidAuthority);
+    }
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_status_by_bit(
+        bit,
+        if disable {
+            PoolStatusBitFlag::Disable
+        } else {
+            PoolStatusBitFlag::Enable
+        },
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
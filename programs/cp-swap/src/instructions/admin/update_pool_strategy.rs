@@ -0,0 +1,46 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolStrategy<'info> {
+    /// Only admin or the custom authority of the pool can authorize a strategy
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_strategy(
+    ctx: Context<UpdatePoolStrategy>,
+    authorized_strategy: Pubkey,
+    max_borrow_bps: u16,
+) -> Result<()> {
+    require_gte!(
+        PRICE_IMPACT_BPS_DENOMINATOR,
+        u64::from(max_borrow_bps),
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // Only the strategy holding the borrowed reserves can bring them back
+    if authorized_strategy != pool_state.authorized_strategy {
+        require!(
+            !pool_state.is_strategy_borrowed(),
+            ErrorCode::StrategyBorrowOutstanding
+        );
+    }
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_authorized_strategy:{}, old_max_borrow_bps:{}, new_authorized_strategy:{}, new_max_borrow_bps:{}",
+        { pool_state.authorized_strategy },
+        { pool_state.strategy_max_borrow_bps },
+        authorized_strategy,
+        max_borrow_bps
+    );
+    pool_state.authorized_strategy = authorized_strategy;
+    pool_state.strategy_max_borrow_bps = max_borrow_bps;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolTradeSizeLimits<'info> {
+    /// Only admin or the custom authority of the pool can set the trade size limits
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_trade_size_limits(
+    ctx: Context<UpdatePoolTradeSizeLimits>,
+    min_trade_amount: u64,
+    max_trade_amount: u64,
+) -> Result<()> {
+    require!(
+        max_trade_amount == 0 || min_trade_amount <= max_trade_amount,
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_min_trade_amount:{}, old_max_trade_amount:{}, new_min_trade_amount:{}, new_max_trade_amount:{}",
+        { pool_state.min_trade_amount },
+        { pool_state.max_trade_amount },
+        min_trade_amount,
+        max_trade_amount
+    );
+    pool_state.min_trade_amount = min_trade_amount;
+    pool_state.max_trade_amount = max_trade_amount;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
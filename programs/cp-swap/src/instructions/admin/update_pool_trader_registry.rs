@@ -0,0 +1,34 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolTraderRegistry<'info> {
+    /// Only admin or the custom authority of the pool can set the trader registry
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The registry of the traders allowed to swap, every trader is allowed when omitted
+    pub trader_registry: Option<Account<'info, TraderRegistry>>,
+}
+
+pub fn update_pool_trader_registry(ctx: Context<UpdatePoolTraderRegistry>) -> Result<()> {
+    let trader_registry = ctx
+        .accounts
+        .trader_registry
+        .as_ref()
+        .map_or(Pubkey::default(), |trader_registry| trader_registry.key());
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_trader_registry:{}, new_trader_registry:{}",
+        { pool_state.trader_registry },
+        trader_registry
+    );
+    pool_state.trader_registry = trader_registry;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
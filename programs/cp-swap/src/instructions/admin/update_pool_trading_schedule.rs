@@ -0,0 +1,52 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolTradingSchedule<'info> {
+    /// Only admin or the custom authority of the pool can set the trading schedule
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_trading_schedule(
+    ctx: Context<UpdatePoolTradingSchedule>,
+    trading_start_time: u64,
+    trading_end_time: u64,
+    trading_days: u8,
+    trading_day_open: u32,
+    trading_day_close: u32,
+) -> Result<()> {
+    require!(
+        trading_end_time == 0 || trading_start_time < trading_end_time,
+        ErrorCode::InvalidInput
+    );
+    require_gt!(1u8 << 7, trading_days, ErrorCode::InvalidInput);
+    require!(
+        trading_days == 0
+            || (trading_day_open < trading_day_close
+                && u64::from(trading_day_close) <= SECONDS_PER_DAY),
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, old_trading_start_time:{}, old_trading_end_time:{}, old_trading_days:{}, new_trading_start_time:{}, new_trading_end_time:{}, new_trading_days:{}",
+        { pool_state.trading_start_time },
+        { pool_state.trading_end_time },
+        { pool_state.trading_days },
+        trading_start_time,
+        trading_end_time,
+        trading_days
+    );
+    pool_state.trading_start_time = trading_start_time;
+    pool_state.trading_end_time = trading_end_time;
+    pool_state.trading_days = trading_days;
+    pool_state.trading_day_open = trading_day_open;
+    pool_state.trading_day_close = trading_day_close;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::curve::CurveType;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct UpdatePoolVirtualReserves<'info> {
+    /// Only admin or the custom authority of the pool can set the virtual reserves
+    #[account(constraint = (authority.key() == crate::admin::ID || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+pub fn update_pool_virtual_reserves(
+    ctx: Context<UpdatePoolVirtualReserves>,
+    virtual_reserve_multiplier: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    // virtual reserves offset a constant product curve only
+    if virtual_reserve_multiplier != 0 {
+        require!(
+            pool_state.curve(oracle::block_timestamp())? == CurveType::ConstantProduct,
+            ErrorCode::InvalidCurveType
+        );
+    }
+
+    let (token_0_amount, token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    pool_state.set_virtual_reserve_multiplier(
+        virtual_reserve_multiplier,
+        token_0_amount,
+        token_1_amount,
+    )?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, virtual_reserve_multiplier:{}, virtual_token_0_amount:{}, virtual_token_1_amount:{}",
+        virtual_reserve_multiplier,
+        { pool_state.virtual_token_0_amount },
+        { pool_state.virtual_token_1_amount }
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use crate::curve::calculator::CurveCalculator;
+use crate::error::ErrorCode;
+use crate::instructions::*;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ExecuteSwap<'info> {
+    /// The filler during the auction window, anyone after it
+    pub executor: Signer<'info>,
+
+    /// CHECK: pool vault and request vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the swap is requested in
+    #[account(
+        constraint = pool.pool_state.key() == swap_request.pool_id @ ErrorCode::InvalidInput,
+        constraint = pool.input_vault.mint == swap_request.input_mint @ ErrorCode::InvalidVault,
+        constraint = pool.output_vault.mint == swap_request.output_mint @ ErrorCode::InvalidVault
+    )]
+    pub pool: SwapRoutePool<'info>,
+
+    /// CHECK: owner of the request, receives the rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The request to execute, closed once executed
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        has_one = request_vault @ ErrorCode::InvalidVault,
+        has_one = output_token_account @ ErrorCode::InvalidInput,
+        close = owner
+    )]
+    pub swap_request: Box<Account<'info, SwapRequest>>,
+
+    /// Escrows the input tokens of the request, closed with the request
+    #[account(mut)]
+    pub request_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving the bought tokens
+    #[account(mut)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The filler token account receiving the input tokens of a fill
+    #[account(
+        mut,
+        token::mint = input_token_mint
+    )]
+    pub filler_input_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The filler token account paying the output tokens of a fill
+    #[account(
+        mut,
+        token::mint = output_token_mint,
+        token::authority = executor
+    )]
+    pub filler_output_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of input token
+    #[account(
+        address = swap_request.input_mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(
+        address = swap_request.output_mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn execute_swap<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ExecuteSwap<'info>>,
+    fill_amount_out: Option<u64>,
+) -> Result<()> {
+    let block_timestamp = oracle::block_timestamp();
+    let slot = Clock::get()?.slot;
+    let swap_request = &ctx.accounts.swap_request;
+    let amount_in = swap_request.amount_in;
+    let is_filler = ctx.accounts.executor.key() == swap_request.filler;
+    if swap_request.is_auction_open(slot) {
+        require!(is_filler, ErrorCode::AuctionWindowActive);
+    }
+
+    // The pool output the filler has to improve on, or the swap executes against the pool
+    let hop = ctx.accounts.pool.prepare_hop(
+        &swap_request.owner,
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+        block_timestamp,
+    )?;
+    let input_transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    let actual_amount_in = amount_in.saturating_sub(input_transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+    let result = CurveCalculator::swap_base_input_scaled(
+        hop.curve_type,
+        hop.amount_scale,
+        u128::from(actual_amount_in),
+        hop.curve_input_token_amount,
+        hop.curve_output_token_amount,
+        hop.fee_rates,
+    )?;
+    let pool_amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
+    let pool_output_transfer_fee = get_transfer_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        pool_amount_out,
+    )?;
+    let pool_amount_received = pool_amount_out
+        .checked_sub(pool_output_transfer_fee)
+        .unwrap();
+
+    let auth_bump = ctx.accounts.pool.auth_bump()?;
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]];
+    let amount_received = if let Some(fill_amount_out) = fill_amount_out {
+        require!(is_filler, ErrorCode::InvalidAuthority);
+        require!(
+            ctx.accounts.swap_request.is_auction_open(slot),
+            ErrorCode::AuctionWindowClosed
+        );
+        let (Some(filler_input_token_account), Some(filler_output_token_account)) = (
+            ctx.accounts.filler_input_token_account.as_ref(),
+            ctx.accounts.filler_output_token_account.as_ref(),
+        ) else {
+            return err!(ErrorCode::InvalidInput);
+        };
+        // the whole price improvement over the pool goes to the owner
+        let amount_received = fill_amount_out
+            .checked_sub(get_transfer_fee(
+                &ctx.accounts.output_token_mint.to_account_info(),
+                fill_amount_out,
+            )?)
+            .unwrap();
+        require_gte!(
+            amount_received,
+            pool_amount_received,
+            ErrorCode::AuctionPriceNotImproved
+        );
+        require_gte!(
+            amount_received,
+            ctx.accounts.swap_request.minimum_amount_out,
+            ErrorCode::ExceededSlippage
+        );
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.executor.to_account_info(),
+            filler_output_token_account.to_account_info(),
+            ctx.accounts.output_token_account.to_account_info(),
+            ctx.accounts.output_token_mint.to_account_info(),
+            ctx.accounts.output_token_program.to_account_info(),
+            fill_amount_out,
+            ctx.accounts.output_token_mint.decimals,
+            ctx.remaining_accounts,
+        )?;
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.request_vault.to_account_info(),
+            filler_input_token_account.to_account_info(),
+            ctx.accounts.input_token_mint.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            amount_in,
+            ctx.accounts.input_token_mint.decimals,
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+        amount_received
+    } else {
+        require_gt!(pool_amount_received, 0);
+        require_gte!(
+            pool_amount_received,
+            ctx.accounts.swap_request.minimum_amount_out,
+            ErrorCode::ExceededSlippage
+        );
+        // the request already went through the auction, it is not refused for its size
+        ctx.accounts.pool.settle_hop(
+            &hop,
+            &result,
+            input_transfer_fee,
+            pool_output_transfer_fee,
+            true,
+            block_timestamp,
+        )?;
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.request_vault.to_account_info(),
+            ctx.accounts.pool.input_vault.to_account_info(),
+            ctx.accounts.input_token_mint.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            amount_in,
+            ctx.accounts.input_token_mint.decimals,
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.pool.output_vault.to_account_info(),
+            ctx.accounts.output_token_account.to_account_info(),
+            ctx.accounts.output_token_mint.to_account_info(),
+            ctx.accounts.output_token_program.to_account_info(),
+            pool_amount_out,
+            ctx.accounts.output_token_mint.decimals,
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+        ctx.accounts.pool.check_hop(
+            &hop,
+            &ctx.accounts.input_token_mint.to_account_info(),
+            &ctx.accounts.output_token_mint.to_account_info(),
+        )?;
+        pool_amount_received
+    };
+    ctx.accounts.pool.pool_state.load_mut()?.close_order();
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.input_token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.request_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(SwapRequestExecutedEvent {
+        pool_id: ctx.accounts.swap_request.pool_id,
+        request: ctx.accounts.swap_request.key(),
+        executor: ctx.accounts.executor.key(),
+        filled: fill_amount_out.is_some(),
+        amount_in,
+        amount_out: amount_received,
+        price_improvement: amount_received.checked_sub(pool_amount_received).unwrap(),
+    });
+    Ok(())
+}
@@ -0,0 +1,8 @@
+pub mod request_swap;
+pub use request_swap::*;
+
+pub mod execute_swap;
+pub use execute_swap::*;
+
+pub mod cancel_swap_request;
+pub use cancel_swap_request::*;
@@ -0,0 +1,157 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct RequestSwap<'info> {
+    /// Owner of the input tokens to sell, the only address allowed to cancel the request
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and request vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool running the order-flow auction, counting its open requests
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The mint of the tokens sold
+    #[account(
+        constraint = input_token_mint.key() == pool_state.load()?.token_0_mint || input_token_mint.key() == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the tokens bought
+    #[account(
+        constraint = output_token_mint.key() == pool_state.load()?.token_0_mint || output_token_mint.key() == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput,
+        constraint = output_token_mint.key() != input_token_mint.key() @ ErrorCode::InvalidInput
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner token account paying the input tokens
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving the bought tokens
+    #[account(
+        token::mint = output_token_mint,
+        token::authority = owner
+    )]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores the minimum output and the auction window of the request
+    #[account(
+        init,
+        seeds = [
+            SWAP_REQUEST_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &request_id.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = SwapRequest::LEN
+    )]
+    pub swap_request: Box<Account<'info, SwapRequest>>,
+
+    /// CHECK: Escrows the input tokens of the request, created by contract
+    #[account(
+        mut,
+        seeds = [
+            SWAP_REQUEST_VAULT_SEED.as_bytes(),
+            swap_request.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub request_vault: UncheckedAccount<'info>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// To create the request accounts
+    pub system_program: Program<'info, System>,
+}
+
+pub fn request_swap<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, RequestSwap<'info>>,
+    _request_id: u64,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    require_gt!(amount_in, 0);
+    let (filler, auction_window_slots) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        require!(pool_state.is_auction_enabled(), ErrorCode::NotApproved);
+        (pool_state.auction_filler, pool_state.auction_window_slots)
+    };
+
+    let request_key = ctx.accounts.swap_request.key();
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.request_vault.to_account_info(),
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.input_token_program.to_account_info(),
+        &[
+            SWAP_REQUEST_VAULT_SEED.as_bytes(),
+            request_key.as_ref(),
+            &[ctx.bumps.request_vault][..],
+        ],
+    )?;
+
+    // The request escrows the amount received after the transfer fee
+    let transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.request_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.pool_state.load_mut()?.open_order()?;
+
+    let request_slot = Clock::get()?.slot;
+    let swap_request = &mut ctx.accounts.swap_request;
+    swap_request.bump = ctx.bumps.swap_request;
+    swap_request.pool_id = ctx.accounts.pool_state.key();
+    swap_request.owner = ctx.accounts.owner.key();
+    swap_request.request_vault = ctx.accounts.request_vault.key();
+    swap_request.input_mint = ctx.accounts.input_token_mint.key();
+    swap_request.output_mint = ctx.accounts.output_token_mint.key();
+    swap_request.output_token_account = ctx.accounts.output_token_account.key();
+    swap_request.filler = filler;
+    swap_request.amount_in = actual_amount_in;
+    swap_request.minimum_amount_out = minimum_amount_out;
+    swap_request.request_slot = request_slot;
+    swap_request.auction_end_slot = request_slot.saturating_add(auction_window_slots);
+    emit!(SwapRequestedEvent {
+        pool_id: swap_request.pool_id,
+        request: request_key,
+        owner: swap_request.owner,
+        filler,
+        amount_in: actual_amount_in,
+        minimum_amount_out,
+        auction_end_slot: swap_request.auction_end_slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ClaimFeeRebate<'info> {
+    /// The trader the rebates were earned by
+    pub trader: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores the fee rebate parameters and the protocol fees paying the rebates
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The volume and rebates of the trader in the pool
+    #[account(
+        mut,
+        seeds = [
+            TRADER_STATS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            trader.key().as_ref(),
+        ],
+        bump = trader_stats.bump,
+    )]
+    pub trader_stats: Box<Account<'info, TraderStats>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The address that receives the token_0 rebate
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+    )]
+    pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that receives the token_1 rebate
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+    )]
+    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn claim_fee_rebate<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ClaimFeeRebate<'info>>,
+) -> Result<()> {
+    let amount_0: u64;
+    let amount_1: u64;
+    let auth_bump: u8;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        let trader_stats = &mut ctx.accounts.trader_stats;
+        trader_stats.settle_epoch(
+            Clock::get()?.epoch,
+            pool_state.fee_rebate_volume_threshold,
+            pool_state.fee_rebate_bps,
+        );
+
+        // the rebates not covered by the protocol fees stay claimable
+        amount_0 = trader_stats
+            .rebate_token_0
+            .min(pool_state.protocol_fees_token_0);
+        amount_1 = trader_stats
+            .rebate_token_1
+            .min(pool_state.protocol_fees_token_1);
+        trader_stats.rebate_token_0 -= amount_0;
+        trader_stats.rebate_token_1 -= amount_1;
+        pool_state.protocol_fees_token_0 = pool_state
+            .protocol_fees_token_0
+            .checked_sub(amount_0)
+            .unwrap();
+        pool_state.protocol_fees_token_1 = pool_state
+            .protocol_fees_token_1
+            .checked_sub(amount_1)
+            .unwrap();
+
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+
+        emit!(FeeRebateClaimedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            trader: ctx.accounts.trader.key(),
+            token_0_amount: amount_0,
+            token_1_amount: amount_1,
+            token_0_rebate_remaining: trader_stats.rebate_token_0,
+            token_1_rebate_remaining: trader_stats.rebate_token_1,
+        });
+    }
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.recipient_token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.recipient_token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    )
+    .check(&*ctx.accounts.pool_state.load()?, None, None)?;
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    /// The pool creator or the admin
+    #[account(constraint = (closer.key() == pool_state.load()?.pool_creator || closer.key() == crate::admin::ID) @ ErrorCode::InvalidOwner)]
+    pub closer: Signer<'info>,
+
+    /// CHECK: receives the rent of the closed accounts, the creator paid for them
+    #[account(mut, address = pool_state.load()?.pool_creator)]
+    pub pool_creator: UncheckedAccount<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool to close
+    #[account(mut, close = pool_creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault of token_0, must be empty
+    #[account(
+        mut,
+        address = pool_state.load()?.token_0_vault @ ErrorCode::InvalidVault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault of token_1, must be empty
+    #[account(
+        mut,
+        address = pool_state.load()?.token_1_vault @ ErrorCode::InvalidVault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program of the token_0 vault
+    #[account(address = *token_0_vault.to_account_info().owner)]
+    pub token_0_program: Interface<'info, TokenInterface>,
+
+    /// SPL program of the token_1 vault
+    #[account(address = *token_1_vault.to_account_info().owner)]
+    pub token_1_program: Interface<'info, TokenInterface>,
+
+    /// The program account for the oracle observations of the pool
+    #[account(
+        mut,
+        address = pool_state.load()?.observation_key,
+        close = pool_creator
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The registry of the mint pair, the pool is removed from it when provided
+    #[account(
+        mut,
+        constraint = pool_registry.token_0_mint == pool_state.load()?.token_0_mint && pool_registry.token_1_mint == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput
+    )]
+    pub pool_registry: Option<Box<Account<'info, PoolRegistry>>>,
+
+    /// The stats of the pools of the amm config, updated when provided
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_SEED.as_bytes(),
+            pool_state.load()?.amm_config.as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+}
+
+pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let auth_bump = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        require!(
+            !pool_state.is_flash_loan_active(),
+            ErrorCode::FlashLoanInProgress
+        );
+        // The lp mint cannot be closed, an empty supply and empty vaults leave nothing
+        // of value behind it
+        require_eq!(pool_state.lp_supply, 0, ErrorCode::PoolNotEmpty);
+        require!(
+            !pool_state.is_strategy_borrowed(),
+            ErrorCode::StrategyBorrowOutstanding
+        );
+        pool_state.auth_bump
+    };
+    require_eq!(
+        ctx.accounts.token_0_vault.amount,
+        0,
+        ErrorCode::PoolNotEmpty
+    );
+    require_eq!(
+        ctx.accounts.token_1_vault.amount,
+        0,
+        ErrorCode::PoolNotEmpty
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]];
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_0_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.token_0_vault.to_account_info(),
+            destination: ctx.accounts.pool_creator.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_1_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.token_1_vault.to_account_info(),
+            destination: ctx.accounts.pool_creator.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    if let Some(pool_registry) = ctx.accounts.pool_registry.as_mut() {
+        pool_registry.remove_pool(&pool_id);
+    }
+    if let Some(protocol_stats) = ctx.accounts.protocol_stats.as_mut() {
+        protocol_stats.record_pool_closed();
+    }
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "close pool:{}, closer:{}",
+        pool_id.to_string(),
+        ctx.accounts.closer.key().to_string()
+    );
+    emit!(ClosePoolEvent {
+        pool_id,
+        closer: ctx.accounts.closer.key(),
+        rent_receiver: ctx.accounts.pool_creator.key(),
+    });
+    Ok(())
+}
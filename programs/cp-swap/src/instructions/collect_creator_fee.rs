@@ -0,0 +1,144 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Mint;
+use anchor_spl::token_interface::Token2022;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct CollectCreatorFee<'info> {
+    /// Only the pool creator can collect the creator fees
+    #[account(address = pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores accumulated creator fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The address that receives the collected token_0 creator fees
+    #[account(mut)]
+    pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that receives the collected token_1 creator fees
+    #[account(mut)]
+    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn collect_creator_fee<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CollectCreatorFee<'info>>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Result<()> {
+    let amount_0: u64;
+    let amount_1: u64;
+    let auth_bump: u8;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        amount_0 = amount_0_requested.min(pool_state.creator_fees_token_0);
+        amount_1 = amount_1_requested.min(pool_state.creator_fees_token_1);
+
+        pool_state.creator_fees_token_0 = pool_state
+            .creator_fees_token_0
+            .checked_sub(amount_0)
+            .unwrap();
+        pool_state.creator_fees_token_1 = pool_state
+            .creator_fees_token_1
+            .checked_sub(amount_1)
+            .unwrap();
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+
+        emit!(FeeCollectedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            fee_type: 2,
+            token_0_amount: amount_0,
+            token_1_amount: amount_1,
+            token_0_fees_remaining: pool_state.creator_fees_token_0,
+            token_1_fees_remaining: pool_state.creator_fees_token_1,
+        });
+    }
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.recipient_token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.recipient_token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    )
+    .check(&*ctx.accounts.pool_state.load()?, None, None)?;
+    Ok(())
+}
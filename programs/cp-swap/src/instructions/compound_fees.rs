@@ -0,0 +1,58 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CompoundFees<'info> {
+    /// Pool state stores accumulated protocol fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores the compound rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn compound_fees(ctx: Context<CompoundFees>) -> Result<()> {
+    require_gt!(
+        ctx.accounts.amm_config.protocol_fee_compound_rate,
+        0,
+        ErrorCode::NotApproved
+    );
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+
+    // the fees already sit in the vaults, dropping them from the accumulated protocol
+    // fees makes them part of the liquidity backing the lp supply
+    let (amount_0, amount_1) = ctx.accounts.amm_config.protocol_fees_to_compound(
+        pool_state.protocol_fees_token_0,
+        pool_state.protocol_fees_token_1,
+    );
+    pool_state.protocol_fees_token_0 = pool_state
+        .protocol_fees_token_0
+        .checked_sub(amount_0)
+        .unwrap();
+    pool_state.protocol_fees_token_1 = pool_state
+        .protocol_fees_token_1
+        .checked_sub(amount_1)
+        .unwrap();
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "compound protocol fees, token_0_amount:{}, token_1_amount:{}",
+        amount_0,
+        amount_1
+    );
+    emit!(CompoundFeesEvent {
+        pool_id,
+        lp_supply: pool_state.lp_supply,
+        token_0_amount: amount_0,
+        token_1_amount: amount_1,
+    });
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CompressDepositReceipt<'info> {
+    /// The owner of the deposit receipt, receives its rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: the pool of the deposit receipt, checked by the seeds of the receipt tree
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// The deposit receipt closed into a leaf of the receipt tree
+    #[account(
+        mut,
+        seeds = [
+            DEPOSIT_RECEIPT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = deposit_receipt.bump,
+        close = owner,
+    )]
+    pub deposit_receipt: Box<Account<'info, DepositReceipt>>,
+
+    /// The receipt tree of the pool
+    #[account(
+        mut,
+        seeds = [
+            RECEIPT_TREE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub receipt_tree: AccountLoader<'info, ReceiptTree>,
+}
+
+/// Close the deposit receipt of the owner and append it to the receipt tree of the pool,
+/// its lp tokens keep their lockup once decompressed
+pub fn compress_deposit_receipt(ctx: Context<CompressDepositReceipt>) -> Result<()> {
+    let deposit_receipt = &ctx.accounts.deposit_receipt;
+    require_gt!(deposit_receipt.lp_amount, 0, ErrorCode::InvalidInput);
+    let mut receipt_tree = ctx.accounts.receipt_tree.load_mut()?;
+    let leaf_index = receipt_tree.next_index;
+    receipt_tree.append(deposit_receipt_leaf(
+        &deposit_receipt.pool_id,
+        &deposit_receipt.owner,
+        deposit_receipt.lp_amount,
+        deposit_receipt.last_deposit_time,
+        leaf_index,
+    ))?;
+
+    emit!(DepositReceiptCompressedEvent {
+        pool_id: deposit_receipt.pool_id,
+        owner: deposit_receipt.owner,
+        receipt_tree: ctx.accounts.receipt_tree.key(),
+        leaf_index,
+        lp_amount: deposit_receipt.lp_amount,
+        last_deposit_time: deposit_receipt.last_deposit_time,
+    });
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateReceiptTree<'info> {
+    /// Pays to create the receipt tree account. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool of the compressed receipts
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The receipt tree of the pool
+    #[account(
+        init,
+        seeds = [
+            RECEIPT_TREE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ReceiptTree::LEN
+    )]
+    pub receipt_tree: AccountLoader<'info, ReceiptTree>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_receipt_tree(ctx: Context<CreateReceiptTree>) -> Result<()> {
+    let mut receipt_tree = ctx.accounts.receipt_tree.load_init()?;
+    receipt_tree.initialize(ctx.bumps.receipt_tree, ctx.accounts.pool_state.key());
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DecompressDepositReceipt<'info> {
+    /// The owner of the compressed deposit receipt, pays to create the deposit receipt
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: the pool of the deposit receipt, checked by the seeds of the receipt tree
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// The receipt tree of the pool
+    #[account(
+        mut,
+        seeds = [
+            RECEIPT_TREE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub receipt_tree: AccountLoader<'info, ReceiptTree>,
+
+    /// The deposit receipt of the owner, the compressed receipt is added to the one of the
+    /// deposits since its compression
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSIT_RECEIPT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = DepositReceipt::LEN
+    )]
+    pub deposit_receipt: Box<Account<'info, DepositReceipt>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Restore the compressed deposit receipt at `leaf_index` of the receipt tree, proven by
+/// the `proof` of its leaf against `root`, and empty its leaf
+pub fn decompress_deposit_receipt(
+    ctx: Context<DecompressDepositReceipt>,
+    root: [u8; 32],
+    leaf_index: u64,
+    lp_amount: u64,
+    last_deposit_time: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let owner = ctx.accounts.owner.key();
+    ctx.accounts.receipt_tree.load_mut()?.replace_leaf(
+        root,
+        deposit_receipt_leaf(&pool_id, &owner, lp_amount, last_deposit_time, leaf_index),
+        EMPTY_LEAF,
+        &proof,
+        leaf_index,
+    )?;
+
+    let deposit_receipt = &mut ctx.accounts.deposit_receipt;
+    if !deposit_receipt.is_initialized() {
+        deposit_receipt.initialize(ctx.bumps.deposit_receipt, pool_id, owner);
+    }
+    deposit_receipt.record_decompression(lp_amount, last_deposit_time);
+
+    emit!(DepositReceiptDecompressedEvent {
+        pool_id,
+        owner,
+        receipt_tree: ctx.accounts.receipt_tree.key(),
+        leaf_index,
+        lp_amount,
+    });
+    Ok(())
+}
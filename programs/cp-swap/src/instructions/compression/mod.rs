@@ -0,0 +1,8 @@
+pub mod create_receipt_tree;
+pub use create_receipt_tree::*;
+
+pub mod compress_deposit_receipt;
+pub use compress_deposit_receipt::*;
+
+pub mod decompress_deposit_receipt;
+pub use decompress_deposit_receipt::*;
@@ -0,0 +1,263 @@
+use crate::curve::fees::Fees;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CrankProtocolFees<'info> {
+    /// Anyone can crank the fees, receives the bounty
+    pub caller: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores accumulated protocol and fund fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores the fee owners and the bounty rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The protocol fee recipient token account receiving the token_0 protocol fees
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        token::authority = amm_config.protocol_fee_recipient()
+    )]
+    pub protocol_owner_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The protocol fee recipient token account receiving the token_1 protocol fees
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        token::authority = amm_config.protocol_fee_recipient()
+    )]
+    pub protocol_owner_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The fund fee recipient token account receiving the token_0 fund fees
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        token::authority = amm_config.fund_fee_recipient()
+    )]
+    pub fund_owner_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The fund fee recipient token account receiving the token_1 fund fees
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        token::authority = amm_config.fund_fee_recipient()
+    )]
+    pub fund_owner_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The caller token account receiving the token_0 bounty
+    #[account(
+        mut,
+        token::mint = vault_0_mint
+    )]
+    pub caller_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The caller token account receiving the token_1 bounty
+    #[account(
+        mut,
+        token::mint = vault_1_mint
+    )]
+    pub caller_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Split an accumulated fee into the owner share and the caller bounty
+fn split_bounty(fee: u64, fee_crank_bounty_rate: u64) -> (u64, u64) {
+    let bounty =
+        u64::try_from(Fees::crank_bounty(u128::from(fee), fee_crank_bounty_rate).unwrap()).unwrap();
+    (fee.checked_sub(bounty).unwrap(), bounty)
+}
+
+pub fn crank_protocol_fees<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CrankProtocolFees<'info>>,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let fee_crank_bounty_rate = ctx.accounts.amm_config.fee_crank_bounty_rate;
+    let (protocol_fee_0, protocol_fee_1, fund_fee_0, fund_fee_1, auth_bump) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        require!(
+            !pool_state.is_flash_loan_active(),
+            ErrorCode::FlashLoanInProgress
+        );
+        // the fund fees of buyback pools are left for `execute_buyback`, and those of a
+        // config with an insurance fund for `collect_fund_fee`
+        let (fund_fee_0, fund_fee_1) = if ctx
+            .accounts
+            .amm_config
+            .is_buyback_pool(&pool_state.token_0_mint, &pool_state.token_1_mint)
+            || ctx.accounts.amm_config.is_insurance_fund_enabled()
+        {
+            (0, 0)
+        } else {
+            (pool_state.fund_fees_token_0, pool_state.fund_fees_token_1)
+        };
+        // and the protocol fees of a config with a revenue program for `collect_protocol_fee`
+        let (protocol_fee_0, protocol_fee_1) = if ctx.accounts.amm_config.is_revenue_program_set() {
+            (0, 0)
+        } else {
+            (
+                pool_state.protocol_fees_token_0,
+                pool_state.protocol_fees_token_1,
+            )
+        };
+        let fees = (
+            protocol_fee_0,
+            protocol_fee_1,
+            fund_fee_0,
+            fund_fee_1,
+            pool_state.auth_bump,
+        );
+        pool_state.protocol_fees_token_0 = pool_state
+            .protocol_fees_token_0
+            .checked_sub(protocol_fee_0)
+            .unwrap();
+        pool_state.protocol_fees_token_1 = pool_state
+            .protocol_fees_token_1
+            .checked_sub(protocol_fee_1)
+            .unwrap();
+        pool_state.fund_fees_token_0 = pool_state
+            .fund_fees_token_0
+            .checked_sub(fund_fee_0)
+            .unwrap();
+        pool_state.fund_fees_token_1 = pool_state
+            .fund_fees_token_1
+            .checked_sub(fund_fee_1)
+            .unwrap();
+        pool_state.recent_epoch = Clock::get()?.epoch;
+        fees
+    };
+    let (protocol_amount_0, protocol_bounty_0) =
+        split_bounty(protocol_fee_0, fee_crank_bounty_rate);
+    let (protocol_amount_1, protocol_bounty_1) =
+        split_bounty(protocol_fee_1, fee_crank_bounty_rate);
+    let (fund_amount_0, fund_bounty_0) = split_bounty(fund_fee_0, fee_crank_bounty_rate);
+    let (fund_amount_1, fund_bounty_1) = split_bounty(fund_fee_1, fee_crank_bounty_rate);
+    let bounty_0 = protocol_bounty_0.checked_add(fund_bounty_0).unwrap();
+    let bounty_1 = protocol_bounty_1.checked_add(fund_bounty_1).unwrap();
+
+    let token_0_program =
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    let token_1_program =
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    for (to, amount) in [
+        (&ctx.accounts.protocol_owner_token_0, protocol_amount_0),
+        (&ctx.accounts.fund_owner_token_0, fund_amount_0),
+        (&ctx.accounts.caller_token_0, bounty_0),
+    ] {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_0_vault.to_account_info(),
+            to.to_account_info(),
+            ctx.accounts.vault_0_mint.to_account_info(),
+            token_0_program.clone(),
+            amount,
+            ctx.accounts.vault_0_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+            ctx.remaining_accounts,
+        )?;
+    }
+    for (to, amount) in [
+        (&ctx.accounts.protocol_owner_token_1, protocol_amount_1),
+        (&ctx.accounts.fund_owner_token_1, fund_amount_1),
+        (&ctx.accounts.caller_token_1, bounty_1),
+    ] {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_1_vault.to_account_info(),
+            to.to_account_info(),
+            ctx.accounts.vault_1_mint.to_account_info(),
+            token_1_program.clone(),
+            amount,
+            ctx.accounts.vault_1_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    for (fee_type, token_0_amount, token_1_amount) in [
+        (0, protocol_fee_0, protocol_fee_1),
+        (1, fund_fee_0, fund_fee_1),
+    ] {
+        emit!(FeeCollectedEvent {
+            pool_id,
+            fee_type,
+            token_0_amount,
+            token_1_amount,
+            token_0_fees_remaining: 0,
+            token_1_fees_remaining: 0,
+        });
+    }
+    emit!(CrankProtocolFeesEvent {
+        pool_id,
+        caller: ctx.accounts.caller.key(),
+        protocol_fee_0: protocol_amount_0,
+        protocol_fee_1: protocol_amount_1,
+        fund_fee_0: fund_amount_0,
+        fund_fee_1: fund_amount_1,
+        bounty_0,
+        bounty_1,
+    });
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    )
+    .check(&*ctx.accounts.pool_state.load()?, None, None)?;
+    Ok(())
+}
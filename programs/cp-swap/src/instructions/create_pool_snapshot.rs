@@ -0,0 +1,34 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreatePoolSnapshot<'info> {
+    /// Pays to create the snapshot account. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool of the snapshot
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The snapshot of the pool
+    #[account(
+        init,
+        seeds = [
+            POOL_SNAPSHOT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PoolSnapshot::LEN
+    )]
+    pub pool_snapshot: Box<Account<'info, PoolSnapshot>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_pool_snapshot(ctx: Context<CreatePoolSnapshot>) -> Result<()> {
+    let pool_snapshot = &mut ctx.accounts.pool_snapshot;
+    pool_snapshot.bump = ctx.bumps.pool_snapshot;
+    pool_snapshot.pool_id = ctx.accounts.pool_state.key();
+    Ok(())
+}
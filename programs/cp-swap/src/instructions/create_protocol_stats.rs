@@ -0,0 +1,34 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateProtocolStats<'info> {
+    /// Pays to create the stats account. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The amm config of the pools
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The stats of the pools of the amm config
+    #[account(
+        init,
+        seeds = [
+            PROTOCOL_STATS_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ProtocolStats::LEN
+    )]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_protocol_stats(ctx: Context<CreateProtocolStats>) -> Result<()> {
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.bump = ctx.bumps.protocol_stats;
+    protocol_stats.amm_config = ctx.accounts.amm_config.key();
+    Ok(())
+}
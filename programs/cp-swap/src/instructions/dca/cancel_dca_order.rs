@@ -0,0 +1,97 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::AccountLoad;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CancelDcaOrder<'info> {
+    /// Owner of the order, receives the remaining input tokens and the rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and order vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: the pool the tranches are swapped against, not loaded so orders can still be
+    /// cancelled once the pool is closed. An open pool stops counting the order
+    #[account(mut, address = dca_order.pool_id)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// The order to cancel
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        has_one = order_vault @ ErrorCode::InvalidVault,
+        close = owner
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,
+
+    /// Escrows the input tokens of the order, closed with the order
+    #[account(mut)]
+    pub order_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving the input tokens not swapped yet
+    #[account(
+        mut,
+        token::mint = input_token_mint
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of input token
+    #[account(
+        address = dca_order.input_mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn cancel_dca_order<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CancelDcaOrder<'info>>,
+) -> Result<()> {
+    // a closed pool no longer counts its orders
+    if let Ok(pool_state) =
+        AccountLoad::<PoolState>::try_from(&ctx.accounts.pool_state.to_account_info())
+    {
+        pool_state.load_mut()?.close_order();
+    }
+    let auth_bump = ctx.bumps.authority;
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        ctx.accounts.order_vault.amount,
+        ctx.accounts.input_token_mint.decimals,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.input_token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.order_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "cancel dca order, amount_in_remaining:{}, amount_out_filled:{}, tranches_executed:{}",
+        ctx.accounts.dca_order.amount_in_remaining,
+        ctx.accounts.dca_order.amount_out_filled,
+        ctx.accounts.dca_order.tranches_executed
+    );
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use crate::curve::calculator::CurveCalculator;
+use crate::error::ErrorCode;
+use crate::instructions::*;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ExecuteDcaTranche<'info> {
+    /// Executes the tranche and receives the tip. Can be anyone
+    pub keeper: Signer<'info>,
+
+    /// CHECK: pool vault and order vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the tranches are swapped against
+    #[account(
+        constraint = pool.pool_state.key() == dca_order.pool_id @ ErrorCode::InvalidInput,
+        constraint = pool.input_vault.mint == dca_order.input_mint @ ErrorCode::InvalidVault,
+        constraint = pool.output_vault.mint == dca_order.output_mint @ ErrorCode::InvalidVault
+    )]
+    pub pool: SwapRoutePool<'info>,
+
+    /// The order whose next tranche is executed
+    #[account(
+        mut,
+        has_one = order_vault @ ErrorCode::InvalidVault,
+        has_one = output_token_account @ ErrorCode::InvalidInput
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,
+
+    /// Escrows the input tokens of the order
+    #[account(mut)]
+    pub order_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving the bought tokens
+    #[account(mut)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The keeper token account receiving the tip
+    #[account(
+        mut,
+        token::mint = output_token_mint
+    )]
+    pub keeper_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of input token
+    #[account(
+        address = dca_order.input_mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(
+        address = dca_order.output_mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn execute_dca_tranche<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ExecuteDcaTranche<'info>>,
+) -> Result<()> {
+    let block_timestamp = oracle::block_timestamp();
+    require!(
+        ctx.accounts.dca_order.is_tranche_due(block_timestamp),
+        ErrorCode::DcaTrancheNotDue
+    );
+    let amount_in = ctx.accounts.dca_order.tranche_amount();
+
+    let owner = ctx.accounts.dca_order.owner;
+    let hop = ctx.accounts.pool.prepare_hop(
+        &owner,
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+        block_timestamp,
+    )?;
+    let input_transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(input_transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+    let result = CurveCalculator::swap_base_input_scaled(
+        hop.curve_type,
+        hop.amount_scale,
+        u128::from(actual_amount_in),
+        hop.curve_input_token_amount,
+        hop.curve_output_token_amount,
+        hop.fee_rates,
+    )?;
+    let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
+    let keeper_tip = ctx.accounts.dca_order.keeper_tip(amount_out);
+    let owner_amount_out = amount_out.checked_sub(keeper_tip).unwrap();
+    require_gt!(owner_amount_out, 0);
+    // The minimum price applies to the output the owner receives for the escrowed input
+    require!(
+        ctx.accounts
+            .dca_order
+            .is_min_price_reached(amount_in, owner_amount_out),
+        ErrorCode::DcaMinPriceNotReached
+    );
+    let output_transfer_fee = get_transfer_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        owner_amount_out,
+    )?
+    .checked_add(get_transfer_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        keeper_tip,
+    )?)
+    .unwrap();
+    ctx.accounts.pool.apply_hop(
+        &hop,
+        &result,
+        input_transfer_fee,
+        output_transfer_fee,
+        true,
+        block_timestamp,
+    )?;
+
+    let auth_bump = ctx.accounts.pool.auth_bump()?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.pool.input_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.pool.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_token_program.to_account_info(),
+        owner_amount_out,
+        ctx.accounts.output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.pool.output_vault.to_account_info(),
+        ctx.accounts.keeper_token_account.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_token_program.to_account_info(),
+        keeper_tip,
+        ctx.accounts.output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.pool.check_hop(
+        &hop,
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+    )?;
+
+    let dca_order = &mut ctx.accounts.dca_order;
+    dca_order.record_tranche(amount_in, owner_amount_out, block_timestamp);
+    emit!(DcaTrancheExecutedEvent {
+        pool_id: dca_order.pool_id,
+        order: dca_order.key(),
+        keeper: ctx.accounts.keeper.key(),
+        amount_in,
+        amount_out: owner_amount_out,
+        keeper_tip,
+        amount_in_remaining: dca_order.amount_in_remaining,
+        next_execution_time: dca_order.next_execution_time,
+    });
+    Ok(())
+}
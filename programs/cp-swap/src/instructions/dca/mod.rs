@@ -0,0 +1,8 @@
+pub mod open_dca_order;
+pub use open_dca_order::*;
+
+pub mod execute_dca_tranche;
+pub use execute_dca_tranche::*;
+
+pub mod cancel_dca_order;
+pub use cancel_dca_order::*;
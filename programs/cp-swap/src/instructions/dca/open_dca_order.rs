@@ -0,0 +1,166 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct OpenDcaOrder<'info> {
+    /// Owner of the input tokens to sell, the only address allowed to cancel the order
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and order vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the tranches are swapped against, counting its open orders
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The mint of the tokens sold
+    #[account(
+        constraint = input_token_mint.key() == pool_state.load()?.token_0_mint || input_token_mint.key() == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the tokens bought
+    #[account(
+        constraint = output_token_mint.key() == pool_state.load()?.token_0_mint || output_token_mint.key() == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput,
+        constraint = output_token_mint.key() != input_token_mint.key() @ ErrorCode::InvalidInput
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner token account paying the input tokens
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving the bought tokens
+    #[account(
+        token::mint = output_token_mint,
+        token::authority = owner
+    )]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores the schedule and the execution status of the order
+    #[account(
+        init,
+        seeds = [
+            DCA_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &order_id.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = DcaOrder::LEN
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,
+
+    /// CHECK: Escrows the input tokens of the order, created by contract
+    #[account(
+        mut,
+        seeds = [
+            DCA_ORDER_VAULT_SEED.as_bytes(),
+            dca_order.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub order_vault: UncheckedAccount<'info>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// To create the order accounts
+    pub system_program: Program<'info, System>,
+}
+
+pub fn open_dca_order<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, OpenDcaOrder<'info>>,
+    _order_id: u64,
+    amount_in: u64,
+    amount_per_trade: u64,
+    interval: u64,
+    min_price_x64: u128,
+    keeper_tip_bps: u16,
+) -> Result<()> {
+    require_gt!(amount_in, 0);
+    require_gt!(amount_per_trade, 0, ErrorCode::InvalidInput);
+    require_gt!(interval, 0, ErrorCode::InvalidInput);
+    require_gt!(
+        KEEPER_TIP_BPS_DENOMINATOR,
+        u64::from(keeper_tip_bps),
+        ErrorCode::InvalidInput
+    );
+
+    let order_key = ctx.accounts.dca_order.key();
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.order_vault.to_account_info(),
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.input_token_program.to_account_info(),
+        &[
+            DCA_ORDER_VAULT_SEED.as_bytes(),
+            order_key.as_ref(),
+            &[ctx.bumps.order_vault][..],
+        ],
+    )?;
+
+    // The order escrows the amount received after the transfer fee
+    let transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.pool_state.load_mut()?.open_order()?;
+
+    let dca_order = &mut ctx.accounts.dca_order;
+    dca_order.bump = ctx.bumps.dca_order;
+    dca_order.pool_id = ctx.accounts.pool_state.key();
+    dca_order.owner = ctx.accounts.owner.key();
+    dca_order.order_vault = ctx.accounts.order_vault.key();
+    dca_order.input_mint = ctx.accounts.input_token_mint.key();
+    dca_order.output_mint = ctx.accounts.output_token_mint.key();
+    dca_order.output_token_account = ctx.accounts.output_token_account.key();
+    dca_order.amount_per_trade = amount_per_trade;
+    dca_order.interval = interval;
+    // the first tranche can be executed right away
+    dca_order.next_execution_time = Clock::get()?.unix_timestamp as u64;
+    dca_order.min_price_x64 = min_price_x64;
+    dca_order.keeper_tip_bps = keeper_tip_bps;
+    dca_order.amount_in = actual_amount_in;
+    dca_order.amount_in_remaining = actual_amount_in;
+    dca_order.amount_out_filled = 0;
+    dca_order.tranches_executed = 0;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "open dca order, amount_in:{}, amount_per_trade:{}, interval:{}, min_price_x64:{}, keeper_tip_bps:{}",
+        actual_amount_in,
+        amount_per_trade,
+        interval,
+        min_price_x64,
+        keeper_tip_bps
+    );
+    Ok(())
+}
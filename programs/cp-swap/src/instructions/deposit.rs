@@ -3,13 +3,16 @@ use crate::curve::RoundDirection;
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::token::*;
+use crate::utils::PoolVaults;
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     /// Pays to mint the position
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -24,9 +27,10 @@ pub struct Deposit<'info> {
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
 
-    /// Owner lp token account
-    #[account(mut,  token::authority = owner)]
-    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: owner lp token account, created as the associated token account of the owner
+    /// when it does not exist yet, checked by `init_owner_lp_token_if_needed` otherwise
+    #[account(mut)]
+    pub owner_lp_token: UncheckedAccount<'info>,
 
     /// The payer's token account for token_0
     #[account(
@@ -82,20 +86,97 @@ pub struct Deposit<'info> {
         address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
     ]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Only required to wrap native SOL for a WSOL deposit, to create the deposit receipt or
+    /// the owner lp token account
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Records the deposited lp tokens and their lockup, created by the first deposit passing
+    /// it. Required by the pools with a withdrawal lockup
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSIT_RECEIPT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = DepositReceipt::LEN
+    )]
+    pub deposit_receipt: Option<Box<Account<'info, DepositReceipt>>>,
+
+    /// Only required to create the owner lp token account
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
 }
 
-pub fn deposit(
-    ctx: Context<Deposit>,
+impl<'info> Deposit<'info> {
+    /// Wrap the native SOL paid for a WSOL side of the deposit into the owner token account
+    pub fn wrap_sol(&self, token_0_amount: u64, token_1_amount: u64) -> Result<()> {
+        for (token_account, mint, amount) in [
+            (&self.token_0_account, &self.vault_0_mint, token_0_amount),
+            (&self.token_1_account, &self.vault_1_mint, token_1_amount),
+        ] {
+            if !is_native_mint(&mint.key()) {
+                continue;
+            }
+            let system_program = self
+                .system_program
+                .as_ref()
+                .ok_or(ErrorCode::InvalidInput)?;
+            wrap_native_sol(
+                self.owner.to_account_info(),
+                token_account.to_account_info(),
+                self.token_program.to_account_info(),
+                system_program.to_account_info(),
+                amount,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn deposit<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Deposit<'info>>,
     lp_token_amount: u64,
     maximum_token_0_amount: u64,
     maximum_token_1_amount: u64,
+    wrap_sol: bool,
+    deadline: Option<u64>,
+    price_slippage: Option<PriceSlippage>,
 ) -> Result<()> {
     require_gt!(lp_token_amount, 0);
+    if let Some(deadline) = deadline {
+        require_gte!(
+            deadline,
+            Clock::get()?.unix_timestamp as u64,
+            ErrorCode::DeadlineExceeded
+        );
+    }
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
         return err!(ErrorCode::NotApproved);
     }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    // the liquidity of position mode pools is deposited with `deposit_position`
+    require!(!pool_state.is_position_mode(), ErrorCode::LpTokensDisabled);
+    // the lp tokens deposited without a receipt would never unlock
+    require!(
+        !pool_state.is_lockup_enabled() || ctx.accounts.deposit_receipt.is_some(),
+        ErrorCode::DepositReceiptRequired
+    );
+    pool_state.lock()?;
+    let pool_vaults = PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
     
     // For custom authority pools, only the custom authority can deposit
     if pool_state.is_custom_authority() {
@@ -108,14 +189,16 @@ pub fn deposit(
         ctx.accounts.token_0_vault.amount,
         ctx.accounts.token_1_vault.amount,
     );
+    if let Some(price_slippage) = price_slippage {
+        price_slippage.check(total_token_0_amount, total_token_1_amount)?;
+    }
     let results = CurveCalculator::lp_tokens_to_trading_tokens(
         u128::from(lp_token_amount),
         u128::from(pool_state.lp_supply),
         u128::from(total_token_0_amount),
         u128::from(total_token_1_amount),
         RoundDirection::Ceiling,
-    )
-    .ok_or(ErrorCode::ZeroTradingTokens)?;
+    )?;
     if results.token_0_amount == 0 || results.token_1_amount == 0 {
         return err!(ErrorCode::ZeroTradingTokens);
     }
@@ -162,6 +245,28 @@ pub fn deposit(
         token_1_transfer_fee: transfer_token_1_fee,
         change_type: 0
     });
+    emit!(DepositEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_amount: lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee: transfer_token_0_fee,
+        token_1_transfer_fee: transfer_token_1_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            total_token_0_amount,
+            total_token_1_amount,
+            pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            total_token_0_amount.checked_add(token_0_amount).unwrap(),
+            total_token_1_amount.checked_add(token_1_amount).unwrap(),
+            pool_state.lp_supply.checked_add(lp_token_amount).unwrap(),
+        ),
+    });
 
     if transfer_token_0_amount > maximum_token_0_amount
         || transfer_token_1_amount > maximum_token_1_amount
@@ -169,6 +274,10 @@ pub fn deposit(
         return Err(ErrorCode::ExceededSlippage.into());
     }
 
+    if wrap_sol {
+        ctx.accounts
+            .wrap_sol(transfer_token_0_amount, transfer_token_1_amount)?;
+    }
     transfer_from_user_to_pool_vault(
         ctx.accounts.owner.to_account_info(),
         ctx.accounts.token_0_account.to_account_info(),
@@ -181,6 +290,7 @@ pub fn deposit(
         },
         transfer_token_0_amount,
         ctx.accounts.vault_0_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_user_to_pool_vault(
@@ -195,19 +305,61 @@ pub fn deposit(
         },
         transfer_token_1_amount,
         ctx.accounts.vault_1_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
-    pool_state.lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).unwrap();
+    let lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).unwrap();
+    pool_state.set_lp_supply(lp_supply);
+    if let Some(deposit_receipt) = ctx.accounts.deposit_receipt.as_mut() {
+        if !deposit_receipt.is_initialized() {
+            deposit_receipt.initialize(
+                ctx.bumps.deposit_receipt.unwrap(),
+                pool_id,
+                ctx.accounts.owner.key(),
+            );
+        }
+        deposit_receipt.record_deposit(
+            lp_token_amount,
+            Clock::get()?.unix_timestamp as u64,
+            pool_state.min_lock_duration,
+        );
+    }
 
+    let lp_token_program =
+        if ctx.accounts.lp_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    init_owner_lp_token_if_needed(
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.owner_lp_token.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &lp_token_program,
+        ctx.accounts
+            .system_program
+            .as_ref()
+            .map(|program| program.to_account_info()),
+        ctx.accounts
+            .associated_token_program
+            .as_ref()
+            .map(|program| program.to_account_info()),
+    )?;
     token_mint_to(
         ctx.accounts.authority.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        lp_token_program,
         ctx.accounts.lp_mint.to_account_info(),
         ctx.accounts.owner_lp_token.to_account_info(),
         lp_token_amount,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
     )?;
     pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        Some(&pool_snapshot),
+    )?;
+    pool_state.unlock();
 
     Ok(())
 }
@@ -0,0 +1,53 @@
+use crate::curve::CurveCalculator;
+use crate::error::ErrorCode;
+use crate::instructions::{deposit, Deposit};
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+
+pub fn deposit_exact_tokens<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Deposit<'info>>,
+    token_0_amount: u64,
+    token_1_amount: u64,
+    minimum_lp_token_amount: u64,
+    wrap_sol: bool,
+    deadline: Option<u64>,
+) -> Result<()> {
+    let lp_token_amount = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        );
+        // the vaults receive the token amounts less their transfer fees
+        let actual_token_0_amount = token_0_amount
+            - get_transfer_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
+        let actual_token_1_amount = token_1_amount
+            - get_transfer_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
+        CurveCalculator::trading_tokens_to_lp_tokens(
+            u128::from(actual_token_0_amount),
+            u128::from(actual_token_1_amount),
+            u128::from(pool_state.lp_supply),
+            u128::from(total_token_0_amount),
+            u128::from(total_token_1_amount),
+        )
+        .and_then(|lp_token_amount| {
+            u64::try_from(lp_token_amount).map_err(|_| error!(ErrorCode::MathOverflow))
+        })?
+    };
+    require_gt!(lp_token_amount, 0, ErrorCode::ZeroTradingTokens);
+    require_gte!(
+        lp_token_amount,
+        minimum_lp_token_amount,
+        ErrorCode::ExceededSlippage
+    );
+    // the token amounts of the lp are at most the given amounts, which bound the transfers
+    deposit(
+        ctx,
+        lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        wrap_sol,
+        deadline,
+        None,
+    )
+}
@@ -0,0 +1,444 @@
+use crate::curve::{CurveCalculator, FeeRates, TradeDirection};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct DepositSingleToken<'info> {
+    /// Pays to mint the position, and to create its lp token account when it does not exist
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: owner lp token account, created as the associated token account of the owner
+    /// when it does not exist yet, checked by `init_owner_lp_token_if_needed` otherwise
+    #[account(mut)]
+    pub owner_lp_token: UncheckedAccount<'info>,
+
+    /// The payer's token account for the deposited token
+    #[account(
+        mut,
+        token::mint = input_vault.mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for the deposited token
+    #[account(
+        mut,
+        constraint = input_vault.key() == pool_state.load()?.token_0_vault || input_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for the other token of the pool
+    #[account(
+        constraint = other_vault.key() == pool_state.load()?.token_0_vault || other_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub other_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for the deposited token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of the deposited token
+    #[account(
+        address = input_vault.mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the other token of the pool
+    #[account(
+        address = other_vault.mint
+    )]
+    pub other_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Lp token mint
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Only required to create the owner lp token account
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Only required to create the owner lp token account
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+pub fn deposit_single_token<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, DepositSingleToken<'info>>,
+    amount_in: u64,
+    minimum_lp_token_amount: u64,
+) -> Result<()> {
+    let block_timestamp = oracle::block_timestamp();
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    // part of the deposit is swapped, so the pool must accept both
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
+        return err!(ErrorCode::NotApproved);
+    }
+    pool_state.check_swap_allowed(block_timestamp)?;
+    // the liquidity of position mode pools is deposited with `deposit_position`
+    require!(!pool_state.is_position_mode(), ErrorCode::LpTokensDisabled);
+    pool_state.check_deposit_without_receipt()?;
+    pool_state.check_derived_swap_allowed(&ctx.accounts.amm_config)?;
+    pool_state.lock()?;
+    // the single token math runs on the real reserves, which boosted pools do not trade on
+    require!(!pool_state.is_boosted(), ErrorCode::NotApproved);
+
+    // For custom authority pools, only the custom authority can deposit
+    if pool_state.is_custom_authority() {
+        require!(
+            ctx.accounts.owner.key() == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+
+    // the mints raising their transfer fee beyond the cap of the config stop trading
+    for mint in [
+        &ctx.accounts.input_token_mint,
+        &ctx.accounts.other_token_mint,
+    ] {
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(get_transfer_fee_bps(&mint.to_account_info())?)?;
+    }
+
+    let transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+    let input_is_token_0 = if ctx.accounts.input_vault.key() == pool_state.token_0_vault
+        && ctx.accounts.other_vault.key() == pool_state.token_1_vault
+    {
+        true
+    } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
+        && ctx.accounts.other_vault.key() == pool_state.token_0_vault
+    {
+        false
+    } else {
+        return err!(ErrorCode::InvalidVault);
+    };
+    let pool_vaults = PoolVaults::from_trade(
+        input_is_token_0,
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.other_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.other_token_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+    let trade_direction = if input_is_token_0 {
+        TradeDirection::ZeroForOne
+    } else {
+        TradeDirection::OneForZero
+    };
+    pool_state.check_launch_trade(trade_direction, false)?;
+    let (vault_0_amount, vault_1_amount) = if input_is_token_0 {
+        (
+            ctx.accounts.input_vault.amount,
+            ctx.accounts.other_vault.amount,
+        )
+    } else {
+        (
+            ctx.accounts.other_vault.amount,
+            ctx.accounts.input_vault.amount,
+        )
+    };
+    let (total_token_0_amount, total_token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    let (token_0_price_x64, token_1_price_x64) =
+        pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+    let (total_input_token_amount, total_other_token_amount) = if input_is_token_0 {
+        (total_token_0_amount, total_token_1_amount)
+    } else {
+        (total_token_1_amount, total_token_0_amount)
+    };
+
+    let trade_fee_rate = {
+        let (observation_state, observation_extension, _) =
+            ctx.accounts.observation_state.load_observations()?;
+        ctx.accounts.amm_config.swap_trade_fee_rate(
+            pool_state.trade_fee_rate_override(block_timestamp),
+            &observation_state,
+            &observation_extension,
+            block_timestamp,
+            token_0_price_x64,
+        )
+    };
+    let curve_type = pool_state.curve(block_timestamp)?.oriented(trade_direction);
+    // The single token lp share is priced on the raw amounts, which a StableSwap pool
+    // only pegs when both tokens have the same UI amount multiplier
+    let amount_scale = get_amount_scale(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.other_token_mint.to_account_info(),
+    )?;
+    require!(amount_scale.is_neutral(curve_type), ErrorCode::NotApproved);
+    let constant_before = CurveCalculator::invariant(
+        curve_type,
+        u128::from(total_input_token_amount),
+        u128::from(total_other_token_amount),
+    )
+    .unwrap();
+
+    let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&ctx.accounts.amm_config);
+    let result = CurveCalculator::deposit_single_token(
+        curve_type,
+        u128::from(actual_amount_in),
+        u128::from(total_input_token_amount),
+        u128::from(total_other_token_amount),
+        u128::from(pool_state.lp_supply),
+        FeeRates {
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+        },
+    )?;
+    let swap_result = &result.swap_result;
+
+    let constant_after = CurveCalculator::invariant(
+        curve_type,
+        swap_result
+            .new_swap_source_amount
+            .checked_sub(swap_result.trade_fee)
+            .unwrap(),
+        swap_result.new_swap_destination_amount,
+    )
+    .unwrap();
+    require_gte!(
+        constant_after,
+        constant_before,
+        ErrorCode::CurveInvariantViolated
+    );
+    pool_state.check_price_impact(
+        u128::from(total_input_token_amount),
+        u128::from(total_other_token_amount),
+        swap_result.new_swap_source_amount,
+        swap_result.new_swap_destination_amount,
+    )?;
+    pool_state.check_auction_size(u64::try_from(swap_result.source_amount_swapped).unwrap())?;
+    pool_state.check_trade_size(trade_direction, swap_result)?;
+
+    let lp_token_amount = u64::try_from(result.lp_token_amount).unwrap();
+    if lp_token_amount == 0 {
+        return err!(ErrorCode::ZeroTradingTokens);
+    }
+    require_gte!(
+        lp_token_amount,
+        minimum_lp_token_amount,
+        ErrorCode::ExceededSlippage
+    );
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "source_amount_swapped:{}, destination_amount_swapped:{}, source_amount_deposited:{}, lp_token_amount:{}",
+        swap_result.source_amount_swapped,
+        swap_result.destination_amount_swapped,
+        result.source_amount_deposited,
+        lp_token_amount
+    );
+
+    let protocol_fee = u64::try_from(swap_result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(swap_result.fund_fee).unwrap();
+    let source_amount_deposited = u64::try_from(result.source_amount_deposited).unwrap();
+    let destination_amount_swapped = u64::try_from(swap_result.destination_amount_swapped).unwrap();
+    let (token_0_amount, token_1_amount, token_0_transfer_fee, token_1_transfer_fee) =
+        if input_is_token_0 {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+            (
+                source_amount_deposited,
+                destination_amount_swapped,
+                transfer_fee,
+                0,
+            )
+        } else {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+            (
+                destination_amount_swapped,
+                source_amount_deposited,
+                0,
+                transfer_fee,
+            )
+        };
+    let creator_fee = pool_state.accrue_creator_fee(trade_direction, swap_result.trade_fee);
+    pool_state.record_swap(trade_direction, swap_result, creator_fee, block_timestamp);
+
+    let trade_fee = u64::try_from(swap_result.trade_fee).unwrap();
+    let reserves_before = PoolReserves::new(
+        total_token_0_amount,
+        total_token_1_amount,
+        pool_state.lp_supply,
+    );
+    // the whole deposit stays in the input vault
+    let reserves_after = PoolReserves::from_trade_amounts(
+        trade_direction,
+        total_input_token_amount
+            .checked_add(actual_amount_in)
+            .unwrap()
+            .checked_sub(protocol_fee + fund_fee + creator_fee)
+            .unwrap(),
+        total_other_token_amount,
+        pool_state.lp_supply.checked_add(lp_token_amount).unwrap(),
+    );
+    pool_state.check_price_band(&reserves_before, &reserves_after, block_timestamp)?;
+    emit!(SwapEvent {
+        pool_id,
+        input_vault_before: total_input_token_amount,
+        output_vault_before: total_other_token_amount,
+        input_amount: u64::try_from(swap_result.source_amount_swapped).unwrap(),
+        output_amount: destination_amount_swapped,
+        input_transfer_fee: 0,
+        output_transfer_fee: 0,
+        base_input: true,
+        trade_fee,
+        protocol_fee,
+        fund_fee,
+        referral_fee: 0,
+        creator_fee,
+        reserves_before,
+        reserves_after: reserves_before.after_swap(trade_direction, swap_result, creator_fee),
+    });
+    emit!(LpChangeEvent {
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        change_type: 0
+    });
+    emit!(DepositEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_amount: lp_token_amount,
+        token_0_amount: if input_is_token_0 {
+            actual_amount_in
+        } else {
+            0
+        },
+        token_1_amount: if input_is_token_0 {
+            0
+        } else {
+            actual_amount_in
+        },
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        trade_fee,
+        protocol_fee,
+        fund_fee,
+        reserves_before,
+        reserves_after,
+    });
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    pool_state.lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).unwrap();
+
+    init_owner_lp_token_if_needed(
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.owner_lp_token.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        ctx.accounts
+            .system_program
+            .as_ref()
+            .map(|program| program.to_account_info()),
+        ctx.accounts
+            .associated_token_program
+            .as_ref()
+            .map(|program| program.to_account_info()),
+    )?;
+    token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        lp_token_amount,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )?;
+
+    // update the previous price to the observation
+    let (mut observation_state, mut observation_extension, mut log_price_observations) =
+        ctx.accounts.observation_state.load_observations_mut()?;
+    if let Some(deviation_bps) = pool_state.update_circuit_breaker(
+        &observation_state,
+        &observation_extension,
+        block_timestamp,
+        token_0_price_x64,
+        token_1_price_x64,
+        &reserves_after,
+    ) {
+        emit!(CircuitBreakerTrippedEvent {
+            pool_id,
+            deviation_bps,
+            threshold_bps: pool_state.circuit_breaker_threshold_bps,
+            window: pool_state.circuit_breaker_window,
+        });
+    }
+    observation_state.update(
+        &mut observation_extension,
+        &mut log_price_observations,
+        block_timestamp,
+        token_0_price_x64,
+        token_1_price_x64,
+        ctx.accounts.amm_config.observation_update_duration(),
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        Some(&pool_snapshot),
+    )?;
+    pool_state.unlock();
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct EnableLogPriceObservations<'info> {
+    /// Pays the rent of the appended log prices. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The observation account to record the log prices of
+    #[account(mut)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn enable_log_price_observations(ctx: Context<EnableLogPriceObservations>) -> Result<()> {
+    let (cardinality, log_price_since) = {
+        let (observation_state, observation_extension, _) =
+            ctx.accounts.observation_state.load_observations()?;
+        require!(
+            !observation_state.is_log_price_recorded(),
+            ErrorCode::InvalidInput
+        );
+        // the log prices are recorded from the most recent observation, their zeroed
+        // cumulative log price is the starting point
+        let log_price_since = observation_state
+            .last_observation_timestamp(&observation_extension)
+            .unwrap_or_else(oracle::block_timestamp);
+        (
+            ObservationState::cardinality(&observation_extension),
+            log_price_since,
+        )
+    };
+
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let new_len = ObservationState::account_len(cardinality, true);
+    // the account data can only grow a limited size per instruction
+    require_gte!(
+        observation_info.data_len() + MAX_PERMITTED_DATA_INCREASE,
+        new_len,
+        ErrorCode::InvalidObservationCardinality
+    );
+
+    let required_lamports = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(observation_info.lamports());
+    if required_lamports > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: observation_info.clone(),
+        };
+        let cpi_context =
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_context, required_lamports)?;
+    }
+    observation_info.realloc(new_len, true)?;
+    ctx.accounts.observation_state.load_mut()?.log_price_since = log_price_since;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "log price observations, cardinality:{}, since:{}",
+        cardinality,
+        log_price_since
+    );
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use crate::curve::calculator::CurveCalculator;
+use crate::curve::TradeDirection;
+use crate::error::ErrorCode;
+use crate::instructions::*;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ExecuteBuyback<'info> {
+    /// Anyone can execute the buyback
+    pub payer: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool spending its fund fees, the input vault holds the fund fees swapped and
+    /// the output vault holds the buyback token
+    #[account(
+        constraint = pool.output_vault.mint == buyback_mint.key() @ ErrorCode::InvalidVault
+    )]
+    pub pool: SwapRoutePool<'info>,
+
+    /// The mint of the fund fees token swapped
+    #[account(address = pool.input_vault.mint)]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the buyback token, its supply is reduced by the burn
+    #[account(
+        mut,
+        address = pool.amm_config.buyback_mint @ ErrorCode::InvalidInput
+    )]
+    pub buyback_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program of the buyback token
+    #[account(address = *buyback_mint.to_account_info().owner)]
+    pub buyback_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn execute_buyback<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ExecuteBuyback<'info>>,
+) -> Result<()> {
+    let amm_config = &ctx.accounts.pool.amm_config;
+    require!(
+        amm_config.is_buyback_enabled() && amm_config.buyback_max_deviation_bps != 0,
+        ErrorCode::NotApproved
+    );
+    let block_timestamp = oracle::block_timestamp();
+    let epoch = Clock::get()?.epoch;
+    let hop = ctx.accounts.pool.prepare_hop(
+        &ctx.accounts.payer.key(),
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.buyback_mint.to_account_info(),
+        block_timestamp,
+    )?;
+
+    // The fund fees already sit in the input vault, so swapping them only moves them from
+    // the fees to the reserves, while the bought tokens are burned from the output vault
+    let (amount_in, buyback_fund_fees) = {
+        let pool_state = ctx.accounts.pool.pool_state.load()?;
+        let (input_fund_fees, output_fund_fees) = match hop.trade_direction {
+            TradeDirection::ZeroForOne => {
+                (pool_state.fund_fees_token_0, pool_state.fund_fees_token_1)
+            }
+            TradeDirection::OneForZero => {
+                (pool_state.fund_fees_token_1, pool_state.fund_fees_token_0)
+            }
+        };
+        (
+            pool_state.buyback_amount(
+                input_fund_fees,
+                ctx.accounts.pool.amm_config.buyback_epoch_cap,
+                epoch,
+            ),
+            output_fund_fees,
+        )
+    };
+    let amount_out = if amount_in > 0 {
+        let result = CurveCalculator::swap_base_input_scaled(
+            hop.curve_type,
+            hop.amount_scale,
+            u128::from(amount_in),
+            hop.curve_input_token_amount,
+            hop.curve_output_token_amount,
+            hop.fee_rates,
+        )?;
+        // Anyone can move the pool price right before a buyback, so the buyback token must
+        // not cost more than the TWAP of the pool allows
+        let twap = {
+            let (observation_state, observation_extension, _) =
+                ctx.accounts.pool.observation_state.load_observations()?;
+            observation_state
+                .twap(
+                    &observation_extension,
+                    block_timestamp,
+                    u64::from(amm_config.buyback_twap_window),
+                    hop.token_0_price_x64,
+                    hop.token_1_price_x64,
+                )
+                .ok_or(ErrorCode::ObservationNotEnough)?
+        };
+        let twap_price_x32 = match hop.trade_direction {
+            TradeDirection::ZeroForOne => twap.token_0_price_x32,
+            TradeDirection::OneForZero => twap.token_1_price_x32,
+        };
+        require_gte!(
+            result.destination_amount_swapped,
+            u128::from(amm_config.buyback_minimum_amount_out(amount_in, twap_price_x32)),
+            ErrorCode::BuybackPriceDeviation
+        );
+        ctx.accounts
+            .pool
+            .apply_hop(&hop, &result, 0, 0, true, block_timestamp)?;
+        u64::try_from(result.destination_amount_swapped).unwrap()
+    } else {
+        0
+    };
+    // The fund fees of the buyback token are burned as they are
+    let amount_burned = amount_out.checked_add(buyback_fund_fees).unwrap();
+    require_gt!(amount_burned, 0, ErrorCode::BuybackAmountZero);
+
+    let pool_id = ctx.accounts.pool.pool_state.key();
+    let auth_bump = {
+        let mut pool_state = ctx.accounts.pool.pool_state.load_mut()?;
+        match hop.trade_direction {
+            TradeDirection::ZeroForOne => {
+                pool_state.fund_fees_token_0 =
+                    pool_state.fund_fees_token_0.checked_sub(amount_in).unwrap();
+                pool_state.fund_fees_token_1 = pool_state
+                    .fund_fees_token_1
+                    .checked_sub(buyback_fund_fees)
+                    .unwrap();
+            }
+            TradeDirection::OneForZero => {
+                pool_state.fund_fees_token_1 =
+                    pool_state.fund_fees_token_1.checked_sub(amount_in).unwrap();
+                pool_state.fund_fees_token_0 = pool_state
+                    .fund_fees_token_0
+                    .checked_sub(buyback_fund_fees)
+                    .unwrap();
+            }
+        };
+        pool_state.record_buyback(amount_in, epoch);
+        pool_state.recent_epoch = epoch;
+        emit!(BuybackEvent {
+            pool_id,
+            buyback_mint: ctx.accounts.buyback_mint.key(),
+            epoch,
+            amount_in,
+            amount_burned,
+            epoch_amount: pool_state.buyback_epoch_amount,
+        });
+        pool_state.auth_bump
+    };
+
+    token_burn(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.buyback_token_program.to_account_info(),
+        ctx.accounts.buyback_mint.to_account_info(),
+        ctx.accounts.pool.output_vault.to_account_info(),
+        amount_burned,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+    ctx.accounts.pool.check_hop(
+        &hop,
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.buyback_mint.to_account_info(),
+    )?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "buyback, amount_in:{}, amount_out:{}, amount_burned:{}",
+        amount_in,
+        amount_out,
+        amount_burned
+    );
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use crate::curve::fees::Fees;
+use crate::error::ErrorCode;
+use crate::instructions::REPAY_FLASH_LOAN_POOL_STATE_INDEX;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    /// The borrower of the vault tokens
+    pub borrower: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read the flash loan fee
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Pool state account
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The token account for receive token_0
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account for receive token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: instructions sysvar, used to find the repay instruction
+    #[account(
+        address = anchor_lang::solana_program::sysvar::instructions::ID
+    )]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+pub fn flash_loan<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, FlashLoan<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+) -> Result<()> {
+    require!(amount_0 > 0 || amount_1 > 0, ErrorCode::InvalidInput);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::FlashLoan) {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    pool_state.lock()?;
+
+    // For custom authority pools, only the custom authority can borrow
+    if pool_state.is_custom_authority() {
+        require!(
+            ctx.accounts.borrower.key() == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+
+    // The loan must be taken by a top level instruction and repaid later in the same transaction
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)? as usize;
+    let current_instruction = load_instruction_at_checked(current_index, &instructions)?;
+    require_keys_eq!(
+        current_instruction.program_id,
+        crate::id(),
+        ErrorCode::InvalidFlashLoan
+    );
+    let mut index = current_index + 1;
+    loop {
+        let instruction = load_instruction_at_checked(index, &instructions)
+            .map_err(|_| error!(ErrorCode::InvalidFlashLoan))?;
+        if instruction.program_id == crate::id()
+            && instruction
+                .data
+                .starts_with(crate::instruction::RepayFlashLoan::DISCRIMINATOR)
+            && instruction
+                .accounts
+                .get(REPAY_FLASH_LOAN_POOL_STATE_INDEX)
+                .map(|meta| meta.pubkey)
+                == Some(pool_id)
+        {
+            break;
+        }
+        index += 1;
+    }
+
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    require_gte!(total_token_0_amount, amount_0);
+    require_gte!(total_token_1_amount, amount_1);
+
+    let flash_loan_fee_rate = ctx.accounts.amm_config.flash_loan_fee_rate;
+    let token_0_fee = u64::try_from(Fees::flash_loan_fee(
+        u128::from(amount_0),
+        flash_loan_fee_rate,
+    )?)
+    .map_err(|_| error!(ErrorCode::MathOverflow))?;
+    let token_1_fee = u64::try_from(Fees::flash_loan_fee(
+        u128::from(amount_1),
+        flash_loan_fee_rate,
+    )?)
+    .map_err(|_| error!(ErrorCode::MathOverflow))?;
+    pool_state.flash_loan_amount_0 = amount_0
+        .checked_add(token_0_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool_state.flash_loan_amount_1 = amount_1
+        .checked_add(token_1_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(FlashLoanEvent {
+        pool_id,
+        borrower: ctx.accounts.borrower.key(),
+        token_0_amount: amount_0,
+        token_1_amount: amount_1,
+        token_0_fee,
+        token_1_fee,
+    });
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_state.unlock();
+
+    Ok(())
+}
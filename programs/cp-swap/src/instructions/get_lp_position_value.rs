@@ -0,0 +1,51 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct GetLpPositionValue<'info> {
+    /// Pool state account
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+pub fn get_lp_position_value(
+    ctx: Context<GetLpPositionValue>,
+    lp_amount: u64,
+) -> Result<LpPositionValue> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    // the vault balances do not hold the whole liquidity while tokens are lent out
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    require!(!pool_state.is_locked(), ErrorCode::ReentrancyDetected);
+    let value = pool_state
+        .lp_position_value(
+            lp_amount,
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        )
+        .ok_or(ErrorCode::InvalidInput)?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "lp_amount:{}, lp_supply:{}, token_0_amount:{}, token_1_amount:{}",
+        value.lp_amount,
+        value.lp_supply,
+        value.token_0_amount,
+        value.token_1_amount
+    );
+    Ok(value)
+}
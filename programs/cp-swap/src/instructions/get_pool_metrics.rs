@@ -0,0 +1,22 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetPoolMetrics<'info> {
+    /// Pool state account
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn get_pool_metrics(ctx: Context<GetPoolMetrics>) -> Result<PoolMetrics> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let metrics = pool_state.metrics(oracle::block_timestamp());
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "fee_growth_0_x64:{}, fee_growth_1_x64:{}, bucket_volume_token_0:{}, bucket_volume_token_1:{}",
+        metrics.fee_growth_0_x64,
+        metrics.fee_growth_1_x64,
+        metrics.bucket_volume_token_0,
+        metrics.bucket_volume_token_1
+    );
+    Ok(metrics)
+}
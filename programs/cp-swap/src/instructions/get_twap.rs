@@ -0,0 +1,86 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    /// Pool state account
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the oracle observations of the pool
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn get_twap(ctx: Context<GetTwap>, seconds_ago: u64) -> Result<TwapPrice> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (token_0_price_x32, token_1_price_x32) = pool_state.token_price_x32(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+        block_timestamp,
+    );
+    let (observation_state, observation_extension, _) =
+        ctx.accounts.observation_state.load_observations()?;
+    let twap = observation_state
+        .twap(
+            &observation_extension,
+            block_timestamp,
+            seconds_ago,
+            token_0_price_x32,
+            token_1_price_x32,
+        )
+        .ok_or(ErrorCode::ObservationNotEnough)?;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "seconds_ago:{}, token_0_price_x32:{}, token_1_price_x32:{}",
+        seconds_ago,
+        twap.token_0_price_x32,
+        twap.token_1_price_x32
+    );
+    Ok(twap)
+}
+
+pub fn get_geometric_twap(ctx: Context<GetTwap>, seconds_ago: u64) -> Result<TwapPrice> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (token_0_price_x32, _) = pool_state.token_price_x32(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+        block_timestamp,
+    );
+    let (observation_state, observation_extension, log_price_observations) =
+        ctx.accounts.observation_state.load_observations()?;
+    let twap = observation_state
+        .geometric_twap(
+            &observation_extension,
+            &log_price_observations,
+            block_timestamp,
+            seconds_ago,
+            token_0_price_x32,
+        )
+        .ok_or(ErrorCode::ObservationNotEnough)?;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "seconds_ago:{}, geometric token_0_price_x32:{}, token_1_price_x32:{}",
+        seconds_ago,
+        twap.token_0_price_x32,
+        twap.token_1_price_x32
+    );
+    Ok(twap)
+}
@@ -0,0 +1,290 @@
+use crate::curve::{CurveCalculator, RoundDirection};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct HandleFrozenVault<'info> {
+    /// Owner of the lp tokens to burn
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Owner lp token account
+    #[account(mut, token::authority = owner)]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the token of the vault not frozen
+    #[account(
+        mut,
+        token::mint = output_vault.mint,
+    )]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault of the pool not frozen
+    #[account(
+        mut,
+        constraint = output_vault.key() == pool_state.load()?.token_0_vault || output_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault of the pool frozen by the freeze authority of its mint, the share of the
+    /// owner stays in it
+    #[account(
+        constraint = frozen_vault.key() == pool_state.load()?.token_0_vault || frozen_vault.key() == pool_state.load()?.token_1_vault,
+        constraint = frozen_vault.is_frozen() @ ErrorCode::VaultNotFrozen
+    )]
+    pub frozen_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for the output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of the output token
+    #[account(
+        address = output_vault.mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the frozen vault
+    #[account(
+        address = frozen_vault.mint
+    )]
+    pub frozen_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Lp token mint
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The deposit receipt of the owner, its unlocked lp tokens are withdrawn without the
+    /// early exit fee of a pool with a withdrawal lockup
+    #[account(
+        mut,
+        seeds = [
+            DEPOSIT_RECEIPT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = deposit_receipt.bump,
+    )]
+    pub deposit_receipt: Option<Box<Account<'info, DepositReceipt>>>,
+}
+
+/// Burn `lp_token_amount` lp tokens for their share of the vault not frozen while the other
+/// vault of the pool is frozen, which stops its swaps and withdrawals. The share of the
+/// frozen vault is given up to the remaining liquidity providers
+pub fn handle_frozen_vault<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, HandleFrozenVault<'info>>,
+    lp_token_amount: u64,
+    minimum_out: u64,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    require_gt!(ctx.accounts.lp_mint.supply, 0);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    pool_state.lock()?;
+
+    // For custom authority pools, only the custom authority can withdraw
+    if pool_state.is_custom_authority() {
+        require!(
+            ctx.accounts.owner.key() == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+
+    let output_is_token_0 = if ctx.accounts.output_vault.key() == pool_state.token_0_vault
+        && ctx.accounts.frozen_vault.key() == pool_state.token_1_vault
+    {
+        true
+    } else if ctx.accounts.output_vault.key() == pool_state.token_1_vault
+        && ctx.accounts.frozen_vault.key() == pool_state.token_0_vault
+    {
+        false
+    } else {
+        return err!(ErrorCode::InvalidVault);
+    };
+    let pool_vaults = PoolVaults::from_trade(
+        output_is_token_0,
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.frozen_vault.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.frozen_token_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+    let (vault_0_amount, vault_1_amount) = if output_is_token_0 {
+        (
+            ctx.accounts.output_vault.amount,
+            ctx.accounts.frozen_vault.amount,
+        )
+    } else {
+        (
+            ctx.accounts.frozen_vault.amount,
+            ctx.accounts.output_vault.amount,
+        )
+    };
+    let (total_token_0_amount, total_token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Floor,
+    )?;
+    let (total_output_token_amount, output_amount, forfeited_amount) = if output_is_token_0 {
+        (
+            total_token_0_amount,
+            results.token_0_amount,
+            results.token_1_amount,
+        )
+    } else {
+        (
+            total_token_1_amount,
+            results.token_1_amount,
+            results.token_0_amount,
+        )
+    };
+    let output_amount = std::cmp::min(
+        total_output_token_amount,
+        u64::try_from(output_amount).unwrap(),
+    );
+    require_gt!(output_amount, 0, ErrorCode::ZeroTradingTokens);
+
+    // the lp tokens not unlocked by the deposit receipt pay the early exit fee, as `withdraw`
+    let early_exit_lp_amount = match ctx.accounts.deposit_receipt.as_mut() {
+        Some(deposit_receipt) => {
+            let unlocked_lp_amount = deposit_receipt.unlocked_lp_amount(
+                Clock::get()?.unix_timestamp as u64,
+                pool_state.min_lock_duration,
+            );
+            deposit_receipt.record_withdrawal(lp_token_amount);
+            lp_token_amount.saturating_sub(unlocked_lp_amount)
+        }
+        None => lp_token_amount,
+    };
+    let early_exit_fee =
+        pool_state.early_exit_fee(output_amount, early_exit_lp_amount, lp_token_amount);
+    let output_amount = output_amount - early_exit_fee;
+    let output_transfer_fee = get_transfer_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        output_amount,
+    )?;
+    let receive_amount = output_amount.checked_sub(output_transfer_fee).unwrap();
+    require_gte!(receive_amount, minimum_out, ErrorCode::ExceededSlippage);
+
+    let lp_supply = pool_state
+        .lp_supply
+        .checked_sub(lp_token_amount)
+        .ok_or(ErrorCode::LpSupplyUnderflow)?;
+    let (token_0_amount, token_1_amount, token_0_transfer_fee, token_1_transfer_fee) =
+        if output_is_token_0 {
+            (output_amount, 0, output_transfer_fee, 0)
+        } else {
+            (0, output_amount, 0, output_transfer_fee)
+        };
+    emit!(WithdrawEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_amount: lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            total_token_0_amount,
+            total_token_1_amount,
+            pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            total_token_0_amount - token_0_amount,
+            total_token_1_amount - token_1_amount,
+            lp_supply,
+        ),
+    });
+    emit!(FrozenVaultWithdrawEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        frozen_vault: ctx.accounts.frozen_vault.key(),
+        lp_amount: lp_token_amount,
+        output_amount,
+        forfeited_amount: u64::try_from(forfeited_amount).unwrap(),
+    });
+    if early_exit_fee != 0 {
+        let (token_0_fee, token_1_fee) = if output_is_token_0 {
+            (early_exit_fee, 0)
+        } else {
+            (0, early_exit_fee)
+        };
+        emit!(EarlyExitFeeEvent {
+            pool_id,
+            owner: ctx.accounts.owner.key(),
+            early_exit_lp_amount,
+            token_0_fee,
+            token_1_fee,
+        });
+    }
+
+    pool_state.set_lp_supply(lp_supply);
+    token_burn(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        lp_token_amount,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )?;
+    // the reserves lent to the strategy are not in the vaults to pay the withdrawal
+    if output_is_token_0 {
+        pool_state.check_vault_outflow(vault_0_amount, vault_1_amount, output_amount, 0)?;
+    } else {
+        pool_state.check_vault_outflow(vault_0_amount, vault_1_amount, 0, output_amount)?;
+    }
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_token_program.to_account_info(),
+        output_amount,
+        ctx.accounts.output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        Some(&pool_snapshot),
+    )?;
+    pool_state.unlock();
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct HarvestWithheldFees<'info> {
+    /// The withdraw withheld authority of the mint, anyone when it is the pool vault authority
+    pub payer: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Amm config account stores where the harvested fees are credited
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Pool state stores accumulated protocol fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault withholding the transfer fees
+    #[account(
+        mut,
+        constraint = vault.key() == pool_state.load()?.token_0_vault || vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The transfer fee mint of the vault
+    #[account(
+        mut,
+        address = vault.mint
+    )]
+    pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The SPL program 2022 to withdraw the withheld fees
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn harvest_withheld_fees(ctx: Context<HarvestWithheldFees>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let withdraw_withheld_authority =
+        get_withdraw_withheld_authority(&ctx.accounts.vault_mint.to_account_info())?
+            .ok_or(ErrorCode::InvalidInput)?;
+    {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        require!(
+            !pool_state.is_flash_loan_active(),
+            ErrorCode::FlashLoanInProgress
+        );
+    }
+
+    let amount_before = ctx.accounts.vault.amount;
+    // the withheld fees are only withdrawn by the authority of the mint, which signs the
+    // harvest unless it delegated it to the pool vault authority
+    let authority = if withdraw_withheld_authority == ctx.accounts.authority.key() {
+        ctx.accounts.authority.to_account_info()
+    } else {
+        require_keys_eq!(
+            withdraw_withheld_authority,
+            ctx.accounts.payer.key(),
+            ErrorCode::InvalidOwner
+        );
+        ctx.accounts.payer.to_account_info()
+    };
+    withdraw_withheld_tokens_from_vault(
+        authority,
+        ctx.accounts.vault_mint.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.token_program_2022.to_account_info(),
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+    ctx.accounts.vault.reload()?;
+    let amount = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_sub(amount_before)
+        .unwrap();
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // the harvested fees left in the vault are part of the reserves backing the lp supply
+    let to_protocol = ctx.accounts.amm_config.withheld_fees_to_protocol;
+    if to_protocol {
+        if ctx.accounts.vault.key() == pool_state.token_0_vault {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(amount)
+                .unwrap();
+        } else {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(amount)
+                .unwrap();
+        }
+    }
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "harvest withheld fees, amount:{}, to_protocol:{}",
+        amount,
+        to_protocol
+    );
+    emit!(HarvestWithheldFeesEvent {
+        pool_id,
+        mint: ctx.accounts.vault_mint.key(),
+        amount,
+        to_protocol,
+    });
+    Ok(())
+}
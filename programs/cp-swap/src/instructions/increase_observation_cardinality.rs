@@ -0,0 +1,87 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct IncreaseObservationCardinality<'info> {
+    /// Pays the rent of the appended observations. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The observation account to extend
+    #[account(mut)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn increase_observation_cardinality(
+    ctx: Context<IncreaseObservationCardinality>,
+    observation_cardinality: u16,
+) -> Result<()> {
+    let observation_cardinality = usize::from(observation_cardinality);
+    let (current_cardinality, log_prices) = {
+        let (_, observation_extension, log_prices) =
+            ctx.accounts.observation_state.load_observations()?;
+        (
+            ObservationState::cardinality(&observation_extension),
+            !log_prices.is_empty(),
+        )
+    };
+    require_gt!(
+        observation_cardinality,
+        current_cardinality,
+        ErrorCode::InvalidObservationCardinality
+    );
+    require_gte!(
+        OBSERVATION_CARDINALITY_MAX,
+        observation_cardinality,
+        ErrorCode::InvalidObservationCardinality
+    );
+
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let new_len = ObservationState::account_len(observation_cardinality, log_prices);
+    // the account data can only grow a limited size per instruction
+    require_gte!(
+        observation_info.data_len() + MAX_PERMITTED_DATA_INCREASE,
+        new_len,
+        ErrorCode::InvalidObservationCardinality
+    );
+
+    let required_lamports = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(observation_info.lamports());
+    if required_lamports > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: observation_info.clone(),
+        };
+        let cpi_context =
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_context, required_lamports)?;
+    }
+    // the appended observations are zeroed, which are skipped as empty slots until written
+    observation_info.realloc(new_len, true)?;
+    if log_prices {
+        // move the log prices after the appended observations, the log prices of the
+        // appended observations are zeroed by the realloc
+        let mut data = observation_info.try_borrow_mut_data()?;
+        let old_extension_end = ObservationState::account_len(current_cardinality, false);
+        let new_extension_end = ObservationState::account_len(observation_cardinality, false);
+        let log_prices_len = LogPriceObservation::LEN * current_cardinality;
+        data.copy_within(
+            old_extension_end..old_extension_end + log_prices_len,
+            new_extension_end,
+        );
+        data[old_extension_end..new_extension_end].fill(0);
+    }
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "observation cardinality, old:{}, new:{}",
+        current_cardinality,
+        observation_cardinality
+    );
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::curve::CurveCalculator;
+use crate::curve::{CurveCalculator, CurveType};
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::*;
@@ -9,12 +9,13 @@ use anchor_lang::{
     system_program,
 };
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{self, AssociatedToken},
     token::spl_token,
     token::Token,
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{Mint, Token2022, TokenAccount, TokenInterface},
 };
 use spl_token_2022;
+use spl_token_metadata_interface::state::TokenMetadata;
 use std::ops::Deref;
 
 #[derive(Accounts)]
@@ -45,6 +46,8 @@ pub struct Initialize<'info> {
     ///     token_1_mint.key().as_ref(),
     /// ],
     ///
+    /// With the `pool_salt` appended when it is not 0
+    ///
     /// Or random account: must be signed by cli
     #[account(mut)]
     pub pool_state: UncheckedAccount<'info>,
@@ -62,20 +65,17 @@ pub struct Initialize<'info> {
     )]
     pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// pool lp mint
+    /// CHECK: pool lp mint, created by contract under the spl token program, or token
+    /// program 2022 when it has metadata
     #[account(
-        init,
+        mut,
         seeds = [
             POOL_LP_MINT_SEED.as_bytes(),
             pool_state.key().as_ref(),
         ],
         bump,
-        mint::decimals = 9,
-        mint::authority = authority,
-        payer = creator,
-        mint::token_program = token_program,
     )]
-    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub lp_mint: UncheckedAccount<'info>,
 
     /// payer token0 account
     #[account(
@@ -93,15 +93,10 @@ pub struct Initialize<'info> {
     )]
     pub creator_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// creator lp token account
-    #[account(
-        init,
-        associated_token::mint = lp_mint,
-        associated_token::authority = creator,
-        payer = creator,
-        token::token_program = token_program,
-    )]
-    pub creator_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: creator lp token account, the associated token account of the lp mint
+    /// created by contract, the associated token program checks the address
+    #[account(mut)]
+    pub creator_lp_token: UncheckedAccount<'info>,
 
     /// CHECK: Token_0 vault for the pool, created by contract
     #[account(
@@ -130,7 +125,8 @@ pub struct Initialize<'info> {
     /// create pool fee account
     #[account(
         mut,
-        constraint = create_pool_fee.owner == crate::create_pool_fee_reveiver::ID,
+        token::mint = amm_config.create_pool_fee_mint,
+        token::authority = amm_config.create_pool_fee_receiver,
     )]
     pub create_pool_fee: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -143,11 +139,24 @@ pub struct Initialize<'info> {
         ],
         bump,
         payer = creator,
-        space = ObservationState::LEN
+        space = ObservationState::account_len(OBSERVATION_NUM, true)
     )]
     pub observation_state: AccountLoader<'info, ObservationState>,
 
-    /// Program to create mint account and mint tokens
+    /// The registry resolving the lp mint to the pool
+    #[account(
+        init,
+        seeds = [
+            LP_MINT_REGISTRY_SEED.as_bytes(),
+            lp_mint.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = LpMintRegistry::LEN
+    )]
+    pub lp_mint_registry: Box<Account<'info, LpMintRegistry>>,
+
+    /// Program to create the lp mint and mint lp tokens, unless the lp mint has metadata
     pub token_program: Program<'info, Token>,
     /// Spl token program or token program 2022
     pub token_0_program: Interface<'info, TokenInterface>,
@@ -159,18 +168,82 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
     /// Sysvar for program account
     pub rent: Sysvar<'info, Rent>,
+
+    /// The mint of the fee to create a pool, only required when it is not native SOL
+    #[account(address = amm_config.create_pool_fee_mint)]
+    pub create_pool_fee_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// The creator token account paying the fee to create a pool, only required when it is not native SOL
+    #[account(
+        mut,
+        token::mint = amm_config.create_pool_fee_mint,
+        token::authority = creator,
+    )]
+    pub creator_fee_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Spl token program or token program 2022 of the fee mint, only required when it is not native SOL
+    pub create_pool_fee_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// The stats of the pools of the amm config, updated when provided
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+
+    /// The mint policy of the amm config, required when the amm config references one
+    #[account(address = amm_config.mint_policy @ ErrorCode::InvalidInput)]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// Token program 2022, only required to create an lp mint with metadata
+    pub token_program_2022: Option<Program<'info, Token2022>>,
+
+    /// The badge of token_0_mint, required when it has a permanent delegate or a transfer
+    /// hook of an unsupported program
+    #[account(
+        seeds = [
+            TOKEN_BADGE_SEED.as_bytes(),
+            token_0_mint.key().as_ref(),
+        ],
+        bump = token_0_badge.bump,
+    )]
+    pub token_0_badge: Option<Box<Account<'info, TokenBadge>>>,
+
+    /// The badge of token_1_mint, required when it has a permanent delegate or a transfer
+    /// hook of an unsupported program
+    #[account(
+        seeds = [
+            TOKEN_BADGE_SEED.as_bytes(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump = token_1_badge.bump,
+    )]
+    pub token_1_badge: Option<Box<Account<'info, TokenBadge>>>,
 }
 
-pub fn initialize(
-    ctx: Context<Initialize>,
+pub fn initialize<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Initialize<'info>>,
     init_amount_0: u64,
     init_amount_1: u64,
     mut open_time: u64,
     authority_type: u8,
     custom_authority: Option<Pubkey>,
+    curve_type: u8,
+    amp: u64,
+    launch_mode: u8,
+    launch_duration: u64,
+    lp_mint_metadata: Option<LpMintMetadata>,
+    accept_freeze_risk: bool,
+    pool_salt: u8,
 ) -> Result<()> {
-    if !(is_supported_mint(&ctx.accounts.token_0_mint).unwrap()
-        && is_supported_mint(&ctx.accounts.token_1_mint).unwrap())
+    let has_token_0_badge = ctx.accounts.token_0_badge.is_some();
+    let has_token_1_badge = ctx.accounts.token_1_badge.is_some();
+    if !(is_supported_mint(&ctx.accounts.token_0_mint, has_token_0_badge).unwrap()
+        && is_supported_mint(&ctx.accounts.token_1_mint, has_token_1_badge).unwrap())
     {
         return err!(ErrorCode::NotSupportMint);
     }
@@ -178,6 +251,36 @@ pub fn initialize(
     if ctx.accounts.amm_config.disable_create_pool {
         return err!(ErrorCode::NotApproved);
     }
+    // a mint pair only has its canonical pool unless the config allows more
+    require!(
+        pool_salt == 0 || ctx.accounts.amm_config.allow_salted_pools,
+        ErrorCode::NotApproved
+    );
+    if !ctx
+        .accounts
+        .amm_config
+        .is_allowed_pool_creator(ctx.accounts.creator.key)
+    {
+        return err!(ErrorCode::NotAllowedPoolCreator);
+    }
+    ctx.accounts.amm_config.check_pool_mints(
+        ctx.accounts
+            .mint_policy
+            .as_deref()
+            .map(|mint_policy| &**mint_policy),
+        &ctx.accounts.token_0_mint.key(),
+        &ctx.accounts.token_1_mint.key(),
+    )?;
+    for mint in [&ctx.accounts.token_0_mint, &ctx.accounts.token_1_mint] {
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(get_max_transfer_fee_bps(&mint.to_account_info())?)?;
+        // a frozen vault stops the swaps and the withdrawals of both tokens of the pool
+        require!(
+            accept_freeze_risk || mint.freeze_authority.is_none(),
+            ErrorCode::FreezeRiskNotAccepted
+        );
+    }
     
     // Validate authority type and custom authority
     if authority_type > 1 {
@@ -187,6 +290,9 @@ pub fn initialize(
     if authority_type == 1 && custom_authority.is_none() {
         return err!(ErrorCode::InvalidAuthority);
     }
+
+    let curve_type = CurveType::from_raw(curve_type, amp).ok_or(ErrorCode::InvalidCurveType)?;
+    let launch_mode = LaunchMode::from_u8(launch_mode).ok_or(ErrorCode::InvalidInput)?;
     
     let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
     if open_time <= block_timestamp {
@@ -230,11 +336,60 @@ pub fn initialize(
         &ctx.accounts.token_0_mint.to_account_info(),
         &ctx.accounts.token_1_mint.to_account_info(),
         &ctx.accounts.system_program.to_account_info(),
+        pool_salt,
     )?;
     let pool_state = &mut pool_state_loader.load_init()?;
 
+    let lp_token_program = match &lp_mint_metadata {
+        Some(_) => ctx
+            .accounts
+            .token_program_2022
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?
+            .to_account_info(),
+        None => ctx.accounts.token_program.to_account_info(),
+    };
+    create_lp_mint(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &lp_token_program,
+        LP_MINT_DECIMALS,
+        lp_mint_metadata.map(|metadata| TokenMetadata {
+            mint: ctx.accounts.lp_mint.key(),
+            name: metadata.name,
+            symbol: metadata.symbol,
+            uri: metadata.uri,
+            ..Default::default()
+        }),
+        &[
+            POOL_LP_MINT_SEED.as_bytes(),
+            ctx.accounts.pool_state.key().as_ref(),
+            &[ctx.bumps.lp_mint][..],
+        ],
+        &[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]],
+    )?;
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.creator.to_account_info(),
+            associated_token: ctx.accounts.creator_lp_token.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: lp_token_program.clone(),
+        },
+    ))?;
+
     let mut observation_state = ctx.accounts.observation_state.load_init()?;
     observation_state.pool_id = ctx.accounts.pool_state.key();
+    observation_state.log_price_since = block_timestamp;
+
+    let lp_mint_registry = &mut ctx.accounts.lp_mint_registry;
+    lp_mint_registry.bump = ctx.bumps.lp_mint_registry;
+    lp_mint_registry.lp_mint = ctx.accounts.lp_mint.key();
+    lp_mint_registry.pool_id = ctx.accounts.pool_state.key();
 
     transfer_from_user_to_pool_vault(
         ctx.accounts.creator.to_account_info(),
@@ -244,6 +399,7 @@ pub fn initialize(
         ctx.accounts.token_0_program.to_account_info(),
         init_amount_0,
         ctx.accounts.token_0_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_user_to_pool_vault(
@@ -254,6 +410,7 @@ pub fn initialize(
         ctx.accounts.token_1_program.to_account_info(),
         init_amount_1,
         ctx.accounts.token_1_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     let token_0_vault =
@@ -277,32 +434,56 @@ pub fn initialize(
 
     CurveCalculator::validate_supply(token_0_vault.amount, token_1_vault.amount)?;
 
-    let liquidity = U128::from(token_0_vault.amount)
-        .checked_mul(token_1_vault.amount.into())
-        .unwrap()
-        .integer_sqrt()
-        .as_u64();
-    let lock_lp_amount = 100;
+    let liquidity =
+        CurveCalculator::initial_lp_amount(curve_type, token_0_vault.amount, token_1_vault.amount)?;
+    // the creator must receive lp tokens beyond the locked ones
+    require_gt!(liquidity, LOCKED_LP_AMOUNT, ErrorCode::InitLpAmountTooLess);
     msg!(
         "liquidity:{}, lock_lp_amount:{}, vault_0_amount:{},vault_1_amount:{}",
         liquidity,
-        lock_lp_amount,
+        LOCKED_LP_AMOUNT,
         token_0_vault.amount,
         token_1_vault.amount
     );
     token::token_mint_to(
         ctx.accounts.authority.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        lp_token_program,
         ctx.accounts.lp_mint.to_account_info(),
         ctx.accounts.creator_lp_token.to_account_info(),
-        liquidity
-            .checked_sub(lock_lp_amount)
-            .ok_or(ErrorCode::InitLpAmountTooLess)?,
+        liquidity - LOCKED_LP_AMOUNT,
         &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
     )?;
 
     // Charge the fee to create a pool
-    if ctx.accounts.amm_config.create_pool_fee != 0 {
+    if ctx.accounts.amm_config.create_pool_fee != 0
+        && !is_native_mint(&ctx.accounts.amm_config.create_pool_fee_mint)
+    {
+        let create_pool_fee_mint = ctx
+            .accounts
+            .create_pool_fee_mint
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?;
+        let creator_fee_token = ctx
+            .accounts
+            .creator_fee_token
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?;
+        let create_pool_fee_token_program = ctx
+            .accounts
+            .create_pool_fee_token_program
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?;
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.creator.to_account_info(),
+            creator_fee_token.to_account_info(),
+            ctx.accounts.create_pool_fee.to_account_info(),
+            create_pool_fee_mint.to_account_info(),
+            create_pool_fee_token_program.to_account_info(),
+            ctx.accounts.amm_config.create_pool_fee,
+            create_pool_fee_mint.decimals,
+            &[],
+        )?;
+    } else if ctx.accounts.amm_config.create_pool_fee != 0 {
         invoke(
             &system_instruction::transfer(
                 ctx.accounts.creator.key,
@@ -337,11 +518,47 @@ pub fn initialize(
         ctx.accounts.token_1_vault.key(),
         &ctx.accounts.token_0_mint,
         &ctx.accounts.token_1_mint,
-        &ctx.accounts.lp_mint,
+        ctx.accounts.lp_mint.key(),
+        LP_MINT_DECIMALS,
         ctx.accounts.observation_state.key(),
         authority_type,
         custom_authority,
+        curve_type,
     );
+    pool_state.pool_salt = pool_salt;
+    if launch_mode != LaunchMode::None {
+        pool_state.launch_mode = launch_mode as u8;
+        pool_state.launch_end_time = open_time.checked_add(launch_duration).unwrap();
+    }
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.token_0_mint.to_account_info(),
+        ctx.accounts.token_1_mint.to_account_info(),
+    )
+    .check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        None,
+    )?;
+    if let Some(protocol_stats) = ctx.accounts.protocol_stats.as_mut() {
+        protocol_stats.record_pool_created();
+    }
+
+    emit!(DepositEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.creator.key(),
+        lp_amount: liquidity - LOCKED_LP_AMOUNT,
+        token_0_amount: token_0_vault.amount,
+        token_1_amount: token_1_vault.amount,
+        token_0_transfer_fee: init_amount_0.saturating_sub(token_0_vault.amount),
+        token_1_transfer_fee: init_amount_1.saturating_sub(token_1_vault.amount),
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::default(),
+        reserves_after: PoolReserves::new(token_0_vault.amount, token_1_vault.amount, liquidity),
+    });
 
     Ok(())
 }
@@ -353,37 +570,40 @@ pub fn create_pool<'info>(
     token_0_mint: &AccountInfo<'info>,
     token_1_mint: &AccountInfo<'info>,
     system_program: &AccountInfo<'info>,
+    pool_salt: u8,
 ) -> Result<AccountLoad<'info, PoolState>> {
     if pool_account_info.owner != &system_program::ID {
         return err!(ErrorCode::NotApproved);
     }
 
-    let (expect_pda_address, bump) = Pubkey::find_program_address(
-        &[
-            POOL_SEED.as_bytes(),
-            amm_config.key().as_ref(),
-            token_0_mint.key().as_ref(),
-            token_1_mint.key().as_ref(),
-        ],
-        &crate::id(),
-    );
+    let amm_config_key = amm_config.key();
+    let token_0_mint_key = token_0_mint.key();
+    let token_1_mint_key = token_1_mint.key();
+    let salt = [pool_salt];
+    let mut seeds: Vec<&[u8]> = vec![
+        POOL_SEED.as_bytes(),
+        amm_config_key.as_ref(),
+        token_0_mint_key.as_ref(),
+        token_1_mint_key.as_ref(),
+    ];
+    // the canonical pool keeps the seeds of the pools created before salts
+    if pool_salt != 0 {
+        seeds.push(&salt);
+    }
+    let (expect_pda_address, bump) = Pubkey::find_program_address(&seeds, &crate::id());
 
     if pool_account_info.key() != expect_pda_address {
         require_eq!(pool_account_info.is_signer, true);
     }
+    let bump_seed = [bump];
+    seeds.push(&bump_seed);
 
     token::create_or_allocate_account(
         &crate::id(),
         payer.to_account_info(),
         system_program.to_account_info(),
         pool_account_info.clone(),
-        &[
-            POOL_SEED.as_bytes(),
-            amm_config.key().as_ref(),
-            token_0_mint.key().as_ref(),
-            token_1_mint.key().as_ref(),
-            &[bump],
-        ],
+        &seeds,
         PoolState::LEN,
     )?;
 
@@ -0,0 +1,277 @@
+use crate::curve::{CurveCalculator, FeeRates, SwapParams, SwapSide, TradeDirection};
+use crate::error::ErrorCode;
+use crate::instruction::InitializeAndSwap as InitializeAndSwapArgs;
+use crate::instructions::{initialize, Initialize};
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::{AccountLoad, PoolVaults};
+use anchor_lang::prelude::*;
+use std::ops::Deref;
+
+/// Create a pool with `initialize`, then swap `amount_in` of the creator tokens in the
+/// same instruction, so the first buy of a launch can not be sandwiched. The swap is
+/// allowed before the open time and during the launch phase in its allowed direction
+pub fn initialize_and_swap<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Initialize<'info>>,
+    args: InitializeAndSwapArgs,
+) -> Result<()> {
+    let InitializeAndSwapArgs {
+        init_amount_0,
+        init_amount_1,
+        open_time,
+        authority_type,
+        custom_authority,
+        curve_type,
+        amp,
+        launch_mode,
+        launch_duration,
+        lp_mint_metadata,
+        accept_freeze_risk,
+        zero_for_one,
+        amount_in,
+        minimum_amount_out,
+    } = args;
+    require_gt!(amount_in, 0);
+    let Context {
+        program_id,
+        accounts,
+        remaining_accounts,
+        bumps,
+    } = ctx;
+    initialize(
+        Context::new(program_id, &mut *accounts, remaining_accounts, bumps),
+        init_amount_0,
+        init_amount_1,
+        open_time,
+        authority_type,
+        custom_authority,
+        curve_type,
+        amp,
+        launch_mode,
+        launch_duration,
+        lp_mint_metadata,
+        accept_freeze_risk,
+        0,
+    )?;
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = accounts.pool_state.key();
+    let pool_state_loader =
+        AccountLoad::<PoolState>::try_from(&accounts.pool_state.to_account_info())?;
+    let pool_state = &mut pool_state_loader.load_mut()?;
+    pool_state.lock()?;
+    if pool_state.is_custom_authority() {
+        require!(
+            accounts.creator.key() == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+    let trade_direction = if zero_for_one {
+        TradeDirection::ZeroForOne
+    } else {
+        TradeDirection::OneForZero
+    };
+    // the creator trades the launch phase of its own pool
+    pool_state.check_launch_trade(trade_direction, true)?;
+    let (
+        input_token_account,
+        output_token_account,
+        input_vault,
+        output_vault,
+        input_token_mint,
+        output_token_mint,
+        input_token_program,
+        output_token_program,
+    ) = match trade_direction {
+        TradeDirection::ZeroForOne => (
+            accounts.creator_token_0.to_account_info(),
+            accounts.creator_token_1.to_account_info(),
+            accounts.token_0_vault.to_account_info(),
+            accounts.token_1_vault.to_account_info(),
+            &accounts.token_0_mint,
+            &accounts.token_1_mint,
+            accounts.token_0_program.to_account_info(),
+            accounts.token_1_program.to_account_info(),
+        ),
+        TradeDirection::OneForZero => (
+            accounts.creator_token_1.to_account_info(),
+            accounts.creator_token_0.to_account_info(),
+            accounts.token_1_vault.to_account_info(),
+            accounts.token_0_vault.to_account_info(),
+            &accounts.token_1_mint,
+            &accounts.token_0_mint,
+            accounts.token_1_program.to_account_info(),
+            accounts.token_0_program.to_account_info(),
+        ),
+    };
+    let token_amount = |vault: &AccountInfo| -> Result<u64> {
+        Ok(spl_token_2022::extension::StateWithExtensions::<
+            spl_token_2022::state::Account,
+        >::unpack(vault.try_borrow_data()?.deref())?
+        .base
+        .amount)
+    };
+    let (total_input_token_amount, total_output_token_amount) = match trade_direction {
+        TradeDirection::ZeroForOne => pool_state
+            .vault_amount_without_fee(token_amount(&input_vault)?, token_amount(&output_vault)?),
+        TradeDirection::OneForZero => {
+            let (total_output_token_amount, total_input_token_amount) = pool_state
+                .vault_amount_without_fee(
+                    token_amount(&output_vault)?,
+                    token_amount(&input_vault)?,
+                );
+            (total_input_token_amount, total_output_token_amount)
+        }
+    };
+    let pool_vaults = PoolVaults::new(
+        accounts.token_0_vault.to_account_info(),
+        accounts.token_1_vault.to_account_info(),
+        accounts.token_0_mint.to_account_info(),
+        accounts.token_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+
+    let transfer_fee = get_transfer_fee(&input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+    // the pool has no price history and no fee rate override yet
+    let trade_fee_rate = accounts.amm_config.pool_trade_fee_rate(None);
+    let curve_type = pool_state.curve(block_timestamp)?.oriented(trade_direction);
+    let amount_scale = get_amount_scale(
+        &input_token_mint.to_account_info(),
+        &output_token_mint.to_account_info(),
+    )?;
+    let constant_before = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        u128::from(total_input_token_amount),
+        u128::from(total_output_token_amount),
+    )
+    .unwrap();
+    let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&accounts.amm_config);
+    let result = CurveCalculator::swap_scaled(
+        SwapParams {
+            curve_type,
+            amount_scale,
+            fee_rates: FeeRates {
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+            },
+            fee_on_output: accounts.amm_config.fee_on_output,
+        },
+        SwapSide::ExactInput,
+        u128::from(actual_amount_in),
+        u128::from(total_input_token_amount),
+        u128::from(total_output_token_amount),
+    )?;
+    let (swap_source_amount_without_fee, swap_destination_amount_without_fee) =
+        result.new_swap_amounts_without_fee()?;
+    let constant_after = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        swap_source_amount_without_fee,
+        swap_destination_amount_without_fee,
+    )
+    .unwrap();
+    require_gte!(
+        constant_after,
+        constant_before,
+        ErrorCode::CurveInvariantViolated
+    );
+    require_eq!(
+        u64::try_from(result.source_amount_swapped).unwrap(),
+        actual_amount_in
+    );
+
+    let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
+    let output_transfer_fee = get_transfer_fee(&output_token_mint.to_account_info(), amount_out)?;
+    let amount_received = amount_out.checked_sub(output_transfer_fee).unwrap();
+    require_gt!(amount_received, 0);
+    require_gte!(
+        amount_received,
+        minimum_amount_out,
+        ErrorCode::ExceededSlippage
+    );
+
+    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(result.fund_fee).unwrap();
+    let fee_direction = result.fee_direction(trade_direction);
+    let creator_fee = pool_state.accrue_creator_fee(fee_direction, result.trade_fee);
+    // the fees are accrued in the token they are charged in
+    match fee_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        }
+        TradeDirection::OneForZero => {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    };
+    pool_state.record_swap(trade_direction, &result, creator_fee, block_timestamp);
+    if let Some(protocol_stats) = accounts.protocol_stats.as_mut() {
+        protocol_stats.record_swap(&result);
+    }
+
+    let reserves_before = PoolReserves::from_trade_amounts(
+        trade_direction,
+        total_input_token_amount,
+        total_output_token_amount,
+        pool_state.lp_supply,
+    );
+    let reserves_after = reserves_before.after_swap(trade_direction, &result, creator_fee);
+    emit!(SwapEvent {
+        pool_id,
+        input_vault_before: total_input_token_amount,
+        output_vault_before: total_output_token_amount,
+        input_amount: actual_amount_in,
+        output_amount: amount_out,
+        input_transfer_fee: transfer_fee,
+        output_transfer_fee,
+        base_input: true,
+        trade_fee: u64::try_from(result.trade_fee).unwrap(),
+        protocol_fee,
+        fund_fee,
+        referral_fee: 0,
+        creator_fee,
+        reserves_before,
+        reserves_after,
+    });
+
+    transfer_from_user_to_pool_vault(
+        accounts.creator.to_account_info(),
+        input_token_account,
+        input_vault,
+        input_token_mint.to_account_info(),
+        input_token_program,
+        amount_in,
+        input_token_mint.decimals,
+        remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        accounts.authority.to_account_info(),
+        output_vault,
+        output_token_account,
+        output_token_mint.to_account_info(),
+        output_token_program,
+        amount_out,
+        output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        remaining_accounts,
+    )?;
+    pool_vaults.check(pool_state, None, Some(&pool_snapshot))?;
+    pool_state.unlock();
+
+    Ok(())
+}
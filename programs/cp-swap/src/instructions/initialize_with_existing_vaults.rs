@@ -0,0 +1,321 @@
+use super::initialize::create_pool;
+use crate::curve::{CurveCalculator, CurveType};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::{
+    accounts::interface_account::InterfaceAccount, prelude::*, solana_program::clock,
+};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::Token,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct InitializeWithExistingVaults<'info> {
+    /// Only admin can create a pool around existing vaults, as the migration authority
+    /// vouching the vaults are not used by another pool
+    #[account(
+        mut,
+        address = crate::admin::ID @ ErrorCode::InvalidOwner
+    )]
+    pub creator: Signer<'info>,
+
+    /// Which config the pool belongs to.
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK:
+    /// pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: Initialize an account to store the pool state
+    /// PDA account:
+    /// seeds = [
+    ///     POOL_SEED.as_bytes(),
+    ///     amm_config.key().as_ref(),
+    ///     token_0_mint.key().as_ref(),
+    ///     token_1_mint.key().as_ref(),
+    /// ],
+    ///
+    /// Or random account: must be signed by cli
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// Token_0 mint, the key must smaller than token_1 mint.
+    #[account(
+        constraint = token_0_mint.key() < token_1_mint.key(),
+        mint::token_program = token_0_program,
+    )]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token_1 mint, the key must grater then token_0 mint.
+    #[account(
+        mint::token_program = token_1_program,
+    )]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: pool lp mint, created by contract under the spl token program
+    #[account(
+        mut,
+        seeds = [
+            POOL_LP_MINT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: The owner of the initial lp tokens, such as the program migrating the liquidity
+    pub lp_owner: UncheckedAccount<'info>,
+
+    /// CHECK: lp token account of the lp owner, the associated token account of the lp mint
+    /// created by contract, the associated token program checks the address
+    #[account(mut)]
+    pub owner_lp_token: UncheckedAccount<'info>,
+
+    /// The pre-funded token_0 vault, owned by the pool authority without delegate or
+    /// close authority
+    #[account(
+        mut,
+        token::mint = token_0_mint,
+        token::authority = authority,
+        token::token_program = token_0_program,
+        constraint = token_0_vault.delegate.is_none() && token_0_vault.close_authority.is_none() @ ErrorCode::InvalidVault,
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pre-funded token_1 vault, owned by the pool authority without delegate or
+    /// close authority
+    #[account(
+        mut,
+        token::mint = token_1_mint,
+        token::authority = authority,
+        token::token_program = token_1_program,
+        constraint = token_1_vault.delegate.is_none() && token_1_vault.close_authority.is_none() @ ErrorCode::InvalidVault,
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// an account to store oracle observations
+    #[account(
+        init,
+        seeds = [
+            OBSERVATION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = ObservationState::account_len(OBSERVATION_NUM, true)
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Program to create the lp mint and mint lp tokens
+    pub token_program: Program<'info, Token>,
+    /// Spl token program or token program 2022
+    pub token_0_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_1_program: Interface<'info, TokenInterface>,
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+
+    /// The stats of the pools of the amm config, updated when provided
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+
+    /// The mint policy of the amm config, required when the amm config references one
+    #[account(address = amm_config.mint_policy @ ErrorCode::InvalidInput)]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// The badge of token_0_mint, required when it has a permanent delegate or a transfer
+    /// hook of an unsupported program
+    #[account(
+        seeds = [
+            TOKEN_BADGE_SEED.as_bytes(),
+            token_0_mint.key().as_ref(),
+        ],
+        bump = token_0_badge.bump,
+    )]
+    pub token_0_badge: Option<Box<Account<'info, TokenBadge>>>,
+
+    /// The badge of token_1_mint, required when it has a permanent delegate or a transfer
+    /// hook of an unsupported program
+    #[account(
+        seeds = [
+            TOKEN_BADGE_SEED.as_bytes(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump = token_1_badge.bump,
+    )]
+    pub token_1_badge: Option<Box<Account<'info, TokenBadge>>>,
+}
+
+pub fn initialize_with_existing_vaults(
+    ctx: Context<InitializeWithExistingVaults>,
+    mut open_time: u64,
+    authority_type: u8,
+    custom_authority: Option<Pubkey>,
+    curve_type: u8,
+    amp: u64,
+) -> Result<()> {
+    let has_token_0_badge = ctx.accounts.token_0_badge.is_some();
+    let has_token_1_badge = ctx.accounts.token_1_badge.is_some();
+    if !(is_supported_mint(&ctx.accounts.token_0_mint, has_token_0_badge).unwrap()
+        && is_supported_mint(&ctx.accounts.token_1_mint, has_token_1_badge).unwrap())
+    {
+        return err!(ErrorCode::NotSupportMint);
+    }
+    if ctx.accounts.amm_config.disable_create_pool {
+        return err!(ErrorCode::NotApproved);
+    }
+    ctx.accounts.amm_config.check_pool_mints(
+        ctx.accounts
+            .mint_policy
+            .as_deref()
+            .map(|mint_policy| &**mint_policy),
+        &ctx.accounts.token_0_mint.key(),
+        &ctx.accounts.token_1_mint.key(),
+    )?;
+    for mint in [&ctx.accounts.token_0_mint, &ctx.accounts.token_1_mint] {
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(get_max_transfer_fee_bps(&mint.to_account_info())?)?;
+    }
+    if authority_type > 1 || (authority_type == 1 && custom_authority.is_none()) {
+        return err!(ErrorCode::InvalidAuthority);
+    }
+    let curve_type = CurveType::from_raw(curve_type, amp).ok_or(ErrorCode::InvalidCurveType)?;
+
+    let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
+    if open_time <= block_timestamp {
+        open_time = block_timestamp + 1;
+    }
+
+    let vault_0_amount = ctx.accounts.token_0_vault.amount;
+    let vault_1_amount = ctx.accounts.token_1_vault.amount;
+    CurveCalculator::validate_supply(vault_0_amount, vault_1_amount)?;
+
+    let pool_state_loader = create_pool(
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.amm_config.to_account_info(),
+        &ctx.accounts.token_0_mint.to_account_info(),
+        &ctx.accounts.token_1_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        0,
+    )?;
+    let pool_state = &mut pool_state_loader.load_init()?;
+
+    create_lp_mint(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        LP_MINT_DECIMALS,
+        None,
+        &[
+            POOL_LP_MINT_SEED.as_bytes(),
+            ctx.accounts.pool_state.key().as_ref(),
+            &[ctx.bumps.lp_mint][..],
+        ],
+        &[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]],
+    )?;
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.creator.to_account_info(),
+            associated_token: ctx.accounts.owner_lp_token.to_account_info(),
+            authority: ctx.accounts.lp_owner.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+    ))?;
+
+    let mut observation_state = ctx.accounts.observation_state.load_init()?;
+    observation_state.pool_id = ctx.accounts.pool_state.key();
+    observation_state.log_price_since = block_timestamp;
+
+    let liquidity = CurveCalculator::initial_lp_amount(curve_type, vault_0_amount, vault_1_amount)?;
+    // the creator must receive lp tokens beyond the locked ones
+    require_gt!(liquidity, LOCKED_LP_AMOUNT, ErrorCode::InitLpAmountTooLess);
+    msg!(
+        "liquidity:{}, lock_lp_amount:{}, vault_0_amount:{},vault_1_amount:{}",
+        liquidity,
+        LOCKED_LP_AMOUNT,
+        vault_0_amount,
+        vault_1_amount
+    );
+    token::token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        liquidity - LOCKED_LP_AMOUNT,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    pool_state.initialize(
+        ctx.bumps.authority,
+        liquidity,
+        open_time,
+        ctx.accounts.creator.key(),
+        ctx.accounts.amm_config.key(),
+        ctx.accounts.token_0_vault.key(),
+        ctx.accounts.token_1_vault.key(),
+        &ctx.accounts.token_0_mint,
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.lp_mint.key(),
+        LP_MINT_DECIMALS,
+        ctx.accounts.observation_state.key(),
+        authority_type,
+        custom_authority,
+        curve_type,
+    );
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.token_0_mint.to_account_info(),
+        ctx.accounts.token_1_mint.to_account_info(),
+    )
+    .check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        None,
+    )?;
+    if let Some(protocol_stats) = ctx.accounts.protocol_stats.as_mut() {
+        protocol_stats.record_pool_created();
+    }
+
+    emit!(DepositEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.lp_owner.key(),
+        lp_amount: liquidity - LOCKED_LP_AMOUNT,
+        token_0_amount: vault_0_amount,
+        token_1_amount: vault_1_amount,
+        token_0_transfer_fee: 0,
+        token_1_transfer_fee: 0,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::default(),
+        reserves_after: PoolReserves::new(vault_0_amount, vault_1_amount, liquidity),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(trader: Pubkey)]
+pub struct AddLaunchWhitelist<'info> {
+    /// Only the pool creator can whitelist traders
+    #[account(mut, address = pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner)]
+    pub pool_creator: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Allows the trader to swap during the launch phase
+    #[account(
+        init,
+        seeds = [
+            LAUNCH_WHITELIST_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            trader.as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        space = LaunchWhitelist::LEN
+    )]
+    pub launch_whitelist: Account<'info, LaunchWhitelist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_launch_whitelist(ctx: Context<AddLaunchWhitelist>, trader: Pubkey) -> Result<()> {
+    let launch_whitelist = &mut ctx.accounts.launch_whitelist;
+    launch_whitelist.bump = ctx.bumps.launch_whitelist;
+    launch_whitelist.pool_id = ctx.accounts.pool_state.key();
+    launch_whitelist.trader = trader;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "launch whitelist, pool_id:{}, trader:{}",
+        launch_whitelist.pool_id.to_string(),
+        trader.to_string()
+    );
+    Ok(())
+}
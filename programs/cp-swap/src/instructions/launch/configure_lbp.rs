@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ConfigureLbp<'info> {
+    /// Only the pool creator can turn the pool into a liquidity bootstrapping pool
+    #[account(address = pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner)]
+    pub pool_creator: Signer<'info>,
+
+    /// The weighted pool, not opened yet
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn configure_lbp(
+    ctx: Context<ConfigureLbp>,
+    start_weight: u64,
+    lbp_duration: u32,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.start_lbp(start_weight, lbp_duration, oracle::block_timestamp())?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state, lbp start_weight:{}, lbp_start_time:{}, lbp_end_time:{}",
+        start_weight,
+        { pool_state.open_time },
+        pool_state.lbp_end_time()
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct FinalizeLaunch<'info> {
+    /// The pool to open to full trading
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn finalize_launch(ctx: Context<FinalizeLaunch>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        pool_state.launch_mode != LaunchMode::None as u8,
+        ErrorCode::NotApproved
+    );
+    require_gte!(
+        oracle::block_timestamp(),
+        pool_state.launch_end_time,
+        ErrorCode::LaunchNotEnded
+    );
+
+    emit!(LaunchFinalizedEvent {
+        pool_id,
+        launch_mode: pool_state.launch_mode,
+        launch_end_time: pool_state.launch_end_time,
+    });
+    pool_state.launch_mode = LaunchMode::None as u8;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct FinalizeLbp<'info> {
+    /// The liquidity bootstrapping pool to convert to a constant product pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn finalize_lbp(ctx: Context<FinalizeLbp>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let lbp_end_time = pool_state.lbp_end_time();
+    pool_state.finalize_lbp(oracle::block_timestamp())?;
+
+    emit!(LbpFinalizedEvent {
+        pool_id,
+        lbp_end_time,
+    });
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
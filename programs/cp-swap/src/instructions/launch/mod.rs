@@ -0,0 +1,14 @@
+pub mod add_launch_whitelist;
+pub use add_launch_whitelist::*;
+
+pub mod remove_launch_whitelist;
+pub use remove_launch_whitelist::*;
+
+pub mod finalize_launch;
+pub use finalize_launch::*;
+
+pub mod configure_lbp;
+pub use configure_lbp::*;
+
+pub mod finalize_lbp;
+pub use finalize_lbp::*;
@@ -0,0 +1,29 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemoveLaunchWhitelist<'info> {
+    /// Only the pool creator can remove whitelisted traders, receives the rent
+    #[account(mut, address = pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner)]
+    pub pool_creator: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The whitelist entry to close
+    #[account(
+        mut,
+        seeds = [
+            LAUNCH_WHITELIST_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            launch_whitelist.trader.as_ref(),
+        ],
+        bump = launch_whitelist.bump,
+        close = pool_creator
+    )]
+    pub launch_whitelist: Account<'info, LaunchWhitelist>,
+}
+
+pub fn remove_launch_whitelist(_ctx: Context<RemoveLaunchWhitelist>) -> Result<()> {
+    Ok(())
+}
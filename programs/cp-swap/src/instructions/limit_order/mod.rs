@@ -0,0 +1,8 @@
+pub mod open_order;
+pub use open_order::*;
+
+pub mod fill_order;
+pub use fill_order::*;
+
+pub mod cancel_order;
+pub use cancel_order::*;
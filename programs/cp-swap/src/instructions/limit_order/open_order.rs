@@ -0,0 +1,156 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct OpenOrder<'info> {
+    /// Owner of the input tokens to sell, the only address allowed to cancel the order
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and order vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the order is filled against, counting its open orders
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The mint of the tokens sold
+    #[account(
+        constraint = input_token_mint.key() == pool_state.load()?.token_0_mint || input_token_mint.key() == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the tokens bought
+    #[account(
+        constraint = output_token_mint.key() == pool_state.load()?.token_0_mint || output_token_mint.key() == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput,
+        constraint = output_token_mint.key() != input_token_mint.key() @ ErrorCode::InvalidInput
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner token account paying the input tokens
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving the bought tokens
+    #[account(
+        token::mint = output_token_mint,
+        token::authority = owner
+    )]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores the limit price and the fill status of the order
+    #[account(
+        init,
+        seeds = [
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &order_id.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = OrderState::LEN
+    )]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    /// CHECK: Escrows the input tokens of the order, created by contract
+    #[account(
+        mut,
+        seeds = [
+            LIMIT_ORDER_VAULT_SEED.as_bytes(),
+            order_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub order_vault: UncheckedAccount<'info>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// To create the order accounts
+    pub system_program: Program<'info, System>,
+}
+
+pub fn open_order<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, OpenOrder<'info>>,
+    _order_id: u64,
+    amount_in: u64,
+    limit_price_x64: u128,
+    keeper_tip_bps: u16,
+) -> Result<()> {
+    require_gt!(amount_in, 0);
+    require_gt!(limit_price_x64, 0, ErrorCode::InvalidInput);
+    require_gt!(
+        KEEPER_TIP_BPS_DENOMINATOR,
+        u64::from(keeper_tip_bps),
+        ErrorCode::InvalidInput
+    );
+
+    let order_key = ctx.accounts.order_state.key();
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.order_vault.to_account_info(),
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.input_token_program.to_account_info(),
+        &[
+            LIMIT_ORDER_VAULT_SEED.as_bytes(),
+            order_key.as_ref(),
+            &[ctx.bumps.order_vault][..],
+        ],
+    )?;
+
+    // The order escrows the amount received after the transfer fee
+    let transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.pool_state.load_mut()?.open_order()?;
+
+    let order_state = &mut ctx.accounts.order_state;
+    order_state.bump = ctx.bumps.order_state;
+    order_state.pool_id = ctx.accounts.pool_state.key();
+    order_state.owner = ctx.accounts.owner.key();
+    order_state.order_vault = ctx.accounts.order_vault.key();
+    order_state.input_mint = ctx.accounts.input_token_mint.key();
+    order_state.output_mint = ctx.accounts.output_token_mint.key();
+    order_state.output_token_account = ctx.accounts.output_token_account.key();
+    order_state.limit_price_x64 = limit_price_x64;
+    order_state.keeper_tip_bps = keeper_tip_bps;
+    order_state.amount_in = actual_amount_in;
+    order_state.amount_in_remaining = actual_amount_in;
+    order_state.amount_out_filled = 0;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "open order, amount_in:{}, limit_price_x64:{}, keeper_tip_bps:{}",
+        actual_amount_in,
+        limit_price_x64,
+        keeper_tip_bps
+    );
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClaimUnlockedLiquidity<'info> {
+    /// Owner of the lock
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the lp tokens belong to
+    #[account(address = liquidity_lock.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The lock to claim from
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        has_one = lp_vault @ ErrorCode::InvalidVault
+    )]
+    pub liquidity_lock: Box<Account<'info, LiquidityLock>>,
+
+    /// Escrows the locked lp tokens
+    #[account(mut)]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account receiving the unlocked lp tokens
+    #[account(
+        mut,
+        token::mint = lp_mint
+    )]
+    pub recipient_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool lp token mint
+    #[account(
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022 of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_unlocked_liquidity(ctx: Context<ClaimUnlockedLiquidity>) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let liquidity_lock = &mut ctx.accounts.liquidity_lock;
+    let claimable_amount = liquidity_lock.claimable_amount(block_timestamp);
+    require_gt!(claimable_amount, 0, ErrorCode::NotApproved);
+    liquidity_lock.claimed_amount = liquidity_lock
+        .claimed_amount
+        .checked_add(claimable_amount)
+        .unwrap();
+
+    let auth_bump = ctx.accounts.pool_state.load()?.auth_bump;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.lp_vault.to_account_info(),
+        ctx.accounts.recipient_lp_token.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        claimable_amount,
+        ctx.accounts.lp_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        &[],
+    )?;
+
+    emit!(ClaimUnlockedLiquidityEvent {
+        pool_id: liquidity_lock.pool_id,
+        liquidity_lock: liquidity_lock.key(),
+        owner: liquidity_lock.owner,
+        lp_amount: claimable_amount,
+        claimed_amount: liquidity_lock.claimed_amount,
+    });
+    Ok(())
+}
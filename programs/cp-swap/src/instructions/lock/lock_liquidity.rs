@@ -0,0 +1,121 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(lock_index: u64)]
+pub struct LockLiquidity<'info> {
+    /// Owner of the lp tokens to lock, the only address allowed to claim them
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the lp tokens belong to
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Pool lp token mint
+    #[account(
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Owner lp token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stores the vesting schedule of the lock
+    #[account(
+        init,
+        seeds = [
+            LIQUIDITY_LOCK_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &lock_index.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = LiquidityLock::LEN
+    )]
+    pub liquidity_lock: Box<Account<'info, LiquidityLock>>,
+
+    /// Escrows the locked lp tokens
+    #[account(
+        init,
+        seeds = [
+            LIQUIDITY_LOCK_VAULT_SEED.as_bytes(),
+            liquidity_lock.key().as_ref(),
+        ],
+        bump,
+        token::mint = lp_mint,
+        token::authority = authority,
+        token::token_program = token_program,
+        payer = owner,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// To create the lock accounts
+    pub system_program: Program<'info, System>,
+}
+
+pub fn lock_liquidity(
+    ctx: Context<LockLiquidity>,
+    _lock_index: u64,
+    lp_token_amount: u64,
+    cliff_duration: u64,
+    vesting_duration: u64,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    require_gt!(vesting_duration, 0);
+    require_gte!(vesting_duration, cliff_duration, ErrorCode::InvalidInput);
+
+    let start_time = Clock::get()?.unix_timestamp as u64;
+    let liquidity_lock = &mut ctx.accounts.liquidity_lock;
+    liquidity_lock.bump = ctx.bumps.liquidity_lock;
+    liquidity_lock.pool_id = ctx.accounts.pool_state.key();
+    liquidity_lock.owner = ctx.accounts.owner.key();
+    liquidity_lock.lp_vault = ctx.accounts.lp_vault.key();
+    liquidity_lock.locked_amount = lp_token_amount;
+    liquidity_lock.claimed_amount = 0;
+    liquidity_lock.start_time = start_time;
+    liquidity_lock.cliff_time = start_time.checked_add(cliff_duration).unwrap();
+    liquidity_lock.end_time = start_time.checked_add(vesting_duration).unwrap();
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        ctx.accounts.lp_vault.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        lp_token_amount,
+        ctx.accounts.lp_mint.decimals,
+        &[],
+    )?;
+
+    emit!(LockLiquidityEvent {
+        pool_id: liquidity_lock.pool_id,
+        liquidity_lock: liquidity_lock.key(),
+        owner: liquidity_lock.owner,
+        lp_amount: lp_token_amount,
+        cliff_time: liquidity_lock.cliff_time,
+        end_time: liquidity_lock.end_time,
+    });
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub mod lock_liquidity;
+pub use lock_liquidity::*;
+
+pub mod claim_unlocked_liquidity;
+pub use claim_unlocked_liquidity::*;
@@ -0,0 +1,385 @@
+use crate::curve::CurveCalculator;
+use crate::curve::RoundDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// The instruction tag of `withdraw` in the legacy Raydium AMM v4 program
+pub const LEGACY_WITHDRAW_INSTRUCTION: u8 = 4;
+
+#[derive(Accounts)]
+pub struct MigrateFromLegacy<'info> {
+    /// Owner of the migrated liquidity, signs the withdrawal from the legacy pool
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the liquidity is deposited into
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: the legacy Raydium AMM v4 program the liquidity is withdrawn from
+    #[account(address = crate::legacy_amm_program::ID)]
+    pub legacy_amm_program: UncheckedAccount<'info>,
+
+    /// Owner lp token account of the legacy pool, the legacy lp tokens are burned from it
+    #[account(
+        mut,
+        token::authority = owner
+    )]
+    pub legacy_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner lp token account of the pool
+    #[account(mut, token::mint = lp_mint)]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account for token_0, receives the token_0 withdrawn from the legacy
+    /// pool and keeps the token_0 left over by the deposit
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = owner
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account for token_1, receives the token_1 withdrawn from the legacy
+    /// pool and keeps the token_1 left over by the deposit
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = owner
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Lp token mint
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+impl<'info> MigrateFromLegacy<'info> {
+    fn mint_token_program(&self, mint: &InterfaceAccount<'info, Mint>) -> AccountInfo<'info> {
+        if mint.to_account_info().owner == self.token_program.key {
+            self.token_program.to_account_info()
+        } else {
+            self.token_program_2022.to_account_info()
+        }
+    }
+
+    /// Withdraw `legacy_lp_amount` legacy lp tokens from the legacy pool, `legacy_accounts`
+    /// are the accounts of the legacy `withdraw` instruction in its order
+    fn withdraw_from_legacy(
+        &self,
+        legacy_accounts: &[AccountInfo<'info>],
+        legacy_lp_amount: u64,
+    ) -> Result<()> {
+        let mut data = vec![LEGACY_WITHDRAW_INSTRUCTION];
+        data.extend_from_slice(&legacy_lp_amount.to_le_bytes());
+        let instruction = Instruction {
+            program_id: self.legacy_amm_program.key(),
+            accounts: legacy_accounts
+                .iter()
+                .map(|account| {
+                    if account.is_writable {
+                        AccountMeta::new(account.key(), account.is_signer)
+                    } else {
+                        AccountMeta::new_readonly(account.key(), account.is_signer)
+                    }
+                })
+                .collect(),
+            data,
+        };
+        let mut account_infos = legacy_accounts.to_vec();
+        account_infos.push(self.legacy_amm_program.to_account_info());
+        invoke(&instruction, &account_infos)?;
+        Ok(())
+    }
+}
+
+pub fn migrate_from_legacy<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, MigrateFromLegacy<'info>>,
+    legacy_lp_amount: u64,
+    legacy_account_count: u8,
+    minimum_lp_token_amount: u64,
+) -> Result<()> {
+    require_gt!(legacy_lp_amount, 0);
+    // the first remaining accounts are the legacy withdraw accounts, the others are the
+    // transfer hook accounts of the deposit
+    let legacy_account_count = usize::from(legacy_account_count);
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        legacy_account_count,
+        ErrorCode::InvalidInput
+    );
+    let (legacy_accounts, transfer_hook_accounts) =
+        ctx.remaining_accounts.split_at(legacy_account_count);
+
+    // The legacy pool pays the withdrawn tokens to the owner token accounts, the deposit
+    // takes them from there and the owner keeps what the pool price does not take
+    let legacy_lp_amount_before = ctx.accounts.legacy_lp_token.amount;
+    let token_0_amount_before = ctx.accounts.token_0_account.amount;
+    let token_1_amount_before = ctx.accounts.token_1_account.amount;
+    ctx.accounts
+        .withdraw_from_legacy(legacy_accounts, legacy_lp_amount)?;
+    ctx.accounts.legacy_lp_token.reload()?;
+    ctx.accounts.token_0_account.reload()?;
+    ctx.accounts.token_1_account.reload()?;
+    require_eq!(
+        legacy_lp_amount_before
+            .checked_sub(ctx.accounts.legacy_lp_token.amount)
+            .ok_or(ErrorCode::InvalidInput)?,
+        legacy_lp_amount,
+        ErrorCode::InvalidInput
+    );
+    let token_0_amount = ctx
+        .accounts
+        .token_0_account
+        .amount
+        .checked_sub(token_0_amount_before)
+        .ok_or(ErrorCode::InvalidInput)?;
+    let token_1_amount = ctx
+        .accounts
+        .token_1_account
+        .amount
+        .checked_sub(token_1_amount_before)
+        .ok_or(ErrorCode::InvalidInput)?;
+    if token_0_amount == 0 || token_1_amount == 0 {
+        return err!(ErrorCode::ZeroTradingTokens);
+    }
+
+    let pool_id = ctx.accounts.pool_state.key();
+    let owner = ctx.accounts.owner.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    // the liquidity of position mode pools is deposited with `deposit_position`
+    require!(!pool_state.is_position_mode(), ErrorCode::LpTokensDisabled);
+    // For custom authority pools, only the custom authority can deposit
+    if pool_state.is_custom_authority() {
+        require!(
+            owner == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+    pool_state.lock()?;
+    let pool_vaults = PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+
+    // Deposit the most the withdrawn tokens allow at the pool price, as `migrate_liquidity`
+    let vault_0_mint_info = ctx.accounts.vault_0_mint.to_account_info();
+    let vault_1_mint_info = ctx.accounts.vault_1_mint.to_account_info();
+    let received_token_0_amount = token_0_amount
+        .checked_sub(get_transfer_fee(&vault_0_mint_info, token_0_amount)?)
+        .unwrap();
+    let received_token_1_amount = token_1_amount
+        .checked_sub(get_transfer_fee(&vault_1_mint_info, token_1_amount)?)
+        .unwrap();
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens(
+        u128::from(received_token_0_amount),
+        u128::from(received_token_1_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+    )?;
+    let lp_token_amount = u64::try_from(lp_token_amount).unwrap();
+    require_gt!(lp_token_amount, 0, ErrorCode::ZeroTradingTokens);
+    require_gte!(
+        lp_token_amount,
+        minimum_lp_token_amount,
+        ErrorCode::ExceededSlippage
+    );
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Ceiling,
+    )?;
+    let deposit_token_0_amount = u64::try_from(results.token_0_amount).unwrap();
+    let deposit_token_1_amount = u64::try_from(results.token_1_amount).unwrap();
+    let transfer_token_0_fee =
+        get_transfer_inverse_fee(&vault_0_mint_info, deposit_token_0_amount)?;
+    let transfer_token_1_fee =
+        get_transfer_inverse_fee(&vault_1_mint_info, deposit_token_1_amount)?;
+    let transfer_token_0_amount = deposit_token_0_amount
+        .checked_add(transfer_token_0_fee)
+        .unwrap();
+    let transfer_token_1_amount = deposit_token_1_amount
+        .checked_add(transfer_token_1_fee)
+        .unwrap();
+    require_gte!(
+        token_0_amount,
+        transfer_token_0_amount,
+        ErrorCode::ExceededSlippage
+    );
+    require_gte!(
+        token_1_amount,
+        transfer_token_1_amount,
+        ErrorCode::ExceededSlippage
+    );
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "legacy_lp_amount:{}, token_0_amount:{}, token_1_amount:{}, lp_token_amount:{}, transfer_token_0_amount:{}, transfer_token_1_amount:{}",
+        legacy_lp_amount,
+        token_0_amount,
+        token_1_amount,
+        lp_token_amount,
+        transfer_token_0_amount,
+        transfer_token_1_amount
+    );
+    emit!(LpChangeEvent {
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount: deposit_token_0_amount,
+        token_1_amount: deposit_token_1_amount,
+        token_0_transfer_fee: transfer_token_0_fee,
+        token_1_transfer_fee: transfer_token_1_fee,
+        change_type: 0
+    });
+    emit!(DepositEvent {
+        pool_id,
+        owner,
+        lp_amount: lp_token_amount,
+        token_0_amount: deposit_token_0_amount,
+        token_1_amount: deposit_token_1_amount,
+        token_0_transfer_fee: transfer_token_0_fee,
+        token_1_transfer_fee: transfer_token_1_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            total_token_0_amount,
+            total_token_1_amount,
+            pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            total_token_0_amount
+                .checked_add(deposit_token_0_amount)
+                .unwrap(),
+            total_token_1_amount
+                .checked_add(deposit_token_1_amount)
+                .unwrap(),
+            pool_state.lp_supply.checked_add(lp_token_amount).unwrap(),
+        ),
+    });
+    emit!(LegacyLiquidityMigratedEvent {
+        pool_id,
+        owner,
+        legacy_lp_amount,
+        token_0_amount,
+        token_1_amount,
+        lp_amount: lp_token_amount,
+    });
+
+    for (token_account, vault, mint, transfer_amount) in [
+        (
+            &ctx.accounts.token_0_account,
+            &ctx.accounts.token_0_vault,
+            &ctx.accounts.vault_0_mint,
+            transfer_token_0_amount,
+        ),
+        (
+            &ctx.accounts.token_1_account,
+            &ctx.accounts.token_1_vault,
+            &ctx.accounts.vault_1_mint,
+            transfer_token_1_amount,
+        ),
+    ] {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.owner.to_account_info(),
+            token_account.to_account_info(),
+            vault.to_account_info(),
+            mint.to_account_info(),
+            ctx.accounts.mint_token_program(mint),
+            transfer_amount,
+            mint.decimals,
+            transfer_hook_accounts,
+        )?;
+    }
+
+    let lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).unwrap();
+    pool_state.set_lp_supply(lp_supply);
+    token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.mint_token_program(&ctx.accounts.lp_mint),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        lp_token_amount,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        Some(&pool_snapshot),
+    )?;
+    pool_state.unlock();
+
+    Ok(())
+}
@@ -0,0 +1,474 @@
+use crate::curve::CurveCalculator;
+use crate::curve::RoundDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct MigrateLiquidity<'info> {
+    /// Owner of the migrated liquidity
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the liquidity is withdrawn from
+    #[account(mut)]
+    pub from_pool_state: AccountLoader<'info, PoolState>,
+
+    /// The pool the liquidity is deposited into, with the same mints
+    #[account(
+        mut,
+        constraint = to_pool_state.key() != from_pool_state.key() @ ErrorCode::InvalidInput,
+        constraint = to_pool_state.load()?.token_0_mint == from_pool_state.load()?.token_0_mint @ ErrorCode::InvalidInput,
+        constraint = to_pool_state.load()?.token_1_mint == from_pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput
+    )]
+    pub to_pool_state: AccountLoader<'info, PoolState>,
+
+    /// Owner lp token account of the source pool
+    #[account(
+        mut,
+        token::mint = from_lp_mint,
+        token::authority = owner
+    )]
+    pub from_owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner lp token account of the destination pool
+    #[account(
+        mut,
+        token::mint = to_lp_mint
+    )]
+    pub to_owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account for receive the token_0 left over by the deposit
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account for receive the token_1 left over by the deposit
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds source pool tokens for token_0
+    #[account(
+        mut,
+        constraint = from_token_0_vault.key() == from_pool_state.load()?.token_0_vault
+    )]
+    pub from_token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds source pool tokens for token_1
+    #[account(
+        mut,
+        constraint = from_token_1_vault.key() == from_pool_state.load()?.token_1_vault
+    )]
+    pub from_token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds destination pool tokens for token_0
+    #[account(
+        mut,
+        constraint = to_token_0_vault.key() == to_pool_state.load()?.token_0_vault
+    )]
+    pub to_token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds destination pool tokens for token_1
+    #[account(
+        mut,
+        constraint = to_token_1_vault.key() == to_pool_state.load()?.token_1_vault
+    )]
+    pub to_token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vaults
+    #[account(
+        address = from_token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vaults
+    #[account(
+        address = from_token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Source pool lp token mint
+    #[account(
+        mut,
+        address = from_pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub from_lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Destination pool lp token mint
+    #[account(
+        mut,
+        address = to_pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub to_lp_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+impl<'info> MigrateLiquidity<'info> {
+    fn mint_token_program(&self, mint: &InterfaceAccount<'info, Mint>) -> AccountInfo<'info> {
+        if mint.to_account_info().owner == self.token_program.key {
+            self.token_program.to_account_info()
+        } else {
+            self.token_program_2022.to_account_info()
+        }
+    }
+}
+
+/// Check a pool accepts the liquidity change of the owner
+fn check_pool_liquidity_change(
+    pool_state: &PoolState,
+    owner: &Pubkey,
+    bit: PoolStatusBitIndex,
+) -> Result<()> {
+    if !pool_state.get_status_by_bit(bit) {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    if pool_state.is_custom_authority() {
+        require!(
+            *owner == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+    Ok(())
+}
+
+pub fn migrate_liquidity<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, MigrateLiquidity<'info>>,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+    minimum_lp_token_amount: u64,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let owner = ctx.accounts.owner.key();
+    let from_pool_id = ctx.accounts.from_pool_state.key();
+    let to_pool_id = ctx.accounts.to_pool_state.key();
+    let from_pool_state = &mut ctx.accounts.from_pool_state.load_mut()?;
+    let to_pool_state = &mut ctx.accounts.to_pool_state.load_mut()?;
+    check_pool_liquidity_change(from_pool_state, &owner, PoolStatusBitIndex::Withdraw)?;
+    from_pool_state.check_withdraw_without_receipt()?;
+    check_pool_liquidity_change(to_pool_state, &owner, PoolStatusBitIndex::Deposit)?;
+    require!(
+        !to_pool_state.is_position_mode(),
+        ErrorCode::LpTokensDisabled
+    );
+    to_pool_state.check_deposit_without_receipt()?;
+    let from_pool_vaults = PoolVaults::new(
+        ctx.accounts.from_token_0_vault.to_account_info(),
+        ctx.accounts.from_token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let from_pool_snapshot = from_pool_vaults.snapshot(from_pool_state)?;
+    let to_pool_vaults = PoolVaults::new(
+        ctx.accounts.to_token_0_vault.to_account_info(),
+        ctx.accounts.to_token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let to_pool_snapshot = to_pool_vaults.snapshot(to_pool_state)?;
+
+    // Withdraw from the source pool as `withdraw` does
+    let (from_total_token_0_amount, from_total_token_1_amount) = from_pool_state
+        .vault_amount_without_fee(
+            ctx.accounts.from_token_0_vault.amount,
+            ctx.accounts.from_token_1_vault.amount,
+        );
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(from_pool_state.lp_supply),
+        u128::from(from_total_token_0_amount),
+        u128::from(from_total_token_1_amount),
+        RoundDirection::Floor,
+    )?;
+    if results.token_0_amount == 0 || results.token_1_amount == 0 {
+        return err!(ErrorCode::ZeroTradingTokens);
+    }
+    let token_0_amount = std::cmp::min(
+        from_total_token_0_amount,
+        u64::try_from(results.token_0_amount).unwrap(),
+    );
+    let token_1_amount = std::cmp::min(
+        from_total_token_1_amount,
+        u64::try_from(results.token_1_amount).unwrap(),
+    );
+    from_pool_state.check_vault_outflow(
+        ctx.accounts.from_token_0_vault.amount,
+        ctx.accounts.from_token_1_vault.amount,
+        token_0_amount,
+        token_1_amount,
+    )?;
+    // The withdrawn amounts are bounded as in `withdraw`, before the transfer fee
+    let vault_0_mint_info = ctx.accounts.vault_0_mint.to_account_info();
+    let vault_1_mint_info = ctx.accounts.vault_1_mint.to_account_info();
+    let received_token_0_amount = token_0_amount
+        .checked_sub(get_transfer_fee(&vault_0_mint_info, token_0_amount)?)
+        .unwrap();
+    let received_token_1_amount = token_1_amount
+        .checked_sub(get_transfer_fee(&vault_1_mint_info, token_1_amount)?)
+        .unwrap();
+    if received_token_0_amount < minimum_token_0_amount
+        || received_token_1_amount < minimum_token_1_amount
+    {
+        return Err(ErrorCode::ExceededSlippage.into());
+    }
+
+    // Deposit the most the withdrawn tokens allow at the destination pool price, the tokens
+    // move between the vaults directly so the transfer fee is only charged once
+    let (to_total_token_0_amount, to_total_token_1_amount) = to_pool_state
+        .vault_amount_without_fee(
+            ctx.accounts.to_token_0_vault.amount,
+            ctx.accounts.to_token_1_vault.amount,
+        );
+    let to_lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens(
+        u128::from(received_token_0_amount),
+        u128::from(received_token_1_amount),
+        u128::from(to_pool_state.lp_supply),
+        u128::from(to_total_token_0_amount),
+        u128::from(to_total_token_1_amount),
+    )?;
+    let to_lp_token_amount = u64::try_from(to_lp_token_amount).unwrap();
+    require_gt!(to_lp_token_amount, 0, ErrorCode::ZeroTradingTokens);
+    require_gte!(
+        to_lp_token_amount,
+        minimum_lp_token_amount,
+        ErrorCode::ExceededSlippage
+    );
+    let deposit_results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(to_lp_token_amount),
+        u128::from(to_pool_state.lp_supply),
+        u128::from(to_total_token_0_amount),
+        u128::from(to_total_token_1_amount),
+        RoundDirection::Ceiling,
+    )?;
+    let deposit_token_0_amount = u64::try_from(deposit_results.token_0_amount).unwrap();
+    let deposit_token_1_amount = u64::try_from(deposit_results.token_1_amount).unwrap();
+    let deposit_token_0_transfer_fee =
+        get_transfer_inverse_fee(&vault_0_mint_info, deposit_token_0_amount)?;
+    let deposit_token_1_transfer_fee =
+        get_transfer_inverse_fee(&vault_1_mint_info, deposit_token_1_amount)?;
+    let transfer_token_0_amount = deposit_token_0_amount
+        .checked_add(deposit_token_0_transfer_fee)
+        .unwrap();
+    let transfer_token_1_amount = deposit_token_1_amount
+        .checked_add(deposit_token_1_transfer_fee)
+        .unwrap();
+    require_gte!(
+        token_0_amount,
+        transfer_token_0_amount,
+        ErrorCode::ExceededSlippage
+    );
+    require_gte!(
+        token_1_amount,
+        transfer_token_1_amount,
+        ErrorCode::ExceededSlippage
+    );
+    // The tokens the destination pool price does not take are returned to the owner
+    let refund_token_0_amount = token_0_amount - transfer_token_0_amount;
+    let refund_token_1_amount = token_1_amount - transfer_token_1_amount;
+    let refund_token_0_transfer_fee = get_transfer_fee(&vault_0_mint_info, refund_token_0_amount)?;
+    let refund_token_1_transfer_fee = get_transfer_fee(&vault_1_mint_info, refund_token_1_amount)?;
+    let withdraw_token_0_transfer_fee = deposit_token_0_transfer_fee + refund_token_0_transfer_fee;
+    let withdraw_token_1_transfer_fee = deposit_token_1_transfer_fee + refund_token_1_transfer_fee;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "token_0_amount:{}, token_1_amount:{}, to_lp_token_amount:{}, refund_token_0_amount:{}, refund_token_1_amount:{}",
+        token_0_amount,
+        token_1_amount,
+        to_lp_token_amount,
+        refund_token_0_amount,
+        refund_token_1_amount
+    );
+    emit!(LpChangeEvent {
+        pool_id: from_pool_id,
+        lp_amount_before: from_pool_state.lp_supply,
+        token_0_vault_before: from_total_token_0_amount,
+        token_1_vault_before: from_total_token_1_amount,
+        token_0_amount: token_0_amount - withdraw_token_0_transfer_fee,
+        token_1_amount: token_1_amount - withdraw_token_1_transfer_fee,
+        token_0_transfer_fee: withdraw_token_0_transfer_fee,
+        token_1_transfer_fee: withdraw_token_1_transfer_fee,
+        change_type: 1
+    });
+    emit!(WithdrawEvent {
+        pool_id: from_pool_id,
+        owner,
+        lp_amount: lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee: withdraw_token_0_transfer_fee,
+        token_1_transfer_fee: withdraw_token_1_transfer_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            from_total_token_0_amount,
+            from_total_token_1_amount,
+            from_pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            from_total_token_0_amount
+                .checked_sub(token_0_amount)
+                .unwrap(),
+            from_total_token_1_amount
+                .checked_sub(token_1_amount)
+                .unwrap(),
+            from_pool_state
+                .lp_supply
+                .checked_sub(lp_token_amount)
+                .unwrap(),
+        ),
+    });
+    emit!(LpChangeEvent {
+        pool_id: to_pool_id,
+        lp_amount_before: to_pool_state.lp_supply,
+        token_0_vault_before: to_total_token_0_amount,
+        token_1_vault_before: to_total_token_1_amount,
+        token_0_amount: deposit_token_0_amount,
+        token_1_amount: deposit_token_1_amount,
+        token_0_transfer_fee: deposit_token_0_transfer_fee,
+        token_1_transfer_fee: deposit_token_1_transfer_fee,
+        change_type: 0
+    });
+    emit!(DepositEvent {
+        pool_id: to_pool_id,
+        owner,
+        lp_amount: to_lp_token_amount,
+        token_0_amount: deposit_token_0_amount,
+        token_1_amount: deposit_token_1_amount,
+        token_0_transfer_fee: deposit_token_0_transfer_fee,
+        token_1_transfer_fee: deposit_token_1_transfer_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            to_total_token_0_amount,
+            to_total_token_1_amount,
+            to_pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            to_total_token_0_amount
+                .checked_add(deposit_token_0_amount)
+                .unwrap(),
+            to_total_token_1_amount
+                .checked_add(deposit_token_1_amount)
+                .unwrap(),
+            to_pool_state
+                .lp_supply
+                .checked_add(to_lp_token_amount)
+                .unwrap(),
+        ),
+    });
+
+    let from_lp_supply = from_pool_state
+        .lp_supply
+        .checked_sub(lp_token_amount)
+        .unwrap();
+    from_pool_state.set_lp_supply(from_lp_supply);
+    let to_lp_supply = to_pool_state
+        .lp_supply
+        .checked_add(to_lp_token_amount)
+        .unwrap();
+    to_pool_state.set_lp_supply(to_lp_supply);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[from_pool_state.auth_bump]]];
+    token_burn(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.mint_token_program(&ctx.accounts.from_lp_mint),
+        ctx.accounts.from_lp_mint.to_account_info(),
+        ctx.accounts.from_owner_lp_token.to_account_info(),
+        lp_token_amount,
+        signer_seeds,
+    )?;
+    for (from_vault, to_vault, to_account, mint, transfer_amount, refund_amount) in [
+        (
+            &ctx.accounts.from_token_0_vault,
+            &ctx.accounts.to_token_0_vault,
+            &ctx.accounts.token_0_account,
+            &ctx.accounts.vault_0_mint,
+            transfer_token_0_amount,
+            refund_token_0_amount,
+        ),
+        (
+            &ctx.accounts.from_token_1_vault,
+            &ctx.accounts.to_token_1_vault,
+            &ctx.accounts.token_1_account,
+            &ctx.accounts.vault_1_mint,
+            transfer_token_1_amount,
+            refund_token_1_amount,
+        ),
+    ] {
+        let token_program = ctx.accounts.mint_token_program(mint);
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            from_vault.to_account_info(),
+            to_vault.to_account_info(),
+            mint.to_account_info(),
+            token_program.clone(),
+            transfer_amount,
+            mint.decimals,
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            from_vault.to_account_info(),
+            to_account.to_account_info(),
+            mint.to_account_info(),
+            token_program,
+            refund_amount,
+            mint.decimals,
+            signer_seeds,
+            ctx.remaining_accounts,
+        )?;
+    }
+    token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.mint_token_program(&ctx.accounts.to_lp_mint),
+        ctx.accounts.to_lp_mint.to_account_info(),
+        ctx.accounts.to_owner_lp_token.to_account_info(),
+        to_lp_token_amount,
+        signer_seeds,
+    )?;
+    let epoch = Clock::get()?.epoch;
+    from_pool_state.recent_epoch = epoch;
+    to_pool_state.recent_epoch = epoch;
+    from_pool_vaults.check(
+        from_pool_state,
+        Some(&ctx.accounts.from_lp_mint.to_account_info()),
+        Some(&from_pool_snapshot),
+    )?;
+    to_pool_vaults.check(
+        to_pool_state,
+        Some(&ctx.accounts.to_lp_mint.to_account_info()),
+        Some(&to_pool_snapshot),
+    )?;
+
+    Ok(())
+}
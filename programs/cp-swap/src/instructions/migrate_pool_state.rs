@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::AccountLoad;
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct MigratePoolState<'info> {
+    /// Pays the rent of the grown pool state. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The pool state in an older layout, which does not load as the current one,
+    /// its discriminator and size are checked
+    #[account(mut, owner = crate::id())]
+    pub pool_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+    let pool_state_info = ctx.accounts.pool_state.to_account_info();
+    let old_len = pool_state_info.data_len();
+    {
+        let data = pool_state_info.try_borrow_data()?;
+        require!(
+            data.starts_with(PoolState::DISCRIMINATOR),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+    }
+    // every layout since the first one only appended fields
+    require!(
+        (POOL_STATE_V1_LEN..=PoolState::LEN).contains(&old_len),
+        ErrorCode::InvalidPoolStateVersion
+    );
+
+    let required_lamports = Rent::get()?
+        .minimum_balance(PoolState::LEN)
+        .saturating_sub(pool_state_info.lamports());
+    if required_lamports > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: pool_state_info.clone(),
+        };
+        let cpi_context =
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_context, required_lamports)?;
+    }
+    // the appended fields are zeroed, which disables the features added since
+    pool_state_info.realloc(PoolState::LEN, true)?;
+
+    let pool_state_loader = AccountLoad::<PoolState>::try_from(&pool_state_info)?;
+    let mut pool_state = pool_state_loader.load_mut()?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool state, old_len:{}, old_version:{}, new_len:{}, new_version:{}",
+        old_len,
+        { pool_state.version },
+        PoolState::LEN,
+        POOL_STATE_VERSION
+    );
+    pool_state.migrate()
+}
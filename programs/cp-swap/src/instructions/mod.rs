@@ -11,5 +11,155 @@ pub use withdraw::*;
 pub mod admin;
 pub use admin::*;
 
+pub mod initialize_with_existing_vaults;
+pub use initialize_with_existing_vaults::*;
+
+pub mod initialize_and_swap;
+pub use initialize_and_swap::*;
+
 pub mod swap_base_output;
 pub use swap_base_output::*;
+
+pub mod swap_signed_order;
+pub use swap_signed_order::*;
+
+pub mod rebalance_to_target_price;
+pub use rebalance_to_target_price::*;
+
+pub mod flash_loan;
+pub use flash_loan::*;
+
+pub mod repay_flash_loan;
+pub use repay_flash_loan::*;
+
+pub mod strategy_borrow;
+pub use strategy_borrow::*;
+
+pub mod strategy_repay;
+pub use strategy_repay::*;
+
+pub mod claim_fee_rebate;
+pub use claim_fee_rebate::*;
+
+pub mod migrate_pool_state;
+pub use migrate_pool_state::*;
+
+pub mod get_twap;
+pub use get_twap::*;
+
+pub mod get_pool_metrics;
+pub use get_pool_metrics::*;
+
+pub mod get_lp_position_value;
+pub use get_lp_position_value::*;
+
+pub mod quote_swap;
+pub use quote_swap::*;
+
+pub mod increase_observation_cardinality;
+pub use increase_observation_cardinality::*;
+
+pub mod enable_log_price_observations;
+pub use enable_log_price_observations::*;
+
+pub mod snapshot_observation;
+pub use snapshot_observation::*;
+
+pub mod deposit_single_token;
+pub use deposit_single_token::*;
+
+pub mod withdraw_single_token;
+pub use withdraw_single_token::*;
+
+pub mod deposit_exact_tokens;
+pub use deposit_exact_tokens::*;
+
+pub mod withdraw_exact_tokens;
+pub use withdraw_exact_tokens::*;
+
+pub mod deposit_for;
+pub use deposit_for::*;
+
+pub mod withdraw_to;
+pub use withdraw_to::*;
+
+pub mod compound_fees;
+pub use compound_fees::*;
+
+pub mod collect_creator_fee;
+pub use collect_creator_fee::*;
+
+pub mod sync_reserves;
+pub use sync_reserves::*;
+
+pub mod harvest_withheld_fees;
+pub use harvest_withheld_fees::*;
+
+pub mod crank_protocol_fees;
+pub use crank_protocol_fees::*;
+
+pub mod execute_buyback;
+pub use execute_buyback::*;
+
+pub mod migrate_liquidity;
+pub use migrate_liquidity::*;
+
+pub mod migrate_from_legacy;
+pub use migrate_from_legacy::*;
+
+pub mod register_pool;
+pub use register_pool::*;
+
+pub mod create_protocol_stats;
+pub use create_protocol_stats::*;
+
+pub mod create_pool_snapshot;
+pub use create_pool_snapshot::*;
+
+pub mod set_pool_metadata;
+pub use set_pool_metadata::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+
+pub mod swap_route;
+pub use swap_route::*;
+
+pub mod swap_route_base_output;
+pub use swap_route_base_output::*;
+
+pub mod lock;
+pub use lock::*;
+
+pub mod position;
+pub use position::*;
+
+pub mod launch;
+pub use launch::*;
+
+pub mod limit_order;
+pub use limit_order::*;
+
+pub mod dca;
+pub use dca::*;
+
+pub mod auction;
+pub use auction::*;
+
+pub mod session;
+pub use session::*;
+
+pub mod reward;
+pub use reward::*;
+
+pub mod trader_registry;
+pub use trader_registry::*;
+
+pub mod multi_pool;
+pub use multi_pool::*;
+
+pub mod compression;
+pub use compression::*;
+
+pub mod handle_frozen_vault;
+pub use handle_frozen_vault::*;
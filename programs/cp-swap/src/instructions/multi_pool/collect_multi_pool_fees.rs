@@ -0,0 +1,94 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Token2022;
+
+#[derive(Accounts)]
+pub struct CollectMultiPoolFees<'info> {
+    /// Only admin, protocol owner or fund owner can collect fee, the protocol owner
+    /// collects the protocol fees, the fund owner the fund fees and the admin both
+    #[account(constraint = (owner.key() == amm_config.protocol_owner || owner.key() == amm_config.fund_owner || owner.key() == crate::admin::ID) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores accumulated protocol and fund fee amounts
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, MultiPoolState>>,
+
+    /// Amm config account stores owners
+    #[account(address = pool_state.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Collect the fees accrued to a multi token pool. The remaining accounts hold 3
+/// accounts per pool token, in the order of the pool tokens: the vault, the mint and the
+/// recipient token account
+pub fn collect_multi_pool_fees<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CollectMultiPoolFees<'info>>,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let collect_protocol_fees =
+        owner == ctx.accounts.amm_config.protocol_owner || owner == crate::admin::ID;
+    let collect_fund_fees =
+        owner == ctx.accounts.amm_config.fund_owner || owner == crate::admin::ID;
+    let pool_state = &mut ctx.accounts.pool_state;
+    let token_count = pool_state.token_count();
+    require!(
+        ctx.remaining_accounts.len() == token_count * 3,
+        ErrorCode::InvalidInput
+    );
+    for (i, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+        let (vault, mint, recipient_token) = (&accounts[0], &accounts[1], &accounts[2]);
+        require_keys_eq!(
+            vault.key(),
+            pool_state.token_vaults[i],
+            ErrorCode::InvalidVault
+        );
+        require_keys_eq!(
+            mint.key(),
+            pool_state.token_mints[i],
+            ErrorCode::InvalidInput
+        );
+        let mut amount = 0;
+        if collect_protocol_fees {
+            amount += pool_state.protocol_fees[i];
+            pool_state.protocol_fees[i] = 0;
+        }
+        if collect_fund_fees {
+            amount += pool_state.fund_fees[i];
+            pool_state.fund_fees[i] = 0;
+        }
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            vault.clone(),
+            recipient_token.clone(),
+            mint.clone(),
+            if *mint.owner == ctx.accounts.token_program.key() {
+                ctx.accounts.token_program.to_account_info()
+            } else {
+                ctx.accounts.token_program_2022.to_account_info()
+            },
+            amount,
+            pool_state.mint_decimals[i],
+            &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+            &[],
+        )?;
+    }
+    Ok(())
+}
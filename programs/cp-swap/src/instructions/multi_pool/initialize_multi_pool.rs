@@ -0,0 +1,281 @@
+use crate::curve::{MultiStableSwapCurve, MAX_AMP, MIN_AMP};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::{
+    prelude::*, solana_program::program::invoke, solana_program::system_instruction,
+};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{spl_token, Token},
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct InitializeMultiPool<'info> {
+    /// Address paying to create the pool. Can be anyone
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Which config the pool belongs to.
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The multi token pool, a random account signed by the creator
+    #[account(
+        init,
+        payer = creator,
+        space = MultiPoolState::LEN
+    )]
+    pub pool_state: Box<Account<'info, MultiPoolState>>,
+
+    /// CHECK: pool lp mint, created by contract under the spl token program
+    #[account(
+        mut,
+        seeds = [
+            POOL_LP_MINT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: creator lp token account, the associated token account of the lp mint
+    /// created by contract, the associated token program checks the address
+    #[account(mut)]
+    pub creator_lp_token: UncheckedAccount<'info>,
+
+    /// create pool fee account, the fee must be paid in native SOL
+    #[account(
+        mut,
+        token::mint = amm_config.create_pool_fee_mint,
+        token::authority = amm_config.create_pool_fee_receiver,
+    )]
+    pub create_pool_fee: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint policy of the amm config, required when the amm config references one
+    #[account(address = amm_config.mint_policy @ ErrorCode::InvalidInput)]
+    pub mint_policy: Option<Box<Account<'info, MintPolicy>>>,
+
+    /// Program to create the lp mint and the vaults of the spl token mints
+    pub token_program: Program<'info, Token>,
+    /// Program to create the vaults of the token program 2022 mints
+    pub token_program_2022: Program<'info, Token2022>,
+    /// Program to create the lp token account of the creator
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a stable pool of the 3 to 4 tokens passed in the remaining accounts, 3
+/// accounts per token sorted by mint: the mint, the vault created by contract and the
+/// creator token account paying the initial amount
+pub fn initialize_multi_pool<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, InitializeMultiPool<'info>>,
+    amp: u64,
+    init_amounts: Vec<u64>,
+    open_time: u64,
+) -> Result<()> {
+    let token_count = init_amounts.len();
+    require!(
+        (MIN_MULTI_POOL_TOKENS..=MAX_MULTI_POOL_TOKENS).contains(&token_count)
+            && ctx.remaining_accounts.len() == token_count * 3,
+        ErrorCode::InvalidInput
+    );
+    require!(
+        (MIN_AMP..=MAX_AMP).contains(&amp),
+        ErrorCode::InvalidCurveType
+    );
+    if ctx.accounts.amm_config.disable_create_pool {
+        return err!(ErrorCode::NotApproved);
+    }
+    if !ctx
+        .accounts
+        .amm_config
+        .is_allowed_pool_creator(ctx.accounts.creator.key)
+    {
+        return err!(ErrorCode::NotAllowedPoolCreator);
+    }
+
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state;
+    let mut vault_amounts = Vec::with_capacity(token_count);
+    for (i, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+        let (mint_info, vault, creator_token) = (&accounts[0], &accounts[1], &accounts[2]);
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        if !is_supported_mint(&mint, false)? || is_transfer_hook_mint(mint_info, &[])? {
+            return err!(ErrorCode::NotSupportMint);
+        }
+        // the mints are sorted, which also rejects a token listed twice
+        require!(
+            i == 0 || pool_state.token_mints[i - 1] < mint.key(),
+            ErrorCode::InvalidInput
+        );
+        ctx.accounts.amm_config.check_pool_mints(
+            ctx.accounts
+                .mint_policy
+                .as_deref()
+                .map(|mint_policy| &**mint_policy),
+            &mint.key(),
+            &mint.key(),
+        )?;
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(get_max_transfer_fee_bps(mint_info)?)?;
+        let (vault_key, vault_bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                mint.key().as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require_keys_eq!(vault.key(), vault_key, ErrorCode::InvalidVault);
+        let token_program = if *mint_info.owner == ctx.accounts.token_program.key() {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+        create_token_account(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            vault,
+            mint_info,
+            &ctx.accounts.system_program.to_account_info(),
+            &token_program,
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                mint.key().as_ref(),
+                &[vault_bump][..],
+            ],
+        )?;
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.creator.to_account_info(),
+            creator_token.clone(),
+            vault.clone(),
+            mint_info.clone(),
+            token_program,
+            init_amounts[i],
+            mint.decimals,
+            &[],
+        )?;
+        let vault_amount = get_token_account_amount(vault)?;
+        require_gt!(vault_amount, 0, ErrorCode::ZeroTradingTokens);
+        vault_amounts.push(vault_amount);
+        pool_state.token_mints[i] = mint.key();
+        pool_state.token_vaults[i] = vault_key;
+        pool_state.mint_decimals[i] = mint.decimals;
+    }
+    pool_state.token_count = token_count as u8;
+
+    // the lp tokens count the invariant in the decimals of the most precise token
+    let liquidity = u64::try_from(MultiStableSwapCurve::compute_d(
+        amp,
+        &pool_state.scaled_amounts(&vault_amounts),
+    )?)
+    .map_err(|_| error!(ErrorCode::MathOverflow))?;
+    require_gt!(liquidity, LOCKED_LP_AMOUNT, ErrorCode::InitLpAmountTooLess);
+    let lp_mint_decimals = pool_state.mint_decimals[..token_count]
+        .iter()
+        .copied()
+        .max()
+        .unwrap();
+    create_lp_mint(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        lp_mint_decimals,
+        None,
+        &[
+            POOL_LP_MINT_SEED.as_bytes(),
+            pool_id.as_ref(),
+            &[ctx.bumps.lp_mint][..],
+        ],
+        &[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]],
+    )?;
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.creator.to_account_info(),
+            associated_token: ctx.accounts.creator_lp_token.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+    ))?;
+    token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.creator_lp_token.to_account_info(),
+        liquidity - LOCKED_LP_AMOUNT,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    // Charge the fee to create a pool
+    if ctx.accounts.amm_config.create_pool_fee != 0 {
+        require!(
+            is_native_mint(&ctx.accounts.amm_config.create_pool_fee_mint),
+            ErrorCode::NotApproved
+        );
+        invoke(
+            &system_instruction::transfer(
+                ctx.accounts.creator.key,
+                &ctx.accounts.create_pool_fee.key(),
+                ctx.accounts.amm_config.create_pool_fee,
+            ),
+            &[
+                ctx.accounts.creator.to_account_info(),
+                ctx.accounts.create_pool_fee.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::sync_native(
+                ctx.accounts.token_program.key,
+                &ctx.accounts.create_pool_fee.key(),
+            )?,
+            &[
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.create_pool_fee.to_account_info(),
+            ],
+        )?;
+    }
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    pool_state.amm_config = ctx.accounts.amm_config.key();
+    pool_state.pool_creator = ctx.accounts.creator.key();
+    pool_state.lp_mint = ctx.accounts.lp_mint.key();
+    pool_state.auth_bump = ctx.bumps.authority;
+    pool_state.lp_mint_decimals = lp_mint_decimals;
+    pool_state.amp = amp;
+    pool_state.lp_supply = liquidity;
+    pool_state.open_time = open_time.max(block_timestamp + 1);
+
+    emit!(MultiPoolLpChangeEvent {
+        pool_id,
+        owner: ctx.accounts.creator.key(),
+        lp_amount_before: 0,
+        lp_amount: liquidity - LOCKED_LP_AMOUNT,
+        transfer_fees: init_amounts
+            .iter()
+            .zip(&vault_amounts)
+            .map(|(init_amount, vault_amount)| init_amount.saturating_sub(*vault_amount))
+            .collect(),
+        token_amounts: vault_amounts,
+        change_type: 0,
+    });
+    Ok(())
+}
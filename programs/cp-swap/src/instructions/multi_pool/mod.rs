@@ -0,0 +1,14 @@
+pub mod initialize_multi_pool;
+pub use initialize_multi_pool::*;
+
+pub mod multi_pool_deposit;
+pub use multi_pool_deposit::*;
+
+pub mod multi_pool_withdraw;
+pub use multi_pool_withdraw::*;
+
+pub mod multi_pool_swap;
+pub use multi_pool_swap::*;
+
+pub mod collect_multi_pool_fees;
+pub use collect_multi_pool_fees::*;
@@ -0,0 +1,200 @@
+use crate::curve::{Fees, MultiStableSwapCurve};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct MultiPoolSwap<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, MultiPoolState>>,
+
+    /// The user token account for input token
+    #[account(mut)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user token account for output token
+    #[account(mut)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for input token
+    #[account(mut)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(mut)]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of input token
+    #[account(address = input_vault.mint)]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(address = output_vault.mint)]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Swap `amount_in` input tokens of a multi token pool for its output tokens on the
+/// StableSwap invariant of all the pool balances. The remaining accounts hold the vaults
+/// of every pool token, in the order of the pool tokens
+pub fn multi_pool_swap<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, MultiPoolSwap<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    require_gt!(amount_in, 0);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
+        || block_timestamp < pool_state.open_time
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    let token_count = pool_state.token_count();
+    require!(
+        ctx.remaining_accounts.len() == token_count,
+        ErrorCode::InvalidInput
+    );
+    let token_vaults = &pool_state.token_vaults[..token_count];
+    let input_index = token_vaults
+        .iter()
+        .position(|vault| *vault == ctx.accounts.input_vault.key())
+        .ok_or(ErrorCode::InvalidVault)?;
+    let output_index = token_vaults
+        .iter()
+        .position(|vault| *vault == ctx.accounts.output_vault.key())
+        .ok_or(ErrorCode::InvalidVault)?;
+    require_neq!(input_index, output_index, ErrorCode::InvalidInput);
+
+    let mut vault_amounts = Vec::with_capacity(token_count);
+    for (vault, vault_key) in ctx.remaining_accounts.iter().zip(token_vaults) {
+        require_keys_eq!(vault.key(), *vault_key, ErrorCode::InvalidVault);
+        vault_amounts.push(get_token_account_amount(vault)?);
+    }
+    let pool_amounts = pool_state.vault_amounts_without_fee(&vault_amounts);
+    let multipliers = pool_state.amount_multipliers();
+    let mut balances = pool_state.scaled_amounts(&pool_amounts);
+
+    for mint in [
+        &ctx.accounts.input_token_mint,
+        &ctx.accounts.output_token_mint,
+    ] {
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(get_transfer_fee_bps(&mint.to_account_info())?)?;
+    }
+    let input_transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(input_transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+    let amm_config = &ctx.accounts.amm_config;
+    let trade_fee = Fees::trading_fee(u128::from(actual_amount_in), amm_config.trade_fee_rate)?;
+    let protocol_fee = Fees::protocol_fee(trade_fee, amm_config.protocol_fee_rate)?;
+    let fund_fee = Fees::fund_fee(trade_fee, amm_config.fund_fee_rate)?;
+    let source_amount = u128::from(actual_amount_in)
+        .checked_sub(trade_fee)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    let d_before = MultiStableSwapCurve::compute_d(pool_state.amp, &balances)?;
+    let destination_amount = MultiStableSwapCurve::swap_base_input_without_fees(
+        pool_state.amp,
+        &balances,
+        input_index,
+        output_index,
+        source_amount * multipliers[input_index],
+    )?;
+    // the output is rounded down to the output token decimals in favor of the pool
+    let amount_out = u64::try_from(destination_amount / multipliers[output_index])
+        .map_err(|_| error!(ErrorCode::MathOverflow))?;
+    require_gt!(amount_out, 0, ErrorCode::ZeroTradingTokens);
+
+    // the liquidity provider fee stays in the pool balances
+    balances[input_index] +=
+        (u128::from(actual_amount_in) - protocol_fee - fund_fee) * multipliers[input_index];
+    balances[output_index] -= u128::from(amount_out) * multipliers[output_index];
+    let d_after = MultiStableSwapCurve::compute_d(pool_state.amp, &balances)?;
+    require_gte!(d_after, d_before, ErrorCode::CurveInvariantViolated);
+
+    let output_transfer_fee = get_transfer_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        amount_out,
+    )?;
+    let amount_received = amount_out.checked_sub(output_transfer_fee).unwrap();
+    require_gt!(amount_received, 0);
+    require_gte!(
+        amount_received,
+        minimum_amount_out,
+        ErrorCode::ExceededSlippage
+    );
+
+    let protocol_fee = u64::try_from(protocol_fee).unwrap();
+    let fund_fee = u64::try_from(fund_fee).unwrap();
+    pool_state.protocol_fees[input_index] = pool_state.protocol_fees[input_index]
+        .checked_add(protocol_fee)
+        .unwrap();
+    pool_state.fund_fees[input_index] = pool_state.fund_fees[input_index]
+        .checked_add(fund_fee)
+        .unwrap();
+
+    emit!(MultiPoolSwapEvent {
+        pool_id,
+        input_index: input_index as u8,
+        output_index: output_index as u8,
+        input_amount: actual_amount_in,
+        output_amount: amount_out,
+        input_transfer_fee,
+        output_transfer_fee,
+        trade_fee: u64::try_from(trade_fee).unwrap(),
+        protocol_fee,
+        fund_fee,
+    });
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        &[],
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_token_program.to_account_info(),
+        amount_out,
+        ctx.accounts.output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        &[],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use crate::curve::RoundDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct MultiPoolWithdraw<'info> {
+    /// Owner of the burned lp tokens
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, MultiPoolState>>,
+
+    /// Owner lp token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool lp token mint
+    #[account(
+        mut,
+        address = pool_state.lp_mint @ ErrorCode::IncorrectLpMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Burn `lp_token_amount` lp tokens for their share of every token of a multi token pool.
+/// The remaining accounts hold 3 accounts per pool token, in the order of the pool
+/// tokens: the vault, the mint and the token account receiving the token
+pub fn multi_pool_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, MultiPoolWithdraw<'info>>,
+    lp_token_amount: u64,
+    minimum_token_amounts: Vec<u64>,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
+        return err!(ErrorCode::NotApproved);
+    }
+    let token_count = pool_state.token_count();
+    require!(
+        minimum_token_amounts.len() == token_count
+            && ctx.remaining_accounts.len() == token_count * 3,
+        ErrorCode::InvalidInput
+    );
+    require_gte!(
+        pool_state.lp_supply,
+        lp_token_amount,
+        ErrorCode::LpSupplyUnderflow
+    );
+
+    let mut vault_amounts = Vec::with_capacity(token_count);
+    for (i, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+        require_keys_eq!(
+            accounts[0].key(),
+            pool_state.token_vaults[i],
+            ErrorCode::InvalidVault
+        );
+        require_keys_eq!(
+            accounts[1].key(),
+            pool_state.token_mints[i],
+            ErrorCode::InvalidInput
+        );
+        vault_amounts.push(get_token_account_amount(&accounts[0])?);
+    }
+    let pool_amounts = pool_state.vault_amounts_without_fee(&vault_amounts);
+
+    token_burn(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        lp_token_amount,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )?;
+
+    let mut token_amounts = Vec::with_capacity(token_count);
+    let mut transfer_fees = Vec::with_capacity(token_count);
+    for (i, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+        let (vault, mint, token_account) = (&accounts[0], &accounts[1], &accounts[2]);
+        let token_amount = pool_state
+            .lp_tokens_to_token_amount(lp_token_amount, pool_amounts[i], RoundDirection::Floor)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if token_amount == 0 {
+            return err!(ErrorCode::ZeroTradingTokens);
+        }
+        let transfer_fee = get_transfer_fee(mint, token_amount)?;
+        if token_amount.checked_sub(transfer_fee).unwrap() < minimum_token_amounts[i] {
+            return Err(ErrorCode::ExceededSlippage.into());
+        }
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            vault.clone(),
+            token_account.clone(),
+            mint.clone(),
+            if *mint.owner == ctx.accounts.token_program.key() {
+                ctx.accounts.token_program.to_account_info()
+            } else {
+                ctx.accounts.token_program_2022.to_account_info()
+            },
+            token_amount,
+            pool_state.mint_decimals[i],
+            &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+            &[],
+        )?;
+        token_amounts.push(token_amount);
+        transfer_fees.push(transfer_fee);
+    }
+
+    emit!(MultiPoolLpChangeEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_amount_before: pool_state.lp_supply,
+        lp_amount: lp_token_amount,
+        token_amounts,
+        transfer_fees,
+        change_type: 1,
+    });
+    pool_state.lp_supply -= lp_token_amount;
+    Ok(())
+}
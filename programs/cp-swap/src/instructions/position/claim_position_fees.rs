@@ -0,0 +1,28 @@
+use crate::error::ErrorCode;
+use crate::instructions::{withdraw_position, WithdrawPosition};
+use anchor_lang::prelude::*;
+
+pub fn claim_position_fees<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, WithdrawPosition<'info>>,
+) -> Result<()> {
+    let fee_shares = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        );
+        let personal_position = &mut ctx.accounts.personal_position;
+        personal_position.accrue_fees(pool_state.fee_growth_0_x64, pool_state.fee_growth_1_x64);
+        let fee_shares = personal_position.fee_shares(
+            pool_state.lp_supply,
+            total_token_0_amount,
+            total_token_1_amount,
+        );
+        personal_position.fees_owed_token_0 = 0;
+        personal_position.fees_owed_token_1 = 0;
+        fee_shares
+    };
+    require_gt!(fee_shares, 0, ErrorCode::ZeroTradingTokens);
+    // the fees compound into the shares, claiming them withdraws the shares they are worth
+    withdraw_position(ctx, fee_shares, 0, 0)
+}
@@ -0,0 +1,284 @@
+use crate::curve::CurveCalculator;
+use crate::curve::RoundDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct DepositPosition<'info> {
+    /// Owner of the position, pays to create it
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Records the shares of the owner in the pool
+    #[account(
+        init_if_needed,
+        seeds = [
+            PERSONAL_POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The payer's token account for token_0
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = owner
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer's token account for token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = owner
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// To create the position
+    pub system_program: Program<'info, System>,
+
+    /// The reward emissions of the pool, required when the pool has rewards
+    #[account(
+        mut,
+        seeds = [
+            REWARD_STATE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = reward_state.bump,
+    )]
+    pub reward_state: Option<Box<Account<'info, RewardState>>>,
+
+    /// The rewards earned by the position, required when the pool has rewards and created
+    /// by the first share change passing it
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_REWARD_SEED.as_bytes(),
+            personal_position.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PositionRewardState::LEN
+    )]
+    pub position_reward: Option<Box<Account<'info, PositionRewardState>>>,
+}
+
+pub fn deposit_position<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, DepositPosition<'info>>,
+    shares: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+) -> Result<()> {
+    require_gt!(shares, 0);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.is_position_mode() || !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    // the positions are not recorded by deposit receipts, so their shares would never unlock
+    pool_state.check_deposit_without_receipt()?;
+    pool_state.lock()?;
+    let pool_vaults = PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+
+    // For custom authority pools, only the custom authority can deposit
+    if pool_state.is_custom_authority() {
+        require!(
+            ctx.accounts.owner.key() == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(shares),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Ceiling,
+    )?;
+    if results.token_0_amount == 0 || results.token_1_amount == 0 {
+        return err!(ErrorCode::ZeroTradingTokens);
+    }
+    let token_0_amount = u64::try_from(results.token_0_amount).unwrap();
+    let token_0_transfer_fee =
+        get_transfer_inverse_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
+    let transfer_token_0_amount = token_0_amount.checked_add(token_0_transfer_fee).unwrap();
+    let token_1_amount = u64::try_from(results.token_1_amount).unwrap();
+    let token_1_transfer_fee =
+        get_transfer_inverse_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
+    let transfer_token_1_amount = token_1_amount.checked_add(token_1_transfer_fee).unwrap();
+    if transfer_token_0_amount > maximum_token_0_amount
+        || transfer_token_1_amount > maximum_token_1_amount
+    {
+        return Err(ErrorCode::ExceededSlippage.into());
+    }
+
+    emit!(LpChangeEvent {
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        change_type: 0
+    });
+    emit!(DepositEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_amount: shares,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            total_token_0_amount,
+            total_token_1_amount,
+            pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            total_token_0_amount.checked_add(token_0_amount).unwrap(),
+            total_token_1_amount.checked_add(token_1_amount).unwrap(),
+            pool_state.lp_supply.checked_add(shares).unwrap(),
+        ),
+    });
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        transfer_token_0_amount,
+        ctx.accounts.vault_0_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        transfer_token_1_amount,
+        ctx.accounts.vault_1_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    // the rewards are accrued at the shares before they change
+    if pool_state.is_rewards_enabled() {
+        let (Some(reward_state), Some(position_reward)) = (
+            ctx.accounts.reward_state.as_mut(),
+            ctx.accounts.position_reward.as_mut(),
+        ) else {
+            return err!(ErrorCode::MissingRewardAccounts);
+        };
+        reward_state.update(pool_state.lp_supply, Clock::get()?.unix_timestamp as u64);
+        if !position_reward.is_initialized() {
+            position_reward.initialize(
+                ctx.bumps.position_reward.unwrap(),
+                ctx.accounts.personal_position.key(),
+            );
+        }
+        position_reward.accrue(reward_state, ctx.accounts.personal_position.shares);
+    }
+    // the shares are recorded in the position instead of minted as lp tokens
+    let personal_position = &mut ctx.accounts.personal_position;
+    if personal_position.pool_id == Pubkey::default() {
+        personal_position.initialize(
+            ctx.bumps.personal_position,
+            pool_id,
+            ctx.accounts.owner.key(),
+            pool_state.fee_growth_0_x64,
+            pool_state.fee_growth_1_x64,
+        );
+    }
+    personal_position.accrue_fees(pool_state.fee_growth_0_x64, pool_state.fee_growth_1_x64);
+    personal_position.shares = personal_position.shares.checked_add(shares).unwrap();
+    let lp_supply = pool_state.lp_supply.checked_add(shares).unwrap();
+    pool_state.set_lp_supply(lp_supply);
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(pool_state, None, Some(&pool_snapshot))?;
+    pool_state.unlock();
+
+    Ok(())
+}
@@ -0,0 +1,8 @@
+pub mod deposit_position;
+pub use deposit_position::*;
+
+pub mod withdraw_position;
+pub use withdraw_position::*;
+
+pub mod claim_position_fees;
+pub use claim_position_fees::*;
@@ -0,0 +1,286 @@
+use crate::curve::CurveCalculator;
+use crate::curve::RoundDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct WithdrawPosition<'info> {
+    /// Owner of the position, pays to create its reward account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state account
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The position the shares are withdrawn from
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        constraint = personal_position.pool_id == pool_state.key() @ ErrorCode::InvalidInput
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The token account for receive token_0,
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account for receive token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The reward emissions of the pool, required when the pool has rewards
+    #[account(
+        mut,
+        seeds = [
+            REWARD_STATE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = reward_state.bump,
+    )]
+    pub reward_state: Option<Box<Account<'info, RewardState>>>,
+
+    /// The rewards earned by the position, required when the pool has rewards and created
+    /// by the first share change passing it
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_REWARD_SEED.as_bytes(),
+            personal_position.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PositionRewardState::LEN
+    )]
+    pub position_reward: Option<Box<Account<'info, PositionRewardState>>>,
+
+    /// To create the position reward account
+    pub system_program: Option<Program<'info, System>>,
+}
+
+pub fn withdraw_position<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, WithdrawPosition<'info>>,
+    shares: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> Result<()> {
+    require_gt!(shares, 0);
+    require_gte!(
+        ctx.accounts.personal_position.shares,
+        shares,
+        ErrorCode::InvalidInput
+    );
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    pool_state.lock()?;
+    let pool_vaults = PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+
+    // For custom authority pools, only the custom authority can withdraw
+    if pool_state.is_custom_authority() {
+        require!(
+            ctx.accounts.owner.key() == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(shares),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Floor,
+    )?;
+    if results.token_0_amount == 0 || results.token_1_amount == 0 {
+        return err!(ErrorCode::ZeroTradingTokens);
+    }
+    let token_0_amount = u64::try_from(results.token_0_amount).unwrap();
+    let token_0_amount = std::cmp::min(total_token_0_amount, token_0_amount);
+    let token_0_transfer_fee =
+        get_transfer_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
+    let receive_token_0_amount = token_0_amount.checked_sub(token_0_transfer_fee).unwrap();
+    let token_1_amount = u64::try_from(results.token_1_amount).unwrap();
+    let token_1_amount = std::cmp::min(total_token_1_amount, token_1_amount);
+    let token_1_transfer_fee =
+        get_transfer_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
+    let receive_token_1_amount = token_1_amount.checked_sub(token_1_transfer_fee).unwrap();
+    if receive_token_0_amount < minimum_token_0_amount
+        || receive_token_1_amount < minimum_token_1_amount
+    {
+        return Err(ErrorCode::ExceededSlippage.into());
+    }
+
+    emit!(LpChangeEvent {
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount: receive_token_0_amount,
+        token_1_amount: receive_token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        change_type: 1
+    });
+    emit!(WithdrawEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_amount: shares,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            total_token_0_amount,
+            total_token_1_amount,
+            pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            total_token_0_amount.checked_sub(token_0_amount).unwrap(),
+            total_token_1_amount.checked_sub(token_1_amount).unwrap(),
+            pool_state
+                .lp_supply
+                .checked_sub(shares)
+                .ok_or(ErrorCode::LpSupplyUnderflow)?,
+        ),
+    });
+
+    // the rewards are accrued at the shares before they change
+    if pool_state.is_rewards_enabled() {
+        let (Some(reward_state), Some(position_reward)) = (
+            ctx.accounts.reward_state.as_mut(),
+            ctx.accounts.position_reward.as_mut(),
+        ) else {
+            return err!(ErrorCode::MissingRewardAccounts);
+        };
+        reward_state.update(pool_state.lp_supply, Clock::get()?.unix_timestamp as u64);
+        if !position_reward.is_initialized() {
+            position_reward.initialize(
+                ctx.bumps.position_reward.unwrap(),
+                ctx.accounts.personal_position.key(),
+            );
+        }
+        position_reward.accrue(reward_state, ctx.accounts.personal_position.shares);
+    }
+    let personal_position = &mut ctx.accounts.personal_position;
+    personal_position.accrue_fees(pool_state.fee_growth_0_x64, pool_state.fee_growth_1_x64);
+    personal_position.remove_shares(shares);
+    let lp_supply = pool_state
+        .lp_supply
+        .checked_sub(shares)
+        .ok_or(ErrorCode::LpSupplyUnderflow)?;
+    pool_state.set_lp_supply(lp_supply);
+
+    // the reserves lent to the strategy are not in the vaults to pay the withdrawal
+    pool_state.check_vault_outflow(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+        token_0_amount,
+        token_1_amount,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        token_0_amount,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        token_1_amount,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(pool_state, None, Some(&pool_snapshot))?;
+    pool_state.unlock();
+
+    Ok(())
+}
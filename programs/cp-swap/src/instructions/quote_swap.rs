@@ -0,0 +1,335 @@
+use crate::curve::calculator::{CurveCalculator, SwapParams, SwapResult, SwapSide};
+use crate::curve::{FeeRates, Fees, TradeDirection};
+use crate::error::ErrorCode;
+use crate::instructions::SwapRouteHop;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolSnapshot;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use std::collections::BTreeSet;
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool in which the swap would be performed
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault token account for input token
+    #[account(
+        constraint = input_vault.key() == pool_state.load()?.token_0_vault || input_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(
+        constraint = output_vault.key() == pool_state.load()?.token_0_vault || output_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of input token
+    #[account(
+        address = input_vault.mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(
+        address = output_vault.mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+/// The number of accounts of `QuoteSwap`, the accounts of each pool quoted by `quote_many`
+pub const QUOTE_SWAP_ACCOUNTS: usize = 7;
+/// Maximum number of pools quoted by `quote_many`, their quotes fit in the return data
+pub const MAX_QUOTE_POOLS: usize = 10;
+
+/// The pools quoted by `quote_many` are passed as remaining accounts
+#[derive(Accounts)]
+pub struct QuoteMany {}
+
+impl<'info> QuoteSwap<'info> {
+    /// Check the pool accepts the swap and get the pool amounts before it, as a swap
+    /// of a trader without launch whitelist entry would
+    fn prepare(&self, block_timestamp: u64) -> Result<SwapRouteHop> {
+        let pool_state = self.pool_state.load()?;
+        pool_state.check_swap_allowed(block_timestamp)?;
+
+        let (trade_direction, vault_0_amount, vault_1_amount) = if self.input_vault.key()
+            == pool_state.token_0_vault
+            && self.output_vault.key() == pool_state.token_1_vault
+        {
+            (
+                TradeDirection::ZeroForOne,
+                self.input_vault.amount,
+                self.output_vault.amount,
+            )
+        } else if self.input_vault.key() == pool_state.token_1_vault
+            && self.output_vault.key() == pool_state.token_0_vault
+        {
+            (
+                TradeDirection::OneForZero,
+                self.output_vault.amount,
+                self.input_vault.amount,
+            )
+        } else {
+            return err!(ErrorCode::InvalidVault);
+        };
+        let (total_token_0_amount, total_token_1_amount) =
+            pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+        let (token_0_price_x64, token_1_price_x64) =
+            pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+        let (total_input_token_amount, total_output_token_amount) = match trade_direction {
+            TradeDirection::ZeroForOne => (total_token_0_amount, total_token_1_amount),
+            TradeDirection::OneForZero => (total_token_1_amount, total_token_0_amount),
+        };
+        pool_state.check_launch_trade(trade_direction, false)?;
+        let (curve_input_token_amount, curve_output_token_amount) = pool_state.curve_trade_amounts(
+            trade_direction,
+            total_input_token_amount,
+            total_output_token_amount,
+        );
+
+        let trade_fee_rate = {
+            let (observation_state, observation_extension, _) =
+                self.observation_state.load_observations()?;
+            self.amm_config.swap_trade_fee_rate(
+                pool_state.trade_fee_rate_override(block_timestamp),
+                &observation_state,
+                &observation_extension,
+                block_timestamp,
+                token_0_price_x64,
+            )
+        };
+
+        let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&self.amm_config);
+        Ok(SwapRouteHop {
+            trade_direction,
+            vault_0_amount,
+            vault_1_amount,
+            curve_type: pool_state.curve(block_timestamp)?.oriented(trade_direction),
+            total_input_token_amount,
+            total_output_token_amount,
+            curve_input_token_amount,
+            curve_output_token_amount,
+            token_0_price_x64,
+            token_1_price_x64,
+            fee_rates: FeeRates {
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+            },
+            amount_scale: get_amount_scale(
+                &self.input_token_mint.to_account_info(),
+                &self.output_token_mint.to_account_info(),
+            )?,
+            // a quote moves no tokens, there is nothing to check against
+            pool_snapshot: PoolSnapshot::default(),
+        })
+    }
+
+    /// Check the swap would not revert for its size, price impact or output and build the quote
+    fn quote(
+        &self,
+        hop: &SwapRouteHop,
+        result: &SwapResult,
+        input_transfer_fee: u64,
+        output_transfer_fee: u64,
+        block_timestamp: u64,
+    ) -> Result<SwapQuote> {
+        // the virtual reserves only deepen the curve, the output is paid from the real reserve
+        require_gt!(
+            u128::from(hop.total_output_token_amount),
+            result.destination_amount_swapped,
+            ErrorCode::ExceededVirtualReserveRange
+        );
+        self.pool_state.load()?.check_swap_outflow(
+            hop.trade_direction,
+            hop.vault_0_amount,
+            hop.vault_1_amount,
+            u64::try_from(result.destination_amount_swapped).unwrap(),
+        )?;
+        self.pool_state
+            .load()?
+            .check_trade_size(hop.trade_direction, result)?;
+        self.pool_state.load()?.check_price_impact(
+            hop.curve_input_token_amount,
+            hop.curve_output_token_amount,
+            result.new_swap_source_amount,
+            result.new_swap_destination_amount,
+        )?;
+        let pool_state = self.pool_state.load()?;
+        let reserves_before = PoolReserves::from_trade_amounts(
+            hop.trade_direction,
+            hop.total_input_token_amount,
+            hop.total_output_token_amount,
+            pool_state.lp_supply,
+        );
+        // the creator fee stays in the vault but leaves the reserves, as in the swap
+        let creator_fee = u64::try_from(Fees::creator_fee(
+            result.trade_fee,
+            pool_state.creator_fee_rate,
+        )?)
+        .unwrap();
+        pool_state.check_price_band(
+            &reserves_before,
+            &reserves_before.after_swap(hop.trade_direction, result, creator_fee),
+            block_timestamp,
+        )?;
+        let price_impact_bps = CurveCalculator::price_impact_bps(
+            hop.curve_input_token_amount,
+            hop.curve_output_token_amount,
+            result.new_swap_source_amount,
+            result.new_swap_destination_amount,
+        )?;
+        let source_amount_swapped = u64::try_from(result.source_amount_swapped).unwrap();
+        let destination_amount_swapped = u64::try_from(result.destination_amount_swapped).unwrap();
+        Ok(SwapQuote {
+            amount_in: source_amount_swapped
+                .checked_add(input_transfer_fee)
+                .unwrap(),
+            amount_out: destination_amount_swapped
+                .checked_sub(output_transfer_fee)
+                .unwrap(),
+            input_transfer_fee,
+            output_transfer_fee,
+            trade_fee: u64::try_from(result.trade_fee).unwrap(),
+            protocol_fee: u64::try_from(result.protocol_fee).unwrap(),
+            fund_fee: u64::try_from(result.fund_fee).unwrap(),
+            price_impact_bps,
+        })
+    }
+
+    /// Quote a swap of `amount_in` input tokens, including the input transfer fee
+    fn quote_base_input(&self, amount_in: u64, block_timestamp: u64) -> Result<SwapQuote> {
+        let hop = self.prepare(block_timestamp)?;
+        let input_transfer_fee =
+            get_transfer_fee(&self.input_token_mint.to_account_info(), amount_in)?;
+        let actual_amount_in = amount_in.saturating_sub(input_transfer_fee);
+        require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+        let result = CurveCalculator::swap_scaled(
+            SwapParams {
+                curve_type: hop.curve_type,
+                amount_scale: hop.amount_scale,
+                fee_rates: hop.fee_rates,
+                fee_on_output: self.amm_config.fee_on_output,
+            },
+            SwapSide::ExactInput,
+            u128::from(actual_amount_in),
+            hop.curve_input_token_amount,
+            hop.curve_output_token_amount,
+        )?;
+        let output_transfer_fee = get_transfer_fee(
+            &self.output_token_mint.to_account_info(),
+            u64::try_from(result.destination_amount_swapped).unwrap(),
+        )?;
+        let quote = self.quote(
+            &hop,
+            &result,
+            input_transfer_fee,
+            output_transfer_fee,
+            block_timestamp,
+        )?;
+        require_gt!(quote.amount_out, 0);
+        Ok(quote)
+    }
+}
+
+pub fn quote_swap_base_input(ctx: Context<QuoteSwap>, amount_in: u64) -> Result<SwapQuote> {
+    let quote = ctx
+        .accounts
+        .quote_base_input(amount_in, oracle::block_timestamp())?;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "quote amount_in:{}, amount_out:{}, trade_fee:{}",
+        quote.amount_in,
+        quote.amount_out,
+        quote.trade_fee
+    );
+    Ok(quote)
+}
+
+pub fn quote_swap_base_output(ctx: Context<QuoteSwap>, amount_out: u64) -> Result<SwapQuote> {
+    require_gt!(amount_out, 0);
+    let block_timestamp = oracle::block_timestamp();
+    let hop = ctx.accounts.prepare(block_timestamp)?;
+    let output_transfer_fee = get_transfer_inverse_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        amount_out,
+    )?;
+    let actual_amount_out = amount_out.checked_add(output_transfer_fee).unwrap();
+
+    let result = CurveCalculator::swap_scaled(
+        SwapParams {
+            curve_type: hop.curve_type,
+            amount_scale: hop.amount_scale,
+            fee_rates: hop.fee_rates,
+            fee_on_output: ctx.accounts.amm_config.fee_on_output,
+        },
+        SwapSide::ExactOutput,
+        u128::from(actual_amount_out),
+        hop.curve_input_token_amount,
+        hop.curve_output_token_amount,
+    )?;
+    let source_amount_swapped = u64::try_from(result.source_amount_swapped).unwrap();
+    require_gt!(source_amount_swapped, 0);
+    let input_transfer_fee = get_transfer_inverse_fee(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        source_amount_swapped,
+    )?;
+    let quote = ctx.accounts.quote(
+        &hop,
+        &result,
+        input_transfer_fee,
+        output_transfer_fee,
+        block_timestamp,
+    )?;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "quote amount_in:{}, amount_out:{}, trade_fee:{}",
+        quote.amount_in,
+        quote.amount_out,
+        quote.trade_fee
+    );
+    Ok(quote)
+}
+
+/// Quote a swap of `amount_in` input tokens in each pool of the remaining accounts, 7
+/// accounts per pool in the order of the `QuoteSwap` accounts. A pool the swap would be
+/// rejected by is quoted `None` instead of failing the other quotes
+pub fn quote_many<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, QuoteMany>,
+    amount_in: u64,
+) -> Result<Vec<Option<SwapQuote>>> {
+    let mut remaining_accounts = ctx.remaining_accounts;
+    require!(
+        !remaining_accounts.is_empty()
+            && remaining_accounts.len().is_multiple_of(QUOTE_SWAP_ACCOUNTS)
+            && remaining_accounts.len() <= QUOTE_SWAP_ACCOUNTS * MAX_QUOTE_POOLS,
+        ErrorCode::InvalidInput
+    );
+    let block_timestamp = oracle::block_timestamp();
+    let mut quotes = Vec::with_capacity(remaining_accounts.len() / QUOTE_SWAP_ACCOUNTS);
+    while !remaining_accounts.is_empty() {
+        let accounts = QuoteSwap::try_accounts(
+            ctx.program_id,
+            &mut remaining_accounts,
+            &[],
+            &mut QuoteSwapBumps::default(),
+            &mut BTreeSet::new(),
+        )?;
+        quotes.push(accounts.quote_base_input(amount_in, block_timestamp).ok());
+    }
+    Ok(quotes)
+}
@@ -0,0 +1,386 @@
+use crate::curve::calculator::CurveCalculator;
+use crate::curve::{
+    ConstantProductCurve, CurveType, FeeRates, TradeDirection, FEE_RATE_DENOMINATOR_VALUE,
+};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RebalanceToTargetPrice<'info> {
+    /// The custom authority of the pool, pays the swap from its protocol owned reserves
+    #[account(constraint = (pool_state.load()?.is_custom_authority() && custom_authority.key() == pool_state.load()?.custom_authority) @ ErrorCode::InvalidAuthority)]
+    pub custom_authority: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool rebalanced
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The custom authority token account for token_0
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = custom_authority
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The custom authority token account for token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = custom_authority
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for token_0 transfers
+    pub token_0_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for token_1 transfers
+    pub token_1_program: Interface<'info, TokenInterface>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint,
+        mint::token_program = token_0_program,
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint,
+        mint::token_program = token_1_program,
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn rebalance_to_target_price<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, RebalanceToTargetPrice<'info>>,
+    target_sqrt_price_x64: u128,
+    max_amount_in: u64,
+) -> Result<()> {
+    let block_timestamp = oracle::block_timestamp();
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_swap_allowed(block_timestamp)?;
+    pool_state.check_derived_swap_allowed(&ctx.accounts.amm_config)?;
+    for mint in [&ctx.accounts.vault_0_mint, &ctx.accounts.vault_1_mint] {
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(get_transfer_fee_bps(&mint.to_account_info())?)?;
+    }
+    pool_state.lock()?;
+    // the amount moving the price to the target is solved on the constant product curve
+    let curve_type = pool_state.curve(block_timestamp)?;
+    require!(
+        curve_type == CurveType::ConstantProduct,
+        ErrorCode::InvalidCurveType
+    );
+
+    let vault_0_amount = ctx.accounts.token_0_vault.amount;
+    let vault_1_amount = ctx.accounts.token_1_vault.amount;
+    let (total_token_0_amount, total_token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    let (curve_token_0_amount, curve_token_1_amount) =
+        pool_state.curve_amounts(total_token_0_amount, total_token_1_amount);
+    let (trade_direction, source_amount) = ConstantProductCurve::source_amount_to_sqrt_price(
+        curve_token_0_amount,
+        curve_token_1_amount,
+        target_sqrt_price_x64,
+    )?
+    .ok_or(ErrorCode::TargetPriceReached)?;
+    let (
+        input_token_account,
+        output_token_account,
+        input_vault,
+        output_vault,
+        input_token_mint,
+        output_token_mint,
+        input_token_program,
+        output_token_program,
+    ) = match trade_direction {
+        TradeDirection::ZeroForOne => (
+            &ctx.accounts.token_0_account,
+            &ctx.accounts.token_1_account,
+            &ctx.accounts.token_0_vault,
+            &ctx.accounts.token_1_vault,
+            &ctx.accounts.vault_0_mint,
+            &ctx.accounts.vault_1_mint,
+            &ctx.accounts.token_0_program,
+            &ctx.accounts.token_1_program,
+        ),
+        TradeDirection::OneForZero => (
+            &ctx.accounts.token_1_account,
+            &ctx.accounts.token_0_account,
+            &ctx.accounts.token_1_vault,
+            &ctx.accounts.token_0_vault,
+            &ctx.accounts.vault_1_mint,
+            &ctx.accounts.vault_0_mint,
+            &ctx.accounts.token_1_program,
+            &ctx.accounts.token_0_program,
+        ),
+    };
+    let pool_vaults = PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+    pool_state.check_launch_trade(trade_direction, false)?;
+    let (token_0_price_x64, token_1_price_x64) =
+        pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+    let (total_input_token_amount, total_output_token_amount) = match trade_direction {
+        TradeDirection::ZeroForOne => (total_token_0_amount, total_token_1_amount),
+        TradeDirection::OneForZero => (total_token_1_amount, total_token_0_amount),
+    };
+    let (curve_input_token_amount, curve_output_token_amount) = pool_state.curve_trade_amounts(
+        trade_direction,
+        total_input_token_amount,
+        total_output_token_amount,
+    );
+
+    let trade_fee_rate = {
+        let (observation_state, observation_extension, _) =
+            ctx.accounts.observation_state.load_observations()?;
+        ctx.accounts.amm_config.swap_trade_fee_rate(
+            pool_state.trade_fee_rate_override(block_timestamp),
+            &observation_state,
+            &observation_extension,
+            block_timestamp,
+            token_0_price_x64,
+        )
+    };
+    // the trade fee stays in the input reserve without moving the price, so the input
+    // is grossed up by it
+    let net_input_rate = u128::from(FEE_RATE_DENOMINATOR_VALUE - trade_fee_rate);
+    let amount_to_target = source_amount
+        .checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))
+        .and_then(|amount| amount.checked_add(net_input_rate - 1))
+        .map(|amount| amount / net_input_rate)
+        .and_then(|amount| u64::try_from(amount).ok())
+        .unwrap_or(u64::MAX);
+    let transfer_amount_to_target = amount_to_target.saturating_add(get_transfer_inverse_fee(
+        &input_token_mint.to_account_info(),
+        amount_to_target,
+    )?);
+    let target_reached = transfer_amount_to_target <= max_amount_in;
+    let amount_in = transfer_amount_to_target.min(max_amount_in);
+    let transfer_fee = get_transfer_fee(&input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+    let amount_scale = get_amount_scale(
+        &input_token_mint.to_account_info(),
+        &output_token_mint.to_account_info(),
+    )?;
+    let constant_before = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        curve_input_token_amount,
+        curve_output_token_amount,
+    )
+    .unwrap();
+
+    let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&ctx.accounts.amm_config);
+    let result = CurveCalculator::swap_base_input_scaled(
+        curve_type,
+        amount_scale,
+        u128::from(actual_amount_in),
+        curve_input_token_amount,
+        curve_output_token_amount,
+        FeeRates {
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+        },
+    )?;
+
+    let constant_after = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        result
+            .new_swap_source_amount
+            .checked_sub(result.trade_fee)
+            .unwrap(),
+        result.new_swap_destination_amount,
+    )
+    .unwrap();
+    require_gte!(
+        constant_after,
+        constant_before,
+        ErrorCode::CurveInvariantViolated
+    );
+    require_eq!(
+        u64::try_from(result.source_amount_swapped).unwrap(),
+        actual_amount_in
+    );
+    pool_state.check_auction_size(actual_amount_in)?;
+    // the virtual reserves only deepen the curve, the output is paid from the real reserve
+    require_gt!(
+        u128::from(total_output_token_amount),
+        result.destination_amount_swapped,
+        ErrorCode::ExceededVirtualReserveRange
+    );
+    pool_state.check_swap_outflow(
+        trade_direction,
+        vault_0_amount,
+        vault_1_amount,
+        u64::try_from(result.destination_amount_swapped).unwrap(),
+    )?;
+    pool_state.check_price_impact(
+        curve_input_token_amount,
+        curve_output_token_amount,
+        result.new_swap_source_amount,
+        result.new_swap_destination_amount,
+    )?;
+
+    let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
+    let output_transfer_fee = get_transfer_fee(&output_token_mint.to_account_info(), amount_out)?;
+    require_gt!(amount_out.checked_sub(output_transfer_fee).unwrap(), 0);
+
+    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(result.fund_fee).unwrap();
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        }
+        TradeDirection::OneForZero => {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    };
+    let creator_fee = pool_state.accrue_creator_fee(trade_direction, result.trade_fee);
+    pool_state.record_swap(trade_direction, &result, creator_fee, block_timestamp);
+
+    let reserves_before = PoolReserves::from_trade_amounts(
+        trade_direction,
+        total_input_token_amount,
+        total_output_token_amount,
+        pool_state.lp_supply,
+    );
+    let reserves_after = reserves_before.after_swap(trade_direction, &result, creator_fee);
+    pool_state.check_price_band(&reserves_before, &reserves_after, block_timestamp)?;
+    emit!(SwapEvent {
+        pool_id,
+        input_vault_before: total_input_token_amount,
+        output_vault_before: total_output_token_amount,
+        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
+        output_amount: amount_out,
+        input_transfer_fee: transfer_fee,
+        output_transfer_fee,
+        base_input: true,
+        trade_fee: u64::try_from(result.trade_fee).unwrap(),
+        protocol_fee,
+        fund_fee,
+        referral_fee: 0,
+        creator_fee,
+        reserves_before,
+        reserves_after,
+    });
+    emit!(RebalanceToTargetPriceEvent {
+        pool_id,
+        target_sqrt_price_x64,
+        zero_for_one: trade_direction == TradeDirection::ZeroForOne,
+        amount_in,
+        amount_out,
+        target_reached,
+    });
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.custom_authority.to_account_info(),
+        input_token_account.to_account_info(),
+        input_vault.to_account_info(),
+        input_token_mint.to_account_info(),
+        input_token_program.to_account_info(),
+        amount_in,
+        input_token_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        output_vault.to_account_info(),
+        output_token_account.to_account_info(),
+        output_token_mint.to_account_info(),
+        output_token_program.to_account_info(),
+        amount_out,
+        output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    // update the previous price to the observation
+    let (mut observation_state, mut observation_extension, mut log_price_observations) =
+        ctx.accounts.observation_state.load_observations_mut()?;
+    if let Some(deviation_bps) = pool_state.update_circuit_breaker(
+        &observation_state,
+        &observation_extension,
+        block_timestamp,
+        token_0_price_x64,
+        token_1_price_x64,
+        &reserves_after,
+    ) {
+        emit!(CircuitBreakerTrippedEvent {
+            pool_id,
+            deviation_bps,
+            threshold_bps: pool_state.circuit_breaker_threshold_bps,
+            window: pool_state.circuit_breaker_window,
+        });
+    }
+    observation_state.update(
+        &mut observation_extension,
+        &mut log_price_observations,
+        block_timestamp,
+        token_0_price_x64,
+        token_1_price_x64,
+        ctx.accounts.amm_config.observation_update_duration(),
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(pool_state, None, Some(&pool_snapshot))?;
+    pool_state.unlock();
+
+    Ok(())
+}
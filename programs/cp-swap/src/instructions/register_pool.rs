@@ -0,0 +1,68 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RegisterPool<'info> {
+    /// Pays to create the registry of the mint pair. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The pool to list
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config the pool belongs to
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: Token_0 mint of the pool, checked by address
+    #[account(address = pool_state.load()?.token_0_mint)]
+    pub token_0_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token_1 mint of the pool, checked by address
+    #[account(address = pool_state.load()?.token_1_mint)]
+    pub token_1_mint: UncheckedAccount<'info>,
+
+    /// The registry of the mint pair
+    #[account(
+        init_if_needed,
+        seeds = [
+            POOL_REGISTRY_SEED.as_bytes(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PoolRegistry::LEN
+    )]
+    pub pool_registry: Box<Account<'info, PoolRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+    let pool_registry = &mut ctx.accounts.pool_registry;
+    pool_registry.bump = ctx.bumps.pool_registry;
+    pool_registry.token_0_mint = ctx.accounts.token_0_mint.key();
+    pool_registry.token_1_mint = ctx.accounts.token_1_mint.key();
+
+    let pool = RegisteredPool {
+        pool_id: ctx.accounts.pool_state.key(),
+        amm_config: ctx.accounts.amm_config.key(),
+        amm_config_index: ctx.accounts.amm_config.index,
+    };
+    require!(
+        !pool_registry.is_registered(&pool.pool_id),
+        ErrorCode::PoolAlreadyRegistered
+    );
+    require!(pool_registry.add_pool(pool), ErrorCode::PoolRegistryFull);
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "register pool:{}, amm_config_index:{}, pool_count:{}",
+        pool.pool_id.to_string(),
+        pool.amm_config_index,
+        pool_registry.pool_count
+    );
+    Ok(())
+}
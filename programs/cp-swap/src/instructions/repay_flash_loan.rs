@@ -0,0 +1,146 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// Position of `pool_state` in the `RepayFlashLoan` accounts, checked by `flash_loan`
+pub const REPAY_FLASH_LOAN_POOL_STATE_INDEX: usize = 1;
+
+#[derive(Accounts)]
+pub struct RepayFlashLoan<'info> {
+    /// The payer of the loan, may be different from the borrower
+    pub payer: Signer<'info>,
+
+    /// Pool state account
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The payer's token account for token_0
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = payer
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer's token account for token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = payer
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn repay_flash_loan<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, RepayFlashLoan<'info>>,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    require!(
+        pool_state.is_flash_loan_active(),
+        ErrorCode::InvalidFlashLoan
+    );
+    pool_state.lock()?;
+
+    // The vaults must receive the full amount owed, so transfer fees are paid on top
+    let repay_amount_0 = pool_state.flash_loan_amount_0;
+    let repay_amount_1 = pool_state.flash_loan_amount_1;
+    let transfer_amount_0 = if repay_amount_0 == 0 {
+        0
+    } else {
+        repay_amount_0
+            .checked_add(get_transfer_inverse_fee(
+                &ctx.accounts.vault_0_mint.to_account_info(),
+                repay_amount_0,
+            )?)
+            .ok_or(ErrorCode::MathOverflow)?
+    };
+    let transfer_amount_1 = if repay_amount_1 == 0 {
+        0
+    } else {
+        repay_amount_1
+            .checked_add(get_transfer_inverse_fee(
+                &ctx.accounts.vault_1_mint.to_account_info(),
+                repay_amount_1,
+            )?)
+            .ok_or(ErrorCode::MathOverflow)?
+    };
+    pool_state.flash_loan_amount_0 = 0;
+    pool_state.flash_loan_amount_1 = 0;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        transfer_amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        transfer_amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    )
+    .check(pool_state, None, None)?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_state.unlock();
+
+    Ok(())
+}
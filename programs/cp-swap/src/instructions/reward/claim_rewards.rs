@@ -0,0 +1,125 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct ClaimRewards<'info> {
+    /// Owner of the position, pays to create its reward account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: reward vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the rewards are emitted to
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The position earning the rewards
+    #[account(
+        has_one = owner @ ErrorCode::InvalidOwner,
+        constraint = personal_position.pool_id == pool_state.key() @ ErrorCode::InvalidInput
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The reward emissions of the pool
+    #[account(
+        mut,
+        seeds = [
+            REWARD_STATE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = reward_state.bump,
+    )]
+    pub reward_state: Box<Account<'info, RewardState>>,
+
+    /// The rewards earned by the position, created by its first claim or share change
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_REWARD_SEED.as_bytes(),
+            personal_position.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PositionRewardState::LEN
+    )]
+    pub position_reward: Box<Account<'info, PositionRewardState>>,
+
+    /// The mint of the claimed reward
+    #[account(
+        address = reward_state.reward_infos.get(usize::from(reward_index)).ok_or(ErrorCode::InvalidInput)?.mint @ ErrorCode::InvalidInput,
+        mint::token_program = token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Holds the funded rewards
+    #[account(
+        mut,
+        address = reward_state.reward_infos[usize::from(reward_index)].vault @ ErrorCode::InvalidVault
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account receiving the rewards
+    #[account(
+        mut,
+        token::mint = reward_mint,
+    )]
+    pub owner_reward_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// To create the position reward account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_rewards<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ClaimRewards<'info>>,
+    reward_index: u8,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let reward_state = &mut ctx.accounts.reward_state;
+    reward_state.update(pool_state.lp_supply, block_timestamp);
+    let position_reward = &mut ctx.accounts.position_reward;
+    if !position_reward.is_initialized() {
+        position_reward.initialize(
+            ctx.bumps.position_reward,
+            ctx.accounts.personal_position.key(),
+        );
+    }
+    position_reward.accrue(reward_state, ctx.accounts.personal_position.shares);
+    let amount = position_reward.take_rewards(usize::from(reward_index));
+    require_gt!(amount, 0, ErrorCode::InvalidInput);
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.owner_reward_token.to_account_info(),
+        ctx.accounts.reward_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    emit!(ClaimRewardsEvent {
+        pool_id,
+        personal_position: ctx.accounts.personal_position.key(),
+        owner: ctx.accounts.owner.key(),
+        reward_mint: ctx.accounts.reward_mint.key(),
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct InitializeReward<'info> {
+    /// Admin or the pool creator for the first reward of the pool, then the authority of
+    /// the reward state. Funds the reward emissions
+    #[account(mut)]
+    pub reward_authority: Signer<'info>,
+
+    /// CHECK: reward vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool the rewards are emitted to
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The reward emissions of the pool, created with its first reward
+    #[account(
+        init_if_needed,
+        seeds = [
+            REWARD_STATE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = reward_authority,
+        space = RewardState::LEN
+    )]
+    pub reward_state: Box<Account<'info, RewardState>>,
+
+    /// The mint of the reward
+    #[account(mint::token_program = token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Holds the funded rewards
+    #[account(
+        init,
+        seeds = [
+            REWARD_VAULT_SEED.as_bytes(),
+            reward_state.key().as_ref(),
+            reward_mint.key().as_ref(),
+        ],
+        bump,
+        token::mint = reward_mint,
+        token::authority = authority,
+        token::token_program = token_program,
+        payer = reward_authority,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The reward authority token account funding the emissions
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = reward_authority
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// To create the reward accounts
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_reward<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, InitializeReward<'info>>,
+    emissions_per_second: u64,
+    end_time: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    // the shares of positions can not be transferred, so their rewards are tracked exactly
+    require!(pool_state.is_position_mode(), ErrorCode::NotApproved);
+    let reward_authority = ctx.accounts.reward_authority.key();
+    let reward_state = &mut ctx.accounts.reward_state;
+    if reward_state.pool_id == Pubkey::default() {
+        require!(
+            reward_authority == crate::admin::ID || reward_authority == pool_state.pool_creator,
+            ErrorCode::InvalidAuthority
+        );
+        reward_state.bump = ctx.bumps.reward_state;
+        reward_state.pool_id = pool_id;
+        reward_state.authority = reward_authority;
+        reward_state.last_update_time = block_timestamp;
+    }
+    require_keys_eq!(
+        reward_authority,
+        reward_state.authority,
+        ErrorCode::InvalidAuthority
+    );
+
+    reward_state.update(pool_state.lp_supply, block_timestamp);
+    let index = reward_state.add_reward(
+        ctx.accounts.reward_mint.key(),
+        ctx.accounts.reward_vault.key(),
+    )?;
+    let funded_amount =
+        reward_state.set_emissions(index, emissions_per_second, end_time, block_timestamp)?;
+    if funded_amount > 0 {
+        let transfer_fee =
+            get_transfer_inverse_fee(&ctx.accounts.reward_mint.to_account_info(), funded_amount)?;
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.reward_authority.to_account_info(),
+            ctx.accounts.funder_token_account.to_account_info(),
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.reward_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            funded_amount.checked_add(transfer_fee).unwrap(),
+            ctx.accounts.reward_mint.decimals,
+            ctx.remaining_accounts,
+        )?;
+    }
+    pool_state.rewards_enabled = 1;
+
+    emit!(RewardEmissionsUpdatedEvent {
+        pool_id,
+        reward_mint: ctx.accounts.reward_mint.key(),
+        emissions_per_second,
+        end_time,
+        funded_amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,8 @@
+pub mod initialize_reward;
+pub use initialize_reward::*;
+
+pub mod set_reward_emissions;
+pub use set_reward_emissions::*;
+
+pub mod claim_rewards;
+pub use claim_rewards::*;
@@ -0,0 +1,95 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct SetRewardEmissions<'info> {
+    /// The authority of the reward state, funds the reward emissions
+    pub reward_authority: Signer<'info>,
+
+    /// The pool the rewards are emitted to
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The reward emissions of the pool
+    #[account(
+        mut,
+        seeds = [
+            REWARD_STATE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = reward_state.bump,
+        constraint = reward_state.authority == reward_authority.key() @ ErrorCode::InvalidAuthority
+    )]
+    pub reward_state: Box<Account<'info, RewardState>>,
+
+    /// The mint of the reward
+    #[account(
+        address = reward_state.reward_infos.get(usize::from(reward_index)).ok_or(ErrorCode::InvalidInput)?.mint @ ErrorCode::InvalidInput,
+        mint::token_program = token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Holds the funded rewards
+    #[account(
+        mut,
+        address = reward_state.reward_infos[usize::from(reward_index)].vault @ ErrorCode::InvalidVault
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The reward authority token account funding the emissions
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = reward_authority
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn set_reward_emissions<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SetRewardEmissions<'info>>,
+    reward_index: u8,
+    emissions_per_second: u64,
+    end_time: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let lp_supply = ctx.accounts.pool_state.load()?.lp_supply;
+    let reward_state = &mut ctx.accounts.reward_state;
+    // the rewards emitted so far are accrued at the previous emissions
+    reward_state.update(lp_supply, block_timestamp);
+    let funded_amount = reward_state.set_emissions(
+        usize::from(reward_index),
+        emissions_per_second,
+        end_time,
+        block_timestamp,
+    )?;
+    if funded_amount > 0 {
+        let transfer_fee =
+            get_transfer_inverse_fee(&ctx.accounts.reward_mint.to_account_info(), funded_amount)?;
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.reward_authority.to_account_info(),
+            ctx.accounts.funder_token_account.to_account_info(),
+            ctx.accounts.reward_vault.to_account_info(),
+            ctx.accounts.reward_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            funded_amount.checked_add(transfer_fee).unwrap(),
+            ctx.accounts.reward_mint.decimals,
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    emit!(RewardEmissionsUpdatedEvent {
+        pool_id,
+        reward_mint: ctx.accounts.reward_mint.key(),
+        emissions_per_second,
+        end_time,
+        funded_amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Approve, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSessionAuthority<'info> {
+    /// Owner of the liquidity, pays to create the session
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The pool the session manages the liquidity of
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Delegates the liquidity management of the owner to the session key
+    #[account(
+        init,
+        seeds = [
+            SESSION_AUTHORITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            session_key.as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = SessionAuthority::LEN
+    )]
+    pub session_authority: Box<Account<'info, SessionAuthority>>,
+
+    /// Owner lp token account, the session authority becomes its delegate
+    #[account(
+        mut,
+        token::authority = owner,
+        constraint = owner_lp_token.mint == pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint
+    )]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner token account for token_0, the session authority becomes its delegate
+    #[account(
+        mut,
+        token::authority = owner,
+        constraint = token_0_account.mint == pool_state.load()?.token_0_mint @ ErrorCode::InvalidInput
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner token account for token_1, the session authority becomes its delegate
+    #[account(
+        mut,
+        token::authority = owner,
+        constraint = token_1_account.mint == pool_state.load()?.token_1_mint @ ErrorCode::InvalidInput
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// To create the session authority
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_session_authority(
+    ctx: Context<CreateSessionAuthority>,
+    session_key: Pubkey,
+    expires_at: i64,
+    deposit_lp_limit: u64,
+    withdraw_lp_limit: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+) -> Result<()> {
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        ErrorCode::SessionExpired
+    );
+    require_keys_neq!(session_key, ctx.accounts.owner.key());
+
+    // the session authority spends the owner tokens as their delegate, bounded by the
+    // approved amounts
+    for (token_account, amount) in [
+        (&ctx.accounts.owner_lp_token, withdraw_lp_limit),
+        (&ctx.accounts.token_0_account, maximum_token_0_amount),
+        (&ctx.accounts.token_1_account, maximum_token_1_amount),
+    ] {
+        token_interface::approve(
+            CpiContext::new(
+                if token_account.to_account_info().owner == ctx.accounts.token_program.key {
+                    ctx.accounts.token_program.to_account_info()
+                } else {
+                    ctx.accounts.token_program_2022.to_account_info()
+                },
+                Approve {
+                    to: token_account.to_account_info(),
+                    delegate: ctx.accounts.session_authority.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    let session_authority = &mut ctx.accounts.session_authority;
+    session_authority.bump = ctx.bumps.session_authority;
+    session_authority.pool_id = ctx.accounts.pool_state.key();
+    session_authority.owner = ctx.accounts.owner.key();
+    session_authority.session_key = session_key;
+    session_authority.expires_at = expires_at;
+    session_authority.deposit_lp_limit = deposit_lp_limit;
+    session_authority.withdraw_lp_limit = withdraw_lp_limit;
+    Ok(())
+}
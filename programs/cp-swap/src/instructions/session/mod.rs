@@ -0,0 +1,11 @@
+pub mod create_session_authority;
+pub use create_session_authority::*;
+
+pub mod revoke_session_authority;
+pub use revoke_session_authority::*;
+
+pub mod session_deposit;
+pub use session_deposit::*;
+
+pub mod session_withdraw;
+pub use session_withdraw::*;
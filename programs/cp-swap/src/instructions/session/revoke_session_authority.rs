@@ -0,0 +1,65 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Revoke, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RevokeSessionAuthority<'info> {
+    /// Owner of the liquidity, receives the rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The session to revoke, closed once revoked
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::InvalidOwner,
+        close = owner
+    )]
+    pub session_authority: Box<Account<'info, SessionAuthority>>,
+
+    /// Owner lp token account delegated to the session authority
+    #[account(mut, token::authority = owner)]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner token account for token_0 delegated to the session authority
+    #[account(mut, token::authority = owner)]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner token account for token_1 delegated to the session authority
+    #[account(mut, token::authority = owner)]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn revoke_session_authority(ctx: Context<RevokeSessionAuthority>) -> Result<()> {
+    let session_authority = ctx.accounts.session_authority.key();
+    for token_account in [
+        &ctx.accounts.owner_lp_token,
+        &ctx.accounts.token_0_account,
+        &ctx.accounts.token_1_account,
+    ] {
+        // the owner may have delegated the account to someone else since
+        if token_account.delegate != COption::Some(session_authority) {
+            continue;
+        }
+        token_interface::revoke(CpiContext::new(
+            if token_account.to_account_info().owner == ctx.accounts.token_program.key {
+                ctx.accounts.token_program.to_account_info()
+            } else {
+                ctx.accounts.token_program_2022.to_account_info()
+            },
+            Revoke {
+                source: token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ))?;
+    }
+    Ok(())
+}
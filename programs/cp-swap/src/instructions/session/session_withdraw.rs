@@ -0,0 +1,272 @@
+use crate::curve::CurveCalculator;
+use crate::curve::RoundDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SessionWithdraw<'info> {
+    /// The session key managing the liquidity of the owner
+    pub session_key: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state account
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The session, delegate of the owner lp token account
+    #[account(
+        mut,
+        has_one = session_key @ ErrorCode::InvalidAuthority,
+        constraint = session_authority.pool_id == pool_state.key() @ ErrorCode::InvalidInput
+    )]
+    pub session_authority: Box<Account<'info, SessionAuthority>>,
+
+    /// Owner lp token account the lp tokens are burnt from
+    #[account(mut, token::authority = session_authority.owner)]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving token_0, the session key can not withdraw
+    /// anywhere else
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = session_authority.owner
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account receiving token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = session_authority.owner
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Pool lp token mint
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn session_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SessionWithdraw<'info>>,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    ctx.accounts
+        .session_authority
+        .use_withdraw_limit(Clock::get()?.unix_timestamp, lp_token_amount)?;
+    let owner = ctx.accounts.session_authority.owner;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
+        return err!(ErrorCode::NotApproved);
+    }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    pool_state.check_withdraw_without_receipt()?;
+    pool_state.lock()?;
+    let pool_vaults = PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+
+    // For custom authority pools, only the custom authority can withdraw
+    if pool_state.is_custom_authority() {
+        require!(
+            owner == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Floor,
+    )?;
+    if results.token_0_amount == 0 || results.token_1_amount == 0 {
+        return err!(ErrorCode::ZeroTradingTokens);
+    }
+    let token_0_amount = u64::try_from(results.token_0_amount).unwrap();
+    let token_0_amount = std::cmp::min(total_token_0_amount, token_0_amount);
+    let token_0_transfer_fee =
+        get_transfer_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
+    let receive_token_0_amount = token_0_amount.checked_sub(token_0_transfer_fee).unwrap();
+    let token_1_amount = u64::try_from(results.token_1_amount).unwrap();
+    let token_1_amount = std::cmp::min(total_token_1_amount, token_1_amount);
+    let token_1_transfer_fee =
+        get_transfer_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
+    let receive_token_1_amount = token_1_amount.checked_sub(token_1_transfer_fee).unwrap();
+    if receive_token_0_amount < minimum_token_0_amount
+        || receive_token_1_amount < minimum_token_1_amount
+    {
+        return Err(ErrorCode::ExceededSlippage.into());
+    }
+    let lp_supply = pool_state
+        .lp_supply
+        .checked_sub(lp_token_amount)
+        .ok_or(ErrorCode::LpSupplyUnderflow)?;
+
+    emit!(LpChangeEvent {
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount: receive_token_0_amount,
+        token_1_amount: receive_token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        change_type: 1
+    });
+    emit!(WithdrawEvent {
+        pool_id,
+        owner,
+        lp_amount: lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            total_token_0_amount,
+            total_token_1_amount,
+            pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            total_token_0_amount.checked_sub(token_0_amount).unwrap(),
+            total_token_1_amount.checked_sub(token_1_amount).unwrap(),
+            lp_supply,
+        ),
+    });
+
+    pool_state.set_lp_supply(lp_supply);
+    // the session authority burns the owner lp tokens as their delegate
+    let session_key = ctx.accounts.session_key.key();
+    token_burn(
+        ctx.accounts.session_authority.to_account_info(),
+        if ctx.accounts.lp_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        lp_token_amount,
+        &[&[
+            SESSION_AUTHORITY_SEED.as_bytes(),
+            pool_id.as_ref(),
+            owner.as_ref(),
+            session_key.as_ref(),
+            &[ctx.accounts.session_authority.bump],
+        ]],
+    )?;
+
+    // the reserves lent to the strategy are not in the vaults to pay the withdrawal
+    pool_state.check_vault_outflow(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+        token_0_amount,
+        token_1_amount,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        token_0_amount,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        token_1_amount,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        Some(&pool_snapshot),
+    )?;
+    pool_state.unlock();
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolMetadata<'info> {
+    /// The pool creator or the custom authority of the pool, pays to create the metadata
+    #[account(
+        mut,
+        constraint = (authority.key() == pool_state.load()?.pool_creator || (pool_state.load()?.is_custom_authority() && authority.key() == pool_state.load()?.custom_authority)) @ ErrorCode::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    /// The pool described
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The display data of the pool
+    #[account(
+        init_if_needed,
+        seeds = [
+            POOL_METADATA_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = PoolMetadata::LEN
+    )]
+    pub pool_metadata: Box<Account<'info, PoolMetadata>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_pool_metadata(
+    ctx: Context<SetPoolMetadata>,
+    name: String,
+    uri: String,
+    tags: Vec<String>,
+) -> Result<()> {
+    let pool_metadata = &mut ctx.accounts.pool_metadata;
+    pool_metadata.bump = ctx.bumps.pool_metadata;
+    pool_metadata.pool_id = ctx.accounts.pool_state.key();
+    require!(pool_metadata.set(name, uri, tags), ErrorCode::InvalidInput);
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool metadata, name:{}, uri:{}",
+        pool_metadata.name,
+        pool_metadata.uri
+    );
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SnapshotObservation<'info> {
+    /// The factory state to read the observation update duration
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool whose price is observed
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn snapshot_observation(ctx: Context<SnapshotObservation>) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    // the vault balances do not reflect the pool price while it is used by a flash loan
+    // or the instruction calling back into it
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    require!(!pool_state.is_locked(), ErrorCode::ReentrancyDetected);
+
+    let vault_0_amount = ctx.accounts.token_0_vault.amount;
+    let vault_1_amount = ctx.accounts.token_1_vault.amount;
+    let (token_0_amount, token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    require!(
+        token_0_amount > 0 && token_1_amount > 0,
+        ErrorCode::ZeroTradingTokens
+    );
+    let block_timestamp = oracle::block_timestamp();
+    let (token_0_price_x32, token_1_price_x32) =
+        pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+
+    // no observation is written until the update duration passed since the last one
+    let (mut observation_state, mut observation_extension, mut log_price_observations) =
+        ctx.accounts.observation_state.load_observations_mut()?;
+    observation_state.update(
+        &mut observation_extension,
+        &mut log_price_observations,
+        block_timestamp,
+        token_0_price_x32,
+        token_1_price_x32,
+        ctx.accounts.amm_config.observation_update_duration(),
+    );
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "snapshot observation, token_0_price_x32:{}, token_1_price_x32:{}, observation_index:{}",
+        token_0_price_x32,
+        token_1_price_x32,
+        { observation_state.observation_index }
+    );
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct StrategyRepay<'info> {
+    /// The payer of the borrowed reserves, may be different from the strategy
+    pub payer: Signer<'info>,
+
+    /// Pool state account
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The payer's token account for token_0
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = payer
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer's token account for token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = payer
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn strategy_repay<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, StrategyRepay<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+) -> Result<()> {
+    require!(amount_0 > 0 || amount_1 > 0, ErrorCode::InvalidInput);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    require!(pool_state.is_strategy_borrowed(), ErrorCode::InvalidInput);
+    pool_state.lock()?;
+
+    let vault_0_amount = ctx.accounts.token_0_vault.amount;
+    let vault_1_amount = ctx.accounts.token_1_vault.amount;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    // Only the tokens the vaults received repay the strategy, net of transfer fees
+    ctx.accounts.token_0_vault.reload()?;
+    ctx.accounts.token_1_vault.reload()?;
+    let token_0_amount = ctx
+        .accounts
+        .token_0_vault
+        .amount
+        .checked_sub(vault_0_amount)
+        .unwrap();
+    let token_1_amount = ctx
+        .accounts
+        .token_1_vault
+        .amount
+        .checked_sub(vault_1_amount)
+        .unwrap();
+    let (token_0_yield, token_1_yield) = pool_state.strategy_repay(token_0_amount, token_1_amount);
+
+    emit!(StrategyRepayEvent {
+        pool_id,
+        payer: ctx.accounts.payer.key(),
+        token_0_amount,
+        token_1_amount,
+        token_0_yield,
+        token_1_yield,
+        strategy_borrowed_0: pool_state.strategy_borrowed_0,
+        strategy_borrowed_1: pool_state.strategy_borrowed_1,
+    });
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    )
+    .check(pool_state, None, None)?;
+    pool_state.unlock();
+
+    Ok(())
+}
@@ -1,8 +1,11 @@
-use crate::curve::calculator::CurveCalculator;
-use crate::curve::TradeDirection;
+use crate::curve::calculator::{CurveCalculator, SwapParams, SwapResult};
+use crate::curve::fees::{FeeRates, Fees};
+use crate::curve::{SwapSide, TradeDirection};
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::utils::load_oracle_price;
 use crate::utils::token::*;
+use crate::utils::PoolVaults;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
@@ -10,6 +13,7 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 #[derive(Accounts)]
 pub struct Swap<'info> {
     /// The user performing the swap
+    #[account(mut)]
     pub payer: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -71,17 +75,363 @@ pub struct Swap<'info> {
     /// The program account for the most recent oracle observation
     #[account(mut, address = pool_state.load()?.observation_key)]
     pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The referrer token account for the fee token, the output token when the amm config
+    /// charges the fee on the output token and the input token otherwise, receives the
+    /// referral share of the trade fee
+    #[account(
+        mut,
+        constraint = referrer_token_account.mint == if amm_config.fee_on_output { output_vault.mint } else { input_vault.mint } @ ErrorCode::InvalidInput,
+    )]
+    pub referrer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Only required to wrap native SOL for a WSOL input or to create the trader stats
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Whitelist entry of the payer, only required to swap in a whitelisted launch phase
+    #[account(
+        seeds = [
+            LAUNCH_WHITELIST_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump = launch_whitelist.bump,
+    )]
+    pub launch_whitelist: Option<Box<Account<'info, LaunchWhitelist>>>,
+
+    /// Registry entry of the payer, only required by the pools with a trader registry
+    #[account(
+        seeds = [
+            TRADER_ENTRY_SEED.as_bytes(),
+            trader_entry.trader_registry.as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump = trader_entry.bump,
+        constraint = trader_entry.trader_registry == pool_state.load()?.trader_registry @ ErrorCode::TraderNotRegistered
+    )]
+    pub trader_entry: Option<Box<Account<'info, TraderEntry>>>,
+
+    /// The payer token account of the fee discount token, only required for the fee discount
+    #[account(
+        token::mint = amm_config.fee_discount_mint,
+        token::authority = payer,
+    )]
+    pub fee_discount_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The stats of the pools of the amm config, updated when provided
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_STATS_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump = protocol_stats.bump,
+    )]
+    pub protocol_stats: Option<Box<Account<'info, ProtocolStats>>>,
+
+    /// The fee exemptions of the amm config, only required to lower the protocol fee of
+    /// a listed payer
+    #[account(
+        seeds = [
+            FEE_EXEMPTION_LIST_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump = fee_exemption_list.bump,
+    )]
+    pub fee_exemption_list: Option<Box<Account<'info, FeeExemptionList>>>,
+
+    /// CHECK: The Pyth price update account of the pool oracle, required when the pool
+    /// is oracle guarded, decoded by `load_oracle_price`
+    #[account(address = pool_state.load()?.price_oracle @ ErrorCode::InvalidOracle)]
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+
+    /// The volume and trade fees of the payer in the pool earning it fee rebates, updated
+    /// when provided and created by the first swap passing it
+    #[account(
+        init_if_needed,
+        seeds = [
+            TRADER_STATS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = TraderStats::LEN
+    )]
+    pub trader_stats: Option<Box<Account<'info, TraderStats>>>,
+
+    /// The compact snapshot of the pool, refreshed when provided
+    #[account(
+        mut,
+        seeds = [
+            POOL_SNAPSHOT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = pool_snapshot.bump,
+    )]
+    pub pool_snapshot: Option<Box<Account<'info, PoolSnapshot>>>,
+
+    /// The lp mint of the pool, only required to rebate a share of the trade fee in lp tokens
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint
+    )]
+    pub lp_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// The payer lp token account receiving the lp fee rebate of the amm config, the lp
+    /// mint and its token program are required with it
+    #[account(
+        mut,
+        token::mint = pool_state.load()?.lp_mint,
+        token::authority = payer,
+    )]
+    pub payer_lp_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Spl token program or token program 2022 of the lp mint
+    pub lp_token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+impl<'info> Swap<'info> {
+    /// Wrap the native SOL paid for a WSOL input into the input token account
+    pub fn wrap_input_sol(&self, wrap_sol: bool, amount: u64) -> Result<()> {
+        if !wrap_sol || !is_native_mint(&self.input_token_mint.key()) {
+            return Ok(());
+        }
+        let system_program = self
+            .system_program
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?;
+        wrap_native_sol(
+            self.payer.to_account_info(),
+            self.input_token_account.to_account_info(),
+            self.input_token_program.to_account_info(),
+            system_program.to_account_info(),
+            amount,
+        )
+    }
+
+    /// Unwrap a WSOL output by closing the output token account to the payer
+    pub fn unwrap_output_sol(&self, wrap_sol: bool) -> Result<()> {
+        if !wrap_sol || !is_native_mint(&self.output_token_mint.key()) {
+            return Ok(());
+        }
+        unwrap_native_sol(
+            self.payer.to_account_info(),
+            self.output_token_account.to_account_info(),
+            self.output_token_program.to_account_info(),
+        )
+    }
+
+    /// Get the referrer share of the trade fee, 0 when no referrer is passed
+    pub fn referral_fee(&self, trade_fee: u128) -> Result<u64> {
+        if self.referrer_token_account.is_none() {
+            return Ok(0);
+        }
+        let referral_fee = Fees::referral_fee(trade_fee, self.amm_config.referral_fee_rate)?;
+        Ok(u64::try_from(referral_fee).unwrap())
+    }
+
+    /// Get the share of the liquidity provider part `lp_fee` of the trade fee rebated to the
+    /// payer in lp tokens, 0 when no lp token account of the payer is passed
+    pub fn lp_fee_rebate(&self, lp_fee: u128) -> Result<u64> {
+        if self.payer_lp_token.is_none() {
+            return Ok(0);
+        }
+        require!(
+            self.lp_mint.is_some() && self.lp_token_program.is_some(),
+            ErrorCode::InvalidInput
+        );
+        let rebate_fee = Fees::lp_fee_rebate(lp_fee, self.amm_config.lp_fee_rebate_rate)?;
+        Ok(u64::try_from(rebate_fee).unwrap())
+    }
+
+    /// Check the execution price of the swap against the oracle price of an oracle
+    /// guarded pool
+    pub fn check_oracle_price(
+        &self,
+        pool_state: &PoolState,
+        block_timestamp: u64,
+        trade_direction: TradeDirection,
+        result: &SwapResult,
+    ) -> Result<()> {
+        if !pool_state.is_oracle_guarded() {
+            return Ok(());
+        }
+        let price_oracle = self.price_oracle.as_ref().ok_or(ErrorCode::InvalidOracle)?;
+        let oracle_price = load_oracle_price(
+            &price_oracle.to_account_info(),
+            block_timestamp,
+            pool_state.oracle_max_age,
+        )?;
+        pool_state.check_oracle_deviation(
+            &oracle_price,
+            trade_direction,
+            result.source_amount_swapped,
+            result.destination_amount_swapped,
+        )
+    }
+
+    /// Send the referrer share of the trade fee out of the vault of the fee token
+    pub fn transfer_referral_fee(
+        &self,
+        pool_id: Pubkey,
+        referral_fee: u64,
+        auth_bump: u8,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let referrer_token_account = match &self.referrer_token_account {
+            Some(referrer_token_account) if referral_fee > 0 => referrer_token_account,
+            _ => return Ok(()),
+        };
+        let (fee_vault, fee_mint, fee_token_program) = if self.amm_config.fee_on_output {
+            (
+                &self.output_vault,
+                &self.output_token_mint,
+                &self.output_token_program,
+            )
+        } else {
+            (
+                &self.input_vault,
+                &self.input_token_mint,
+                &self.input_token_program,
+            )
+        };
+        transfer_from_pool_vault_to_user(
+            self.authority.to_account_info(),
+            fee_vault.to_account_info(),
+            referrer_token_account.to_account_info(),
+            fee_mint.to_account_info(),
+            fee_token_program.to_account_info(),
+            referral_fee,
+            fee_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+            remaining_accounts,
+        )?;
+        emit!(ReferralFeeEvent {
+            pool_id,
+            referrer_token_account: referrer_token_account.key(),
+            mint: fee_mint.key(),
+            amount: referral_fee,
+        });
+        Ok(())
+    }
+
+    /// Mint the `lp_amount` lp tokens of the lp fee rebate to the payer
+    pub fn mint_lp_fee_rebate(
+        &self,
+        pool_id: Pubkey,
+        rebate_fee: u64,
+        lp_amount: u64,
+        auth_bump: u8,
+    ) -> Result<()> {
+        let (Some(lp_mint), Some(payer_lp_token), Some(lp_token_program)) =
+            (&self.lp_mint, &self.payer_lp_token, &self.lp_token_program)
+        else {
+            return Ok(());
+        };
+        if lp_amount == 0 {
+            return Ok(());
+        }
+        token_mint_to(
+            self.authority.to_account_info(),
+            lp_token_program.to_account_info(),
+            lp_mint.to_account_info(),
+            payer_lp_token.to_account_info(),
+            lp_amount,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        )?;
+        let fee_mint = if self.amm_config.fee_on_output {
+            &self.output_token_mint
+        } else {
+            &self.input_token_mint
+        };
+        emit!(LpFeeRebateEvent {
+            pool_id,
+            payer_lp_token: payer_lp_token.key(),
+            mint: fee_mint.key(),
+            rebate_fee,
+            lp_amount,
+        });
+        Ok(())
+    }
+}
+
+pub fn swap_base_input<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    wrap_sol: bool,
+    deadline: Option<u64>,
+    sqrt_price_limit_x64: Option<u128>,
+) -> Result<SwapReceipt> {
+    swap(
+        ctx,
+        SwapSide::ExactInput,
+        amount_in,
+        minimum_amount_out,
+        wrap_sol,
+        deadline,
+        sqrt_price_limit_x64,
+    )
+}
+
+/// Whether the pool sqrt price after a swap in `trade_direction` is within the limit,
+/// selling token_0 only moves its price down to the limit and buying it only up to it
+fn within_sqrt_price_limit(
+    trade_direction: TradeDirection,
+    sqrt_price_x64: u128,
+    sqrt_price_limit_x64: u128,
+) -> bool {
+    match trade_direction {
+        TradeDirection::ZeroForOne => sqrt_price_x64 >= sqrt_price_limit_x64,
+        TradeDirection::OneForZero => sqrt_price_x64 <= sqrt_price_limit_x64,
+    }
+}
+
+/// Get the largest input amount up to `amount_in` whose swap is `within_limit`, by
+/// bisection since the pool price moves monotonically with the input
+fn price_limited_amount_in(
+    amount_in: u64,
+    within_limit: impl Fn(u64) -> Result<bool>,
+) -> Result<u64> {
+    let (mut low, mut high) = (0, amount_in);
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if within_limit(mid)? {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    require_gt!(low, 0, ErrorCode::SqrtPriceLimitReached);
+    Ok(low)
 }
 
-pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
-    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+/// The swap engine of `swap_base_input` and `swap_base_output`, `amount_specified` is the
+/// input amount paid for an exact input swap or the output amount received for an exact
+/// output swap, both including the transfer fees, and `other_amount_threshold` bounds the
+/// other side: the minimum output received or the maximum input paid. An exact input swap
+/// crossing `sqrt_price_limit_x64` is partially filled up to the limit
+pub fn swap<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
+    side: SwapSide,
+    mut amount_specified: u64,
+    other_amount_threshold: u64,
+    wrap_sol: bool,
+    deadline: Option<u64>,
+    sqrt_price_limit_x64: Option<u128>,
+) -> Result<SwapReceipt> {
+    require_gt!(amount_specified, 0);
+    // the clock and the transfer fees of the mints are read once for the whole swap
+    let clock = solana_program::clock::Clock::get()?;
+    let block_timestamp = clock.unix_timestamp as u64;
+    if let Some(deadline) = deadline {
+        require_gte!(deadline, block_timestamp, ErrorCode::DeadlineExceeded);
+    }
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
-        || block_timestamp < pool_state.open_time
-    {
-        return err!(ErrorCode::NotApproved);
-    }
+    pool_state.check_swap_allowed(block_timestamp)?;
+    pool_state.lock()?;
     
     // Validate authority for custom authority pools
     if pool_state.is_custom_authority() {
@@ -91,83 +441,193 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         );
     }
 
-    let transfer_fee =
-        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
-    // Take transfer fees into account for actual amount transferred in
-    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
-    require_gt!(actual_amount_in, 0);
+    let input_transfer_fee_cache = TransferFeeCache::load(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        clock.epoch,
+    )?;
+    let output_transfer_fee_cache = TransferFeeCache::load(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        clock.epoch,
+    )?;
+    // the mints raising their transfer fee beyond the cap of the config stop trading
+    for transfer_fee_cache in [&input_transfer_fee_cache, &output_transfer_fee_cache] {
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(transfer_fee_cache.transfer_fee_bps())?;
+    }
+
+    // Take transfer fees into account for the actual amount the curve swaps
+    let (mut specified_transfer_fee, mut actual_amount_specified) = match side {
+        SwapSide::ExactInput => {
+            let transfer_fee = input_transfer_fee_cache.transfer_fee(amount_specified);
+            (transfer_fee, amount_specified.saturating_sub(transfer_fee))
+        }
+        SwapSide::ExactOutput => {
+            let transfer_fee = output_transfer_fee_cache.transfer_inverse_fee(amount_specified)?;
+            (
+                transfer_fee,
+                amount_specified.checked_add(transfer_fee).unwrap(),
+            )
+        }
+    };
+    require_gt!(actual_amount_specified, 0);
 
-    // Calculate the trade amounts and the price before swap
-    let (
-        trade_direction,
-        total_input_token_amount,
-        total_output_token_amount,
-        token_0_price_x64,
-        token_1_price_x64,
-    ) = if ctx.accounts.input_vault.key() == pool_state.token_0_vault
+    // Calculate the trade amounts and the price before swap, from the vault amounts read
+    // once in the order of the pool
+    let (trade_direction, vault_0_amount, vault_1_amount) = if ctx.accounts.input_vault.key()
+        == pool_state.token_0_vault
         && ctx.accounts.output_vault.key() == pool_state.token_1_vault
     {
-        let (total_input_token_amount, total_output_token_amount) = pool_state
-            .vault_amount_without_fee(
-                ctx.accounts.input_vault.amount,
-                ctx.accounts.output_vault.amount,
-            );
-        let (token_0_price_x64, token_1_price_x64) = pool_state.token_price_x32(
-            ctx.accounts.input_vault.amount,
-            ctx.accounts.output_vault.amount,
-        );
-
         (
             TradeDirection::ZeroForOne,
-            total_input_token_amount,
-            total_output_token_amount,
-            token_0_price_x64,
-            token_1_price_x64,
+            ctx.accounts.input_vault.amount,
+            ctx.accounts.output_vault.amount,
         )
     } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
         && ctx.accounts.output_vault.key() == pool_state.token_0_vault
     {
-        let (total_output_token_amount, total_input_token_amount) = pool_state
-            .vault_amount_without_fee(
-                ctx.accounts.output_vault.amount,
-                ctx.accounts.input_vault.amount,
-            );
-        let (token_0_price_x64, token_1_price_x64) = pool_state.token_price_x32(
-            ctx.accounts.output_vault.amount,
-            ctx.accounts.input_vault.amount,
-        );
-
         (
             TradeDirection::OneForZero,
-            total_input_token_amount,
-            total_output_token_amount,
-            token_0_price_x64,
-            token_1_price_x64,
+            ctx.accounts.output_vault.amount,
+            ctx.accounts.input_vault.amount,
         )
     } else {
         return err!(ErrorCode::InvalidVault);
     };
-    let constant_before = u128::from(total_input_token_amount)
-        .checked_mul(u128::from(total_output_token_amount))
-        .unwrap();
-
-    let result = CurveCalculator::swap_base_input(
-        u128::from(actual_amount_in),
-        u128::from(total_input_token_amount),
-        u128::from(total_output_token_amount),
-        ctx.accounts.amm_config.trade_fee_rate,
-        ctx.accounts.amm_config.protocol_fee_rate,
-        ctx.accounts.amm_config.fund_fee_rate,
+    let (total_token_0_amount, total_token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    let (token_0_price_x64, token_1_price_x64) =
+        pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+    let (total_input_token_amount, total_output_token_amount) = match trade_direction {
+        TradeDirection::ZeroForOne => (total_token_0_amount, total_token_1_amount),
+        TradeDirection::OneForZero => (total_token_1_amount, total_token_0_amount),
+    };
+    let pool_vaults = PoolVaults::from_trade(
+        trade_direction == TradeDirection::ZeroForOne,
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+    let trade_fee_rate = {
+        let (observation_state, observation_extension, _) =
+            ctx.accounts.observation_state.load_observations()?;
+        let trade_fee_rate = ctx.accounts.amm_config.swap_trade_fee_rate(
+            pool_state.trade_fee_rate_override(block_timestamp),
+            &observation_state,
+            &observation_extension,
+            block_timestamp,
+            token_0_price_x64,
+        );
+        ctx.accounts.amm_config.discounted_trade_fee_rate(
+            trade_fee_rate,
+            ctx.accounts
+                .fee_discount_token_account
+                .as_ref()
+                .map(|token_account| token_account.amount),
+        )
+    };
+    pool_state.check_launch_trade(trade_direction, ctx.accounts.launch_whitelist.is_some())?;
+    pool_state.check_trader_registered(ctx.accounts.trader_entry.is_some())?;
+    // boosted pools trade on the real reserves offset by their virtual reserves
+    let (curve_input_token_amount, curve_output_token_amount) = pool_state.curve_trade_amounts(
+        trade_direction,
+        total_input_token_amount,
+        total_output_token_amount,
+    );
+    let curve_type = pool_state.curve(block_timestamp)?.oriented(trade_direction);
+    let amount_scale = get_amount_scale_at(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+        clock.unix_timestamp,
+    )?;
+    let constant_before = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        curve_input_token_amount,
+        curve_output_token_amount,
     )
-    .ok_or(ErrorCode::ZeroTradingTokens)?;
+    .unwrap();
 
-    let constant_after = u128::from(
-        result
-            .new_swap_source_amount
-            .checked_sub(result.trade_fee)
-            .unwrap(),
+    let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&ctx.accounts.amm_config);
+    let exemption_bps = ctx
+        .accounts
+        .fee_exemption_list
+        .as_ref()
+        .map_or(0, |fee_exemption_list| {
+            fee_exemption_list.exemption_bps(&ctx.accounts.payer.key())
+        });
+    let (trade_fee_rate, protocol_fee_rate, fund_fee_rate) = FeeExemptionList::exempt_fee_rates(
+        exemption_bps,
+        trade_fee_rate,
+        protocol_fee_rate,
+        fund_fee_rate,
+    );
+    let swap_curve = |actual_amount: u64| {
+        CurveCalculator::swap_scaled(
+            SwapParams {
+                curve_type,
+                amount_scale,
+                fee_rates: FeeRates {
+ trade_fee_rate,
+ protocol_fee_rate,
+ fund_fee_rate,
+ },
+                fee_on_output: ctx.accounts.amm_config.fee_on_output,
+            },
+            side,
+            u128::from(actual_amount),
+            curve_input_token_amount,
+            curve_output_token_amount,
+        )
+    };
+    let reserves_before = PoolReserves::from_trade_amounts(
+        trade_direction,
+        total_input_token_amount,
+        total_output_token_amount,
+        pool_state.lp_supply,
+    );
+    let mut result = swap_curve(actual_amount_specified)?;
+    if let (SwapSide::ExactInput, Some(sqrt_price_limit_x64)) = (side, sqrt_price_limit_x64) {
+        // the sqrt price after the swap as the swap receipt reports it
+        let within_limit = |result: &SwapResult| -> Result<bool> {
+            let lp_fee_cut = ctx.accounts.referral_fee(result.trade_fee)?
+                + pool_state.creator_fee(result.trade_fee);
+            let reserves_after = reserves_before.after_swap(trade_direction, result, lp_fee_cut);
+            Ok(within_sqrt_price_limit(
+                trade_direction,
+                reserves_after.sqrt_price_x64,
+                sqrt_price_limit_x64,
+            ))
+        };
+        if !within_limit(&result)? {
+            // the input beyond the price limit is not transferred from the payer
+            amount_specified = price_limited_amount_in(amount_specified, |amount_in| {
+                let actual_amount_in = amount_in - input_transfer_fee_cache.transfer_fee(amount_in);
+                match swap_curve(actual_amount_in) {
+                    Ok(result) if actual_amount_in > 0 => within_limit(&result),
+                    _ => Ok(false),
+                }
+            })?;
+            specified_transfer_fee = input_transfer_fee_cache.transfer_fee(amount_specified);
+            actual_amount_specified = amount_specified - specified_transfer_fee;
+            result = swap_curve(actual_amount_specified)?;
+        }
+    }
+    ctx.accounts
+        .check_oracle_price(pool_state, block_timestamp, trade_direction, &result)?;
+    pool_state.check_auction_size(u64::try_from(result.source_amount_swapped).unwrap())?;
+    pool_state.check_trade_size(trade_direction, &result)?;
+
+    let (swap_source_amount_without_fee, swap_destination_amount_without_fee) =
+        result.new_swap_amounts_without_fee()?;
+    let constant_after = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        swap_source_amount_without_fee,
+        swap_destination_amount_without_fee,
     )
-    .checked_mul(u128::from(result.new_swap_destination_amount))
     .unwrap();
     #[cfg(feature = "enable-log")]
     msg!(
@@ -178,31 +638,79 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         constant_before,
         constant_after
     );
-    require_eq!(
-        u64::try_from(result.source_amount_swapped).unwrap(),
-        actual_amount_in
+    // the virtual reserves only deepen the curve, the output is paid from the real reserve
+    require_gt!(
+        u128::from(total_output_token_amount),
+        result.destination_amount_swapped,
+        ErrorCode::ExceededVirtualReserveRange
     );
-    let (input_transfer_amount, input_transfer_fee) = (amount_in, transfer_fee);
-    let (output_transfer_amount, output_transfer_fee) = {
-        let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
-        let transfer_fee = get_transfer_fee(
-            &ctx.accounts.output_token_mint.to_account_info(),
-            amount_out,
-        )?;
-        let amount_received = amount_out.checked_sub(transfer_fee).unwrap();
-        require_gt!(amount_received, 0);
-        require_gte!(
-            amount_received,
-            minimum_amount_out,
-            ErrorCode::ExceededSlippage
-        );
-        (amount_out, transfer_fee)
+    // and not from the reserves lent to the strategy
+    pool_state.check_swap_outflow(
+        trade_direction,
+        vault_0_amount,
+        vault_1_amount,
+        u64::try_from(result.destination_amount_swapped).unwrap(),
+    )?;
+    let source_amount_swapped = u64::try_from(result.source_amount_swapped).unwrap();
+    let destination_amount_swapped = u64::try_from(result.destination_amount_swapped).unwrap();
+    let (
+        (input_transfer_amount, input_transfer_fee),
+        (output_transfer_amount, output_transfer_fee),
+    ) = match side {
+        SwapSide::ExactInput => {
+            require_eq!(source_amount_swapped, actual_amount_specified);
+            let transfer_fee = output_transfer_fee_cache.transfer_fee(destination_amount_swapped);
+            let amount_received = destination_amount_swapped
+                .checked_sub(transfer_fee)
+                .unwrap();
+            require_gt!(amount_received, 0);
+            require_gte!(
+                amount_received,
+                other_amount_threshold,
+                ErrorCode::ExceededSlippage
+            );
+            (
+                (amount_specified, specified_transfer_fee),
+                (destination_amount_swapped, transfer_fee),
+            )
+        }
+        SwapSide::ExactOutput => {
+            require_eq!(destination_amount_swapped, actual_amount_specified);
+            // Re-calculate the source amount swapped based on what the curve says
+            require_gt!(source_amount_swapped, 0);
+            let transfer_fee =
+                input_transfer_fee_cache.transfer_inverse_fee(source_amount_swapped)?;
+            let input_transfer_amount = source_amount_swapped.checked_add(transfer_fee).unwrap();
+            require_gte!(
+                other_amount_threshold,
+                input_transfer_amount,
+                ErrorCode::ExceededSlippage
+            );
+            (
+                (input_transfer_amount, transfer_fee),
+                (destination_amount_swapped, specified_transfer_fee),
+            )
+        }
     };
 
     let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
     let fund_fee = u64::try_from(result.fund_fee).unwrap();
+    // the referral fee is taken from the liquidity provider share of the trade fee
+    let referral_fee = ctx.accounts.referral_fee(result.trade_fee)?;
+    // and so is the creator fee, kept in the vault until collected
+    let fee_direction = result.fee_direction(trade_direction);
+    let creator_fee = pool_state.accrue_creator_fee(fee_direction, result.trade_fee);
+    let lp_fee_cut = referral_fee.checked_add(creator_fee).unwrap();
+    // and a share of what is left to the liquidity providers is rebated to the payer in lp
+    // tokens, the rebated fee stays in the vault
+    let rebate_fee = ctx.accounts.lp_fee_rebate(
+        result
+            .trade_fee
+            .saturating_sub(result.protocol_fee + result.fund_fee + u128::from(lp_fee_cut)),
+    )?;
 
-    match trade_direction {
+    // the fees are accrued in the token they are charged in
+    match fee_direction {
         TradeDirection::ZeroForOne => {
             pool_state.protocol_fees_token_0 = pool_state
                 .protocol_fees_token_0
@@ -220,19 +728,117 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
                 pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
         }
     };
+    pool_state.record_swap(trade_direction, &result, lp_fee_cut, block_timestamp);
+    if let Some(protocol_stats) = ctx.accounts.protocol_stats.as_mut() {
+        protocol_stats.record_swap(&result);
+    }
+    if let Some(trader_stats) = ctx.accounts.trader_stats.as_mut() {
+        if !trader_stats.is_initialized() {
+            trader_stats.initialize(
+                ctx.bumps.trader_stats.unwrap(),
+                ctx.accounts.pool_state.key(),
+                ctx.accounts.payer.key(),
+            );
+        }
+        trader_stats.record_swap(
+            clock.epoch,
+            trade_direction,
+            &result,
+            pool_state.fee_rebate_volume_threshold,
+            pool_state.fee_rebate_bps,
+        );
+    }
 
+    let mut reserves_after = reserves_before.after_swap(trade_direction, &result, lp_fee_cut);
+    let lp_fee_rebate_amount = pool_state.lp_fee_rebate_amount(
+        pool_state.lp_fee_rebate_lp_amount(
+            fee_direction,
+            rebate_fee,
+            &reserves_after,
+            block_timestamp,
+        ),
+        ctx.accounts.amm_config.lp_fee_rebate_epoch_cap,
+        clock.epoch,
+    );
+    if lp_fee_rebate_amount > 0 {
+        pool_state.record_lp_fee_rebate(lp_fee_rebate_amount, clock.epoch);
+        let lp_supply = pool_state
+            .lp_supply
+            .checked_add(lp_fee_rebate_amount)
+            .unwrap();
+        pool_state.set_lp_supply(lp_supply);
+        reserves_after.lp_supply = lp_supply;
+    }
+    pool_state.check_price_band(&reserves_before, &reserves_after, block_timestamp)?;
+    if let Some(pool_snapshot) = ctx.accounts.pool_snapshot.as_mut() {
+        pool_snapshot.refresh(
+            reserves_after,
+            pool_state.fee_growth_0_x64,
+            pool_state.fee_growth_1_x64,
+            clock.slot,
+        );
+    }
     emit!(SwapEvent {
         pool_id,
         input_vault_before: total_input_token_amount,
         output_vault_before: total_output_token_amount,
-        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
-        output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
+        input_amount: source_amount_swapped,
+        output_amount: destination_amount_swapped,
         input_transfer_fee,
         output_transfer_fee,
-        base_input: true
+        base_input: side == SwapSide::ExactInput,
+        trade_fee: u64::try_from(result.trade_fee).unwrap(),
+        protocol_fee,
+        fund_fee,
+        referral_fee,
+        creator_fee,
+        reserves_before,
+        reserves_after,
     });
-    require_gte!(constant_after, constant_before);
+    if exemption_bps > 0 {
+        emit!(FeeExemptSwapEvent {
+            pool_id,
+            trader: ctx.accounts.payer.key(),
+            exemption_bps,
+            input_amount: source_amount_swapped,
+            output_amount: destination_amount_swapped,
+            trade_fee: u64::try_from(result.trade_fee).unwrap(),
+            protocol_fee,
+        });
+    }
+    require_gte!(
+        constant_after,
+        constant_before,
+        ErrorCode::CurveInvariantViolated
+    );
+    pool_state.check_price_impact(
+        curve_input_token_amount,
+        curve_output_token_amount,
+        result.new_swap_source_amount,
+        result.new_swap_destination_amount,
+    )?;
+    let receipt = SwapReceipt {
+        amount_in: input_transfer_amount,
+        amount_out: output_transfer_amount
+            .checked_sub(output_transfer_fee)
+            .unwrap(),
+        input_transfer_fee,
+        output_transfer_fee,
+        trade_fee: u64::try_from(result.trade_fee).unwrap(),
+        protocol_fee,
+        fund_fee,
+        sqrt_price_before_x64: reserves_before.sqrt_price_x64,
+        sqrt_price_after_x64: reserves_after.sqrt_price_x64,
+        price_impact_bps: CurveCalculator::price_impact_bps(
+            curve_input_token_amount,
+            curve_output_token_amount,
+            result.new_swap_source_amount,
+            result.new_swap_destination_amount,
+        )?,
+    };
 
+    ctx.accounts
+        .wrap_input_sol(wrap_sol, input_transfer_amount)?;
     transfer_from_user_to_pool_vault(
         ctx.accounts.payer.to_account_info(),
         ctx.accounts.input_token_account.to_account_info(),
@@ -241,6 +847,7 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         ctx.accounts.input_token_program.to_account_info(),
         input_transfer_amount,
         ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     // Vaults are always owned by the PDA, even for custom authority pools
@@ -253,15 +860,57 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         output_transfer_amount,
         ctx.accounts.output_token_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.unwrap_output_sol(wrap_sol)?;
+
+    ctx.accounts.transfer_referral_fee(
+        pool_id,
+        referral_fee,
+        pool_state.auth_bump,
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.mint_lp_fee_rebate(
+        pool_id,
+        rebate_fee,
+        lp_fee_rebate_amount,
+        pool_state.auth_bump,
     )?;
 
     // update the previous price to the observation
-    ctx.accounts.observation_state.load_mut()?.update(
-        oracle::block_timestamp(),
+    let (mut observation_state, mut observation_extension, mut log_price_observations) =
+        ctx.accounts.observation_state.load_observations_mut()?;
+    if let Some(deviation_bps) = pool_state.update_circuit_breaker(
+        &observation_state,
+        &observation_extension,
+        block_timestamp,
+        token_0_price_x64,
+        token_1_price_x64,
+        &reserves_after,
+    ) {
+        emit!(CircuitBreakerTrippedEvent {
+            pool_id,
+            deviation_bps,
+            threshold_bps: pool_state.circuit_breaker_threshold_bps,
+            window: pool_state.circuit_breaker_window,
+        });
+    }
+    observation_state.update(
+        &mut observation_extension,
+        &mut log_price_observations,
+        block_timestamp,
         token_0_price_x64,
         token_1_price_x64,
+        ctx.accounts.amm_config.observation_update_duration(),
     );
-    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_state.recent_epoch = clock.epoch;
+    let lp_mint = ctx
+        .accounts
+        .lp_mint
+        .as_ref()
+        .map(|lp_mint| lp_mint.to_account_info());
+    pool_vaults.check(pool_state, lp_mint.as_ref(), Some(&pool_snapshot))?;
+    pool_state.unlock();
 
-    Ok(())
+    Ok(receipt)
 }
@@ -0,0 +1,578 @@
+use crate::curve::calculator::{AmountScale, CurveCalculator, CurveType, SwapResult};
+use crate::curve::{FeeRates, TradeDirection};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::{PoolSnapshot, PoolVaults};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// The accounts of a pool a route goes through
+#[derive(Accounts)]
+pub struct SwapRoutePool<'info> {
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool in which the swap will be performed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault token account for input token
+    #[account(
+        mut,
+        constraint = input_vault.key() == pool_state.load()?.token_0_vault || input_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(
+        mut,
+        constraint = output_vault.key() == pool_state.load()?.token_0_vault || output_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+/// The pool amounts and rates a hop of a route is calculated with
+pub struct SwapRouteHop {
+    pub trade_direction: TradeDirection,
+    /// The vault balances before the hop, in the order of the pool
+    pub vault_0_amount: u64,
+    pub vault_1_amount: u64,
+    /// The curve of the pool oriented along the trade direction
+    pub curve_type: CurveType,
+    pub total_input_token_amount: u64,
+    pub total_output_token_amount: u64,
+    /// The amounts the curve trades on, including the virtual reserves of boosted pools
+    pub curve_input_token_amount: u128,
+    pub curve_output_token_amount: u128,
+    pub token_0_price_x64: u128,
+    pub token_1_price_x64: u128,
+    pub fee_rates: FeeRates,
+    /// The UI amount multipliers of the input and output tokens
+    pub amount_scale: AmountScale,
+    /// The pool before the hop, checked against once the tokens moved
+    pub pool_snapshot: PoolSnapshot,
+}
+
+impl<'info> SwapRoutePool<'info> {
+    /// Check the pool accepts the swap and get the pool amounts before it
+    pub fn prepare_hop(
+        &self,
+        payer: &Pubkey,
+        input_mint: &AccountInfo<'info>,
+        output_mint: &AccountInfo<'info>,
+        block_timestamp: u64,
+    ) -> Result<SwapRouteHop> {
+        let pool_state = self.pool_state.load()?;
+        pool_state.check_swap_allowed(block_timestamp)?;
+        pool_state.check_derived_swap_allowed(&self.amm_config)?;
+        require!(!pool_state.is_locked(), ErrorCode::ReentrancyDetected);
+        self.amm_config
+            .check_transfer_fee_bps(get_transfer_fee_bps(input_mint)?)?;
+        self.amm_config
+            .check_transfer_fee_bps(get_transfer_fee_bps(output_mint)?)?;
+        // Validate authority for custom authority pools
+        if pool_state.is_custom_authority() {
+            require!(
+                *payer == pool_state.custom_authority,
+                ErrorCode::InvalidAuthority
+            );
+        }
+
+        let (trade_direction, vault_0_amount, vault_1_amount) = if self.input_vault.key()
+            == pool_state.token_0_vault
+            && self.output_vault.key() == pool_state.token_1_vault
+        {
+            (
+                TradeDirection::ZeroForOne,
+                self.input_vault.amount,
+                self.output_vault.amount,
+            )
+        } else if self.input_vault.key() == pool_state.token_1_vault
+            && self.output_vault.key() == pool_state.token_0_vault
+        {
+            (
+                TradeDirection::OneForZero,
+                self.output_vault.amount,
+                self.input_vault.amount,
+            )
+        } else {
+            return err!(ErrorCode::InvalidVault);
+        };
+        let pool_snapshot = self
+            .pool_vaults(trade_direction, input_mint, output_mint)
+            .snapshot(&pool_state)?;
+        let (total_token_0_amount, total_token_1_amount) =
+            pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+        let (token_0_price_x64, token_1_price_x64) =
+            pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+        let (total_input_token_amount, total_output_token_amount) = match trade_direction {
+            TradeDirection::ZeroForOne => (total_token_0_amount, total_token_1_amount),
+            TradeDirection::OneForZero => (total_token_1_amount, total_token_0_amount),
+        };
+        // whitelist entries are not passed for the pools of a route
+        pool_state.check_launch_trade(trade_direction, false)?;
+        let (curve_input_token_amount, curve_output_token_amount) = pool_state.curve_trade_amounts(
+            trade_direction,
+            total_input_token_amount,
+            total_output_token_amount,
+        );
+
+        let trade_fee_rate = {
+            let (observation_state, observation_extension, _) =
+                self.observation_state.load_observations()?;
+            self.amm_config.swap_trade_fee_rate(
+                pool_state.trade_fee_rate_override(block_timestamp),
+                &observation_state,
+                &observation_extension,
+                block_timestamp,
+                token_0_price_x64,
+            )
+        };
+
+        let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&self.amm_config);
+        Ok(SwapRouteHop {
+            trade_direction,
+            vault_0_amount,
+            vault_1_amount,
+            curve_type: pool_state.curve(block_timestamp)?.oriented(trade_direction),
+            total_input_token_amount,
+            total_output_token_amount,
+            curve_input_token_amount,
+            curve_output_token_amount,
+            token_0_price_x64,
+            token_1_price_x64,
+            fee_rates: FeeRates {
+                trade_fee_rate,
+                protocol_fee_rate,
+                fund_fee_rate,
+            },
+            amount_scale: get_amount_scale(input_mint, output_mint)?,
+            pool_snapshot,
+        })
+    }
+
+    /// Check the pool invariants against the snapshot of the hop once its tokens moved,
+    /// releasing the pool held since the hop settled
+    pub fn check_hop(
+        &self,
+        hop: &SwapRouteHop,
+        input_mint: &AccountInfo<'info>,
+        output_mint: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let mut pool_state = self.pool_state.load_mut()?;
+        pool_state.unlock();
+        self.pool_vaults(hop.trade_direction, input_mint, output_mint)
+            .check(&pool_state, None, Some(&hop.pool_snapshot))
+    }
+
+    fn pool_vaults(
+        &self,
+        trade_direction: TradeDirection,
+        input_mint: &AccountInfo<'info>,
+        output_mint: &AccountInfo<'info>,
+    ) -> PoolVaults<'info> {
+        PoolVaults::from_trade(
+            trade_direction == TradeDirection::ZeroForOne,
+            self.input_vault.to_account_info(),
+            self.output_vault.to_account_info(),
+            input_mint.clone(),
+            output_mint.clone(),
+        )
+    }
+
+    /// Check the invariant, accrue the fees and update the oracle for a calculated hop
+    pub fn apply_hop(
+        &self,
+        hop: &SwapRouteHop,
+        result: &SwapResult,
+        input_transfer_fee: u64,
+        output_transfer_fee: u64,
+        base_input: bool,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        self.pool_state
+            .load()?
+            .check_auction_size(u64::try_from(result.source_amount_swapped).unwrap())?;
+        self.pool_state
+            .load()?
+            .check_trade_size(hop.trade_direction, result)?;
+        self.settle_hop(
+            hop,
+            result,
+            input_transfer_fee,
+            output_transfer_fee,
+            base_input,
+            block_timestamp,
+        )
+    }
+
+    /// Apply a calculated hop like `apply_hop` without the auction size check, for the
+    /// requested swaps the filler passed on. The pool is held until `check_hop`, across the
+    /// transfers of the hop
+    pub fn settle_hop(
+        &self,
+        hop: &SwapRouteHop,
+        result: &SwapResult,
+        input_transfer_fee: u64,
+        output_transfer_fee: u64,
+        base_input: bool,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let constant_before = CurveCalculator::invariant_scaled(
+            hop.curve_type,
+            hop.amount_scale,
+            hop.curve_input_token_amount,
+            hop.curve_output_token_amount,
+        )
+        .unwrap();
+        let constant_after = CurveCalculator::invariant_scaled(
+            hop.curve_type,
+            hop.amount_scale,
+            result
+                .new_swap_source_amount
+                .checked_sub(result.trade_fee)
+                .unwrap(),
+            result.new_swap_destination_amount,
+        )
+        .unwrap();
+        require_gte!(
+            constant_after,
+            constant_before,
+            ErrorCode::CurveInvariantViolated
+        );
+        // the virtual reserves only deepen the curve, the output is paid from the real reserve
+        require_gt!(
+            u128::from(hop.total_output_token_amount),
+            result.destination_amount_swapped,
+            ErrorCode::ExceededVirtualReserveRange
+        );
+        // and not from the reserves lent to the strategy
+        self.pool_state.load()?.check_swap_outflow(
+            hop.trade_direction,
+            hop.vault_0_amount,
+            hop.vault_1_amount,
+            u64::try_from(result.destination_amount_swapped).unwrap(),
+        )?;
+
+        let pool_id = self.pool_state.key();
+        let mut pool_state = self.pool_state.load_mut()?;
+        pool_state.lock()?;
+        pool_state.check_price_impact(
+            hop.curve_input_token_amount,
+            hop.curve_output_token_amount,
+            result.new_swap_source_amount,
+            result.new_swap_destination_amount,
+        )?;
+        let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+        let fund_fee = u64::try_from(result.fund_fee).unwrap();
+        match hop.trade_direction {
+            TradeDirection::ZeroForOne => {
+                pool_state.protocol_fees_token_0 = pool_state
+                    .protocol_fees_token_0
+                    .checked_add(protocol_fee)
+                    .unwrap();
+                pool_state.fund_fees_token_0 =
+                    pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+            }
+            TradeDirection::OneForZero => {
+                pool_state.protocol_fees_token_1 = pool_state
+                    .protocol_fees_token_1
+                    .checked_add(protocol_fee)
+                    .unwrap();
+                pool_state.fund_fees_token_1 =
+                    pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+            }
+        };
+        let creator_fee = pool_state.accrue_creator_fee(hop.trade_direction, result.trade_fee);
+        pool_state.record_swap(hop.trade_direction, result, creator_fee, block_timestamp);
+
+        let reserves_before = PoolReserves::from_trade_amounts(
+            hop.trade_direction,
+            hop.total_input_token_amount,
+            hop.total_output_token_amount,
+            pool_state.lp_supply,
+        );
+        let reserves_after = reserves_before.after_swap(hop.trade_direction, result, creator_fee);
+        pool_state.check_price_band(&reserves_before, &reserves_after, block_timestamp)?;
+        emit!(SwapEvent {
+            pool_id,
+            input_vault_before: hop.total_input_token_amount,
+            output_vault_before: hop.total_output_token_amount,
+            input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
+            output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
+            input_transfer_fee,
+            output_transfer_fee,
+            base_input,
+            trade_fee: u64::try_from(result.trade_fee).unwrap(),
+            protocol_fee,
+            fund_fee,
+            referral_fee: 0,
+            creator_fee,
+            reserves_before,
+            reserves_after,
+        });
+
+        // update the previous price to the observation
+        let (mut observation_state, mut observation_extension, mut log_price_observations) =
+            self.observation_state.load_observations_mut()?;
+        if let Some(deviation_bps) = pool_state.update_circuit_breaker(
+            &observation_state,
+            &observation_extension,
+            block_timestamp,
+            hop.token_0_price_x64,
+            hop.token_1_price_x64,
+            &reserves_after,
+        ) {
+            emit!(CircuitBreakerTrippedEvent {
+                pool_id,
+                deviation_bps,
+                threshold_bps: pool_state.circuit_breaker_threshold_bps,
+                window: pool_state.circuit_breaker_window,
+            });
+        }
+        observation_state.update(
+            &mut observation_extension,
+            &mut log_price_observations,
+            block_timestamp,
+            hop.token_0_price_x64,
+            hop.token_1_price_x64,
+            self.amm_config.observation_update_duration(),
+        );
+        pool_state.recent_epoch = Clock::get()?.epoch;
+        Ok(())
+    }
+
+    pub fn auth_bump(&self) -> Result<u8> {
+        Ok(self.pool_state.load()?.auth_bump)
+    }
+}
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool swapping the input token for the intermediate token
+    pub first_pool: SwapRoutePool<'info>,
+
+    /// The pool swapping the intermediate token for the output token
+    #[account(
+        constraint = second_pool.pool_state.key() != first_pool.pool_state.key() @ ErrorCode::InvalidInput,
+        constraint = second_pool.input_vault.mint == first_pool.output_vault.mint @ ErrorCode::InvalidVault
+    )]
+    pub second_pool: SwapRoutePool<'info>,
+
+    /// The user token account for input token
+    #[account(mut)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user token account for output token
+    #[account(mut)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for intermediate token transfers
+    pub intermediate_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of input token
+    #[account(
+        address = first_pool.input_vault.mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of intermediate token
+    #[account(
+        address = first_pool.output_vault.mint
+    )]
+    pub intermediate_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(
+        address = second_pool.output_vault.mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn swap_route<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SwapRoute<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    let block_timestamp = oracle::block_timestamp();
+    let payer = ctx.accounts.payer.key();
+    let first_hop = ctx.accounts.first_pool.prepare_hop(
+        &payer,
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.intermediate_token_mint.to_account_info(),
+        block_timestamp,
+    )?;
+    let second_hop = ctx.accounts.second_pool.prepare_hop(
+        &payer,
+        &ctx.accounts.intermediate_token_mint.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+        block_timestamp,
+    )?;
+
+    let input_transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(input_transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+    let first_result = CurveCalculator::swap_base_input_scaled(
+        first_hop.curve_type,
+        first_hop.amount_scale,
+        u128::from(actual_amount_in),
+        first_hop.curve_input_token_amount,
+        first_hop.curve_output_token_amount,
+        first_hop.fee_rates,
+    )?;
+    // The intermediate token moves between the vaults, its transfer fee is charged once
+    let intermediate_amount = u64::try_from(first_result.destination_amount_swapped).unwrap();
+    let intermediate_transfer_fee = get_transfer_fee(
+        &ctx.accounts.intermediate_token_mint.to_account_info(),
+        intermediate_amount,
+    )?;
+    let actual_intermediate_amount = intermediate_amount
+        .checked_sub(intermediate_transfer_fee)
+        .unwrap();
+    require_gt!(actual_intermediate_amount, 0);
+
+    let second_result = CurveCalculator::swap_base_input_scaled(
+        second_hop.curve_type,
+        second_hop.amount_scale,
+        u128::from(actual_intermediate_amount),
+        second_hop.curve_input_token_amount,
+        second_hop.curve_output_token_amount,
+        second_hop.fee_rates,
+    )?;
+    let amount_out = u64::try_from(second_result.destination_amount_swapped).unwrap();
+    let output_transfer_fee = get_transfer_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        amount_out,
+    )?;
+    let amount_received = amount_out.checked_sub(output_transfer_fee).unwrap();
+    require_gt!(amount_received, 0);
+    // Slippage is only checked on the final output
+    require_gte!(
+        amount_received,
+        minimum_amount_out,
+        ErrorCode::ExceededSlippage
+    );
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amount_in:{}, intermediate_amount:{}, amount_out:{}",
+        amount_in,
+        intermediate_amount,
+        amount_out
+    );
+
+    ctx.accounts.first_pool.apply_hop(
+        &first_hop,
+        &first_result,
+        input_transfer_fee,
+        intermediate_transfer_fee,
+        true,
+        block_timestamp,
+    )?;
+    ctx.accounts.second_pool.apply_hop(
+        &second_hop,
+        &second_result,
+        intermediate_transfer_fee,
+        output_transfer_fee,
+        true,
+        block_timestamp,
+    )?;
+
+    ctx.accounts.transfer_route(
+        amount_in,
+        intermediate_amount,
+        amount_out,
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.check_route(&first_hop, &second_hop)
+}
+
+impl<'info> SwapRoute<'info> {
+    /// Check the invariants of both pools once the tokens of the route moved
+    pub fn check_route(&self, first_hop: &SwapRouteHop, second_hop: &SwapRouteHop) -> Result<()> {
+        self.first_pool.check_hop(
+            first_hop,
+            &self.input_token_mint.to_account_info(),
+            &self.intermediate_token_mint.to_account_info(),
+        )?;
+        self.second_pool.check_hop(
+            second_hop,
+            &self.intermediate_token_mint.to_account_info(),
+            &self.output_token_mint.to_account_info(),
+        )
+    }
+
+    /// Move the tokens of a route, the intermediate token goes straight from the
+    /// first pool vault to the second pool vault
+    pub fn transfer_route(
+        &self,
+        amount_in: u64,
+        intermediate_amount: u64,
+        amount_out: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        transfer_from_user_to_pool_vault(
+            self.payer.to_account_info(),
+            self.input_token_account.to_account_info(),
+            self.first_pool.input_vault.to_account_info(),
+            self.input_token_mint.to_account_info(),
+            self.input_token_program.to_account_info(),
+            amount_in,
+            self.input_token_mint.decimals,
+            remaining_accounts,
+        )?;
+
+        // Vaults are always owned by the PDA, even for custom authority pools
+        let auth_bump = self.first_pool.auth_bump()?;
+        transfer_from_pool_vault_to_user(
+            self.authority.to_account_info(),
+            self.first_pool.output_vault.to_account_info(),
+            self.second_pool.input_vault.to_account_info(),
+            self.intermediate_token_mint.to_account_info(),
+            self.intermediate_token_program.to_account_info(),
+            intermediate_amount,
+            self.intermediate_token_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+            remaining_accounts,
+        )?;
+
+        transfer_from_pool_vault_to_user(
+            self.authority.to_account_info(),
+            self.second_pool.output_vault.to_account_info(),
+            self.output_token_account.to_account_info(),
+            self.output_token_mint.to_account_info(),
+            self.output_token_program.to_account_info(),
+            amount_out,
+            self.output_token_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+            remaining_accounts,
+        )
+    }
+}
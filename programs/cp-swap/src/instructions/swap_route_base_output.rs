@@ -0,0 +1,115 @@
+use super::swap_route::SwapRoute;
+use crate::curve::calculator::CurveCalculator;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+
+pub fn swap_route_base_output<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SwapRoute<'info>>,
+    max_amount_in: u64,
+    amount_out_less_fee: u64,
+) -> Result<()> {
+    require_gt!(amount_out_less_fee, 0);
+    let block_timestamp = oracle::block_timestamp();
+    let payer = ctx.accounts.payer.key();
+    let first_hop = ctx.accounts.first_pool.prepare_hop(
+        &payer,
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.intermediate_token_mint.to_account_info(),
+        block_timestamp,
+    )?;
+    let second_hop = ctx.accounts.second_pool.prepare_hop(
+        &payer,
+        &ctx.accounts.intermediate_token_mint.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+        block_timestamp,
+    )?;
+
+    // The amounts are calculated backwards, from the output of the last hop to the route input
+    let output_transfer_fee = get_transfer_inverse_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        amount_out_less_fee,
+    )?;
+    let amount_out = amount_out_less_fee
+        .checked_add(output_transfer_fee)
+        .unwrap();
+
+    let second_result = CurveCalculator::swap_base_output_scaled(
+        second_hop.curve_type,
+        second_hop.amount_scale,
+        u128::from(amount_out),
+        second_hop.curve_input_token_amount,
+        second_hop.curve_output_token_amount,
+        second_hop.fee_rates,
+    )?;
+    require_eq!(
+        u64::try_from(second_result.destination_amount_swapped).unwrap(),
+        amount_out
+    );
+    // The intermediate token moves between the vaults, its transfer fee is charged once
+    let actual_intermediate_amount = u64::try_from(second_result.source_amount_swapped).unwrap();
+    require_gt!(actual_intermediate_amount, 0);
+    let intermediate_transfer_fee = get_transfer_inverse_fee(
+        &ctx.accounts.intermediate_token_mint.to_account_info(),
+        actual_intermediate_amount,
+    )?;
+    let intermediate_amount = actual_intermediate_amount
+        .checked_add(intermediate_transfer_fee)
+        .unwrap();
+
+    let first_result = CurveCalculator::swap_base_output_scaled(
+        first_hop.curve_type,
+        first_hop.amount_scale,
+        u128::from(intermediate_amount),
+        first_hop.curve_input_token_amount,
+        first_hop.curve_output_token_amount,
+        first_hop.fee_rates,
+    )?;
+    require_eq!(
+        u64::try_from(first_result.destination_amount_swapped).unwrap(),
+        intermediate_amount
+    );
+    let actual_amount_in = u64::try_from(first_result.source_amount_swapped).unwrap();
+    require_gt!(actual_amount_in, 0);
+    let input_transfer_fee = get_transfer_inverse_fee(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        actual_amount_in,
+    )?;
+    let amount_in = actual_amount_in.checked_add(input_transfer_fee).unwrap();
+    // Slippage is only checked on the route input
+    require_gte!(max_amount_in, amount_in, ErrorCode::ExceededSlippage);
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amount_in:{}, intermediate_amount:{}, amount_out:{}",
+        amount_in,
+        intermediate_amount,
+        amount_out
+    );
+
+    ctx.accounts.first_pool.apply_hop(
+        &first_hop,
+        &first_result,
+        input_transfer_fee,
+        intermediate_transfer_fee,
+        false,
+        block_timestamp,
+    )?;
+    ctx.accounts.second_pool.apply_hop(
+        &second_hop,
+        &second_result,
+        intermediate_transfer_fee,
+        output_transfer_fee,
+        false,
+        block_timestamp,
+    )?;
+
+    ctx.accounts.transfer_route(
+        amount_in,
+        intermediate_amount,
+        amount_out,
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.check_route(&first_hop, &second_hop)
+}
@@ -0,0 +1,409 @@
+use crate::curve::calculator::CurveCalculator;
+use crate::curve::{FeeRates, TradeDirection};
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use crate::utils::verify_ed25519_signature;
+use crate::utils::PoolVaults;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(order: SwapOrder)]
+pub struct SwapSignedOrder<'info> {
+    /// Submits the order and pays the transaction. Can be anyone
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: The owner who signed the order, checked by the ed25519 instruction
+    #[account(address = order.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: The delegate approved by the owner on the input token account, only signing
+    /// the transfers of the orders the owner signed
+    #[account(
+        seeds = [
+            SWAP_ORDER_AUTHORITY_SEED.as_bytes(),
+            order.owner.as_ref(),
+        ],
+        bump,
+    )]
+    pub order_authority: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool in which the swap will be performed
+    #[account(mut, address = order.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The owner token account for input token
+    #[account(mut, token::authority = owner)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner token account for output token
+    #[account(mut, token::authority = owner)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for input token
+    #[account(
+        mut,
+        constraint = input_vault.key() == pool_state.load()?.token_0_vault || input_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(
+        mut,
+        constraint = output_vault.key() == pool_state.load()?.token_0_vault || output_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of input token
+    #[account(
+        address = input_vault.mint,
+        constraint = input_token_mint.key() == order.input_mint @ ErrorCode::InvalidInput
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of output token
+    #[account(
+        address = output_vault.mint,
+        constraint = output_token_mint.key() == order.output_mint @ ErrorCode::InvalidInput
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The next order nonce of the owner
+    #[account(
+        init_if_needed,
+        seeds = [
+            SWAP_ORDER_NONCE_SEED.as_bytes(),
+            order.owner.as_ref(),
+        ],
+        bump,
+        payer = relayer,
+        space = SwapOrderNonce::LEN
+    )]
+    pub order_nonce: Box<Account<'info, SwapOrderNonce>>,
+
+    /// CHECK: The instructions sysvar, to read the ed25519 signature verification
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn swap_signed_order<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SwapSignedOrder<'info>>,
+    order: SwapOrder,
+) -> Result<()> {
+    let block_timestamp = oracle::block_timestamp();
+    require_gte!(order.expiry, block_timestamp, ErrorCode::OrderExpired);
+    verify_ed25519_signature(
+        &ctx.accounts.instructions_sysvar.to_account_info(),
+        &order.owner,
+        &order.try_to_vec()?,
+    )?;
+    let order_nonce = &mut ctx.accounts.order_nonce;
+    require_eq!(
+        order.nonce,
+        order_nonce.next_nonce,
+        ErrorCode::InvalidOrderNonce
+    );
+    order_nonce.bump = ctx.bumps.order_nonce;
+    order_nonce.owner = order.owner;
+    order_nonce.next_nonce = order_nonce.next_nonce.checked_add(1).unwrap();
+
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    pool_state.check_swap_allowed(block_timestamp)?;
+    pool_state.check_derived_swap_allowed(&ctx.accounts.amm_config)?;
+    pool_state.lock()?;
+    // Validate authority for custom authority pools
+    if pool_state.is_custom_authority() {
+        require!(
+            order.owner == pool_state.custom_authority,
+            ErrorCode::InvalidAuthority
+        );
+    }
+
+    for mint in [
+        &ctx.accounts.input_token_mint,
+        &ctx.accounts.output_token_mint,
+    ] {
+        ctx.accounts
+            .amm_config
+            .check_transfer_fee_bps(get_transfer_fee_bps(&mint.to_account_info())?)?;
+    }
+    let amount_in = order.amount_in;
+    let transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0, ErrorCode::TransferFeeExceedsInput);
+
+    let (trade_direction, vault_0_amount, vault_1_amount) = if ctx.accounts.input_vault.key()
+        == pool_state.token_0_vault
+        && ctx.accounts.output_vault.key() == pool_state.token_1_vault
+    {
+        (
+            TradeDirection::ZeroForOne,
+            ctx.accounts.input_vault.amount,
+            ctx.accounts.output_vault.amount,
+        )
+    } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
+        && ctx.accounts.output_vault.key() == pool_state.token_0_vault
+    {
+        (
+            TradeDirection::OneForZero,
+            ctx.accounts.output_vault.amount,
+            ctx.accounts.input_vault.amount,
+        )
+    } else {
+        return err!(ErrorCode::InvalidVault);
+    };
+    let pool_vaults = PoolVaults::from_trade(
+        trade_direction == TradeDirection::ZeroForOne,
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
+    pool_state.check_launch_trade(trade_direction, false)?;
+    let (total_token_0_amount, total_token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    let (token_0_price_x64, token_1_price_x64) =
+        pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+    let (total_input_token_amount, total_output_token_amount) = match trade_direction {
+        TradeDirection::ZeroForOne => (total_token_0_amount, total_token_1_amount),
+        TradeDirection::OneForZero => (total_token_1_amount, total_token_0_amount),
+    };
+    // boosted pools trade on the real reserves offset by their virtual reserves
+    let (curve_input_token_amount, curve_output_token_amount) = pool_state.curve_trade_amounts(
+        trade_direction,
+        total_input_token_amount,
+        total_output_token_amount,
+    );
+
+    let trade_fee_rate = {
+        let (observation_state, observation_extension, _) =
+            ctx.accounts.observation_state.load_observations()?;
+        ctx.accounts.amm_config.swap_trade_fee_rate(
+            pool_state.trade_fee_rate_override(block_timestamp),
+            &observation_state,
+            &observation_extension,
+            block_timestamp,
+            token_0_price_x64,
+        )
+    };
+    let curve_type = pool_state.curve(block_timestamp)?.oriented(trade_direction);
+    let amount_scale = get_amount_scale(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+    )?;
+    let constant_before = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        curve_input_token_amount,
+        curve_output_token_amount,
+    )
+    .unwrap();
+
+    let (protocol_fee_rate, fund_fee_rate) = pool_state.fee_split(&ctx.accounts.amm_config);
+    let result = CurveCalculator::swap_base_input_scaled(
+        curve_type,
+        amount_scale,
+        u128::from(actual_amount_in),
+        curve_input_token_amount,
+        curve_output_token_amount,
+        FeeRates {
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+        },
+    )?;
+
+    let constant_after = CurveCalculator::invariant_scaled(
+        curve_type,
+        amount_scale,
+        result
+            .new_swap_source_amount
+            .checked_sub(result.trade_fee)
+            .unwrap(),
+        result.new_swap_destination_amount,
+    )
+    .unwrap();
+    require_gte!(
+        constant_after,
+        constant_before,
+        ErrorCode::CurveInvariantViolated
+    );
+    require_eq!(
+        u64::try_from(result.source_amount_swapped).unwrap(),
+        actual_amount_in
+    );
+    pool_state.check_auction_size(actual_amount_in)?;
+    pool_state.check_trade_size(trade_direction, &result)?;
+    // the virtual reserves only deepen the curve, the output is paid from the real reserve
+    require_gt!(
+        u128::from(total_output_token_amount),
+        result.destination_amount_swapped,
+        ErrorCode::ExceededVirtualReserveRange
+    );
+    pool_state.check_swap_outflow(
+        trade_direction,
+        vault_0_amount,
+        vault_1_amount,
+        u64::try_from(result.destination_amount_swapped).unwrap(),
+    )?;
+    pool_state.check_price_impact(
+        curve_input_token_amount,
+        curve_output_token_amount,
+        result.new_swap_source_amount,
+        result.new_swap_destination_amount,
+    )?;
+
+    let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
+    let output_transfer_fee = get_transfer_fee(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        amount_out,
+    )?;
+    let amount_received = amount_out.checked_sub(output_transfer_fee).unwrap();
+    require_gt!(amount_received, 0);
+    require_gte!(
+        amount_received,
+        order.minimum_amount_out,
+        ErrorCode::ExceededSlippage
+    );
+
+    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(result.fund_fee).unwrap();
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        }
+        TradeDirection::OneForZero => {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    };
+    let creator_fee = pool_state.accrue_creator_fee(trade_direction, result.trade_fee);
+    pool_state.record_swap(trade_direction, &result, creator_fee, block_timestamp);
+
+    let reserves_before = PoolReserves::from_trade_amounts(
+        trade_direction,
+        total_input_token_amount,
+        total_output_token_amount,
+        pool_state.lp_supply,
+    );
+    let reserves_after = reserves_before.after_swap(trade_direction, &result, creator_fee);
+    pool_state.check_price_band(&reserves_before, &reserves_after, block_timestamp)?;
+    emit!(SwapEvent {
+        pool_id,
+        input_vault_before: total_input_token_amount,
+        output_vault_before: total_output_token_amount,
+        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
+        output_amount: amount_out,
+        input_transfer_fee: transfer_fee,
+        output_transfer_fee,
+        base_input: true,
+        trade_fee: u64::try_from(result.trade_fee).unwrap(),
+        protocol_fee,
+        fund_fee,
+        referral_fee: 0,
+        creator_fee,
+        reserves_before,
+        reserves_after,
+    });
+
+    // The owner approved its order authority as the delegate of the input token account
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.order_authority.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        &[&[
+            SWAP_ORDER_AUTHORITY_SEED.as_bytes(),
+            order.owner.as_ref(),
+            &[ctx.bumps.order_authority],
+        ]],
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_token_program.to_account_info(),
+        amount_out,
+        ctx.accounts.output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    // update the previous price to the observation
+    let (mut observation_state, mut observation_extension, mut log_price_observations) =
+        ctx.accounts.observation_state.load_observations_mut()?;
+    if let Some(deviation_bps) = pool_state.update_circuit_breaker(
+        &observation_state,
+        &observation_extension,
+        block_timestamp,
+        token_0_price_x64,
+        token_1_price_x64,
+        &reserves_after,
+    ) {
+        emit!(CircuitBreakerTrippedEvent {
+            pool_id,
+            deviation_bps,
+            threshold_bps: pool_state.circuit_breaker_threshold_bps,
+            window: pool_state.circuit_breaker_window,
+        });
+    }
+    observation_state.update(
+        &mut observation_extension,
+        &mut log_price_observations,
+        block_timestamp,
+        token_0_price_x64,
+        token_1_price_x64,
+        ctx.accounts.amm_config.observation_update_duration(),
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(pool_state, None, Some(&pool_snapshot))?;
+    pool_state.unlock();
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    /// The factory state to read the observation update duration
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Pool state account
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn sync_reserves(ctx: Context<SyncReserves>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    // the vaults of a pool held by a swap are mid transfer, their price is not recorded
+    require!(!pool_state.is_locked(), ErrorCode::ReentrancyDetected);
+
+    // The reserves are the vault balances less the accrued protocol, fund and creator fees, so
+    // a token sent directly to a vault is already part of the liquidity backing the lp
+    // supply. Syncing records the resulting price in the oracle right away, instead of
+    // it being accounted over the interval before the next trade
+    let vault_0_amount = ctx.accounts.token_0_vault.amount;
+    let vault_1_amount = ctx.accounts.token_1_vault.amount;
+    let accrued_fees_0 = pool_state
+        .protocol_fees_token_0
+        .checked_add(pool_state.fund_fees_token_0)
+        .and_then(|fees| fees.checked_add(pool_state.creator_fees_token_0))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let accrued_fees_1 = pool_state
+        .protocol_fees_token_1
+        .checked_add(pool_state.fund_fees_token_1)
+        .and_then(|fees| fees.checked_add(pool_state.creator_fees_token_1))
+        .ok_or(ErrorCode::MathOverflow)?;
+    require_gte!(vault_0_amount, accrued_fees_0, ErrorCode::InvalidVault);
+    require_gte!(vault_1_amount, accrued_fees_1, ErrorCode::InvalidVault);
+    let (token_0_amount, token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    require!(
+        token_0_amount > 0 && token_1_amount > 0,
+        ErrorCode::ZeroTradingTokens
+    );
+    let block_timestamp = oracle::block_timestamp();
+    let (token_0_price_x32, token_1_price_x32) =
+        pool_state.token_price_x32(vault_0_amount, vault_1_amount, block_timestamp);
+
+    let (mut observation_state, mut observation_extension, mut log_price_observations) =
+        ctx.accounts.observation_state.load_observations_mut()?;
+    observation_state.update(
+        &mut observation_extension,
+        &mut log_price_observations,
+        block_timestamp,
+        token_0_price_x32,
+        token_1_price_x32,
+        ctx.accounts.amm_config.observation_update_duration(),
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "sync reserves, token_0_amount:{}, token_1_amount:{}",
+        token_0_amount,
+        token_1_amount
+    );
+    emit!(SyncReservesEvent {
+        pool_id,
+        reserves: PoolReserves::new(token_0_amount, token_1_amount, pool_state.lp_supply),
+    });
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(trader: Pubkey)]
+pub struct AddRegisteredTrader<'info> {
+    /// Only the registry authority can register traders
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::InvalidAuthority
+    )]
+    pub trader_registry: Account<'info, TraderRegistry>,
+
+    /// Allows the trader to swap in the pools of the registry
+    #[account(
+        init,
+        seeds = [
+            TRADER_ENTRY_SEED.as_bytes(),
+            trader_registry.key().as_ref(),
+            trader.as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = TraderEntry::LEN
+    )]
+    pub trader_entry: Account<'info, TraderEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_registered_trader(ctx: Context<AddRegisteredTrader>, trader: Pubkey) -> Result<()> {
+    let trader_entry = &mut ctx.accounts.trader_entry;
+    trader_entry.bump = ctx.bumps.trader_entry;
+    trader_entry.trader_registry = ctx.accounts.trader_registry.key();
+    trader_entry.trader = trader;
+    let trader_registry = &mut ctx.accounts.trader_registry;
+    trader_registry.trader_count = trader_registry.trader_count.checked_add(1).unwrap();
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "registered trader, trader_registry:{}, trader:{}",
+        trader_entry.trader_registry.to_string(),
+        trader.to_string()
+    );
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateTraderRegistry<'info> {
+    /// The authority of the registry, usually the custom authority of its pools
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Lists the traders allowed to swap in the pools pointing to it
+    #[account(
+        init,
+        seeds = [
+            TRADER_REGISTRY_SEED.as_bytes(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = TraderRegistry::LEN
+    )]
+    pub trader_registry: Account<'info, TraderRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_trader_registry(ctx: Context<CreateTraderRegistry>) -> Result<()> {
+    let trader_registry = &mut ctx.accounts.trader_registry;
+    trader_registry.bump = ctx.bumps.trader_registry;
+    trader_registry.authority = ctx.accounts.authority.key();
+    trader_registry.trader_count = 0;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "trader registry, authority:{}",
+        trader_registry.authority.to_string()
+    );
+    Ok(())
+}
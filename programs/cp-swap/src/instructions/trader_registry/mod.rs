@@ -0,0 +1,8 @@
+pub mod create_trader_registry;
+pub use create_trader_registry::*;
+
+pub mod add_registered_trader;
+pub use add_registered_trader::*;
+
+pub mod remove_registered_trader;
+pub use remove_registered_trader::*;
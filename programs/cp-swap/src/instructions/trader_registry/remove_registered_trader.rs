@@ -0,0 +1,35 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemoveRegisteredTrader<'info> {
+    /// Only the registry authority can remove registered traders, receives the rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::InvalidAuthority
+    )]
+    pub trader_registry: Account<'info, TraderRegistry>,
+
+    /// The trader entry to close
+    #[account(
+        mut,
+        seeds = [
+            TRADER_ENTRY_SEED.as_bytes(),
+            trader_registry.key().as_ref(),
+            trader_entry.trader.as_ref(),
+        ],
+        bump = trader_entry.bump,
+        close = authority
+    )]
+    pub trader_entry: Account<'info, TraderEntry>,
+}
+
+pub fn remove_registered_trader(ctx: Context<RemoveRegisteredTrader>) -> Result<()> {
+    let trader_registry = &mut ctx.accounts.trader_registry;
+    trader_registry.trader_count = trader_registry.trader_count.saturating_sub(1);
+    Ok(())
+}
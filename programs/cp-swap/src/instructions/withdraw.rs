@@ -3,6 +3,7 @@ use crate::curve::RoundDirection;
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::token::*;
+use crate::utils::PoolVaults;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     memo::spl_memo,
@@ -12,7 +13,8 @@ use anchor_spl::{
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
-    /// Pays to mint the position
+    /// Pays to mint the position, receives the lamports of the unwrapped WSOL account
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -94,21 +96,75 @@ pub struct Withdraw<'info> {
         address = spl_memo::id()
     )]
     pub memo_program: UncheckedAccount<'info>,
+
+    /// The deposit receipt of the owner, its unlocked lp tokens are withdrawn without the
+    /// early exit fee of a pool with a withdrawal lockup
+    #[account(
+        mut,
+        seeds = [
+            DEPOSIT_RECEIPT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = deposit_receipt.bump,
+    )]
+    pub deposit_receipt: Option<Box<Account<'info, DepositReceipt>>>,
+}
+
+impl<'info> Withdraw<'info> {
+    /// Unwrap the WSOL side of the withdrawal by closing the owner token account
+    pub fn unwrap_sol(&self) -> Result<()> {
+        for (token_account, mint) in [
+            (&self.token_0_account, &self.vault_0_mint),
+            (&self.token_1_account, &self.vault_1_mint),
+        ] {
+            if is_native_mint(&mint.key()) {
+                unwrap_native_sol(
+                    self.owner.to_account_info(),
+                    token_account.to_account_info(),
+                    self.token_program.to_account_info(),
+                )?;
+            }
+        }
+        Ok(())
+    }
 }
 
-pub fn withdraw(
-    ctx: Context<Withdraw>,
+pub fn withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>,
     lp_token_amount: u64,
     minimum_token_0_amount: u64,
     minimum_token_1_amount: u64,
+    wrap_sol: bool,
+    deadline: Option<u64>,
+    price_slippage: Option<PriceSlippage>,
 ) -> Result<()> {
     require_gt!(lp_token_amount, 0);
+    if let Some(deadline) = deadline {
+        require_gte!(
+            deadline,
+            Clock::get()?.unix_timestamp as u64,
+            ErrorCode::DeadlineExceeded
+        );
+    }
     require_gt!(ctx.accounts.lp_mint.supply, 0);
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
         return err!(ErrorCode::NotApproved);
     }
+    require!(
+        !pool_state.is_flash_loan_active(),
+        ErrorCode::FlashLoanInProgress
+    );
+    pool_state.lock()?;
+    let pool_vaults = PoolVaults::new(
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+    );
+    let pool_snapshot = pool_vaults.snapshot(pool_state)?;
     
     // For custom authority pools, only the custom authority can withdraw
     if pool_state.is_custom_authority() {
@@ -121,19 +177,37 @@ pub fn withdraw(
         ctx.accounts.token_0_vault.amount,
         ctx.accounts.token_1_vault.amount,
     );
+    if let Some(price_slippage) = price_slippage {
+        price_slippage.check(total_token_0_amount, total_token_1_amount)?;
+    }
     let results = CurveCalculator::lp_tokens_to_trading_tokens(
         u128::from(lp_token_amount),
         u128::from(pool_state.lp_supply),
         u128::from(total_token_0_amount),
         u128::from(total_token_1_amount),
         RoundDirection::Floor,
-    )
-    .ok_or(ErrorCode::ZeroTradingTokens)?;
+    )?;
     if results.token_0_amount == 0 || results.token_1_amount == 0 {
         return err!(ErrorCode::ZeroTradingTokens);
     }
+    // the lp tokens not unlocked by the deposit receipt pay the early exit fee, which stays
+    // in the vaults for the remaining liquidity providers
+    let early_exit_lp_amount = match ctx.accounts.deposit_receipt.as_mut() {
+        Some(deposit_receipt) => {
+            let unlocked_lp_amount = deposit_receipt.unlocked_lp_amount(
+                Clock::get()?.unix_timestamp as u64,
+                pool_state.min_lock_duration,
+            );
+            deposit_receipt.record_withdrawal(lp_token_amount);
+            lp_token_amount.saturating_sub(unlocked_lp_amount)
+        }
+        None => lp_token_amount,
+    };
     let token_0_amount = u64::try_from(results.token_0_amount).unwrap();
     let token_0_amount = std::cmp::min(total_token_0_amount, token_0_amount);
+    let early_exit_fee_0 =
+        pool_state.early_exit_fee(token_0_amount, early_exit_lp_amount, lp_token_amount);
+    let token_0_amount = token_0_amount - early_exit_fee_0;
     let (receive_token_0_amount, token_0_transfer_fee) = {
         let transfer_fee =
             get_transfer_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
@@ -145,6 +219,9 @@ pub fn withdraw(
 
     let token_1_amount = u64::try_from(results.token_1_amount).unwrap();
     let token_1_amount = std::cmp::min(total_token_1_amount, token_1_amount);
+    let early_exit_fee_1 =
+        pool_state.early_exit_fee(token_1_amount, early_exit_lp_amount, lp_token_amount);
+    let token_1_amount = token_1_amount - early_exit_fee_1;
     let (receive_token_1_amount, token_1_transfer_fee) = {
         let transfer_fee =
             get_transfer_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
@@ -176,6 +253,41 @@ pub fn withdraw(
         token_1_transfer_fee,
         change_type: 1
     });
+    emit!(WithdrawEvent {
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_amount: lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        trade_fee: 0,
+        protocol_fee: 0,
+        fund_fee: 0,
+        reserves_before: PoolReserves::new(
+            total_token_0_amount,
+            total_token_1_amount,
+            pool_state.lp_supply,
+        ),
+        reserves_after: PoolReserves::new(
+            total_token_0_amount.checked_sub(token_0_amount).unwrap(),
+            total_token_1_amount.checked_sub(token_1_amount).unwrap(),
+            pool_state
+                .lp_supply
+                .checked_sub(lp_token_amount)
+                .ok_or(ErrorCode::LpSupplyUnderflow)?,
+        ),
+    });
+
+    if early_exit_fee_0 != 0 || early_exit_fee_1 != 0 {
+        emit!(EarlyExitFeeEvent {
+            pool_id,
+            owner: ctx.accounts.owner.key(),
+            early_exit_lp_amount,
+            token_0_fee: early_exit_fee_0,
+            token_1_fee: early_exit_fee_1,
+        });
+    }
 
     if receive_token_0_amount < minimum_token_0_amount
         || receive_token_1_amount < minimum_token_1_amount
@@ -183,16 +295,31 @@ pub fn withdraw(
         return Err(ErrorCode::ExceededSlippage.into());
     }
 
-    pool_state.lp_supply = pool_state.lp_supply.checked_sub(lp_token_amount).unwrap();
+    let lp_supply = pool_state
+        .lp_supply
+        .checked_sub(lp_token_amount)
+        .ok_or(ErrorCode::LpSupplyUnderflow)?;
+    pool_state.set_lp_supply(lp_supply);
     token_burn(
         ctx.accounts.owner.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        if ctx.accounts.lp_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
         ctx.accounts.lp_mint.to_account_info(),
         ctx.accounts.owner_lp_token.to_account_info(),
         lp_token_amount,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
     )?;
 
+    // the reserves lent to the strategy are not in the vaults to pay the withdrawal
+    pool_state.check_vault_outflow(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+        token_0_amount,
+        token_1_amount,
+    )?;
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.token_0_vault.to_account_info(),
@@ -206,6 +333,7 @@ pub fn withdraw(
         token_0_amount,
         ctx.accounts.vault_0_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_pool_vault_to_user(
@@ -221,8 +349,18 @@ pub fn withdraw(
         token_1_amount,
         ctx.accounts.vault_1_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
     )?;
+    if wrap_sol {
+        ctx.accounts.unwrap_sol()?;
+    }
     pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_vaults.check(
+        pool_state,
+        Some(&ctx.accounts.lp_mint.to_account_info()),
+        Some(&pool_snapshot),
+    )?;
+    pool_state.unlock();
 
     Ok(())
 }
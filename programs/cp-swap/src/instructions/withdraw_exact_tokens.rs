@@ -0,0 +1,64 @@
+use crate::curve::CurveCalculator;
+use crate::error::ErrorCode;
+use crate::instructions::{withdraw, Withdraw};
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+
+pub fn withdraw_exact_tokens<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>,
+    token_0_amount: u64,
+    token_1_amount: u64,
+    maximum_lp_token_amount: u64,
+    wrap_sol: bool,
+    deadline: Option<u64>,
+) -> Result<()> {
+    let lp_token_amount = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        );
+        // the vaults send the token amounts plus their transfer fees
+        let transfer_token_0_amount = token_0_amount
+            + get_transfer_inverse_fee(
+                &ctx.accounts.vault_0_mint.to_account_info(),
+                token_0_amount,
+            )?;
+        let transfer_token_1_amount = token_1_amount
+            + get_transfer_inverse_fee(
+                &ctx.accounts.vault_1_mint.to_account_info(),
+                token_1_amount,
+            )?;
+        let lp_token_amount = CurveCalculator::trading_tokens_to_lp_tokens_ceiling(
+            u128::from(transfer_token_0_amount),
+            u128::from(transfer_token_1_amount),
+            u128::from(pool_state.lp_supply),
+            u128::from(total_token_0_amount),
+            u128::from(total_token_1_amount),
+        )
+        .and_then(|lp_token_amount| {
+            u64::try_from(lp_token_amount).map_err(|_| error!(ErrorCode::MathOverflow))
+        })?;
+        require_gte!(
+            pool_state.lp_supply,
+            lp_token_amount,
+            ErrorCode::ExceededSlippage
+        );
+        lp_token_amount
+    };
+    require_gte!(
+        maximum_lp_token_amount,
+        lp_token_amount,
+        ErrorCode::ExceededSlippage
+    );
+    // the token amounts of the lp are at least the given amounts, which bound the receipts
+    withdraw(
+        ctx,
+        lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        wrap_sol,
+        deadline,
+        None,
+    )
+}
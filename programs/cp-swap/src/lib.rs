@@ -40,6 +40,14 @@ pub mod create_pool_fee_reveiver {
     pub const ID: Pubkey = pubkey!("GsV1jugD8ftfWBYNykA9SLK2V4mQqUW2sLop8MAfjVRq");
 }
 
+pub mod legacy_amm_program {
+    use super::{pubkey, Pubkey};
+    #[cfg(feature = "devnet")]
+    pub const ID: Pubkey = pubkey!("HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8");
+    #[cfg(not(feature = "devnet"))]
+    pub const ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+}
+
 pub const AUTH_SEED: &str = "vault_and_lp_mint_auth_seed";
 
 #[program]
@@ -86,15 +94,212 @@ pub mod raydium_cp_swap {
     /// * `trade_fee_rate`- The new trade fee rate of amm config, be set when `param` is 0
     /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1
     /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
-    /// * `new_owner`- The config's new owner, be set when `param` is 3
-    /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `flash_loan_fee_rate`- The new flash loan fee rate of amm config, be set when `param` is 7
+    /// * `dynamic_fee_min_rate`- The new lower bound of the dynamic trade fee, at most the upper bound of an enabled dynamic fee mode, be set when `param` is 8
+    /// * `dynamic_fee_max_rate`- The new upper bound of the dynamic trade fee, at least the lower bound, 0 disables the dynamic fee mode, be set when `param` is 9
+    /// * `dynamic_fee_volatility_factor`- The new trade fee added per unit of volatility, be set when `param` is 10
+    /// * `pool_creation_authority`- The only address allowed to create pools, passed as the first remaining account, permissionless if absent, be set when `param` is 11
+    /// * `protocol_fee_compound_rate`- The new fraction of protocol fees re-deposited by `compound_fees`, be set when `param` is 12
+    /// * `referral_fee_rate`- The new referrer share of the trade fee, be set when `param` is 13
+    /// * `fee_crank_bounty_rate`- The new share of the swept fees paid to the `crank_protocol_fees` caller, be set when `param` is 14
+    /// * `min_trade_fee_rate`- The new lower bound of the trade fee custom authority pools can set, be set when `param` is 15
+    /// * `max_trade_fee_rate`- The new upper bound of the trade fee custom authority pools can set, 0 disables pool trade fees, be set when `param` is 16
+    /// * `buyback_mint`- The token bought back and burned with the fund fees, passed as the first remaining account, disabled if absent, be set when `param` is 17
+    /// * `buyback_epoch_cap`- The new maximum of fund fees a pool spends on buybacks per epoch, 0 is unbounded, be set when `param` is 18
+    /// * `protocol_fee_destination`- The owner of the token accounts protocol fees are collected to by anyone, passed as the first remaining account, only the protocol owner collects if absent, be set when `param` is 19
+    /// * `fund_fee_destination`- The owner of the token accounts fund fees are collected to by anyone, passed as the first remaining account, only the fund owner collects if absent, be set when `param` is 20
+    /// * `create_pool_fee_mint`- The mint the fee to create a pool is paid in, passed as the first remaining account, be set when `param` is 21
+    /// * `create_pool_fee_receiver`- The owner of the token account receiving the fee to create a pool, passed as the first remaining account, be set when `param` is 22
+    /// * `withheld_fees_to_protocol`- Credit the transfer fees harvested from the vaults to the protocol fees when not 0, be set when `param` is 23
+    /// * `observation_update_duration`- The minimum seconds between two oracle observations of the pools, the default duration when 0, be set when `param` is 24
+    /// * `mint_policy`- The mint policy of the amm config new pools are checked against, passed as the first remaining account, any supported mint is allowed if absent, be set when `param` is 25
+    /// * `fee_on_output`- Charge the swap trade fee on the output token instead of the input token when not 0, be set when `param` is 26
+    /// * `allow_salted_pools`- Allow creating pools at a salted address with `initialize_with_salt` when not 0, be set when `param` is 27
+    /// * `pause_guardian`- The address allowed to pause the pools of the config and their creation, passed as the first remaining account, disabled if absent, be set when `param` is 28
+    /// * `revenue_program`- The revenue distribution program `collect_protocol_fee` deposits the protocol fees to, passed as the first remaining account, disabled if absent, be set when `param` is 29
+    /// * `insurance_fund_rate`- The new share of the fund fees `collect_fund_fee` sends to the insurance fund of the config, be set when `param` is 30
+    /// * `max_transfer_fee_bps`- The new maximum token 2022 transfer fee in basis points of the mints of the pools, checked at pool creation and on each swap, 0 is unbounded, be set when `param` is 31
+    /// * `lp_fee_rebate_rate`- The new share of the liquidity provider part of the trade fee rebated as lp tokens to the payers of the swaps passing their lp token account, be set when `param` is 32
+    /// * `lp_fee_rebate_epoch_cap`- The new maximum of lp tokens a pool mints for lp fee rebates per epoch, 0 is unbounded, be set when `param` is 33
+    /// * `buyback_max_deviation_bps`- The new maximum in basis points the price of a buyback may fall below the TWAP of its pool, 0 disables buybacks, be set when `param` is 34
+    /// * `buyback_twap_window`- The new seconds of the TWAP buybacks are priced against, be set when `param` is 35
+    /// * `param`- The value can be 0 | 1 | 2 | 5 ~ 35, otherwise will report a error,
+    ///   the owners are transferred by `propose_new_admin` and `accept_admin`.
+    ///   Fee rate changes (0 | 1 | 2 | 7 ~ 10 | 13 | 32) are queued in a `PendingConfigChange` account
+    ///   and applied by `execute_config_change` after `CONFIG_CHANGE_DELAY`
     ///
     pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
         instructions::update_amm_config(ctx, param, value)
     }
 
-    /// Update pool status for given value
+    /// Apply a queued fee rate change once its timelock has expired, can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change(ctx)
+    }
+
+    /// Set the trade fee discount of the holders of a token, must be called by the admin.
+    /// A swap passing a token account of the payer holding at least the balance of a tier
+    /// is charged the trade fee reduced by the discount of the tier
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `fee_discount_mint` - The token granting the discount, the default pubkey disables the fee discount
+    /// * `tiers` - At most `FEE_DISCOUNT_TIER_COUNT` balance tiers with their discount in basis points, sorted by increasing balance and discount
+    ///
+    pub fn update_fee_discount(
+        ctx: Context<UpdateFeeDiscount>,
+        fee_discount_mint: Pubkey,
+        tiers: Vec<states::FeeDiscountTier>,
+    ) -> Result<()> {
+        instructions::update_fee_discount(ctx, fee_discount_mint, tiers)
+    }
+
+    /// Create the mint policy of an amm config and reference it from the amm config, the
+    /// pools are then only created with the mints it allows. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `mode` - 0 bans the listed mints, 1 only allows the listed mints, see `MintPolicyMode`
+    ///
+    pub fn create_mint_policy(ctx: Context<CreateMintPolicy>, mode: u8) -> Result<()> {
+        instructions::create_mint_policy(ctx, mode)
+    }
+
+    /// Issue the token badge of a vetted token program 2022 mint, the pools can then be
+    /// created with the mint despite its permanent delegate or transfer hook. The badge
+    /// records the hook program of the mint, whose vault transfers then pass the badge in
+    /// the remaining accounts to run the hook. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_token_badge(ctx: Context<CreateTokenBadge>) -> Result<()> {
+        instructions::create_token_badge(ctx)
+    }
+
+    /// Revoke the token badge of a mint, no more pools can be created with it and the
+    /// vault transfers of a transfer hook mint stop. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn close_token_badge(ctx: Context<CloseTokenBadge>) -> Result<()> {
+        instructions::close_token_badge(ctx)
+    }
+
+    /// Create the insurance fund of an amm config, receiving a share of the fund fees of
+    /// its pools. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_insurance_fund(ctx: Context<CreateInsuranceFund>) -> Result<()> {
+        instructions::create_insurance_fund(ctx)
+    }
+
+    /// Inject tokens of the insurance fund into a vault of a pool of its amm config to
+    /// cover a shortfall after an exploit or an accounting bug. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount` - Amount of tokens sent to the vault, including the transfer fee
+    ///
+    pub fn cover_shortfall<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CoverShortfall<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::cover_shortfall(ctx, amount)
+    }
+
+    /// List or unlist mints in a mint policy and set its mode, must be called by the admin.
+    /// Existing pools are not affected
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `mode` - 0 bans the listed mints, 1 only allows the listed mints, see `MintPolicyMode`
+    /// * `mints` - The mints to list or unlist, at most `MAX_POLICY_MINTS` are listed
+    /// * `listed` - Whether the mints are listed or unlisted
+    ///
+    pub fn update_mint_policy(
+        ctx: Context<UpdateMintPolicy>,
+        mode: u8,
+        mints: Vec<Pubkey>,
+        listed: bool,
+    ) -> Result<()> {
+        instructions::update_mint_policy(ctx, mode, mints, listed)
+    }
+
+    /// Create the fee exemption list of an amm config, the swaps of its listed traders
+    /// pay a reduced or no protocol fee. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_fee_exemption_list(ctx: Context<CreateFeeExemptionList>) -> Result<()> {
+        instructions::create_fee_exemption_list(ctx)
+    }
+
+    /// List traders in a fee exemption list, update their exemption or unlist them, must
+    /// be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `traders` - The traders to update, at most `MAX_FEE_EXEMPTIONS` are listed
+    /// * `exemption_bps` - The share of the protocol fee in basis points the traders do not pay, 0 unlists them
+    ///
+    pub fn update_fee_exemptions(
+        ctx: Context<UpdateFeeExemptions>,
+        traders: Vec<Pubkey>,
+        exemption_bps: u16,
+    ) -> Result<()> {
+        instructions::update_fee_exemptions(ctx, traders, exemption_bps)
+    }
+
+    /// Propose a new admin for a role of an amm config or a custom authority pool,
+    /// the role is transferred once the new admin calls `accept_admin`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `role` - 0: protocol owner, 1: fund owner, 2: pool custom authority
+    /// * `new_admin` - The proposed admin
+    ///
+    pub fn propose_new_admin(
+        ctx: Context<ProposeNewAdmin>,
+        role: u8,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_new_admin(ctx, role, new_admin)
+    }
+
+    /// Accept a proposed role, must be signed by the proposed admin. The proposal is
+    /// rejected once its proposer no longer holds the role
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `role` - 0: protocol owner, 1: fund owner, 2: pool custom authority
+    ///
+    pub fn accept_admin(ctx: Context<AcceptAdmin>, role: u8) -> Result<()> {
+        instructions::accept_admin(ctx, role)
+    }
+
+    /// Update pool status for given value, the pause guardian of the amm config can only
+    /// disable more operations
     ///
     /// # Arguments
     ///
@@ -105,131 +310,2088 @@ pub mod raydium_cp_swap {
         instructions::update_pool_status(ctx, status)
     }
 
-    /// Collect the protocol fee accrued to the pool
+    /// Disable or enable a single operation of the pool, the other operations are left unchanged.
+    /// The pause guardian of the amm config can only disable operations
     ///
     /// # Arguments
     ///
-    /// * `ctx` - The context of accounts
-    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
-    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    /// * `ctx`- The context of accounts
+    /// * `bit` - 0: deposit, 1: withdraw, 2: swap, 3: flash loan
+    /// * `disable` - Whether to disable or enable the operation
     ///
-    pub fn collect_protocol_fee(
-        ctx: Context<CollectProtocolFee>,
-        amount_0_requested: u64,
-        amount_1_requested: u64,
+    pub fn update_pool_status_v2(
+        ctx: Context<UpdatePoolStatus>,
+        bit: u8,
+        disable: bool,
     ) -> Result<()> {
-        instructions::collect_protocol_fee(ctx, amount_0_requested, amount_1_requested)
+        instructions::update_pool_status_v2(ctx, bit, disable)
     }
 
-    /// Collect the fund fee accrued to the pool
+    /// Pause the pools of an amm config passed as remaining accounts, and optionally the
+    /// creation of new pools, must be called by the admin or the pause guardian of the amm
+    /// config. Only the admin resumes them with `update_pool_status` and `update_amm_config`
     ///
     /// # Arguments
     ///
-    /// * `ctx` - The context of accounts
-    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
-    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    /// * `ctx`- The context of accounts
+    /// * `disable_create_pool` - Whether to disable the creation of new pools too
     ///
-    pub fn collect_fund_fee(
-        ctx: Context<CollectFundFee>,
-        amount_0_requested: u64,
-        amount_1_requested: u64,
+    pub fn pause_pools<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, PausePools<'info>>,
+        disable_create_pool: bool,
     ) -> Result<()> {
-        instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
+        instructions::pause_pools(ctx, disable_create_pool)
     }
 
-    /// Creates a pool for the given token pair and the initial price
+    /// Apply a batch of pause, status, trade fee rate and custom authority operations to
+    /// the pools passed as remaining accounts, must be called by the admin. The batch is
+    /// applied all or nothing
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `init_amount_0` - the initial amount_0 to deposit
-    /// * `init_amount_1` - the initial amount_1 to deposit
-    /// * `open_time` - the timestamp allowed for swap
-    /// * `authority_type` - 0 for default PDA authority, 1 for custom authority
-    /// * `custom_authority` - custom authority pubkey (required when authority_type = 1)
+    /// * `ops` - The operations with the index of their pool in the remaining accounts
     ///
-    pub fn initialize(
-        ctx: Context<Initialize>,
-        init_amount_0: u64,
-        init_amount_1: u64,
-        open_time: u64,
-        authority_type: u8,
-        custom_authority: Option<Pubkey>,
+    pub fn batch_admin<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, BatchAdmin<'info>>,
+        ops: Vec<states::BatchAdminOp>,
     ) -> Result<()> {
-        instructions::initialize(ctx, init_amount_0, init_amount_1, open_time, authority_type, custom_authority)
+        instructions::batch_admin(ctx, ops)
     }
 
-    /// Deposit lp token to the pool
+    /// Set the trade fee rate of a custom authority pool within the bounds of its amm config,
+    /// must be called by the custom authority
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `lp_token_amount` - Pool token amount to transfer. token_a and token_b amount are set by the current exchange rate and size of the pool
-    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
-    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
+    /// * `trade_fee_rate` - The trade fee rate of the pool, None to use the trade fee rate of the amm config
     ///
-    pub fn deposit(
-        ctx: Context<Deposit>,
-        lp_token_amount: u64,
-        maximum_token_0_amount: u64,
-        maximum_token_1_amount: u64,
+    pub fn update_pool_fee_rate(
+        ctx: Context<UpdatePoolFeeRate>,
+        trade_fee_rate: Option<u64>,
     ) -> Result<()> {
-        instructions::deposit(
-            ctx,
-            lp_token_amount,
-            maximum_token_0_amount,
-            maximum_token_1_amount,
-        )
+        instructions::update_pool_fee_rate(ctx, trade_fee_rate)
     }
 
-    /// Withdraw lp for token0 and token1
+    /// Set the split of the trade fee of the pool between the liquidity providers, the
+    /// protocol and the fund, must be called by the admin
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `lp_token_amount` - Amount of pool tokens to burn. User receives an output of token a and b based on the percentage of the pool tokens that are returned.
-    /// * `minimum_token_0_amount` -  Minimum amount of token 0 to receive, prevents excessive slippage
-    /// * `minimum_token_1_amount` -  Minimum amount of token 1 to receive, prevents excessive slippage
+    /// * `protocol_fee_rate` - The share of the trade fee taken as protocol fee, None with `fund_fee_rate` to use the split of the amm config
+    /// * `fund_fee_rate` - The share of the trade fee taken as fund fee, None with `protocol_fee_rate` to use the split of the amm config
     ///
-    pub fn withdraw(
-        ctx: Context<Withdraw>,
-        lp_token_amount: u64,
-        minimum_token_0_amount: u64,
-        minimum_token_1_amount: u64,
+    pub fn set_pool_fee_split(
+        ctx: Context<SetPoolFeeSplit>,
+        protocol_fee_rate: Option<u64>,
+        fund_fee_rate: Option<u64>,
     ) -> Result<()> {
-        instructions::withdraw(
+        instructions::set_pool_fee_split(ctx, protocol_fee_rate, fund_fee_rate)
+    }
+
+    /// Set the max price impact of a swap in the pool, must be called by the admin
+    /// or the custom authority of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_price_impact_bps` - Swaps moving the pool price by more basis points revert, 0 disables the check
+    ///
+    pub fn update_pool_max_price_impact(
+        ctx: Context<UpdatePoolMaxPriceImpact>,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        instructions::update_pool_max_price_impact(ctx, max_price_impact_bps)
+    }
+
+    /// Set the trade size limits of the pool, must be called by the admin or the custom
+    /// authority of the pool. Swaps trading fewer or more token_0 revert
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `min_trade_amount` - The fewest token_0 a swap trades, 0 disables the limit
+    /// * `max_trade_amount` - The most token_0 a swap trades, 0 disables the limit
+    ///
+    pub fn update_pool_trade_size_limits(
+        ctx: Context<UpdatePoolTradeSizeLimits>,
+        min_trade_amount: u64,
+        max_trade_amount: u64,
+    ) -> Result<()> {
+        instructions::update_pool_trade_size_limits(ctx, min_trade_amount, max_trade_amount)
+    }
+
+    /// Set the trading schedule of the pool, must be called by the admin or the custom
+    /// authority of the pool. Swaps outside of the trading window or the weekly trading
+    /// hours revert, deposits and withdrawals are always allowed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trading_start_time` - The unix timestamp swaps are allowed from, 0 disables it
+    /// * `trading_end_time` - The unix timestamp swaps revert from, 0 disables it
+    /// * `trading_days` - The days of the week the pool trades on, bit 0 for Monday to bit 6 for Sunday, 0 disables the weekly trading hours
+    /// * `trading_day_open` - The seconds since 00:00 UTC of a trading day swaps are allowed from
+    /// * `trading_day_close` - The seconds since 00:00 UTC of a trading day swaps revert from, at most a day
+    ///
+    pub fn update_pool_trading_schedule(
+        ctx: Context<UpdatePoolTradingSchedule>,
+        trading_start_time: u64,
+        trading_end_time: u64,
+        trading_days: u8,
+        trading_day_open: u32,
+        trading_day_close: u32,
+    ) -> Result<()> {
+        instructions::update_pool_trading_schedule(
             ctx,
-            lp_token_amount,
-            minimum_token_0_amount,
-            minimum_token_1_amount,
+            trading_start_time,
+            trading_end_time,
+            trading_days,
+            trading_day_open,
+            trading_day_close,
         )
     }
 
-    /// Swap the tokens in the pool base input amount
+    /// Set the withdrawal lockup of the pool, must be called by the admin or the custom
+    /// authority of the pool. Withdrawing lp tokens deposited less than `min_lock_duration`
+    /// seconds ago leaves the early exit fee in the vaults for the remaining liquidity
+    /// providers. The liquidity of the pool is then only deposited by `deposit` with the
+    /// deposit receipt of the owner
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
-    /// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
+    /// * `min_lock_duration` - The seconds the deposited lp tokens are locked, 0 disables the lockup
+    /// * `early_exit_fee_bps` - The share of the withdrawn tokens left in the vaults, below 10000
     ///
-    pub fn swap_base_input(
-        ctx: Context<Swap>,
-        amount_in: u64,
-        minimum_amount_out: u64,
+    pub fn update_pool_lockup(
+        ctx: Context<UpdatePoolLockup>,
+        min_lock_duration: u32,
+        early_exit_fee_bps: u16,
     ) -> Result<()> {
-        instructions::swap_base_input(ctx, amount_in, minimum_amount_out)
+        instructions::update_pool_lockup(ctx, min_lock_duration, early_exit_fee_bps)
     }
 
-    /// Swap the tokens in the pool base output amount
+    /// Update the status of a multi token pool, must be called by the admin
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `max_amount_in` -  input amount prevents excessive slippage
-    /// * `amount_out` -  amount of output token
+    /// * `status` - The value of status, a set bit disables the operation as for `update_pool_status`
+    ///
+    pub fn update_multi_pool_status(ctx: Context<UpdateMultiPoolStatus>, status: u8) -> Result<()> {
+        instructions::update_multi_pool_status(ctx, status)
+    }
+
+    /// Queue the reassignment of a pool to another amm config, must be called by the admin.
+    /// It is applied by `execute_pool_config_change` after `CONFIG_CHANGE_DELAY`, a new
+    /// reassignment of the pool replaces the queued one
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn reassign_pool_config(ctx: Context<ReassignPoolConfig>) -> Result<()> {
+        instructions::reassign_pool_config(ctx)
+    }
+
+    /// Apply a queued reassignment of a pool once its timelock has expired, can be called by
+    /// anyone. The accrued protocol and fund fees of the pool must be collected first
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn execute_pool_config_change(ctx: Context<ExecutePoolConfigChange>) -> Result<()> {
+        instructions::execute_pool_config_change(ctx)
+    }
+
+    /// Set the virtual reserve multiplier of a constant product pool and re-center its
+    /// virtual reserves around the current price, must be called by the admin or the
+    /// custom authority of the pool. Swaps which would drain a real reserve revert
+    /// until the range is re-centered by calling it again
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `virtual_reserve_multiplier` - Ratio of the curve to the real liquidity in basis points, at least 10000, 0 disables the virtual reserves
+    ///
+    pub fn update_pool_virtual_reserves(
+        ctx: Context<UpdatePoolVirtualReserves>,
+        virtual_reserve_multiplier: u64,
+    ) -> Result<()> {
+        instructions::update_pool_virtual_reserves(ctx, virtual_reserve_multiplier)
+    }
+
+    /// Set the circuit breaker of the pool, must be called by the admin or the custom
+    /// authority of the pool. A trade moving the price further from its TWAP than the
+    /// threshold pauses the swaps until `unpause_pool`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `threshold_bps` - Max deviation of the price from the TWAP in basis points, 0 disables the circuit breaker
+    /// * `window` - Duration in seconds of the TWAP
+    ///
+    pub fn update_pool_circuit_breaker(
+        ctx: Context<UpdatePoolCircuitBreaker>,
+        threshold_bps: u16,
+        window: u32,
+    ) -> Result<()> {
+        instructions::update_pool_circuit_breaker(ctx, threshold_bps, window)
+    }
+
+    /// Resume the swaps of a pool paused by its circuit breaker, must be called by the admin
+    /// or the custom authority of the pool. The circuit breaker stays idle for a window after it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn unpause_pool(ctx: Context<UpdatePoolCircuitBreaker>) -> Result<()> {
+        instructions::unpause_pool(ctx)
+    }
+
+    /// Set the price band of the pool, must be called by the admin or the custom authority
+    /// of the pool. Trades moving the price of token_0 in token_1 out of the band revert
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `min_price_x64` - The lowest price of token_0 in token_1, Q64.64, 0 is unbounded
+    /// * `max_price_x64` - The highest price of token_0 in token_1, Q64.64, 0 is unbounded
+    ///
+    pub fn update_pool_price_band(
+        ctx: Context<UpdatePoolPriceBand>,
+        min_price_x64: u128,
+        max_price_x64: u128,
+    ) -> Result<()> {
+        instructions::update_pool_price_band(ctx, min_price_x64, max_price_x64)
+    }
+
+    /// Authorize a strategy to borrow pool reserves, must be called by the admin or the
+    /// custom authority of the pool. The strategy cannot change while it owes reserves
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `authorized_strategy` - The address allowed to borrow, the default pubkey disables borrowing
+    /// * `max_borrow_bps` - The share of each reserve in basis points the strategy can have borrowed
     ///
-    pub fn swap_base_output(ctx: Context<Swap>, max_amount_in: u64, amount_out: u64) -> Result<()> {
-        instructions::swap_base_output(ctx, max_amount_in, amount_out)
+    pub fn update_pool_strategy(
+        ctx: Context<UpdatePoolStrategy>,
+        authorized_strategy: Pubkey,
+        max_borrow_bps: u16,
+    ) -> Result<()> {
+        instructions::update_pool_strategy(ctx, authorized_strategy, max_borrow_bps)
+    }
+
+    /// Guard the swaps of a pegged pool with an oracle price, must be called by the admin or
+    /// the custom authority of the pool. Swaps executing too far from the oracle price revert
+    /// and the trades not checking it are refused
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `price_oracle` - The Pyth price update account of token_0 in token_1, the default pubkey removes the guard
+    /// * `max_deviation_bps` - The largest deviation in basis points of a swap price from the oracle price
+    /// * `max_age` - The largest age in seconds of the oracle price
+    ///
+    pub fn update_pool_oracle(
+        ctx: Context<UpdatePoolOracle>,
+        price_oracle: Pubkey,
+        max_deviation_bps: u16,
+        max_age: u32,
+    ) -> Result<()> {
+        instructions::update_pool_oracle(ctx, price_oracle, max_deviation_bps, max_age)
+    }
+
+    /// Restrict the swaps of a pool to the traders of a trader registry, must be called by
+    /// the admin or the custom authority of the pool. Omitting the registry allows every
+    /// trader again, and the trades not checking the trader entry are refused
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn update_pool_trader_registry(ctx: Context<UpdatePoolTraderRegistry>) -> Result<()> {
+        instructions::update_pool_trader_registry(ctx)
+    }
+
+    /// Set the fee rebates of the pool, must be called by the admin or the custom authority
+    /// of the pool. The traders reaching the volume threshold in an epoch earn a share of their
+    /// trade fees back, paid out of the protocol fees
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `volume_threshold` - The token_0 volume a trader must reach in an epoch
+    /// * `rebate_bps` - The share of the trade fees in basis points paid back, 0 disables the rebates
+    ///
+    pub fn update_pool_fee_rebate(
+        ctx: Context<UpdatePoolFeeRebate>,
+        volume_threshold: u64,
+        rebate_bps: u16,
+    ) -> Result<()> {
+        instructions::update_pool_fee_rebate(ctx, volume_threshold, rebate_bps)
+    }
+
+    /// Move liquidity between two pools of a mint pair without trading, must be called by
+    /// the admin. The lp tokens are burned in the first pool and minted in the second pool,
+    /// the tokens left over by the ratios of the pools are sent to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - The amount of lp tokens of the first pool to burn
+    /// * `minimum_lp_token_amount_out` - The minimum amount of lp tokens of the second pool to mint
+    ///
+    pub fn rebalance_between_pools<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, RebalanceBetweenPools<'info>>,
+        lp_token_amount: u64,
+        minimum_lp_token_amount_out: u64,
+    ) -> Result<()> {
+        instructions::rebalance_between_pools(ctx, lp_token_amount, minimum_lp_token_amount_out)
+    }
+
+    /// Run the order-flow auction of the pool, must be called by the admin or the custom
+    /// authority of the pool. The swaps reaching the size threshold must be requested with
+    /// `request_swap`, the filler can fill them at a better price during the auction window,
+    /// after it they execute against the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `filler` - The filler given the first look at the requested swaps
+    /// * `size_threshold` - The smallest input amount of the requested swaps, 0 disables the auction
+    /// * `window_slots` - The number of slots after a request only the filler can execute it
+    ///
+    pub fn update_pool_auction(
+        ctx: Context<UpdatePoolAuction>,
+        filler: Pubkey,
+        size_threshold: u64,
+        window_slots: u64,
+    ) -> Result<()> {
+        instructions::update_pool_auction(ctx, filler, size_threshold, window_slots)
+    }
+
+    /// Update the share of the trade fee earned by the pool creator, must be called by the
+    /// admin or the custom authority of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `creator_fee_rate` - The creator share of the trade fee in hundredths of a bip, 0 disables it
+    ///
+    pub fn update_pool_creator_fee(
+        ctx: Context<UpdatePoolCreatorFee>,
+        creator_fee_rate: u64,
+    ) -> Result<()> {
+        instructions::update_pool_creator_fee(ctx, creator_fee_rate)
+    }
+
+    /// Switch the pool to providing liquidity through personal positions, which cannot be
+    /// transferred, must be called by the admin or the custom authority of the pool. The lp
+    /// tokens minted before keep being withdrawable, but no more are minted
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `position_mode` - Whether deposits are recorded in personal positions
+    ///
+    pub fn update_pool_position_mode(
+        ctx: Context<UpdatePoolPositionMode>,
+        position_mode: bool,
+    ) -> Result<()> {
+        instructions::update_pool_position_mode(ctx, position_mode)
+    }
+
+    /// Renounce the custom authority of a pool, called by the current custom authority, or
+    /// transfer it as break-glass, called by the admin. The custom authority transfers it
+    /// through `propose_new_admin` and `accept_admin`, the proposals of a former custom
+    /// authority are rejected
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `new_authority` - The new custom authority, the default pubkey renounces it permanently and opens the pool to anyone, the only value the custom authority sets
+    ///
+    pub fn set_pool_custom_authority(
+        ctx: Context<SetPoolCustomAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::set_pool_custom_authority(ctx, new_authority)
+    }
+
+    /// Ramp a parameter of a pool linearly from its current value to a target value, so the
+    /// swaps never see it jump. The amplification coefficient of a stable swap pool can be
+    /// ramped by the admin or the custom authority, by at most MAX_AMP_RAMP_FACTOR per ramp,
+    /// the trade fee rate of a custom authority pool by its custom authority
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `parameter` - The parameter to ramp
+    /// * `target_value` - The value the parameter reaches at the end of the ramp
+    /// * `ramp_duration` - The duration in seconds of the ramp, at least MIN_RAMP_DURATION
+    ///
+    pub fn ramp_pool_parameter(
+        ctx: Context<RampPoolParameter>,
+        parameter: states::RampParameter,
+        target_value: u64,
+        ramp_duration: u32,
+    ) -> Result<()> {
+        instructions::ramp_pool_parameter(ctx, parameter, target_value, ramp_duration)
+    }
+
+    /// Collect the protocol fee accrued to the pool, permissionless when the amm config
+    /// sets a protocol fee destination, which must own the recipient token accounts. When
+    /// the amm config sets a revenue program the collected fees are deposited to it in the
+    /// same instruction, see `REVENUE_DEPOSIT_INSTRUCTION`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_protocol_fee<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CollectProtocolFee<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_protocol_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Collect all the protocol fees accrued to many pools of an amm config with a single
+    /// signature, permissionless when the amm config sets a protocol fee destination.
+    /// The mints of the pools must not have a transfer hook
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, the remaining accounts are the pool state, the token_0
+    ///   and token_1 vaults, their mints and the token_0 and token_1 recipient token accounts of
+    ///   each pool
+    ///
+    pub fn collect_protocol_fees_batch<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CollectProtocolFeesBatch<'info>>,
+    ) -> Result<()> {
+        instructions::collect_protocol_fees_batch(ctx)
+    }
+
+    /// Collect the fund fee accrued to the pool, permissionless when the amm config
+    /// sets a fund fee destination, which must own the recipient token accounts. The
+    /// share of the insurance fund of the config is sent to its token accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_fund_fee<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CollectFundFee<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Collect the creator fee accrued to the pool, must be called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_creator_fee<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CollectCreatorFee<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_creator_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Re-deposit the `protocol_fee_compound_rate` fraction of the accumulated protocol fees
+    /// into the pool liquidity, increasing the backing of the lp tokens, can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn compound_fees(ctx: Context<CompoundFees>) -> Result<()> {
+        instructions::compound_fees(ctx)
+    }
+
+    /// Sync the pool with its vault balances, tokens sent directly to the vaults are
+    /// credited to the liquidity providers and the resulting price is recorded in the oracle,
+    /// can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn sync_reserves(ctx: Context<SyncReserves>) -> Result<()> {
+        instructions::sync_reserves(ctx)
+    }
+
+    /// Withdraw the Token-2022 transfer fees withheld by a vault into the vault, they are
+    /// credited to the protocol fees when the amm config sets `withheld_fees_to_protocol`,
+    /// otherwise to the liquidity providers. Must be signed by the withdraw withheld authority
+    /// of the mint, or can be called by anyone when it is the pool vault authority
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn harvest_withheld_fees(ctx: Context<HarvestWithheldFees>) -> Result<()> {
+        instructions::harvest_withheld_fees(ctx)
+    }
+
+    /// Sweep the accumulated protocol and fund fees to the token accounts of the amm config owners,
+    /// paying the `fee_crank_bounty_rate` share to the caller, can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn crank_protocol_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CrankProtocolFees<'info>>,
+    ) -> Result<()> {
+        instructions::crank_protocol_fees(ctx)
+    }
+
+    /// Swap the fund fees of a pool holding the buyback token of its amm config for the buyback
+    /// token and burn it with the buyback token fund fees, at most `buyback_epoch_cap` fund fees
+    /// are spent per epoch, can be called by anyone. The swap is rejected when its price falls
+    /// more than `buyback_max_deviation_bps` below the TWAP of the pool over
+    /// `buyback_twap_window`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn execute_buyback<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ExecuteBuyback<'info>>,
+    ) -> Result<()> {
+        instructions::execute_buyback(ctx)
+    }
+
+    /// Creates a pool for the given token pair and the initial price
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `init_amount_0` - the initial amount_0 to deposit
+    /// * `init_amount_1` - the initial amount_1 to deposit
+    /// * `open_time` - the timestamp allowed for swap
+    /// * `authority_type` - 0 for default PDA authority, 1 for custom authority
+    /// * `custom_authority` - custom authority pubkey (required when authority_type = 1)
+    /// * `curve_type` - 0 for constant product, 1 for StableSwap, 2 for weighted
+    /// * `amp` - the amplification coefficient of the StableSwap curve, or the token_0 weight
+    /// out of WEIGHT_DENOMINATOR of the weighted curve, ignored for constant product
+    /// * `launch_mode` - 0 for no launch phase, 1 to only allow buying token_0, 2 to only allow buying token_1, 3 to only allow whitelisted traders
+    /// * `launch_duration` - seconds after the open time until the launch phase can be finalized
+    /// * `lp_mint_metadata` - the token metadata to create the lp mint with under token program 2022, a plain spl token lp mint when None
+    /// * `accept_freeze_risk` - required when a mint has a freeze authority, which can freeze a
+    /// vault and stop the swaps and withdrawals of the pool, see `handle_frozen_vault`
+    ///
+    pub fn initialize<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Initialize<'info>>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        authority_type: u8,
+        custom_authority: Option<Pubkey>,
+        curve_type: u8,
+        amp: u64,
+        launch_mode: u8,
+        launch_duration: u64,
+        lp_mint_metadata: Option<states::LpMintMetadata>,
+        accept_freeze_risk: bool,
+    ) -> Result<()> {
+        instructions::initialize(
+            ctx,
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            authority_type,
+            custom_authority,
+            curve_type,
+            amp,
+            launch_mode,
+            launch_duration,
+            lp_mint_metadata,
+            accept_freeze_risk,
+            0,
+        )
+    }
+
+    /// Creates a pool like `initialize` at the address salted with `pool_salt`, so a mint
+    /// pair can have more independent pools in an amm config, such as separate launch
+    /// pools. Only allowed when the amm config enables salted pools
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `init_amount_0` - the initial amount_0 to deposit
+    /// * `init_amount_1` - the initial amount_1 to deposit
+    /// * `open_time` - the timestamp allowed for swap
+    /// * `authority_type` - 0 for default PDA authority, 1 for custom authority
+    /// * `custom_authority` - custom authority pubkey (required when authority_type = 1)
+    /// * `curve_type` - 0 for constant product, 1 for StableSwap, 2 for weighted
+    /// * `amp` - the amplification coefficient of the StableSwap curve, or the token_0 weight
+    /// out of WEIGHT_DENOMINATOR of the weighted curve, ignored for constant product
+    /// * `launch_mode` - 0 for no launch phase, 1 to only allow buying token_0, 2 to only allow buying token_1, 3 to only allow whitelisted traders
+    /// * `launch_duration` - seconds after the open time until the launch phase can be finalized
+    /// * `lp_mint_metadata` - the token metadata to create the lp mint with under token program 2022, a plain spl token lp mint when None
+    /// * `accept_freeze_risk` - required when a mint has a freeze authority, which can freeze a
+    /// vault and stop the swaps and withdrawals of the pool, see `handle_frozen_vault`
+    /// * `pool_salt` - the salt appended to the pool address seeds, 0 creates the canonical pool like `initialize`
+    ///
+    pub fn initialize_with_salt<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Initialize<'info>>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        authority_type: u8,
+        custom_authority: Option<Pubkey>,
+        curve_type: u8,
+        amp: u64,
+        launch_mode: u8,
+        launch_duration: u64,
+        lp_mint_metadata: Option<states::LpMintMetadata>,
+        accept_freeze_risk: bool,
+        pool_salt: u8,
+    ) -> Result<()> {
+        instructions::initialize(
+            ctx,
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            authority_type,
+            custom_authority,
+            curve_type,
+            amp,
+            launch_mode,
+            launch_duration,
+            lp_mint_metadata,
+            accept_freeze_risk,
+            pool_salt,
+        )
+    }
+
+    /// Creates a pool like `initialize` and swaps the creator tokens in it in the same
+    /// instruction, so the first buy of a launch can not be sandwiched between the pool
+    /// creation and the first trade. The swap is allowed before the open time
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `init_amount_0` - the initial amount_0 to deposit
+    /// * `init_amount_1` - the initial amount_1 to deposit
+    /// * `open_time` - the timestamp allowed for swap
+    /// * `authority_type` - 0 for default PDA authority, 1 for custom authority
+    /// * `custom_authority` - custom authority pubkey (required when authority_type = 1)
+    /// * `curve_type` - 0 for constant product, 1 for StableSwap, 2 for weighted
+    /// * `amp` - the amplification coefficient of the StableSwap curve, or the token_0 weight
+    /// out of WEIGHT_DENOMINATOR of the weighted curve, ignored for constant product
+    /// * `launch_mode` - 0 for no launch phase, 1 to only allow buying token_0, 2 to only allow buying token_1, 3 to only allow whitelisted traders
+    /// * `launch_duration` - seconds after the open time until the launch phase can be finalized
+    /// * `lp_mint_metadata` - the token metadata to create the lp mint with under token program 2022, a plain spl token lp mint when None
+    /// * `accept_freeze_risk` - required when a mint has a freeze authority, which can freeze a
+    /// vault and stop the swaps and withdrawals of the pool, see `handle_frozen_vault`
+    /// * `zero_for_one` - true to swap token_0 for token_1, false to swap token_1 for token_0
+    /// * `amount_in` - the creator tokens swapped, including the transfer fee
+    /// * `minimum_amount_out` - the minimum output tokens received, prevents excessive slippage
+    ///
+    pub fn initialize_and_swap<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Initialize<'info>>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        authority_type: u8,
+        custom_authority: Option<Pubkey>,
+        curve_type: u8,
+        amp: u64,
+        launch_mode: u8,
+        launch_duration: u64,
+        lp_mint_metadata: Option<states::LpMintMetadata>,
+        accept_freeze_risk: bool,
+        zero_for_one: bool,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::initialize_and_swap(
+            ctx,
+            instruction::InitializeAndSwap {
+                init_amount_0,
+                init_amount_1,
+                open_time,
+                authority_type,
+                custom_authority,
+                curve_type,
+                amp,
+                launch_mode,
+                launch_duration,
+                lp_mint_metadata,
+                accept_freeze_risk,
+                zero_for_one,
+                amount_in,
+                minimum_amount_out,
+            },
+        )
+    }
+
+    /// Creates a pool around pre-funded vaults owned by the pool authority, the vault balances
+    /// set the initial price and liquidity. Must be called by the admin, to migrate the
+    /// liquidity of a legacy AMM in one transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `open_time` - the timestamp allowed for swap
+    /// * `authority_type` - 0 for default PDA authority, 1 for custom authority
+    /// * `custom_authority` - custom authority pubkey (required when authority_type = 1)
+    /// * `curve_type` - 0 for constant product, 1 for StableSwap, 2 for weighted
+    /// * `amp` - the amplification coefficient of the StableSwap curve, or the token_0 weight
+    /// out of WEIGHT_DENOMINATOR of the weighted curve, ignored for constant product
+    ///
+    pub fn initialize_with_existing_vaults(
+        ctx: Context<InitializeWithExistingVaults>,
+        open_time: u64,
+        authority_type: u8,
+        custom_authority: Option<Pubkey>,
+        curve_type: u8,
+        amp: u64,
+    ) -> Result<()> {
+        instructions::initialize_with_existing_vaults(
+            ctx,
+            open_time,
+            authority_type,
+            custom_authority,
+            curve_type,
+            amp,
+        )
+    }
+
+    /// Allow a trader to swap in a pool during its whitelisted launch phase, must be
+    /// called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trader` - The trader to whitelist
+    ///
+    pub fn add_launch_whitelist(ctx: Context<AddLaunchWhitelist>, trader: Pubkey) -> Result<()> {
+        instructions::add_launch_whitelist(ctx, trader)
+    }
+
+    /// Remove a trader from the launch whitelist of a pool, must be called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn remove_launch_whitelist(ctx: Context<RemoveLaunchWhitelist>) -> Result<()> {
+        instructions::remove_launch_whitelist(ctx)
+    }
+
+    /// Create the trader registry of its signer, the only traders allowed to swap in the
+    /// pools pointing to it are the ones it adds
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_trader_registry(ctx: Context<CreateTraderRegistry>) -> Result<()> {
+        instructions::create_trader_registry(ctx)
+    }
+
+    /// Allow a trader to swap in the pools of a trader registry, must be called by the
+    /// registry authority
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trader` - The trader to register
+    ///
+    pub fn add_registered_trader(ctx: Context<AddRegisteredTrader>, trader: Pubkey) -> Result<()> {
+        instructions::add_registered_trader(ctx, trader)
+    }
+
+    /// Remove a trader from a trader registry, must be called by the registry authority
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn remove_registered_trader(ctx: Context<RemoveRegisteredTrader>) -> Result<()> {
+        instructions::remove_registered_trader(ctx)
+    }
+
+    /// End the launch phase of a pool once its duration has passed and allow every
+    /// trade, can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn finalize_launch(ctx: Context<FinalizeLaunch>) -> Result<()> {
+        instructions::finalize_launch(ctx)
+    }
+
+    /// Turn a weighted pool not opened yet into a liquidity bootstrapping pool, its weights
+    /// shift linearly from the start weight at the open time to 50/50, must be called by the
+    /// pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `start_weight` - token_0 weight out of 10000 when the pool opens, such as 9500 for 95/5
+    /// * `lbp_duration` - seconds after the open time until the weights reach 50/50
+    ///
+    pub fn configure_lbp(
+        ctx: Context<ConfigureLbp>,
+        start_weight: u64,
+        lbp_duration: u32,
+    ) -> Result<()> {
+        instructions::configure_lbp(ctx, start_weight, lbp_duration)
+    }
+
+    /// Convert a liquidity bootstrapping pool whose weights reached 50/50 to a constant
+    /// product pool, can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn finalize_lbp(ctx: Context<FinalizeLbp>) -> Result<()> {
+        instructions::finalize_lbp(ctx)
+    }
+
+    /// List a pool in the registry of its mint pair, so routers can discover the pools
+    /// of every fee tier, can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+        instructions::register_pool(ctx)
+    }
+
+    /// Create the account aggregating the swap volume, fees and pool count of the pools of an
+    /// amm config, updated by the swaps, pool creations and closures passing it.
+    /// Can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_protocol_stats(ctx: Context<CreateProtocolStats>) -> Result<()> {
+        instructions::create_protocol_stats(ctx)
+    }
+
+    /// Create the compact snapshot of the price, reserves and fee growths of a pool,
+    /// refreshed by the swaps passing it, for pollers subscribing to a small account.
+    /// Can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_pool_snapshot(ctx: Context<CreatePoolSnapshot>) -> Result<()> {
+        instructions::create_pool_snapshot(ctx)
+    }
+
+    /// Create or update the display data of a pool, must be called by the pool creator or the
+    /// custom authority of the pool. Can be sent in the transaction creating the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `name` - Display name of the pool, at most `MAX_POOL_NAME_LEN` bytes
+    /// * `uri` - URI of the off-chain metadata with the logo and social links, at most `MAX_POOL_URI_LEN` bytes
+    /// * `tags` - At most `MAX_POOL_TAGS` labels of the pool, each at most `MAX_POOL_TAG_LEN` bytes
+    ///
+    pub fn set_pool_metadata(
+        ctx: Context<SetPoolMetadata>,
+        name: String,
+        uri: String,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        instructions::set_pool_metadata(ctx, name, uri, tags)
+    }
+
+    /// Close a pool without lp supply and with empty vaults, the rent of the pool, observation
+    /// and vault accounts is refunded to the pool creator. Can be called by the pool creator or the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    /// Deposit lp token to the pool, the lp token account of the owner is created as its
+    /// associated token account when it does not exist yet
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Pool token amount to transfer. token_a and token_b amount are set by the current exchange rate and size of the pool
+    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
+    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
+    /// * `wrap_sol` - Wrap the native SOL of the owner into its WSOL token account to deposit
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    /// * `price_slippage` - Maximum deviation of the pool price from an expected price, an
+    /// alternative to the token maxima which can then be set to u64::MAX, None for no check
+    ///
+    pub fn deposit<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Deposit<'info>>,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+        wrap_sol: bool,
+        deadline: Option<u64>,
+        price_slippage: Option<states::PriceSlippage>,
+    ) -> Result<()> {
+        instructions::deposit(
+            ctx,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            wrap_sol,
+            deadline,
+            price_slippage,
+        )
+    }
+
+    /// Deposit at most the given amounts of both tokens, for the most lp token they mint at
+    /// the current exchange rate of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `token_0_amount` - Maximum token 0 amount to deposit, including its transfer fee
+    /// * `token_1_amount` - Maximum token 1 amount to deposit, including its transfer fee
+    /// * `minimum_lp_token_amount` - Minimum amount of lp token to receive, prevents excessive slippage
+    /// * `wrap_sol` - Wrap the native SOL of the owner into its WSOL token account to deposit
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    ///
+    pub fn deposit_exact_tokens<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Deposit<'info>>,
+        token_0_amount: u64,
+        token_1_amount: u64,
+        minimum_lp_token_amount: u64,
+        wrap_sol: bool,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        instructions::deposit_exact_tokens(
+            ctx,
+            token_0_amount,
+            token_1_amount,
+            minimum_lp_token_amount,
+            wrap_sol,
+            deadline,
+        )
+    }
+
+    /// Deposit only one token of the pool, part of it is swapped for the other token
+    /// and the swap fee is charged on the swapped part. The lp token account of the owner
+    /// is created as its associated token account when it does not exist yet
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` - Amount of the input token to deposit
+    /// * `minimum_lp_token_amount` - Minimum amount of lp token to receive, prevents excessive slippage
+    ///
+    pub fn deposit_single_token<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DepositSingleToken<'info>>,
+        amount_in: u64,
+        minimum_lp_token_amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_single_token(ctx, amount_in, minimum_lp_token_amount)
+    }
+
+    /// Withdraw lp for token0 and token1
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn. User receives an output of token a and b based on the percentage of the pool tokens that are returned.
+    /// * `minimum_token_0_amount` -  Minimum amount of token 0 to receive, prevents excessive slippage
+    /// * `minimum_token_1_amount` -  Minimum amount of token 1 to receive, prevents excessive slippage
+    /// * `wrap_sol` - Unwrap the WSOL received by closing the WSOL token account to the owner
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    /// * `price_slippage` - Maximum deviation of the pool price from an expected price, an
+    /// alternative to the token minima which can then be set to 0, None for no check
+    ///
+    pub fn withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+        wrap_sol: bool,
+        deadline: Option<u64>,
+        price_slippage: Option<states::PriceSlippage>,
+    ) -> Result<()> {
+        instructions::withdraw(
+            ctx,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            wrap_sol,
+            deadline,
+            price_slippage,
+        )
+    }
+
+    /// Withdraw lp for the token of the vault not frozen while the other vault of the pool
+    /// is frozen by its mint, the share of the frozen vault is given up to the remaining
+    /// liquidity providers
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn
+    /// * `minimum_out` - Minimum amount of the output token to receive, prevents excessive slippage
+    ///
+    pub fn handle_frozen_vault<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, HandleFrozenVault<'info>>,
+        lp_token_amount: u64,
+        minimum_out: u64,
+    ) -> Result<()> {
+        instructions::handle_frozen_vault(ctx, lp_token_amount, minimum_out)
+    }
+
+    /// Withdraw at least the given amounts of both tokens, burning the fewest lp token they
+    /// take at the current exchange rate of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `token_0_amount` - Minimum token 0 amount to receive, after its transfer fee
+    /// * `token_1_amount` - Minimum token 1 amount to receive, after its transfer fee
+    /// * `maximum_lp_token_amount` - Maximum amount of lp token to burn, prevents excessive slippage
+    /// * `wrap_sol` - Unwrap the WSOL received by closing the WSOL token account to the owner
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    ///
+    pub fn withdraw_exact_tokens<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>,
+        token_0_amount: u64,
+        token_1_amount: u64,
+        maximum_lp_token_amount: u64,
+        wrap_sol: bool,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        instructions::withdraw_exact_tokens(
+            ctx,
+            token_0_amount,
+            token_1_amount,
+            maximum_lp_token_amount,
+            wrap_sol,
+            deadline,
+        )
+    }
+
+    /// Deposit both tokens of the owner for lp tokens minted to the lp token account of the
+    /// beneficiary, so programs integrating the pool hold the liquidity under their own PDAs
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Pool token amount minted to the beneficiary
+    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
+    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    ///
+    pub fn deposit_for<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DepositFor<'info>>,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        instructions::deposit_for(
+            ctx,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            deadline,
+        )
+    }
+
+    /// Burn lp tokens of the owner for both tokens, sent to the token accounts of the
+    /// beneficiary
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn
+    /// * `minimum_token_0_amount` -  Minimum amount of token 0 the beneficiary receives, prevents excessive slippage
+    /// * `minimum_token_1_amount` -  Minimum amount of token 1 the beneficiary receives, prevents excessive slippage
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    ///
+    pub fn withdraw_to<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, WithdrawTo<'info>>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+        deadline: Option<u64>,
+    ) -> Result<()> {
+        instructions::withdraw_to(
+            ctx,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            deadline,
+        )
+    }
+
+    /// Withdraw lp of a pool and deposit the underlying tokens into another pool of the same
+    /// mints in one instruction, the tokens the destination pool price does not take are
+    /// returned to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of source pool tokens to burn
+    /// * `minimum_token_0_amount` -  Minimum amount of token 0 withdrawn, prevents excessive slippage
+    /// * `minimum_token_1_amount` -  Minimum amount of token 1 withdrawn, prevents excessive slippage
+    /// * `minimum_lp_token_amount` -  Minimum amount of destination pool tokens to receive, prevents excessive slippage
+    ///
+    pub fn migrate_liquidity<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MigrateLiquidity<'info>>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+        minimum_lp_token_amount: u64,
+    ) -> Result<()> {
+        instructions::migrate_liquidity(
+            ctx,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            minimum_lp_token_amount,
+        )
+    }
+
+    /// Withdraw lp of a legacy Raydium AMM v4 pool through its program and deposit the
+    /// withdrawn tokens into the pool in one instruction, the tokens the pool price does
+    /// not take are left in the owner token accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, the first remaining accounts are the accounts of the legacy withdraw instruction
+    /// * `legacy_lp_amount` - Amount of legacy pool tokens to burn
+    /// * `legacy_account_count` - Number of remaining accounts passed to the legacy withdraw instruction
+    /// * `minimum_lp_token_amount` -  Minimum amount of pool tokens to receive, prevents excessive slippage
+    ///
+    pub fn migrate_from_legacy<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MigrateFromLegacy<'info>>,
+        legacy_lp_amount: u64,
+        legacy_account_count: u8,
+        minimum_lp_token_amount: u64,
+    ) -> Result<()> {
+        instructions::migrate_from_legacy(
+            ctx,
+            legacy_lp_amount,
+            legacy_account_count,
+            minimum_lp_token_amount,
+        )
+    }
+
+    /// Withdraw lp for only one token of the pool, the other token share is swapped
+    /// for the chosen token and the swap fee is charged on it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn
+    /// * `minimum_out` - Minimum amount of the chosen token to receive, prevents excessive slippage
+    ///
+    pub fn withdraw_single_token<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, WithdrawSingleToken<'info>>,
+        lp_token_amount: u64,
+        minimum_out: u64,
+    ) -> Result<()> {
+        instructions::withdraw_single_token(ctx, lp_token_amount, minimum_out)
+    }
+
+    /// Swap the tokens in the pool base input amount, the amounts, fees and price move of
+    /// the swap are returned as `SwapReceipt` return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
+    /// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
+    /// * `wrap_sol` - Wrap the native SOL of the payer for a WSOL input, or unwrap a WSOL output by closing the output token account
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    /// * `sqrt_price_limit_x64` - The sqrt of the token_0 price in token_1, Q64.64, the swap stops at instead of crossing: only the input up to the limit is transferred, the receipt `amount_in` reports it. None for no limit
+    ///
+    pub fn swap_base_input<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        wrap_sol: bool,
+        deadline: Option<u64>,
+        sqrt_price_limit_x64: Option<u128>,
+    ) -> Result<states::SwapReceipt> {
+        instructions::swap_base_input(
+            ctx,
+            amount_in,
+            minimum_amount_out,
+            wrap_sol,
+            deadline,
+            sqrt_price_limit_x64,
+        )
+    }
+
+    /// Swap the tokens through two pools, the intermediate token moves between the pool vaults
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate of both pools
+    /// * `minimum_amount_out` -  Minimum amount of the output token, only checked on the final output
+    ///
+    pub fn swap_route<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SwapRoute<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::swap_route(ctx, amount_in, minimum_amount_out)
+    }
+
+    /// Swap the tokens through two pools for an exact output amount, the inputs of
+    /// the hops are calculated backwards from the output
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_amount_in` -  Maximum amount of the input token, only checked on the route input
+    /// * `amount_out` -  amount of the output token to receive
+    ///
+    pub fn swap_route_base_output<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SwapRoute<'info>>,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Result<()> {
+        instructions::swap_route_base_output(ctx, max_amount_in, amount_out)
+    }
+
+    /// Swap the tokens in the pool base output amount, the amounts, fees and price move of
+    /// the swap are returned as `SwapReceipt` return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_amount_in` -  input amount prevents excessive slippage
+    /// * `amount_out` -  amount of output token
+    /// * `wrap_sol` - Wrap the native SOL of the payer for a WSOL input, or unwrap a WSOL output by closing the output token account
+    /// * `deadline` - Unix timestamp after which the transaction reverts, None for no deadline
+    ///
+    pub fn swap_base_output<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
+        max_amount_in: u64,
+        amount_out: u64,
+        wrap_sol: bool,
+        deadline: Option<u64>,
+    ) -> Result<states::SwapReceipt> {
+        instructions::swap_base_output(ctx, max_amount_in, amount_out, wrap_sol, deadline)
+    }
+
+    /// Execute a swap base input signed off-chain by the owner of the input tokens, the
+    /// relayer pays the transaction. The previous instruction must verify the signature
+    /// of the owner over the serialized order with the ed25519 program, and the owner
+    /// must have approved its order authority PDA as the delegate of the input token account
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `order` - The signed order, with the pool, amounts, expiry and nonce of the swap
+    ///
+    pub fn swap_signed_order<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SwapSignedOrder<'info>>,
+        order: states::SwapOrder,
+    ) -> Result<()> {
+        instructions::swap_signed_order(ctx, order)
+    }
+
+    /// Swap the protocol owned reserves of the custom authority into a constant product
+    /// pool to move its price toward a target price, for treasury managed pools tracking
+    /// an external benchmark. The trade direction follows from the target price
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `target_sqrt_price_x64` - The square root of the target token_0 price in token_1, Q64.64
+    /// * `max_amount_in` - The most input tokens transferred, the swap stops short of the target beyond
+    ///
+    pub fn rebalance_to_target_price<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, RebalanceToTargetPrice<'info>>,
+        target_sqrt_price_x64: u128,
+        max_amount_in: u64,
+    ) -> Result<()> {
+        instructions::rebalance_to_target_price(ctx, target_sqrt_price_x64, max_amount_in)
+    }
+
+    /// Open a limit order selling the input tokens at a price no worse than the limit, the
+    /// input tokens are escrowed in an order vault until filled or cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `order_id` - The id of the order, unique per owner and pool
+    /// * `amount_in` - The amount of input tokens to sell
+    /// * `limit_price_x64` - The minimum output per input token, as a Q64.64 fixed point
+    /// * `keeper_tip_bps` - The share of each fill output paid to the keeper, in bps
+    ///
+    pub fn open_order<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, OpenOrder<'info>>,
+        order_id: u64,
+        amount_in: u64,
+        limit_price_x64: u128,
+        keeper_tip_bps: u16,
+    ) -> Result<()> {
+        instructions::open_order(ctx, order_id, amount_in, limit_price_x64, keeper_tip_bps)
+    }
+
+    /// Fill part or all of a limit order against the pool once the pool price reaches the
+    /// limit, permissionless, the keeper receives the tip from the output
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` - The amount of remaining order input to swap
+    ///
+    pub fn fill_order<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, FillOrder<'info>>,
+        amount_in: u64,
+    ) -> Result<()> {
+        instructions::fill_order(ctx, amount_in)
+    }
+
+    /// Cancel a limit order, the unfilled input tokens and the rent are returned to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_order<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CancelOrder<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_order(ctx)
+    }
+
+    /// Open a DCA order selling the input tokens against the pool in tranches executed by
+    /// keepers, the input tokens are escrowed in an order vault until sold or cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `order_id` - The id of the order, unique per owner and pool
+    /// * `amount_in` - The amount of input tokens to sell
+    /// * `amount_per_trade` - The amount of input tokens sold by each tranche
+    /// * `interval` - The minimum seconds between two tranches
+    /// * `min_price_x64` - The minimum output per input token of a tranche, as a Q64.64 fixed point
+    /// * `keeper_tip_bps` - The share of each tranche output paid to the keeper, in bps
+    ///
+    pub fn open_dca_order<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, OpenDcaOrder<'info>>,
+        order_id: u64,
+        amount_in: u64,
+        amount_per_trade: u64,
+        interval: u64,
+        min_price_x64: u128,
+        keeper_tip_bps: u16,
+    ) -> Result<()> {
+        instructions::open_dca_order(
+            ctx,
+            order_id,
+            amount_in,
+            amount_per_trade,
+            interval,
+            min_price_x64,
+            keeper_tip_bps,
+        )
+    }
+
+    /// Swap the next tranche of a DCA order against the pool once it is due, permissionless,
+    /// the keeper receives the tip from the output
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn execute_dca_tranche<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ExecuteDcaTranche<'info>>,
+    ) -> Result<()> {
+        instructions::execute_dca_tranche(ctx)
+    }
+
+    /// Cancel a DCA order, the input tokens not sold yet and the rent are returned to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_dca_order<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CancelDcaOrder<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_dca_order(ctx)
+    }
+
+    /// Request a swap in a pool running the order-flow auction, the input tokens are escrowed
+    /// in a request vault until the request is executed or cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `request_id` - The id of the request, unique per owner and pool
+    /// * `amount_in` - The amount of input tokens to swap
+    /// * `minimum_amount_out` - The minimum amount of output tokens received by the owner
+    ///
+    pub fn request_swap<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, RequestSwap<'info>>,
+        request_id: u64,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::request_swap(ctx, request_id, amount_in, minimum_amount_out)
+    }
+
+    /// Execute a swap request. During the auction window only the filler can execute it,
+    /// either filling it at a price no worse than the pool or passing it on to the pool,
+    /// after the window anyone can execute it against the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `fill_amount_out` - The output tokens the filler pays the owner, None executes against the pool
+    ///
+    pub fn execute_swap<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ExecuteSwap<'info>>,
+        fill_amount_out: Option<u64>,
+    ) -> Result<()> {
+        instructions::execute_swap(ctx, fill_amount_out)
+    }
+
+    /// Cancel a swap request, the input tokens and the rent are returned to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_swap_request<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CancelSwapRequest<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_swap_request(ctx)
+    }
+
+    /// Borrow tokens from the pool vaults, the loan must be repaid by `repay_flash_loan`
+    /// later in the same transaction, the fee accrues to the liquidity providers
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_0` - The amount of token_0 to borrow
+    /// * `amount_1` - The amount of token_1 to borrow
+    ///
+    pub fn flash_loan<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, FlashLoan<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<()> {
+        instructions::flash_loan(ctx, amount_0, amount_1)
+    }
+
+    /// Repay the outstanding flash loan of the pool, principal plus fee
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn repay_flash_loan<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, RepayFlashLoan<'info>>,
+    ) -> Result<()> {
+        instructions::repay_flash_loan(ctx)
+    }
+
+    /// Lend pool reserves to the authorized strategy of the pool, e.g. to deploy idle
+    /// liquidity into lending, within the share of the reserves the pool allows
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_0` - The amount of token_0 to borrow
+    /// * `amount_1` - The amount of token_1 to borrow
+    ///
+    pub fn strategy_borrow<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, StrategyBorrow<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<()> {
+        instructions::strategy_borrow(ctx, amount_0, amount_1)
+    }
+
+    /// Return reserves borrowed by the strategy of the pool, the amount received above
+    /// the borrowed reserves accrues to the liquidity providers
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_0` - The amount of token_0 to transfer to the vault
+    /// * `amount_1` - The amount of token_1 to transfer to the vault
+    ///
+    pub fn strategy_repay<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, StrategyRepay<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<()> {
+        instructions::strategy_repay(ctx, amount_0, amount_1)
+    }
+
+    /// Claim the fee rebates earned by the trader in the past epochs, the rebates are paid
+    /// out of the protocol fees and the part they do not cover stays claimable
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn claim_fee_rebate<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClaimFeeRebate<'info>>,
+    ) -> Result<()> {
+        instructions::claim_fee_rebate(ctx)
+    }
+
+    /// Get the time weighted average prices of the pool over a recent window,
+    /// the result is set as the return data of the instruction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `seconds_ago` - The length in seconds of the window, ending at the current time
+    ///
+    pub fn get_twap(ctx: Context<GetTwap>, seconds_ago: u64) -> Result<states::TwapPrice> {
+        instructions::get_twap(ctx, seconds_ago)
+    }
+
+    /// Get the time weighted geometric mean prices of the pool over a recent window, the
+    /// token_1 price is the exact reciprocal of the token_0 price. The result is set as
+    /// the return data of the instruction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `seconds_ago` - The length in seconds of the window, ending at the current time
+    ///
+    pub fn get_geometric_twap(
+        ctx: Context<GetTwap>,
+        seconds_ago: u64,
+    ) -> Result<states::TwapPrice> {
+        instructions::get_geometric_twap(ctx, seconds_ago)
+    }
+
+    /// Get the fee growth per lp token and the swap volumes of the pool, the result
+    /// is set as the return data of the instruction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn get_pool_metrics(ctx: Context<GetPoolMetrics>) -> Result<states::PoolMetrics> {
+        instructions::get_pool_metrics(ctx)
+    }
+
+    /// Get the underlying token_0 and token_1 amounts of an amount of lp tokens, the protocol
+    /// and fund fees owed by the pool excluded, the result is set as the return data of the
+    /// instruction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_amount` - The amount of lp tokens to value, at most the lp supply
+    ///
+    pub fn get_lp_position_value(
+        ctx: Context<GetLpPositionValue>,
+        lp_amount: u64,
+    ) -> Result<states::LpPositionValue> {
+        instructions::get_lp_position_value(ctx, lp_amount)
+    }
+
+    /// Quote a swap of an exact input amount with the fees, transfer fees and curve of
+    /// `swap_base_input`, the result is set as the return data of the instruction so
+    /// other programs can get it through CPI
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` - Amount of input token sent by the trader, including the transfer fee
+    ///
+    pub fn quote_swap_base_input(
+        ctx: Context<QuoteSwap>,
+        amount_in: u64,
+    ) -> Result<states::SwapQuote> {
+        instructions::quote_swap_base_input(ctx, amount_in)
+    }
+
+    /// Quote a swap of an exact output amount with the fees, transfer fees and curve of
+    /// `swap_base_output`, the result is set as the return data of the instruction so
+    /// other programs can get it through CPI
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_out` - Amount of output token received by the trader, the transfer fee excluded
+    ///
+    pub fn quote_swap_base_output(
+        ctx: Context<QuoteSwap>,
+        amount_out: u64,
+    ) -> Result<states::SwapQuote> {
+        instructions::quote_swap_base_output(ctx, amount_out)
+    }
+
+    /// Quote a swap of an exact input amount in up to `MAX_QUOTE_POOLS` pools at once, so
+    /// routers compare the fee tiers and venues of a pair in one simulation. The accounts
+    /// of each pool are passed as remaining accounts, the quotes are set as the return
+    /// data of the instruction in the order of the pools
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` - Amount of input token sent by the trader, including the transfer fee
+    ///
+    pub fn quote_many<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, QuoteMany>,
+        amount_in: u64,
+    ) -> Result<Vec<Option<states::SwapQuote>>> {
+        instructions::quote_many(ctx, amount_in)
+    }
+
+    /// Append observations to the oracle ring buffer of a pool to keep longer price history,
+    /// the payer funds the rent of the appended observations
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `observation_cardinality` - The new number of observations, at most 1000 and at most 256 more than the current one,
+    /// or 182 more when the log prices are recorded
+    ///
+    pub fn increase_observation_cardinality(
+        ctx: Context<IncreaseObservationCardinality>,
+        observation_cardinality: u16,
+    ) -> Result<()> {
+        instructions::increase_observation_cardinality(ctx, observation_cardinality)
+    }
+
+    /// Record the cumulative log prices in the oracle of a pool created before they were
+    /// recorded, for the geometric mean prices of `get_geometric_twap`. Can be called by
+    /// anyone, the payer funds the rent of the appended log prices
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn enable_log_price_observations(ctx: Context<EnableLogPriceObservations>) -> Result<()> {
+        instructions::enable_log_price_observations(ctx)
+    }
+
+    /// Record the current pool price in the oracle without a swap, so pools trading rarely
+    /// still produce usable TWAPs. Can be called by anyone, nothing is recorded before the
+    /// observation update duration of the amm config passed since the last observation
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn snapshot_observation(ctx: Context<SnapshotObservation>) -> Result<()> {
+        instructions::snapshot_observation(ctx)
+    }
+
+    /// Upgrade a pool state of an older layout in place, the account grows to the current
+    /// layout with the fields added since zeroed, the payer funds the rent of the growth
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+        instructions::migrate_pool_state(ctx)
+    }
+
+    /// Lock lp tokens of a pool in a program owned vault, released on a cliff and linear vesting schedule
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lock_index` - Index of the lock, an owner can have multiple locks for a pool
+    /// * `lp_token_amount` - Amount of lp tokens to lock
+    /// * `cliff_duration` - Seconds from now before anything can be claimed
+    /// * `vesting_duration` - Seconds from now until everything can be claimed, must not be less than `cliff_duration`
+    ///
+    pub fn lock_liquidity(
+        ctx: Context<LockLiquidity>,
+        lock_index: u64,
+        lp_token_amount: u64,
+        cliff_duration: u64,
+        vesting_duration: u64,
+    ) -> Result<()> {
+        instructions::lock_liquidity(
+            ctx,
+            lock_index,
+            lp_token_amount,
+            cliff_duration,
+            vesting_duration,
+        )
+    }
+
+    /// Claim the vested lp tokens of a lock, must be signed by the lock owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn claim_unlocked_liquidity(ctx: Context<ClaimUnlockedLiquidity>) -> Result<()> {
+        instructions::claim_unlocked_liquidity(ctx)
+    }
+
+    /// Deposit into the personal position of the owner in a pool in position mode, the
+    /// shares are recorded in the position instead of minted as lp tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `shares` - Shares of the lp supply to add to the position, the token amounts are set by the current exchange rate and size of the pool
+    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
+    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
+    ///
+    pub fn deposit_position<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DepositPosition<'info>>,
+        shares: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_position(ctx, shares, maximum_token_0_amount, maximum_token_1_amount)
+    }
+
+    /// Withdraw shares of a personal position, must be signed by the position owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `shares` - Shares of the position to withdraw
+    /// * `minimum_token_0_amount` -  Minimum amount of token 0 to receive, prevents excessive slippage
+    /// * `minimum_token_1_amount` -  Minimum amount of token 1 to receive, prevents excessive slippage
+    ///
+    pub fn withdraw_position<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, WithdrawPosition<'info>>,
+        shares: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_position(ctx, shares, minimum_token_0_amount, minimum_token_1_amount)
+    }
+
+    /// Claim the trade fees a personal position earned since its last claim, the fees
+    /// compound into the shares until claimed, so the shares they are worth are withdrawn
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn claim_position_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, WithdrawPosition<'info>>,
+    ) -> Result<()> {
+        instructions::claim_position_fees(ctx)
+    }
+
+    /// Add a reward mint emitted to the positions of a position mode pool in proportion to
+    /// their shares, and fund its emissions from the reward authority. The admin or the pool
+    /// creator adds the first reward and becomes the reward authority of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `emissions_per_second` - The reward tokens emitted per second
+    /// * `end_time` - Unix timestamp the emission stops at
+    ///
+    pub fn initialize_reward<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, InitializeReward<'info>>,
+        emissions_per_second: u64,
+        end_time: u64,
+    ) -> Result<()> {
+        instructions::initialize_reward(ctx, emissions_per_second, end_time)
+    }
+
+    /// Change the emissions of a reward from now on, the reward authority funds the
+    /// reward tokens the new emissions need beyond the ones not emitted yet
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - The index of the reward in the reward state
+    /// * `emissions_per_second` - The reward tokens emitted per second
+    /// * `end_time` - Unix timestamp the emission stops at
+    ///
+    pub fn set_reward_emissions<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SetRewardEmissions<'info>>,
+        reward_index: u8,
+        emissions_per_second: u64,
+        end_time: u64,
+    ) -> Result<()> {
+        instructions::set_reward_emissions(ctx, reward_index, emissions_per_second, end_time)
+    }
+
+    /// Claim the rewards a personal position earned, once a pool has rewards its positions
+    /// pass their reward accounts to `deposit_position` and `withdraw_position` too
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - The index of the claimed reward in the reward state
+    ///
+    pub fn claim_rewards<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClaimRewards<'info>>,
+        reward_index: u8,
+    ) -> Result<()> {
+        instructions::claim_rewards(ctx, reward_index)
+    }
+
+    /// Create a session delegating the liquidity management of the owner in a pool to a
+    /// session key until it expires. The session becomes the delegate of the owner lp and
+    /// token accounts, the session key can only deposit from and withdraw to them
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `session_key` - The hot key allowed to sign `session_deposit` and `session_withdraw`
+    /// * `expires_at` - Unix timestamp after which the session key is refused
+    /// * `deposit_lp_limit` - The most lp tokens the session key can mint by depositing
+    /// * `withdraw_lp_limit` - The most lp tokens the session key can burn by withdrawing
+    /// * `maximum_token_0_amount` - The most token 0 the session key can deposit
+    /// * `maximum_token_1_amount` - The most token 1 the session key can deposit
+    ///
+    pub fn create_session_authority(
+        ctx: Context<CreateSessionAuthority>,
+        session_key: Pubkey,
+        expires_at: i64,
+        deposit_lp_limit: u64,
+        withdraw_lp_limit: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::create_session_authority(
+            ctx,
+            session_key,
+            expires_at,
+            deposit_lp_limit,
+            withdraw_lp_limit,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        )
+    }
+
+    /// Revoke a session, its delegations of the owner token accounts are revoked and the
+    /// rent is returned to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn revoke_session_authority(ctx: Context<RevokeSessionAuthority>) -> Result<()> {
+        instructions::revoke_session_authority(ctx)
+    }
+
+    /// Deposit lp tokens for the owner of a session, must be signed by the session key
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Pool tokens to mint to the owner, counted against the deposit limit of the session
+    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
+    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
+    ///
+    pub fn session_deposit<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SessionDeposit<'info>>,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::session_deposit(
+            ctx,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        )
+    }
+
+    /// Withdraw lp tokens of the owner of a session, must be signed by the session key.
+    /// The tokens are withdrawn to the owner token accounts only
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn, counted against the withdraw limit of the session
+    /// * `minimum_token_0_amount` -  Minimum amount of token 0 to receive, prevents excessive slippage
+    /// * `minimum_token_1_amount` -  Minimum amount of token 1 to receive, prevents excessive slippage
+    ///
+    pub fn session_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SessionWithdraw<'info>>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::session_withdraw(
+            ctx,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        )
+    }
+
+    /// Create a stable pool of 3 to 4 tokens, such as a USDC/USDT/PYUSD tri-pool, trading on
+    /// the StableSwap invariant of all its balances. The remaining accounts hold the mint,
+    /// the vault and the creator token account of each token, sorted by mint
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amp` - The amplification coefficient of the invariant
+    /// * `init_amounts` - The initial amount of each token, sorted by mint
+    /// * `open_time` - The timestamp allowed for swap
+    ///
+    pub fn initialize_multi_pool<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, InitializeMultiPool<'info>>,
+        amp: u64,
+        init_amounts: Vec<u64>,
+        open_time: u64,
+    ) -> Result<()> {
+        instructions::initialize_multi_pool(ctx, amp, init_amounts, open_time)
+    }
+
+    /// Deposit every token of a multi token pool in proportion to its balance. The remaining
+    /// accounts hold the vault, the mint and the owner token account of each pool token
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to mint
+    /// * `maximum_token_amounts` - Maximum amount of each token to deposit, prevents excessive slippage
+    ///
+    pub fn multi_pool_deposit<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MultiPoolDeposit<'info>>,
+        lp_token_amount: u64,
+        maximum_token_amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::multi_pool_deposit(ctx, lp_token_amount, maximum_token_amounts)
+    }
+
+    /// Withdraw the share of every token of a multi token pool of burned lp tokens. The
+    /// remaining accounts hold the vault, the mint and the receiving token account of each
+    /// pool token
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn
+    /// * `minimum_token_amounts` - Minimum amount of each token to receive, prevents excessive slippage
+    ///
+    pub fn multi_pool_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MultiPoolWithdraw<'info>>,
+        lp_token_amount: u64,
+        minimum_token_amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::multi_pool_withdraw(ctx, lp_token_amount, minimum_token_amounts)
+    }
+
+    /// Swap two tokens of a multi token pool. The remaining accounts hold the vaults of
+    /// every pool token, whose balances enter the invariant
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` - Amount of input tokens to swap
+    /// * `minimum_amount_out` - Minimum amount of output tokens, prevents excessive slippage
+    ///
+    pub fn multi_pool_swap<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MultiPoolSwap<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::multi_pool_swap(ctx, amount_in, minimum_amount_out)
+    }
+
+    /// Collect the protocol and fund fees accrued to a multi token pool, to the recipient
+    /// token accounts passed with the vault and the mint of each pool token
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn collect_multi_pool_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CollectMultiPoolFees<'info>>,
+    ) -> Result<()> {
+        instructions::collect_multi_pool_fees(ctx)
+    }
+
+    /// Create the receipt tree of a pool, the concurrent merkle tree holding its compressed
+    /// deposit receipts. Can be created by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_receipt_tree(ctx: Context<CreateReceiptTree>) -> Result<()> {
+        instructions::create_receipt_tree(ctx)
+    }
+
+    /// Close the deposit receipt of the owner into a leaf of the receipt tree of the pool,
+    /// refunding its rent. The receipt data is emitted to build its proof
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn compress_deposit_receipt(ctx: Context<CompressDepositReceipt>) -> Result<()> {
+        instructions::compress_deposit_receipt(ctx)
+    }
+
+    /// Restore a compressed deposit receipt of the owner proven against the receipt tree,
+    /// adding it to the deposit receipt of the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `root` - The root of the receipt tree the proof was built against
+    /// * `leaf_index` - The index of the compressed receipt in the tree
+    /// * `lp_amount` - The lp tokens of the compressed receipt
+    /// * `last_deposit_time` - The last deposit time of the compressed receipt
+    /// * `proof` - The sibling nodes from the leaf up to the root
+    ///
+    pub fn decompress_deposit_receipt(
+        ctx: Context<DecompressDepositReceipt>,
+        root: [u8; 32],
+        leaf_index: u64,
+        lp_amount: u64,
+        last_deposit_time: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::decompress_deposit_receipt(
+            ctx,
+            root,
+            leaf_index,
+            lp_amount,
+            last_deposit_time,
+            proof,
+        )
     }
 }
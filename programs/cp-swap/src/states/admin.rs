@@ -0,0 +1,143 @@
+use crate::error::ErrorCode;
+use crate::states::{AmmConfig, PoolState};
+use anchor_lang::prelude::*;
+
+pub const PENDING_ADMIN_SEED: &str = "pending_admin";
+
+/// Roles that are transferred with the propose/accept flow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminRole {
+    /// `protocol_owner` of an amm config
+    ProtocolOwner,
+    /// `fund_owner` of an amm config
+    FundOwner,
+    /// `custom_authority` of a custom authority pool
+    PoolCustomAuthority,
+}
+
+impl AdminRole {
+    pub fn from_u8(role: u8) -> Option<AdminRole> {
+        match role {
+            0 => Some(AdminRole::ProtocolOwner),
+            1 => Some(AdminRole::FundOwner),
+            2 => Some(AdminRole::PoolCustomAuthority),
+            _ => None,
+        }
+    }
+}
+
+/// Holds a proposed admin until it accepts the role
+#[account]
+#[derive(Default, Debug)]
+pub struct PendingAdmin {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The role to transfer, see `AdminRole`
+    pub role: u8,
+    /// The amm config or pool state the role belongs to
+    pub target: Pubkey,
+    /// Address of the proposed admin, which must sign to accept the role
+    pub pending_admin: Pubkey,
+    /// Address paid the rent, refunded when the role is accepted
+    pub proposer: Pubkey,
+}
+
+impl PendingAdmin {
+    pub const LEN: usize = 8 + 1 + 1 + 32 * 3;
+}
+
+/// A pool admin operation of `batch_admin`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolAdminOp {
+    /// Disable every operation of the pool, as `pause_pools`
+    Pause,
+    /// Set the status bits of the pool, as `update_pool_status`
+    SetStatus { status: u8 },
+    /// Set the trade fee rate override of the pool within the bounds of its amm config, or
+    /// clear it when None
+    SetTradeFeeRate { trade_fee_rate: Option<u64> },
+    /// Set the custom authority of a custom authority pool, the default pubkey renounces it
+    SetCustomAuthority { new_authority: Pubkey },
+}
+
+impl PoolAdminOp {
+    /// Apply the operation to `pool_state`, the trade fee rate is checked against the amm
+    /// config of the pool
+    pub fn apply(&self, pool_state: &mut PoolState, amm_config: Option<&AmmConfig>) -> Result<()> {
+        match *self {
+            PoolAdminOp::Pause => pool_state.pause(),
+            PoolAdminOp::SetStatus { status } => pool_state.set_status(status),
+            PoolAdminOp::SetTradeFeeRate { trade_fee_rate } => {
+                if let Some(trade_fee_rate) = trade_fee_rate {
+                    let amm_config = amm_config.ok_or(ErrorCode::InvalidInput)?;
+                    require!(
+                        amm_config.is_valid_pool_trade_fee_rate(trade_fee_rate),
+                        ErrorCode::InvalidTradeFeeRate
+                    );
+                }
+                pool_state.set_trade_fee_rate_override(trade_fee_rate);
+            }
+            PoolAdminOp::SetCustomAuthority { new_authority } => {
+                // a renounced custom authority cannot be set again
+                require!(pool_state.is_custom_authority(), ErrorCode::NotApproved);
+                pool_state.set_custom_authority(new_authority);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An operation of `batch_admin` on the pool at `pool_index` of the remaining accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchAdminOp {
+    pub pool_index: u8,
+    pub op: PoolAdminOp,
+}
+
+#[cfg(test)]
+pub mod admin_test {
+    use super::*;
+
+    #[test]
+    fn pool_admin_op_apply_test() {
+        let mut pool_state = PoolState::default();
+        PoolAdminOp::Pause.apply(&mut pool_state, None).unwrap();
+        assert_eq!(pool_state.status, u8::MAX);
+        PoolAdminOp::SetStatus { status: 4 }
+            .apply(&mut pool_state, None)
+            .unwrap();
+        assert_eq!(pool_state.status, 4);
+
+        // the trade fee rate is bounded by the amm config, which is required to set one
+        let mut amm_config = AmmConfig::default();
+        amm_config.max_trade_fee_rate = 10_000;
+        let set_trade_fee_rate = PoolAdminOp::SetTradeFeeRate {
+            trade_fee_rate: Some(5_000),
+        };
+        assert!(set_trade_fee_rate.apply(&mut pool_state, None).is_err());
+        assert!(PoolAdminOp::SetTradeFeeRate {
+            trade_fee_rate: Some(20_000),
+        }
+        .apply(&mut pool_state, Some(&amm_config))
+        .is_err());
+        set_trade_fee_rate
+            .apply(&mut pool_state, Some(&amm_config))
+            .unwrap();
+        assert_eq!(pool_state.trade_fee_rate_override(0), Some(5_000));
+        PoolAdminOp::SetTradeFeeRate {
+            trade_fee_rate: None,
+        }
+        .apply(&mut pool_state, None)
+        .unwrap();
+        assert_eq!(pool_state.trade_fee_rate_override(0), None);
+
+        // only a custom authority pool changes its custom authority
+        let new_authority = Pubkey::new_unique();
+        let set_custom_authority = PoolAdminOp::SetCustomAuthority { new_authority };
+        assert!(set_custom_authority.apply(&mut pool_state, None).is_err());
+        pool_state.authority_type = 1;
+        pool_state.custom_authority = Pubkey::new_unique();
+        set_custom_authority.apply(&mut pool_state, None).unwrap();
+        assert_eq!({ pool_state.custom_authority }, new_authority);
+    }
+}
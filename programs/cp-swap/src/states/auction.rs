@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+pub const SWAP_REQUEST_SEED: &str = "swap_request";
+pub const SWAP_REQUEST_VAULT_SEED: &str = "swap_request_vault";
+
+/// Escrows the input tokens of a swap going through the order-flow auction of a pool,
+/// the filler can fill it at a better price than the pool until the auction window ends,
+/// then anyone can execute it against the pool
+#[account]
+#[derive(Default, Debug)]
+pub struct SwapRequest {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the swap is requested in
+    pub pool_id: Pubkey,
+    /// Owner of the request, receives the output tokens and the rent
+    pub owner: Pubkey,
+    /// Token account escrowing the input tokens
+    pub request_vault: Pubkey,
+    /// The mint of the tokens sold
+    pub input_mint: Pubkey,
+    /// The mint of the tokens bought
+    pub output_mint: Pubkey,
+    /// The owner token account receiving the bought tokens
+    pub output_token_account: Pubkey,
+    /// The pool filler when the swap was requested, the only address allowed to execute
+    /// the request during the auction window
+    pub filler: Pubkey,
+    /// Amount of input tokens escrowed
+    pub amount_in: u64,
+    /// Minimum amount of output tokens received by the owner
+    pub minimum_amount_out: u64,
+    /// Slot the swap was requested at
+    pub request_slot: u64,
+    /// Last slot of the auction window
+    pub auction_end_slot: u64,
+}
+
+impl SwapRequest {
+    pub const LEN: usize = 8 + 1 + 32 * 7 + 8 * 4;
+
+    /// Whether only the filler can execute the request at `slot`
+    pub fn is_auction_open(&self, slot: u64) -> bool {
+        slot <= self.auction_end_slot
+    }
+}
+
+#[cfg(test)]
+pub mod auction_test {
+    use super::*;
+
+    #[test]
+    fn swap_request_size_test() {
+        let request = SwapRequest::default();
+        assert_eq!(request.try_to_vec().unwrap().len(), SwapRequest::LEN - 8)
+    }
+
+    #[test]
+    fn auction_window_test() {
+        let request = SwapRequest {
+            request_slot: 100,
+            auction_end_slot: 104,
+            ..Default::default()
+        };
+        assert!(request.is_auction_open(100));
+        assert!(request.is_auction_open(104));
+        assert!(!request.is_auction_open(105));
+    }
+}
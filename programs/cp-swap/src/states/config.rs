@@ -1,6 +1,33 @@
+use crate::curve::calculator::PRICE_IMPACT_BPS_DENOMINATOR;
+use crate::curve::fees::Fees;
+use crate::error::ErrorCode;
+use crate::states::{
+    MintPolicy, Observation, ObservationState, OBSERVATION_UPDATE_DURATION_DEFAULT, Q32,
+    VOLATILITY_WINDOW_DEFAULT,
+};
+use crate::utils::U256;
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
+pub const PENDING_CONFIG_CHANGE_SEED: &str = "pending_config_change";
+pub const PENDING_POOL_CONFIG_CHANGE_SEED: &str = "pending_pool_config_change";
+/// Delay in seconds before a queued fee rate change can be executed
+pub const CONFIG_CHANGE_DELAY: u64 = 24 * 60 * 60;
+/// Max number of balance tiers of the trade fee discount
+pub const FEE_DISCOUNT_TIER_COUNT: usize = 4;
+
+/// A trade fee discount granted to the holders of at least `min_balance` discount tokens
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct FeeDiscountTier {
+    /// The discount token balance of the tier, the tier is unused when it is 0
+    pub min_balance: u64,
+    /// The discount of the trade fee in basis points
+    pub discount_bps: u16,
+}
+
+impl FeeDiscountTier {
+    pub const LEN: usize = 8 + 2;
+}
 
 /// Holds the current owner of the factory
 #[account]
@@ -18,16 +45,582 @@ pub struct AmmConfig {
     pub protocol_fee_rate: u64,
     /// The fund fee, denominated in hundredths of a bip (10^-6)
     pub fund_fee_rate: u64,
-    /// Fee for create a new pool
+    /// Fee for create a new pool, in `create_pool_fee_mint`
     pub create_pool_fee: u64,
     /// Address of the protocol fee owner
     pub protocol_owner: Pubkey,
     /// Address of the fund fee owner
     pub fund_owner: Pubkey,
+    /// The flash loan fee, denominated in hundredths of a bip (10^-6)
+    pub flash_loan_fee_rate: u64,
+    /// The lower bound of the dynamic trade fee, denominated in hundredths of a bip (10^-6)
+    pub dynamic_fee_min_rate: u64,
+    /// The upper bound of the dynamic trade fee, denominated in hundredths of a bip (10^-6)
+    /// The dynamic fee mode is disabled when it is 0
+    pub dynamic_fee_max_rate: u64,
+    /// The trade fee added per unit of volatility, denominated in hundredths of a bip (10^-6)
+    pub dynamic_fee_volatility_factor: u64,
+    /// The only address allowed to create pools with this config,
+    /// anyone can create pools when it is the default pubkey
+    pub pool_creation_authority: Pubkey,
+    /// The fraction of accumulated protocol fees re-deposited into the pool liquidity by
+    /// `compound_fees`, denominated in hundredths of a bip (10^-6), disabled when it is 0
+    pub protocol_fee_compound_rate: u64,
+    /// The referrer share of the trade fee when a swap passes a referrer,
+    /// denominated in hundredths of a bip (10^-6)
+    pub referral_fee_rate: u64,
+    /// The share of the swept fees paid to the `crank_protocol_fees` caller,
+    /// denominated in hundredths of a bip (10^-6)
+    pub fee_crank_bounty_rate: u64,
+    /// The lower bound of the trade fee custom authority pools can set,
+    /// denominated in hundredths of a bip (10^-6)
+    pub min_trade_fee_rate: u64,
+    /// The upper bound of the trade fee custom authority pools can set,
+    /// denominated in hundredths of a bip (10^-6), pool trade fees are disabled when it is 0
+    pub max_trade_fee_rate: u64,
+    /// The token bought back with the fund fees of its pools and burned by `execute_buyback`,
+    /// the buyback mode is disabled when it is the default pubkey
+    pub buyback_mint: Pubkey,
+    /// The maximum amount of fund fees a pool spends on buybacks per epoch, unbounded when it is 0
+    pub buyback_epoch_cap: u64,
+    /// The owner of the token accounts the protocol fees are collected to, the fees can be
+    /// collected by anyone when it is set, otherwise by the protocol owner to any account
+    pub protocol_fee_destination: Pubkey,
+    /// The owner of the token accounts the fund fees are collected to, the fees can be
+    /// collected by anyone when it is set, otherwise by the fund owner to any account
+    pub fund_fee_destination: Pubkey,
+    /// The mint the fee to create a pool is paid in, native SOL is paid as WSOL
+    pub create_pool_fee_mint: Pubkey,
+    /// The owner of the token account receiving the fee to create a pool
+    pub create_pool_fee_receiver: Pubkey,
+    /// The token whose holders get a trade fee discount when they pass their token account
+    /// to a swap, the fee discount is disabled when it is the default pubkey
+    pub fee_discount_mint: Pubkey,
+    /// The fee discount tiers, sorted by increasing balance
+    pub fee_discount_tiers: [FeeDiscountTier; FEE_DISCOUNT_TIER_COUNT],
+    /// Whether the transfer fees harvested from the vaults are credited to the protocol fees
+    /// instead of the liquidity providers
+    pub withheld_fees_to_protocol: bool,
+    /// The minimum seconds between two oracle observations of the pools,
+    /// `OBSERVATION_UPDATE_DURATION_DEFAULT` when it is 0
+    pub observation_update_duration: u64,
+    /// The mint policy new pools are checked against, see `MintPolicy`,
+    /// any supported mint is allowed when it is the default pubkey
+    pub mint_policy: Pubkey,
+    /// Whether the swaps charge the trade fee on the output token instead of the input token
+    pub fee_on_output: bool,
+    /// Whether pools can be created at a salted address next to the canonical pool of
+    /// their mint pair, which fragments its liquidity
+    pub allow_salted_pools: bool,
+    /// Can pause the pools of the config and their creation, but never resume them or
+    /// change fees, disabled when it is the default pubkey
+    pub pause_guardian: Pubkey,
+    /// The revenue distribution program the protocol fees are deposited to by
+    /// `collect_protocol_fee`, such as a staking program, disabled when it is the default pubkey
+    pub revenue_program: Pubkey,
+    /// The share of the fund fees collected by `collect_fund_fee` sent to the insurance fund
+    /// of the config, in units of FEE_RATE_DENOMINATOR_VALUE
+    pub insurance_fund_rate: u64,
+    /// The maximum token 2022 transfer fee of the mints of the pools, in basis points, checked
+    /// at pool creation and on each swap, unbounded when it is 0
+    pub max_transfer_fee_bps: u16,
+    /// The share of the liquidity provider part of the trade fee rebated as lp tokens to the
+    /// payers passing their lp token account to a swap, denominated in hundredths of a bip
+    /// (10^-6), disabled when it is 0
+    pub lp_fee_rebate_rate: u64,
+    /// The maximum amount of lp tokens a pool mints for lp fee rebates per epoch, unbounded
+    /// when it is 0
+    pub lp_fee_rebate_epoch_cap: u64,
+    /// The most the price of a buyback may fall below the TWAP of its pool, in basis points,
+    /// buybacks are disabled while it is 0
+    pub buyback_max_deviation_bps: u16,
+    /// The seconds of the TWAP the buybacks are priced against
+    pub buyback_twap_window: u16,
     /// padding
-    pub padding: [u64; 16],
+    pub padding: [u8; 2],
 }
 
 impl AmmConfig {
-    pub const LEN: usize = 8 + 1 + 1 + 2 + 4 * 8 + 32 * 2 + 8 * 16;
+    pub const LEN: usize = 8
+        + 1
+        + 1
+        + 2
+        + 4 * 8
+        + 32 * 9
+        + 8 * 10
+        + 10 * 4
+        + 1
+        + 8
+        + 32
+        + 1
+        + 1
+        + 32 * 2
+        + 8
+        + 2
+        + 8 * 2
+        + 2 * 2
+        + 2;
+
+    pub fn is_pause_guardian(&self, authority: &Pubkey) -> bool {
+        self.pause_guardian != Pubkey::default() && self.pause_guardian == *authority
+    }
+
+    pub fn is_allowed_pool_creator(&self, creator: &Pubkey) -> bool {
+        self.pool_creation_authority == Pubkey::default()
+            || self.pool_creation_authority == *creator
+    }
+
+    /// Get the protocol fee amounts re-deposited into the pool liquidity
+    pub fn protocol_fees_to_compound(
+        &self,
+        protocol_fees_token_0: u64,
+        protocol_fees_token_1: u64,
+    ) -> (u64, u64) {
+        let compound = |protocol_fees: u64| {
+            u64::try_from(
+                Fees::protocol_fee(u128::from(protocol_fees), self.protocol_fee_compound_rate)
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+        (
+            compound(protocol_fees_token_0),
+            compound(protocol_fees_token_1),
+        )
+    }
+
+    pub fn is_protocol_fee_destination_set(&self) -> bool {
+        self.protocol_fee_destination != Pubkey::default()
+    }
+
+    pub fn is_revenue_program_set(&self) -> bool {
+        self.revenue_program != Pubkey::default()
+    }
+
+    pub fn is_insurance_fund_enabled(&self) -> bool {
+        self.insurance_fund_rate != 0
+    }
+
+    /// Get the share of the collected fund fees sent to the insurance fund of the config
+    pub fn insurance_fund_share(&self, fund_fees: u64) -> u64 {
+        u64::try_from(Fees::protocol_fee(u128::from(fund_fees), self.insurance_fund_rate).unwrap())
+            .unwrap()
+    }
+
+    pub fn is_transfer_fee_capped(&self) -> bool {
+        self.max_transfer_fee_bps != 0
+    }
+
+    /// Reject a mint whose transfer fee of `transfer_fee_bps` basis points exceeds the cap
+    pub fn check_transfer_fee_bps(&self, transfer_fee_bps: u16) -> Result<()> {
+        require!(
+            !self.is_transfer_fee_capped() || transfer_fee_bps <= self.max_transfer_fee_bps,
+            ErrorCode::TransferFeeTooHigh
+        );
+        Ok(())
+    }
+
+    pub fn is_fund_fee_destination_set(&self) -> bool {
+        self.fund_fee_destination != Pubkey::default()
+    }
+
+    /// The owner of the token accounts receiving the swept protocol fees
+    pub fn protocol_fee_recipient(&self) -> Pubkey {
+        if self.is_protocol_fee_destination_set() {
+            self.protocol_fee_destination
+        } else {
+            self.protocol_owner
+        }
+    }
+
+    /// The owner of the token accounts receiving the swept fund fees
+    pub fn fund_fee_recipient(&self) -> Pubkey {
+        if self.is_fund_fee_destination_set() {
+            self.fund_fee_destination
+        } else {
+            self.fund_owner
+        }
+    }
+
+    pub fn is_buyback_enabled(&self) -> bool {
+        self.buyback_mint != Pubkey::default()
+    }
+
+    /// The least output a buyback of `amount_in` receives at most `buyback_max_deviation_bps`
+    /// below the TWAP price of the input token in the output token, rounded up
+    pub fn buyback_minimum_amount_out(&self, amount_in: u64, twap_price_x32: u128) -> u64 {
+        let minimum_amount_out = U256::from(amount_in)
+            * U256::from(twap_price_x32)
+            * U256::from(PRICE_IMPACT_BPS_DENOMINATOR - u64::from(self.buyback_max_deviation_bps));
+        let denominator = U256::from(Q32) * U256::from(PRICE_IMPACT_BPS_DENOMINATOR);
+        u64::try_from((minimum_amount_out + denominator - 1) / denominator).unwrap_or(u64::MAX)
+    }
+
+    /// Whether the fund fees of a pool are reserved for buybacks instead of the fund owner
+    pub fn is_buyback_pool(&self, token_0_mint: &Pubkey, token_1_mint: &Pubkey) -> bool {
+        self.is_buyback_enabled()
+            && (self.buyback_mint == *token_0_mint || self.buyback_mint == *token_1_mint)
+    }
+
+    /// The minimum seconds between two oracle observations of the pools
+    pub fn observation_update_duration(&self) -> u64 {
+        if self.observation_update_duration == 0 {
+            OBSERVATION_UPDATE_DURATION_DEFAULT
+        } else {
+            self.observation_update_duration
+        }
+    }
+
+    pub fn is_mint_policy_set(&self) -> bool {
+        self.mint_policy != Pubkey::default()
+    }
+
+    /// Check the mints of a new pool against the mint policy, when one is set
+    pub fn check_pool_mints(
+        &self,
+        mint_policy: Option<&MintPolicy>,
+        token_0_mint: &Pubkey,
+        token_1_mint: &Pubkey,
+    ) -> Result<()> {
+        if !self.is_mint_policy_set() {
+            return Ok(());
+        }
+        let mint_policy = mint_policy.ok_or(ErrorCode::InvalidInput)?;
+        require!(
+            mint_policy.is_mint_allowed(token_0_mint) && mint_policy.is_mint_allowed(token_1_mint),
+            ErrorCode::MintNotAllowed
+        );
+        Ok(())
+    }
+
+    pub fn is_dynamic_fee_enabled(&self) -> bool {
+        self.dynamic_fee_max_rate != 0
+    }
+
+    pub fn is_pool_trade_fee_rate_enabled(&self) -> bool {
+        self.max_trade_fee_rate != 0
+    }
+
+    pub fn is_valid_pool_trade_fee_rate(&self, trade_fee_rate: u64) -> bool {
+        self.is_pool_trade_fee_rate_enabled()
+            && (self.min_trade_fee_rate..=self.max_trade_fee_rate).contains(&trade_fee_rate)
+    }
+
+    /// Get the static trade fee rate of a pool, the trade fee rate set by its custom
+    /// authority is bounded by the current min and max rates
+    pub fn pool_trade_fee_rate(&self, pool_trade_fee_rate: Option<u64>) -> u64 {
+        match pool_trade_fee_rate {
+            Some(trade_fee_rate) if self.is_pool_trade_fee_rate_enabled() => {
+                trade_fee_rate.clamp(self.min_trade_fee_rate, self.max_trade_fee_rate)
+            }
+            _ => self.trade_fee_rate,
+        }
+    }
+
+    /// Get the trade fee rate charged by a swap, the static trade fee rate of the pool
+    /// unless the dynamic fee mode is enabled
+    pub fn swap_trade_fee_rate(
+        &self,
+        pool_trade_fee_rate: Option<u64>,
+        observation_state: &ObservationState,
+        observation_extension: &[Observation],
+        block_timestamp: u64,
+        token_0_price_x32: u128,
+    ) -> u64 {
+        let trade_fee_rate = self.pool_trade_fee_rate(pool_trade_fee_rate);
+        if !self.is_dynamic_fee_enabled() {
+            return trade_fee_rate;
+        }
+        let volatility = observation_state.volatility(
+            observation_extension,
+            block_timestamp,
+            VOLATILITY_WINDOW_DEFAULT,
+            token_0_price_x32,
+        );
+        self.dynamic_trade_fee_rate(trade_fee_rate, volatility)
+    }
+
+    pub fn is_fee_discount_enabled(&self) -> bool {
+        self.fee_discount_mint != Pubkey::default()
+    }
+
+    /// Get the discount in basis points of the highest tier reached by a discount token balance
+    pub fn fee_discount_bps(&self, balance: u64) -> u16 {
+        if !self.is_fee_discount_enabled() {
+            return 0;
+        }
+        self.fee_discount_tiers
+            .iter()
+            .filter(|tier| tier.min_balance != 0 && balance >= tier.min_balance)
+            .map(|tier| tier.discount_bps)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Apply the fee discount of the payer to the trade fee rate of a swap, `balance` is
+    /// the discount token balance of the payer when it passed its token account
+    pub fn discounted_trade_fee_rate(&self, trade_fee_rate: u64, balance: Option<u64>) -> u64 {
+        let discount_bps = match balance {
+            Some(balance) => u64::from(self.fee_discount_bps(balance)),
+            None => return trade_fee_rate,
+        };
+        let discount_rate = u128::from(trade_fee_rate) * u128::from(discount_bps)
+            / u128::from(PRICE_IMPACT_BPS_DENOMINATOR);
+        trade_fee_rate - u64::try_from(discount_rate).unwrap()
+    }
+
+    /// Set the fee discount tiers, they must reach higher discounts with higher balances
+    pub fn set_fee_discount(
+        &mut self,
+        fee_discount_mint: Pubkey,
+        tiers: &[FeeDiscountTier],
+    ) -> Result<()> {
+        require_gte!(
+            FEE_DISCOUNT_TIER_COUNT,
+            tiers.len(),
+            crate::error::ErrorCode::InvalidInput
+        );
+        let mut previous = FeeDiscountTier::default();
+        for tier in tiers {
+            require!(
+                tier.min_balance > previous.min_balance
+                    && tier.discount_bps >= previous.discount_bps
+                    && u64::from(tier.discount_bps) <= PRICE_IMPACT_BPS_DENOMINATOR,
+                crate::error::ErrorCode::InvalidInput
+            );
+            previous = *tier;
+        }
+        self.fee_discount_mint = fee_discount_mint;
+        self.fee_discount_tiers = [FeeDiscountTier::default(); FEE_DISCOUNT_TIER_COUNT];
+        self.fee_discount_tiers[..tiers.len()].copy_from_slice(tiers);
+        Ok(())
+    }
+
+    /// Get the trade fee rate of the dynamic fee mode for the given static rate and volatility
+    pub fn dynamic_trade_fee_rate(&self, trade_fee_rate: u64, volatility: u64) -> u64 {
+        Fees::dynamic_trade_fee_rate(
+            trade_fee_rate,
+            volatility,
+            self.dynamic_fee_volatility_factor,
+            self.dynamic_fee_min_rate,
+            self.dynamic_fee_max_rate,
+        )
+    }
+}
+
+/// A fee rate change of an amm config waiting for its timelock
+#[account]
+#[derive(Default, Debug)]
+pub struct PendingConfigChange {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The `param` of `update_amm_config`
+    pub param: u8,
+    /// The amm config to be changed
+    pub amm_config: Pubkey,
+    /// The new value of the param
+    pub value: u64,
+    /// The timestamp after which the change can be executed
+    pub effective_timestamp: u64,
+    /// Address paid the rent, refunded when the change is executed
+    pub payer: Pubkey,
+}
+
+impl PendingConfigChange {
+    pub const LEN: usize = 8 + 1 + 1 + 32 + 8 + 8 + 32;
+}
+
+/// A reassignment of a pool to another amm config waiting for its timelock
+#[account]
+#[derive(Default, Debug)]
+pub struct PendingPoolConfigChange {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool to be reassigned
+    pub pool_id: Pubkey,
+    /// The amm config the pool is reassigned to
+    pub new_amm_config: Pubkey,
+    /// The timestamp after which the reassignment can be executed
+    pub effective_timestamp: u64,
+    /// Address paid the rent, refunded when the reassignment is executed
+    pub payer: Pubkey,
+}
+
+impl PendingPoolConfigChange {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 + 32;
+}
+
+#[cfg(test)]
+pub mod config_test {
+    use super::*;
+
+    #[test]
+    fn fee_recipient_defaults_to_owner() {
+        let mut amm_config = AmmConfig {
+            protocol_owner: Pubkey::new_unique(),
+            fund_owner: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        assert_eq!(
+            amm_config.protocol_fee_recipient(),
+            amm_config.protocol_owner
+        );
+        assert_eq!(amm_config.fund_fee_recipient(), amm_config.fund_owner);
+
+        amm_config.protocol_fee_destination = Pubkey::new_unique();
+        assert_eq!(
+            amm_config.protocol_fee_recipient(),
+            amm_config.protocol_fee_destination
+        );
+        assert_eq!(amm_config.fund_fee_recipient(), amm_config.fund_owner);
+    }
+
+    #[test]
+    fn buyback_minimum_amount_out_test() {
+        let mut amm_config = AmmConfig {
+            buyback_max_deviation_bps: 100,
+            ..Default::default()
+        };
+        // 1_000 input tokens at a TWAP of 2 output tokens each, less 1%
+        assert_eq!(amm_config.buyback_minimum_amount_out(1_000, 2 * Q32), 1_980);
+        // rounded up against the buyback
+        assert_eq!(amm_config.buyback_minimum_amount_out(1, Q32 / 3), 1);
+        assert_eq!(amm_config.buyback_minimum_amount_out(0, Q32), 0);
+        amm_config.buyback_max_deviation_bps = 10_000;
+        assert_eq!(amm_config.buyback_minimum_amount_out(1_000, Q32), 0);
+        amm_config.buyback_max_deviation_bps = 0;
+        assert_eq!(
+            amm_config.buyback_minimum_amount_out(u64::MAX, 2 * Q32),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn insurance_fund_share_test() {
+        let mut amm_config = AmmConfig::default();
+        assert!(!amm_config.is_insurance_fund_enabled());
+        assert_eq!(amm_config.insurance_fund_share(1_000), 0);
+        // 10% of the fund fees, rounded down
+        amm_config.insurance_fund_rate = 100_000;
+        assert!(amm_config.is_insurance_fund_enabled());
+        assert_eq!(amm_config.insurance_fund_share(1_000), 100);
+        assert_eq!(amm_config.insurance_fund_share(9), 0);
+    }
+
+    #[test]
+    fn transfer_fee_bps_bounded_by_config() {
+        let mut amm_config = AmmConfig::default();
+        assert!(amm_config.check_transfer_fee_bps(10_000).is_ok());
+        amm_config.max_transfer_fee_bps = 100;
+        assert!(amm_config.check_transfer_fee_bps(100).is_ok());
+        assert_eq!(
+            amm_config.check_transfer_fee_bps(101).unwrap_err(),
+            ErrorCode::TransferFeeTooHigh.into()
+        );
+    }
+
+    #[test]
+    fn observation_update_duration_defaults() {
+        let mut amm_config = AmmConfig::default();
+        assert_eq!(
+            amm_config.observation_update_duration(),
+            OBSERVATION_UPDATE_DURATION_DEFAULT
+        );
+        amm_config.observation_update_duration = 60;
+        assert_eq!(amm_config.observation_update_duration(), 60);
+    }
+
+    #[test]
+    fn check_pool_mints_against_mint_policy() {
+        let mut amm_config = AmmConfig::default();
+        let mut mint_policy = MintPolicy::default();
+        let banned_mint = Pubkey::new_unique();
+        let other_mint = Pubkey::new_unique();
+        mint_policy.add_mint(banned_mint);
+        // no mint policy is set
+        assert!(amm_config
+            .check_pool_mints(None, &banned_mint, &other_mint)
+            .is_ok());
+
+        amm_config.mint_policy = Pubkey::new_unique();
+        assert_eq!(
+            amm_config
+                .check_pool_mints(None, &banned_mint, &other_mint)
+                .unwrap_err(),
+            ErrorCode::InvalidInput.into()
+        );
+        assert_eq!(
+            amm_config
+                .check_pool_mints(Some(&mint_policy), &other_mint, &banned_mint)
+                .unwrap_err(),
+            ErrorCode::MintNotAllowed.into()
+        );
+        assert!(amm_config
+            .check_pool_mints(Some(&mint_policy), &other_mint, &Pubkey::new_unique())
+            .is_ok());
+    }
+
+    #[test]
+    fn pool_trade_fee_rate_bounded_by_config() {
+        let mut amm_config = AmmConfig {
+            trade_fee_rate: 2500,
+            ..Default::default()
+        };
+        // pool trade fees are disabled
+        assert_eq!(amm_config.pool_trade_fee_rate(Some(100)), 2500);
+        assert!(!amm_config.is_valid_pool_trade_fee_rate(100));
+
+        amm_config.min_trade_fee_rate = 500;
+        amm_config.max_trade_fee_rate = 10000;
+        assert_eq!(amm_config.pool_trade_fee_rate(None), 2500);
+        assert_eq!(amm_config.pool_trade_fee_rate(Some(1000)), 1000);
+        assert!(amm_config.is_valid_pool_trade_fee_rate(1000));
+        assert!(!amm_config.is_valid_pool_trade_fee_rate(100));
+        // narrowed bounds apply to the existing pool trade fee rates
+        assert_eq!(amm_config.pool_trade_fee_rate(Some(100)), 500);
+        assert_eq!(amm_config.pool_trade_fee_rate(Some(20000)), 10000);
+    }
+
+    #[test]
+    fn fee_discount_of_highest_tier_reached() {
+        let mut amm_config = AmmConfig::default();
+        let tiers = [
+            FeeDiscountTier {
+                min_balance: 100,
+                discount_bps: 1000,
+            },
+            FeeDiscountTier {
+                min_balance: 1000,
+                discount_bps: 2500,
+            },
+        ];
+        // the tiers are ignored while the discount mint is not set
+        amm_config
+            .set_fee_discount(Pubkey::default(), &tiers)
+            .unwrap();
+        assert_eq!(amm_config.discounted_trade_fee_rate(2500, Some(1000)), 2500);
+
+        amm_config
+            .set_fee_discount(Pubkey::new_unique(), &tiers)
+            .unwrap();
+        assert_eq!(amm_config.discounted_trade_fee_rate(2500, None), 2500);
+        assert_eq!(amm_config.discounted_trade_fee_rate(2500, Some(99)), 2500);
+        assert_eq!(amm_config.discounted_trade_fee_rate(2500, Some(100)), 2250);
+        assert_eq!(amm_config.discounted_trade_fee_rate(2500, Some(5000)), 1875);
+
+        // unsorted tiers
+        let mint = amm_config.fee_discount_mint;
+        assert!(amm_config
+            .set_fee_discount(mint, &[tiers[1], tiers[0]])
+            .is_err());
+        assert!(amm_config
+            .set_fee_discount(
+                mint,
+                &[FeeDiscountTier {
+                    min_balance: 1,
+                    discount_bps: 10001,
+                }]
+            )
+            .is_err());
+    }
 }
@@ -0,0 +1,123 @@
+use crate::states::KEEPER_TIP_BPS_DENOMINATOR;
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+pub const DCA_ORDER_SEED: &str = "dca_order";
+pub const DCA_ORDER_VAULT_SEED: &str = "dca_order_vault";
+
+/// Escrows input tokens sold against its pool in tranches of `amount_per_trade`, at most
+/// one every `interval` seconds, executed by keepers
+#[account]
+#[derive(Default, Debug)]
+pub struct DcaOrder {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the tranches are swapped against
+    pub pool_id: Pubkey,
+    /// Owner of the order, the only address allowed to cancel it
+    pub owner: Pubkey,
+    /// Token account escrowing the remaining input tokens
+    pub order_vault: Pubkey,
+    /// The mint of the tokens sold
+    pub input_mint: Pubkey,
+    /// The mint of the tokens bought
+    pub output_mint: Pubkey,
+    /// The owner token account receiving the bought tokens
+    pub output_token_account: Pubkey,
+    /// Amount of input tokens sold by each tranche, the last tranche sells the rest
+    pub amount_per_trade: u64,
+    /// Minimum seconds between two tranches
+    pub interval: u64,
+    /// The unix timestamp from which the next tranche can be executed
+    pub next_execution_time: u64,
+    /// Minimum amount of output tokens per input token received by the owner, Q64.64,
+    /// tranches executing at a lower price revert
+    pub min_price_x64: u128,
+    /// Share of the output of each tranche paid to the keeper, in basis points
+    pub keeper_tip_bps: u16,
+    /// Amount of input tokens escrowed when the order was opened
+    pub amount_in: u64,
+    /// Amount of input tokens not swapped yet
+    pub amount_in_remaining: u64,
+    /// Amount of output tokens received by the owner
+    pub amount_out_filled: u64,
+    /// Number of tranches executed
+    pub tranches_executed: u64,
+}
+
+impl DcaOrder {
+    pub const LEN: usize = 8 + 1 + 32 * 6 + 8 * 3 + 16 + 2 + 8 * 4;
+
+    /// Get the input amount of the next tranche
+    pub fn tranche_amount(&self) -> u64 {
+        self.amount_per_trade.min(self.amount_in_remaining)
+    }
+
+    /// Whether the next tranche can be executed at `block_timestamp`
+    pub fn is_tranche_due(&self, block_timestamp: u64) -> bool {
+        self.amount_in_remaining > 0 && block_timestamp >= self.next_execution_time
+    }
+
+    /// Get the keeper share of the output of a tranche
+    pub fn keeper_tip(&self, amount_out: u64) -> u64 {
+        let keeper_tip = u128::from(amount_out) * u128::from(self.keeper_tip_bps)
+            / u128::from(KEEPER_TIP_BPS_DENOMINATOR);
+        u64::try_from(keeper_tip).unwrap()
+    }
+
+    /// Whether the owner receiving `amount_out` for `amount_in` meets the minimum price
+    pub fn is_min_price_reached(&self, amount_in: u64, amount_out: u64) -> bool {
+        U256::from(amount_out) << 64 >= U256::from(amount_in) * U256::from(self.min_price_x64)
+    }
+
+    /// Record a tranche swapping `amount_in` for `amount_out` sent to the owner, the next
+    /// tranche is due `interval` seconds after this one
+    pub fn record_tranche(&mut self, amount_in: u64, amount_out: u64, block_timestamp: u64) {
+        self.amount_in_remaining = self.amount_in_remaining.checked_sub(amount_in).unwrap();
+        self.amount_out_filled = self.amount_out_filled.checked_add(amount_out).unwrap();
+        self.tranches_executed += 1;
+        self.next_execution_time = block_timestamp.saturating_add(self.interval);
+    }
+}
+
+#[cfg(test)]
+pub mod dca_order_test {
+    use super::*;
+
+    #[test]
+    fn dca_order_size_test() {
+        let order = DcaOrder::default();
+        assert_eq!(order.try_to_vec().unwrap().len(), DcaOrder::LEN - 8)
+    }
+
+    #[test]
+    fn tranches_follow_the_schedule() {
+        let mut order = DcaOrder {
+            amount_per_trade: 400,
+            interval: 3600,
+            next_execution_time: 1_000,
+            amount_in: 1_000,
+            amount_in_remaining: 1_000,
+            // at least 2 output tokens per input token
+            min_price_x64: 2u128 << 64,
+            keeper_tip_bps: 10,
+            ..Default::default()
+        };
+        assert!(!order.is_tranche_due(999));
+        assert!(order.is_tranche_due(1_000));
+        assert_eq!(order.tranche_amount(), 400);
+        assert!(order.is_min_price_reached(400, 800));
+        assert!(!order.is_min_price_reached(400, 799));
+        assert_eq!(order.keeper_tip(10_000), 10);
+
+        order.record_tranche(400, 800, 1_200);
+        assert_eq!(order.next_execution_time, 4_800);
+        assert!(!order.is_tranche_due(4_799));
+        order.record_tranche(400, 800, 4_800);
+        assert_eq!(order.tranche_amount(), 200);
+        order.record_tranche(200, 400, 8_400);
+        assert_eq!(order.tranches_executed, 3);
+        assert_eq!(order.amount_out_filled, 2_000);
+        assert!(!order.is_tranche_due(u64::MAX));
+    }
+}
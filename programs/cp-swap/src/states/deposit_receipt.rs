@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+pub const DEPOSIT_RECEIPT_SEED: &str = "deposit_receipt";
+
+/// The lp tokens an owner deposited in a pool with a withdrawal lockup and their deposit
+/// time, the withdrawals of the lp tokens before the lockup ends pay the early exit fee of
+/// the pool
+#[account]
+#[derive(Default, Debug)]
+pub struct DepositReceipt {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the lp tokens were deposited in
+    pub pool_id: Pubkey,
+    /// The owner of the deposited lp tokens
+    pub owner: Pubkey,
+    /// The lp tokens deposited and not withdrawn yet
+    pub lp_amount: u64,
+    /// The deposit time of the lp tokens, the mean of the times of the deposits weighted by
+    /// their lp tokens
+    pub last_deposit_time: u64,
+}
+
+impl DepositReceipt {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 * 2;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, owner: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.owner = owner;
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.owner != Pubkey::default()
+    }
+
+    /// Add the deposited lp tokens, the deposit time moves to the lp weighted mean of both
+    /// deposits so a small deposit barely extends the lockup of the recorded lp tokens. The
+    /// recorded lp tokens already unlocked count as deposited `min_lock_duration` seconds ago,
+    /// which keeps the lockup of the new lp tokens at least proportional to their share
+    pub fn record_deposit(&mut self, lp_amount: u64, block_timestamp: u64, min_lock_duration: u32) {
+        let recorded_deposit_time = self
+            .last_deposit_time
+            .max(block_timestamp.saturating_sub(u64::from(min_lock_duration)));
+        let total_lp_amount = u128::from(self.lp_amount) + u128::from(lp_amount);
+        if total_lp_amount != 0 {
+            // rounded up, in favor of the lockup
+            let weighted_time = (u128::from(self.lp_amount) * u128::from(recorded_deposit_time)
+                + u128::from(lp_amount) * u128::from(block_timestamp))
+            .div_ceil(total_lp_amount);
+            self.last_deposit_time = u64::try_from(weighted_time).unwrap_or(block_timestamp);
+        }
+        self.lp_amount = self.lp_amount.saturating_add(lp_amount);
+    }
+
+    pub fn record_withdrawal(&mut self, lp_amount: u64) {
+        self.lp_amount = self.lp_amount.saturating_sub(lp_amount);
+    }
+
+    /// Add the lp tokens of a decompressed receipt, the lockup restarts from the latest of
+    /// both deposits
+    pub fn record_decompression(&mut self, lp_amount: u64, last_deposit_time: u64) {
+        self.lp_amount = self.lp_amount.saturating_add(lp_amount);
+        self.last_deposit_time = self.last_deposit_time.max(last_deposit_time);
+    }
+
+    /// The lp tokens withdrawn without the early exit fee at `block_timestamp`, all the
+    /// recorded lp tokens once `min_lock_duration` seconds passed since the last deposit
+    pub fn unlocked_lp_amount(&self, block_timestamp: u64, min_lock_duration: u32) -> u64 {
+        if block_timestamp
+            >= self
+                .last_deposit_time
+                .saturating_add(u64::from(min_lock_duration))
+        {
+            self.lp_amount
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod deposit_receipt_test {
+    use super::*;
+
+    #[test]
+    fn deposit_receipt_size_test() {
+        let deposit_receipt = DepositReceipt::default();
+        assert_eq!(
+            deposit_receipt.try_to_vec().unwrap().len(),
+            DepositReceipt::LEN - 8
+        )
+    }
+
+    #[test]
+    fn unlocked_lp_amount_waits_for_the_lockup() {
+        let mut deposit_receipt = DepositReceipt::default();
+        deposit_receipt.record_deposit(1_000, 100, 100);
+        assert_eq!(deposit_receipt.unlocked_lp_amount(199, 100), 0);
+        assert_eq!(deposit_receipt.unlocked_lp_amount(200, 100), 1_000);
+
+        // a new deposit moves the lockup of all the lp tokens to their weighted deposit time,
+        // (1_000 * 100 + 500 * 150) / 1_500 rounded up
+        deposit_receipt.record_deposit(500, 150, 100);
+        assert_eq!(deposit_receipt.last_deposit_time, 117);
+        assert_eq!(deposit_receipt.unlocked_lp_amount(216, 100), 0);
+        assert_eq!(deposit_receipt.unlocked_lp_amount(217, 100), 1_500);
+
+        deposit_receipt.record_withdrawal(600);
+        assert_eq!(deposit_receipt.lp_amount, 900);
+        deposit_receipt.record_withdrawal(1_000);
+        assert_eq!(deposit_receipt.lp_amount, 0);
+        assert_eq!(deposit_receipt.unlocked_lp_amount(0, 0), 0);
+    }
+
+    #[test]
+    fn small_deposits_barely_extend_the_lockup() {
+        let mut deposit_receipt = DepositReceipt::default();
+        deposit_receipt.record_deposit(1_000_000, 1_000, 86_400);
+        // 1 lp token deposited a day later does not restart the lockup of the others
+        deposit_receipt.record_deposit(1, 1_000 + 43_200, 86_400);
+        assert_eq!(deposit_receipt.last_deposit_time, 1_001);
+        assert_eq!(
+            deposit_receipt.unlocked_lp_amount(1_001 + 86_400, 86_400),
+            1_000_001
+        );
+
+        // the unlocked lp tokens count as deposited a lockup ago, so a deposit as large as
+        // them is locked for half the lockup
+        let mut deposit_receipt = DepositReceipt::default();
+        deposit_receipt.record_deposit(1_000, 0, 100);
+        deposit_receipt.record_deposit(1_000, 1_000, 100);
+        assert_eq!(deposit_receipt.last_deposit_time, 950);
+        assert_eq!(deposit_receipt.unlocked_lp_amount(1_049, 100), 0);
+        assert_eq!(deposit_receipt.unlocked_lp_amount(1_050, 100), 2_000);
+
+        // an emptied receipt locks the next deposit for the whole lockup
+        deposit_receipt.record_withdrawal(2_000);
+        deposit_receipt.record_deposit(10, 2_000, 100);
+        assert_eq!(deposit_receipt.last_deposit_time, 2_000);
+    }
+
+    #[test]
+    fn decompression_keeps_the_latest_lockup() {
+        let mut deposit_receipt = DepositReceipt::default();
+        deposit_receipt.record_deposit(1_000, 200, 100);
+        deposit_receipt.record_decompression(500, 150);
+        assert_eq!(deposit_receipt.lp_amount, 1_500);
+        assert_eq!(deposit_receipt.last_deposit_time, 200);
+        deposit_receipt.record_decompression(500, 250);
+        assert_eq!(deposit_receipt.lp_amount, 2_000);
+        assert_eq!(deposit_receipt.last_deposit_time, 250);
+    }
+}
@@ -1,5 +1,85 @@
+use crate::curve::{SwapResult, TradeDirection};
+use crate::states::{FeeDiscountTier, PoolAdminOp};
+use crate::utils::U256;
 use anchor_lang::prelude::*;
 
+/// Reserves of a pool, the vault amounts exclude the accrued protocol and fund fees
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct PoolReserves {
+    pub token_0_vault: u64,
+    pub token_1_vault: u64,
+    pub lp_supply: u64,
+    /// sqrt of the token_0 price denominated in token_1, Q64.64
+    pub sqrt_price_x64: u128,
+}
+
+impl PoolReserves {
+    pub fn new(token_0_vault: u64, token_1_vault: u64, lp_supply: u64) -> Self {
+        let sqrt_price_x64 = if token_0_vault == 0 {
+            0
+        } else {
+            ((U256::from(token_1_vault) << 128) / U256::from(token_0_vault))
+                .integer_sqrt()
+                .as_u128()
+        };
+        Self {
+            token_0_vault,
+            token_1_vault,
+            lp_supply,
+            sqrt_price_x64,
+        }
+    }
+
+    /// Get the reserves from the input and output vault amounts of a trade
+    pub fn from_trade_amounts(
+        trade_direction: TradeDirection,
+        input_vault: u64,
+        output_vault: u64,
+        lp_supply: u64,
+    ) -> Self {
+        match trade_direction {
+            TradeDirection::ZeroForOne => Self::new(input_vault, output_vault, lp_supply),
+            TradeDirection::OneForZero => Self::new(output_vault, input_vault, lp_supply),
+        }
+    }
+
+    /// Get the reserves after a swap from these reserves, the protocol and fund fees and
+    /// the `lp_fee_cut` of the referral and creator fees are taken out of the pool liquidity
+    pub fn after_swap(
+        &self,
+        trade_direction: TradeDirection,
+        result: &SwapResult,
+        lp_fee_cut: u64,
+    ) -> Self {
+        // the result amounts of boosted pools include the virtual reserves, so the
+        // reserves are moved by the swapped amounts instead
+        let (input_vault, output_vault) = match trade_direction {
+            TradeDirection::ZeroForOne => (self.token_0_vault, self.token_1_vault),
+            TradeDirection::OneForZero => (self.token_1_vault, self.token_0_vault),
+        };
+        // the fees leaving the reserves are taken from the vault of the fee token
+        let fees = result.protocol_fee + result.fund_fee + u128::from(lp_fee_cut);
+        let (input_fees, output_fees) = if result.fee_on_output {
+            (0, fees)
+        } else {
+            (fees, 0)
+        };
+        let input_vault = u64::try_from(
+            (u128::from(input_vault) + result.source_amount_swapped)
+                .checked_sub(input_fees)
+                .unwrap(),
+        )
+        .unwrap();
+        let output_vault = u64::try_from(
+            u128::from(output_vault)
+                .checked_sub(result.destination_amount_swapped + output_fees)
+                .unwrap(),
+        )
+        .unwrap();
+        Self::from_trade_amounts(trade_direction, input_vault, output_vault, self.lp_supply)
+    }
+}
+
 /// Emitted when deposit and withdraw
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -36,4 +116,705 @@ pub struct SwapEvent {
     pub input_transfer_fee: u64,
     pub output_transfer_fee: u64,
     pub base_input: bool,
+    /// total trade fee in the input token, including the protocol, fund and referral fees
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    /// part of the trade fee paid to the referrer
+    pub referral_fee: u64,
+    /// part of the trade fee accrued to the pool creator
+    pub creator_fee: u64,
+    pub reserves_before: PoolReserves,
+    pub reserves_after: PoolReserves,
+}
+
+/// Emitted when liquidity is added to a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DepositEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    /// lp tokens minted to the owner
+    pub lp_amount: u64,
+    /// amount received by the vault, without transfer fee
+    pub token_0_amount: u64,
+    /// amount received by the vault, without transfer fee
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    /// trade fee of the swapped part of a single token deposit
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    pub reserves_before: PoolReserves,
+    pub reserves_after: PoolReserves,
+}
+
+/// Emitted when liquidity is removed from a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct WithdrawEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    /// lp tokens burned from the owner
+    pub lp_amount: u64,
+    /// amount sent out of the vault, including transfer fee
+    pub token_0_amount: u64,
+    /// amount sent out of the vault, including transfer fee
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    /// trade fee of the swapped part of a single token withdraw
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    pub reserves_before: PoolReserves,
+    pub reserves_after: PoolReserves,
+}
+
+/// Emitted with the `WithdrawEvent` of a withdrawal from a pool with a frozen vault, whose
+/// share of the frozen vault is given up
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FrozenVaultWithdrawEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub frozen_vault: Pubkey,
+    pub lp_amount: u64,
+    /// amount sent out of the vault not frozen, including transfer fee
+    pub output_amount: u64,
+    /// share of the lp tokens in the frozen vault left to the remaining liquidity providers
+    pub forfeited_amount: u64,
+}
+
+/// Emitted when flash loan
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FlashLoanEvent {
+    pub pool_id: Pubkey,
+    pub borrower: Pubkey,
+    /// amount borrowed out of the vault
+    pub token_0_amount: u64,
+    /// amount borrowed out of the vault
+    pub token_1_amount: u64,
+    /// fee owed to the liquidity providers
+    pub token_0_fee: u64,
+    /// fee owed to the liquidity providers
+    pub token_1_fee: u64,
+}
+
+/// Emitted when a swap pays the referrer share of the trade fee
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ReferralFeeEvent {
+    pub pool_id: Pubkey,
+    /// the token account of the referrer
+    pub referrer_token_account: Pubkey,
+    pub mint: Pubkey,
+    /// amount sent from the input vault, the referrer pays the transfer fee
+    pub amount: u64,
+}
+
+/// Emitted when a swap rebates a share of the liquidity provider fee to the payer in lp tokens
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LpFeeRebateEvent {
+    pub pool_id: Pubkey,
+    /// the lp token account of the payer
+    pub payer_lp_token: Pubkey,
+    /// the mint of the rebated fee
+    pub mint: Pubkey,
+    /// the rebated share of the liquidity provider fee, left in the vault
+    pub rebate_fee: u64,
+    /// lp tokens minted for the rebate, worth less than it when the epoch cap bounds them
+    pub lp_amount: u64,
+}
+
+/// Emitted when protocol fees are re-deposited into the pool liquidity
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CompoundFeesEvent {
+    pub pool_id: Pubkey,
+    /// lp supply backed by the compounded fees
+    pub lp_supply: u64,
+    /// protocol fees moved into the pool liquidity
+    pub token_0_amount: u64,
+    /// protocol fees moved into the pool liquidity
+    pub token_1_amount: u64,
+}
+
+/// Emitted when the vault balances of a pool are synced, tokens sent directly to the
+/// vaults are credited to the liquidity providers
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SyncReservesEvent {
+    pub pool_id: Pubkey,
+    pub reserves: PoolReserves,
+}
+
+/// Emitted when a keeper fills a limit order against its pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LimitOrderFilledEvent {
+    pub pool_id: Pubkey,
+    pub order: Pubkey,
+    pub keeper: Pubkey,
+    pub amount_in: u64,
+    /// Output tokens sent to the order owner
+    pub amount_out: u64,
+    pub keeper_tip: u64,
+    pub amount_in_remaining: u64,
+}
+
+/// Emitted when a keeper executes a tranche of a DCA order against its pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DcaTrancheExecutedEvent {
+    pub pool_id: Pubkey,
+    pub order: Pubkey,
+    pub keeper: Pubkey,
+    pub amount_in: u64,
+    /// Output tokens sent to the order owner
+    pub amount_out: u64,
+    pub keeper_tip: u64,
+    pub amount_in_remaining: u64,
+    pub next_execution_time: u64,
+}
+
+/// Emitted when the fund fees of a pool buy back and burn the buyback token
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BuybackEvent {
+    pub pool_id: Pubkey,
+    pub buyback_mint: Pubkey,
+    pub epoch: u64,
+    /// Fund fees of the other token swapped for the buyback token
+    pub amount_in: u64,
+    /// Swapped output plus the fund fees of the buyback token, burned from the vault
+    pub amount_burned: u64,
+    /// Fund fees spent on buybacks in the epoch so far
+    pub epoch_amount: u64,
+}
+
+/// Emitted when the launch phase of a pool ends and every trade is allowed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LaunchFinalizedEvent {
+    pub pool_id: Pubkey,
+    pub launch_mode: u8,
+    pub launch_end_time: u64,
+}
+
+/// Emitted when a liquidity bootstrapping pool is converted to a constant product pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LbpFinalizedEvent {
+    pub pool_id: Pubkey,
+    pub lbp_end_time: u64,
+}
+
+/// Emitted when lp tokens are locked
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LockLiquidityEvent {
+    pub pool_id: Pubkey,
+    pub liquidity_lock: Pubkey,
+    pub owner: Pubkey,
+    pub lp_amount: u64,
+    /// nothing can be claimed before this timestamp
+    pub cliff_time: u64,
+    /// everything can be claimed after this timestamp
+    pub end_time: u64,
+}
+
+/// Emitted when unlocked lp tokens are claimed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClaimUnlockedLiquidityEvent {
+    pub pool_id: Pubkey,
+    pub liquidity_lock: Pubkey,
+    pub owner: Pubkey,
+    pub lp_amount: u64,
+    /// total amount claimed from the lock
+    pub claimed_amount: u64,
+}
+
+/// Emitted when the emissions of a pool reward are set
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RewardEmissionsUpdatedEvent {
+    pub pool_id: Pubkey,
+    pub reward_mint: Pubkey,
+    pub emissions_per_second: u64,
+    pub end_time: u64,
+    /// reward tokens the vault was funded with to emit them
+    pub funded_amount: u64,
+}
+
+/// Emitted when the rewards earned by a position are claimed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClaimRewardsEvent {
+    pub pool_id: Pubkey,
+    pub personal_position: Pubkey,
+    pub owner: Pubkey,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the custom authority swaps the pool price toward a target price, along
+/// with the `SwapEvent` of the swap
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RebalanceToTargetPriceEvent {
+    pub pool_id: Pubkey,
+    pub target_sqrt_price_x64: u128,
+    /// True when token_0 was sold for token_1
+    pub zero_for_one: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// False when `max_amount_in` stopped the swap short of the target price
+    pub target_reached: bool,
+}
+
+/// Emitted when the accumulated protocol and fund fees are swept by the fee crank
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CrankProtocolFeesEvent {
+    pub pool_id: Pubkey,
+    pub caller: Pubkey,
+    /// protocol fees sent to the protocol owner
+    pub protocol_fee_0: u64,
+    /// protocol fees sent to the protocol owner
+    pub protocol_fee_1: u64,
+    /// fund fees sent to the fund owner
+    pub fund_fee_0: u64,
+    /// fund fees sent to the fund owner
+    pub fund_fee_1: u64,
+    /// bounty sent to the caller
+    pub bounty_0: u64,
+    /// bounty sent to the caller
+    pub bounty_1: u64,
+}
+
+/// Emitted when a fee rate change is queued
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigChangeQueuedEvent {
+    pub amm_config: Pubkey,
+    pub param: u8,
+    pub value: u64,
+    /// the change can be executed after this timestamp
+    pub effective_timestamp: u64,
+}
+
+/// Emitted when the reassignment of a pool to another amm config is queued
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolConfigChangeQueuedEvent {
+    pub pool_id: Pubkey,
+    pub amm_config: Pubkey,
+    pub new_amm_config: Pubkey,
+    /// the reassignment can be executed after this timestamp
+    pub effective_timestamp: u64,
+}
+
+/// Emitted when a pool is reassigned to another amm config
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolConfigChangedEvent {
+    pub pool_id: Pubkey,
+    pub old_amm_config: Pubkey,
+    pub new_amm_config: Pubkey,
+}
+
+/// Emitted when accrued protocol or fund fees are sent out of the pool vaults
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FeeCollectedEvent {
+    pub pool_id: Pubkey,
+    // 0: protocol fee, 1: fund fee, 2: creator fee
+    pub fee_type: u8,
+    /// fees sent out of the vault, including the fee crank bounty
+    pub token_0_amount: u64,
+    /// fees sent out of the vault, including the fee crank bounty
+    pub token_1_amount: u64,
+    /// fees still accrued in the pool
+    pub token_0_fees_remaining: u64,
+    /// fees still accrued in the pool
+    pub token_1_fees_remaining: u64,
+}
+
+/// Emitted when a param of an amm config is changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub param: u8,
+    pub value: u64,
+    /// fee rates of the amm config after the change
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub referral_fee_rate: u64,
+    pub flash_loan_fee_rate: u64,
+}
+
+/// Emitted when an empty pool is closed and its rent refunded
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClosePoolEvent {
+    pub pool_id: Pubkey,
+    pub closer: Pubkey,
+    pub rent_receiver: Pubkey,
+}
+
+/// Emitted when a trade trips the circuit breaker of a pool and its swaps are paused
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CircuitBreakerTrippedEvent {
+    pub pool_id: Pubkey,
+    /// deviation of the price after the trade from the TWAP
+    pub deviation_bps: u64,
+    pub threshold_bps: u16,
+    pub window: u32,
+}
+
+/// Emitted when the fee discount tiers of an amm config are set
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FeeDiscountUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub fee_discount_mint: Pubkey,
+    pub tiers: Vec<FeeDiscountTier>,
+}
+
+/// Emitted when the transfer fees withheld by a vault are harvested
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct HarvestWithheldFeesEvent {
+    pub pool_id: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// whether the amount is credited to the protocol fees instead of the liquidity providers
+    pub to_protocol: bool,
+}
+
+/// Emitted when the authorized strategy borrows pool reserves
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct StrategyBorrowEvent {
+    pub pool_id: Pubkey,
+    pub strategy: Pubkey,
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    /// reserves borrowed by the strategy after the borrow
+    pub strategy_borrowed_0: u64,
+    pub strategy_borrowed_1: u64,
+}
+
+/// Emitted when borrowed reserves are returned to the pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct StrategyRepayEvent {
+    pub pool_id: Pubkey,
+    pub payer: Pubkey,
+    /// amount received by the vault
+    pub token_0_amount: u64,
+    /// amount received by the vault
+    pub token_1_amount: u64,
+    /// amount received above the borrowed reserves, left to the liquidity providers
+    pub token_0_yield: u64,
+    pub token_1_yield: u64,
+    /// reserves borrowed by the strategy after the repay
+    pub strategy_borrowed_0: u64,
+    pub strategy_borrowed_1: u64,
+}
+
+/// Emitted when a trader claims its fee rebates
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FeeRebateClaimedEvent {
+    pub pool_id: Pubkey,
+    pub trader: Pubkey,
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    /// rebates left to claim once the protocol fees accrue again
+    pub token_0_rebate_remaining: u64,
+    pub token_1_rebate_remaining: u64,
+}
+
+/// Emitted when liquidity moves between two pools of a mint pair
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolRebalanceEvent {
+    pub from_pool_id: Pubkey,
+    pub to_pool_id: Pubkey,
+    /// lp amount burned in the pool the liquidity is withdrawn from
+    pub from_lp_amount: u64,
+    /// lp amount minted in the pool the liquidity is deposited to
+    pub to_lp_amount: u64,
+    /// amount moved between the vaults, including the transfer fee
+    pub token_0_amount: u64,
+    /// amount moved between the vaults, including the transfer fee
+    pub token_1_amount: u64,
+    /// amount left over by the ratio of the pools, sent to the owner
+    pub token_0_refund: u64,
+    pub token_1_refund: u64,
+}
+
+/// Emitted when a swap is requested in a pool running the order-flow auction
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SwapRequestedEvent {
+    pub pool_id: Pubkey,
+    pub request: Pubkey,
+    pub owner: Pubkey,
+    pub filler: Pubkey,
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+    /// last slot only the filler can execute the request
+    pub auction_end_slot: u64,
+}
+
+/// Emitted when a swap request is filled by the filler or executed against its pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SwapRequestExecutedEvent {
+    pub pool_id: Pubkey,
+    pub request: Pubkey,
+    pub executor: Pubkey,
+    /// whether the filler filled the request instead of the pool
+    pub filled: bool,
+    pub amount_in: u64,
+    /// Output tokens received by the request owner
+    pub amount_out: u64,
+    /// Output received over the pool output, 0 when executed against the pool
+    pub price_improvement: u64,
+}
+
+/// Emitted when the custom authority of a pool is transferred or renounced
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CustomAuthorityChangedEvent {
+    pub pool_id: Pubkey,
+    pub old_authority: Pubkey,
+    /// the default pubkey when the custom authority is renounced
+    pub new_authority: Pubkey,
+    /// the admin signed instead of the custom authority
+    pub by_admin: bool,
+}
+
+/// Emitted when the mints of the mint policy of an amm config are listed or unlisted
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct MintPolicyUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub mint_policy: Pubkey,
+    /// see `MintPolicyMode`
+    pub mode: u8,
+    pub mints: Vec<Pubkey>,
+    /// whether the mints were listed or unlisted
+    pub listed: bool,
+}
+
+/// Emitted when traders are listed in, updated in or unlisted from the fee exemption list
+/// of an amm config
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FeeExemptionsUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub fee_exemption_list: Pubkey,
+    pub traders: Vec<Pubkey>,
+    /// the share of the protocol fee waived, 0 unlists the traders
+    pub exemption_bps: u16,
+}
+
+/// Emitted when the admin or the pause guardian of an amm config pauses its pools
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolsPausedEvent {
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub pools: Vec<Pubkey>,
+    /// whether the creation of new pools was disabled too
+    pub disable_create_pool: bool,
+}
+
+/// Emitted with the `SwapEvent` of a swap exempted from a share of the protocol fee, so the
+/// exempt volume can be audited
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FeeExemptSwapEvent {
+    pub pool_id: Pubkey,
+    pub trader: Pubkey,
+    pub exemption_bps: u16,
+    /// calculate result without transfer fee
+    pub input_amount: u64,
+    /// calculate result without transfer fee
+    pub output_amount: u64,
+    /// trade fee charged after the exemption
+    pub trade_fee: u64,
+    /// protocol fee charged after the exemption
+    pub protocol_fee: u64,
+}
+
+/// Emitted when the liquidity of a legacy Raydium AMM v4 pool is migrated into a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LegacyLiquidityMigratedEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    /// the legacy pool tokens burned
+    pub legacy_lp_amount: u64,
+    /// the tokens withdrawn from the legacy pool, including the tokens left to the owner
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    /// the pool tokens minted
+    pub lp_amount: u64,
+}
+
+/// Emitted when a withdrawal of locked lp tokens leaves the early exit fee in the vaults
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EarlyExitFeeEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    /// the withdrawn lp tokens still locked
+    pub early_exit_lp_amount: u64,
+    pub token_0_fee: u64,
+    pub token_1_fee: u64,
+}
+
+/// Emitted when the liquidity of a multi token pool is deposited or withdrawn
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct MultiPoolLpChangeEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub lp_amount_before: u64,
+    /// lp tokens minted or burned
+    pub lp_amount: u64,
+    /// the tokens received or sent by the vaults, in the order of the pool tokens
+    pub token_amounts: Vec<u64>,
+    pub transfer_fees: Vec<u64>,
+    // 0: deposit, 1: withdraw
+    pub change_type: u8,
+}
+
+/// Emitted when a multi token pool is swapped
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct MultiPoolSwapEvent {
+    pub pool_id: Pubkey,
+    pub input_index: u8,
+    pub output_index: u8,
+    /// the input tokens received by the vault, without transfer fee
+    pub input_amount: u64,
+    /// the output tokens sent by the vault, including transfer fee
+    pub output_amount: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+}
+
+/// Emitted when a share of the collected fund fees of a pool is sent to the insurance fund
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct InsuranceFundDepositEvent {
+    pub amm_config: Pubkey,
+    pub pool_id: Pubkey,
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+}
+
+/// Emitted when the insurance fund injects tokens into a vault of a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ShortfallCoveredEvent {
+    pub amm_config: Pubkey,
+    pub pool_id: Pubkey,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    /// the tokens sent by the insurance fund, including transfer fee
+    pub amount: u64,
+    /// the vault balance before and after the tokens are received
+    pub vault_amount_before: u64,
+    pub vault_amount_after: u64,
+}
+
+/// Emitted when a deposit receipt is compressed into a receipt tree, the leaf is proven
+/// back from the data of the event
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DepositReceiptCompressedEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub receipt_tree: Pubkey,
+    pub leaf_index: u64,
+    pub lp_amount: u64,
+    pub last_deposit_time: u64,
+}
+
+/// Emitted when a compressed deposit receipt is claimed, its leaf is emptied
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DepositReceiptDecompressedEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub receipt_tree: Pubkey,
+    pub leaf_index: u64,
+    pub lp_amount: u64,
+}
+
+/// Emitted for each operation applied by `batch_admin`, in order
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolAdminOpEvent {
+    pub pool_id: Pubkey,
+    /// index of the operation in the batch
+    pub index: u16,
+    pub op: PoolAdminOp,
+}
+
+#[cfg(test)]
+mod events_test {
+    use super::*;
+
+    #[test]
+    fn pool_reserves_sqrt_price_test() {
+        assert_eq!(PoolReserves::new(100, 400, 200).sqrt_price_x64, 2u128 << 64);
+        assert_eq!(PoolReserves::new(400, 100, 200).sqrt_price_x64, 1u128 << 63);
+        assert_eq!(PoolReserves::new(0, 100, 0).sqrt_price_x64, 0);
+    }
+
+    #[test]
+    fn pool_reserves_after_swap_test() {
+        let result = SwapResult {
+            new_swap_source_amount: 1_100,
+            new_swap_destination_amount: 910,
+            source_amount_swapped: 100,
+            destination_amount_swapped: 90,
+            trade_fee: 10,
+            protocol_fee: 2,
+            fund_fee: 1,
+            fee_on_output: false,
+        };
+        let reserves = PoolReserves::new(1_000, 1_000, 1_000);
+        let after = reserves.after_swap(TradeDirection::OneForZero, &result, 3);
+        assert_eq!(after.token_0_vault, 910);
+        assert_eq!(after.token_1_vault, 1_094);
+        assert_eq!(after.lp_supply, 1_000);
+
+        // the fees charged on the output token leave the output vault
+        let result = SwapResult {
+            fee_on_output: true,
+            ..result
+        };
+        let after = reserves.after_swap(TradeDirection::OneForZero, &result, 3);
+        assert_eq!(after.token_0_vault, 904);
+        assert_eq!(after.token_1_vault, 1_100);
+    }
 }
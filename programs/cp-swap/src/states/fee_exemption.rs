@@ -0,0 +1,172 @@
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::curve::PRICE_IMPACT_BPS_DENOMINATOR;
+use anchor_lang::prelude::*;
+
+pub const FEE_EXEMPTION_LIST_SEED: &str = "fee_exemption_list";
+/// Maximum number of traders listed in a fee exemption list
+pub const MAX_FEE_EXEMPTIONS: usize = 16;
+
+/// A trader exempted from a share of the protocol fee of its swaps
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct FeeExemption {
+    pub trader: Pubkey,
+    /// The share of the protocol fee in basis points the trader does not pay, 10000 waives
+    /// the whole protocol fee
+    pub exemption_bps: u16,
+}
+
+/// The traders of an amm config whose swaps pay a reduced or no protocol fee, such as the
+/// protocol rebalancer or market makers under agreement
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeExemptionList {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The amm config the exemptions apply to
+    pub amm_config: Pubkey,
+    /// Number of listed traders
+    pub exemption_count: u8,
+    /// The listed traders, only the first `exemption_count` are set
+    pub exemptions: [FeeExemption; MAX_FEE_EXEMPTIONS],
+}
+
+impl FeeExemptionList {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + (32 + 2) * MAX_FEE_EXEMPTIONS;
+
+    pub fn listed_exemptions(&self) -> &[FeeExemption] {
+        &self.exemptions[..usize::from(self.exemption_count)]
+    }
+
+    /// The share of the protocol fee in basis points `trader` does not pay, 0 when it is
+    /// not listed
+    pub fn exemption_bps(&self, trader: &Pubkey) -> u16 {
+        self.listed_exemptions()
+            .iter()
+            .find(|exemption| exemption.trader == *trader)
+            .map_or(0, |exemption| exemption.exemption_bps)
+    }
+
+    /// List `trader` or update its exemption, an exemption of 0 unlists it and the last
+    /// listed trader takes its place. Returns false when the list is full
+    pub fn set_exemption(&mut self, trader: Pubkey, exemption_bps: u16) -> bool {
+        let index = self
+            .listed_exemptions()
+            .iter()
+            .position(|exemption| exemption.trader == trader);
+        match (index, exemption_bps) {
+            (Some(index), 0) => {
+                let last = usize::from(self.exemption_count) - 1;
+                self.exemptions[index] = self.exemptions[last];
+                self.exemptions[last] = FeeExemption::default();
+                self.exemption_count -= 1;
+            }
+            (Some(index), _) => self.exemptions[index].exemption_bps = exemption_bps,
+            (None, 0) => {}
+            (None, _) => {
+                if usize::from(self.exemption_count) >= MAX_FEE_EXEMPTIONS {
+                    return false;
+                }
+                self.exemptions[usize::from(self.exemption_count)] = FeeExemption {
+                    trader,
+                    exemption_bps,
+                };
+                self.exemption_count += 1;
+            }
+        }
+        true
+    }
+
+    /// Get the trade, protocol and fund fee rates of a swap exempted from `exemption_bps`
+    /// of the protocol fee. The trade fee shrinks by the waived protocol fee and the split
+    /// is rescaled so the fund fee and the liquidity provider fee stay the same
+    pub fn exempt_fee_rates(
+        exemption_bps: u16,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+    ) -> (u64, u64, u64) {
+        if exemption_bps == 0 {
+            return (trade_fee_rate, protocol_fee_rate, fund_fee_rate);
+        }
+        let waived_rate = u128::from(protocol_fee_rate) * u128::from(exemption_bps)
+            / u128::from(PRICE_IMPACT_BPS_DENOMINATOR);
+        let kept_rate = u128::from(FEE_RATE_DENOMINATOR_VALUE) - waived_rate;
+        if kept_rate == 0 {
+            return (0, 0, 0);
+        }
+        let rescale = |rate: u128| -> u64 {
+            u64::try_from(rate * u128::from(FEE_RATE_DENOMINATOR_VALUE) / kept_rate).unwrap()
+        };
+        (
+            u64::try_from(
+                u128::from(trade_fee_rate) * kept_rate / u128::from(FEE_RATE_DENOMINATOR_VALUE),
+            )
+            .unwrap(),
+            rescale(u128::from(protocol_fee_rate) - waived_rate),
+            rescale(u128::from(fund_fee_rate)),
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod fee_exemption_test {
+    use super::*;
+
+    #[test]
+    fn fee_exemption_list_size_test() {
+        let fee_exemption_list = FeeExemptionList::default();
+        assert_eq!(
+            fee_exemption_list.try_to_vec().unwrap().len(),
+            FeeExemptionList::LEN - 8
+        )
+    }
+
+    #[test]
+    fn set_exemption_lists_and_unlists() {
+        let mut fee_exemption_list = FeeExemptionList::default();
+        let rebalancer = Pubkey::new_unique();
+        let market_maker = Pubkey::new_unique();
+        assert!(fee_exemption_list.set_exemption(rebalancer, 10000));
+        assert!(fee_exemption_list.set_exemption(market_maker, 5000));
+        assert_eq!(fee_exemption_list.exemption_bps(&rebalancer), 10000);
+        assert_eq!(fee_exemption_list.exemption_bps(&market_maker), 5000);
+
+        assert!(fee_exemption_list.set_exemption(market_maker, 2500));
+        assert_eq!(fee_exemption_list.exemption_bps(&market_maker), 2500);
+        assert!(fee_exemption_list.set_exemption(rebalancer, 0));
+        assert_eq!(fee_exemption_list.exemption_bps(&rebalancer), 0);
+        assert_eq!(fee_exemption_list.exemption_count, 1);
+        assert_eq!(fee_exemption_list.exemptions[0].trader, market_maker);
+
+        for _ in 1..MAX_FEE_EXEMPTIONS {
+            assert!(fee_exemption_list.set_exemption(Pubkey::new_unique(), 100));
+        }
+        assert!(!fee_exemption_list.set_exemption(Pubkey::new_unique(), 100));
+        assert!(fee_exemption_list.set_exemption(Pubkey::new_unique(), 0));
+    }
+
+    #[test]
+    fn exempt_fee_rates_keep_the_fund_and_lp_fees() {
+        // 0.25% trade fee, 12% protocol and 4% fund shares
+        let (trade_fee_rate, protocol_fee_rate, fund_fee_rate) =
+            FeeExemptionList::exempt_fee_rates(10000, 2500, 120000, 40000);
+        assert_eq!(trade_fee_rate, 2200);
+        assert_eq!(protocol_fee_rate, 0);
+        assert_eq!(fund_fee_rate, 45454);
+
+        let (trade_fee_rate, protocol_fee_rate, fund_fee_rate) =
+            FeeExemptionList::exempt_fee_rates(5000, 2500, 120000, 40000);
+        assert_eq!(trade_fee_rate, 2350);
+        assert_eq!(protocol_fee_rate, 63829);
+        assert_eq!(fund_fee_rate, 42553);
+
+        assert_eq!(
+            FeeExemptionList::exempt_fee_rates(0, 2500, 120000, 40000),
+            (2500, 120000, 40000)
+        );
+        assert_eq!(
+            FeeExemptionList::exempt_fee_rates(10000, 2500, 1000000, 0),
+            (0, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+pub const INSURANCE_FUND_SEED: &str = "insurance_fund";
+
+/// The insurance fund of an amm config, the authority of the token accounts receiving a
+/// share of the fund fees of its pools. The admin injects its tokens into the vaults of a
+/// pool of the config to cover a shortfall after an exploit or an accounting bug
+#[account]
+#[derive(Default, Debug)]
+pub struct InsuranceFund {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The amm config whose pools the fund covers
+    pub amm_config: Pubkey,
+    /// Number of shortfalls covered by the fund
+    pub shortfall_count: u64,
+}
+
+impl InsuranceFund {
+    pub const LEN: usize = 8 + 1 + 32 + 8;
+}
+
+#[cfg(test)]
+pub mod insurance_fund_test {
+    use super::*;
+
+    #[test]
+    fn insurance_fund_size_test() {
+        assert_eq!(
+            InsuranceFund::default().try_to_vec().unwrap().len(),
+            InsuranceFund::LEN - 8
+        )
+    }
+}
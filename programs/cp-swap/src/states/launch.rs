@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+pub const LAUNCH_WHITELIST_SEED: &str = "launch_whitelist";
+
+/// Trades allowed in the launch phase of a pool, before `finalize_launch`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LaunchMode {
+    /// No launch phase, every trade is allowed
+    None,
+    /// Only swaps buying token_0 with token_1
+    BuyToken0,
+    /// Only swaps buying token_1 with token_0
+    BuyToken1,
+    /// Only swaps of whitelisted traders
+    Whitelist,
+}
+
+impl LaunchMode {
+    pub fn from_u8(launch_mode: u8) -> Option<LaunchMode> {
+        match launch_mode {
+            0 => Some(LaunchMode::None),
+            1 => Some(LaunchMode::BuyToken0),
+            2 => Some(LaunchMode::BuyToken1),
+            3 => Some(LaunchMode::Whitelist),
+            _ => None,
+        }
+    }
+}
+
+/// Allows a trader to swap in a pool during its whitelisted launch phase
+#[account]
+#[derive(Default, Debug)]
+pub struct LaunchWhitelist {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the trader is whitelisted in
+    pub pool_id: Pubkey,
+    /// The whitelisted trader
+    pub trader: Pubkey,
+}
+
+impl LaunchWhitelist {
+    pub const LEN: usize = 8 + 1 + 32 * 2;
+}
@@ -0,0 +1,79 @@
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+pub const LIMIT_ORDER_SEED: &str = "limit_order";
+pub const LIMIT_ORDER_VAULT_SEED: &str = "limit_order_vault";
+/// Denominator of keeper tips in basis points
+pub const KEEPER_TIP_BPS_DENOMINATOR: u64 = 10_000;
+
+/// Escrows input tokens of a limit order, filled against its pool by keepers
+/// once the pool price reaches the limit price
+#[account]
+#[derive(Default, Debug)]
+pub struct OrderState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the order is filled against
+    pub pool_id: Pubkey,
+    /// Owner of the order, the only address allowed to cancel it
+    pub owner: Pubkey,
+    /// Token account escrowing the remaining input tokens
+    pub order_vault: Pubkey,
+    /// The mint of the tokens sold
+    pub input_mint: Pubkey,
+    /// The mint of the tokens bought
+    pub output_mint: Pubkey,
+    /// The owner token account receiving the bought tokens
+    pub output_token_account: Pubkey,
+    /// Minimum amount of output tokens per input token received by the owner, Q64.64
+    pub limit_price_x64: u128,
+    /// Share of the output of each fill paid to the keeper, in basis points
+    pub keeper_tip_bps: u16,
+    /// Amount of input tokens escrowed when the order was opened
+    pub amount_in: u64,
+    /// Amount of input tokens not filled yet
+    pub amount_in_remaining: u64,
+    /// Amount of output tokens received by the owner
+    pub amount_out_filled: u64,
+}
+
+impl OrderState {
+    pub const LEN: usize = 8 + 1 + 32 * 6 + 16 + 2 + 8 * 3;
+
+    /// Get the keeper share of the output of a fill
+    pub fn keeper_tip(&self, amount_out: u64) -> u64 {
+        let keeper_tip = u128::from(amount_out) * u128::from(self.keeper_tip_bps)
+            / u128::from(KEEPER_TIP_BPS_DENOMINATOR);
+        u64::try_from(keeper_tip).unwrap()
+    }
+
+    /// Whether the owner receiving `amount_out` for `amount_in` meets the limit price
+    pub fn is_limit_price_reached(&self, amount_in: u64, amount_out: u64) -> bool {
+        U256::from(amount_out) << 64 >= U256::from(amount_in) * U256::from(self.limit_price_x64)
+    }
+}
+
+#[cfg(test)]
+pub mod limit_order_test {
+    use super::*;
+
+    #[test]
+    fn order_state_size_test() {
+        let order = OrderState::default();
+        assert_eq!(order.try_to_vec().unwrap().len(), OrderState::LEN - 8)
+    }
+
+    #[test]
+    fn limit_price_and_keeper_tip_test() {
+        let order = OrderState {
+            // at least 1.5 output tokens per input token
+            limit_price_x64: 3u128 << 63,
+            keeper_tip_bps: 50,
+            ..Default::default()
+        };
+        assert!(order.is_limit_price_reached(1_000, 1_500));
+        assert!(!order.is_limit_price_reached(1_000, 1_499));
+        assert_eq!(order.keeper_tip(10_000), 50);
+        assert_eq!(order.keeper_tip(199), 0);
+    }
+}
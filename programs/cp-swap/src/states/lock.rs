@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+
+pub const LIQUIDITY_LOCK_SEED: &str = "liquidity_lock";
+pub const LIQUIDITY_LOCK_VAULT_SEED: &str = "liquidity_lock_vault";
+
+/// Escrows lp tokens of a pool, released on a cliff and linear vesting schedule
+#[account]
+#[derive(Default, Debug)]
+pub struct LiquidityLock {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the lp tokens belong to
+    pub pool_id: Pubkey,
+    /// Address allowed to claim the unlocked lp tokens
+    pub owner: Pubkey,
+    /// Token account holding the locked lp tokens
+    pub lp_vault: Pubkey,
+    /// Amount of lp tokens locked
+    pub locked_amount: u64,
+    /// Amount of lp tokens already claimed
+    pub claimed_amount: u64,
+    /// The timestamp the vesting starts from
+    pub start_time: u64,
+    /// Nothing can be claimed before this timestamp
+    pub cliff_time: u64,
+    /// Everything can be claimed after this timestamp
+    pub end_time: u64,
+}
+
+impl LiquidityLock {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 5;
+
+    /// Get the amount of lp tokens vested at the timestamp, it vests linearly
+    /// from `start_time` to `end_time` and is released from `cliff_time`
+    pub fn unlocked_amount(&self, block_timestamp: u64) -> u64 {
+        if block_timestamp < self.cliff_time {
+            return 0;
+        }
+        if block_timestamp >= self.end_time {
+            return self.locked_amount;
+        }
+        let unlocked = u128::from(self.locked_amount)
+            .checked_mul(u128::from(block_timestamp - self.start_time))
+            .unwrap()
+            .checked_div(u128::from(self.end_time - self.start_time))
+            .unwrap();
+        u64::try_from(unlocked).unwrap()
+    }
+
+    /// Get the amount of lp tokens that can be claimed at the timestamp
+    pub fn claimable_amount(&self, block_timestamp: u64) -> u64 {
+        self.unlocked_amount(block_timestamp)
+            .saturating_sub(self.claimed_amount)
+    }
+}
+
+#[cfg(test)]
+pub mod lock_test {
+    use super::*;
+
+    fn liquidity_lock() -> LiquidityLock {
+        LiquidityLock {
+            locked_amount: 1_000_000,
+            start_time: 1_000,
+            cliff_time: 2_000,
+            end_time: 11_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn liquidity_lock_size_test() {
+        let lock = LiquidityLock::default();
+        assert_eq!(lock.try_to_vec().unwrap().len(), LiquidityLock::LEN - 8)
+    }
+
+    #[test]
+    fn unlocked_amount_follows_cliff_and_linear_vesting() {
+        let lock = liquidity_lock();
+        assert_eq!(lock.unlocked_amount(1_000), 0);
+        assert_eq!(lock.unlocked_amount(1_999), 0);
+        // the cliff releases what vested since the start
+        assert_eq!(lock.unlocked_amount(2_000), 100_000);
+        assert_eq!(lock.unlocked_amount(6_000), 500_000);
+        assert_eq!(lock.unlocked_amount(11_000), 1_000_000);
+        assert_eq!(lock.unlocked_amount(u64::MAX), 1_000_000);
+    }
+
+    #[test]
+    fn claimable_amount_excludes_claimed() {
+        let mut lock = liquidity_lock();
+        lock.claimed_amount = lock.claimable_amount(6_000);
+        assert_eq!(lock.claimable_amount(6_000), 0);
+        assert_eq!(lock.claimable_amount(11_000), 500_000);
+    }
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+pub const POOL_METADATA_SEED: &str = "pool_metadata";
+/// Maximum length in bytes of the name of a pool
+pub const MAX_POOL_NAME_LEN: usize = 32;
+/// Maximum length in bytes of the metadata URI of a pool
+pub const MAX_POOL_URI_LEN: usize = 200;
+/// Maximum number of tags of a pool
+pub const MAX_POOL_TAGS: usize = 4;
+/// Maximum length in bytes of a tag of a pool
+pub const MAX_POOL_TAG_LEN: usize = 16;
+
+/// Display data of a pool for frontends, the URI points to the off-chain metadata
+/// with the logo and social links
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolMetadata {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool described
+    pub pool_id: Pubkey,
+    /// Display name of the pool
+    pub name: String,
+    /// URI of the off-chain metadata
+    pub uri: String,
+    /// Short labels of the pool, such as its category
+    pub tags: Vec<String>,
+}
+
+impl PoolMetadata {
+    pub const LEN: usize = 8
+        + 1
+        + 32
+        + (4 + MAX_POOL_NAME_LEN)
+        + (4 + MAX_POOL_URI_LEN)
+        + 4
+        + (4 + MAX_POOL_TAG_LEN) * MAX_POOL_TAGS;
+
+    /// Set the display data, returns false when it exceeds the maximum lengths
+    pub fn set(&mut self, name: String, uri: String, tags: Vec<String>) -> bool {
+        if name.len() > MAX_POOL_NAME_LEN
+            || uri.len() > MAX_POOL_URI_LEN
+            || tags.len() > MAX_POOL_TAGS
+            || tags.iter().any(|tag| tag.len() > MAX_POOL_TAG_LEN)
+        {
+            return false;
+        }
+        self.name = name;
+        self.uri = uri;
+        self.tags = tags;
+        true
+    }
+}
+
+#[cfg(test)]
+pub mod metadata_test {
+    use super::*;
+
+    #[test]
+    fn pool_metadata_size_test() {
+        let mut metadata = PoolMetadata::default();
+        assert!(metadata.set(
+            "n".repeat(MAX_POOL_NAME_LEN),
+            "u".repeat(MAX_POOL_URI_LEN),
+            vec!["t".repeat(MAX_POOL_TAG_LEN); MAX_POOL_TAGS],
+        ));
+        assert_eq!(metadata.try_to_vec().unwrap().len(), PoolMetadata::LEN - 8);
+
+        assert!(!metadata.set("n".repeat(MAX_POOL_NAME_LEN + 1), String::new(), Vec::new()));
+        assert!(!metadata.set(
+            String::new(),
+            String::new(),
+            vec![String::new(); MAX_POOL_TAGS + 1]
+        ));
+        assert_eq!(metadata.name.len(), MAX_POOL_NAME_LEN);
+    }
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+
+pub const MINT_POLICY_SEED: &str = "mint_policy";
+/// Maximum number of mints listed in a mint policy
+pub const MAX_POLICY_MINTS: usize = 32;
+
+/// Whether the listed mints are banned from or the only ones allowed in new pools
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MintPolicyMode {
+    /// The listed mints are banned
+    Denylist,
+    /// Only the listed mints are allowed
+    Allowlist,
+}
+
+impl MintPolicyMode {
+    pub fn from_u8(mode: u8) -> Option<MintPolicyMode> {
+        match mode {
+            0 => Some(MintPolicyMode::Denylist),
+            1 => Some(MintPolicyMode::Allowlist),
+            _ => None,
+        }
+    }
+}
+
+/// The mints new pools of an amm config can or can not be created with
+#[account]
+#[derive(Default, Debug)]
+pub struct MintPolicy {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The amm config the policy applies to
+    pub amm_config: Pubkey,
+    /// Whether the listed mints are banned or the only ones allowed, see `MintPolicyMode`
+    pub mode: u8,
+    /// Number of listed mints
+    pub mint_count: u8,
+    /// The listed mints, only the first `mint_count` are set
+    pub mints: [Pubkey; MAX_POLICY_MINTS],
+}
+
+impl MintPolicy {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 1 + 32 * MAX_POLICY_MINTS;
+
+    pub fn listed_mints(&self) -> &[Pubkey] {
+        &self.mints[..usize::from(self.mint_count)]
+    }
+
+    pub fn is_listed(&self, mint: &Pubkey) -> bool {
+        self.listed_mints().contains(mint)
+    }
+
+    /// Whether a new pool can hold `mint`
+    pub fn is_mint_allowed(&self, mint: &Pubkey) -> bool {
+        if self.mode == MintPolicyMode::Allowlist as u8 {
+            self.is_listed(mint)
+        } else {
+            !self.is_listed(mint)
+        }
+    }
+
+    /// Append a mint, returns false when it is already listed or the policy is full
+    pub fn add_mint(&mut self, mint: Pubkey) -> bool {
+        if self.is_listed(&mint) || usize::from(self.mint_count) >= MAX_POLICY_MINTS {
+            return false;
+        }
+        self.mints[usize::from(self.mint_count)] = mint;
+        self.mint_count += 1;
+        true
+    }
+
+    /// Remove a mint, the last listed mint takes its place. Returns false when it is not listed
+    pub fn remove_mint(&mut self, mint: &Pubkey) -> bool {
+        let Some(index) = self.listed_mints().iter().position(|listed| listed == mint) else {
+            return false;
+        };
+        let last = usize::from(self.mint_count) - 1;
+        self.mints[index] = self.mints[last];
+        self.mints[last] = Pubkey::default();
+        self.mint_count -= 1;
+        true
+    }
+}
+
+#[cfg(test)]
+pub mod mint_policy_test {
+    use super::*;
+
+    #[test]
+    fn mint_policy_size_test() {
+        let mint_policy = MintPolicy::default();
+        assert_eq!(mint_policy.try_to_vec().unwrap().len(), MintPolicy::LEN - 8)
+    }
+
+    #[test]
+    fn is_mint_allowed_follows_mode() {
+        let mut mint_policy = MintPolicy::default();
+        let banned_mint = Pubkey::new_unique();
+        let other_mint = Pubkey::new_unique();
+        assert!(mint_policy.add_mint(banned_mint));
+        assert!(!mint_policy.add_mint(banned_mint));
+        assert!(!mint_policy.is_mint_allowed(&banned_mint));
+        assert!(mint_policy.is_mint_allowed(&other_mint));
+
+        mint_policy.mode = MintPolicyMode::Allowlist as u8;
+        assert!(mint_policy.is_mint_allowed(&banned_mint));
+        assert!(!mint_policy.is_mint_allowed(&other_mint));
+
+        assert!(mint_policy.remove_mint(&banned_mint));
+        assert!(!mint_policy.remove_mint(&banned_mint));
+        assert!(!mint_policy.is_mint_allowed(&banned_mint));
+        assert_eq!(mint_policy.mints[0], Pubkey::default());
+    }
+
+    #[test]
+    fn add_mint_rejects_overflow() {
+        let mut mint_policy = MintPolicy::default();
+        for _ in 0..MAX_POLICY_MINTS {
+            assert!(mint_policy.add_mint(Pubkey::new_unique()));
+        }
+        assert_eq!(mint_policy.listed_mints().len(), MAX_POLICY_MINTS);
+        assert!(!mint_policy.add_mint(Pubkey::new_unique()));
+    }
+}
@@ -9,3 +9,69 @@ pub use events::*;
 
 pub mod oracle;
 pub use oracle::*;
+
+pub mod admin;
+pub use admin::*;
+
+pub mod registry;
+pub use registry::*;
+
+pub mod lock;
+pub use lock::*;
+
+pub mod position;
+pub use position::*;
+
+pub mod launch;
+pub use launch::*;
+
+pub mod swap_order;
+pub use swap_order::*;
+
+pub mod limit_order;
+pub use limit_order::*;
+
+pub mod auction;
+pub use auction::*;
+
+pub mod stats;
+pub use stats::*;
+
+pub mod metadata;
+pub use metadata::*;
+
+pub mod session;
+pub use session::*;
+
+pub mod mint_policy;
+pub use mint_policy::*;
+
+pub mod reward;
+pub use reward::*;
+
+pub mod pool_snapshot;
+pub use pool_snapshot::*;
+
+pub mod trader_registry;
+pub use trader_registry::*;
+
+pub mod fee_exemption;
+pub use fee_exemption::*;
+
+pub mod dca_order;
+pub use dca_order::*;
+
+pub mod deposit_receipt;
+pub use deposit_receipt::*;
+
+pub mod multi_pool;
+pub use multi_pool::*;
+
+pub mod token_badge;
+pub use token_badge::*;
+
+pub mod insurance_fund;
+pub use insurance_fund::*;
+
+pub mod receipt_tree;
+pub use receipt_tree::*;
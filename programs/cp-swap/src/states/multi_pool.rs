@@ -0,0 +1,170 @@
+use crate::curve::RoundDirection;
+use crate::states::PoolStatusBitIndex;
+use anchor_lang::prelude::*;
+use std::ops::BitAnd;
+
+/// Maximum number of tokens of a multi token pool
+pub const MAX_MULTI_POOL_TOKENS: usize = 4;
+/// Minimum number of tokens of a multi token pool, 2 token stable pools are plain pools
+pub const MIN_MULTI_POOL_TOKENS: usize = 3;
+
+/// A stable pool of 3 to 4 tokens trading on the StableSwap invariant of all the balances,
+/// such as a USDC/USDT/PYUSD tri-pool. Only the first `token_count` tokens are set
+#[account]
+#[derive(Default, Debug)]
+pub struct MultiPoolState {
+    /// Which config the pool belongs to, its trade fee and fee shares apply
+    pub amm_config: Pubkey,
+    /// The creator of the pool
+    pub pool_creator: Pubkey,
+    /// The lp mint of the pool, counted in the decimals of the most precise token
+    pub lp_mint: Pubkey,
+    /// The mints of the tokens, sorted by key
+    pub token_mints: [Pubkey; MAX_MULTI_POOL_TOKENS],
+    /// The vaults holding the tokens
+    pub token_vaults: [Pubkey; MAX_MULTI_POOL_TOKENS],
+    /// The decimals of the token mints, the balances are scaled to the most precise token
+    /// before entering the invariant
+    pub mint_decimals: [u8; MAX_MULTI_POOL_TOKENS],
+    /// The number of tokens of the pool
+    pub token_count: u8,
+    pub auth_bump: u8,
+    pub lp_mint_decimals: u8,
+    /// Bitwise representation of the disabled operations, as `PoolState::status`
+    pub status: u8,
+    /// The amplification coefficient of the invariant
+    pub amp: u64,
+    /// True circulating supply without burns and lock ups
+    pub lp_supply: u64,
+    /// The protocol fees accrued in each token
+    pub protocol_fees: [u64; MAX_MULTI_POOL_TOKENS],
+    /// The fund fees accrued in each token
+    pub fund_fees: [u64; MAX_MULTI_POOL_TOKENS],
+    /// Swaps are allowed from the open time
+    pub open_time: u64,
+    /// Reserved for future fields
+    pub padding: [u64; 8],
+}
+
+impl MultiPoolState {
+    pub const LEN: usize = 8
+        + 32 * 3
+        + 32 * MAX_MULTI_POOL_TOKENS * 2
+        + MAX_MULTI_POOL_TOKENS
+        + 4
+        + 8 * 2
+        + 8 * MAX_MULTI_POOL_TOKENS * 2
+        + 8
+        + 8 * 8;
+
+    pub fn token_count(&self) -> usize {
+        usize::from(self.token_count)
+    }
+
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+        let status = u8::from(1) << (bit as u8);
+        self.status.bitand(status) == 0
+    }
+
+    /// The multiplier scaling the amounts of each token to the decimals of the most
+    /// precise token
+    pub fn amount_multipliers(&self) -> Vec<u128> {
+        let decimals = &self.mint_decimals[..self.token_count()];
+        let max_decimals = decimals.iter().copied().max().unwrap_or_default();
+        decimals
+            .iter()
+            .map(|d| 10u128.pow(u32::from(max_decimals - d)))
+            .collect()
+    }
+
+    /// Get the pool amounts of the vault balances, without the fees
+    pub fn vault_amounts_without_fee(&self, vault_amounts: &[u64]) -> Vec<u64> {
+        vault_amounts
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| {
+                amount
+                    .checked_sub(self.protocol_fees[i] + self.fund_fees[i])
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    /// Get the pool amounts scaled to the decimals of the most precise token, the balances
+    /// of the invariant
+    pub fn scaled_amounts(&self, amounts: &[u64]) -> Vec<u128> {
+        amounts
+            .iter()
+            .zip(self.amount_multipliers())
+            .map(|(amount, multiplier)| u128::from(*amount) * multiplier)
+            .collect()
+    }
+
+    /// Get the tokens of `lp_token_amount` lp tokens out of a pool balance of
+    /// `token_amount`, rounded up for deposits and down for withdrawals
+    pub fn lp_tokens_to_token_amount(
+        &self,
+        lp_token_amount: u64,
+        token_amount: u64,
+        round_direction: RoundDirection,
+    ) -> Option<u64> {
+        let numerator = u128::from(token_amount).checked_mul(u128::from(lp_token_amount))?;
+        let lp_supply = u128::from(self.lp_supply);
+        let amount = match round_direction {
+            RoundDirection::Floor => numerator.checked_div(lp_supply)?,
+            RoundDirection::Ceiling => numerator
+                .checked_add(lp_supply - 1)?
+                .checked_div(lp_supply)?,
+        };
+        u64::try_from(amount).ok()
+    }
+}
+
+#[cfg(test)]
+pub mod multi_pool_test {
+    use super::*;
+
+    #[test]
+    fn multi_pool_state_size_test() {
+        let multi_pool_state = MultiPoolState::default();
+        assert_eq!(
+            multi_pool_state.try_to_vec().unwrap().len(),
+            MultiPoolState::LEN - 8
+        )
+    }
+
+    #[test]
+    fn scaled_amounts_use_the_most_precise_decimals() {
+        let mut multi_pool_state = MultiPoolState::default();
+        multi_pool_state.token_count = 3;
+        multi_pool_state.mint_decimals = [6, 9, 6, 0];
+        multi_pool_state.protocol_fees = [10, 0, 0, 0];
+        multi_pool_state.fund_fees = [0, 0, 5, 0];
+        assert_eq!(multi_pool_state.amount_multipliers(), vec![1_000, 1, 1_000]);
+        let amounts = multi_pool_state.vault_amounts_without_fee(&[1_010, 1_000_000, 1_005]);
+        assert_eq!(amounts, vec![1_000, 1_000_000, 1_000]);
+        assert_eq!(
+            multi_pool_state.scaled_amounts(&amounts),
+            vec![1_000_000, 1_000_000, 1_000_000]
+        );
+    }
+
+    #[test]
+    fn lp_tokens_to_token_amount_rounds_in_favor_of_the_pool() {
+        let mut multi_pool_state = MultiPoolState::default();
+        multi_pool_state.lp_supply = 3;
+        assert_eq!(
+            multi_pool_state.lp_tokens_to_token_amount(1, 100, RoundDirection::Floor),
+            Some(33)
+        );
+        assert_eq!(
+            multi_pool_state.lp_tokens_to_token_amount(1, 100, RoundDirection::Ceiling),
+            Some(34)
+        );
+        multi_pool_state.lp_supply = 0;
+        assert_eq!(
+            multi_pool_state.lp_tokens_to_token_amount(1, 100, RoundDirection::Floor),
+            None
+        );
+    }
+}
@@ -1,13 +1,20 @@
 /// Oracle provides price data useful for a wide variety of system designs
 ///
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::curve::weighted::{exp2_ceil, log2_x64};
 use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
 #[cfg(test)]
 use std::time::{SystemTime, UNIX_EPOCH};
 /// Seed to derive account address and signature
 pub const OBSERVATION_SEED: &str = "observation";
 // Number of ObservationState element
 pub const OBSERVATION_NUM: usize = 100;
+// Max number of observations, including the appended ones, of an observation account
+pub const OBSERVATION_CARDINALITY_MAX: usize = 1000;
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u64 = 15;
+// Duration of the recent observations used to measure the volatility
+pub const VOLATILITY_WINDOW_DEFAULT: u64 = 300;
 
 /// The element of observations in ObservationState
 #[zero_copy(unsafe)]
@@ -25,6 +32,40 @@ impl Observation {
     pub const LEN: usize = 8 + 16 + 16;
 }
 
+// Observations appended after the ObservationState are cast from the account data,
+// the packed struct only holds integers, so any bit pattern is valid
+unsafe impl bytemuck::Zeroable for Observation {}
+unsafe impl bytemuck::Pod for Observation {}
+
+/// The cumulative log price of an observation, appended after the observations of the
+/// accounts recording it in the order of the observations
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct LogPriceObservation {
+    /// the cumulative of log2(token_0_price_x32) during the duration time, Q32.32 wrapping.
+    /// log2(token_1_price_x32) is 64 minus it, so both geometric means are exactly reciprocal
+    pub cumulative_log_token_0_price_x32: u128,
+}
+impl LogPriceObservation {
+    pub const LEN: usize = 16;
+}
+
+unsafe impl bytemuck::Zeroable for LogPriceObservation {}
+unsafe impl bytemuck::Pod for LogPriceObservation {}
+
+/// Get log2(price_x32), Q32.32 rounded down, a zero price is counted as the smallest one
+fn log2_price_x32(price_x32: u128) -> u128 {
+    log2_x64(price_x32.max(1)) >> 32
+}
+
+/// Time weighted average prices over a window, Q32.32
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct TwapPrice {
+    pub token_0_price_x32: u128,
+    pub token_1_price_x32: u128,
+}
+
 #[account(zero_copy(unsafe))]
 #[repr(C, packed)]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -36,8 +77,11 @@ pub struct ObservationState {
     pub pool_id: Pubkey,
     /// observation array
     pub observations: [Observation; OBSERVATION_NUM],
+    /// The timestamp the cumulative log prices are recorded since, appended after the
+    /// observations, they are not recorded when it is 0
+    pub log_price_since: u64,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 3],
 }
 
 impl Default for ObservationState {
@@ -48,7 +92,8 @@ impl Default for ObservationState {
             observation_index: 0,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u64; 4],
+            log_price_since: 0,
+            padding: [0u64; 3],
         }
     }
 }
@@ -56,16 +101,107 @@ impl Default for ObservationState {
 impl ObservationState {
     pub const LEN: usize = 8 + 1 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 8 * 4;
 
+    /// Get the account size to hold `cardinality` observations, the observations
+    /// beyond `OBSERVATION_NUM` are appended after the state, followed by the cumulative
+    /// log price of every observation when `log_prices` are recorded
+    pub fn account_len(cardinality: usize, log_prices: bool) -> usize {
+        let log_prices_len = if log_prices {
+            LogPriceObservation::LEN * cardinality
+        } else {
+            0
+        };
+        Self::LEN + Observation::LEN * cardinality.saturating_sub(OBSERVATION_NUM) + log_prices_len
+    }
+
+    /// Returns the length of the observations appended after the state, the rest of the
+    /// account holds the log prices when `log_prices` are recorded
+    pub fn extension_len(data_len: usize, log_prices: bool) -> Result<usize> {
+        let rest_len = data_len
+            .checked_sub(ObservationState::LEN)
+            .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+        if !log_prices {
+            require_eq!(
+                rest_len % Observation::LEN,
+                0,
+                ErrorCode::AccountDidNotDeserialize
+            );
+            return Ok(rest_len);
+        }
+        // every extension observation comes with its log price
+        let extension_len = rest_len
+            .checked_sub(LogPriceObservation::LEN * OBSERVATION_NUM)
+            .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+        let observation_len = Observation::LEN + LogPriceObservation::LEN;
+        require_eq!(
+            extension_len % observation_len,
+            0,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        Ok(extension_len / observation_len * Observation::LEN)
+    }
+
+    pub fn is_log_price_recorded(&self) -> bool {
+        self.log_price_since != 0
+    }
+
+    /// Number of slots of the observation ring buffer
+    pub fn cardinality(extension: &[Observation]) -> usize {
+        OBSERVATION_NUM + extension.len()
+    }
+
+    fn observation(&self, extension: &[Observation], index: usize) -> Observation {
+        if index < OBSERVATION_NUM {
+            self.observations[index]
+        } else {
+            extension[index - OBSERVATION_NUM]
+        }
+    }
+
+    /// Returns the block timestamp of the most recent observation, `None` before the first one
+    pub fn last_observation_timestamp(&self, extension: &[Observation]) -> Option<u64> {
+        if !self.initialized {
+            return None;
+        }
+        Some(
+            self.observation(extension, usize::from(self.observation_index))
+                .block_timestamp,
+        )
+    }
+
+    fn observation_mut<'a>(
+        &'a mut self,
+        extension: &'a mut [Observation],
+        index: usize,
+    ) -> &'a mut Observation {
+        if index < OBSERVATION_NUM {
+            &mut self.observations[index]
+        } else {
+            &mut extension[index - OBSERVATION_NUM]
+        }
+    }
+
+    fn previous_index(extension: &[Observation], index: usize) -> usize {
+        if index == 0 {
+            Self::cardinality(extension) - 1
+        } else {
+            index - 1
+        }
+    }
+
     // Writes an oracle observation to the account, returning the next observation_index.
     /// Writable at most once per second. Index represents the most recently written element.
-    /// If the index is at the end of the allowable array length (100 - 1), the next index will turn to 0.
+    /// If the index is at the end of the ring buffer (cardinality - 1), the next index will turn to 0.
     ///
     /// # Arguments
     ///
     /// * `self` - The ObservationState account to write in
+    /// * `extension` - The observations appended after the state by `increase_observation_cardinality`
+    /// * `log_prices` - The cumulative log prices of the observations, empty when they are not recorded
     /// * `block_timestamp` - The current timestamp of to update
     /// * `token_0_price_x32` - The token_0_price_x32 at the time of the new observation
     /// * `token_1_price_x32` - The token_1_price_x32 at the time of the new observation
+    /// * `update_duration` - The minimum seconds since the last observation, the amm config
+    ///   `observation_update_duration`
     /// * `observation_index` - The last update index of element in the oracle array
     ///
     /// # Return
@@ -73,42 +209,339 @@ impl ObservationState {
     ///
     pub fn update(
         &mut self,
+        extension: &mut [Observation],
+        log_prices: &mut [LogPriceObservation],
         block_timestamp: u64,
         token_0_price_x32: u128,
         token_1_price_x32: u128,
+        update_duration: u64,
     ) {
-        let observation_index = self.observation_index;
+        let observation_index = self.observation_index as usize;
         if !self.initialized {
             // skip the pool init price
             self.initialized = true;
-            self.observations[observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[observation_index as usize].cumulative_token_0_price_x32 = 0;
-            self.observations[observation_index as usize].cumulative_token_1_price_x32 = 0;
+            let observation = self.observation_mut(extension, observation_index);
+            observation.block_timestamp = block_timestamp;
+            observation.cumulative_token_0_price_x32 = 0;
+            observation.cumulative_token_1_price_x32 = 0;
+            if let Some(log_price) = log_prices.get_mut(observation_index) {
+                log_price.cumulative_log_token_0_price_x32 = 0;
+            }
         } else {
-            let last_observation = self.observations[observation_index as usize];
+            let last_observation = self.observation(extension, observation_index);
             let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
-            if delta_time < OBSERVATION_UPDATE_DURATION_DEFAULT {
+            if delta_time < update_duration {
                 return;
             }
             let delta_token_0_price_x32 = token_0_price_x32.checked_mul(delta_time.into()).unwrap();
             let delta_token_1_price_x32 = token_1_price_x32.checked_mul(delta_time.into()).unwrap();
-            let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
+            let next_observation_index = if observation_index == Self::cardinality(extension) - 1 {
                 0
             } else {
                 observation_index + 1
             };
-            self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
+            let next_observation = self.observation_mut(extension, next_observation_index);
+            next_observation.block_timestamp = block_timestamp;
             // cumulative_token_price_x32 only occupies the first 64 bits, and the remaining 64 bits are used to store overflow data
-            self.observations[next_observation_index as usize].cumulative_token_0_price_x32 =
-                last_observation
+            next_observation.cumulative_token_0_price_x32 = last_observation
+                .cumulative_token_0_price_x32
+                .wrapping_add(delta_token_0_price_x32);
+            next_observation.cumulative_token_1_price_x32 = last_observation
+                .cumulative_token_1_price_x32
+                .wrapping_add(delta_token_1_price_x32);
+            if !log_prices.is_empty() {
+                let delta_log_price_x32 =
+                    log2_price_x32(token_0_price_x32) * u128::from(delta_time);
+                log_prices[next_observation_index].cumulative_log_token_0_price_x32 = log_prices
+                    [observation_index]
+                    .cumulative_log_token_0_price_x32
+                    .wrapping_add(delta_log_price_x32);
+            }
+            self.observation_index = next_observation_index as u16;
+        }
+    }
+
+    /// Returns the cumulative at `target_timestamp` of the value whose cumulative at each
+    /// observation index is `cumulative`, interpolated between the observations around it,
+    /// or extrapolated with its `current_value` if it is later than the most recent observation.
+    /// Returns `None` if the oracle history doesn't go back far enough.
+    fn cumulative_at(
+        &self,
+        extension: &[Observation],
+        target_timestamp: u64,
+        current_value: u128,
+        cumulative: impl Fn(usize) -> u128,
+    ) -> Option<u128> {
+        if !self.initialized {
+            return None;
+        }
+        let mut index = self.observation_index as usize;
+        let last_observation = self.observation(extension, index);
+        if target_timestamp >= last_observation.block_timestamp {
+            let delta_time = u128::from(target_timestamp - last_observation.block_timestamp);
+            return Some(cumulative(index).wrapping_add(current_value.checked_mul(delta_time)?));
+        }
+        for _ in 1..Self::cardinality(extension) {
+            let previous_index = Self::previous_index(extension, index);
+            let observation = self.observation(extension, index);
+            let previous_observation = self.observation(extension, previous_index);
+            if previous_observation.block_timestamp == 0
+                || previous_observation.block_timestamp >= observation.block_timestamp
+            {
+                return None;
+            }
+            if target_timestamp >= previous_observation.block_timestamp {
+                let interval =
+                    u128::from(observation.block_timestamp - previous_observation.block_timestamp);
+                let delta_time =
+                    u128::from(target_timestamp - previous_observation.block_timestamp);
+                let previous = cumulative(previous_index);
+                return Some(
+                    previous.wrapping_add(
+                        cumulative(index)
+                            .wrapping_sub(previous)
+                            .checked_mul(delta_time)?
+                            .checked_div(interval)?,
+                    ),
+                );
+            }
+            index = previous_index;
+        }
+        None
+    }
+
+    /// Returns the cumulative prices at `target_timestamp`, interpolated between the
+    /// observations around it, or extrapolated with the current prices if it is later
+    /// than the most recent observation.
+    /// Returns `None` if the oracle history doesn't go back far enough.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The observations appended after the state
+    /// * `target_timestamp` - The timestamp to get the cumulative prices at
+    /// * `token_0_price_x32` - The current token_0_price_x32
+    /// * `token_1_price_x32` - The current token_1_price_x32
+    ///
+    pub fn cumulative_price_x32(
+        &self,
+        extension: &[Observation],
+        target_timestamp: u64,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
+    ) -> Option<(u128, u128)> {
+        Some((
+            self.cumulative_at(extension, target_timestamp, token_0_price_x32, |index| {
+                self.observation(extension, index)
                     .cumulative_token_0_price_x32
-                    .wrapping_add(delta_token_0_price_x32);
-            self.observations[next_observation_index as usize].cumulative_token_1_price_x32 =
-                last_observation
+            })?,
+            self.cumulative_at(extension, target_timestamp, token_1_price_x32, |index| {
+                self.observation(extension, index)
                     .cumulative_token_1_price_x32
-                    .wrapping_add(delta_token_1_price_x32);
-            self.observation_index = next_observation_index;
+            })?,
+        ))
+    }
+
+    /// Returns the time weighted average prices over the last `seconds_ago` seconds
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The observations appended after the state
+    /// * `block_timestamp` - The current timestamp
+    /// * `seconds_ago` - The length in seconds of the window
+    /// * `token_0_price_x32` - The current token_0_price_x32
+    /// * `token_1_price_x32` - The current token_1_price_x32
+    ///
+    pub fn twap(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u64,
+        seconds_ago: u64,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
+    ) -> Option<TwapPrice> {
+        if seconds_ago == 0 {
+            return None;
+        }
+        let (end_cumulative_0, end_cumulative_1) = self.cumulative_price_x32(
+            extension,
+            block_timestamp,
+            token_0_price_x32,
+            token_1_price_x32,
+        )?;
+        let (start_cumulative_0, start_cumulative_1) = self.cumulative_price_x32(
+            extension,
+            block_timestamp.checked_sub(seconds_ago)?,
+            token_0_price_x32,
+            token_1_price_x32,
+        )?;
+        Some(TwapPrice {
+            token_0_price_x32: end_cumulative_0.wrapping_sub(start_cumulative_0)
+                / u128::from(seconds_ago),
+            token_1_price_x32: end_cumulative_1.wrapping_sub(start_cumulative_1)
+                / u128::from(seconds_ago),
+        })
+    }
+
+    /// Returns the time weighted geometric mean prices over the last `seconds_ago` seconds,
+    /// from the cumulative log prices. The token_1 price is the exact reciprocal of the
+    /// token_0 price, and a short spike moves them less than the arithmetic means.
+    /// Returns `None` if the log prices do not go back far enough
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The observations appended after the state
+    /// * `log_prices` - The cumulative log prices of the observations
+    /// * `block_timestamp` - The current timestamp
+    /// * `seconds_ago` - The length in seconds of the window
+    /// * `token_0_price_x32` - The current token_0_price_x32
+    ///
+    pub fn geometric_twap(
+        &self,
+        extension: &[Observation],
+        log_prices: &[LogPriceObservation],
+        block_timestamp: u64,
+        seconds_ago: u64,
+        token_0_price_x32: u128,
+    ) -> Option<TwapPrice> {
+        let start_timestamp = block_timestamp.checked_sub(seconds_ago)?;
+        if seconds_ago == 0
+            || !self.is_log_price_recorded()
+            || start_timestamp < self.log_price_since
+            || log_prices.len() != Self::cardinality(extension)
+        {
+            return None;
+        }
+        let current_log_price_x32 = log2_price_x32(token_0_price_x32);
+        let cumulative_log_price =
+            |index: usize| log_prices[index].cumulative_log_token_0_price_x32;
+        let end_cumulative = self.cumulative_at(
+            extension,
+            block_timestamp,
+            current_log_price_x32,
+            cumulative_log_price,
+        )?;
+        let start_cumulative = self.cumulative_at(
+            extension,
+            start_timestamp,
+            current_log_price_x32,
+            cumulative_log_price,
+        )?;
+        let log_price_x32 = end_cumulative.wrapping_sub(start_cumulative) / u128::from(seconds_ago);
+        // log2(token_1_price_x32) = 64 - log2(token_0_price_x32), a price below 2^-32 is 0
+        let token_1_price_x32 = match (64u128 << 32).checked_sub(log_price_x32) {
+            Some(log_price_x32) => exp2_ceil(log_price_x32 << 32)?,
+            None => 0,
+        };
+        Some(TwapPrice {
+            token_0_price_x32: exp2_ceil(log_price_x32 << 32)?,
+            token_1_price_x32,
+        })
+    }
+
+    /// Returns the range of the token_0 price over the recent observations, relative to
+    /// its lowest value and denominated in hundredths of a bip (10^-6).
+    /// The average price of every observation interval ends within `window` seconds is
+    /// compared together with the current price.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The observations appended after the state
+    /// * `block_timestamp` - The current timestamp
+    /// * `window` - The duration in seconds of the observations to look back
+    /// * `token_0_price_x32` - The current token_0_price_x32
+    ///
+    pub fn volatility(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u64,
+        window: u64,
+        token_0_price_x32: u128,
+    ) -> u64 {
+        let mut min_price = token_0_price_x32;
+        let mut max_price = token_0_price_x32;
+        if self.initialized {
+            let mut index = self.observation_index as usize;
+            for _ in 1..Self::cardinality(extension) {
+                let previous_index = Self::previous_index(extension, index);
+                let observation = self.observation(extension, index);
+                let previous_observation = self.observation(extension, previous_index);
+                if previous_observation.block_timestamp == 0
+                    || previous_observation.block_timestamp >= observation.block_timestamp
+                    || observation.block_timestamp.saturating_add(window) < block_timestamp
+                {
+                    break;
+                }
+                let delta_time = observation.block_timestamp - previous_observation.block_timestamp;
+                let price_x32 = observation
+                    .cumulative_token_0_price_x32
+                    .wrapping_sub(previous_observation.cumulative_token_0_price_x32)
+                    / u128::from(delta_time);
+                min_price = min_price.min(price_x32);
+                max_price = max_price.max(price_x32);
+                index = previous_index;
+            }
         }
+        if min_price == 0 {
+            return 0;
+        }
+        u64::try_from(
+            (max_price - min_price).saturating_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))
+                / min_price,
+        )
+        .unwrap_or(u64::MAX)
+    }
+}
+
+/// Loads an observation account together with the observations appended after the state
+/// The state, the appended observations and the cumulative log prices of an observation account
+pub type Observations<'a> = (
+    Ref<'a, ObservationState>,
+    Ref<'a, [Observation]>,
+    Ref<'a, [LogPriceObservation]>,
+);
+/// The mutable state, appended observations and cumulative log prices of an observation account
+pub type ObservationsMut<'a> = (
+    RefMut<'a, ObservationState>,
+    RefMut<'a, [Observation]>,
+    RefMut<'a, [LogPriceObservation]>,
+);
+
+pub trait ObservationLoader {
+    fn load_observations(&self) -> Result<Observations<'_>>;
+    fn load_observations_mut(&self) -> Result<ObservationsMut<'_>>;
+}
+
+impl<'info> ObservationLoader for AccountLoader<'info, ObservationState> {
+    fn load_observations(&self) -> Result<Observations<'_>> {
+        // checks the owner and discriminator
+        let log_prices = self.load()?.is_log_price_recorded();
+        let data = self.as_ref().try_borrow_data()?;
+        let extension_len = ObservationState::extension_len(data.len(), log_prices)?;
+        let (state, rest) = Ref::map_split(data, |data| data.split_at(ObservationState::LEN));
+        let (extension, log_prices) = Ref::map_split(rest, |rest| rest.split_at(extension_len));
+        Ok((
+            Ref::map(state, |state| bytemuck::from_bytes(&state[8..])),
+            Ref::map(extension, |extension| bytemuck::cast_slice(extension)),
+            Ref::map(log_prices, |log_prices| bytemuck::cast_slice(log_prices)),
+        ))
+    }
+
+    fn load_observations_mut(&self) -> Result<ObservationsMut<'_>> {
+        // checks the owner, discriminator and that the account is writable
+        let log_prices = self.load_mut()?.is_log_price_recorded();
+        let data = self.as_ref().try_borrow_mut_data()?;
+        let extension_len = ObservationState::extension_len(data.len(), log_prices)?;
+        let (state, rest) =
+            RefMut::map_split(data, |data| data.split_at_mut(ObservationState::LEN));
+        let (extension, log_prices) =
+            RefMut::map_split(rest, |rest| rest.split_at_mut(extension_len));
+        Ok((
+            RefMut::map(state, |state| bytemuck::from_bytes_mut(&mut state[8..])),
+            RefMut::map(extension, |extension| bytemuck::cast_slice_mut(extension)),
+            RefMut::map(log_prices, |log_prices| {
+                bytemuck::cast_slice_mut(log_prices)
+            }),
+        ))
     }
 }
 
@@ -137,4 +570,286 @@ pub mod observation_test {
             ObservationState::LEN - 8
         )
     }
+
+    #[test]
+    fn twap_test() {
+        let mut observation_state = ObservationState::default();
+        let block_timestamp = block_timestamp_mock();
+        let start = block_timestamp - 100;
+        let price_x32 = 10 * crate::states::Q32;
+        observation_state.update(
+            &mut [],
+            &mut [],
+            start,
+            price_x32,
+            price_x32,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+        // price doubles for the last 50 seconds
+        observation_state.update(
+            &mut [],
+            &mut [],
+            start + 50,
+            price_x32,
+            price_x32,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+        observation_state.update(
+            &mut [],
+            &mut [],
+            start + 100,
+            price_x32 * 2,
+            price_x32,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+
+        let twap = observation_state
+            .twap(&[], block_timestamp, 50, price_x32 * 2, price_x32)
+            .unwrap();
+        assert_eq!(twap.token_0_price_x32, price_x32 * 2);
+        assert_eq!(twap.token_1_price_x32, price_x32);
+        let twap = observation_state
+            .twap(&[], block_timestamp, 100, price_x32 * 2, price_x32)
+            .unwrap();
+        assert_eq!(twap.token_0_price_x32, price_x32 * 3 / 2);
+        // interpolate within an observation interval
+        let twap = observation_state
+            .twap(&[], block_timestamp, 75, price_x32 * 2, price_x32)
+            .unwrap();
+        assert_eq!(twap.token_0_price_x32, price_x32 * 5 / 3);
+        // extrapolate with the current price after the last observation
+        let twap = observation_state
+            .twap(&[], block_timestamp + 50, 100, price_x32 * 4, price_x32)
+            .unwrap();
+        assert_eq!(twap.token_0_price_x32, price_x32 * 3);
+        // window longer than the history
+        assert!(observation_state
+            .twap(&[], block_timestamp, 101, price_x32 * 2, price_x32)
+            .is_none());
+        assert!(observation_state
+            .twap(&[], block_timestamp, 0, price_x32 * 2, price_x32)
+            .is_none());
+    }
+
+    #[test]
+    fn geometric_twap_test() {
+        let mut observation_state = ObservationState::default();
+        let mut log_prices = [LogPriceObservation::default(); OBSERVATION_NUM];
+        let block_timestamp = block_timestamp_mock();
+        let start = block_timestamp - 100;
+        observation_state.log_price_since = start;
+        let price_x32 = 4 * crate::states::Q32;
+        observation_state.update(
+            &mut [],
+            &mut log_prices,
+            start,
+            price_x32,
+            crate::states::Q32 / 4,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+        // price quadruples for the last 50 seconds
+        observation_state.update(
+            &mut [],
+            &mut log_prices,
+            start + 50,
+            price_x32,
+            crate::states::Q32 / 4,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+        observation_state.update(
+            &mut [],
+            &mut log_prices,
+            start + 100,
+            price_x32 * 4,
+            crate::states::Q32 / 16,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+
+        let twap = observation_state
+            .geometric_twap(&[], &log_prices, block_timestamp, 50, price_x32 * 4)
+            .unwrap();
+        assert_eq!(twap.token_0_price_x32, price_x32 * 4);
+        assert_eq!(twap.token_1_price_x32, crate::states::Q32 / 16);
+        // the geometric mean of 4 and 16 is 8, below the arithmetic mean of 10
+        let twap = observation_state
+            .geometric_twap(&[], &log_prices, block_timestamp, 100, price_x32 * 4)
+            .unwrap();
+        assert_eq!(twap.token_0_price_x32, price_x32 * 2);
+        assert_eq!(twap.token_1_price_x32, crate::states::Q32 / 8);
+        // the prices are reciprocal within the rounding of the prices
+        let twap = observation_state
+            .geometric_twap(&[], &log_prices, block_timestamp, 75, price_x32 * 4)
+            .unwrap();
+        let product = twap.token_0_price_x32 * twap.token_1_price_x32;
+        assert!(product.abs_diff(1 << 64) <= 1 << 40);
+
+        // the log prices are not recorded before the start, or at all
+        assert!(observation_state
+            .geometric_twap(&[], &log_prices, block_timestamp, 101, price_x32 * 4)
+            .is_none());
+        assert!(observation_state
+            .geometric_twap(&[], &[], block_timestamp, 50, price_x32 * 4)
+            .is_none());
+        observation_state.log_price_since = 0;
+        assert!(observation_state
+            .geometric_twap(&[], &log_prices, block_timestamp, 50, price_x32 * 4)
+            .is_none());
+    }
+
+    #[test]
+    fn update_duration_test() {
+        let mut observation_state = ObservationState::default();
+        let start = block_timestamp_mock();
+        let price_x32 = 10 * crate::states::Q32;
+        observation_state.update(&mut [], &mut [], start, price_x32, price_x32, 60);
+        // too early for a 60 seconds update duration
+        observation_state.update(&mut [], &mut [], start + 15, price_x32, price_x32, 60);
+        assert_eq!({ observation_state.observation_index }, 0);
+        observation_state.update(&mut [], &mut [], start + 60, price_x32, price_x32, 60);
+        assert_eq!({ observation_state.observation_index }, 1);
+        assert_eq!(
+            { observation_state.observations[1].block_timestamp },
+            start + 60
+        );
+    }
+
+    #[test]
+    fn extension_test() {
+        assert_eq!(
+            ObservationState::account_len(OBSERVATION_NUM, false),
+            ObservationState::LEN
+        );
+        assert_eq!(
+            ObservationState::account_len(OBSERVATION_CARDINALITY_MAX, false),
+            ObservationState::LEN + Observation::LEN * 900
+        );
+        assert_eq!(
+            ObservationState::account_len(OBSERVATION_NUM + 10, true),
+            ObservationState::LEN + Observation::LEN * 10 + LogPriceObservation::LEN * 110
+        );
+        assert_eq!(
+            ObservationState::extension_len(
+                ObservationState::account_len(OBSERVATION_NUM + 10, true),
+                true
+            )
+            .unwrap(),
+            Observation::LEN * 10
+        );
+
+        let mut observation_state = ObservationState::default();
+        let mut extension = [Observation::default(); 50];
+        let block_timestamp = block_timestamp_mock();
+        let start = block_timestamp - 15 * 200;
+        let price_x32 = 10 * crate::states::Q32;
+        for i in 0..=200 {
+            observation_state.update(
+                &mut extension,
+                &mut [],
+                start + i * 15,
+                price_x32,
+                price_x32,
+                OBSERVATION_UPDATE_DURATION_DEFAULT,
+            );
+        }
+        // the index wraps at the end of the extension
+        assert_eq!({ observation_state.observation_index }, 50);
+        assert_eq!({ extension[49].block_timestamp }, start + 149 * 15);
+
+        // the history covers all of the 150 observations
+        let twap = observation_state
+            .twap(&extension, block_timestamp, 149 * 15, price_x32, price_x32)
+            .unwrap();
+        assert_eq!(twap.token_0_price_x32, price_x32);
+        assert!(observation_state
+            .twap(
+                &extension,
+                block_timestamp,
+                149 * 15 + 1,
+                price_x32,
+                price_x32
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn volatility_test() {
+        let mut observation_state = ObservationState::default();
+        let block_timestamp = block_timestamp_mock();
+        let start = block_timestamp - 600;
+        let price_x32 = 10 * crate::states::Q32;
+        observation_state.update(
+            &mut [],
+            &mut [],
+            start,
+            price_x32,
+            price_x32,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+        // stable price has no volatility
+        for i in 1..=20 {
+            observation_state.update(
+                &mut [],
+                &mut [],
+                start + i * 15,
+                price_x32,
+                price_x32,
+                OBSERVATION_UPDATE_DURATION_DEFAULT,
+            );
+        }
+        assert_eq!(
+            observation_state.volatility(
+                &[],
+                block_timestamp,
+                VOLATILITY_WINDOW_DEFAULT,
+                price_x32
+            ),
+            0
+        );
+        // a 10% move of the current price
+        assert_eq!(
+            observation_state.volatility(
+                &[],
+                block_timestamp,
+                VOLATILITY_WINDOW_DEFAULT,
+                price_x32 * 11 / 10
+            ),
+            100_000
+        );
+        // a 20% move recorded long before the window is ignored
+        let mut observation_state = ObservationState::default();
+        observation_state.update(
+            &mut [],
+            &mut [],
+            start,
+            price_x32,
+            price_x32,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+        observation_state.update(
+            &mut [],
+            &mut [],
+            start + 15,
+            price_x32 * 12 / 10,
+            price_x32,
+            OBSERVATION_UPDATE_DURATION_DEFAULT,
+        );
+        for i in 2..=20 {
+            observation_state.update(
+                &mut [],
+                &mut [],
+                start + i * 15,
+                price_x32,
+                price_x32,
+                OBSERVATION_UPDATE_DURATION_DEFAULT,
+            );
+        }
+        assert_eq!(
+            observation_state.volatility(&[], block_timestamp, 300, price_x32),
+            0
+        );
+        assert_eq!(
+            observation_state.volatility(&[], block_timestamp, 600, price_x32),
+            200_000
+        );
+    }
 }
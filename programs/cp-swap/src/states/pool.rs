@@ -1,3 +1,9 @@
+use crate::curve::{
+    AmountScale, CurveCalculator, CurveType, Fees, RoundDirection, SwapResult, TradeDirection,
+    MAX_AMP, MAX_WEIGHT, MIN_AMP, MIN_WEIGHT, PRICE_IMPACT_BPS_DENOMINATOR, WEIGHT_DENOMINATOR,
+};
+use crate::states::{AmmConfig, LaunchMode, Observation, ObservationState, PoolReserves};
+use crate::utils::{OraclePrice, U256};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
 use std::ops::{BitAnd, BitOr, BitXor};
@@ -5,13 +11,46 @@ use std::ops::{BitAnd, BitOr, BitXor};
 pub const POOL_SEED: &str = "pool";
 pub const POOL_LP_MINT_SEED: &str = "pool_lp_mint";
 pub const POOL_VAULT_SEED: &str = "pool_vault";
+/// Decimals of the lp mints
+pub const LP_MINT_DECIMALS: u8 = 9;
+/// Lp tokens permanently locked at initialization, counted in the lp supply but never
+/// minted so the lp token value can not be inflated out of reach of a deposit
+pub const LOCKED_LP_AMOUNT: u64 = 1000;
+/// Layout version of the pool state, the first layout has no version byte and reads as 0
+pub const POOL_STATE_VERSION: u8 = 7;
+/// Size of the first pool state layout: 11 Pubkeys, 7 u8s, 7 u64s and 27 u64s padding.
+/// Its fields are a prefix of the current layout and its padding was zeroed
+pub const POOL_STATE_V1_LEN: usize = 8 + 11 * 32 + 1 * 7 + 8 * 7 + 8 * 27;
 
 pub const Q32: u128 = (u32::MAX as u128) + 1; // 2^32
+/// Largest power of ten between an oracle price and the price in token amounts
+pub const MAX_ORACLE_PRICE_SCALE: u32 = 30;
+/// Duration in seconds of the volume buckets of the pool metrics
+pub const METRICS_BUCKET_DURATION: u64 = 86400;
+/// Seconds of a day of the weekly trading hours
+pub const SECONDS_PER_DAY: u64 = 86400;
+/// Shortest duration in seconds a parameter ramp can last
+pub const MIN_RAMP_DURATION: u32 = 86400;
+/// The amplification coefficient can at most be multiplied or divided by this factor by a ramp
+pub const MAX_AMP_RAMP_FACTOR: u64 = 10;
 
 pub enum PoolStatusBitIndex {
     Deposit,
     Withdraw,
     Swap,
+    FlashLoan,
+}
+
+impl PoolStatusBitIndex {
+    pub fn from_u8(bit: u8) -> Option<PoolStatusBitIndex> {
+        match bit {
+            0 => Some(PoolStatusBitIndex::Deposit),
+            1 => Some(PoolStatusBitIndex::Withdraw),
+            2 => Some(PoolStatusBitIndex::Swap),
+            3 => Some(PoolStatusBitIndex::FlashLoan),
+            _ => None,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -20,6 +59,130 @@ pub enum PoolStatusBitFlag {
     Disable,
 }
 
+/// The pool parameter moved by `ramp_pool_parameter`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RampParameter {
+    /// The amplification coefficient of a stable swap pool
+    Amp,
+    /// The trade fee rate of a custom authority pool
+    TradeFeeRate,
+}
+
+/// Fee growth and volume of a pool, returned by `get_pool_metrics`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct PoolMetrics {
+    pub lp_supply: u64,
+    /// Cumulative liquidity provider fees per lp token, Q64.64, wrapping on overflow
+    pub fee_growth_0_x64: u128,
+    pub fee_growth_1_x64: u128,
+    /// Cumulative swapped amounts
+    pub volume_token_0: u128,
+    pub volume_token_1: u128,
+    /// Start timestamp of the current bucket lasting METRICS_BUCKET_DURATION
+    pub bucket_start_time: u64,
+    pub bucket_volume_token_0: u64,
+    pub bucket_volume_token_1: u64,
+    /// Swapped amounts of the bucket before the current one
+    pub previous_bucket_volume_token_0: u64,
+    pub previous_bucket_volume_token_1: u64,
+}
+
+/// Expected amounts of a swap, returned by `quote_swap_base_input` and `quote_swap_base_output`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Amount of input token sent by the trader, including the input transfer fee
+    pub amount_in: u64,
+    /// Amount of output token received by the trader, the output transfer fee excluded
+    pub amount_out: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    /// Fees of input token taken by the swap
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    /// Move of the pool price by the swap in basis points
+    pub price_impact_bps: u64,
+}
+
+/// Executed amounts of a swap, returned by `swap_base_input` and `swap_base_output` so CPI
+/// callers and simulations read the breakdown of the swap without parsing its events
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct SwapReceipt {
+    /// Amount of input token sent by the trader, including the input transfer fee
+    pub amount_in: u64,
+    /// Amount of output token received by the trader, the output transfer fee excluded
+    pub amount_out: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    /// Fees taken by the swap, in the input token or in the output token when the amm
+    /// config charges the fees on the output
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    /// Square root of the price of token_0 in token_1 of the reserves before and after
+    /// the swap, Q64.64
+    pub sqrt_price_before_x64: u128,
+    pub sqrt_price_after_x64: u128,
+    /// Move of the pool price by the swap in basis points
+    pub price_impact_bps: u64,
+}
+
+/// Underlying amounts of an lp position, returned by `get_lp_position_value`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct LpPositionValue {
+    pub lp_amount: u64,
+    pub lp_supply: u64,
+    /// Amounts a withdrawal of the lp tokens receives before transfer fees, the protocol and
+    /// fund fees owed by the pool excluded
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+}
+
+/// Slippage of a deposit or withdrawal expressed as the deviation of the pool price, the
+/// reserve of token_1 per token_0, from the price expected by the caller
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct PriceSlippage {
+    /// The expected reserve of token_1 per token_0, Q64.64
+    pub expected_price_x64: u128,
+    /// Maximum deviation of the pool price from the expected price in basis points
+    pub max_price_deviation_bps: u64,
+}
+
+impl PriceSlippage {
+    /// Check the price of the pool reserves deviates from the expected price by at most
+    /// `max_price_deviation_bps`
+    pub fn check(&self, token_0_amount: u64, token_1_amount: u64) -> Result<()> {
+        require_gt!(
+            token_0_amount,
+            0,
+            crate::error::ErrorCode::ZeroTradingTokens
+        );
+        // compare the token_1 reserve to its expected amount, without the rounding of a price
+        let value_x64 = U256::from(token_1_amount) << 64;
+        let expected_value_x64 = U256::from(self.expected_price_x64) * U256::from(token_0_amount);
+        let deviation = if value_x64 > expected_value_x64 {
+            value_x64 - expected_value_x64
+        } else {
+            expected_value_x64 - value_x64
+        };
+        require!(
+            deviation * U256::from(PRICE_IMPACT_BPS_DENOMINATOR)
+                <= expected_value_x64 * U256::from(self.max_price_deviation_bps),
+            crate::error::ErrorCode::ExceededSlippage
+        );
+        Ok(())
+    }
+}
+
+/// Token metadata of an lp mint created under token program 2022, so wallets display the
+/// lp token, e.g. "RAY-CP USDC/SOL LP"
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct LpMintMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
 #[account(zero_copy(unsafe))]
 #[repr(C, packed)]
 #[derive(Default, Debug)]
@@ -54,6 +217,7 @@ pub struct PoolState {
     /// bit0, 1: disable deposit(value is 1), 0: normal
     /// bit1, 1: disable withdraw(value is 2), 0: normal
     /// bit2, 1: disable swap(value is 4), 0: normal
+    /// bit3, 1: disable flash loan(value is 8), 0: normal
     pub status: u8,
 
     pub lp_mint_decimals: u8,
@@ -81,14 +245,203 @@ pub struct PoolState {
     pub custom_authority: Pubkey,
     /// Pool authority bump (for custom authority validation)
     pub custom_auth_bump: u8,
-    
-    /// padding for future updates (reduced from 31 to accommodate new fields)
-    pub padding: [u64; 27],
+
+    /// Curve invariant: 0 = Constant product, 1 = StableSwap, 2 = Weighted
+    pub curve_type: u8,
+    /// Amplification coefficient of the StableSwap curve, or the token_0 weight out of
+    /// WEIGHT_DENOMINATOR of the weighted curve
+    pub amp: u64,
+
+    /// Whether `trade_fee_rate` overrides the trade fee rate of the amm config
+    pub trade_fee_rate_overridden: u8,
+    /// Swaps moving the pool price by more than this many basis points revert, disabled when it is 0
+    pub max_price_impact_bps: u16,
+    /// Trades allowed until the launch is finalized, see `LaunchMode`
+    pub launch_mode: u8,
+    pub padding1: [u8; 3],
+
+    /// Amounts of token_0 and token_1 (principal plus fee) owed by an outstanding flash loan
+    pub flash_loan_amount_0: u64,
+    pub flash_loan_amount_1: u64,
+
+    /// The trade fee set by the custom authority, denominated in hundredths of a bip (10^-6)
+    pub trade_fee_rate: u64,
+
+    /// Ratio of the curve to the real liquidity in basis points, the pool trades on the real
+    /// reserves offset by virtual reserves when it is set, disabled when it is 0
+    pub virtual_reserve_multiplier: u64,
+    /// Virtual amounts of token_0 and token_1 added to the real reserves on swaps
+    pub virtual_token_0_amount: u64,
+    pub virtual_token_1_amount: u64,
+
+    /// The timestamp from which anyone can finalize the launch phase
+    pub launch_end_time: u64,
+
+    /// Cumulative liquidity provider fees of token_0 and token_1 per lp token, Q64.64,
+    /// wrapping on overflow so only differences are meaningful
+    pub fee_growth_0_x64: u128,
+    pub fee_growth_1_x64: u128,
+    /// Cumulative amounts of token_0 and token_1 swapped in or out of the pool
+    pub volume_token_0: u128,
+    pub volume_token_1: u128,
+    /// Index of the current volume bucket, the block timestamp divided by METRICS_BUCKET_DURATION
+    pub metrics_bucket: u64,
+    /// Amounts of token_0 and token_1 swapped in the current bucket
+    pub bucket_volume_token_0: u64,
+    pub bucket_volume_token_1: u64,
+    /// Amounts of token_0 and token_1 swapped in the previous bucket
+    pub previous_bucket_volume_token_0: u64,
+    pub previous_bucket_volume_token_1: u64,
+
+    /// The epoch of the last buyback
+    pub buyback_epoch: u64,
+    /// Amount of fund fees spent on buybacks in `buyback_epoch`
+    pub buyback_epoch_amount: u64,
+
+    /// Duration in seconds of the TWAP the price after a trade is compared to by the circuit breaker
+    pub circuit_breaker_window: u32,
+    /// Trades moving the price further than this many basis points from the TWAP pause the
+    /// swaps, the circuit breaker is disabled when it is 0
+    pub circuit_breaker_threshold_bps: u16,
+    /// Whether the circuit breaker paused the swaps, until `unpause_pool`
+    pub circuit_breaker_tripped: u8,
+    /// Set while an instruction moving the pool tokens runs, a transfer hook calling back
+    /// into the pool before it ends is rejected
+    pub reentrancy_lock: u8,
+    /// The timestamp of the last unpause, the circuit breaker stays idle for a window after it
+    /// so the TWAP catches up with the price
+    pub circuit_breaker_resume_time: u64,
+
+    /// The lowest price of token_0 in token_1 trades can move the pool to, Q64.64,
+    /// unbounded when it is 0
+    pub min_price_x64: u128,
+    /// The highest price of token_0 in token_1 trades can move the pool to, Q64.64,
+    /// unbounded when it is 0
+    pub max_price_x64: u128,
+
+    /// The address allowed to borrow reserves through `strategy_borrow`, usually a PDA of
+    /// a strategy program, borrowing is disabled when it is the default pubkey
+    pub authorized_strategy: Pubkey,
+    /// Share in basis points of each reserve the strategy can have borrowed at once
+    pub strategy_max_borrow_bps: u16,
+    /// Reserves borrowed by the strategy and not repaid yet, still counted in the pool amounts
+    pub strategy_borrowed_0: u64,
+    pub strategy_borrowed_1: u64,
+
+    /// Whether `protocol_fee_rate` and `fund_fee_rate` override the fee split of the amm config
+    pub fee_split_overridden: u8,
+    /// The share of the trade fee taken as protocol fee, set by the admin
+    pub protocol_fee_rate: u64,
+    /// The share of the trade fee taken as fund fee, set by the admin
+    pub fund_fee_rate: u64,
+
+    /// The Pyth price update account of the price of token_0 in token_1 guarding the swaps,
+    /// unguarded when it is the default pubkey
+    pub price_oracle: Pubkey,
+    /// Swaps executing further than this many basis points from the oracle price revert
+    pub oracle_max_deviation_bps: u16,
+    /// Oracle prices published more than this many seconds ago are stale
+    pub oracle_max_age: u32,
+
+    /// The token_0 volume a trader must reach in an epoch to earn a fee rebate
+    pub fee_rebate_volume_threshold: u64,
+    /// The share of their trade fees in basis points paid back to the traders reaching the
+    /// volume threshold, out of the protocol fees, the fee rebates are disabled when it is 0
+    pub fee_rebate_bps: u16,
+
+    /// Layout version of the account, `POOL_STATE_VERSION` once created or migrated
+    pub version: u8,
+
+    /// The filler given the first look at the swaps going through the order-flow auction
+    pub auction_filler: Pubkey,
+    /// Swaps of at least this many input tokens must be requested with `request_swap` and
+    /// executed with `execute_swap`, the auction is disabled when it is 0
+    pub auction_size_threshold: u64,
+    /// The number of slots after a swap request only the filler can execute it
+    pub auction_window_slots: u64,
+
+    /// The share of the trade fee earned by the pool creator, taken from the liquidity
+    /// provider share, denominated in hundredths of a bip (10^-6), disabled when it is 0
+    pub creator_fee_rate: u64,
+    /// The creator fees of token_0 accrued in the vault and not collected yet
+    pub creator_fees_token_0: u64,
+    /// The creator fees of token_1 accrued in the vault and not collected yet
+    pub creator_fees_token_1: u64,
+
+    /// Whether the liquidity is only provided through personal positions, which cannot be
+    /// transferred, instead of minted lp tokens
+    pub position_mode: u8,
+    /// Whether the pool has a `RewardState` emitting rewards to the positions, their
+    /// rewards must then be accrued whenever their shares change
+    pub rewards_enabled: u8,
+    /// The salt of the pool address, 0 for the canonical pool of the mint pair in the amm
+    /// config, see `initialize_with_salt`
+    pub pool_salt: u8,
+    /// The open limit orders, DCA orders and swap requests of the pool, whose swaps
+    /// the amm config of the pool must keep allowing
+    pub open_order_count: u32,
+    pub padding2: [u8; 1],
+
+    /// The amplification coefficient ramps linearly from `amp_ramp_start_amp` at
+    /// `amp_ramp_start_time` to `amp` over `amp_ramp_duration` seconds
+    pub amp_ramp_start_amp: u64,
+    pub amp_ramp_start_time: u64,
+    pub amp_ramp_duration: u32,
+    /// The trade fee rate override ramps linearly from `fee_ramp_start_rate` at
+    /// `fee_ramp_start_time` to `trade_fee_rate` over `fee_ramp_duration` seconds
+    pub fee_ramp_start_rate: u64,
+    pub fee_ramp_start_time: u64,
+    pub fee_ramp_duration: u32,
+
+    /// The `TraderRegistry` listing the only traders allowed to swap in the pool, every
+    /// trader is allowed when it is the default pubkey
+    pub trader_registry: Pubkey,
+
+    /// Swaps trading fewer token_0, as input or output, revert, disabled when it is 0
+    pub min_trade_amount: u64,
+    /// Swaps trading more token_0, as input or output, revert, disabled when it is 0
+    pub max_trade_amount: u64,
+
+    /// The seconds the lp tokens recorded by a `DepositReceipt` are locked after their last
+    /// deposit, the lockup is disabled when it is 0
+    pub min_lock_duration: u32,
+    /// The share of the withdrawn tokens in basis points the withdrawals of locked lp
+    /// tokens leave in the vaults for the remaining liquidity providers
+    pub early_exit_fee_bps: u16,
+
+    /// Swaps revert before this unix timestamp, disabled when it is 0
+    pub trading_start_time: u64,
+    /// Swaps revert from this unix timestamp, disabled when it is 0
+    pub trading_end_time: u64,
+    /// The days of the week the pool trades on, bit 0 for Monday to bit 6 for Sunday, the
+    /// weekly trading hours are disabled when it is 0
+    pub trading_days: u8,
+    /// The trading hours of the trading days in seconds since 00:00 UTC, swaps are allowed
+    /// from `trading_day_open` until `trading_day_close`
+    pub trading_day_open: u32,
+    pub trading_day_close: u32,
+
+    /// Whether the weighted pool is a liquidity bootstrapping pool, its token_0 weight
+    /// decays along the amp ramp to 50/50 and `finalize_lbp` converts it to a constant
+    /// product pool
+    pub lbp_mode: u8,
+
+    /// The epoch of the last lp fee rebate
+    pub lp_fee_rebate_epoch: u64,
+    /// Amount of lp tokens minted for lp fee rebates in `lp_fee_rebate_epoch`
+    pub lp_fee_rebate_epoch_amount: u64,
+
+    /// Reserved for future fields, which must read their zero value as a disabled feature
+    /// so accounts migrate by reallocation
+    pub padding: [u8; 2],
 }
 
+// the pools are migrated to the account size of `PoolState::LEN` by `migrate_pool_state`, a
+// layout change must update it deliberately
+const _: () = assert!(PoolState::LEN == 997);
+
 impl PoolState {
-    // Updated to account for: 11 Pubkeys (32 bytes each), 7 u8s, 7 u64s, and 27 u64s padding
-    pub const LEN: usize = 8 + 11 * 32 + 1 * 7 + 8 * 7 + 8 * 27;
+    pub const LEN: usize = 8 + std::mem::size_of::<PoolState>();
 
     pub fn initialize(
         &mut self,
@@ -101,23 +454,25 @@ impl PoolState {
         token_1_vault: Pubkey,
         token_0_mint: &InterfaceAccount<Mint>,
         token_1_mint: &InterfaceAccount<Mint>,
-        lp_mint: &InterfaceAccount<Mint>,
+        lp_mint: Pubkey,
+        lp_mint_decimals: u8,
         observation_key: Pubkey,
         authority_type: u8,
         custom_authority: Option<Pubkey>,
+        curve_type: CurveType,
     ) {
         self.amm_config = amm_config.key();
         self.pool_creator = pool_creator.key();
         self.token_0_vault = token_0_vault;
         self.token_1_vault = token_1_vault;
-        self.lp_mint = lp_mint.key();
+        self.lp_mint = lp_mint;
         self.token_0_mint = token_0_mint.key();
         self.token_1_mint = token_1_mint.key();
         self.token_0_program = *token_0_mint.to_account_info().owner;
         self.token_1_program = *token_1_mint.to_account_info().owner;
         self.observation_key = observation_key;
         self.auth_bump = auth_bump;
-        self.lp_mint_decimals = lp_mint.decimals;
+        self.lp_mint_decimals = lp_mint_decimals;
         self.mint_0_decimals = token_0_mint.decimals;
         self.mint_1_decimals = token_1_mint.decimals;
         self.lp_supply = lp_supply;
@@ -130,7 +485,1007 @@ impl PoolState {
         self.authority_type = authority_type;
         self.custom_authority = custom_authority.unwrap_or_default();
         self.custom_auth_bump = if authority_type == 1 { 0 } else { auth_bump };
-        self.padding = [0u64; 27];
+        (self.curve_type, self.amp) = match curve_type {
+            CurveType::ConstantProduct => (CurveType::CONSTANT_PRODUCT, 0),
+            CurveType::StableSwap { amp } => (CurveType::STABLE_SWAP, amp),
+            CurveType::Weighted { source_weight, .. } => (CurveType::WEIGHTED, source_weight),
+        };
+        self.trade_fee_rate_overridden = 0;
+        self.max_price_impact_bps = 0;
+        self.launch_mode = 0;
+        self.padding1 = [0u8; 3];
+        self.flash_loan_amount_0 = 0;
+        self.flash_loan_amount_1 = 0;
+        self.trade_fee_rate = 0;
+        self.virtual_reserve_multiplier = 0;
+        self.virtual_token_0_amount = 0;
+        self.virtual_token_1_amount = 0;
+        self.launch_end_time = 0;
+        self.fee_growth_0_x64 = 0;
+        self.fee_growth_1_x64 = 0;
+        self.volume_token_0 = 0;
+        self.volume_token_1 = 0;
+        self.metrics_bucket = 0;
+        self.bucket_volume_token_0 = 0;
+        self.bucket_volume_token_1 = 0;
+        self.previous_bucket_volume_token_0 = 0;
+        self.previous_bucket_volume_token_1 = 0;
+        self.buyback_epoch = 0;
+        self.buyback_epoch_amount = 0;
+        self.circuit_breaker_window = 0;
+        self.circuit_breaker_threshold_bps = 0;
+        self.circuit_breaker_tripped = 0;
+        self.reentrancy_lock = 0;
+        self.circuit_breaker_resume_time = 0;
+        self.min_price_x64 = 0;
+        self.max_price_x64 = 0;
+        self.authorized_strategy = Pubkey::default();
+        self.strategy_max_borrow_bps = 0;
+        self.strategy_borrowed_0 = 0;
+        self.strategy_borrowed_1 = 0;
+        self.fee_split_overridden = 0;
+        self.protocol_fee_rate = 0;
+        self.fund_fee_rate = 0;
+        self.price_oracle = Pubkey::default();
+        self.oracle_max_deviation_bps = 0;
+        self.oracle_max_age = 0;
+        self.fee_rebate_volume_threshold = 0;
+        self.fee_rebate_bps = 0;
+        self.version = POOL_STATE_VERSION;
+        self.auction_filler = Pubkey::default();
+        self.auction_size_threshold = 0;
+        self.auction_window_slots = 0;
+        self.creator_fee_rate = 0;
+        self.creator_fees_token_0 = 0;
+        self.creator_fees_token_1 = 0;
+        self.position_mode = 0;
+        self.rewards_enabled = 0;
+        self.pool_salt = 0;
+        self.open_order_count = 0;
+        self.padding2 = [0u8; 1];
+        self.amp_ramp_start_amp = 0;
+        self.amp_ramp_start_time = 0;
+        self.amp_ramp_duration = 0;
+        self.fee_ramp_start_rate = 0;
+        self.fee_ramp_start_time = 0;
+        self.fee_ramp_duration = 0;
+        self.trader_registry = Pubkey::default();
+        self.min_trade_amount = 0;
+        self.max_trade_amount = 0;
+        self.min_lock_duration = 0;
+        self.early_exit_fee_bps = 0;
+        self.trading_start_time = 0;
+        self.trading_end_time = 0;
+        self.trading_days = 0;
+        self.trading_day_open = 0;
+        self.trading_day_close = 0;
+        self.lbp_mode = 0;
+        self.lp_fee_rebate_epoch = 0;
+        self.lp_fee_rebate_epoch_amount = 0;
+        self.padding = [0u8; 2];
+    }
+
+    /// Get the trade fee rate set by the custom authority at `block_timestamp`, if any,
+    /// following a running ramp
+    pub fn trade_fee_rate_override(&self, block_timestamp: u64) -> Option<u64> {
+        if self.trade_fee_rate_overridden == 0 {
+            None
+        } else {
+            Some(ramp_value(
+                self.fee_ramp_start_rate,
+                self.trade_fee_rate,
+                self.fee_ramp_start_time,
+                self.fee_ramp_duration,
+                block_timestamp,
+            ))
+        }
+    }
+
+    /// Set the trade fee rate override at once, cancelling a running ramp
+    pub fn set_trade_fee_rate_override(&mut self, trade_fee_rate: Option<u64>) {
+        self.trade_fee_rate_overridden = u8::from(trade_fee_rate.is_some());
+        self.trade_fee_rate = trade_fee_rate.unwrap_or_default();
+        self.fee_ramp_duration = 0;
+    }
+
+    /// Ramp the trade fee rate override from `trade_fee_rate`, the rate applied at
+    /// `block_timestamp`, to `target_trade_fee_rate` over `ramp_duration` seconds
+    pub fn ramp_trade_fee_rate(
+        &mut self,
+        trade_fee_rate: u64,
+        target_trade_fee_rate: u64,
+        block_timestamp: u64,
+        ramp_duration: u32,
+    ) -> Result<()> {
+        require_gte!(
+            ramp_duration,
+            MIN_RAMP_DURATION,
+            crate::error::ErrorCode::InvalidRamp
+        );
+        self.trade_fee_rate_overridden = 1;
+        self.fee_ramp_start_rate = trade_fee_rate;
+        self.fee_ramp_start_time = block_timestamp;
+        self.fee_ramp_duration = ramp_duration;
+        self.trade_fee_rate = target_trade_fee_rate;
+        Ok(())
+    }
+
+    /// Get the amplification coefficient at `block_timestamp`, following a running ramp
+    pub fn amp_at(&self, block_timestamp: u64) -> u64 {
+        ramp_value(
+            self.amp_ramp_start_amp,
+            self.amp,
+            self.amp_ramp_start_time,
+            self.amp_ramp_duration,
+            block_timestamp,
+        )
+    }
+
+    /// Ramp the amplification coefficient of a stable swap pool from its value at
+    /// `block_timestamp` to `target_amp` over `ramp_duration` seconds. The target is at most
+    /// MAX_AMP_RAMP_FACTOR times higher or lower, and a new ramp can only start
+    /// MIN_RAMP_DURATION after the previous one started
+    pub fn ramp_amp(
+        &mut self,
+        target_amp: u64,
+        block_timestamp: u64,
+        ramp_duration: u32,
+    ) -> Result<()> {
+        require!(
+            self.curve_type == CurveType::STABLE_SWAP,
+            crate::error::ErrorCode::InvalidCurveType
+        );
+        require!(
+            (MIN_AMP..=MAX_AMP).contains(&target_amp),
+            crate::error::ErrorCode::InvalidRamp
+        );
+        require_gte!(
+            ramp_duration,
+            MIN_RAMP_DURATION,
+            crate::error::ErrorCode::InvalidRamp
+        );
+        if self.amp_ramp_duration != 0 {
+            require_gte!(
+                block_timestamp,
+                self.amp_ramp_start_time + u64::from(MIN_RAMP_DURATION),
+                crate::error::ErrorCode::InvalidRamp
+            );
+        }
+        let amp = self.amp_at(block_timestamp);
+        require!(
+            target_amp <= amp * MAX_AMP_RAMP_FACTOR && target_amp * MAX_AMP_RAMP_FACTOR >= amp,
+            crate::error::ErrorCode::InvalidRamp
+        );
+        self.amp_ramp_start_amp = amp;
+        self.amp_ramp_start_time = block_timestamp;
+        self.amp_ramp_duration = ramp_duration;
+        self.amp = target_amp;
+        Ok(())
+    }
+
+    /// Whether the pool is a liquidity bootstrapping pool not finalized yet
+    pub fn is_lbp(&self) -> bool {
+        self.lbp_mode != 0
+    }
+
+    /// Get the unix timestamp the weights of the liquidity bootstrapping pool reach 50/50
+    pub fn lbp_end_time(&self) -> u64 {
+        self.amp_ramp_start_time + u64::from(self.amp_ramp_duration)
+    }
+
+    /// Turn a weighted pool not opened yet into a liquidity bootstrapping pool, its token_0
+    /// weight decays from `start_weight` at the open time to 50/50 over `lbp_duration`
+    /// seconds, as the amp ramp of the weight
+    pub fn start_lbp(
+        &mut self,
+        start_weight: u64,
+        lbp_duration: u32,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        require!(
+            self.curve_type == CurveType::WEIGHTED,
+            crate::error::ErrorCode::InvalidCurveType
+        );
+        require!(
+            !self.is_lbp() && block_timestamp < self.open_time,
+            crate::error::ErrorCode::NotApproved
+        );
+        require!(
+            (MIN_WEIGHT..=MAX_WEIGHT).contains(&start_weight),
+            crate::error::ErrorCode::InvalidRamp
+        );
+        require_gte!(
+            lbp_duration,
+            MIN_RAMP_DURATION,
+            crate::error::ErrorCode::InvalidRamp
+        );
+        self.lbp_mode = 1;
+        self.amp_ramp_start_amp = start_weight;
+        self.amp_ramp_start_time = self.open_time;
+        self.amp_ramp_duration = lbp_duration;
+        self.amp = WEIGHT_DENOMINATOR / 2;
+        Ok(())
+    }
+
+    /// Convert the liquidity bootstrapping pool to a constant product pool once its weights
+    /// reached 50/50, which trades the same
+    pub fn finalize_lbp(&mut self, block_timestamp: u64) -> Result<()> {
+        require!(self.is_lbp(), crate::error::ErrorCode::NotApproved);
+        require_gte!(
+            block_timestamp,
+            self.lbp_end_time(),
+            crate::error::ErrorCode::LbpNotEnded
+        );
+        self.lbp_mode = 0;
+        self.curve_type = CurveType::CONSTANT_PRODUCT;
+        self.amp = 0;
+        self.amp_ramp_start_amp = 0;
+        self.amp_ramp_start_time = 0;
+        self.amp_ramp_duration = 0;
+        Ok(())
+    }
+
+    /// Get the protocol and fund fee rates of the pool, the split set by the admin or else
+    /// the one of the amm config
+    pub fn fee_split(&self, amm_config: &AmmConfig) -> (u64, u64) {
+        if self.fee_split_overridden == 0 {
+            (amm_config.protocol_fee_rate, amm_config.fund_fee_rate)
+        } else {
+            (self.protocol_fee_rate, self.fund_fee_rate)
+        }
+    }
+
+    /// Whether the liquidity is held in personal positions, lp tokens are not minted then
+    pub fn is_position_mode(&self) -> bool {
+        self.position_mode != 0
+    }
+
+    pub fn is_rewards_enabled(&self) -> bool {
+        self.rewards_enabled != 0
+    }
+
+    /// Get the creator share of the trade fee of a swap
+    pub fn creator_fee(&self, trade_fee: u128) -> u64 {
+        u64::try_from(Fees::creator_fee(trade_fee, self.creator_fee_rate).unwrap()).unwrap()
+    }
+
+    /// Accrue the creator share of the trade fee of a swap, returns the creator fee
+    pub fn accrue_creator_fee(&mut self, trade_direction: TradeDirection, trade_fee: u128) -> u64 {
+        let creator_fee = self.creator_fee(trade_fee);
+        match trade_direction {
+            TradeDirection::ZeroForOne => {
+                self.creator_fees_token_0 =
+                    self.creator_fees_token_0.checked_add(creator_fee).unwrap()
+            }
+            TradeDirection::OneForZero => {
+                self.creator_fees_token_1 =
+                    self.creator_fees_token_1.checked_add(creator_fee).unwrap()
+            }
+        }
+        creator_fee
+    }
+
+    pub fn set_fee_split_override(&mut self, fee_split: Option<(u64, u64)>) {
+        self.fee_split_overridden = u8::from(fee_split.is_some());
+        let (protocol_fee_rate, fund_fee_rate) = fee_split.unwrap_or_default();
+        self.protocol_fee_rate = protocol_fee_rate;
+        self.fund_fee_rate = fund_fee_rate;
+    }
+
+    /// Check a swap does not move the pool price beyond `max_price_impact_bps`
+    pub fn check_price_impact(
+        &self,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        new_swap_source_amount: u128,
+        new_swap_destination_amount: u128,
+    ) -> Result<()> {
+        if self.max_price_impact_bps == 0 {
+            return Ok(());
+        }
+        let price_impact_bps = CurveCalculator::price_impact_bps(
+            swap_source_amount,
+            swap_destination_amount,
+            new_swap_source_amount,
+            new_swap_destination_amount,
+        )?;
+        require_gte!(
+            u64::from(self.max_price_impact_bps),
+            price_impact_bps,
+            crate::error::ErrorCode::ExceededPriceImpact
+        );
+        Ok(())
+    }
+
+    /// Check the launch phase of the pool allows a swap in the trade direction
+    pub fn check_launch_trade(
+        &self,
+        trade_direction: TradeDirection,
+        whitelisted: bool,
+    ) -> Result<()> {
+        let allowed = match LaunchMode::from_u8(self.launch_mode) {
+            Some(LaunchMode::None) => true,
+            Some(LaunchMode::BuyToken0) => trade_direction == TradeDirection::OneForZero,
+            Some(LaunchMode::BuyToken1) => trade_direction == TradeDirection::ZeroForOne,
+            Some(LaunchMode::Whitelist) => whitelisted,
+            None => false,
+        };
+        require!(allowed, crate::error::ErrorCode::LaunchTradeNotAllowed);
+        Ok(())
+    }
+
+    /// Accrue the volume and the liquidity provider fee of a swap to the pool metrics,
+    /// the liquidity providers get the trade fee less the protocol and fund fees and the
+    /// `lp_fee_cut` of the referral and creator fees
+    pub fn record_swap(
+        &mut self,
+        trade_direction: TradeDirection,
+        result: &SwapResult,
+        lp_fee_cut: u64,
+        block_timestamp: u64,
+    ) {
+        let (token_0_amount, token_1_amount) = match trade_direction {
+            TradeDirection::ZeroForOne => (
+                result.source_amount_swapped,
+                result.destination_amount_swapped,
+            ),
+            TradeDirection::OneForZero => (
+                result.destination_amount_swapped,
+                result.source_amount_swapped,
+            ),
+        };
+        self.volume_token_0 = self.volume_token_0.saturating_add(token_0_amount);
+        self.volume_token_1 = self.volume_token_1.saturating_add(token_1_amount);
+
+        let (bucket_volume_token_0, bucket_volume_token_1, previous_0, previous_1) =
+            self.bucket_volumes(block_timestamp);
+        self.metrics_bucket = block_timestamp / METRICS_BUCKET_DURATION;
+        self.bucket_volume_token_0 =
+            bucket_volume_token_0.saturating_add(u64::try_from(token_0_amount).unwrap());
+        self.bucket_volume_token_1 =
+            bucket_volume_token_1.saturating_add(u64::try_from(token_1_amount).unwrap());
+        self.previous_bucket_volume_token_0 = previous_0;
+        self.previous_bucket_volume_token_1 = previous_1;
+
+        if self.lp_supply == 0 {
+            return;
+        }
+        let lp_fee = result
+            .trade_fee
+            .saturating_sub(result.protocol_fee + result.fund_fee + u128::from(lp_fee_cut));
+        let fee_growth_x64 = (U256::from(lp_fee) << 64) / U256::from(self.lp_supply);
+        let fee_growth_x64 = fee_growth_x64.low_u128();
+        match result.fee_direction(trade_direction) {
+            TradeDirection::ZeroForOne => {
+                self.fee_growth_0_x64 = self.fee_growth_0_x64.wrapping_add(fee_growth_x64)
+            }
+            TradeDirection::OneForZero => {
+                self.fee_growth_1_x64 = self.fee_growth_1_x64.wrapping_add(fee_growth_x64)
+            }
+        }
+    }
+
+    /// Get the current and previous bucket volumes at the block timestamp, the buckets
+    /// roll over when no swap has been recorded since they ended
+    fn bucket_volumes(&self, block_timestamp: u64) -> (u64, u64, u64, u64) {
+        let bucket = block_timestamp / METRICS_BUCKET_DURATION;
+        if bucket == self.metrics_bucket {
+            (
+                self.bucket_volume_token_0,
+                self.bucket_volume_token_1,
+                self.previous_bucket_volume_token_0,
+                self.previous_bucket_volume_token_1,
+            )
+        } else if bucket == self.metrics_bucket + 1 {
+            (0, 0, self.bucket_volume_token_0, self.bucket_volume_token_1)
+        } else {
+            (0, 0, 0, 0)
+        }
+    }
+
+    /// Get the fee growth and volume metrics of the pool at the block timestamp
+    pub fn metrics(&self, block_timestamp: u64) -> PoolMetrics {
+        let (bucket_volume_token_0, bucket_volume_token_1, previous_0, previous_1) =
+            self.bucket_volumes(block_timestamp);
+        PoolMetrics {
+            lp_supply: self.lp_supply,
+            fee_growth_0_x64: self.fee_growth_0_x64,
+            fee_growth_1_x64: self.fee_growth_1_x64,
+            volume_token_0: self.volume_token_0,
+            volume_token_1: self.volume_token_1,
+            bucket_start_time: block_timestamp / METRICS_BUCKET_DURATION * METRICS_BUCKET_DURATION,
+            bucket_volume_token_0,
+            bucket_volume_token_1,
+            previous_bucket_volume_token_0: previous_0,
+            previous_bucket_volume_token_1: previous_1,
+        }
+    }
+
+    /// Get the underlying amounts of `lp_amount` lp tokens, rounded down as a withdrawal
+    pub fn lp_position_value(
+        &self,
+        lp_amount: u64,
+        vault_0: u64,
+        vault_1: u64,
+    ) -> Option<LpPositionValue> {
+        if lp_amount > self.lp_supply {
+            return None;
+        }
+        let (total_token_0_amount, total_token_1_amount) =
+            self.vault_amount_without_fee(vault_0, vault_1);
+        let results = CurveCalculator::lp_tokens_to_trading_tokens(
+            u128::from(lp_amount),
+            u128::from(self.lp_supply),
+            u128::from(total_token_0_amount),
+            u128::from(total_token_1_amount),
+            RoundDirection::Floor,
+        )
+        .ok()?;
+        Some(LpPositionValue {
+            lp_amount,
+            lp_supply: self.lp_supply,
+            token_0_amount: u64::try_from(results.token_0_amount).ok()?,
+            token_1_amount: u64::try_from(results.token_1_amount).ok()?,
+        })
+    }
+
+    /// Vault balances are reduced while a flash loan is outstanding, so the pool
+    /// must not be traded against until it is repaid
+    pub fn is_flash_loan_active(&self) -> bool {
+        self.flash_loan_amount_0 != 0 || self.flash_loan_amount_1 != 0
+    }
+
+    /// Get the amount of fund fees the next buyback spends, bounded by what is left of the
+    /// epoch cap, the cap resets when the epoch changes
+    pub fn buyback_amount(&self, fund_fees: u64, buyback_epoch_cap: u64, epoch: u64) -> u64 {
+        if buyback_epoch_cap == 0 {
+            return fund_fees;
+        }
+        let spent = if epoch == self.buyback_epoch {
+            self.buyback_epoch_amount
+        } else {
+            0
+        };
+        fund_fees.min(buyback_epoch_cap.saturating_sub(spent))
+    }
+
+    pub fn record_buyback(&mut self, amount: u64, epoch: u64) {
+        if epoch != self.buyback_epoch {
+            self.buyback_epoch = epoch;
+            self.buyback_epoch_amount = 0;
+        }
+        self.buyback_epoch_amount = self.buyback_epoch_amount.checked_add(amount).unwrap();
+    }
+
+    /// Get the lp tokens worth `rebate_fee` of the fee token of `fee_direction` at the spot
+    /// price of `reserves`, the reserves after the swap which still hold the rebated fee.
+    /// The `lp_amount / (lp_supply + lp_amount)` share of the reserves is worth the rebated
+    /// fee, so the liquidity providers give up no more than it, rounded down
+    pub fn lp_fee_rebate_lp_amount(
+        &self,
+        fee_direction: TradeDirection,
+        rebate_fee: u64,
+        reserves: &PoolReserves,
+        block_timestamp: u64,
+    ) -> u64 {
+        if rebate_fee == 0 {
+            return 0;
+        }
+        let (token_0_amount, token_1_amount) =
+            self.curve_amounts(reserves.token_0_vault, reserves.token_1_vault);
+        let (token_0_price_amount, token_1_price_amount) =
+            self.price_amounts(token_0_amount, token_1_amount, block_timestamp);
+        // one token of the other side is worth `fee_price_amount / other_price_amount` fee tokens
+        let (fee_amount, other_amount, fee_price_amount, other_price_amount) = match fee_direction {
+            TradeDirection::ZeroForOne => (
+                reserves.token_0_vault,
+                reserves.token_1_vault,
+                token_0_price_amount,
+                token_1_price_amount,
+            ),
+            TradeDirection::OneForZero => (
+                reserves.token_1_vault,
+                reserves.token_0_vault,
+                token_1_price_amount,
+                token_0_price_amount,
+            ),
+        };
+        // the value of the reserves without the rebated fee in fee tokens, scaled by
+        // `other_price_amount`
+        let value = U256::from(fee_amount.saturating_sub(rebate_fee))
+            * U256::from(other_price_amount)
+            + U256::from(other_amount) * U256::from(fee_price_amount);
+        if value.is_zero() {
+            return 0;
+        }
+        let lp_amount = U256::from(reserves.lp_supply)
+            * U256::from(rebate_fee)
+            * U256::from(other_price_amount)
+            / value;
+        lp_amount.min(U256::from(u64::MAX)).as_u64()
+    }
+
+    /// Get the lp tokens the next lp fee rebate mints, bounded by what is left of the epoch
+    /// cap, the cap resets when the epoch changes
+    pub fn lp_fee_rebate_amount(
+        &self,
+        lp_amount: u64,
+        lp_fee_rebate_epoch_cap: u64,
+        epoch: u64,
+    ) -> u64 {
+        if lp_fee_rebate_epoch_cap == 0 {
+            return lp_amount;
+        }
+        let minted = if epoch == self.lp_fee_rebate_epoch {
+            self.lp_fee_rebate_epoch_amount
+        } else {
+            0
+        };
+        lp_amount.min(lp_fee_rebate_epoch_cap.saturating_sub(minted))
+    }
+
+    pub fn record_lp_fee_rebate(&mut self, lp_amount: u64, epoch: u64) {
+        if epoch != self.lp_fee_rebate_epoch {
+            self.lp_fee_rebate_epoch = epoch;
+            self.lp_fee_rebate_epoch_amount = 0;
+        }
+        self.lp_fee_rebate_epoch_amount = self
+            .lp_fee_rebate_epoch_amount
+            .checked_add(lp_amount)
+            .unwrap();
+    }
+
+    pub fn is_circuit_breaker_tripped(&self) -> bool {
+        self.circuit_breaker_tripped != 0
+    }
+
+    /// Trip the circuit breaker when the token_0 price after a trade deviates from its TWAP
+    /// over `circuit_breaker_window` by more than `circuit_breaker_threshold_bps`, the
+    /// current prices extend the TWAP up to the block timestamp.
+    /// Returns the deviation in basis points when it trips
+    pub fn update_circuit_breaker(
+        &mut self,
+        observation_state: &ObservationState,
+        extension: &[Observation],
+        block_timestamp: u64,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
+        reserves_after: &PoolReserves,
+    ) -> Option<u64> {
+        if self.circuit_breaker_threshold_bps == 0 {
+            return None;
+        }
+        let twap = observation_state.twap(
+            extension,
+            block_timestamp,
+            u64::from(self.circuit_breaker_window),
+            token_0_price_x32,
+            token_1_price_x32,
+        )?;
+        let (token_0_amount, token_1_amount) =
+            self.curve_amounts(reserves_after.token_0_vault, reserves_after.token_1_vault);
+        let price_after_x32 = token_1_amount
+            .checked_mul(Q32)?
+            .checked_div(token_0_amount)?;
+        self.trip_circuit_breaker(twap.token_0_price_x32, price_after_x32, block_timestamp)
+    }
+
+    fn trip_circuit_breaker(
+        &mut self,
+        twap_price_x32: u128,
+        price_x32: u128,
+        block_timestamp: u64,
+    ) -> Option<u64> {
+        if twap_price_x32 == 0
+            || block_timestamp
+                < self
+                    .circuit_breaker_resume_time
+                    .saturating_add(u64::from(self.circuit_breaker_window))
+        {
+            return None;
+        }
+        let deviation_bps = u64::try_from(
+            U256::from(price_x32.abs_diff(twap_price_x32))
+                .checked_mul(U256::from(PRICE_IMPACT_BPS_DENOMINATOR))?
+                .checked_div(U256::from(twap_price_x32))?,
+        )
+        .unwrap_or(u64::MAX);
+        if deviation_bps <= u64::from(self.circuit_breaker_threshold_bps) {
+            return None;
+        }
+        self.circuit_breaker_tripped = 1;
+        Some(deviation_bps)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.reentrancy_lock != 0
+    }
+
+    /// Hold the pool until `unlock`, the writes to the zero copy data reach the programs
+    /// invoked after it, so a nested call into the pool fails here
+    pub fn lock(&mut self) -> Result<()> {
+        require!(
+            !self.is_locked(),
+            crate::error::ErrorCode::ReentrancyDetected
+        );
+        self.reentrancy_lock = 1;
+        Ok(())
+    }
+
+    pub fn unlock(&mut self) {
+        self.reentrancy_lock = 0;
+    }
+
+    /// Resume the swaps paused by the circuit breaker
+    pub fn unpause_circuit_breaker(&mut self, block_timestamp: u64) {
+        self.circuit_breaker_tripped = 0;
+        self.circuit_breaker_resume_time = block_timestamp;
+    }
+
+    pub fn is_strategy_borrowed(&self) -> bool {
+        self.strategy_borrowed_0 != 0 || self.strategy_borrowed_1 != 0
+    }
+
+    /// Record a strategy borrow, the borrowed reserves must stay within
+    /// `strategy_max_borrow_bps` of the pool amounts
+    pub fn strategy_borrow(
+        &mut self,
+        amount_0: u64,
+        amount_1: u64,
+        total_token_0_amount: u64,
+        total_token_1_amount: u64,
+    ) -> Result<()> {
+        let borrowed_0 = self.strategy_borrowed_0.checked_add(amount_0).unwrap();
+        let borrowed_1 = self.strategy_borrowed_1.checked_add(amount_1).unwrap();
+        let limit = |amount: u64| {
+            u128::from(amount) * u128::from(self.strategy_max_borrow_bps)
+                / u128::from(PRICE_IMPACT_BPS_DENOMINATOR)
+        };
+        require!(
+            u128::from(borrowed_0) <= limit(total_token_0_amount)
+                && u128::from(borrowed_1) <= limit(total_token_1_amount),
+            crate::error::ErrorCode::StrategyBorrowLimitExceeded
+        );
+        self.strategy_borrowed_0 = borrowed_0;
+        self.strategy_borrowed_1 = borrowed_1;
+        Ok(())
+    }
+
+    /// Record the tokens the vaults received from a strategy repay, the part above the
+    /// borrowed reserves is yield left to the liquidity providers.
+    /// Returns the yield of token_0 and token_1
+    pub fn strategy_repay(&mut self, received_0: u64, received_1: u64) -> (u64, u64) {
+        let repaid_0 = received_0.min(self.strategy_borrowed_0);
+        let repaid_1 = received_1.min(self.strategy_borrowed_1);
+        self.strategy_borrowed_0 -= repaid_0;
+        self.strategy_borrowed_1 -= repaid_1;
+        (received_0 - repaid_0, received_1 - repaid_1)
+    }
+
+    /// Reject paying `amount_0` and `amount_1` out of the vaults beyond their balances less
+    /// the accrued fees. The reserves lent to the strategy count in the pool amounts the
+    /// outputs are priced on, but they are not in the vaults to pay them until repaid
+    pub fn check_vault_outflow(
+        &self,
+        vault_0: u64,
+        vault_1: u64,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<()> {
+        let accrued_fees_0 =
+            self.protocol_fees_token_0 + self.fund_fees_token_0 + self.creator_fees_token_0;
+        let accrued_fees_1 =
+            self.protocol_fees_token_1 + self.fund_fees_token_1 + self.creator_fees_token_1;
+        require!(
+            amount_0 <= vault_0.saturating_sub(accrued_fees_0)
+                && amount_1 <= vault_1.saturating_sub(accrued_fees_1),
+            crate::error::ErrorCode::StrategyReservesLent
+        );
+        Ok(())
+    }
+
+    /// Reject a swap paying `amount_out` from the output vault like `check_vault_outflow`
+    pub fn check_swap_outflow(
+        &self,
+        trade_direction: TradeDirection,
+        vault_0: u64,
+        vault_1: u64,
+        amount_out: u64,
+    ) -> Result<()> {
+        match trade_direction {
+            TradeDirection::ZeroForOne => self.check_vault_outflow(vault_0, vault_1, 0, amount_out),
+            TradeDirection::OneForZero => self.check_vault_outflow(vault_0, vault_1, amount_out, 0),
+        }
+    }
+
+    /// Whether the swaps are checked against an oracle price, only `swap_base_input` and
+    /// `swap_base_output` take the oracle account so the other trades are refused
+    pub fn is_oracle_guarded(&self) -> bool {
+        self.price_oracle != Pubkey::default()
+    }
+
+    /// Whether only the traders of a `TraderRegistry` can swap, only `swap_base_input` and
+    /// `swap_base_output` take the trader entry so the other trades are refused
+    pub fn is_trader_registry_enabled(&self) -> bool {
+        self.trader_registry != Pubkey::default()
+    }
+
+    /// Whether the withdrawals of lp tokens deposited less than `min_lock_duration` ago pay
+    /// the early exit fee, only `withdraw` and `withdraw_exact_tokens` take the deposit
+    /// receipt so the other withdrawals are refused
+    pub fn is_lockup_enabled(&self) -> bool {
+        self.min_lock_duration != 0
+    }
+
+    /// Get the early exit fee of withdrawing `token_amount` for `lp_token_amount` lp tokens
+    /// of which `early_exit_lp_amount` are still locked
+    pub fn early_exit_fee(
+        &self,
+        token_amount: u64,
+        early_exit_lp_amount: u64,
+        lp_token_amount: u64,
+    ) -> u64 {
+        if !self.is_lockup_enabled() || early_exit_lp_amount == 0 || lp_token_amount == 0 {
+            return 0;
+        }
+        u64::try_from(
+            u128::from(token_amount) * u128::from(early_exit_lp_amount.min(lp_token_amount))
+                / u128::from(lp_token_amount)
+                * u128::from(self.early_exit_fee_bps)
+                / u128::from(PRICE_IMPACT_BPS_DENOMINATOR),
+        )
+        .unwrap()
+    }
+
+    /// Check the trader of a swap is registered when the pool has a trader registry
+    pub fn check_trader_registered(&self, registered: bool) -> Result<()> {
+        require!(
+            !self.is_trader_registry_enabled() || registered,
+            crate::error::ErrorCode::TraderNotRegistered
+        );
+        Ok(())
+    }
+
+    /// Check a trade swapping on the curve outside of `swap_base_input` and
+    /// `swap_base_output` is allowed under `amm_config`. Only the plain swaps check the
+    /// oracle price of guarded pools and the trader entries of the pools with a trader
+    /// registry, and charge the trade fee on the output token
+    pub fn check_derived_swap_allowed(&self, amm_config: &AmmConfig) -> Result<()> {
+        require!(
+            !self.is_oracle_guarded(),
+            crate::error::ErrorCode::NotApproved
+        );
+        require!(
+            !self.is_trader_registry_enabled(),
+            crate::error::ErrorCode::TraderNotRegistered
+        );
+        require!(
+            !amm_config.fee_on_output,
+            crate::error::ErrorCode::FeeOnOutputNotSupported
+        );
+        Ok(())
+    }
+
+    /// Check lp tokens can be withdrawn without a deposit receipt, the lockup of the
+    /// deposit receipts is only applied by `withdraw` and `withdraw_exact_tokens`
+    pub fn check_withdraw_without_receipt(&self) -> Result<()> {
+        require!(
+            !self.is_lockup_enabled(),
+            crate::error::ErrorCode::WithdrawalLockup
+        );
+        Ok(())
+    }
+
+    /// Check lp tokens can be deposited without a deposit receipt, the lp tokens of the
+    /// pools with a withdrawal lockup are only recorded by `deposit`
+    pub fn check_deposit_without_receipt(&self) -> Result<()> {
+        require!(
+            !self.is_lockup_enabled(),
+            crate::error::ErrorCode::DepositReceiptRequired
+        );
+        Ok(())
+    }
+
+    /// Check the execution price of a swap, in token_1 per token_0, deviates from the
+    /// oracle price by at most `oracle_max_deviation_bps`
+    pub fn check_oracle_deviation(
+        &self,
+        oracle_price: &OraclePrice,
+        trade_direction: TradeDirection,
+        source_amount_swapped: u128,
+        destination_amount_swapped: u128,
+    ) -> Result<()> {
+        let (token_0_amount, token_1_amount) = match trade_direction {
+            TradeDirection::ZeroForOne => (source_amount_swapped, destination_amount_swapped),
+            TradeDirection::OneForZero => (destination_amount_swapped, source_amount_swapped),
+        };
+        // the oracle price of the token_0 amount in token_1 amounts is price * 10^scale
+        let scale = oracle_price.exponent + i32::from(self.mint_1_decimals)
+            - i32::from(self.mint_0_decimals);
+        require_gte!(
+            MAX_ORACLE_PRICE_SCALE,
+            scale.unsigned_abs(),
+            crate::error::ErrorCode::InvalidOracle
+        );
+        let exp = U256::exp10(scale.unsigned_abs() as usize);
+        let (oracle_value, execution_value) = if scale >= 0 {
+            (
+                U256::from(oracle_price.price) * exp * U256::from(token_0_amount),
+                U256::from(token_1_amount),
+            )
+        } else {
+            (
+                U256::from(oracle_price.price) * U256::from(token_0_amount),
+                U256::from(token_1_amount) * exp,
+            )
+        };
+        let deviation = if oracle_value > execution_value {
+            oracle_value - execution_value
+        } else {
+            execution_value - oracle_value
+        };
+        require!(
+            deviation * U256::from(PRICE_IMPACT_BPS_DENOMINATOR)
+                <= oracle_value * U256::from(self.oracle_max_deviation_bps),
+            crate::error::ErrorCode::OracleDeviationExceeded
+        );
+        Ok(())
+    }
+
+    /// Whether the large swaps go through the order-flow auction of the filler
+    pub fn is_auction_enabled(&self) -> bool {
+        self.auction_size_threshold > 0
+    }
+
+    /// Count a limit order, DCA order or swap request opened against the pool
+    pub fn open_order(&mut self) -> Result<()> {
+        self.open_order_count = self
+            .open_order_count
+            .checked_add(1)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Stop counting an order once it is cancelled or executed, the orders opened before
+    /// the pool counted them are not in the count
+    pub fn close_order(&mut self) {
+        self.open_order_count = self.open_order_count.saturating_sub(1);
+    }
+
+    /// Check the pool can be reassigned to `amm_config`, the open orders and the auction of
+    /// the pool execute through the derived swaps, which do not charge the fee on output
+    pub fn check_amm_config_reassignment(&self, amm_config: &AmmConfig) -> Result<()> {
+        require!(
+            !amm_config.fee_on_output || (self.open_order_count == 0 && !self.is_auction_enabled()),
+            crate::error::ErrorCode::FeeOnOutputNotSupported
+        );
+        Ok(())
+    }
+
+    /// Check a swap of `amount_in` input tokens can execute directly against the pool,
+    /// the swaps reaching the auction size threshold must be requested first
+    pub fn check_auction_size(&self, amount_in: u64) -> Result<()> {
+        require!(
+            !self.is_auction_enabled() || amount_in < self.auction_size_threshold,
+            crate::error::ErrorCode::AuctionRequired
+        );
+        Ok(())
+    }
+
+    /// Whether the trading schedule of the pool allows swaps at `block_timestamp`, within
+    /// the trading window and the weekly trading hours when they are set
+    pub fn is_within_trading_hours(&self, block_timestamp: u64) -> bool {
+        if (self.trading_start_time != 0 && block_timestamp < self.trading_start_time)
+            || (self.trading_end_time != 0 && block_timestamp >= self.trading_end_time)
+        {
+            return false;
+        }
+        if self.trading_days == 0 {
+            return true;
+        }
+        // the unix epoch is a Thursday
+        let weekday = (block_timestamp / SECONDS_PER_DAY + 3) % 7;
+        let second_of_day = (block_timestamp % SECONDS_PER_DAY) as u32;
+        self.trading_days & (1 << weekday) != 0
+            && (self.trading_day_open..self.trading_day_close).contains(&second_of_day)
+    }
+
+    pub fn check_trading_hours(&self, block_timestamp: u64) -> Result<()> {
+        require!(
+            self.is_within_trading_hours(block_timestamp),
+            crate::error::ErrorCode::OutsideTradingHours
+        );
+        Ok(())
+    }
+
+    /// Check the pool accepts a swap at `block_timestamp`, the checks shared by every path
+    /// trading on the curve: swaps enabled and open, within the trading hours, no flash
+    /// loan in flight and the circuit breaker not tripped
+    pub fn check_swap_allowed(&self, block_timestamp: u64) -> Result<()> {
+        if !self.get_status_by_bit(PoolStatusBitIndex::Swap) || block_timestamp < self.open_time {
+            return err!(crate::error::ErrorCode::NotApproved);
+        }
+        self.check_trading_hours(block_timestamp)?;
+        require!(
+            !self.is_flash_loan_active(),
+            crate::error::ErrorCode::FlashLoanInProgress
+        );
+        require!(
+            !self.is_circuit_breaker_tripped(),
+            crate::error::ErrorCode::CircuitBreakerTripped
+        );
+        Ok(())
+    }
+
+    /// Check the token_0 traded by a swap, its input or its output, is within the trade
+    /// size limits of the pool
+    pub fn check_trade_size(
+        &self,
+        trade_direction: TradeDirection,
+        result: &SwapResult,
+    ) -> Result<()> {
+        let token_0_amount = match trade_direction {
+            TradeDirection::ZeroForOne => result.source_amount_swapped,
+            TradeDirection::OneForZero => result.destination_amount_swapped,
+        };
+        require!(
+            token_0_amount >= u128::from(self.min_trade_amount)
+                && (self.max_trade_amount == 0
+                    || token_0_amount <= u128::from(self.max_trade_amount)),
+            crate::error::ErrorCode::TradeSizeOutOfRange
+        );
+        Ok(())
+    }
+
+    /// Check a trade at `block_timestamp` does not move the pool price out of its price
+    /// band, a trade moving the price back towards the band is allowed
+    pub fn check_price_band(
+        &self,
+        reserves_before: &PoolReserves,
+        reserves_after: &PoolReserves,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        if self.min_price_x64 == 0 && self.max_price_x64 == 0 {
+            return Ok(());
+        }
+        let price_before_x64 = self.price_x64(reserves_before, block_timestamp);
+        let price_after_x64 = self.price_x64(reserves_after, block_timestamp);
+        let below_band = self.min_price_x64 != 0
+            && price_after_x64 < self.min_price_x64
+            && price_after_x64 < price_before_x64;
+        let above_band = self.max_price_x64 != 0
+            && price_after_x64 > self.max_price_x64
+            && price_after_x64 > price_before_x64;
+        require!(
+            !below_band && !above_band,
+            crate::error::ErrorCode::PriceOutsideBand
+        );
+        Ok(())
+    }
+
+    /// Get the price of token_0 in token_1 of the curve at the reserves, Q64.64
+    fn price_x64(&self, reserves: &PoolReserves, block_timestamp: u64) -> u128 {
+        let (token_0_amount, token_1_amount) =
+            self.curve_amounts(reserves.token_0_vault, reserves.token_1_vault);
+        let (token_0_amount, token_1_amount) =
+            self.price_amounts(token_0_amount, token_1_amount, block_timestamp);
+        if token_0_amount == 0 {
+            return u128::MAX;
+        }
+        u128::try_from((U256::from(token_1_amount) << 64) / U256::from(token_0_amount))
+            .unwrap_or(u128::MAX)
+    }
+
+    /// Upgrade an account reallocated from an older layout in place, the fields appended
+    /// since were zeroed by the reallocation
+    pub fn migrate(&mut self) -> Result<()> {
+        require_gt!(
+            POOL_STATE_VERSION,
+            self.version,
+            crate::error::ErrorCode::InvalidPoolStateVersion
+        );
+        self.version = POOL_STATE_VERSION;
+        Ok(())
     }
 
     pub fn set_status(&mut self, status: u8) {
@@ -147,30 +1502,197 @@ impl PoolState {
         }
     }
 
+    /// Disable every operation of the pool
+    pub fn pause(&mut self) {
+        self.status = u8::MAX;
+    }
+
+    /// Whether setting `status` only disables operations, and never enables one back
+    pub fn is_pause_only(&self, status: u8) -> bool {
+        status.bitand(self.status) == self.status
+    }
+
     /// Get status by bit, if it is `noraml` status, return true
     pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
         let status = u8::from(1) << (bit as u8);
         self.status.bitand(status) == 0
     }
 
+    /// Get the pool amounts of the vault balances, without the fees and with the reserves
+    /// the strategy borrowed
     pub fn vault_amount_without_fee(&self, vault_0: u64, vault_1: u64) -> (u64, u64) {
         (
-            vault_0
-                .checked_sub(self.protocol_fees_token_0 + self.fund_fees_token_0)
+            (vault_0 + self.strategy_borrowed_0)
+                .checked_sub(
+                    self.protocol_fees_token_0 + self.fund_fees_token_0 + self.creator_fees_token_0,
+                )
                 .unwrap(),
-            vault_1
-                .checked_sub(self.protocol_fees_token_1 + self.fund_fees_token_1)
+            (vault_1 + self.strategy_borrowed_1)
+                .checked_sub(
+                    self.protocol_fees_token_1 + self.fund_fees_token_1 + self.creator_fees_token_1,
+                )
                 .unwrap(),
         )
     }
 
-    pub fn token_price_x32(&self, vault_0: u64, vault_1: u64) -> (u128, u128) {
+    /// Check the vaults, with the amounts lent to the strategy, hold the accrued fees and no
+    /// more lp tokens were minted than the lp supply accounts for
+    pub fn check_accounting(&self, vault_0: u64, vault_1: u64, lp_mint_supply: u64) -> Result<()> {
+        let accrued_fees_0 = u128::from(self.protocol_fees_token_0)
+            + u128::from(self.fund_fees_token_0)
+            + u128::from(self.creator_fees_token_0);
+        let accrued_fees_1 = u128::from(self.protocol_fees_token_1)
+            + u128::from(self.fund_fees_token_1)
+            + u128::from(self.creator_fees_token_1);
+        require_gte!(
+            u128::from(vault_0) + u128::from(self.strategy_borrowed_0),
+            accrued_fees_0,
+            crate::error::ErrorCode::PoolAccountingViolated
+        );
+        require_gte!(
+            u128::from(vault_1) + u128::from(self.strategy_borrowed_1),
+            accrued_fees_1,
+            crate::error::ErrorCode::PoolAccountingViolated
+        );
+        require_gte!(
+            self.lp_supply,
+            lp_mint_supply,
+            crate::error::ErrorCode::PoolAccountingViolated
+        );
+        Ok(())
+    }
+
+    /// Get the invariant of the curve at `block_timestamp` over the reserves of the pool,
+    /// without the accrued fees
+    pub fn reserves_invariant(
+        &self,
+        vault_0: u64,
+        vault_1: u64,
+        amount_scale: AmountScale,
+        block_timestamp: u64,
+    ) -> Result<u128> {
+        let (token_0_amount, token_1_amount) = self.vault_amount_without_fee(vault_0, vault_1);
+        let (token_0_amount, token_1_amount) = self.curve_amounts(token_0_amount, token_1_amount);
+        CurveCalculator::invariant_scaled(
+            self.curve(block_timestamp)?,
+            amount_scale,
+            token_0_amount,
+            token_1_amount,
+        )
+    }
+
+    pub fn token_price_x32(
+        &self,
+        vault_0: u64,
+        vault_1: u64,
+        block_timestamp: u64,
+    ) -> (u128, u128) {
         let (token_0_amount, token_1_amount) = self.vault_amount_without_fee(vault_0, vault_1);
+        let (token_0_amount, token_1_amount) = self.curve_amounts(token_0_amount, token_1_amount);
+        let (token_0_amount, token_1_amount) =
+            self.price_amounts(token_0_amount, token_1_amount, block_timestamp);
         (
-            token_1_amount as u128 * Q32 as u128 / token_0_amount as u128,
-            token_0_amount as u128 * Q32 as u128 / token_1_amount as u128,
+            token_1_amount * Q32 / token_0_amount,
+            token_0_amount * Q32 / token_1_amount,
         )
     }
+
+    /// Whether the pool trades on its real reserves offset by virtual reserves
+    pub fn is_boosted(&self) -> bool {
+        self.virtual_reserve_multiplier != 0
+    }
+
+    /// Get the amounts the curve trades on, the real reserves plus the virtual reserves
+    pub fn curve_amounts(&self, token_0_amount: u64, token_1_amount: u64) -> (u128, u128) {
+        (
+            u128::from(token_0_amount) + u128::from(self.virtual_token_0_amount),
+            u128::from(token_1_amount) + u128::from(self.virtual_token_1_amount),
+        )
+    }
+
+    /// Get the amounts whose ratio is the spot price of the curve amounts at
+    /// `block_timestamp`. The spot price of token_0 in token_1 of a weighted pool is
+    /// `(token_1_amount / w_1) / (token_0_amount / w_0)`, with the weights of a running ramp
+    pub fn price_amounts(
+        &self,
+        token_0_amount: u128,
+        token_1_amount: u128,
+        block_timestamp: u64,
+    ) -> (u128, u128) {
+        if self.curve_type == CurveType::WEIGHTED {
+            let token_0_weight = u128::from(self.amp_at(block_timestamp));
+            let token_1_weight = u128::from(WEIGHT_DENOMINATOR) - token_0_weight;
+            (
+                token_0_amount * token_1_weight,
+                token_1_amount * token_0_weight,
+            )
+        } else {
+            (token_0_amount, token_1_amount)
+        }
+    }
+
+    /// Get the input and output amounts the curve trades on in the trade direction
+    pub fn curve_trade_amounts(
+        &self,
+        trade_direction: TradeDirection,
+        input_token_amount: u64,
+        output_token_amount: u64,
+    ) -> (u128, u128) {
+        match trade_direction {
+            TradeDirection::ZeroForOne => {
+                self.curve_amounts(input_token_amount, output_token_amount)
+            }
+            TradeDirection::OneForZero => {
+                let (output_token_amount, input_token_amount) =
+                    self.curve_amounts(output_token_amount, input_token_amount);
+                (input_token_amount, output_token_amount)
+            }
+        }
+    }
+
+    /// Set the virtual reserve multiplier and re-center the virtual reserves around the
+    /// current curve price, 0 trades on the real reserves only
+    pub fn set_virtual_reserve_multiplier(
+        &mut self,
+        multiplier: u64,
+        token_0_amount: u64,
+        token_1_amount: u64,
+    ) -> Result<()> {
+        let (virtual_token_0_amount, virtual_token_1_amount) = if multiplier == 0 {
+            (0, 0)
+        } else {
+            CurveCalculator::virtual_reserves(
+                multiplier,
+                u128::from(token_0_amount),
+                u128::from(token_1_amount),
+                u128::from(self.virtual_token_0_amount),
+                u128::from(self.virtual_token_1_amount),
+            )?
+        };
+        self.virtual_reserve_multiplier = multiplier;
+        self.virtual_token_0_amount = u64::try_from(virtual_token_0_amount)
+            .map_err(|_| crate::error::ErrorCode::InvalidInput)?;
+        self.virtual_token_1_amount = u64::try_from(virtual_token_1_amount)
+            .map_err(|_| crate::error::ErrorCode::InvalidInput)?;
+        Ok(())
+    }
+
+    /// Set the lp supply, scaling the virtual reserves with it so proportional
+    /// deposits and withdrawals keep the curve price
+    pub fn set_lp_supply(&mut self, lp_supply: u64) {
+        let lp_supply_before = self.lp_supply;
+        if self.is_boosted() && lp_supply_before != 0 {
+            let scale = |amount: u64| {
+                u64::try_from(
+                    u128::from(amount) * u128::from(lp_supply) / u128::from(lp_supply_before),
+                )
+                .unwrap()
+            };
+            self.virtual_token_0_amount = scale(self.virtual_token_0_amount);
+            self.virtual_token_1_amount = scale(self.virtual_token_1_amount);
+        }
+        self.lp_supply = lp_supply;
+    }
     
     /// Get the pool authority based on authority type
     pub fn get_pool_authority(&self, program_id: &Pubkey) -> Pubkey {
@@ -178,10 +1700,7 @@ impl PoolState {
             self.custom_authority
         } else {
             // Use the default PDA authority
-            Pubkey::find_program_address(
-                &[crate::AUTH_SEED.as_bytes()],
-                program_id
-            ).0
+            Pubkey::find_program_address(&[crate::AUTH_SEED.as_bytes()], program_id).0
         }
     }
     
@@ -189,17 +1708,849 @@ impl PoolState {
     pub fn is_custom_authority(&self) -> bool {
         self.authority_type == 1
     }
+
+    /// Transfer the custom authority, the default pubkey renounces it for good: the pool
+    /// reverts to the default PDA authority and its liquidity is open to anyone
+    pub fn set_custom_authority(&mut self, custom_authority: Pubkey) {
+        if custom_authority == Pubkey::default() {
+            self.authority_type = 0;
+            self.custom_auth_bump = self.auth_bump;
+        }
+        self.custom_authority = custom_authority;
+    }
+
+    /// Get the curve the pool trades on at `block_timestamp`, with the ramped amplification
+    /// coefficient
+    pub fn curve(&self, block_timestamp: u64) -> Result<CurveType> {
+        CurveType::from_raw(self.curve_type, self.amp_at(block_timestamp))
+            .ok_or(crate::error::ErrorCode::InvalidCurveType.into())
+    }
+}
+
+/// Interpolate linearly from `start_value` at `start_time` to `target_value` reached
+/// `duration` seconds later
+fn ramp_value(
+    start_value: u64,
+    target_value: u64,
+    start_time: u64,
+    duration: u32,
+    block_timestamp: u64,
+) -> u64 {
+    let elapsed = block_timestamp.saturating_sub(start_time);
+    if elapsed >= u64::from(duration) {
+        return target_value;
+    }
+    let moved = |from: u64, to: u64| -> u64 {
+        (u128::from(to - from) * u128::from(elapsed) / u128::from(duration)) as u64
+    };
+    if target_value >= start_value {
+        start_value + moved(start_value, target_value)
+    } else {
+        start_value - moved(target_value, start_value)
+    }
 }
 
 #[cfg(test)]
 pub mod pool_test {
     use super::*;
+    use crate::error::ErrorCode;
+    use crate::utils::validate_custom_authority_signer;
 
     #[test]
     fn pool_state_size_test() {
         assert_eq!(std::mem::size_of::<PoolState>(), PoolState::LEN - 8)
     }
 
+    #[test]
+    fn lp_position_value_test() {
+        let pool_state = PoolState {
+            lp_supply: 1000,
+            protocol_fees_token_0: 100,
+            fund_fees_token_1: 50,
+            ..Default::default()
+        };
+        let value = pool_state.lp_position_value(250, 4100, 2050).unwrap();
+        // the fees owed by the pool are not part of the liquidity
+        assert_eq!(value.token_0_amount, 1000);
+        assert_eq!(value.token_1_amount, 500);
+        assert_eq!(value.lp_supply, 1000);
+        assert_eq!(
+            pool_state.lp_position_value(3, 4100, 2050).unwrap(),
+            LpPositionValue {
+                lp_amount: 3,
+                lp_supply: 1000,
+                token_0_amount: 12,
+                token_1_amount: 6,
+            }
+        );
+        assert!(pool_state.lp_position_value(1001, 4100, 2050).is_none());
+    }
+
+    #[test]
+    fn check_accounting_test() {
+        let mut pool_state = PoolState {
+            lp_supply: 1000,
+            protocol_fees_token_0: 100,
+            creator_fees_token_1: 50,
+            ..Default::default()
+        };
+        assert!(pool_state.check_accounting(100, 50, 1000).is_ok());
+        assert_eq!(
+            pool_state.check_accounting(99, 50, 1000).unwrap_err(),
+            ErrorCode::PoolAccountingViolated.into()
+        );
+        assert_eq!(
+            pool_state.check_accounting(100, 50, 1001).unwrap_err(),
+            ErrorCode::PoolAccountingViolated.into()
+        );
+        // the amounts lent to the strategy still back the fees
+        pool_state.strategy_borrowed_0 = 10;
+        assert!(pool_state.check_accounting(90, 50, 900).is_ok());
+        assert_eq!(
+            pool_state
+                .reserves_invariant(1100, 2050, AmountScale::ONE, 0)
+                .unwrap(),
+            1010 * 2000
+        );
+    }
+
+    #[test]
+    fn lbp_test() {
+        let mut pool_state = PoolState {
+            curve_type: CurveType::WEIGHTED,
+            amp: 8_000,
+            open_time: 1000,
+            ..Default::default()
+        };
+        let day = u64::from(MIN_RAMP_DURATION);
+        assert_eq!(
+            pool_state
+                .start_lbp(9_900, MIN_RAMP_DURATION, 0)
+                .unwrap_err(),
+            ErrorCode::InvalidRamp.into()
+        );
+        assert_eq!(
+            pool_state
+                .start_lbp(9_500, MIN_RAMP_DURATION, 1000)
+                .unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+        pool_state
+            .start_lbp(9_500, MIN_RAMP_DURATION * 2, 0)
+            .unwrap();
+        assert!(pool_state.is_lbp());
+        assert_eq!(pool_state.lbp_end_time(), 1000 + day * 2);
+        // the weights hold until the open time and then decay to 50/50
+        assert_eq!(pool_state.amp_at(0), 9_500);
+        assert_eq!(pool_state.amp_at(1000 + day), 7_250);
+        assert_eq!(
+            pool_state.price_amounts(1000, 1000, 1000 + day),
+            (1000 * 2_750, 1000 * 7_250)
+        );
+        assert_eq!(
+            pool_state.curve(1000 + day * 2).unwrap(),
+            CurveType::from_raw(CurveType::WEIGHTED, 5_000).unwrap()
+        );
+        // the amp of a weighted pool does not ramp otherwise
+        assert_eq!(
+            pool_state
+                .ramp_amp(100, 1000 + day, MIN_RAMP_DURATION)
+                .unwrap_err(),
+            ErrorCode::InvalidCurveType.into()
+        );
+
+        assert_eq!(
+            pool_state.finalize_lbp(1000 + day * 2 - 1).unwrap_err(),
+            ErrorCode::LbpNotEnded.into()
+        );
+        pool_state.finalize_lbp(1000 + day * 2).unwrap();
+        assert!(!pool_state.is_lbp());
+        assert_eq!(pool_state.curve(0).unwrap(), CurveType::ConstantProduct);
+        assert_eq!(
+            pool_state.finalize_lbp(1000 + day * 2).unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+    }
+
+    #[test]
+    fn ramp_pool_parameter_test() {
+        let mut pool_state = PoolState {
+            curve_type: CurveType::STABLE_SWAP,
+            amp: 100,
+            ..Default::default()
+        };
+        let day = u64::from(MIN_RAMP_DURATION);
+        pool_state
+            .ramp_amp(200, 1000, MIN_RAMP_DURATION * 2)
+            .unwrap();
+        assert_eq!(pool_state.amp_at(1000), 100);
+        assert_eq!(pool_state.amp_at(1000 + day), 150);
+        assert_eq!(pool_state.amp_at(1000 + day * 2), 200);
+        assert_eq!(pool_state.amp_at(1000 + day * 3), 200);
+        // a new ramp starts from the running value and no sooner than a day later
+        assert_eq!(
+            pool_state
+                .ramp_amp(100, 1000 + day - 1, MIN_RAMP_DURATION)
+                .unwrap_err(),
+            ErrorCode::InvalidRamp.into()
+        );
+        pool_state
+            .ramp_amp(50, 1000 + day, MIN_RAMP_DURATION)
+            .unwrap();
+        assert_eq!(pool_state.amp_at(1000 + day + day / 2), 100);
+        assert_eq!(
+            pool_state
+                .ramp_amp(501, 1000 + day * 3, MIN_RAMP_DURATION)
+                .unwrap_err(),
+            ErrorCode::InvalidRamp.into()
+        );
+        assert_eq!(
+            pool_state
+                .ramp_amp(100, 1000 + day * 3, MIN_RAMP_DURATION - 1)
+                .unwrap_err(),
+            ErrorCode::InvalidRamp.into()
+        );
+        pool_state.curve_type = CurveType::CONSTANT_PRODUCT;
+        assert_eq!(
+            pool_state
+                .ramp_amp(100, 1000 + day * 3, MIN_RAMP_DURATION)
+                .unwrap_err(),
+            ErrorCode::InvalidCurveType.into()
+        );
+
+        assert_eq!(pool_state.trade_fee_rate_override(0), None);
+        pool_state
+            .ramp_trade_fee_rate(2500, 500, 1000, MIN_RAMP_DURATION)
+            .unwrap();
+        assert_eq!(pool_state.trade_fee_rate_override(1000), Some(2500));
+        assert_eq!(
+            pool_state.trade_fee_rate_override(1000 + day / 4),
+            Some(2000)
+        );
+        assert_eq!(pool_state.trade_fee_rate_override(1000 + day), Some(500));
+        // setting the override cancels the ramp
+        pool_state.set_trade_fee_rate_override(Some(3000));
+        assert_eq!(pool_state.trade_fee_rate_override(1000), Some(3000));
+    }
+
+    #[test]
+    fn migrate_v1_pool_state_test() {
+        let mut pool_state = PoolState {
+            amm_config: Pubkey::new_unique(),
+            lp_mint: Pubkey::new_unique(),
+            custom_authority: Pubkey::new_unique(),
+            custom_auth_bump: 254,
+            lp_supply: 1000,
+            recent_epoch: 7,
+            ..Default::default()
+        };
+        pool_state.version = 0;
+        // a v1 account reallocated to the current size
+        let mut data = bytemuck::bytes_of(&pool_state)[..POOL_STATE_V1_LEN - 8].to_vec();
+        data.resize(PoolState::LEN - 8, 0);
+        let mut migrated = *bytemuck::from_bytes::<PoolState>(&data);
+        assert_eq!({ migrated.version }, 0);
+        migrated.migrate().unwrap();
+        assert_eq!({ migrated.version }, POOL_STATE_VERSION);
+        assert_eq!(migrated.amm_config, pool_state.amm_config);
+        assert_eq!(migrated.lp_mint, pool_state.lp_mint);
+        assert_eq!(migrated.custom_authority, pool_state.custom_authority);
+        assert_eq!(migrated.custom_auth_bump, 254);
+        assert_eq!({ migrated.lp_supply }, 1000);
+        assert_eq!({ migrated.recent_epoch }, 7);
+        assert_eq!(migrated.curve_type, 0);
+        assert!(migrated.migrate().is_err());
+    }
+
+    #[test]
+    fn record_swap_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.lp_supply = 1_000;
+        let result = SwapResult {
+            new_swap_source_amount: 1_100,
+            new_swap_destination_amount: 910,
+            source_amount_swapped: 100,
+            destination_amount_swapped: 90,
+            trade_fee: 10,
+            protocol_fee: 2,
+            fund_fee: 1,
+            fee_on_output: false,
+        };
+        let day = METRICS_BUCKET_DURATION;
+        pool_state.record_swap(TradeDirection::ZeroForOne, &result, 2, day * 10);
+        // 5 of the trade fee goes to 1_000 lp tokens
+        assert_eq!({ pool_state.fee_growth_0_x64 }, (5u128 << 64) / 1_000);
+        assert_eq!({ pool_state.fee_growth_1_x64 }, 0);
+
+        pool_state.record_swap(TradeDirection::OneForZero, &result, 0, day * 10 + 1);
+        let metrics = pool_state.metrics(day * 10 + 2);
+        assert_eq!(metrics.volume_token_0, 190);
+        assert_eq!(metrics.volume_token_1, 190);
+        assert_eq!(metrics.bucket_start_time, day * 10);
+        assert_eq!(metrics.bucket_volume_token_0, 190);
+        assert_eq!(metrics.previous_bucket_volume_token_0, 0);
+
+        // the next bucket starts empty, then the buckets are stale
+        let metrics = pool_state.metrics(day * 11);
+        assert_eq!(metrics.bucket_volume_token_0, 0);
+        assert_eq!(metrics.previous_bucket_volume_token_0, 190);
+        let metrics = pool_state.metrics(day * 12);
+        assert_eq!(metrics.previous_bucket_volume_token_0, 0);
+        assert_eq!(metrics.volume_token_0, 190);
+    }
+
+    #[test]
+    fn accrue_creator_fee_test() {
+        let mut pool_state = PoolState::default();
+        assert_eq!(
+            pool_state.accrue_creator_fee(TradeDirection::ZeroForOne, 1_000),
+            0
+        );
+
+        // 20% of the trade fee
+        pool_state.creator_fee_rate = 200_000;
+        assert_eq!(
+            pool_state.accrue_creator_fee(TradeDirection::ZeroForOne, 1_000),
+            200
+        );
+        assert_eq!(
+            pool_state.accrue_creator_fee(TradeDirection::OneForZero, 99),
+            19
+        );
+        assert_eq!({ pool_state.creator_fees_token_0 }, 200);
+        assert_eq!({ pool_state.creator_fees_token_1 }, 19);
+        // the creator fees in the vaults are not part of the reserves
+        pool_state.protocol_fees_token_0 = 100;
+        assert_eq!(
+            pool_state.vault_amount_without_fee(1_000, 1_000),
+            (700, 981)
+        );
+    }
+
+    fn signer(key: Pubkey) -> Signer<'static> {
+        let account_info = AccountInfo::new(
+            Box::leak(Box::new(key)),
+            true,
+            false,
+            Box::leak(Box::new(0)),
+            &mut [],
+            Box::leak(Box::new(Pubkey::default())),
+            false,
+            0,
+        );
+        Signer::try_from(Box::leak(Box::new(account_info))).unwrap()
+    }
+
+    #[test]
+    fn set_custom_authority_test() {
+        let old_authority = Pubkey::new_unique();
+        let new_authority = Pubkey::new_unique();
+        let mut pool_state = PoolState {
+            authority_type: 1,
+            custom_authority: old_authority,
+            auth_bump: 253,
+            ..Default::default()
+        };
+        assert!(validate_custom_authority_signer(&pool_state, &signer(old_authority)).is_ok());
+
+        pool_state.set_custom_authority(new_authority);
+        assert!(pool_state.is_custom_authority());
+        assert!(validate_custom_authority_signer(&pool_state, &signer(old_authority)).is_err());
+        assert!(validate_custom_authority_signer(&pool_state, &signer(new_authority)).is_ok());
+
+        // renounced, the pool uses the default PDA authority and any signer is accepted
+        pool_state.set_custom_authority(Pubkey::default());
+        assert!(!pool_state.is_custom_authority());
+        assert_eq!(pool_state.custom_auth_bump, 253);
+        assert!(validate_custom_authority_signer(&pool_state, &signer(new_authority)).is_ok());
+        assert_eq!(
+            pool_state.get_pool_authority(&crate::ID),
+            Pubkey::find_program_address(&[crate::AUTH_SEED.as_bytes()], &crate::ID).0
+        );
+    }
+
+    #[test]
+    fn check_launch_trade_test() {
+        let mut pool_state = PoolState::default();
+        assert!(pool_state
+            .check_launch_trade(TradeDirection::ZeroForOne, false)
+            .is_ok());
+
+        pool_state.launch_mode = LaunchMode::BuyToken0 as u8;
+        assert!(pool_state
+            .check_launch_trade(TradeDirection::OneForZero, false)
+            .is_ok());
+        assert!(pool_state
+            .check_launch_trade(TradeDirection::ZeroForOne, true)
+            .is_err());
+
+        pool_state.launch_mode = LaunchMode::BuyToken1 as u8;
+        assert!(pool_state
+            .check_launch_trade(TradeDirection::ZeroForOne, false)
+            .is_ok());
+        assert!(pool_state
+            .check_launch_trade(TradeDirection::OneForZero, false)
+            .is_err());
+
+        pool_state.launch_mode = LaunchMode::Whitelist as u8;
+        assert!(pool_state
+            .check_launch_trade(TradeDirection::OneForZero, true)
+            .is_ok());
+        assert!(pool_state
+            .check_launch_trade(TradeDirection::OneForZero, false)
+            .is_err());
+    }
+
+    #[test]
+    fn check_trader_registered_test() {
+        let mut pool_state = PoolState::default();
+        assert!(pool_state.check_trader_registered(false).is_ok());
+
+        pool_state.trader_registry = Pubkey::new_unique();
+        assert!(pool_state.check_trader_registered(true).is_ok());
+        assert!(pool_state.check_trader_registered(false).is_err());
+    }
+
+    #[test]
+    fn trading_hours_test() {
+        let mut pool_state = PoolState::default();
+        // Monday 2024-01-01 00:00 UTC
+        let monday = 1_704_067_200;
+        assert!(pool_state.is_within_trading_hours(monday));
+
+        pool_state.trading_start_time = monday + 10;
+        pool_state.trading_end_time = monday + 20;
+        assert!(!pool_state.is_within_trading_hours(monday + 9));
+        assert!(pool_state.is_within_trading_hours(monday + 10));
+        assert!(!pool_state.is_within_trading_hours(monday + 20));
+        assert!(pool_state.check_trading_hours(monday + 20).is_err());
+
+        // weekdays from 09:30 to 16:00
+        pool_state.trading_start_time = 0;
+        pool_state.trading_end_time = 0;
+        pool_state.trading_days = 0b1_1111;
+        pool_state.trading_day_open = 9 * 3600 + 1800;
+        pool_state.trading_day_close = 16 * 3600;
+        assert!(!pool_state.is_within_trading_hours(monday + 9 * 3600));
+        assert!(pool_state.is_within_trading_hours(monday + 9 * 3600 + 1800));
+        assert!(!pool_state.is_within_trading_hours(monday + 16 * 3600));
+        let friday = monday + 4 * SECONDS_PER_DAY;
+        assert!(pool_state.is_within_trading_hours(friday + 12 * 3600));
+        let saturday = friday + SECONDS_PER_DAY;
+        assert!(!pool_state.is_within_trading_hours(saturday + 12 * 3600));
+        assert!(pool_state.check_trading_hours(friday + 12 * 3600).is_ok());
+    }
+
+    #[test]
+    fn check_swap_allowed_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.open_time = 100;
+        assert!(pool_state.check_swap_allowed(99).is_err());
+        assert!(pool_state.check_swap_allowed(100).is_ok());
+
+        pool_state.trading_end_time = 200;
+        assert!(pool_state.check_swap_allowed(200).is_err());
+        pool_state.trading_end_time = 0;
+        pool_state.flash_loan_amount_0 = 1;
+        assert!(pool_state.check_swap_allowed(100).is_err());
+        pool_state.flash_loan_amount_0 = 0;
+        pool_state.circuit_breaker_tripped = 1;
+        assert!(pool_state.check_swap_allowed(100).is_err());
+        pool_state.circuit_breaker_tripped = 0;
+        pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
+        assert!(pool_state.check_swap_allowed(100).is_err());
+    }
+
+    #[test]
+    fn check_trade_size_test() {
+        let mut pool_state = PoolState::default();
+        let result = SwapResult {
+            new_swap_source_amount: 1_100,
+            new_swap_destination_amount: 910,
+            source_amount_swapped: 100,
+            destination_amount_swapped: 90,
+            trade_fee: 10,
+            protocol_fee: 2,
+            fund_fee: 1,
+            fee_on_output: false,
+        };
+        assert!(pool_state
+            .check_trade_size(TradeDirection::ZeroForOne, &result)
+            .is_ok());
+
+        // token_0 is the input of a zero for one swap and the output otherwise
+        pool_state.min_trade_amount = 95;
+        assert!(pool_state
+            .check_trade_size(TradeDirection::ZeroForOne, &result)
+            .is_ok());
+        assert!(pool_state
+            .check_trade_size(TradeDirection::OneForZero, &result)
+            .is_err());
+
+        pool_state.min_trade_amount = 0;
+        pool_state.max_trade_amount = 95;
+        assert!(pool_state
+            .check_trade_size(TradeDirection::ZeroForOne, &result)
+            .is_err());
+        assert!(pool_state
+            .check_trade_size(TradeDirection::OneForZero, &result)
+            .is_ok());
+    }
+
+    #[test]
+    fn early_exit_fee_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.early_exit_fee_bps = 100;
+        // no lockup, no fee
+        assert_eq!(pool_state.early_exit_fee(10_000, 50, 100), 0);
+
+        pool_state.min_lock_duration = 86_400;
+        assert_eq!(pool_state.early_exit_fee(10_000, 100, 100), 100);
+        // only the locked share of the withdrawn lp tokens pays the fee
+        assert_eq!(pool_state.early_exit_fee(10_000, 50, 100), 50);
+        assert_eq!(pool_state.early_exit_fee(10_000, 0, 100), 0);
+        assert_eq!(pool_state.early_exit_fee(10_000, 200, 100), 100);
+        assert_eq!(pool_state.early_exit_fee(u64::MAX, 1, 1), u64::MAX / 100);
+    }
+
+    #[test]
+    fn buyback_amount_test() {
+        let mut pool_state = PoolState::default();
+        // no cap, all the fund fees are spent
+        assert_eq!(pool_state.buyback_amount(500, 0, 1), 500);
+
+        assert_eq!(pool_state.buyback_amount(500, 300, 1), 300);
+        pool_state.record_buyback(300, 1);
+        assert_eq!(pool_state.buyback_amount(500, 300, 1), 0);
+        // the cap resets in the next epoch
+        assert_eq!(pool_state.buyback_amount(500, 300, 2), 300);
+        pool_state.record_buyback(100, 2);
+        assert_eq!({ pool_state.buyback_epoch_amount }, 100);
+        assert_eq!(pool_state.buyback_amount(500, 300, 2), 200);
+    }
+
+    #[test]
+    fn lp_fee_rebate_test() {
+        let mut pool_state = PoolState::default();
+        let reserves = PoolReserves::new(1_000_000, 4_000_000, 2_000_000);
+        // a constant product pool is worth twice its reserve of the fee token, the rebate of
+        // 1_000 token_0 is a 1_000 / 2_000_000 share of the pool
+        assert_eq!(
+            pool_state.lp_fee_rebate_lp_amount(TradeDirection::ZeroForOne, 1_000, &reserves, 0),
+            1_000
+        );
+        assert_eq!(
+            pool_state.lp_fee_rebate_lp_amount(TradeDirection::OneForZero, 4_000, &reserves, 0),
+            1_000
+        );
+        assert_eq!(
+            pool_state.lp_fee_rebate_lp_amount(TradeDirection::ZeroForOne, 0, &reserves, 0),
+            0
+        );
+        // a 20/80 weighted pool is worth 5 times its token_0 reserve
+        pool_state.curve_type = CurveType::WEIGHTED;
+        pool_state.amp = WEIGHT_DENOMINATOR / 5;
+        assert_eq!(
+            pool_state.lp_fee_rebate_lp_amount(TradeDirection::ZeroForOne, 1_000, &reserves, 0),
+            400
+        );
+
+        // no cap, all the lp tokens are minted
+        assert_eq!(pool_state.lp_fee_rebate_amount(500, 0, 1), 500);
+        assert_eq!(pool_state.lp_fee_rebate_amount(500, 300, 1), 300);
+        pool_state.record_lp_fee_rebate(300, 1);
+        assert_eq!(pool_state.lp_fee_rebate_amount(500, 300, 1), 0);
+        // the cap resets in the next epoch
+        assert_eq!(pool_state.lp_fee_rebate_amount(500, 300, 2), 300);
+        pool_state.record_lp_fee_rebate(100, 2);
+        assert_eq!({ pool_state.lp_fee_rebate_epoch_amount }, 100);
+    }
+
+    #[test]
+    fn trip_circuit_breaker_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.circuit_breaker_window = 600;
+        pool_state.circuit_breaker_threshold_bps = 500;
+        let twap = 100 * Q32;
+        assert_eq!(
+            pool_state.trip_circuit_breaker(twap, 105 * Q32, 10_000),
+            None
+        );
+        assert!(!pool_state.is_circuit_breaker_tripped());
+        assert_eq!(
+            pool_state.trip_circuit_breaker(twap, 94 * Q32, 10_000),
+            Some(600)
+        );
+        assert!(pool_state.is_circuit_breaker_tripped());
+
+        // idle for a window after the unpause
+        pool_state.unpause_circuit_breaker(10_000);
+        assert!(!pool_state.is_circuit_breaker_tripped());
+        assert_eq!(
+            pool_state.trip_circuit_breaker(twap, 94 * Q32, 10_599),
+            None
+        );
+        assert_eq!(
+            pool_state.trip_circuit_breaker(twap, 110 * Q32, 10_600),
+            Some(1_000)
+        );
+    }
+
+    #[test]
+    fn reentrancy_lock_test() {
+        let mut pool_state = PoolState::default();
+        // a swap holds the pool while it transfers, a hook calling swap_base_input again
+        // from the transfer fails to take the lock
+        pool_state.lock().unwrap();
+        assert!(pool_state.is_locked());
+        assert!(pool_state.lock().is_err());
+        assert!(pool_state.is_locked());
+
+        // the next instruction takes it again once the swap released it
+        pool_state.unlock();
+        assert!(!pool_state.is_locked());
+        pool_state.lock().unwrap();
+    }
+
+    #[test]
+    fn strategy_borrow_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.strategy_max_borrow_bps = 2_000;
+        // at most 20% of each reserve, including what is already borrowed
+        pool_state.strategy_borrow(100, 0, 1_000, 1_000).unwrap();
+        pool_state.strategy_borrow(100, 200, 1_000, 1_000).unwrap();
+        assert!(pool_state.strategy_borrow(1, 0, 1_000, 1_000).is_err());
+        assert_eq!({ pool_state.strategy_borrowed_0 }, 200);
+        assert_eq!({ pool_state.strategy_borrowed_1 }, 200);
+
+        // the borrowed reserves keep counting in the pool amounts
+        assert_eq!(
+            pool_state.vault_amount_without_fee(800, 800),
+            (1_000, 1_000)
+        );
+
+        // a partial repay, then a repay above the borrow leaves the rest as yield
+        assert_eq!(pool_state.strategy_repay(150, 0), (0, 0));
+        assert_eq!(pool_state.strategy_repay(60, 210), (10, 10));
+        assert!(!pool_state.is_strategy_borrowed());
+    }
+
+    #[test]
+    fn vault_outflow_excludes_the_lent_reserves_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.strategy_max_borrow_bps = 5_000;
+        pool_state.protocol_fees_token_0 = 30;
+        pool_state.fund_fees_token_1 = 20;
+        pool_state.strategy_borrow(400, 0, 970, 980).unwrap();
+        // the pool amounts still hold the lent reserves, the vaults only what is left of them
+        assert_eq!(pool_state.vault_amount_without_fee(600, 1_000), (970, 980));
+        assert!(pool_state.check_vault_outflow(600, 1_000, 570, 980).is_ok());
+        assert_eq!(
+            pool_state
+                .check_vault_outflow(600, 1_000, 571, 0)
+                .unwrap_err(),
+            crate::error::ErrorCode::StrategyReservesLent.into()
+        );
+        assert!(pool_state.check_vault_outflow(600, 1_000, 0, 981).is_err());
+        assert!(pool_state
+            .check_swap_outflow(TradeDirection::OneForZero, 600, 1_000, 570)
+            .is_ok());
+        assert!(pool_state
+            .check_swap_outflow(TradeDirection::OneForZero, 600, 1_000, 571)
+            .is_err());
+        assert!(pool_state
+            .check_swap_outflow(TradeDirection::ZeroForOne, 600, 1_000, 980)
+            .is_ok());
+    }
+
+    #[test]
+    fn derived_swap_exclusions_test() {
+        let mut pool_state = PoolState::default();
+        let mut amm_config = AmmConfig::default();
+        assert!(pool_state.check_derived_swap_allowed(&amm_config).is_ok());
+        amm_config.fee_on_output = true;
+        assert_eq!(
+            pool_state
+                .check_derived_swap_allowed(&amm_config)
+                .unwrap_err(),
+            crate::error::ErrorCode::FeeOnOutputNotSupported.into()
+        );
+        pool_state.trader_registry = Pubkey::new_unique();
+        assert_eq!(
+            pool_state
+                .check_derived_swap_allowed(&amm_config)
+                .unwrap_err(),
+            crate::error::ErrorCode::TraderNotRegistered.into()
+        );
+        pool_state.price_oracle = Pubkey::new_unique();
+        assert_eq!(
+            pool_state
+                .check_derived_swap_allowed(&amm_config)
+                .unwrap_err(),
+            crate::error::ErrorCode::NotApproved.into()
+        );
+
+        assert!(pool_state.check_withdraw_without_receipt().is_ok());
+        assert!(pool_state.check_deposit_without_receipt().is_ok());
+        pool_state.min_lock_duration = 60;
+        assert_eq!(
+            pool_state.check_withdraw_without_receipt().unwrap_err(),
+            crate::error::ErrorCode::WithdrawalLockup.into()
+        );
+        assert_eq!(
+            pool_state.check_deposit_without_receipt().unwrap_err(),
+            crate::error::ErrorCode::DepositReceiptRequired.into()
+        );
+    }
+
+    #[test]
+    fn fee_split_override_test() {
+        let mut pool_state = PoolState::default();
+        let amm_config = AmmConfig {
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+            ..Default::default()
+        };
+        assert_eq!(pool_state.fee_split(&amm_config), (120_000, 40_000));
+
+        pool_state.set_fee_split_override(Some((0, 250_000)));
+        assert_eq!(pool_state.fee_split(&amm_config), (0, 250_000));
+
+        pool_state.set_fee_split_override(None);
+        assert_eq!(pool_state.fee_split(&amm_config), (120_000, 40_000));
+    }
+
+    #[test]
+    fn price_slippage_test() {
+        // 2 token_1 per token_0 within 1%
+        let price_slippage = PriceSlippage {
+            expected_price_x64: 2 << 64,
+            max_price_deviation_bps: 100,
+        };
+        assert!(price_slippage.check(1_000_000, 2_000_000).is_ok());
+        assert!(price_slippage.check(1_000_000, 2_020_000).is_ok());
+        assert!(price_slippage.check(1_000_000, 1_980_000).is_ok());
+        assert_eq!(
+            price_slippage.check(1_000_000, 2_020_001).unwrap_err(),
+            crate::error::ErrorCode::ExceededSlippage.into()
+        );
+        assert_eq!(
+            price_slippage.check(1_000_000, 1_979_999).unwrap_err(),
+            crate::error::ErrorCode::ExceededSlippage.into()
+        );
+        assert!(price_slippage.check(0, 2_000_000).is_err());
+    }
+
+    #[test]
+    fn check_oracle_deviation_test() {
+        let mut pool_state = PoolState::default();
+        assert!(!pool_state.is_oracle_guarded());
+        pool_state.price_oracle = Pubkey::new_unique();
+        assert!(pool_state.is_oracle_guarded());
+        pool_state.mint_0_decimals = 6;
+        pool_state.mint_1_decimals = 9;
+        pool_state.oracle_max_deviation_bps = 50;
+        // 1 token_1 per token_0
+        let oracle_price = OraclePrice {
+            price: 100_000_000,
+            exponent: -8,
+            publish_time: 0,
+        };
+        assert!(pool_state
+            .check_oracle_deviation(
+                &oracle_price,
+                TradeDirection::ZeroForOne,
+                1_000_000,
+                996_000_000
+            )
+            .is_ok());
+        assert!(pool_state
+            .check_oracle_deviation(
+                &oracle_price,
+                TradeDirection::ZeroForOne,
+                1_000_000,
+                990_000_000
+            )
+            .is_err());
+        assert!(pool_state
+            .check_oracle_deviation(
+                &oracle_price,
+                TradeDirection::OneForZero,
+                1_010_000_000,
+                1_000_000
+            )
+            .is_err());
+
+        pool_state.oracle_max_deviation_bps = 100;
+        assert!(pool_state
+            .check_oracle_deviation(
+                &oracle_price,
+                TradeDirection::ZeroForOne,
+                1_000_000,
+                990_000_000
+            )
+            .is_ok());
+        assert!(pool_state
+            .check_oracle_deviation(
+                &OraclePrice {
+                    exponent: -40,
+                    ..oracle_price
+                },
+                TradeDirection::ZeroForOne,
+                1_000_000,
+                990_000_000
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn check_auction_size_test() {
+        let mut pool_state = PoolState::default();
+        assert!(!pool_state.is_auction_enabled());
+        assert!(pool_state.check_auction_size(u64::MAX).is_ok());
+
+        pool_state.auction_size_threshold = 1_000;
+        assert!(pool_state.is_auction_enabled());
+        assert!(pool_state.check_auction_size(999).is_ok());
+        assert!(pool_state.check_auction_size(1_000).is_err());
+    }
+
+    #[test]
+    fn check_price_band_test() {
+        let mut pool_state = PoolState::default();
+        let reserves = PoolReserves::new(1000, 1000, 1000);
+        let price_down = PoolReserves::new(1100, 910, 1000);
+        let price_up = PoolReserves::new(910, 1100, 1000);
+        assert!(pool_state
+            .check_price_band(&reserves, &price_down, 0)
+            .is_ok());
+
+        pool_state.min_price_x64 = 9 << 60; // 0.5625
+        pool_state.max_price_x64 = 5 << 62; // 1.25
+        assert!(pool_state
+            .check_price_band(&reserves, &price_down, 0)
+            .is_ok());
+        assert!(pool_state.check_price_band(&reserves, &price_up, 0).is_ok());
+
+        pool_state.min_price_x64 = 15 << 60; // 0.9375
+        assert!(pool_state
+            .check_price_band(&reserves, &price_down, 0)
+            .is_err());
+        // back towards the band
+        assert!(pool_state
+            .check_price_band(&PoolReserves::new(1200, 830, 1000), &price_down, 0)
+            .is_ok());
+
+        pool_state.min_price_x64 = 0;
+        pool_state.max_price_x64 = 17 << 60; // 1.0625
+        assert!(pool_state
+            .check_price_band(&reserves, &price_down, 0)
+            .is_ok());
+        assert!(pool_state
+            .check_price_band(&reserves, &price_up, 0)
+            .is_err());
+    }
+
     mod pool_status_test {
         use super::*;
 
@@ -280,5 +2631,54 @@ pub mod pool_test {
                 false
             );
         }
+
+        #[test]
+        fn set_status_by_bit_index() {
+            let mut pool_state = PoolState::default();
+            // pause deposits only, withdrawals and swaps keep working
+            pool_state.set_status_by_bit(
+                PoolStatusBitIndex::from_u8(0).unwrap(),
+                PoolStatusBitFlag::Disable,
+            );
+            assert_eq!(pool_state.status, 1);
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw),
+                true
+            );
+            assert_eq!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap), true);
+
+            pool_state.set_status_by_bit(
+                PoolStatusBitIndex::from_u8(3).unwrap(),
+                PoolStatusBitFlag::Disable,
+            );
+            assert_eq!(pool_state.status, 9);
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::FlashLoan),
+                false
+            );
+            assert!(PoolStatusBitIndex::from_u8(4).is_none());
+        }
+
+        #[test]
+        fn pause_only_status() {
+            let mut pool_state = PoolState::default();
+            pool_state.set_status(1);
+            assert!(pool_state.is_pause_only(1));
+            assert!(pool_state.is_pause_only(5));
+            assert!(!pool_state.is_pause_only(4));
+            assert!(!pool_state.is_pause_only(0));
+
+            pool_state.pause();
+            assert!(pool_state.is_pause_only(u8::MAX));
+            assert!(!pool_state.is_pause_only(1));
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                false
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::FlashLoan),
+                false
+            );
+        }
     }
 }
@@ -0,0 +1,55 @@
+use crate::states::PoolReserves;
+use anchor_lang::prelude::*;
+
+pub const POOL_SNAPSHOT_SEED: &str = "pool_snapshot";
+
+/// A compact copy of the pool price and reserves refreshed by the swaps passing it, polled
+/// instead of the pool state and its vaults
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolSnapshot {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool of the snapshot
+    pub pool_id: Pubkey,
+    /// The reserves and price of the pool after the last refreshing swap
+    pub reserves: PoolReserves,
+    /// Cumulative liquidity provider fees of token_0 and token_1 per lp token, Q64.64,
+    /// wrapping on overflow so only differences are meaningful
+    pub fee_growth_0_x64: u128,
+    pub fee_growth_1_x64: u128,
+    /// The slot of the last refreshing swap, 0 until the first one
+    pub last_update_slot: u64,
+}
+
+impl PoolSnapshot {
+    pub const LEN: usize = 8 + 1 + 32 + (8 * 3 + 16) + 16 * 2 + 8;
+
+    pub fn refresh(
+        &mut self,
+        reserves: PoolReserves,
+        fee_growth_0_x64: u128,
+        fee_growth_1_x64: u128,
+        slot: u64,
+    ) {
+        self.reserves = reserves;
+        self.fee_growth_0_x64 = fee_growth_0_x64;
+        self.fee_growth_1_x64 = fee_growth_1_x64;
+        self.last_update_slot = slot;
+    }
+}
+
+#[cfg(test)]
+pub mod pool_snapshot_test {
+    use super::*;
+
+    #[test]
+    fn pool_snapshot_size_test() {
+        let pool_snapshot = PoolSnapshot::default();
+        assert_eq!(
+            pool_snapshot.try_to_vec().unwrap().len(),
+            PoolSnapshot::LEN - 8
+        );
+        assert!(PoolSnapshot::LEN < 128);
+    }
+}
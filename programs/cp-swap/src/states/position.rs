@@ -0,0 +1,155 @@
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+pub const PERSONAL_POSITION_SEED: &str = "personal_position";
+
+/// The liquidity of an owner in a pool in position mode, recorded as shares of the lp
+/// supply instead of minted lp tokens so it cannot be transferred
+#[account]
+#[derive(Default, Debug)]
+pub struct PersonalPositionState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the position provides liquidity to
+    pub pool_id: Pubkey,
+    /// The only address allowed to withdraw the position and claim its fees
+    pub owner: Pubkey,
+    /// Shares of the pool lp supply held by the position
+    pub shares: u64,
+    /// The token_0 fee growth of the pool when the fees of the position were last accrued
+    pub fee_growth_0_last_x64: u128,
+    /// The token_1 fee growth of the pool when the fees of the position were last accrued
+    pub fee_growth_1_last_x64: u128,
+    /// The token_0 fees earned by the position and not claimed yet
+    pub fees_owed_token_0: u64,
+    /// The token_1 fees earned by the position and not claimed yet
+    pub fees_owed_token_1: u64,
+}
+
+impl PersonalPositionState {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 + 16 * 2 + 8 * 2;
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        pool_id: Pubkey,
+        owner: Pubkey,
+        fee_growth_0_x64: u128,
+        fee_growth_1_x64: u128,
+    ) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.owner = owner;
+        self.fee_growth_0_last_x64 = fee_growth_0_x64;
+        self.fee_growth_1_last_x64 = fee_growth_1_x64;
+    }
+
+    /// Accrue the fees the shares earned since the last accrual, must be called before the
+    /// shares change
+    pub fn accrue_fees(&mut self, fee_growth_0_x64: u128, fee_growth_1_x64: u128) {
+        let earned = |fee_growth_x64: u128, fee_growth_last_x64: u128| {
+            let fee = (U256::from(fee_growth_x64.wrapping_sub(fee_growth_last_x64))
+                * U256::from(self.shares))
+                >> 64;
+            u64::try_from(fee.low_u128()).unwrap_or(u64::MAX)
+        };
+        self.fees_owed_token_0 = self
+            .fees_owed_token_0
+            .saturating_add(earned(fee_growth_0_x64, self.fee_growth_0_last_x64));
+        self.fees_owed_token_1 = self
+            .fees_owed_token_1
+            .saturating_add(earned(fee_growth_1_x64, self.fee_growth_1_last_x64));
+        self.fee_growth_0_last_x64 = fee_growth_0_x64;
+        self.fee_growth_1_last_x64 = fee_growth_1_x64;
+    }
+
+    /// Get the shares worth the owed fees at the pool reserves, capped at the position
+    /// shares. The fees compound into the shares until they are claimed, so claiming them
+    /// withdraws these shares
+    pub fn fee_shares(&self, lp_supply: u64, token_0_amount: u64, token_1_amount: u64) -> u64 {
+        if token_0_amount == 0 || token_1_amount == 0 {
+            return 0;
+        }
+        // a share is worth both reserves, so the fees of each token are worth half of the
+        // shares they are of the reserve
+        let shares = (U256::from(self.fees_owed_token_0)
+            * U256::from(lp_supply)
+            * U256::from(token_1_amount)
+            + U256::from(self.fees_owed_token_1)
+                * U256::from(lp_supply)
+                * U256::from(token_0_amount))
+            / (U256::from(token_0_amount) * U256::from(token_1_amount) * U256::from(2u8));
+        u64::try_from(shares.min(U256::from(self.shares)).low_u128()).unwrap()
+    }
+
+    /// Remove shares withdrawn from the position, the owed fees are part of the shares so
+    /// they are withdrawn in proportion
+    pub fn remove_shares(&mut self, shares: u64) {
+        let shares_before = self.shares;
+        self.shares = shares_before.checked_sub(shares).unwrap();
+        let remaining = |fees_owed: u64| {
+            u64::try_from(
+                u128::from(fees_owed) * u128::from(self.shares) / u128::from(shares_before),
+            )
+            .unwrap()
+        };
+        self.fees_owed_token_0 = remaining(self.fees_owed_token_0);
+        self.fees_owed_token_1 = remaining(self.fees_owed_token_1);
+    }
+}
+
+#[cfg(test)]
+pub mod position_test {
+    use super::*;
+
+    #[test]
+    fn personal_position_size_test() {
+        let position = PersonalPositionState::default();
+        assert_eq!(
+            position.try_to_vec().unwrap().len(),
+            PersonalPositionState::LEN - 8
+        )
+    }
+
+    #[test]
+    fn accrue_fees_test() {
+        let mut position = PersonalPositionState::default();
+        position.initialize(255, Pubkey::default(), Pubkey::default(), 1 << 64, 0);
+        position.shares = 100;
+        // 0.5 token_0 and 0.25 token_1 of fees per share
+        position.accrue_fees(3 << 63, 1 << 62);
+        assert_eq!(position.fees_owed_token_0, 50);
+        assert_eq!(position.fees_owed_token_1, 25);
+        // accruing again at the same growth earns nothing
+        position.accrue_fees(3 << 63, 1 << 62);
+        assert_eq!(position.fees_owed_token_0, 50);
+        assert_eq!(position.fee_growth_1_last_x64, 1 << 62);
+        // the fee growth wraps around
+        position.fee_growth_0_last_x64 = u128::MAX;
+        position.accrue_fees(1 << 64, 1 << 62);
+        assert_eq!(position.fees_owed_token_0, 150);
+    }
+
+    #[test]
+    fn fee_shares_test() {
+        let mut position = PersonalPositionState {
+            shares: 100,
+            fees_owed_token_0: 10,
+            fees_owed_token_1: 40,
+            ..Default::default()
+        };
+        // 1_000 shares of 1_000 token_0 and 4_000 token_1, the fees are worth 20 token_0
+        assert_eq!(position.fee_shares(1_000, 1_000, 4_000), 10);
+        assert_eq!(position.fee_shares(1_000, 0, 4_000), 0);
+        // capped at the position shares
+        position.fees_owed_token_0 = 1_000;
+        assert_eq!(position.fee_shares(1_000, 1_000, 4_000), 100);
+
+        position.remove_shares(40);
+        assert_eq!(position.fees_owed_token_0, 600);
+        assert_eq!(position.fees_owed_token_1, 24);
+        position.remove_shares(60);
+        assert_eq!(position.fees_owed_token_0, 0);
+        assert_eq!(position.fees_owed_token_1, 0);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+pub const RECEIPT_TREE_SEED: &str = "receipt_tree";
+/// Depth of the receipt trees, each holds up to 2^20 compressed receipts
+pub const RECEIPT_TREE_MAX_DEPTH: usize = 20;
+/// Number of the last changes of a receipt tree kept on chain, a proof against any of
+/// their roots is still valid
+pub const RECEIPT_TREE_BUFFER_SIZE: usize = 8;
+/// The leaf of a slot not appended yet or of a claimed receipt
+pub const EMPTY_LEAF: [u8; 32] = [0; 32];
+
+/// A change of a receipt tree, the root after it and the path of the changed leaf
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+pub struct ChangeLog {
+    pub root: [u8; 32],
+    /// The nodes from the changed leaf up to the children of the root
+    pub path: [[u8; 32]; RECEIPT_TREE_MAX_DEPTH],
+    /// The index of the changed leaf
+    pub index: u64,
+}
+impl ChangeLog {
+    pub const LEN: usize = 32 + 32 * RECEIPT_TREE_MAX_DEPTH + 8;
+}
+
+// the packed struct only holds bytes and integers, so any bit pattern is valid
+unsafe impl bytemuck::Zeroable for ChangeLog {}
+unsafe impl bytemuck::Pod for ChangeLog {}
+
+/// A concurrent merkle tree of the compressed receipts of a pool. The receipts are
+/// leaves only committed by the root on chain, their data is emitted on compression and
+/// proven back by the owner on claim, so a receipt costs no rent while compressed.
+///
+/// The last `RECEIPT_TREE_BUFFER_SIZE` changes are kept, so that a proof built against a
+/// root changed since by other transactions of the same slots is fast forwarded instead
+/// of failing.
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct ReceiptTree {
+    pub bump: u8,
+    pub pool_id: Pubkey,
+    /// The number of changes of the tree, `change_logs[sequence_number % RECEIPT_TREE_BUFFER_SIZE]`
+    /// is the last one
+    pub sequence_number: u64,
+    /// The number of appended leaves, the index of the next one
+    pub next_index: u64,
+    /// The nodes on the left of the path of the next leaf, at each level
+    pub filled_subtrees: [[u8; 32]; RECEIPT_TREE_MAX_DEPTH],
+    /// The ring buffer of the last changes
+    pub change_logs: [ChangeLog; RECEIPT_TREE_BUFFER_SIZE],
+    /// padding for feature update
+    pub padding: [u64; 8],
+}
+
+impl ReceiptTree {
+    pub const LEN: usize = 8
+        + 1
+        + 32
+        + 8 * 2
+        + 32 * RECEIPT_TREE_MAX_DEPTH
+        + ChangeLog::LEN * RECEIPT_TREE_BUFFER_SIZE
+        + 8 * 8;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey) {
+        let empty_nodes = empty_nodes();
+        let mut empty_path = [EMPTY_LEAF; RECEIPT_TREE_MAX_DEPTH];
+        empty_path.copy_from_slice(&empty_nodes[..RECEIPT_TREE_MAX_DEPTH]);
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.sequence_number = 0;
+        self.next_index = 0;
+        self.filled_subtrees = empty_path;
+        self.change_logs[0] = ChangeLog {
+            root: empty_nodes[RECEIPT_TREE_MAX_DEPTH],
+            path: empty_path,
+            index: 0,
+        };
+    }
+
+    fn change_log(&self, sequence_number: u64) -> ChangeLog {
+        self.change_logs[(sequence_number % RECEIPT_TREE_BUFFER_SIZE as u64) as usize]
+    }
+
+    /// The current root of the tree
+    pub fn root(&self) -> [u8; 32] {
+        self.change_log(self.sequence_number).root
+    }
+
+    fn push_change_log(
+        &mut self,
+        root: [u8; 32],
+        path: [[u8; 32]; RECEIPT_TREE_MAX_DEPTH],
+        index: u64,
+    ) {
+        self.sequence_number += 1;
+        self.change_logs[(self.sequence_number % RECEIPT_TREE_BUFFER_SIZE as u64) as usize] =
+            ChangeLog { root, path, index };
+    }
+
+    /// Append `leaf` to the tree, returns its index
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let index = self.next_index;
+        require_gt!(
+            1u64 << RECEIPT_TREE_MAX_DEPTH,
+            index,
+            ErrorCode::ReceiptTreeFull
+        );
+        let empty_nodes = empty_nodes();
+        let mut node = leaf;
+        let mut path = [EMPTY_LEAF; RECEIPT_TREE_MAX_DEPTH];
+        for level in 0..RECEIPT_TREE_MAX_DEPTH {
+            path[level] = node;
+            if (index >> level) & 1 == 0 {
+                self.filled_subtrees[level] = node;
+                node = hash_pair(&node, &empty_nodes[level]);
+            } else {
+                node = hash_pair(&self.filled_subtrees[level], &node);
+            }
+        }
+        self.next_index = index + 1;
+        self.push_change_log(node, path, index);
+        Ok(index)
+    }
+
+    /// Replace `previous_leaf` at `index` by `new_leaf`, with the `proof` of the leaf in
+    /// the tree of `root`, the current root or one of the roots of the last changes
+    pub fn replace_leaf(
+        &mut self,
+        root: [u8; 32],
+        previous_leaf: [u8; 32],
+        new_leaf: [u8; 32],
+        proof: &[[u8; 32]],
+        index: u64,
+    ) -> Result<()> {
+        require_gt!(self.next_index, index, ErrorCode::InvalidReceiptProof);
+        let mut proof: [[u8; 32]; RECEIPT_TREE_MAX_DEPTH] = proof
+            .try_into()
+            .map_err(|_| error!(ErrorCode::InvalidReceiptProof))?;
+        let sequence_number = self.sequence_number;
+        let buffered = sequence_number.min(RECEIPT_TREE_BUFFER_SIZE as u64 - 1);
+        let lag = (0..=buffered)
+            .find(|lag| self.change_log(sequence_number - lag).root == root)
+            .ok_or(ErrorCode::InvalidReceiptProof)?;
+        // fast forward the proof through the changes since the root, each changes the node
+        // on the proof at the level the paths of both leaves meet
+        for change_sequence_number in sequence_number - lag + 1..=sequence_number {
+            let change_log = self.change_log(change_sequence_number);
+            let changed_index = change_log.index;
+            // the leaf itself changed since the proof
+            require_neq!(changed_index, index, ErrorCode::InvalidReceiptProof);
+            let level = (u64::BITS - 1 - (changed_index ^ index).leading_zeros()) as usize;
+            proof[level] = change_log.path[level];
+        }
+        require!(
+            compute_path(previous_leaf, &proof, index).0 == self.root(),
+            ErrorCode::InvalidReceiptProof
+        );
+
+        let (new_root, path) = compute_path(new_leaf, &proof, index);
+        // the replaced nodes on the left of the path of the next leaf
+        for (level, node) in path.iter().enumerate() {
+            if index >> level == (self.next_index >> level) ^ 1 {
+                self.filled_subtrees[level] = *node;
+            }
+        }
+        self.push_change_log(new_root, path, index);
+        Ok(())
+    }
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[left.as_ref(), right.as_ref()]).to_bytes()
+}
+
+/// The roots of the empty subtrees of each height, from the empty leaf to the empty tree
+fn empty_nodes() -> [[u8; 32]; RECEIPT_TREE_MAX_DEPTH + 1] {
+    let mut empty_nodes = [EMPTY_LEAF; RECEIPT_TREE_MAX_DEPTH + 1];
+    for level in 0..RECEIPT_TREE_MAX_DEPTH {
+        empty_nodes[level + 1] = hash_pair(&empty_nodes[level], &empty_nodes[level]);
+    }
+    empty_nodes
+}
+
+/// The root of the tree of `leaf` at `index` with its `proof`, and the nodes of its path
+fn compute_path(
+    leaf: [u8; 32],
+    proof: &[[u8; 32]; RECEIPT_TREE_MAX_DEPTH],
+    index: u64,
+) -> ([u8; 32], [[u8; 32]; RECEIPT_TREE_MAX_DEPTH]) {
+    let mut node = leaf;
+    let mut path = [EMPTY_LEAF; RECEIPT_TREE_MAX_DEPTH];
+    for (level, sibling) in proof.iter().enumerate() {
+        path[level] = node;
+        node = if (index >> level) & 1 == 0 {
+            hash_pair(&node, sibling)
+        } else {
+            hash_pair(sibling, &node)
+        };
+    }
+    (node, path)
+}
+
+/// The proof of the leaf at `index` of a receipt tree holding `leaves`, as replayed from
+/// the compression events by an indexer
+pub fn receipt_proof(leaves: &[[u8; 32]], index: usize) -> Vec<[u8; 32]> {
+    let empty_nodes = empty_nodes();
+    let mut nodes = leaves.to_vec();
+    let mut index = index;
+    let mut proof = Vec::with_capacity(RECEIPT_TREE_MAX_DEPTH);
+    for empty_node in &empty_nodes[..RECEIPT_TREE_MAX_DEPTH] {
+        proof.push(*nodes.get(index ^ 1).unwrap_or(empty_node));
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(empty_node)))
+            .collect();
+        index >>= 1;
+    }
+    proof
+}
+
+/// The leaf of a compressed deposit receipt, the index makes every leaf unique
+pub fn deposit_receipt_leaf(
+    pool_id: &Pubkey,
+    owner: &Pubkey,
+    lp_amount: u64,
+    last_deposit_time: u64,
+    leaf_index: u64,
+) -> [u8; 32] {
+    hashv(&[
+        pool_id.as_ref(),
+        owner.as_ref(),
+        &lp_amount.to_le_bytes(),
+        &last_deposit_time.to_le_bytes(),
+        &leaf_index.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+#[cfg(test)]
+pub mod receipt_tree_test {
+    use super::*;
+
+    fn new_tree() -> Box<ReceiptTree> {
+        let mut tree = Box::new(<ReceiptTree as bytemuck::Zeroable>::zeroed());
+        tree.initialize(255, Pubkey::new_unique());
+        tree
+    }
+
+    fn leaf(i: u64) -> [u8; 32] {
+        deposit_receipt_leaf(&Pubkey::default(), &Pubkey::default(), i + 1, i, i)
+    }
+
+    fn root_of(leaves: &[[u8; 32]]) -> [u8; 32] {
+        let proof = receipt_proof(leaves, 0);
+        compute_path(leaves[0], &proof.try_into().unwrap(), 0).0
+    }
+
+    #[test]
+    fn receipt_tree_size_test() {
+        assert_eq!(std::mem::size_of::<ReceiptTree>(), ReceiptTree::LEN - 8)
+    }
+
+    #[test]
+    fn append_matches_the_root_of_the_leaves() {
+        let mut tree = new_tree();
+        assert_eq!(tree.root(), empty_nodes()[RECEIPT_TREE_MAX_DEPTH]);
+        let mut leaves = Vec::new();
+        for i in 0..13 {
+            assert_eq!(tree.append(leaf(i)).unwrap(), i);
+            leaves.push(leaf(i));
+            assert_eq!(tree.root(), root_of(&leaves));
+        }
+    }
+
+    #[test]
+    fn replace_leaf_verifies_the_proof() {
+        let mut tree = new_tree();
+        let mut leaves: Vec<_> = (0..6).map(leaf).collect();
+        for leaf in &leaves {
+            tree.append(*leaf).unwrap();
+        }
+        let root = tree.root();
+        let proof = receipt_proof(&leaves, 3);
+
+        // a wrong leaf, index, proof or unknown root is rejected
+        assert!(tree
+            .replace_leaf(root, leaf(4), EMPTY_LEAF, &proof, 3)
+            .is_err());
+        assert!(tree
+            .replace_leaf(root, leaf(3), EMPTY_LEAF, &proof, 2)
+            .is_err());
+        assert!(tree
+            .replace_leaf(root, leaf(3), EMPTY_LEAF, &proof[1..], 3)
+            .is_err());
+        assert!(tree
+            .replace_leaf([1; 32], leaf(3), EMPTY_LEAF, &proof, 3)
+            .is_err());
+
+        tree.replace_leaf(root, leaf(3), EMPTY_LEAF, &proof, 3)
+            .unwrap();
+        leaves[3] = EMPTY_LEAF;
+        assert_eq!(tree.root(), root_of(&leaves));
+        // the leaf is only claimed once
+        assert!(tree
+            .replace_leaf(root, leaf(3), EMPTY_LEAF, &proof, 3)
+            .is_err());
+
+        // the appends after a replaced leaf on the left of the next leaf hash the new leaf
+        tree.replace_leaf(
+            tree.root(),
+            leaf(5),
+            EMPTY_LEAF,
+            &receipt_proof(&leaves, 5),
+            5,
+        )
+        .unwrap();
+        leaves[5] = EMPTY_LEAF;
+        for i in 6..9 {
+            tree.append(leaf(i)).unwrap();
+            leaves.push(leaf(i));
+        }
+        assert_eq!(tree.root(), root_of(&leaves));
+    }
+
+    #[test]
+    fn replace_leaf_fast_forwards_stale_proofs() {
+        let mut tree = new_tree();
+        let mut leaves: Vec<_> = (0..5).map(leaf).collect();
+        for leaf in &leaves {
+            tree.append(*leaf).unwrap();
+        }
+        let root = tree.root();
+        let proofs: Vec<_> = (0..5).map(|i| receipt_proof(&leaves, i)).collect();
+
+        // all the proofs against the same root are claimed in any order, with appends
+        // in between
+        for (i, index) in [4, 0, 2, 1].into_iter().enumerate() {
+            tree.replace_leaf(
+                root,
+                leaves[index],
+                EMPTY_LEAF,
+                &proofs[index],
+                index as u64,
+            )
+            .unwrap();
+            tree.append(leaf(5 + i as u64)).unwrap();
+        }
+        for index in [4, 0, 2, 1] {
+            leaves[index] = EMPTY_LEAF;
+        }
+        leaves.extend((5..9).map(leaf));
+        assert_eq!(tree.root(), root_of(&leaves));
+
+        // the root of the proof is beyond the buffer of the last changes
+        assert!(tree
+            .replace_leaf(root, leaves[3], EMPTY_LEAF, &proofs[3], 3)
+            .is_err());
+        tree.replace_leaf(
+            tree.root(),
+            leaves[3],
+            EMPTY_LEAF,
+            &receipt_proof(&leaves, 3),
+            3,
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+
+pub const POOL_REGISTRY_SEED: &str = "pool_registry";
+pub const LP_MINT_REGISTRY_SEED: &str = "lp_mint_registry";
+/// Maximum number of pools listed for a mint pair
+pub const MAX_REGISTERED_POOLS: usize = 16;
+
+/// A pool listed in a registry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct RegisteredPool {
+    /// Address of the pool state
+    pub pool_id: Pubkey,
+    /// The amm config the pool belongs to
+    pub amm_config: Pubkey,
+    /// Index of the amm config, identifies the fee tier
+    pub amm_config_index: u16,
+}
+
+/// Lists the pools of a mint pair so routers can discover every fee tier
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolRegistry {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// Mint information for token A
+    pub token_0_mint: Pubkey,
+    /// Mint information for token B
+    pub token_1_mint: Pubkey,
+    /// Number of listed pools
+    pub pool_count: u8,
+    /// The listed pools, only the first `pool_count` are set
+    pub pools: [RegisteredPool; MAX_REGISTERED_POOLS],
+}
+
+impl PoolRegistry {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 1 + (32 * 2 + 2) * MAX_REGISTERED_POOLS;
+
+    pub fn registered_pools(&self) -> &[RegisteredPool] {
+        &self.pools[..usize::from(self.pool_count)]
+    }
+
+    pub fn is_registered(&self, pool_id: &Pubkey) -> bool {
+        self.registered_pools()
+            .iter()
+            .any(|pool| pool.pool_id == *pool_id)
+    }
+
+    /// Append a pool, returns false when it is already listed or the registry is full
+    pub fn add_pool(&mut self, pool: RegisteredPool) -> bool {
+        if self.is_registered(&pool.pool_id) || usize::from(self.pool_count) >= MAX_REGISTERED_POOLS
+        {
+            return false;
+        }
+        self.pools[usize::from(self.pool_count)] = pool;
+        self.pool_count += 1;
+        true
+    }
+
+    /// Remove a pool, the last listed pool takes its place. Returns false when it is not listed
+    pub fn remove_pool(&mut self, pool_id: &Pubkey) -> bool {
+        let Some(index) = self
+            .registered_pools()
+            .iter()
+            .position(|pool| pool.pool_id == *pool_id)
+        else {
+            return false;
+        };
+        let last = usize::from(self.pool_count) - 1;
+        self.pools[index] = self.pools[last];
+        self.pools[last] = RegisteredPool::default();
+        self.pool_count -= 1;
+        true
+    }
+}
+
+/// Maps an lp mint back to its pool, so the holders of an lp token can resolve the pool
+/// on chain
+#[account]
+#[derive(Default, Debug)]
+pub struct LpMintRegistry {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The lp mint of the pool
+    pub lp_mint: Pubkey,
+    /// Address of the pool state
+    pub pool_id: Pubkey,
+}
+
+impl LpMintRegistry {
+    pub const LEN: usize = 8 + 1 + 32 * 2;
+}
+
+#[cfg(test)]
+pub mod registry_test {
+    use super::*;
+
+    #[test]
+    fn pool_registry_size_test() {
+        let registry = PoolRegistry::default();
+        assert_eq!(registry.try_to_vec().unwrap().len(), PoolRegistry::LEN - 8)
+    }
+
+    #[test]
+    fn lp_mint_registry_size_test() {
+        assert_eq!(
+            LpMintRegistry::default().try_to_vec().unwrap().len(),
+            LpMintRegistry::LEN - 8
+        )
+    }
+
+    #[test]
+    fn add_pool_rejects_duplicate_and_overflow() {
+        let mut registry = PoolRegistry::default();
+        let pool = RegisteredPool {
+            pool_id: Pubkey::new_unique(),
+            amm_config: Pubkey::new_unique(),
+            amm_config_index: 0,
+        };
+        assert!(registry.add_pool(pool));
+        assert!(!registry.add_pool(pool));
+        for index in 1..MAX_REGISTERED_POOLS {
+            assert!(registry.add_pool(RegisteredPool {
+                pool_id: Pubkey::new_unique(),
+                amm_config_index: index as u16,
+                ..pool
+            }));
+        }
+        assert_eq!(registry.registered_pools().len(), MAX_REGISTERED_POOLS);
+        assert!(!registry.add_pool(RegisteredPool {
+            pool_id: Pubkey::new_unique(),
+            ..pool
+        }));
+    }
+
+    #[test]
+    fn remove_pool_keeps_other_pools() {
+        let mut registry = PoolRegistry::default();
+        let pools: Vec<RegisteredPool> = (0..3)
+            .map(|index| RegisteredPool {
+                pool_id: Pubkey::new_unique(),
+                amm_config: Pubkey::new_unique(),
+                amm_config_index: index,
+            })
+            .collect();
+        for pool in pools.iter() {
+            assert!(registry.add_pool(*pool));
+        }
+        assert!(registry.remove_pool(&pools[0].pool_id));
+        assert!(!registry.remove_pool(&pools[0].pool_id));
+        assert_eq!(registry.registered_pools(), &[pools[2], pools[1]]);
+        assert_eq!(registry.pools[2], RegisteredPool::default());
+        // the pool can be listed again
+        assert!(registry.add_pool(pools[0]));
+        assert_eq!(registry.registered_pools().len(), 3);
+    }
+}
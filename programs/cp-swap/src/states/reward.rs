@@ -0,0 +1,268 @@
+use crate::error::ErrorCode;
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+pub const REWARD_STATE_SEED: &str = "reward_state";
+pub const REWARD_VAULT_SEED: &str = "reward_vault";
+pub const POSITION_REWARD_SEED: &str = "position_reward";
+/// Maximum number of reward mints emitted to the positions of a pool
+pub const REWARD_NUM: usize = 3;
+
+/// A reward mint emitted to the shares of a pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct RewardInfo {
+    /// The reward mint, the reward is not initialized when it is the default pubkey
+    pub mint: Pubkey,
+    /// Token account holding the funded rewards
+    pub vault: Pubkey,
+    /// Reward tokens emitted per second until `end_time`
+    pub emissions_per_second: u64,
+    /// The timestamp the emission stops at
+    pub end_time: u64,
+    /// Reward tokens emitted per share of the lp supply since the reward started, Q64.64
+    pub growth_global_x64: u128,
+}
+
+impl RewardInfo {
+    pub fn is_initialized(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    /// Get the reward tokens still to emit after `block_timestamp`
+    pub fn remaining_emissions(&self, block_timestamp: u64) -> u64 {
+        let remaining = u128::from(self.emissions_per_second)
+            * u128::from(self.end_time.saturating_sub(block_timestamp));
+        u64::try_from(remaining).unwrap_or(u64::MAX)
+    }
+}
+
+/// The reward emissions of a pool in position mode, shared by the positions of the pool in
+/// proportion to their shares
+#[account]
+#[derive(Default, Debug)]
+pub struct RewardState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the rewards are emitted to
+    pub pool_id: Pubkey,
+    /// The only address allowed to add rewards and set their emissions
+    pub authority: Pubkey,
+    /// The timestamp the reward growths were last updated at
+    pub last_update_time: u64,
+    /// The rewards of the pool, only the initialized ones are emitted
+    pub reward_infos: [RewardInfo; REWARD_NUM],
+}
+
+impl RewardState {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 + (32 * 2 + 8 * 2 + 16) * REWARD_NUM;
+
+    /// Accrue the rewards emitted since the last update to the reward growths, must be
+    /// called before the lp supply or the emissions change. Nothing is accrued while the
+    /// lp supply is 0, these rewards stay in the vaults
+    pub fn update(&mut self, lp_supply: u64, block_timestamp: u64) {
+        let last_update_time = self.last_update_time;
+        if block_timestamp <= last_update_time {
+            return;
+        }
+        if lp_supply != 0 {
+            for reward_info in self
+                .reward_infos
+                .iter_mut()
+                .filter(|reward_info| reward_info.is_initialized())
+            {
+                let emission_end_time = block_timestamp.min(reward_info.end_time);
+                if emission_end_time <= last_update_time {
+                    continue;
+                }
+                let emitted = U256::from(reward_info.emissions_per_second)
+                    * U256::from(emission_end_time - last_update_time);
+                let growth_x64 = (emitted << 64) / U256::from(lp_supply);
+                reward_info.growth_global_x64 = reward_info
+                    .growth_global_x64
+                    .wrapping_add(growth_x64.low_u128());
+            }
+        }
+        self.last_update_time = block_timestamp;
+    }
+
+    /// Add a reward mint in the first free slot, returns its index
+    pub fn add_reward(&mut self, mint: Pubkey, vault: Pubkey) -> Result<usize> {
+        require!(
+            !self
+                .reward_infos
+                .iter()
+                .any(|reward_info| reward_info.mint == mint),
+            ErrorCode::InvalidInput
+        );
+        let index = self
+            .reward_infos
+            .iter()
+            .position(|reward_info| !reward_info.is_initialized())
+            .ok_or(ErrorCode::RewardsFull)?;
+        self.reward_infos[index] = RewardInfo {
+            mint,
+            vault,
+            ..Default::default()
+        };
+        Ok(index)
+    }
+
+    /// Set the emissions of an initialized reward from `block_timestamp`, must be called
+    /// after `update`. Returns the reward tokens the vault must be funded with to emit
+    /// them, the rewards no longer emitted stay in the vault
+    pub fn set_emissions(
+        &mut self,
+        index: usize,
+        emissions_per_second: u64,
+        end_time: u64,
+        block_timestamp: u64,
+    ) -> Result<u64> {
+        let reward_info = self
+            .reward_infos
+            .get_mut(index)
+            .filter(|reward_info| reward_info.is_initialized())
+            .ok_or(ErrorCode::InvalidInput)?;
+        require_gt!(end_time, block_timestamp, ErrorCode::InvalidInput);
+        let funded = reward_info.remaining_emissions(block_timestamp);
+        reward_info.emissions_per_second = emissions_per_second;
+        reward_info.end_time = end_time;
+        let required = u128::from(emissions_per_second) * u128::from(end_time - block_timestamp);
+        let required = u64::try_from(required).map_err(|_| ErrorCode::InvalidInput)?;
+        Ok(required.saturating_sub(funded))
+    }
+}
+
+/// The rewards earned by a personal position
+#[account]
+#[derive(Default, Debug)]
+pub struct PositionRewardState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The position earning the rewards
+    pub personal_position: Pubkey,
+    /// The reward growths of the pool when the rewards of the position were last accrued
+    pub growth_last_x64: [u128; REWARD_NUM],
+    /// The rewards earned by the position and not claimed yet
+    pub rewards_owed: [u64; REWARD_NUM],
+}
+
+impl PositionRewardState {
+    pub const LEN: usize = 8 + 1 + 32 + 16 * REWARD_NUM + 8 * REWARD_NUM;
+
+    pub fn is_initialized(&self) -> bool {
+        self.personal_position != Pubkey::default()
+    }
+
+    pub fn initialize(&mut self, bump: u8, personal_position: Pubkey) {
+        self.bump = bump;
+        self.personal_position = personal_position;
+    }
+
+    /// Accrue the rewards the shares earned since the last accrual, must be called after
+    /// `RewardState::update` and before the shares change. The growths start from 0 with
+    /// each reward and the shares of a position only change once its rewards are tracked,
+    /// so a position tracked late earns its rewards since they started
+    pub fn accrue(&mut self, reward_state: &RewardState, shares: u64) {
+        for ((reward_info, growth_last_x64), rewards_owed) in reward_state
+            .reward_infos
+            .iter()
+            .zip(self.growth_last_x64.iter_mut())
+            .zip(self.rewards_owed.iter_mut())
+        {
+            let earned = (U256::from(reward_info.growth_global_x64.wrapping_sub(*growth_last_x64))
+                * U256::from(shares))
+                >> 64;
+            *rewards_owed =
+                rewards_owed.saturating_add(u64::try_from(earned.low_u128()).unwrap_or(u64::MAX));
+            *growth_last_x64 = reward_info.growth_global_x64;
+        }
+    }
+
+    /// Take the owed rewards of a reward index to claim them
+    pub fn take_rewards(&mut self, index: usize) -> u64 {
+        std::mem::take(&mut self.rewards_owed[index])
+    }
+}
+
+#[cfg(test)]
+pub mod reward_test {
+    use super::*;
+
+    #[test]
+    fn reward_size_test() {
+        let reward_state = RewardState::default();
+        assert_eq!(
+            reward_state.try_to_vec().unwrap().len(),
+            RewardState::LEN - 8
+        );
+        let position_reward = PositionRewardState::default();
+        assert_eq!(
+            position_reward.try_to_vec().unwrap().len(),
+            PositionRewardState::LEN - 8
+        );
+    }
+
+    #[test]
+    fn set_emissions_funds_the_shortfall() {
+        let mut reward_state = RewardState::default();
+        let mint = Pubkey::new_unique();
+        assert_eq!(
+            reward_state.add_reward(mint, Pubkey::new_unique()).unwrap(),
+            0
+        );
+        assert!(reward_state.add_reward(mint, Pubkey::new_unique()).is_err());
+        assert!(reward_state.set_emissions(1, 10, 200, 100).is_err());
+        assert!(reward_state.set_emissions(0, 10, 100, 100).is_err());
+
+        assert_eq!(reward_state.set_emissions(0, 10, 200, 100).unwrap(), 1_000);
+        // extending the emission only funds the added seconds
+        assert_eq!(reward_state.set_emissions(0, 10, 300, 150).unwrap(), 1_000);
+        // lowering the emissions needs no funding
+        assert_eq!(reward_state.set_emissions(0, 5, 300, 150).unwrap(), 0);
+
+        reward_state
+            .add_reward(Pubkey::new_unique(), Pubkey::new_unique())
+            .unwrap();
+        reward_state
+            .add_reward(Pubkey::new_unique(), Pubkey::new_unique())
+            .unwrap();
+        assert!(reward_state
+            .add_reward(Pubkey::new_unique(), Pubkey::new_unique())
+            .is_err());
+    }
+
+    #[test]
+    fn rewards_accrue_by_shares() {
+        let mut reward_state = RewardState::default();
+        reward_state
+            .add_reward(Pubkey::new_unique(), Pubkey::new_unique())
+            .unwrap();
+        reward_state.update(1_000, 100);
+        reward_state.set_emissions(0, 10, 200, 100).unwrap();
+
+        let mut position_reward = PositionRewardState::default();
+        let mut late_position_reward = PositionRewardState::default();
+        position_reward.accrue(&reward_state, 250);
+        // 500 tokens over 1_000 shares
+        reward_state.update(1_000, 150);
+        position_reward.accrue(&reward_state, 250);
+        assert_eq!(position_reward.rewards_owed[0], 125);
+
+        // the emission stops at the end time
+        reward_state.update(1_000, 500);
+        position_reward.accrue(&reward_state, 250);
+        assert_eq!(position_reward.rewards_owed[0], 250);
+        // a position whose rewards were not tracked yet earns since the reward started
+        late_position_reward.accrue(&reward_state, 750);
+        assert_eq!(late_position_reward.rewards_owed[0], 750);
+
+        assert_eq!(position_reward.take_rewards(0), 250);
+        assert_eq!(position_reward.rewards_owed[0], 0);
+        // nothing is accrued while the lp supply is 0
+        reward_state.set_emissions(0, 10, 600, 500).unwrap();
+        reward_state.update(0, 550);
+        assert_eq!(reward_state.last_update_time, 550);
+        position_reward.accrue(&reward_state, 250);
+        assert_eq!(position_reward.rewards_owed[0], 0);
+    }
+}
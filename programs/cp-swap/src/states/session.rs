@@ -0,0 +1,94 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const SESSION_AUTHORITY_SEED: &str = "session_authority";
+
+/// Delegates the liquidity management of an owner in a pool to a session key until the
+/// session expires. The session authority is the SPL delegate of the owner token accounts,
+/// so the session key can deposit from and withdraw to the owner up to the limits, but
+/// never transfer the tokens anywhere else
+#[account]
+#[derive(Default, Debug)]
+pub struct SessionAuthority {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the session manages the liquidity of
+    pub pool_id: Pubkey,
+    /// Owner of the token accounts the session authority is the delegate of
+    pub owner: Pubkey,
+    /// The hot key allowed to sign the session deposits and withdrawals
+    pub session_key: Pubkey,
+    /// Unix timestamp after which the session key is refused
+    pub expires_at: i64,
+    /// Lp tokens the session key can still mint by depositing
+    pub deposit_lp_limit: u64,
+    /// Lp tokens the session key can still burn by withdrawing
+    pub withdraw_lp_limit: u64,
+}
+
+impl SessionAuthority {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 3;
+
+    /// Whether the session key can still be used at `unix_timestamp`
+    pub fn is_active(&self, unix_timestamp: i64) -> bool {
+        unix_timestamp <= self.expires_at
+    }
+
+    /// Use `lp_token_amount` of the deposit limit of the session
+    pub fn use_deposit_limit(&mut self, unix_timestamp: i64, lp_token_amount: u64) -> Result<()> {
+        require!(self.is_active(unix_timestamp), ErrorCode::SessionExpired);
+        self.deposit_lp_limit = self
+            .deposit_lp_limit
+            .checked_sub(lp_token_amount)
+            .ok_or(ErrorCode::SessionLimitExceeded)?;
+        Ok(())
+    }
+
+    /// Use `lp_token_amount` of the withdraw limit of the session
+    pub fn use_withdraw_limit(&mut self, unix_timestamp: i64, lp_token_amount: u64) -> Result<()> {
+        require!(self.is_active(unix_timestamp), ErrorCode::SessionExpired);
+        self.withdraw_lp_limit = self
+            .withdraw_lp_limit
+            .checked_sub(lp_token_amount)
+            .ok_or(ErrorCode::SessionLimitExceeded)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod session_test {
+    use super::*;
+
+    #[test]
+    fn session_authority_size_test() {
+        let session = SessionAuthority::default();
+        assert_eq!(
+            session.try_to_vec().unwrap().len(),
+            SessionAuthority::LEN - 8
+        )
+    }
+
+    #[test]
+    fn session_limits_test() {
+        let mut session = SessionAuthority {
+            expires_at: 1_000,
+            deposit_lp_limit: 100,
+            withdraw_lp_limit: 50,
+            ..Default::default()
+        };
+        session.use_deposit_limit(1_000, 60).unwrap();
+        assert_eq!(session.deposit_lp_limit, 40);
+        assert_eq!(
+            session.use_deposit_limit(1_000, 41).unwrap_err(),
+            ErrorCode::SessionLimitExceeded.into()
+        );
+        session.use_withdraw_limit(999, 50).unwrap();
+        assert_eq!(session.withdraw_lp_limit, 0);
+        // the limits are refused once the session expired
+        assert_eq!(
+            session.use_deposit_limit(1_001, 1).unwrap_err(),
+            ErrorCode::SessionExpired.into()
+        );
+        assert_eq!(session.deposit_lp_limit, 40);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::curve::{SwapResult, TradeDirection, PRICE_IMPACT_BPS_DENOMINATOR};
+use anchor_lang::prelude::*;
+
+pub const PROTOCOL_STATS_SEED: &str = "protocol_stats";
+pub const TRADER_STATS_SEED: &str = "trader_stats";
+
+/// Aggregate swap volume and fees of the pools of an amm config, the amounts of all the
+/// mints are summed in raw amounts
+#[account]
+#[derive(Default, Debug)]
+pub struct ProtocolStats {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The amm config of the pools
+    pub amm_config: Pubkey,
+    /// Number of pools created and not closed since the stats account was created
+    pub pool_count: u64,
+    /// Number of swaps that passed the stats account
+    pub swap_count: u64,
+    /// Cumulative input amount of the swaps
+    pub volume: u128,
+    /// Cumulative trade fees, including the protocol and fund fees
+    pub trade_fees: u128,
+    /// Cumulative protocol fees
+    pub protocol_fees: u128,
+    /// Cumulative fund fees
+    pub fund_fees: u128,
+}
+
+impl ProtocolStats {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 2 + 16 * 4;
+
+    pub fn record_swap(&mut self, result: &SwapResult) {
+        self.swap_count = self.swap_count.saturating_add(1);
+        self.volume = self.volume.saturating_add(result.source_amount_swapped);
+        self.trade_fees = self.trade_fees.saturating_add(result.trade_fee);
+        self.protocol_fees = self.protocol_fees.saturating_add(result.protocol_fee);
+        self.fund_fees = self.fund_fees.saturating_add(result.fund_fee);
+    }
+
+    pub fn record_pool_created(&mut self) {
+        self.pool_count = self.pool_count.saturating_add(1);
+    }
+
+    pub fn record_pool_closed(&mut self) {
+        self.pool_count = self.pool_count.saturating_sub(1);
+    }
+}
+
+/// Swap volume and trade fees of a trader in a pool over the current epoch, and the fee
+/// rebates it earned in the past epochs
+#[account]
+#[derive(Default, Debug)]
+pub struct TraderStats {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the trader swapped in
+    pub pool_id: Pubkey,
+    /// The trader paying the swaps
+    pub trader: Pubkey,
+    /// The epoch the volume and trade fees are accumulated for
+    pub epoch: u64,
+    /// Volume of the trader in the epoch, counted in token_0
+    pub epoch_volume: u128,
+    /// Trade fees paid in token_0 in the epoch
+    pub epoch_trade_fees_token_0: u64,
+    /// Trade fees paid in token_1 in the epoch
+    pub epoch_trade_fees_token_1: u64,
+    /// The token_0 rebates earned and not claimed yet
+    pub rebate_token_0: u64,
+    /// The token_1 rebates earned and not claimed yet
+    pub rebate_token_1: u64,
+}
+
+impl TraderStats {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 + 16 + 8 * 4;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, trader: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.trader = trader;
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.trader != Pubkey::default()
+    }
+
+    /// Close the accumulated epoch once `epoch` is past it, the trader earns `rebate_bps` of
+    /// its trade fees back when its volume reached `volume_threshold`
+    pub fn settle_epoch(&mut self, epoch: u64, volume_threshold: u64, rebate_bps: u16) {
+        if epoch <= self.epoch {
+            return;
+        }
+        if rebate_bps != 0 && self.epoch_volume >= u128::from(volume_threshold) {
+            let rebate = |trade_fees: u64| {
+                u64::try_from(
+                    u128::from(trade_fees) * u128::from(rebate_bps)
+                        / u128::from(PRICE_IMPACT_BPS_DENOMINATOR),
+                )
+                .unwrap()
+            };
+            self.rebate_token_0 = self
+                .rebate_token_0
+                .saturating_add(rebate(self.epoch_trade_fees_token_0));
+            self.rebate_token_1 = self
+                .rebate_token_1
+                .saturating_add(rebate(self.epoch_trade_fees_token_1));
+        }
+        self.epoch = epoch;
+        self.epoch_volume = 0;
+        self.epoch_trade_fees_token_0 = 0;
+        self.epoch_trade_fees_token_1 = 0;
+    }
+
+    /// Add a swap of the trader to the volume and trade fees of `epoch`, the trade fee is
+    /// paid in the input token
+    pub fn record_swap(
+        &mut self,
+        epoch: u64,
+        trade_direction: TradeDirection,
+        result: &SwapResult,
+        volume_threshold: u64,
+        rebate_bps: u16,
+    ) {
+        self.settle_epoch(epoch, volume_threshold, rebate_bps);
+        let trade_fee = u64::try_from(result.trade_fee).unwrap();
+        let volume = match trade_direction {
+            TradeDirection::ZeroForOne => result.source_amount_swapped,
+            TradeDirection::OneForZero => result.destination_amount_swapped,
+        };
+        self.epoch_volume = self.epoch_volume.saturating_add(volume);
+        match result.fee_direction(trade_direction) {
+            TradeDirection::ZeroForOne => {
+                self.epoch_trade_fees_token_0 =
+                    self.epoch_trade_fees_token_0.saturating_add(trade_fee);
+            }
+            TradeDirection::OneForZero => {
+                self.epoch_trade_fees_token_1 =
+                    self.epoch_trade_fees_token_1.saturating_add(trade_fee);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod stats_test {
+    use super::*;
+
+    #[test]
+    fn protocol_stats_size_test() {
+        let stats = ProtocolStats::default();
+        assert_eq!(stats.try_to_vec().unwrap().len(), ProtocolStats::LEN - 8)
+    }
+
+    #[test]
+    fn record_swap_saturates() {
+        let mut stats = ProtocolStats {
+            volume: u128::MAX - 1,
+            ..Default::default()
+        };
+        let result = SwapResult {
+            new_swap_source_amount: 1_100,
+            new_swap_destination_amount: 910,
+            source_amount_swapped: 100,
+            destination_amount_swapped: 90,
+            trade_fee: 3,
+            protocol_fee: 1,
+            fund_fee: 0,
+            fee_on_output: false,
+        };
+        stats.record_swap(&result);
+        stats.record_swap(&result);
+        assert_eq!(stats.swap_count, 2);
+        assert_eq!(stats.volume, u128::MAX);
+        assert_eq!(stats.trade_fees, 6);
+        assert_eq!(stats.protocol_fees, 2);
+        assert_eq!(stats.fund_fees, 0);
+
+        stats.record_pool_closed();
+        assert_eq!(stats.pool_count, 0);
+    }
+
+    #[test]
+    fn trader_stats_size_test() {
+        let stats = TraderStats::default();
+        assert_eq!(stats.try_to_vec().unwrap().len(), TraderStats::LEN - 8)
+    }
+
+    #[test]
+    fn fee_rebate_over_volume_threshold() {
+        let mut stats = TraderStats::default();
+        let result = SwapResult {
+            new_swap_source_amount: 1_100,
+            new_swap_destination_amount: 910,
+            source_amount_swapped: 100,
+            destination_amount_swapped: 90,
+            trade_fee: 40,
+            protocol_fee: 10,
+            fund_fee: 0,
+            fee_on_output: false,
+        };
+        stats.record_swap(5, TradeDirection::ZeroForOne, &result, 150, 2500);
+        stats.record_swap(5, TradeDirection::OneForZero, &result, 150, 2500);
+        assert_eq!(stats.epoch_volume, 190);
+        assert_eq!(stats.epoch_trade_fees_token_0, 40);
+        assert_eq!(stats.epoch_trade_fees_token_1, 40);
+
+        // below the threshold in epoch 6
+        stats.record_swap(6, TradeDirection::ZeroForOne, &result, 150, 2500);
+        assert_eq!((stats.rebate_token_0, stats.rebate_token_1), (10, 10));
+        assert_eq!(stats.epoch_volume, 100);
+
+        stats.settle_epoch(6, 150, 2500);
+        assert_eq!(stats.epoch_volume, 100);
+        stats.settle_epoch(7, 150, 2500);
+        assert_eq!((stats.rebate_token_0, stats.rebate_token_1), (10, 10));
+        assert_eq!(stats.epoch, 7);
+        assert_eq!(stats.epoch_trade_fees_token_0, 0);
+    }
+}
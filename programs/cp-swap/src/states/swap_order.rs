@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+pub const SWAP_ORDER_NONCE_SEED: &str = "swap_order_nonce";
+/// Seed of the order authority of an owner, the delegate the owner approves on the input
+/// token accounts of its signed orders
+pub const SWAP_ORDER_AUTHORITY_SEED: &str = "swap_order_authority";
+
+/// A swap signed off-chain by the owner of the input tokens, executed by a relayer.
+/// The borsh serialization of the order is the signed message
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct SwapOrder {
+    /// The owner of the input and output token accounts, signs the order
+    pub owner: Pubkey,
+    /// The pool to swap in
+    pub pool_id: Pubkey,
+    /// The mint of the input token
+    pub input_mint: Pubkey,
+    /// The mint of the output token
+    pub output_mint: Pubkey,
+    /// Amount of input tokens transferred from the owner
+    pub amount_in: u64,
+    /// Minimum amount of output tokens received by the owner, prevents excessive slippage
+    pub minimum_amount_out: u64,
+    /// The order can't be executed after this timestamp
+    pub expiry: u64,
+    /// Must equal the next nonce of the owner, so each order is executed at most once
+    pub nonce: u64,
+}
+
+/// The next order nonce of an owner
+#[account]
+#[derive(Default, Debug)]
+pub struct SwapOrderNonce {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The owner signing the orders
+    pub owner: Pubkey,
+    /// Nonce of the next order of the owner
+    pub next_nonce: u64,
+}
+
+impl SwapOrderNonce {
+    pub const LEN: usize = 8 + 1 + 32 + 8;
+}
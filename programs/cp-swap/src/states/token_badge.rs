@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+pub const TOKEN_BADGE_SEED: &str = "token_badge";
+
+/// An attestation of the admin that a token program 2022 mint with a risky extension, a
+/// permanent delegate or a transfer hook of any program, was vetted and can be pooled
+#[account]
+#[derive(Default, Debug)]
+pub struct TokenBadge {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The vetted mint
+    pub mint: Pubkey,
+    /// The transfer hook program of the mint when it was vetted, default without a hook
+    pub transfer_hook_program: Pubkey,
+}
+
+impl TokenBadge {
+    pub const LEN: usize = 8 + 1 + 32 + 32;
+}
+
+#[cfg(test)]
+pub mod token_badge_test {
+    use super::*;
+
+    #[test]
+    fn token_badge_size_test() {
+        assert_eq!(
+            TokenBadge::default().try_to_vec().unwrap().len(),
+            TokenBadge::LEN - 8
+        )
+    }
+}
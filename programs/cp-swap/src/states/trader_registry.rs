@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+pub const TRADER_REGISTRY_SEED: &str = "trader_registry";
+pub const TRADER_ENTRY_SEED: &str = "trader_entry";
+
+/// Lists the traders allowed to swap in the pools pointing to it, an issuer can share
+/// one registry between all the pools of its asset
+#[account]
+#[derive(Default, Debug)]
+pub struct TraderRegistry {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// Adds and removes the traders of the registry, the custom authority creating it
+    pub authority: Pubkey,
+    /// The number of registered traders
+    pub trader_count: u64,
+}
+
+impl TraderRegistry {
+    pub const LEN: usize = 8 + 1 + 32 + 8;
+}
+
+/// Allows a trader to swap in the pools of a trader registry
+#[account]
+#[derive(Default, Debug)]
+pub struct TraderEntry {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The registry the trader is registered in
+    pub trader_registry: Pubkey,
+    /// The registered trader
+    pub trader: Pubkey,
+}
+
+impl TraderEntry {
+    pub const LEN: usize = 8 + 1 + 32 * 2;
+}
@@ -0,0 +1,72 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+
+/// The signature count and a padding byte precede the signature offsets
+const SIGNATURE_OFFSETS_START: usize = 2;
+/// Seven u16 offsets and instruction indexes
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+/// Instruction index of the offsets which points to the ed25519 instruction itself
+const CURRENT_INSTRUCTION_INDEX: u16 = u16::MAX;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .ok_or(ErrorCode::InvalidSignature)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_slice(data: &[u8], offset: u16, len: usize) -> Result<&[u8]> {
+    let offset = usize::from(offset);
+    Ok(data
+        .get(offset..offset + len)
+        .ok_or(ErrorCode::InvalidSignature)?)
+}
+
+/// Check the instruction before the current one is an ed25519 program instruction
+/// verifying a single signature of `signer` over `message`. The ed25519 program fails
+/// the whole transaction on an invalid signature, so only the signed data is checked
+pub fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require_gt!(current_index, 0, ErrorCode::InvalidSignature);
+    let instruction =
+        load_instruction_at_checked(usize::from(current_index - 1), instructions_sysvar)?;
+    require_keys_eq!(
+        instruction.program_id,
+        ed25519_program::ID,
+        ErrorCode::InvalidSignature
+    );
+    let data = &instruction.data;
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE && data[0] == 1,
+        ErrorCode::InvalidSignature
+    );
+
+    let offsets = SIGNATURE_OFFSETS_START;
+    let signature_instruction_index = read_u16(data, offsets + 2)?;
+    let public_key_offset = read_u16(data, offsets + 4)?;
+    let public_key_instruction_index = read_u16(data, offsets + 6)?;
+    let message_data_offset = read_u16(data, offsets + 8)?;
+    let message_data_size = read_u16(data, offsets + 10)?;
+    let message_instruction_index = read_u16(data, offsets + 12)?;
+    // the signed data must be read from the ed25519 instruction checked here
+    require!(
+        signature_instruction_index == CURRENT_INSTRUCTION_INDEX
+            && public_key_instruction_index == CURRENT_INSTRUCTION_INDEX
+            && message_instruction_index == CURRENT_INSTRUCTION_INDEX,
+        ErrorCode::InvalidSignature
+    );
+
+    let public_key = read_slice(data, public_key_offset, 32)?;
+    require!(public_key == signer.as_ref(), ErrorCode::InvalidSignature);
+    let signed_message = read_slice(data, message_data_offset, usize::from(message_data_size))?;
+    require!(signed_message == message, ErrorCode::InvalidSignature);
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Invariant checks run at the end of the instructions mutating a pool, compiled in with
+//! the `invariant-checks` feature. They recompute the pool accounting from the vault
+//! balances as a last line of defense and abort the instruction on any violation, without
+//! the feature the checks do nothing.
+
+use crate::curve::CurveCalculator;
+use crate::error::ErrorCode;
+use crate::states::{oracle, PoolState};
+use crate::utils::get_amount_scale;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+/// The invariant and lp supply of a pool before an instruction mutates it
+#[derive(Default, Clone, Copy, Debug)]
+pub struct PoolSnapshot {
+    pub invariant: u128,
+    pub lp_supply: u64,
+}
+
+/// The vaults and vault mints of a pool the invariant checks read the balances of
+pub struct PoolVaults<'info> {
+    pub token_0_vault: AccountInfo<'info>,
+    pub token_1_vault: AccountInfo<'info>,
+    pub vault_0_mint: AccountInfo<'info>,
+    pub vault_1_mint: AccountInfo<'info>,
+}
+
+impl<'info> PoolVaults<'info> {
+    pub fn new(
+        token_0_vault: AccountInfo<'info>,
+        token_1_vault: AccountInfo<'info>,
+        vault_0_mint: AccountInfo<'info>,
+        vault_1_mint: AccountInfo<'info>,
+    ) -> Self {
+        Self {
+            token_0_vault,
+            token_1_vault,
+            vault_0_mint,
+            vault_1_mint,
+        }
+    }
+
+    /// Order the vaults of a trade, `input_is_token_0` when the input vault holds token_0
+    pub fn from_trade(
+        input_is_token_0: bool,
+        input_vault: AccountInfo<'info>,
+        output_vault: AccountInfo<'info>,
+        input_mint: AccountInfo<'info>,
+        output_mint: AccountInfo<'info>,
+    ) -> Self {
+        if input_is_token_0 {
+            Self::new(input_vault, output_vault, input_mint, output_mint)
+        } else {
+            Self::new(output_vault, input_vault, output_mint, input_mint)
+        }
+    }
+
+    /// Snapshot the pool before the instruction mutates it, to check the value of the lp
+    /// tokens did not decrease once it is done
+    pub fn snapshot(&self, pool_state: &PoolState) -> Result<PoolSnapshot> {
+        if !cfg!(feature = "invariant-checks") || pool_state.lp_supply == 0 {
+            return Ok(PoolSnapshot::default());
+        }
+        let (vault_0, vault_1) = self.vault_amounts()?;
+        pool_state.check_accounting(vault_0, vault_1, 0)?;
+        Ok(PoolSnapshot {
+            invariant: pool_state.reserves_invariant(
+                vault_0,
+                vault_1,
+                get_amount_scale(&self.vault_0_mint, &self.vault_1_mint)?,
+                oracle::block_timestamp(),
+            )?,
+            lp_supply: pool_state.lp_supply,
+        })
+    }
+
+    /// Check the vaults hold the accrued fees, the lp mint supply is accounted for by the
+    /// lp supply, and when `before` is given, the invariant backing each lp token did not
+    /// decrease. The lp mint is omitted by the instructions not minting or burning lp tokens.
+    pub fn check(
+        &self,
+        pool_state: &PoolState,
+        lp_mint: Option<&AccountInfo>,
+        before: Option<&PoolSnapshot>,
+    ) -> Result<()> {
+        if !cfg!(feature = "invariant-checks") {
+            return Ok(());
+        }
+        let (vault_0, vault_1) = self.vault_amounts()?;
+        let lp_mint_supply = match lp_mint {
+            Some(lp_mint) => Mint::try_deserialize(&mut &lp_mint.try_borrow_data()?[..])?.supply,
+            None => 0,
+        };
+        pool_state.check_accounting(vault_0, vault_1, lp_mint_supply)?;
+
+        let before = match before {
+            Some(before) if before.lp_supply != 0 => before,
+            _ => return Ok(()),
+        };
+        let block_timestamp = oracle::block_timestamp();
+        let invariant = pool_state.reserves_invariant(
+            vault_0,
+            vault_1,
+            get_amount_scale(&self.vault_0_mint, &self.vault_1_mint)?,
+            block_timestamp,
+        )?;
+        require!(
+            CurveCalculator::invariant_per_lp_token_preserved(
+                pool_state.curve(block_timestamp)?,
+                before.invariant,
+                before.lp_supply,
+                invariant,
+                pool_state.lp_supply,
+            )?,
+            ErrorCode::CurveInvariantViolated
+        );
+        Ok(())
+    }
+
+    fn vault_amounts(&self) -> Result<(u64, u64)> {
+        Ok((
+            TokenAccount::try_deserialize(&mut &self.token_0_vault.try_borrow_data()?[..])?.amount,
+            TokenAccount::try_deserialize(&mut &self.token_1_vault.try_borrow_data()?[..])?.amount,
+        ))
+    }
+}
@@ -1,6 +1,7 @@
 ///! 128 and 256 bit numbers
 ///! U128 is more efficient that u128
 ///! https://github.com/solana-labs/solana/issues/19549
+use crate::error::ErrorCode;
 use uint::construct_uint;
 construct_uint! {
     pub struct U128(2);
@@ -12,36 +13,40 @@ construct_uint! {
 
 pub trait CheckedCeilDiv: Sized {
     /// Perform ceiling division
-    fn checked_ceil_div(&self, rhs: Self) -> Option<(Self, Self)>;
+    fn checked_ceil_div(&self, rhs: Self) -> anchor_lang::Result<(Self, Self)>;
 }
 
 impl CheckedCeilDiv for u128 {
-    fn checked_ceil_div(&self, mut rhs: Self) -> Option<(Self, Self)> {
-        let mut quotient = self.checked_div(rhs)?;
+    fn checked_ceil_div(&self, mut rhs: Self) -> anchor_lang::Result<(Self, Self)> {
+        let mut quotient = self.checked_div(rhs).ok_or(ErrorCode::DivisionByZero)?;
         // Avoid dividing a small number by a big one and returning 1, and instead
         // fail.
         if quotient == 0 {
             // return None;
-            if self.checked_mul(2 as u128)? >= rhs {
-                return Some((1, 0));
+            if self.checked_mul(2 as u128).ok_or(ErrorCode::MathOverflow)? >= rhs {
+                return Ok((1, 0));
             } else {
-                return Some((0, 0));
+                return Ok((0, 0));
             }
         }
 
         // Ceiling the destination amount if there's any remainder, which will
         // almost always be the case.
-        let remainder = self.checked_rem(rhs)?;
+        let remainder = self.checked_rem(rhs).ok_or(ErrorCode::DivisionByZero)?;
         if remainder > 0 {
-            quotient = quotient.checked_add(1)?;
+            quotient = quotient.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
             // calculate the minimum amount needed to get the dividend amount to
             // avoid truncating too much
-            rhs = self.checked_div(quotient)?;
-            let remainder = self.checked_rem(quotient)?;
+            rhs = self
+                .checked_div(quotient)
+                .ok_or(ErrorCode::DivisionByZero)?;
+            let remainder = self
+                .checked_rem(quotient)
+                .ok_or(ErrorCode::DivisionByZero)?;
             if remainder > 0 {
-                rhs = rhs.checked_add(1)?;
+                rhs = rhs.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
             }
         }
-        Some((quotient, rhs))
+        Ok((quotient, rhs))
     }
 }
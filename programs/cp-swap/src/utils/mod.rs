@@ -1,9 +1,15 @@
 pub mod account_load;
 pub mod authority;
+pub mod ed25519;
+pub mod invariant;
 pub mod math;
+pub mod price_feed;
 pub mod token;
 
 pub use account_load::*;
 pub use authority::*;
+pub use ed25519::*;
+pub use invariant::*;
 pub use math::*;
+pub use price_feed::*;
 pub use token::*;
@@ -0,0 +1,70 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// The Pyth receiver program owning the price update accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// Anchor discriminator of the Pyth `PriceUpdateV2` account
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// The discriminator and the write authority precede the verification level
+const VERIFICATION_LEVEL_OFFSET: usize = 8 + 32;
+/// Borsh tag of the `Full` verification level, a fully verified update has no other field
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+/// The feed id precedes the price in the price message
+const PRICE_MESSAGE_PRICE_OFFSET: usize = 32;
+
+/// A price of a Pyth feed, `price * 10^exponent`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    Ok(data
+        .get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ErrorCode::InvalidOracle)?)
+}
+
+/// Read the price of a Pyth pull oracle `PriceUpdateV2` account, the update must be fully
+/// verified, positive and published at most `max_age` seconds before `block_timestamp`
+pub fn load_oracle_price(
+    price_update: &AccountInfo,
+    block_timestamp: u64,
+    max_age: u32,
+) -> Result<OraclePrice> {
+    require_keys_eq!(
+        *price_update.owner,
+        PYTH_RECEIVER_PROGRAM_ID,
+        ErrorCode::InvalidOracle
+    );
+    let data = price_update.try_borrow_data()?;
+    require!(
+        data.starts_with(&PRICE_UPDATE_V2_DISCRIMINATOR),
+        ErrorCode::InvalidOracle
+    );
+    require!(
+        data.get(VERIFICATION_LEVEL_OFFSET) == Some(&VERIFICATION_LEVEL_FULL),
+        ErrorCode::InvalidOracle
+    );
+    // price i64, conf u64, exponent i32 and publish_time i64
+    let offset = VERIFICATION_LEVEL_OFFSET + 1 + PRICE_MESSAGE_PRICE_OFFSET;
+    let price = i64::from_le_bytes(read_bytes(&data, offset)?);
+    let exponent = i32::from_le_bytes(read_bytes(&data, offset + 16)?);
+    let publish_time = i64::from_le_bytes(read_bytes(&data, offset + 20)?);
+    let price = u64::try_from(price)
+        .ok()
+        .filter(|price| *price > 0)
+        .ok_or(ErrorCode::InvalidOracle)?;
+    require_gte!(
+        publish_time.saturating_add(i64::from(max_age)),
+        block_timestamp as i64,
+        ErrorCode::InvalidOracle
+    );
+    Ok(OraclePrice {
+        price,
+        exponent,
+        publish_time,
+    })
+}
@@ -1,17 +1,31 @@
+use crate::curve::{AmountScale, AMOUNT_MULTIPLIER_ONE_X64};
 use crate::error::ErrorCode;
-use anchor_lang::{prelude::*, system_program};
+use crate::states::TokenBadge;
+use anchor_lang::{
+    prelude::*,
+    solana_program::program::{invoke, invoke_signed},
+    system_program,
+};
 use anchor_spl::{
-    token::{Token, TokenAccount},
+    associated_token,
+    token::{spl_token, Token, TokenAccount},
     token_2022,
-    token_interface::{initialize_account3, InitializeAccount3, Mint},
+    token_interface::{
+        self, initialize_account3, initialize_mint2, InitializeAccount3, InitializeMint2, Mint,
+    },
 };
 use spl_token_2022::{
     self,
     extension::{
-        transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
-        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer,
+        scaled_ui_amount::ScaledUiAmountConfig,
+        transfer_fee::{TransferFee, TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+        transfer_hook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
     },
+    onchain::invoke_transfer_checked,
 };
+use spl_token_metadata_interface::state::TokenMetadata;
 use std::collections::HashSet;
 
 const MINT_WHITELIST: [&'static str; 4] = [
@@ -21,6 +35,10 @@ const MINT_WHITELIST: [&'static str; 4] = [
     "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo",
 ];
 
+/// Transfer hook programs that are allowed to be invoked by the vault transfers of any
+/// mint, the hook of another program only runs for a mint whose `TokenBadge` vetted it
+const TRANSFER_HOOK_PROGRAM_WHITELIST: [&str; 0] = [];
+
 pub fn transfer_from_user_to_pool_vault<'a>(
     authority: AccountInfo<'a>,
     from: AccountInfo<'a>,
@@ -29,10 +47,25 @@ pub fn transfer_from_user_to_pool_vault<'a>(
     token_program: AccountInfo<'a>,
     amount: u64,
     mint_decimals: u8,
+    remaining_accounts: &[AccountInfo<'a>],
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
+    if is_transfer_hook_mint(&mint, remaining_accounts)? {
+        invoke_transfer_checked(
+            token_program.key,
+            from,
+            mint,
+            to_vault,
+            authority,
+            remaining_accounts,
+            amount,
+            mint_decimals,
+            &[],
+        )?;
+        return Ok(());
+    }
     token_2022::transfer_checked(
         CpiContext::new(
             token_program.to_account_info(),
@@ -57,10 +90,25 @@ pub fn transfer_from_pool_vault_to_user<'a>(
     amount: u64,
     mint_decimals: u8,
     signer_seeds: &[&[&[u8]]],
+    remaining_accounts: &[AccountInfo<'a>],
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
+    if is_transfer_hook_mint(&mint, remaining_accounts)? {
+        invoke_transfer_checked(
+            token_program.key,
+            from_vault,
+            mint,
+            to,
+            authority,
+            remaining_accounts,
+            amount,
+            mint_decimals,
+            signer_seeds,
+        )?;
+        return Ok(());
+    }
     token_2022::transfer_checked(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
@@ -77,6 +125,60 @@ pub fn transfer_from_pool_vault_to_user<'a>(
     )
 }
 
+/// Check if the mint has a transfer hook program, the extra accounts required
+/// by the hook must be provided through the remaining accounts of the instruction.
+/// Returns an error if the hook program is neither whitelisted nor vetted by the
+/// `TokenBadge` of the mint passed in the remaining accounts.
+pub fn is_transfer_hook_mint(
+    mint_info: &AccountInfo,
+    remaining_accounts: &[AccountInfo],
+) -> Result<bool> {
+    match get_transfer_hook_program_id(mint_info)? {
+        Some(program_id) => {
+            if !is_supported_transfer_hook_program(&program_id)
+                && !has_transfer_hook_badge(mint_info.key, &program_id, remaining_accounts)
+            {
+                return err!(ErrorCode::NotSupportMint);
+            }
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// The transfer hook program of the mint, None when the mint has no transfer hook
+pub fn get_transfer_hook_program_id(mint_info: &AccountInfo) -> Result<Option<Pubkey>> {
+    if *mint_info.owner == Token::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(transfer_hook::get_program_id(&mint))
+}
+
+fn is_supported_transfer_hook_program(program_id: &Pubkey) -> bool {
+    TRANSFER_HOOK_PROGRAM_WHITELIST.contains(&program_id.to_string().as_str())
+}
+
+/// Whether the remaining accounts hold the badge of the mint vetting `program_id`, a hook
+/// program changed by the mint since the badge was issued does not run
+fn has_transfer_hook_badge(
+    mint: &Pubkey,
+    program_id: &Pubkey,
+    remaining_accounts: &[AccountInfo],
+) -> bool {
+    remaining_accounts
+        .iter()
+        .filter(|account_info| *account_info.owner == crate::id())
+        .filter_map(|account_info| {
+            let data = account_info.try_borrow_data().ok()?;
+            TokenBadge::try_deserialize(&mut data.as_ref()).ok()
+        })
+        .any(|token_badge| {
+            token_badge.mint == *mint && token_badge.transfer_hook_program == *program_id
+        })
+}
+
 /// Issue a spl_token `MintTo` instruction.
 pub fn token_mint_to<'a>(
     authority: AccountInfo<'a>,
@@ -122,60 +224,348 @@ pub fn token_burn<'a>(
     )
 }
 
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::id()
+}
+
+/// Wrap native SOL of the owner into its WSOL token account
+pub fn wrap_native_sol<'a>(
+    owner: AccountInfo<'a>,
+    token_account: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            system_program::Transfer {
+                from: owner,
+                to: token_account.clone(),
+            },
+        ),
+        amount,
+    )?;
+    token_2022::sync_native(CpiContext::new(
+        token_program,
+        token_2022::SyncNative {
+            account: token_account,
+        },
+    ))
+}
+
+/// Create the lp token account of the owner as its associated token account of `lp_mint`,
+/// paid by the owner, when it does not exist yet, and check it is an lp token account of
+/// the owner otherwise. The system and associated token programs are only required to
+/// create it
+pub fn init_owner_lp_token_if_needed<'a>(
+    owner: &AccountInfo<'a>,
+    owner_lp_token: &AccountInfo<'a>,
+    lp_mint: &AccountInfo<'a>,
+    lp_token_program: &AccountInfo<'a>,
+    system_program: Option<AccountInfo<'a>>,
+    associated_token_program: Option<AccountInfo<'a>>,
+) -> Result<()> {
+    if owner_lp_token.data_is_empty() {
+        let (Some(system_program), Some(associated_token_program)) =
+            (system_program, associated_token_program)
+        else {
+            return err!(ErrorCode::InvalidInput);
+        };
+        associated_token::create(CpiContext::new(
+            associated_token_program,
+            associated_token::Create {
+                payer: owner.clone(),
+                associated_token: owner_lp_token.clone(),
+                authority: owner.clone(),
+                mint: lp_mint.clone(),
+                system_program,
+                token_program: lp_token_program.clone(),
+            },
+        ))?;
+    }
+    require_keys_eq!(
+        *owner_lp_token.owner,
+        lp_token_program.key(),
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+    );
+    let lp_token =
+        token_interface::TokenAccount::try_deserialize(&mut &owner_lp_token.data.borrow()[..])?;
+    require_keys_eq!(lp_token.mint, lp_mint.key(), ErrorCode::IncorrectLpMint);
+    require_keys_eq!(
+        lp_token.owner,
+        owner.key(),
+        anchor_lang::error::ErrorCode::ConstraintTokenOwner
+    );
+    Ok(())
+}
+
+/// Unwrap a WSOL token account by closing it, all of its lamports are sent to the owner
+pub fn unwrap_native_sol<'a>(
+    owner: AccountInfo<'a>,
+    token_account: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+) -> Result<()> {
+    token_2022::close_account(CpiContext::new(
+        token_program,
+        token_2022::CloseAccount {
+            account: token_account,
+            destination: owner.clone(),
+            authority: owner,
+        },
+    ))
+}
+
+/// The transfer fee of a mint at an epoch, unpacked once from the mint so the fees of
+/// all the transfers of an instruction are computed without reading the mint again
+#[derive(Clone, Copy, Debug)]
+pub struct TransferFeeCache {
+    /// Whether the mint is owned by token program 2022
+    token_2022: bool,
+    /// The transfer fee of the epoch, None when the mint has no transfer fee config
+    transfer_fee: Option<TransferFee>,
+}
+
+impl TransferFeeCache {
+    pub fn load(mint_info: &AccountInfo, epoch: u64) -> Result<Self> {
+        if *mint_info.owner == Token::id() {
+            return Ok(Self {
+                token_2022: false,
+                transfer_fee: None,
+            });
+        }
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        let transfer_fee = mint
+            .get_extension::<TransferFeeConfig>()
+            .ok()
+            .map(|transfer_fee_config| *transfer_fee_config.get_epoch_fee(epoch));
+        Ok(Self {
+            token_2022: true,
+            transfer_fee,
+        })
+    }
+
+    /// The transfer fee of the epoch in basis points, 0 without transfer fee
+    pub fn transfer_fee_bps(&self) -> u16 {
+        self.transfer_fee.map_or(0, |transfer_fee| {
+            u16::from(transfer_fee.transfer_fee_basis_points)
+        })
+    }
+
+    /// Calculate the fee for input amount
+    pub fn transfer_fee(&self, pre_fee_amount: u64) -> u64 {
+        match &self.transfer_fee {
+            Some(transfer_fee) => transfer_fee.calculate_fee(pre_fee_amount).unwrap(),
+            None => 0,
+        }
+    }
+
+    /// Calculate the fee for output amount
+    pub fn transfer_inverse_fee(&self, post_fee_amount: u64) -> Result<u64> {
+        if !self.token_2022 {
+            return Ok(0);
+        }
+        if post_fee_amount == 0 {
+            return err!(ErrorCode::InvalidInput);
+        }
+        let transfer_fee = match &self.transfer_fee {
+            Some(transfer_fee) => transfer_fee,
+            None => return Ok(0),
+        };
+        if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+            return Ok(u64::from(transfer_fee.maximum_fee));
+        }
+        let fee = transfer_fee.calculate_inverse_fee(post_fee_amount).unwrap();
+        let fee_for_check = transfer_fee
+            .calculate_fee(post_fee_amount.checked_add(fee).unwrap())
+            .unwrap();
+        if fee != fee_for_check {
+            return err!(ErrorCode::TransferFeeCalculateNotMatch);
+        }
+        Ok(fee)
+    }
+}
+
 /// Calculate the fee for output amount
 pub fn get_transfer_inverse_fee(mint_info: &AccountInfo, post_fee_amount: u64) -> Result<u64> {
     if *mint_info.owner == Token::id() {
         return Ok(0);
     }
-    if post_fee_amount == 0 {
-        return err!(ErrorCode::InvalidInput);
+    TransferFeeCache::load(mint_info, Clock::get()?.epoch)?.transfer_inverse_fee(post_fee_amount)
+}
+
+/// Calculate the fee for input amount
+pub fn get_transfer_fee(mint_info: &AccountInfo, pre_fee_amount: u64) -> Result<u64> {
+    if *mint_info.owner == Token::id() {
+        return Ok(0);
+    }
+    Ok(TransferFeeCache::load(mint_info, Clock::get()?.epoch)?.transfer_fee(pre_fee_amount))
+}
+
+/// The transfer fee in basis points of a mint at the current epoch
+pub fn get_transfer_fee_bps(mint_info: &AccountInfo) -> Result<u16> {
+    if *mint_info.owner == Token::id() {
+        return Ok(0);
+    }
+    Ok(TransferFeeCache::load(mint_info, Clock::get()?.epoch)?.transfer_fee_bps())
+}
+
+/// The highest transfer fee in basis points of a mint, of the current and the scheduled
+/// transfer fees
+pub fn get_max_transfer_fee_bps(mint_info: &AccountInfo) -> Result<u16> {
+    if *mint_info.owner == Token::id() {
+        return Ok(0);
     }
     let mint_data = mint_info.try_borrow_data()?;
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint
+        .get_extension::<TransferFeeConfig>()
+        .map_or(0, |transfer_fee_config| {
+            u16::from(
+                transfer_fee_config
+                    .older_transfer_fee
+                    .transfer_fee_basis_points,
+            )
+            .max(u16::from(
+                transfer_fee_config
+                    .newer_transfer_fee
+                    .transfer_fee_basis_points,
+            ))
+        }))
+}
 
-    let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
-        let epoch = Clock::get()?.epoch;
+/// Get the balance of a token account of the spl token program or token program 2022
+pub fn get_token_account_amount(token_account: &AccountInfo) -> Result<u64> {
+    require!(
+        *token_account.owner == Token::id() || *token_account.owner == token_2022::Token2022::id(),
+        ErrorCode::InvalidVault
+    );
+    Ok(
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+            &token_account.try_borrow_data()?,
+        )?
+        .base
+        .amount,
+    )
+}
 
-        let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
-        if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
-            u64::from(transfer_fee.maximum_fee)
-        } else {
-            let transfer_fee = transfer_fee_config
-                .calculate_inverse_epoch_fee(epoch, post_fee_amount)
-                .unwrap();
-            let transfer_fee_for_check = transfer_fee_config
-                .calculate_epoch_fee(epoch, post_fee_amount.checked_add(transfer_fee).unwrap())
-                .unwrap();
-            if transfer_fee != transfer_fee_for_check {
-                return err!(ErrorCode::TransferFeeCalculateNotMatch);
-            }
-            transfer_fee
-        }
-    } else {
-        0
-    };
-    Ok(fee)
+/// Get the authority allowed to withdraw the transfer fees withheld by the token accounts
+/// of a mint, None when the mint has no transfer fee or no authority
+pub fn get_withdraw_withheld_authority(mint_info: &AccountInfo) -> Result<Option<Pubkey>> {
+    if *mint_info.owner == Token::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint
+        .get_extension::<TransferFeeConfig>()
+        .ok()
+        .and_then(|transfer_fee_config| transfer_fee_config.withdraw_withheld_authority.into()))
 }
 
-/// Calculate the fee for input amount
-pub fn get_transfer_fee(mint_info: &AccountInfo, pre_fee_amount: u64) -> Result<u64> {
+/// Withdraw the transfer fees withheld by a vault into its own balance
+pub fn withdraw_withheld_tokens_from_vault<'a>(
+    authority: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    vault: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let ix = spl_token_2022::extension::transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+        token_program.key,
+        mint.key,
+        vault.key,
+        authority.key,
+        &[],
+        &[vault.key],
+    )?;
+    invoke_signed(
+        &ix,
+        &[token_program, mint, vault.clone(), authority, vault],
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+const SECONDS_PER_YEAR: f64 = 60. * 60. * 24. * 365.24;
+const ONE_IN_BASIS_POINTS: f64 = 10_000.;
+
+/// Continuously compounded growth of an interest bearing mint over a timespan
+fn interest_growth(rate_bps: i16, timespan: i64) -> f64 {
+    (f64::from(rate_bps) * timespan as f64 / SECONDS_PER_YEAR / ONE_IN_BASIS_POINTS).exp()
+}
+
+/// Get the multiplier from raw amounts of the mint to its UI amounts without the decimals,
+/// Q64.64. Balances of interest bearing and scaled UI amount mints keep their raw amounts
+/// while their UI amounts grow or are rescaled, as the token program computes them
+pub fn get_amount_multiplier_x64(mint_info: &AccountInfo, unix_timestamp: i64) -> Result<u128> {
     if *mint_info.owner == Token::id() {
-        return Ok(0);
+        return Ok(AMOUNT_MULTIPLIER_ONE_X64);
     }
     let mint_data = mint_info.try_borrow_data()?;
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let mut multiplier = 1f64;
+    if let Ok(config) = mint.get_extension::<InterestBearingConfig>() {
+        let last_update_timestamp = i64::from(config.last_update_timestamp);
+        multiplier *= interest_growth(
+            i16::from(config.pre_update_average_rate),
+            last_update_timestamp - i64::from(config.initialization_timestamp),
+        ) * interest_growth(
+            i16::from(config.current_rate),
+            unix_timestamp - last_update_timestamp,
+        );
+    }
+    if let Ok(config) = mint.get_extension::<ScaledUiAmountConfig>() {
+        multiplier *= if unix_timestamp >= i64::from(config.new_multiplier_effective_timestamp) {
+            f64::from(config.new_multiplier)
+        } else {
+            f64::from(config.multiplier)
+        };
+    }
+    require!(
+        multiplier.is_finite() && multiplier > 0.,
+        ErrorCode::InvalidInput
+    );
+    Ok((multiplier * AMOUNT_MULTIPLIER_ONE_X64 as f64) as u128)
+}
 
-    let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
-        transfer_fee_config
-            .calculate_epoch_fee(Clock::get()?.epoch, pre_fee_amount)
-            .unwrap()
-    } else {
-        0
-    };
-    Ok(fee)
+/// Get the amount scale of a swap from its source mint to its destination mint
+pub fn get_amount_scale(
+    source_mint_info: &AccountInfo,
+    destination_mint_info: &AccountInfo,
+) -> Result<AmountScale> {
+    get_amount_scale_at(
+        source_mint_info,
+        destination_mint_info,
+        Clock::get()?.unix_timestamp,
+    )
 }
 
-pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
+/// Get the amount scale of a swap at `unix_timestamp`, for the callers already holding
+/// the clock
+pub fn get_amount_scale_at(
+    source_mint_info: &AccountInfo,
+    destination_mint_info: &AccountInfo,
+    unix_timestamp: i64,
+) -> Result<AmountScale> {
+    Ok(AmountScale {
+        source_multiplier_x64: get_amount_multiplier_x64(source_mint_info, unix_timestamp)?,
+        destination_multiplier_x64: get_amount_multiplier_x64(
+            destination_mint_info,
+            unix_timestamp,
+        )?,
+    })
+}
+
+/// Whether the mint can be pooled. The mints of the spl token program and of the whitelist
+/// always can, the token program 2022 mints only with the extensions the pool handles. A
+/// mint with a permanent delegate or a transfer hook of an unsupported program can only be
+/// pooled with a `TokenBadge`, which the transfers of a hook mint then pass along
+pub fn is_supported_mint(
+    mint_account: &InterfaceAccount<Mint>,
+    has_token_badge: bool,
+) -> Result<bool> {
     let mint_info = mint_account.to_account_info();
     if *mint_info.owner == Token::id() {
         return Ok(true);
@@ -188,6 +578,19 @@ pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool>
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
     let extensions = mint.get_extension_types()?;
     for e in extensions {
+        if has_token_badge
+            && (e == ExtensionType::PermanentDelegate || e == ExtensionType::TransferHook)
+        {
+            continue;
+        }
+        if e == ExtensionType::TransferHook {
+            match transfer_hook::get_program_id(&mint) {
+                Some(program_id) if !is_supported_transfer_hook_program(&program_id) => {
+                    return Ok(false);
+                }
+                _ => continue,
+            }
+        }
         if e != ExtensionType::TransferFeeConfig
             && e != ExtensionType::MetadataPointer
             && e != ExtensionType::TokenMetadata
@@ -243,6 +646,92 @@ pub fn create_token_account<'a>(
     ))
 }
 
+/// Create the lp mint of a pool, under token program 2022 with a metadata pointer to its
+/// own `metadata` when passed, the authority signs the metadata as mint authority
+pub fn create_lp_mint<'a>(
+    authority: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    lp_mint: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    decimals: u8,
+    metadata: Option<TokenMetadata>,
+    mint_seeds: &[&[u8]],
+    authority_seeds: &[&[u8]],
+) -> Result<()> {
+    let extensions = if metadata.is_some() {
+        vec![ExtensionType::MetadataPointer]
+    } else {
+        vec![]
+    };
+    let space =
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)?;
+    create_or_allocate_account(
+        token_program.key,
+        payer.to_account_info(),
+        system_program.to_account_info(),
+        lp_mint.to_account_info(),
+        mint_seeds,
+        space,
+    )?;
+    if let Some(metadata) = metadata.as_ref() {
+        // the token program reallocates the mint for the metadata, which must already be rent exempt
+        let rent = Rent::get()?;
+        let metadata_lamports = rent
+            .minimum_balance(space + metadata.tlv_size_of()?)
+            .saturating_sub(lp_mint.lamports());
+        if metadata_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: lp_mint.to_account_info(),
+                    },
+                ),
+                metadata_lamports,
+            )?;
+        }
+        invoke(
+            &metadata_pointer::instruction::initialize(
+                token_program.key,
+                lp_mint.key,
+                Some(authority.key()),
+                Some(lp_mint.key()),
+            )?,
+            &[lp_mint.to_account_info()],
+        )?;
+    }
+    initialize_mint2(
+        CpiContext::new(
+            token_program.to_account_info(),
+            InitializeMint2 {
+                mint: lp_mint.to_account_info(),
+            },
+        ),
+        decimals,
+        authority.key,
+        None,
+    )?;
+    if let Some(metadata) = metadata {
+        invoke_signed(
+            &spl_token_metadata_interface::instruction::initialize(
+                token_program.key,
+                lp_mint.key,
+                authority.key,
+                lp_mint.key,
+                authority.key,
+                metadata.name,
+                metadata.symbol,
+                metadata.uri,
+            ),
+            &[lp_mint.to_account_info(), authority.to_account_info()],
+            &[authority_seeds],
+        )?;
+    }
+    Ok(())
+}
+
 pub fn create_or_allocate_account<'a>(
     program_id: &Pubkey,
     payer: AccountInfo<'a>,
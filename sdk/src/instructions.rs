@@ -0,0 +1,2009 @@
+use crate::pda::{self, MultiPoolKeys, PoolKeys};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    instruction::{AccountMeta, Instruction},
+    system_program, sysvar,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    memo::spl_memo,
+    token::spl_token,
+    token_2022::spl_token_2022,
+};
+use raydium_cp_swap::instructions::is_fee_rate_param;
+use raydium_cp_swap::states::{BatchAdminOp, PriceSlippage, SwapOrder};
+use raydium_cp_swap::{accounts, instruction, ID};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn initialize_accounts(
+    keys: &PoolKeys,
+    creator: Pubkey,
+    creator_token_0: Pubkey,
+    creator_token_1: Pubkey,
+    create_pool_fee: Pubkey,
+    lp_mint_metadata: bool,
+    token_badges: [bool; 2],
+) -> accounts::Initialize {
+    let token_program_2022 = lp_mint_metadata.then(spl_token_2022::id);
+    let lp_token_program = token_program_2022.unwrap_or(spl_token::id());
+    accounts::Initialize {
+        creator,
+        amm_config: keys.amm_config,
+        authority: keys.authority,
+        pool_state: keys.pool_id,
+        token_0_mint: keys.token_0_mint,
+        token_1_mint: keys.token_1_mint,
+        lp_mint: keys.lp_mint,
+        creator_token_0,
+        creator_token_1,
+        creator_lp_token: get_associated_token_address_with_program_id(
+            &creator,
+            &keys.lp_mint,
+            &lp_token_program,
+        ),
+        token_0_vault: keys.token_0_vault,
+        token_1_vault: keys.token_1_vault,
+        create_pool_fee,
+        observation_state: keys.observation_state,
+        lp_mint_registry: pda::lp_mint_registry(&keys.lp_mint).0,
+        token_program: spl_token::id(),
+        token_0_program: keys.token_0_program,
+        token_1_program: keys.token_1_program,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        create_pool_fee_mint: None,
+        creator_fee_token: None,
+        create_pool_fee_token_program: None,
+        protocol_stats: None,
+        mint_policy: None,
+        token_program_2022,
+        token_0_badge: token_badges[0].then(|| pda::token_badge(&keys.token_0_mint).0),
+        token_1_badge: token_badges[1].then(|| pda::token_badge(&keys.token_1_mint).0),
+    }
+}
+
+/// Create the pool of `keys` at its PDA address, the creator receives the initial lp
+/// tokens in its associated token account, under token program 2022 when the lp mint is
+/// created with metadata
+///
+/// * `create_pool_fee` - The token account of the pool creation fee receiver of the amm config
+/// * `token_badges` - Whether to pass the token badge of each mint, required by the mints
+///   with a permanent delegate or a transfer hook of an unsupported program
+/// * `args` - The arguments of the initialize instruction
+///
+pub fn initialize(
+    keys: &PoolKeys,
+    creator: Pubkey,
+    creator_token_0: Pubkey,
+    creator_token_1: Pubkey,
+    create_pool_fee: Pubkey,
+    token_badges: [bool; 2],
+    args: instruction::Initialize,
+) -> Instruction {
+    build(
+        initialize_accounts(
+            keys,
+            creator,
+            creator_token_0,
+            creator_token_1,
+            create_pool_fee,
+            args.lp_mint_metadata.is_some(),
+            token_badges,
+        ),
+        args,
+    )
+}
+
+/// Create the pool of `keys` at its salted address, `keys` are derived with
+/// `PoolKeys::new_salted` and the same salt
+///
+/// * `create_pool_fee` - The token account of the pool creation fee receiver of the amm config
+/// * `token_badges` - Whether to pass the token badge of each mint, required by the mints
+///   with a permanent delegate or a transfer hook of an unsupported program
+/// * `args` - The arguments of the initialize with salt instruction
+///
+pub fn initialize_with_salt(
+    keys: &PoolKeys,
+    creator: Pubkey,
+    creator_token_0: Pubkey,
+    creator_token_1: Pubkey,
+    create_pool_fee: Pubkey,
+    token_badges: [bool; 2],
+    args: instruction::InitializeWithSalt,
+) -> Instruction {
+    build(
+        initialize_accounts(
+            keys,
+            creator,
+            creator_token_0,
+            creator_token_1,
+            create_pool_fee,
+            args.lp_mint_metadata.is_some(),
+            token_badges,
+        ),
+        args,
+    )
+}
+
+/// Create the pool of `keys` like `initialize` and swap the creator tokens in it in the
+/// same instruction, the swap output is received in the creator token accounts
+///
+/// * `create_pool_fee` - The token account of the pool creation fee receiver of the amm config
+/// * `token_badges` - Whether to pass the token badge of each mint, required by the mints
+///   with a permanent delegate or a transfer hook of an unsupported program
+/// * `args` - The arguments of the initialize and swap instruction
+///
+pub fn initialize_and_swap(
+    keys: &PoolKeys,
+    creator: Pubkey,
+    creator_token_0: Pubkey,
+    creator_token_1: Pubkey,
+    create_pool_fee: Pubkey,
+    token_badges: [bool; 2],
+    args: instruction::InitializeAndSwap,
+) -> Instruction {
+    build(
+        initialize_accounts(
+            keys,
+            creator,
+            creator_token_0,
+            creator_token_1,
+            create_pool_fee,
+            args.lp_mint_metadata.is_some(),
+            token_badges,
+        ),
+        args,
+    )
+}
+
+fn deposit_accounts(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    deposit_receipt: bool,
+) -> accounts::Deposit {
+    accounts::Deposit {
+        owner,
+        authority: keys.authority,
+        pool_state: keys.pool_id,
+        owner_lp_token,
+        token_0_account,
+        token_1_account,
+        token_0_vault: keys.token_0_vault,
+        token_1_vault: keys.token_1_vault,
+        token_program: spl_token::id(),
+        token_program_2022: spl_token_2022::id(),
+        vault_0_mint: keys.token_0_mint,
+        vault_1_mint: keys.token_1_mint,
+        lp_mint: keys.lp_mint,
+        // also creates the owner lp token account when it does not exist yet
+        system_program: Some(system_program::id()),
+        deposit_receipt: deposit_receipt.then(|| pda::deposit_receipt(&keys.pool_id, &owner).0),
+        associated_token_program: Some(associated_token::ID),
+    }
+}
+
+/// Deposit both tokens for `lp_token_amount` lp tokens, reverting after the unix
+/// timestamp `deadline` or beyond the pool price deviation of `price_slippage` when set.
+/// With `deposit_receipt` the lp tokens are recorded in the deposit receipt of the owner,
+/// for the pools with a withdrawal lockup
+pub fn deposit(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    deposit_receipt: bool,
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    deadline: Option<u64>,
+    price_slippage: Option<PriceSlippage>,
+) -> Instruction {
+    build(
+        deposit_accounts(
+            keys,
+            owner,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            deposit_receipt,
+        ),
+        instruction::Deposit {
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            wrap_sol: false,
+            deadline,
+            price_slippage,
+        },
+    )
+}
+
+/// Deposit at most `token_0_amount` and `token_1_amount` for the most lp tokens they mint,
+/// reverting after the unix timestamp `deadline` when set. With `deposit_receipt` the lp
+/// tokens are recorded in the deposit receipt of the owner
+pub fn deposit_exact_tokens(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    deposit_receipt: bool,
+    token_0_amount: u64,
+    token_1_amount: u64,
+    minimum_lp_token_amount: u64,
+    deadline: Option<u64>,
+) -> Instruction {
+    build(
+        deposit_accounts(
+            keys,
+            owner,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            deposit_receipt,
+        ),
+        instruction::DepositExactTokens {
+            token_0_amount,
+            token_1_amount,
+            minimum_lp_token_amount,
+            wrap_sol: false,
+            deadline,
+        },
+    )
+}
+
+fn withdraw_accounts(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    deposit_receipt: bool,
+) -> accounts::Withdraw {
+    accounts::Withdraw {
+        owner,
+        authority: keys.authority,
+        pool_state: keys.pool_id,
+        owner_lp_token,
+        token_0_account,
+        token_1_account,
+        token_0_vault: keys.token_0_vault,
+        token_1_vault: keys.token_1_vault,
+        token_program: spl_token::id(),
+        token_program_2022: spl_token_2022::id(),
+        vault_0_mint: keys.token_0_mint,
+        vault_1_mint: keys.token_1_mint,
+        lp_mint: keys.lp_mint,
+        memo_program: spl_memo::id(),
+        deposit_receipt: deposit_receipt.then(|| pda::deposit_receipt(&keys.pool_id, &owner).0),
+    }
+}
+
+/// Burn `lp_token_amount` lp tokens for both tokens, reverting after the unix
+/// timestamp `deadline` or beyond the pool price deviation of `price_slippage` when set.
+/// With `deposit_receipt` the unlocked lp tokens of the deposit receipt of the owner are
+/// withdrawn without the early exit fee
+pub fn withdraw(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    deposit_receipt: bool,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+    deadline: Option<u64>,
+    price_slippage: Option<PriceSlippage>,
+) -> Instruction {
+    build(
+        withdraw_accounts(
+            keys,
+            owner,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            deposit_receipt,
+        ),
+        instruction::Withdraw {
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            wrap_sol: false,
+            deadline,
+            price_slippage,
+        },
+    )
+}
+
+/// Burn `lp_token_amount` lp tokens for their share of the vault not frozen while the vault
+/// of `frozen_mint` is frozen, None when it is not a mint of the pool. With
+/// `deposit_receipt` the unlocked lp tokens of the deposit receipt of the owner are
+/// withdrawn without the early exit fee
+pub fn handle_frozen_vault(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    output_token_account: Pubkey,
+    frozen_mint: &Pubkey,
+    deposit_receipt: bool,
+    lp_token_amount: u64,
+    minimum_out: u64,
+) -> Option<Instruction> {
+    let trade = keys.trade_keys(frozen_mint)?;
+    Some(build(
+        accounts::HandleFrozenVault {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            owner_lp_token,
+            output_token_account,
+            output_vault: trade.output_vault,
+            frozen_vault: trade.input_vault,
+            token_program: spl_token::id(),
+            output_token_program: trade.output_token_program,
+            output_token_mint: trade.output_mint,
+            frozen_token_mint: trade.input_mint,
+            lp_mint: keys.lp_mint,
+            deposit_receipt: deposit_receipt.then(|| pda::deposit_receipt(&keys.pool_id, &owner).0),
+        },
+        instruction::HandleFrozenVault {
+            lp_token_amount,
+            minimum_out,
+        },
+    ))
+}
+
+/// Burn the fewest lp tokens for at least `token_0_amount` and `token_1_amount`,
+/// reverting after the unix timestamp `deadline` when set. With `deposit_receipt` the
+/// unlocked lp tokens of the deposit receipt of the owner pay no early exit fee
+pub fn withdraw_exact_tokens(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    deposit_receipt: bool,
+    token_0_amount: u64,
+    token_1_amount: u64,
+    maximum_lp_token_amount: u64,
+    deadline: Option<u64>,
+) -> Instruction {
+    build(
+        withdraw_accounts(
+            keys,
+            owner,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            deposit_receipt,
+        ),
+        instruction::WithdrawExactTokens {
+            token_0_amount,
+            token_1_amount,
+            maximum_lp_token_amount,
+            wrap_sol: false,
+            deadline,
+        },
+    )
+}
+
+/// Deposit `amount_in` of `input_mint` only, part of it is swapped for the other token of
+/// the pool. None when `input_mint` is not a token of the pool
+pub fn deposit_single_token(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    amount_in: u64,
+    minimum_lp_token_amount: u64,
+) -> Option<Instruction> {
+    let trade = keys.trade_keys(input_mint)?;
+    Some(build(
+        accounts::DepositSingleToken {
+            owner,
+            authority: keys.authority,
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            owner_lp_token,
+            input_token_account,
+            input_vault: trade.input_vault,
+            other_vault: trade.output_vault,
+            token_program: spl_token::id(),
+            input_token_program: trade.input_token_program,
+            input_token_mint: trade.input_mint,
+            other_token_mint: trade.output_mint,
+            lp_mint: keys.lp_mint,
+            observation_state: keys.observation_state,
+            // also creates the owner lp token account when it does not exist yet
+            system_program: Some(system_program::id()),
+            associated_token_program: Some(associated_token::ID),
+        },
+        instruction::DepositSingleToken {
+            amount_in,
+            minimum_lp_token_amount,
+        },
+    ))
+}
+
+/// Burn `lp_token_amount` lp tokens of `owner` for `output_mint` only, the share of the
+/// other token of the pool is swapped for it. None when `output_mint` is not a token of the
+/// pool
+pub fn withdraw_single_token(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    output_mint: &Pubkey,
+    output_token_account: Pubkey,
+    lp_token_amount: u64,
+    minimum_out: u64,
+) -> Option<Instruction> {
+    let trade = keys.trade_keys(output_mint)?;
+    Some(build(
+        accounts::WithdrawSingleToken {
+            owner,
+            authority: keys.authority,
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            owner_lp_token,
+            output_token_account,
+            output_vault: trade.input_vault,
+            other_vault: trade.output_vault,
+            token_program: spl_token::id(),
+            output_token_program: trade.input_token_program,
+            output_token_mint: trade.input_mint,
+            other_token_mint: trade.output_mint,
+            lp_mint: keys.lp_mint,
+            observation_state: keys.observation_state,
+        },
+        instruction::WithdrawSingleToken {
+            lp_token_amount,
+            minimum_out,
+        },
+    ))
+}
+
+/// Deposit both tokens of `owner` for `lp_token_amount` lp tokens minted to the lp token
+/// account of `beneficiary`, reverting after the unix timestamp `deadline` when set
+pub fn deposit_for(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    beneficiary: Pubkey,
+    beneficiary_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    deadline: Option<u64>,
+) -> Instruction {
+    build(
+        accounts::DepositFor {
+            owner,
+            beneficiary,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            beneficiary_lp_token,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            lp_mint: keys.lp_mint,
+        },
+        instruction::DepositFor {
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            deadline,
+        },
+    )
+}
+
+/// Burn `lp_token_amount` lp tokens of `owner` for both tokens sent to the token accounts
+/// of `beneficiary`, reverting after the unix timestamp `deadline` when set
+pub fn withdraw_to(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    beneficiary: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+    deadline: Option<u64>,
+) -> Instruction {
+    build(
+        accounts::WithdrawTo {
+            owner,
+            beneficiary,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            lp_mint: keys.lp_mint,
+        },
+        instruction::WithdrawTo {
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            deadline,
+        },
+    )
+}
+
+/// Migrate `legacy_lp_amount` lp tokens of a legacy Raydium AMM v4 pool into the pool.
+/// `legacy_accounts` are the accounts of the legacy withdraw instruction, with the
+/// `token_0_account` and `token_1_account` of `owner` as its user token accounts
+pub fn migrate_from_legacy(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    legacy_lp_token: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    legacy_accounts: &[AccountMeta],
+    legacy_lp_amount: u64,
+    minimum_lp_token_amount: u64,
+) -> Instruction {
+    let mut instruction = build(
+        accounts::MigrateFromLegacy {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            legacy_amm_program: raydium_cp_swap::legacy_amm_program::ID,
+            legacy_lp_token,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            lp_mint: keys.lp_mint,
+        },
+        instruction::MigrateFromLegacy {
+            legacy_lp_amount,
+            legacy_account_count: u8::try_from(legacy_accounts.len()).unwrap(),
+            minimum_lp_token_amount,
+        },
+    );
+    instruction.accounts.extend_from_slice(legacy_accounts);
+    instruction
+}
+
+/// Open the limit order `args.order_id` of `owner` selling `input_mint` in the pool, the
+/// bought tokens are sent to `output_token_account` as the order fills. None when
+/// `input_mint` is not a mint of the pool
+pub fn open_order(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    args: instruction::OpenOrder,
+) -> Option<Instruction> {
+    let trade = keys.trade_keys(input_mint)?;
+    let order_state = pda::limit_order(&keys.pool_id, &owner, args.order_id).0;
+    Some(build(
+        accounts::OpenOrder {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            input_token_mint: trade.input_mint,
+            output_token_mint: trade.output_mint,
+            input_token_account,
+            output_token_account,
+            order_state,
+            order_vault: pda::limit_order_vault(&order_state).0,
+            input_token_program: trade.input_token_program,
+            system_program: system_program::id(),
+        },
+        args,
+    ))
+}
+
+/// Cancel the limit order `order_id` of `owner` selling `input_mint` in the pool, the
+/// unfilled input tokens are returned to `input_token_account`
+pub fn cancel_order(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    order_id: u64,
+) -> Option<Instruction> {
+    let trade = keys.trade_keys(input_mint)?;
+    let order_state = pda::limit_order(&keys.pool_id, &owner, order_id).0;
+    Some(build(
+        accounts::CancelOrder {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            order_state,
+            order_vault: pda::limit_order_vault(&order_state).0,
+            input_token_account,
+            input_token_mint: trade.input_mint,
+            input_token_program: trade.input_token_program,
+        },
+        instruction::CancelOrder {},
+    ))
+}
+
+fn quote_swap_accounts(keys: &PoolKeys, input_mint: &Pubkey) -> Option<accounts::QuoteSwap> {
+    let trade = keys.trade_keys(input_mint)?;
+    Some(accounts::QuoteSwap {
+        amm_config: keys.amm_config,
+        pool_state: keys.pool_id,
+        input_vault: trade.input_vault,
+        output_vault: trade.output_vault,
+        input_token_mint: trade.input_mint,
+        output_token_mint: trade.output_mint,
+        observation_state: keys.observation_state,
+    })
+}
+
+/// Quote a swap of an exact `amount_in` of `input_mint`, the `SwapQuote` is the return data
+/// of the instruction. None when `input_mint` is not a mint of the pool
+pub fn quote_swap_base_input(
+    keys: &PoolKeys,
+    input_mint: &Pubkey,
+    amount_in: u64,
+) -> Option<Instruction> {
+    Some(build(
+        quote_swap_accounts(keys, input_mint)?,
+        instruction::QuoteSwapBaseInput { amount_in },
+    ))
+}
+
+/// Quote a swap of `input_mint` for an exact `amount_out` of the other token, the
+/// `SwapQuote` is the return data of the instruction. None when `input_mint` is not a mint
+/// of the pool
+pub fn quote_swap_base_output(
+    keys: &PoolKeys,
+    input_mint: &Pubkey,
+    amount_out: u64,
+) -> Option<Instruction> {
+    Some(build(
+        quote_swap_accounts(keys, input_mint)?,
+        instruction::QuoteSwapBaseOutput { amount_out },
+    ))
+}
+
+/// Quote a swap of an exact amount of `input_mint` in each pool of `pools` in one
+/// simulation, None when it is not a mint of every pool
+pub fn quote_many(pools: &[PoolKeys], input_mint: &Pubkey, amount_in: u64) -> Option<Instruction> {
+    let mut instruction = build(accounts::QuoteMany {}, instruction::QuoteMany { amount_in });
+    for keys in pools {
+        instruction
+            .accounts
+            .extend(quote_swap_accounts(keys, input_mint)?.to_account_metas(None));
+    }
+    Some(instruction)
+}
+
+fn swap_accounts(
+    keys: &PoolKeys,
+    payer: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    lp_fee_rebate: Option<(Pubkey, Pubkey)>,
+) -> Option<accounts::Swap> {
+    let trade = keys.trade_keys(input_mint)?;
+    Some(accounts::Swap {
+        payer,
+        authority: keys.authority,
+        amm_config: keys.amm_config,
+        pool_state: keys.pool_id,
+        input_token_account,
+        output_token_account,
+        input_vault: trade.input_vault,
+        output_vault: trade.output_vault,
+        input_token_program: trade.input_token_program,
+        output_token_program: trade.output_token_program,
+        input_token_mint: trade.input_mint,
+        output_token_mint: trade.output_mint,
+        observation_state: keys.observation_state,
+        referrer_token_account: None,
+        system_program: None,
+        launch_whitelist: None,
+        trader_entry: keys
+            .trader_registry
+            .map(|trader_registry| pda::trader_entry(&trader_registry, &payer).0),
+        fee_discount_token_account: None,
+        protocol_stats: None,
+        fee_exemption_list: None,
+        price_oracle: keys.price_oracle,
+        trader_stats: None,
+        pool_snapshot: None,
+        lp_mint: lp_fee_rebate.map(|_| keys.lp_mint),
+        payer_lp_token: lp_fee_rebate.map(|(payer_lp_token, _)| payer_lp_token),
+        lp_token_program: lp_fee_rebate.map(|(_, lp_token_program)| lp_token_program),
+    })
+}
+
+/// Swap an exact amount of `input_mint`, None when it is not a mint of the pool. With
+/// `sqrt_price_limit_x64` the swap is partially filled up to the limit price instead of
+/// crossing it
+///
+/// * `lp_fee_rebate` - The lp token account of the payer and the token program of the lp
+///   mint, the payer receives the lp fee rebate of the amm config in it
+///
+pub fn swap_base_input(
+    keys: &PoolKeys,
+    payer: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    deadline: Option<u64>,
+    sqrt_price_limit_x64: Option<u128>,
+    lp_fee_rebate: Option<(Pubkey, Pubkey)>,
+) -> Option<Instruction> {
+    Some(build(
+        swap_accounts(
+            keys,
+            payer,
+            input_mint,
+            input_token_account,
+            output_token_account,
+            lp_fee_rebate,
+        )?,
+        instruction::SwapBaseInput {
+            amount_in,
+            minimum_amount_out,
+            wrap_sol: false,
+            deadline,
+            sqrt_price_limit_x64,
+        },
+    ))
+}
+
+/// Swap `input_mint` for an exact amount of the other token, None when it is not a mint of
+/// the pool, `lp_fee_rebate` as for `swap_base_input`
+pub fn swap_base_output(
+    keys: &PoolKeys,
+    payer: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    max_amount_in: u64,
+    amount_out: u64,
+    deadline: Option<u64>,
+    lp_fee_rebate: Option<(Pubkey, Pubkey)>,
+) -> Option<Instruction> {
+    Some(build(
+        swap_accounts(
+            keys,
+            payer,
+            input_mint,
+            input_token_account,
+            output_token_account,
+            lp_fee_rebate,
+        )?,
+        instruction::SwapBaseOutput {
+            max_amount_in,
+            amount_out,
+            wrap_sol: false,
+            deadline,
+        },
+    ))
+}
+
+fn swap_route_accounts(
+    first: &PoolKeys,
+    second: &PoolKeys,
+    payer: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+) -> Option<accounts::SwapRoute> {
+    let first_trade = first.trade_keys(input_mint)?;
+    let second_trade = second.trade_keys(&first_trade.output_mint)?;
+    let route_pool = |keys: &PoolKeys, trade: &pda::TradeKeys| accounts::SwapRoutePool {
+        amm_config: keys.amm_config,
+        pool_state: keys.pool_id,
+        input_vault: trade.input_vault,
+        output_vault: trade.output_vault,
+        observation_state: keys.observation_state,
+    };
+    Some(accounts::SwapRoute {
+        payer,
+        authority: first.authority,
+        first_pool: route_pool(first, &first_trade),
+        second_pool: route_pool(second, &second_trade),
+        input_token_account,
+        output_token_account,
+        input_token_program: first_trade.input_token_program,
+        intermediate_token_program: first_trade.output_token_program,
+        output_token_program: second_trade.output_token_program,
+        input_token_mint: first_trade.input_mint,
+        intermediate_token_mint: first_trade.output_mint,
+        output_token_mint: second_trade.output_mint,
+    })
+}
+
+/// Swap an exact amount of `input_mint` through the pools of `first` and `second`, the
+/// intermediate token is the other mint of the first pool. None when the mints of the
+/// pools do not chain
+pub fn swap_route(
+    first: &PoolKeys,
+    second: &PoolKeys,
+    payer: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Option<Instruction> {
+    Some(build(
+        swap_route_accounts(
+            first,
+            second,
+            payer,
+            input_mint,
+            input_token_account,
+            output_token_account,
+        )?,
+        instruction::SwapRoute {
+            amount_in,
+            minimum_amount_out,
+        },
+    ))
+}
+
+/// Swap `input_mint` through the pools of `first` and `second` for an exact `amount_out`
+/// of the output token, like `swap_route`
+pub fn swap_route_base_output(
+    first: &PoolKeys,
+    second: &PoolKeys,
+    payer: Pubkey,
+    input_mint: &Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    max_amount_in: u64,
+    amount_out: u64,
+) -> Option<Instruction> {
+    Some(build(
+        swap_route_accounts(
+            first,
+            second,
+            payer,
+            input_mint,
+            input_token_account,
+            output_token_account,
+        )?,
+        instruction::SwapRouteBaseOutput {
+            max_amount_in,
+            amount_out,
+        },
+    ))
+}
+
+/// Swap the `order` signed by its owner, submitted and paid by `relayer`, None when the
+/// input mint of the order is not a mint of the pool. The transaction verifies the signature
+/// with the `ed25519_verify` instruction of the order right before this one, and the owner
+/// approved its `pda::swap_order_authority` as the delegate of `input_token_account`
+pub fn swap_signed_order(
+    keys: &PoolKeys,
+    relayer: Pubkey,
+    order: SwapOrder,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+) -> Option<Instruction> {
+    let trade = keys.trade_keys(&order.input_mint)?;
+    Some(build(
+        accounts::SwapSignedOrder {
+            relayer,
+            owner: order.owner,
+            authority: keys.authority,
+            order_authority: pda::swap_order_authority(&order.owner).0,
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            input_token_account,
+            output_token_account,
+            input_vault: trade.input_vault,
+            output_vault: trade.output_vault,
+            input_token_program: trade.input_token_program,
+            output_token_program: trade.output_token_program,
+            input_token_mint: trade.input_mint,
+            output_token_mint: trade.output_mint,
+            observation_state: keys.observation_state,
+            order_nonce: pda::swap_order_nonce(&order.owner).0,
+            instructions_sysvar: sysvar::instructions::id(),
+            system_program: system_program::id(),
+        },
+        instruction::SwapSignedOrder { order },
+    ))
+}
+
+/// The ed25519 program instruction verifying the `signature` of `signer` over `message`, in
+/// the layout the signed orders are checked against: the public key, the signature and the
+/// message follow the offsets in the instruction data
+pub fn ed25519_verify(signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    const PUBLIC_KEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = PUBLIC_KEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+    let mut data = vec![1, 0];
+    for offset in [
+        SIGNATURE_OFFSET,
+        u16::MAX,
+        PUBLIC_KEY_OFFSET,
+        u16::MAX,
+        MESSAGE_OFFSET,
+        u16::try_from(message.len()).unwrap(),
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Swap the tokens of the custom authority of the pool to move the pool price toward
+/// `target_sqrt_price_x64`, transferring at most `max_amount_in` input tokens
+pub fn rebalance_to_target_price(
+    keys: &PoolKeys,
+    custom_authority: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    target_sqrt_price_x64: u128,
+    max_amount_in: u64,
+) -> Instruction {
+    build(
+        accounts::RebalanceToTargetPrice {
+            custom_authority,
+            authority: keys.authority,
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_0_program: keys.token_0_program,
+            token_1_program: keys.token_1_program,
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            observation_state: keys.observation_state,
+        },
+        instruction::RebalanceToTargetPrice {
+            target_sqrt_price_x64,
+            max_amount_in,
+        },
+    )
+}
+
+/// Record the price of the vault balances of the pool in its oracle, including the tokens
+/// sent directly to the vaults
+pub fn sync_reserves(keys: &PoolKeys) -> Instruction {
+    build(
+        accounts::SyncReserves {
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            observation_state: keys.observation_state,
+        },
+        instruction::SyncReserves {},
+    )
+}
+
+/// Swap the fund fees in `fund_fee_mint` of the pool for the buyback token of its amm config
+/// and burn it, None when `fund_fee_mint` is not a mint of the pool
+pub fn execute_buyback(
+    keys: &PoolKeys,
+    payer: Pubkey,
+    fund_fee_mint: &Pubkey,
+) -> Option<Instruction> {
+    let trade = keys.trade_keys(fund_fee_mint)?;
+    Some(build(
+        accounts::ExecuteBuyback {
+            payer,
+            authority: keys.authority,
+            pool: accounts::SwapRoutePool {
+                amm_config: keys.amm_config,
+                pool_state: keys.pool_id,
+                input_vault: trade.input_vault,
+                output_vault: trade.output_vault,
+                observation_state: keys.observation_state,
+            },
+            input_token_mint: trade.input_mint,
+            buyback_mint: trade.output_mint,
+            buyback_token_program: trade.output_token_program,
+        },
+        instruction::ExecuteBuyback {},
+    ))
+}
+
+/// Collect the protocol fees accrued to the pool, deposited to the `revenue_program` of the
+/// amm config when it sets one
+pub fn collect_protocol_fee(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    recipient_token_0_account: Pubkey,
+    recipient_token_1_account: Pubkey,
+    revenue_program: Option<Pubkey>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Instruction {
+    build(
+        accounts::CollectProtocolFee {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            amm_config: keys.amm_config,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            recipient_token_0_account,
+            recipient_token_1_account,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            revenue_program,
+        },
+        instruction::CollectProtocolFee {
+            amount_0_requested,
+            amount_1_requested,
+        },
+    )
+}
+
+/// Collect the fund fees accrued to the pool, the share of the insurance fund of the amm
+/// config is sent to the token_0 and token_1 accounts of `insurance_fund_tokens`, required
+/// when the amm config sets an insurance fund rate
+pub fn collect_fund_fee(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    recipient_token_0_account: Pubkey,
+    recipient_token_1_account: Pubkey,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+    insurance_fund_tokens: Option<(Pubkey, Pubkey)>,
+) -> Instruction {
+    build(
+        accounts::CollectFundFee {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            amm_config: keys.amm_config,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            recipient_token_0_account,
+            recipient_token_1_account,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            insurance_fund: insurance_fund_tokens.map(|_| pda::insurance_fund(&keys.amm_config).0),
+            insurance_fund_token_0: insurance_fund_tokens.map(|(token_0, _)| token_0),
+            insurance_fund_token_1: insurance_fund_tokens.map(|(_, token_1)| token_1),
+        },
+        instruction::CollectFundFee {
+            amount_0_requested,
+            amount_1_requested,
+        },
+    )
+}
+
+/// Sweep the protocol and fund fees accrued to the pool to the associated token accounts
+/// of `protocol_fee_recipient` and `fund_fee_recipient`, the fee recipients of the amm
+/// config. The caller is paid the crank bounty in `caller_token_0` and `caller_token_1`
+pub fn crank_protocol_fees(
+    keys: &PoolKeys,
+    caller: Pubkey,
+    protocol_fee_recipient: Pubkey,
+    fund_fee_recipient: Pubkey,
+    caller_token_0: Pubkey,
+    caller_token_1: Pubkey,
+) -> Instruction {
+    let token_account = |owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey| {
+        get_associated_token_address_with_program_id(owner, mint, token_program)
+    };
+    build(
+        accounts::CrankProtocolFees {
+            caller,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            amm_config: keys.amm_config,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            protocol_owner_token_0: token_account(
+                &protocol_fee_recipient,
+                &keys.token_0_mint,
+                &keys.token_0_program,
+            ),
+            protocol_owner_token_1: token_account(
+                &protocol_fee_recipient,
+                &keys.token_1_mint,
+                &keys.token_1_program,
+            ),
+            fund_owner_token_0: token_account(
+                &fund_fee_recipient,
+                &keys.token_0_mint,
+                &keys.token_0_program,
+            ),
+            fund_owner_token_1: token_account(
+                &fund_fee_recipient,
+                &keys.token_1_mint,
+                &keys.token_1_program,
+            ),
+            caller_token_0,
+            caller_token_1,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        },
+        instruction::CrankProtocolFees {},
+    )
+}
+
+/// Collect all the protocol fees accrued to many pools of `amm_config`, each pool with its
+/// token_0 and token_1 recipient token accounts
+pub fn collect_protocol_fees_batch(
+    amm_config: Pubkey,
+    owner: Pubkey,
+    pools: &[(PoolKeys, Pubkey, Pubkey)],
+) -> Instruction {
+    let mut instruction = build(
+        accounts::CollectProtocolFeesBatch {
+            owner,
+            authority: pda::authority().0,
+            amm_config,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        },
+        instruction::CollectProtocolFeesBatch {},
+    );
+    for (keys, recipient_token_0_account, recipient_token_1_account) in pools {
+        instruction.accounts.extend([
+            AccountMeta::new(keys.pool_id, false),
+            AccountMeta::new(keys.token_0_vault, false),
+            AccountMeta::new(keys.token_1_vault, false),
+            AccountMeta::new_readonly(keys.token_0_mint, false),
+            AccountMeta::new_readonly(keys.token_1_mint, false),
+            AccountMeta::new(*recipient_token_0_account, false),
+            AccountMeta::new(*recipient_token_1_account, false),
+        ]);
+    }
+    instruction
+}
+
+/// List the pool in the registry of its mint pair
+pub fn register_pool(keys: &PoolKeys, payer: Pubkey) -> Instruction {
+    build(
+        accounts::RegisterPool {
+            payer,
+            pool_state: keys.pool_id,
+            amm_config: keys.amm_config,
+            token_0_mint: keys.token_0_mint,
+            token_1_mint: keys.token_1_mint,
+            pool_registry: pda::pool_registry(&keys.token_0_mint, &keys.token_1_mint).0,
+            system_program: system_program::id(),
+        },
+        instruction::RegisterPool {},
+    )
+}
+
+/// Create the swap volume and fee stats of the pools of `amm_config`
+pub fn create_protocol_stats(amm_config: Pubkey, payer: Pubkey) -> Instruction {
+    build(
+        accounts::CreateProtocolStats {
+            payer,
+            amm_config,
+            protocol_stats: pda::protocol_stats(&amm_config).0,
+            system_program: system_program::id(),
+        },
+        instruction::CreateProtocolStats {},
+    )
+}
+
+/// Create the compact price and reserves snapshot of the pool, refreshed by the swaps
+/// passing it
+pub fn create_pool_snapshot(keys: &PoolKeys, payer: Pubkey) -> Instruction {
+    build(
+        accounts::CreatePoolSnapshot {
+            payer,
+            pool_state: keys.pool_id,
+            pool_snapshot: pda::pool_snapshot(&keys.pool_id).0,
+            system_program: system_program::id(),
+        },
+        instruction::CreatePoolSnapshot {},
+    )
+}
+
+/// Create the trader registry of `authority`
+pub fn create_trader_registry(authority: Pubkey) -> Instruction {
+    build(
+        accounts::CreateTraderRegistry {
+            authority,
+            trader_registry: pda::trader_registry(&authority).0,
+            system_program: system_program::id(),
+        },
+        instruction::CreateTraderRegistry {},
+    )
+}
+
+/// Register `trader` in the trader registry of `authority`
+pub fn add_registered_trader(authority: Pubkey, trader: Pubkey) -> Instruction {
+    let trader_registry = pda::trader_registry(&authority).0;
+    build(
+        accounts::AddRegisteredTrader {
+            authority,
+            trader_registry,
+            trader_entry: pda::trader_entry(&trader_registry, &trader).0,
+            system_program: system_program::id(),
+        },
+        instruction::AddRegisteredTrader { trader },
+    )
+}
+
+/// Remove `trader` from the trader registry of `authority`
+pub fn remove_registered_trader(authority: Pubkey, trader: Pubkey) -> Instruction {
+    let trader_registry = pda::trader_registry(&authority).0;
+    build(
+        accounts::RemoveRegisteredTrader {
+            authority,
+            trader_registry,
+            trader_entry: pda::trader_entry(&trader_registry, &trader).0,
+        },
+        instruction::RemoveRegisteredTrader {},
+    )
+}
+
+/// Restrict the swaps of a pool to the traders of `trader_registry`, every trader is
+/// allowed again when it is None
+pub fn update_pool_trader_registry(
+    keys: &PoolKeys,
+    authority: Pubkey,
+    trader_registry: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::UpdatePoolTraderRegistry {
+            authority,
+            pool_state: keys.pool_id,
+            trader_registry,
+        },
+        instruction::UpdatePoolTraderRegistry {},
+    )
+}
+
+/// Set the fewest and most token_0 a swap of a pool trades, 0 disables a limit
+pub fn update_pool_trade_size_limits(
+    keys: &PoolKeys,
+    authority: Pubkey,
+    min_trade_amount: u64,
+    max_trade_amount: u64,
+) -> Instruction {
+    build(
+        accounts::UpdatePoolTradeSizeLimits {
+            authority,
+            pool_state: keys.pool_id,
+        },
+        instruction::UpdatePoolTradeSizeLimits {
+            min_trade_amount,
+            max_trade_amount,
+        },
+    )
+}
+
+/// Set the trading window and the weekly trading hours of a pool, `trading_days` has bit 0
+/// for Monday to bit 6 for Sunday and the hours are seconds since 00:00 UTC, 0 disables
+/// the window bounds and the weekly hours
+pub fn update_pool_trading_schedule(
+    keys: &PoolKeys,
+    authority: Pubkey,
+    trading_start_time: u64,
+    trading_end_time: u64,
+    trading_days: u8,
+    trading_day_open: u32,
+    trading_day_close: u32,
+) -> Instruction {
+    build(
+        accounts::UpdatePoolTradingSchedule {
+            authority,
+            pool_state: keys.pool_id,
+        },
+        instruction::UpdatePoolTradingSchedule {
+            trading_start_time,
+            trading_end_time,
+            trading_days,
+            trading_day_open,
+            trading_day_close,
+        },
+    )
+}
+
+/// Turn the weighted pool of `keys`, not opened yet, into a liquidity bootstrapping pool
+/// whose token_0 weight decays from `start_weight` at the open time to 50/50 over
+/// `lbp_duration` seconds
+pub fn configure_lbp(
+    keys: &PoolKeys,
+    pool_creator: Pubkey,
+    start_weight: u64,
+    lbp_duration: u32,
+) -> Instruction {
+    build(
+        accounts::ConfigureLbp {
+            pool_creator,
+            pool_state: keys.pool_id,
+        },
+        instruction::ConfigureLbp {
+            start_weight,
+            lbp_duration,
+        },
+    )
+}
+
+/// Convert the liquidity bootstrapping pool of `keys` to a constant product pool once its
+/// weights reached 50/50
+pub fn finalize_lbp(keys: &PoolKeys) -> Instruction {
+    build(
+        accounts::FinalizeLbp {
+            pool_state: keys.pool_id,
+        },
+        instruction::FinalizeLbp {},
+    )
+}
+
+/// Apply the admin `ops` to `pools` at once, the `pool_index` of an op indexes `pools`.
+/// The amm configs of the pools whose trade fee rate is set must be in `amm_configs`
+pub fn batch_admin(
+    pools: &[Pubkey],
+    amm_configs: &[Pubkey],
+    ops: Vec<BatchAdminOp>,
+) -> Instruction {
+    let mut instruction = build(
+        accounts::BatchAdmin {
+            authority: raydium_cp_swap::admin::ID,
+        },
+        instruction::BatchAdmin { ops },
+    );
+    instruction.accounts.extend(
+        pools
+            .iter()
+            .map(|pool_id| AccountMeta::new(*pool_id, false))
+            .chain(
+                amm_configs
+                    .iter()
+                    .map(|amm_config| AccountMeta::new_readonly(*amm_config, false)),
+            ),
+    );
+    instruction
+}
+
+/// Set the seconds the deposited lp tokens of a pool are locked and the early exit fee
+/// their withdrawals pay meanwhile, a duration of 0 disables the lockup
+pub fn update_pool_lockup(
+    keys: &PoolKeys,
+    authority: Pubkey,
+    min_lock_duration: u32,
+    early_exit_fee_bps: u16,
+) -> Instruction {
+    build(
+        accounts::UpdatePoolLockup {
+            authority,
+            pool_state: keys.pool_id,
+        },
+        instruction::UpdatePoolLockup {
+            min_lock_duration,
+            early_exit_fee_bps,
+        },
+    )
+}
+
+/// Authorize `authorized_strategy` to borrow up to `max_borrow_bps` of the reserves of the
+/// pool, signed by the admin or the custom authority of the pool
+pub fn update_pool_strategy(
+    keys: &PoolKeys,
+    authority: Pubkey,
+    authorized_strategy: Pubkey,
+    max_borrow_bps: u16,
+) -> Instruction {
+    build(
+        accounts::UpdatePoolStrategy {
+            authority,
+            pool_state: keys.pool_id,
+        },
+        instruction::UpdatePoolStrategy {
+            authorized_strategy,
+            max_borrow_bps,
+        },
+    )
+}
+
+/// Borrow `amount_0` and `amount_1` from the vaults of the pool, the transaction must repay
+/// them with `repay_flash_loan` after this instruction
+pub fn flash_loan(
+    keys: &PoolKeys,
+    borrower: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    amount_0: u64,
+    amount_1: u64,
+) -> Instruction {
+    build(
+        accounts::FlashLoan {
+            borrower,
+            authority: keys.authority,
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+            instructions: sysvar::instructions::id(),
+        },
+        instruction::FlashLoan { amount_0, amount_1 },
+    )
+}
+
+/// Repay the flash loan of the pool with its fee from the token accounts of `payer`
+pub fn repay_flash_loan(
+    keys: &PoolKeys,
+    payer: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+) -> Instruction {
+    build(
+        accounts::RepayFlashLoan {
+            payer,
+            pool_state: keys.pool_id,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+        },
+        instruction::RepayFlashLoan {},
+    )
+}
+
+/// Lend `amount_0` and `amount_1` of the reserves of the pool to its authorized strategy
+pub fn strategy_borrow(
+    keys: &PoolKeys,
+    strategy: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    amount_0: u64,
+    amount_1: u64,
+) -> Instruction {
+    build(
+        accounts::StrategyBorrow {
+            strategy,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+        },
+        instruction::StrategyBorrow { amount_0, amount_1 },
+    )
+}
+
+/// Return `amount_0` and `amount_1` to the vaults of the pool from the token accounts of
+/// `payer`, against the reserves borrowed by the strategy
+pub fn strategy_repay(
+    keys: &PoolKeys,
+    payer: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    amount_0: u64,
+    amount_1: u64,
+) -> Instruction {
+    build(
+        accounts::StrategyRepay {
+            payer,
+            pool_state: keys.pool_id,
+            token_0_account,
+            token_1_account,
+            token_0_vault: keys.token_0_vault,
+            token_1_vault: keys.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: keys.token_0_mint,
+            vault_1_mint: keys.token_1_mint,
+        },
+        instruction::StrategyRepay { amount_0, amount_1 },
+    )
+}
+
+/// Update the `param` of `amm_config` to `value`, signed by the admin. The fee rate
+/// changes are queued, paid by the admin, until `execute_config_change` applies them; the
+/// params set from an address expect it appended as remaining account
+pub fn update_amm_config(owner: Pubkey, amm_config: Pubkey, param: u8, value: u64) -> Instruction {
+    let timelocked = is_fee_rate_param(param);
+    build(
+        accounts::UpdateAmmConfig {
+            owner,
+            amm_config,
+            pending_config_change: timelocked
+                .then(|| pda::pending_config_change(&amm_config, param).0),
+            system_program: timelocked.then(system_program::id),
+        },
+        instruction::UpdateAmmConfig { param, value },
+    )
+}
+
+/// Apply the queued change of the `param` of `amm_config` once its timelock expired,
+/// refunding its rent to the `payer` that queued it
+pub fn execute_config_change(amm_config: Pubkey, param: u8, payer: Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteConfigChange {
+            amm_config,
+            pending_config_change: pda::pending_config_change(&amm_config, param).0,
+            payer,
+        },
+        instruction::ExecuteConfigChange {},
+    )
+}
+
+/// Propose `new_admin` for the `role` of the amm config or pool state `target`, signed and
+/// paid by the current holder of the role
+pub fn propose_new_admin(
+    proposer: Pubkey,
+    target: Pubkey,
+    role: u8,
+    new_admin: Pubkey,
+) -> Instruction {
+    build(
+        accounts::ProposeNewAdmin {
+            proposer,
+            target,
+            pending_admin: pda::pending_admin(&target, role).0,
+            system_program: system_program::id(),
+        },
+        instruction::ProposeNewAdmin { role, new_admin },
+    )
+}
+
+/// Accept the `role` of `target` proposed by `proposer`, signed by the proposed admin
+pub fn accept_admin(new_admin: Pubkey, target: Pubkey, role: u8, proposer: Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdmin {
+            new_admin,
+            target,
+            pending_admin: pda::pending_admin(&target, role).0,
+            proposer,
+        },
+        instruction::AcceptAdmin { role },
+    )
+}
+
+/// Transfer the custom authority of a pool as break-glass, signed by the admin, or renounce
+/// it with the default `new_authority`, signed by the current custom authority
+pub fn set_pool_custom_authority(
+    authority: Pubkey,
+    pool_id: Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    build(
+        accounts::SetPoolCustomAuthority {
+            authority,
+            pool_state: pool_id,
+        },
+        instruction::SetPoolCustomAuthority { new_authority },
+    )
+}
+
+/// Queue the reassignment of a pool to `new_amm_config`, signed and paid by the admin
+pub fn reassign_pool_config(keys: &PoolKeys, owner: Pubkey, new_amm_config: Pubkey) -> Instruction {
+    build(
+        accounts::ReassignPoolConfig {
+            owner,
+            pool_state: keys.pool_id,
+            new_amm_config,
+            pending_pool_config_change: pda::pending_pool_config_change(&keys.pool_id).0,
+            system_program: system_program::id(),
+        },
+        instruction::ReassignPoolConfig {},
+    )
+}
+
+/// Apply the queued reassignment of a pool to `new_amm_config` once its timelock expired,
+/// refunding its rent to the `payer` that queued it
+pub fn execute_pool_config_change(
+    keys: &PoolKeys,
+    new_amm_config: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    build(
+        accounts::ExecutePoolConfigChange {
+            pool_state: keys.pool_id,
+            new_amm_config,
+            pending_pool_config_change: pda::pending_pool_config_change(&keys.pool_id).0,
+            payer,
+        },
+        instruction::ExecutePoolConfigChange {},
+    )
+}
+
+/// Move the liquidity of `lp_token_amount` lp tokens of the admin from the pool of `from`
+/// to the pool of `to` of the same mints, between the associated lp token accounts of the
+/// admin. The tokens left over by the ratio of the pools are refunded to `token_0_account`
+/// and `token_1_account`
+pub fn rebalance_between_pools(
+    from: &PoolKeys,
+    to: &PoolKeys,
+    owner: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+    lp_token_amount: u64,
+    minimum_lp_token_amount_out: u64,
+) -> Instruction {
+    build(
+        accounts::RebalanceBetweenPools {
+            owner,
+            authority: from.authority,
+            from_pool_state: from.pool_id,
+            to_pool_state: to.pool_id,
+            owner_from_lp_token: get_associated_token_address_with_program_id(
+                &owner,
+                &from.lp_mint,
+                &spl_token::id(),
+            ),
+            owner_to_lp_token: get_associated_token_address_with_program_id(
+                &owner,
+                &to.lp_mint,
+                &spl_token::id(),
+            ),
+            token_0_account,
+            token_1_account,
+            from_token_0_vault: from.token_0_vault,
+            from_token_1_vault: from.token_1_vault,
+            to_token_0_vault: to.token_0_vault,
+            to_token_1_vault: to.token_1_vault,
+            vault_0_mint: from.token_0_mint,
+            vault_1_mint: from.token_1_mint,
+            from_lp_mint: from.lp_mint,
+            to_lp_mint: to.lp_mint,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        },
+        instruction::RebalanceBetweenPools {
+            lp_token_amount,
+            minimum_lp_token_amount_out,
+        },
+    )
+}
+
+/// Issue the token badge of a vetted token program 2022 mint, signed and paid by the admin
+pub fn create_token_badge(owner: Pubkey, token_mint: Pubkey) -> Instruction {
+    build(
+        accounts::CreateTokenBadge {
+            owner,
+            token_mint,
+            token_badge: pda::token_badge(&token_mint).0,
+            system_program: system_program::id(),
+        },
+        instruction::CreateTokenBadge {},
+    )
+}
+
+/// Revoke the token badge of a mint, its rent is refunded to the admin
+pub fn close_token_badge(owner: Pubkey, token_mint: Pubkey) -> Instruction {
+    build(
+        accounts::CloseTokenBadge {
+            owner,
+            token_badge: pda::token_badge(&token_mint).0,
+        },
+        instruction::CloseTokenBadge {},
+    )
+}
+
+/// Create the insurance fund of `amm_config`, signed and paid by the admin
+pub fn create_insurance_fund(owner: Pubkey, amm_config: Pubkey) -> Instruction {
+    build(
+        accounts::CreateInsuranceFund {
+            owner,
+            amm_config,
+            insurance_fund: pda::insurance_fund(&amm_config).0,
+            system_program: system_program::id(),
+        },
+        instruction::CreateInsuranceFund {},
+    )
+}
+
+/// Inject `amount` tokens of the insurance fund token account `insurance_fund_token` into
+/// the vault of `mint` of the pool, signed by the admin
+pub fn cover_shortfall(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    insurance_fund_token: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let vault = if mint == keys.token_0_mint {
+        keys.token_0_vault
+    } else {
+        keys.token_1_vault
+    };
+    build(
+        accounts::CoverShortfall {
+            owner,
+            insurance_fund: pda::insurance_fund(&keys.amm_config).0,
+            pool_state: keys.pool_id,
+            insurance_fund_token,
+            vault,
+            vault_mint: mint,
+            token_program,
+        },
+        instruction::CoverShortfall { amount },
+    )
+}
+
+/// The vault, the mint and the token account of `token_accounts` of each multi token pool
+/// token, the remaining accounts of its liquidity instructions
+fn multi_pool_token_accounts(keys: &MultiPoolKeys, token_accounts: &[Pubkey]) -> Vec<AccountMeta> {
+    keys.token_vaults
+        .iter()
+        .zip(&keys.token_mints)
+        .zip(token_accounts)
+        .flat_map(|((vault, mint), token_account)| {
+            [
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(*token_account, false),
+            ]
+        })
+        .collect()
+}
+
+/// Create the multi token pool of `keys` at its random address, which must also sign,
+/// with the `init_amounts` of the creator token accounts, both in the order of the pool
+/// tokens. The fee to create a pool must be paid in native SOL
+pub fn initialize_multi_pool(
+    keys: &MultiPoolKeys,
+    creator: Pubkey,
+    creator_token_accounts: &[Pubkey],
+    create_pool_fee: Pubkey,
+    amp: u64,
+    init_amounts: Vec<u64>,
+    open_time: u64,
+) -> Instruction {
+    let mut instruction = build(
+        accounts::InitializeMultiPool {
+            creator,
+            amm_config: keys.amm_config,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            lp_mint: keys.lp_mint,
+            creator_lp_token: get_associated_token_address_with_program_id(
+                &creator,
+                &keys.lp_mint,
+                &spl_token::id(),
+            ),
+            create_pool_fee,
+            mint_policy: None,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            associated_token_program: associated_token::ID,
+            system_program: system_program::id(),
+        },
+        instruction::InitializeMultiPool {
+            amp,
+            init_amounts,
+            open_time,
+        },
+    );
+    instruction.accounts.extend(
+        keys.token_mints
+            .iter()
+            .zip(&keys.token_vaults)
+            .zip(creator_token_accounts)
+            .flat_map(|((mint, vault), creator_token)| {
+                [
+                    AccountMeta::new_readonly(*mint, false),
+                    AccountMeta::new(*vault, false),
+                    AccountMeta::new(*creator_token, false),
+                ]
+            }),
+    );
+    instruction
+}
+
+/// Deposit every token of a multi token pool from the owner token accounts, in the order
+/// of the pool tokens, for `lp_token_amount` lp tokens
+pub fn multi_pool_deposit(
+    keys: &MultiPoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    owner_token_accounts: &[Pubkey],
+    lp_token_amount: u64,
+    maximum_token_amounts: Vec<u64>,
+) -> Instruction {
+    let mut instruction = build(
+        accounts::MultiPoolDeposit {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            owner_lp_token,
+            lp_mint: keys.lp_mint,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        },
+        instruction::MultiPoolDeposit {
+            lp_token_amount,
+            maximum_token_amounts,
+        },
+    );
+    instruction
+        .accounts
+        .extend(multi_pool_token_accounts(keys, owner_token_accounts));
+    instruction
+}
+
+/// Burn `lp_token_amount` lp tokens for every token of a multi token pool, sent to the
+/// token accounts in the order of the pool tokens
+pub fn multi_pool_withdraw(
+    keys: &MultiPoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_accounts: &[Pubkey],
+    lp_token_amount: u64,
+    minimum_token_amounts: Vec<u64>,
+) -> Instruction {
+    let mut instruction = build(
+        accounts::MultiPoolWithdraw {
+            owner,
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            owner_lp_token,
+            lp_mint: keys.lp_mint,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        },
+        instruction::MultiPoolWithdraw {
+            lp_token_amount,
+            minimum_token_amounts,
+        },
+    );
+    instruction
+        .accounts
+        .extend(multi_pool_token_accounts(keys, token_accounts));
+    instruction
+}
+
+/// Swap `amount_in` of the token at `input_index` of a multi token pool for the token at
+/// `output_index`
+pub fn multi_pool_swap(
+    keys: &MultiPoolKeys,
+    payer: Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    input_index: usize,
+    output_index: usize,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let mut instruction = build(
+        accounts::MultiPoolSwap {
+            payer,
+            authority: keys.authority,
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            input_token_account,
+            output_token_account,
+            input_vault: keys.token_vaults[input_index],
+            output_vault: keys.token_vaults[output_index],
+            input_token_program: keys.token_programs[input_index],
+            output_token_program: keys.token_programs[output_index],
+            input_token_mint: keys.token_mints[input_index],
+            output_token_mint: keys.token_mints[output_index],
+        },
+        instruction::MultiPoolSwap {
+            amount_in,
+            minimum_amount_out,
+        },
+    );
+    instruction.accounts.extend(
+        keys.token_vaults
+            .iter()
+            .map(|vault| AccountMeta::new_readonly(*vault, false)),
+    );
+    instruction
+}
+
+/// Create the receipt tree of the compressed deposit receipts of a pool
+pub fn create_receipt_tree(pool_id: Pubkey, payer: Pubkey) -> Instruction {
+    build(
+        accounts::CreateReceiptTree {
+            payer,
+            pool_state: pool_id,
+            receipt_tree: pda::receipt_tree(&pool_id).0,
+            system_program: system_program::id(),
+        },
+        instruction::CreateReceiptTree {},
+    )
+}
+
+/// Close the deposit receipt of `owner` into a leaf of the receipt tree of the pool
+pub fn compress_deposit_receipt(pool_id: Pubkey, owner: Pubkey) -> Instruction {
+    build(
+        accounts::CompressDepositReceipt {
+            owner,
+            pool_state: pool_id,
+            deposit_receipt: pda::deposit_receipt(&pool_id, &owner).0,
+            receipt_tree: pda::receipt_tree(&pool_id).0,
+        },
+        instruction::CompressDepositReceipt {},
+    )
+}
+
+/// Restore the compressed deposit receipt of `owner` at `leaf_index`, with the `proof` of
+/// its leaf against `root`, such as built by `receipt_proof` from the leaves of the tree
+pub fn decompress_deposit_receipt(
+    pool_id: Pubkey,
+    owner: Pubkey,
+    root: [u8; 32],
+    leaf_index: u64,
+    lp_amount: u64,
+    last_deposit_time: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::DecompressDepositReceipt {
+            owner,
+            pool_state: pool_id,
+            receipt_tree: pda::receipt_tree(&pool_id).0,
+            deposit_receipt: pda::deposit_receipt(&pool_id, &owner).0,
+            system_program: system_program::id(),
+        },
+        instruction::DecompressDepositReceipt {
+            root,
+            leaf_index,
+            lp_amount,
+            last_deposit_time,
+            proof,
+        },
+    )
+}
@@ -0,0 +1,13 @@
+//! Client of the Raydium constant product AMM, for off-chain integrators and the
+//! programs calling it through CPI.
+//!
+//! * [`pda`] derives the addresses of the program accounts
+//! * [`instructions`] builds the instructions from typed arguments
+//! * [`state`] decodes the program accounts from their data
+
+pub mod instructions;
+pub mod pda;
+pub mod state;
+
+pub use pda::{MultiPoolKeys, PoolKeys, TradeKeys};
+pub use raydium_cp_swap::{self, ID};
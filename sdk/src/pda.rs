@@ -0,0 +1,458 @@
+use anchor_lang::prelude::Pubkey;
+use raydium_cp_swap::states::{
+    PoolState, AMM_CONFIG_SEED, DEPOSIT_RECEIPT_SEED, FEE_EXEMPTION_LIST_SEED, INSURANCE_FUND_SEED,
+    LIMIT_ORDER_SEED, LIMIT_ORDER_VAULT_SEED, LP_MINT_REGISTRY_SEED, MINT_POLICY_SEED,
+    OBSERVATION_SEED, PENDING_ADMIN_SEED, PENDING_CONFIG_CHANGE_SEED,
+    PENDING_POOL_CONFIG_CHANGE_SEED, PERSONAL_POSITION_SEED, POOL_LP_MINT_SEED, POOL_METADATA_SEED,
+    POOL_REGISTRY_SEED, POOL_SEED, POOL_SNAPSHOT_SEED, POOL_VAULT_SEED, POSITION_REWARD_SEED,
+    PROTOCOL_STATS_SEED, RECEIPT_TREE_SEED, REWARD_STATE_SEED, REWARD_VAULT_SEED,
+    SESSION_AUTHORITY_SEED, SWAP_ORDER_AUTHORITY_SEED, SWAP_ORDER_NONCE_SEED, TOKEN_BADGE_SEED,
+    TRADER_ENTRY_SEED, TRADER_REGISTRY_SEED, TRADER_STATS_SEED,
+};
+use raydium_cp_swap::{AUTH_SEED, ID};
+
+/// The authority of the pool vaults and lp mints
+pub fn authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &ID)
+}
+
+/// The amm config of a fee tier
+pub fn amm_config(index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AMM_CONFIG_SEED.as_bytes(), &index.to_be_bytes()], &ID)
+}
+
+/// The pool of a mint pair in an amm config, `token_0_mint` sorts before `token_1_mint`
+pub fn pool(amm_config: &Pubkey, token_0_mint: &Pubkey, token_1_mint: &Pubkey) -> (Pubkey, u8) {
+    salted_pool(amm_config, token_0_mint, token_1_mint, 0)
+}
+
+/// The pool of a mint pair at the address salted with `pool_salt`, the canonical pool of
+/// `pool` when it is 0
+pub fn salted_pool(
+    amm_config: &Pubkey,
+    token_0_mint: &Pubkey,
+    token_1_mint: &Pubkey,
+    pool_salt: u8,
+) -> (Pubkey, u8) {
+    let salt = [pool_salt];
+    let mut seeds: Vec<&[u8]> = vec![
+        POOL_SEED.as_bytes(),
+        amm_config.as_ref(),
+        token_0_mint.as_ref(),
+        token_1_mint.as_ref(),
+    ];
+    if pool_salt != 0 {
+        seeds.push(&salt);
+    }
+    Pubkey::find_program_address(&seeds, &ID)
+}
+
+/// Every pool address a mint pair can have in an amm config with its salt, starting with
+/// the canonical pool
+pub fn pool_variants(
+    amm_config: &Pubkey,
+    token_0_mint: &Pubkey,
+    token_1_mint: &Pubkey,
+) -> Vec<(u8, Pubkey)> {
+    (0..=u8::MAX)
+        .map(|pool_salt| {
+            (
+                pool_salt,
+                salted_pool(amm_config, token_0_mint, token_1_mint, pool_salt).0,
+            )
+        })
+        .collect()
+}
+
+/// The vault of a pool holding the tokens of `mint`
+pub fn pool_vault(pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POOL_VAULT_SEED.as_bytes(), pool.as_ref(), mint.as_ref()],
+        &ID,
+    )
+}
+
+/// The lp mint of a pool
+pub fn pool_lp_mint(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_LP_MINT_SEED.as_bytes(), pool.as_ref()], &ID)
+}
+
+/// The oracle observations of a pool
+pub fn observation(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[OBSERVATION_SEED.as_bytes(), pool.as_ref()], &ID)
+}
+
+/// The registry resolving an lp mint to its pool
+pub fn lp_mint_registry(lp_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LP_MINT_REGISTRY_SEED.as_bytes(), lp_mint.as_ref()], &ID)
+}
+
+/// The registry listing the pools of a mint pair
+pub fn pool_registry(token_0_mint: &Pubkey, token_1_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            POOL_REGISTRY_SEED.as_bytes(),
+            token_0_mint.as_ref(),
+            token_1_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// The display data of a pool
+pub fn pool_metadata(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_METADATA_SEED.as_bytes(), pool.as_ref()], &ID)
+}
+
+/// The compact price and reserves snapshot of a pool
+pub fn pool_snapshot(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_SNAPSHOT_SEED.as_bytes(), pool.as_ref()], &ID)
+}
+
+/// The swap volume and fee stats of the pools of an amm config
+pub fn protocol_stats(amm_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROTOCOL_STATS_SEED.as_bytes(), amm_config.as_ref()], &ID)
+}
+
+/// The traders of an amm config whose swaps pay a reduced or no protocol fee
+pub fn fee_exemption_list(amm_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FEE_EXEMPTION_LIST_SEED.as_bytes(), amm_config.as_ref()],
+        &ID,
+    )
+}
+
+/// The mints new pools of an amm config can be created with
+pub fn mint_policy(amm_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_POLICY_SEED.as_bytes(), amm_config.as_ref()], &ID)
+}
+
+/// The attestation of the admin that a mint with a risky extension can be pooled
+pub fn token_badge(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TOKEN_BADGE_SEED.as_bytes(), mint.as_ref()], &ID)
+}
+
+/// The insurance fund of an amm config, the authority of its token accounts
+pub fn insurance_fund(amm_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INSURANCE_FUND_SEED.as_bytes(), amm_config.as_ref()], &ID)
+}
+
+/// The volume and fee rebates of a trader in a pool
+pub fn trader_stats(pool: &Pubkey, trader: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TRADER_STATS_SEED.as_bytes(), pool.as_ref(), trader.as_ref()],
+        &ID,
+    )
+}
+
+/// The proposed admin of the `role` of an amm config or pool state, see `AdminRole`
+pub fn pending_admin(target: &Pubkey, role: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PENDING_ADMIN_SEED.as_bytes(), target.as_ref(), &[role]],
+        &ID,
+    )
+}
+
+/// The queued change of the fee rate `param` of an amm config
+pub fn pending_config_change(amm_config: &Pubkey, param: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PENDING_CONFIG_CHANGE_SEED.as_bytes(),
+            amm_config.as_ref(),
+            &[param],
+        ],
+        &ID,
+    )
+}
+
+/// The queued reassignment of a pool to another amm config
+pub fn pending_pool_config_change(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PENDING_POOL_CONFIG_CHANGE_SEED.as_bytes(), pool.as_ref()],
+        &ID,
+    )
+}
+
+/// The lp tokens of an owner locked in a pool with a withdrawal lockup
+pub fn deposit_receipt(pool: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DEPOSIT_RECEIPT_SEED.as_bytes(),
+            pool.as_ref(),
+            owner.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// The concurrent merkle tree of the compressed deposit receipts of a pool
+pub fn receipt_tree(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_TREE_SEED.as_bytes(), pool.as_ref()], &ID)
+}
+
+/// The personal position of an owner in a pool in position mode
+pub fn personal_position(pool: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PERSONAL_POSITION_SEED.as_bytes(),
+            pool.as_ref(),
+            owner.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// The reward emissions of a pool in position mode
+pub fn reward_state(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_STATE_SEED.as_bytes(), pool.as_ref()], &ID)
+}
+
+/// The vault holding the funded rewards of a reward mint
+pub fn reward_vault(reward_state: &Pubkey, reward_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            REWARD_VAULT_SEED.as_bytes(),
+            reward_state.as_ref(),
+            reward_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// The rewards earned by a personal position
+pub fn position_reward(personal_position: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POSITION_REWARD_SEED.as_bytes(), personal_position.as_ref()],
+        &ID,
+    )
+}
+
+/// The trader registry of an authority
+pub fn trader_registry(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TRADER_REGISTRY_SEED.as_bytes(), authority.as_ref()], &ID)
+}
+
+/// The entry allowing a trader to swap in the pools of a trader registry
+pub fn trader_entry(trader_registry: &Pubkey, trader: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TRADER_ENTRY_SEED.as_bytes(),
+            trader_registry.as_ref(),
+            trader.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// The session delegating the liquidity management of an owner in a pool to a session key
+pub fn session_authority(pool: &Pubkey, owner: &Pubkey, session_key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SESSION_AUTHORITY_SEED.as_bytes(),
+            pool.as_ref(),
+            owner.as_ref(),
+            session_key.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// The next signed order nonce of an owner
+pub fn swap_order_nonce(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SWAP_ORDER_NONCE_SEED.as_bytes(), owner.as_ref()], &ID)
+}
+
+/// The delegate an owner approves on the input token accounts of its signed orders
+pub fn swap_order_authority(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SWAP_ORDER_AUTHORITY_SEED.as_bytes(), owner.as_ref()], &ID)
+}
+
+/// The limit order `order_id` of an owner in a pool
+pub fn limit_order(pool: &Pubkey, owner: &Pubkey, order_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool.as_ref(),
+            owner.as_ref(),
+            &order_id.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// The vault escrowing the input tokens of a limit order
+pub fn limit_order_vault(order: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LIMIT_ORDER_VAULT_SEED.as_bytes(), order.as_ref()], &ID)
+}
+
+/// The addresses of a pool the instructions trading or providing liquidity in it need
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub pool_id: Pubkey,
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    /// SPL programs of the token_0 and token_1 mints
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub lp_mint: Pubkey,
+    pub observation_state: Pubkey,
+    /// The oracle price update account the swaps of an oracle guarded pool need
+    pub price_oracle: Option<Pubkey>,
+    /// The trader registry whose entries the swaps of a permissioned pool need
+    pub trader_registry: Option<Pubkey>,
+}
+
+impl PoolKeys {
+    /// Derive the keys of the pool of a mint pair in an amm config, the mints are sorted
+    /// the way the pool stores them
+    pub fn new(
+        amm_config: Pubkey,
+        mint_a: Pubkey,
+        mint_a_program: Pubkey,
+        mint_b: Pubkey,
+        mint_b_program: Pubkey,
+    ) -> Self {
+        Self::new_salted(
+            amm_config,
+            mint_a,
+            mint_a_program,
+            mint_b,
+            mint_b_program,
+            0,
+        )
+    }
+
+    /// Derive the keys of the pool of a mint pair at the address salted with `pool_salt`
+    pub fn new_salted(
+        amm_config: Pubkey,
+        mint_a: Pubkey,
+        mint_a_program: Pubkey,
+        mint_b: Pubkey,
+        mint_b_program: Pubkey,
+        pool_salt: u8,
+    ) -> Self {
+        let ((token_0_mint, token_0_program), (token_1_mint, token_1_program)) = if mint_a < mint_b
+        {
+            ((mint_a, mint_a_program), (mint_b, mint_b_program))
+        } else {
+            ((mint_b, mint_b_program), (mint_a, mint_a_program))
+        };
+        let pool_id = salted_pool(&amm_config, &token_0_mint, &token_1_mint, pool_salt).0;
+        Self {
+            pool_id,
+            amm_config,
+            authority: authority().0,
+            token_0_mint,
+            token_1_mint,
+            token_0_vault: pool_vault(&pool_id, &token_0_mint).0,
+            token_1_vault: pool_vault(&pool_id, &token_1_mint).0,
+            token_0_program,
+            token_1_program,
+            lp_mint: pool_lp_mint(&pool_id).0,
+            observation_state: observation(&pool_id).0,
+            price_oracle: None,
+            trader_registry: None,
+        }
+    }
+
+    /// Get the keys of a pool from its state, also for pools created at a random address
+    pub fn from_state(pool_id: Pubkey, pool_state: &PoolState) -> Self {
+        Self {
+            pool_id,
+            amm_config: pool_state.amm_config,
+            authority: authority().0,
+            token_0_mint: pool_state.token_0_mint,
+            token_1_mint: pool_state.token_1_mint,
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            token_0_program: pool_state.token_0_program,
+            token_1_program: pool_state.token_1_program,
+            lp_mint: pool_state.lp_mint,
+            observation_state: pool_state.observation_key,
+            price_oracle: pool_state
+                .is_oracle_guarded()
+                .then_some(pool_state.price_oracle),
+            trader_registry: pool_state
+                .is_trader_registry_enabled()
+                .then_some(pool_state.trader_registry),
+        }
+    }
+
+    /// Get the accounts of the input and output side of a trade selling `input_mint`,
+    /// None when it is not a mint of the pool
+    pub fn trade_keys(&self, input_mint: &Pubkey) -> Option<TradeKeys> {
+        let token_0 = (self.token_0_vault, self.token_0_mint, self.token_0_program);
+        let token_1 = (self.token_1_vault, self.token_1_mint, self.token_1_program);
+        let (input, output) = if *input_mint == self.token_0_mint {
+            (token_0, token_1)
+        } else if *input_mint == self.token_1_mint {
+            (token_1, token_0)
+        } else {
+            return None;
+        };
+        Some(TradeKeys {
+            input_vault: input.0,
+            output_vault: output.0,
+            input_mint: input.1,
+            output_mint: output.1,
+            input_token_program: input.2,
+            output_token_program: output.2,
+        })
+    }
+}
+
+/// The accounts of a pool on the input and output side of a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradeKeys {
+    pub input_vault: Pubkey,
+    pub output_vault: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub input_token_program: Pubkey,
+    pub output_token_program: Pubkey,
+}
+
+/// The addresses of a multi token pool created at the random address `pool_id`, its
+/// tokens sorted by mint the way the pool stores them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiPoolKeys {
+    pub pool_id: Pubkey,
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_mints: Vec<Pubkey>,
+    pub token_vaults: Vec<Pubkey>,
+    /// SPL programs of the token mints
+    pub token_programs: Vec<Pubkey>,
+}
+
+impl MultiPoolKeys {
+    /// Derive the keys of the multi token pool of the mints and their SPL programs
+    pub fn new(pool_id: Pubkey, amm_config: Pubkey, mints: &[(Pubkey, Pubkey)]) -> Self {
+        let mut mints = mints.to_vec();
+        mints.sort_by_key(|(mint, _)| *mint);
+        Self {
+            pool_id,
+            amm_config,
+            authority: authority().0,
+            lp_mint: pool_lp_mint(&pool_id).0,
+            token_vaults: mints
+                .iter()
+                .map(|(mint, _)| pool_vault(&pool_id, mint).0)
+                .collect(),
+            token_mints: mints.iter().map(|(mint, _)| *mint).collect(),
+            token_programs: mints.iter().map(|(_, program)| *program).collect(),
+        }
+    }
+
+    /// The index of `mint` in the pool tokens, None when it is not a mint of the pool
+    pub fn token_index(&self, mint: &Pubkey) -> Option<usize> {
+        self.token_mints
+            .iter()
+            .position(|token_mint| token_mint == mint)
+    }
+}
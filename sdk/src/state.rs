@@ -0,0 +1,42 @@
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{AccountDeserialize, Result, ZeroCopy};
+use raydium_cp_swap::states::{LogPriceObservation, Observation, ObservationState, PoolState};
+
+/// Decode a borsh account of the program, such as `AmmConfig` or `PoolRegistry`,
+/// after checking its discriminator
+pub fn decode_account<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Cast the data of a zero copy account of the program without copying it, after
+/// checking its discriminator
+pub fn load_zero_copy<T: ZeroCopy>(data: &[u8]) -> Result<&T> {
+    let discriminator = T::DISCRIMINATOR;
+    if !data.starts_with(discriminator) {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    data.get(discriminator.len()..discriminator.len() + std::mem::size_of::<T>())
+        .and_then(|data| bytemuck::try_from_bytes(data).ok())
+        .ok_or_else(|| ErrorCode::AccountDidNotDeserialize.into())
+}
+
+/// Cast the data of a pool account
+pub fn load_pool_state(data: &[u8]) -> Result<&PoolState> {
+    load_zero_copy(data)
+}
+
+/// Cast the data of an observation account, the observations appended by
+/// `increase_observation_cardinality` are returned after the state, followed by the
+/// cumulative log prices, empty when they are not recorded
+pub fn load_observation_state(
+    data: &[u8],
+) -> Result<(&ObservationState, &[Observation], &[LogPriceObservation])> {
+    let state = load_zero_copy::<ObservationState>(data)?;
+    let extension_len = ObservationState::extension_len(data.len(), state.is_log_price_recorded())?;
+    let (extension, log_prices) = data[ObservationState::LEN..].split_at(extension_len);
+    let extension =
+        bytemuck::try_cast_slice(extension).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+    let log_prices =
+        bytemuck::try_cast_slice(log_prices).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+    Ok((state, extension, log_prices))
+}
@@ -0,0 +1,423 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    bpf_loader,
+    clock::Clock,
+    ed25519_program,
+    entrypoint::ProcessInstruction,
+    hash::hashv,
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+    system_program,
+    sysvar::{
+        self,
+        instructions::{
+            construct_instructions_data, store_current_index, BorrowedAccountMeta,
+            BorrowedInstruction,
+        },
+    },
+};
+use anchor_lang::{Discriminator, Event, ZeroCopy};
+use anchor_spl::{
+    associated_token::spl_associated_token_account, memo::spl_memo, token::spl_token,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::runtime::{self, with_invoke_context, InstructionAccount};
+
+/// The unix timestamp of the clock of a new bank
+pub const GENESIS_UNIX_TIMESTAMP: i64 = 1_700_000_000;
+/// The slots the clock advances per second warped
+pub const SLOTS_PER_SECOND: u64 = 2;
+
+const NATIVE_LOADER_ID: Pubkey = pubkey!("NativeLoader1111111111111111111111111111111");
+
+/// An account of the bank
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BankAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+/// The instruction of a transaction that failed and its error, the transaction changes
+/// are discarded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionError {
+    pub instruction_index: usize,
+    pub error: ProgramError,
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} failed: {}",
+            self.instruction_index, self.error
+        )
+    }
+}
+
+/// A deterministic single threaded bank running the cp-swap program with the system,
+/// token, token 2022, associated token account and memo programs in process. The ed25519
+/// instructions of a transaction are verified before it runs, as the precompiles are, and
+/// its instructions are readable from the instructions sysvar.
+///
+/// The signatures are not verified: the `signers` of a transaction stand for the
+/// keypairs signing it, so any address can sign, including the admin of the program.
+/// The transactions are atomic and the clock only moves when warped. The tests load
+/// their own programs, such as transfer hooks, with `add_native_program`.
+///
+/// The programs run natively, so only their syscalls and cross program invocations are
+/// metered, at the costs of the compute budget: the compute units consumed are a lower
+/// bound of the cost on chain, which tracks the calls a change adds to an instruction.
+pub struct Bank {
+    accounts: BTreeMap<Pubkey, BankAccount>,
+    native_programs: BTreeMap<Pubkey, ProcessInstruction>,
+    clock: Clock,
+    rent: Rent,
+    key_nonce: u64,
+    logs: Vec<String>,
+    data_logs: Vec<Vec<u8>>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+    compute_units_consumed: u64,
+}
+
+impl Default for Bank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        runtime::install_syscalls();
+        let mut bank = Self {
+            accounts: BTreeMap::new(),
+            native_programs: BTreeMap::new(),
+            clock: Clock {
+                slot: 1,
+                epoch_start_timestamp: GENESIS_UNIX_TIMESTAMP,
+                epoch: 0,
+                leader_schedule_epoch: 1,
+                unix_timestamp: GENESIS_UNIX_TIMESTAMP,
+            },
+            rent: Rent::default(),
+            key_nonce: 0,
+            logs: Vec::new(),
+            data_logs: Vec::new(),
+            return_data: None,
+            compute_units_consumed: 0,
+        };
+        bank.add_program(system_program::ID, NATIVE_LOADER_ID);
+        bank.add_program(ed25519_program::ID, NATIVE_LOADER_ID);
+        for program_id in [
+            raydium_cp_swap::ID,
+            spl_token::ID,
+            spl_token_2022::ID,
+            spl_associated_token_account::ID,
+            spl_memo::ID,
+        ] {
+            bank.add_program(program_id, bpf_loader::ID);
+        }
+        bank.set_sysvar(&sysvar::rent::ID, &bank.rent.clone());
+        bank.set_sysvar(&sysvar::clock::ID, &bank.clock.clone());
+
+        let mut native_mint = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: spl_token::native_mint::DECIMALS,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut native_mint);
+        bank.set_account(
+            spl_token::native_mint::ID,
+            BankAccount {
+                lamports: bank.rent.minimum_balance(native_mint.len()),
+                data: native_mint,
+                owner: spl_token::ID,
+                executable: false,
+            },
+        );
+        bank
+    }
+
+    fn add_program(&mut self, program_id: Pubkey, loader: Pubkey) {
+        self.set_account(
+            program_id,
+            BankAccount {
+                lamports: 1,
+                data: Vec::new(),
+                owner: loader,
+                executable: true,
+            },
+        );
+    }
+
+    /// Load a program running `process_instruction` in process at `program_id`
+    pub fn add_native_program(
+        &mut self,
+        program_id: Pubkey,
+        process_instruction: ProcessInstruction,
+    ) {
+        self.add_program(program_id, bpf_loader::ID);
+        self.native_programs.insert(program_id, process_instruction);
+    }
+
+    fn set_sysvar<T: serde::Serialize>(&mut self, id: &Pubkey, sysvar: &T) {
+        let data = bincode::serialize(sysvar).unwrap();
+        self.set_account(
+            *id,
+            BankAccount {
+                lamports: self.rent.minimum_balance(data.len()),
+                data,
+                owner: sysvar::ID,
+                executable: false,
+            },
+        );
+    }
+
+    /// A new address, derived from the addresses the bank gave before so that the
+    /// tests are reproducible
+    pub fn new_key(&mut self) -> Pubkey {
+        self.key_nonce += 1;
+        Pubkey::new_from_array(hashv(&[b"test-kit", &self.key_nonce.to_le_bytes()]).to_bytes())
+    }
+
+    pub fn rent(&self) -> &Rent {
+        &self.rent
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&BankAccount> {
+        self.accounts.get(key)
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: BankAccount) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    /// Add `lamports` to an account, created as a system account when missing
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_default();
+        account.lamports = account.lamports.checked_add(lamports).unwrap();
+    }
+
+    /// Decode an anchor account of the bank, None when missing or of another type
+    pub fn get_account<T: AccountDeserialize>(&self, key: &Pubkey) -> Option<T> {
+        T::try_deserialize(&mut self.account(key)?.data.as_slice()).ok()
+    }
+
+    /// Decode an anchor zero copy account of the bank, None when missing or of another
+    /// type
+    pub fn get_zero_copy_account<T: ZeroCopy + Discriminator>(&self, key: &Pubkey) -> Option<T> {
+        let data = &self.account(key)?.data;
+        let (discriminator, data) = data.split_at_checked(T::DISCRIMINATOR.len())?;
+        if discriminator != T::DISCRIMINATOR {
+            return None;
+        }
+        Some(bytemuck::pod_read_unaligned(
+            data.get(..std::mem::size_of::<T>())?,
+        ))
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Move the clock to `unix_timestamp`, the slot advances with the time
+    pub fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        assert!(
+            unix_timestamp >= self.clock.unix_timestamp,
+            "the clock only moves forward"
+        );
+        self.warp_forward((unix_timestamp - self.clock.unix_timestamp) as u64);
+    }
+
+    /// Move the clock `seconds` forward
+    pub fn warp_forward(&mut self, seconds: u64) {
+        self.clock.unix_timestamp += seconds as i64;
+        self.clock.slot += seconds * SLOTS_PER_SECOND;
+        self.set_sysvar(&sysvar::clock::ID, &self.clock.clone());
+    }
+
+    /// Move the clock to the start of `epoch`, such as to activate the newer transfer fee
+    /// of the token 2022 mints
+    pub fn warp_to_epoch(&mut self, epoch: u64) {
+        assert!(epoch >= self.clock.epoch, "the clock only moves forward");
+        self.clock.epoch = epoch;
+        self.clock.leader_schedule_epoch = epoch + 1;
+        self.clock.epoch_start_timestamp = self.clock.unix_timestamp;
+        self.set_sysvar(&sysvar::clock::ID, &self.clock.clone());
+    }
+
+    /// The logs of the last transaction
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    /// The return data of the last transaction
+    pub fn return_data(&self) -> Option<&(Pubkey, Vec<u8>)> {
+        self.return_data.as_ref()
+    }
+
+    /// The compute units the instructions of the last transaction were charged
+    pub fn compute_units_consumed(&self) -> u64 {
+        self.compute_units_consumed
+    }
+
+    /// The anchor events of type `T` emitted by the last transaction
+    pub fn events<T: Event + Discriminator>(&self) -> Vec<T> {
+        self.data_logs
+            .iter()
+            .filter_map(|data| T::try_from_slice(data.strip_prefix(T::DISCRIMINATOR)?).ok())
+            .collect()
+    }
+
+    /// Process a transaction of `instructions` signed by `signers`, the changes of all its
+    /// instructions are discarded when one fails
+    pub fn process_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> std::result::Result<(), TransactionError> {
+        let accounts = self.accounts.clone();
+        self.logs.clear();
+        self.data_logs.clear();
+        self.return_data = None;
+        self.compute_units_consumed = 0;
+        let result = self.execute_transaction(instructions, signers);
+        // the instructions sysvar only exists for the transaction
+        self.accounts.remove(&sysvar::instructions::ID);
+        if let Err(error) = &result {
+            self.accounts = accounts;
+            self.logs.push(format!(
+                "Transaction failed at instruction {}: {}",
+                error.instruction_index, error.error
+            ));
+        }
+        result
+    }
+
+    fn execute_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> std::result::Result<(), TransactionError> {
+        let transaction_error = |instruction_index| {
+            move |error| TransactionError {
+                instruction_index,
+                error,
+            }
+        };
+        for (instruction_index, instruction) in instructions.iter().enumerate() {
+            if instruction.program_id == ed25519_program::ID {
+                runtime::verify_ed25519_instruction(&instruction.data, instructions)
+                    .map_err(transaction_error(instruction_index))?;
+            }
+        }
+
+        let mut instructions_sysvar = construct_instructions_data(
+            &instructions
+                .iter()
+                .map(|instruction| BorrowedInstruction {
+                    program_id: &instruction.program_id,
+                    accounts: instruction
+                        .accounts
+                        .iter()
+                        .map(|meta| BorrowedAccountMeta {
+                            pubkey: &meta.pubkey,
+                            is_signer: meta.is_signer,
+                            is_writable: meta.is_writable,
+                        })
+                        .collect(),
+                    data: &instruction.data,
+                })
+                .collect::<Vec<_>>(),
+        );
+        for (instruction_index, instruction) in instructions.iter().enumerate() {
+            store_current_index(&mut instructions_sysvar, instruction_index as u16);
+            self.set_account(
+                sysvar::instructions::ID,
+                BankAccount {
+                    lamports: 1,
+                    data: instructions_sysvar.clone(),
+                    owner: sysvar::ID,
+                    executable: false,
+                },
+            );
+            self.process_instruction(instruction, signers)
+                .map_err(transaction_error(instruction_index))?;
+        }
+        Ok(())
+    }
+
+    fn process_instruction(
+        &mut self,
+        instruction: &Instruction,
+        signers: &[Pubkey],
+    ) -> std::result::Result<(), ProgramError> {
+        if !self
+            .account(&instruction.program_id)
+            .is_some_and(|program| program.executable)
+        {
+            self.logs
+                .push(format!("Program {} is not loaded", instruction.program_id));
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let mut accounts = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            if meta.is_signer && !signers.contains(&meta.pubkey) {
+                self.logs
+                    .push(format!("Missing the signature of {}", meta.pubkey));
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            accounts.push(InstructionAccount {
+                key: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+                account: self.account(&meta.pubkey).cloned().unwrap_or_default(),
+            });
+        }
+
+        with_invoke_context(|c| {
+            c.clock = self.clock.clone();
+            c.rent = self.rent.clone();
+            c.return_data = None;
+            c.native_programs = self.native_programs.clone();
+            c.compute_units_consumed = 0;
+        });
+        let result = runtime::execute(&instruction.program_id, &accounts, &instruction.data);
+        let (logs, data_logs, return_data, compute_units_consumed) = with_invoke_context(|c| {
+            (
+                std::mem::take(&mut c.logs),
+                std::mem::take(&mut c.data_logs),
+                c.return_data.take(),
+                c.compute_units_consumed,
+            )
+        });
+        self.logs.extend(logs);
+        self.data_logs.extend(data_logs);
+        self.return_data = return_data;
+        self.compute_units_consumed += compute_units_consumed;
+
+        for (account, account_after) in accounts.iter().zip(result?) {
+            if !account.is_writable {
+                continue;
+            }
+            // the accounts without lamports are garbage collected, as the closed accounts
+            if account_after.lamports == 0 {
+                self.accounts.remove(&account.key);
+            } else {
+                self.accounts.insert(account.key, account_after);
+            }
+        }
+        Ok(())
+    }
+}
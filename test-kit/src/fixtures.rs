@@ -0,0 +1,764 @@
+//! One call fixtures of the mints, token accounts and pools of a test, with the swap
+//! helpers and the balance assertions the test matrices share
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    system_instruction,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token::spl_associated_token_account, token::spl_token};
+use raydium_cp_swap::curve::CurveType;
+use raydium_cp_swap::instructions::is_fee_rate_param;
+use raydium_cp_swap::states::{AmmConfig, ObservationState, PoolState, CONFIG_CHANGE_DELAY};
+use raydium_cp_swap_client::{instructions, pda, PoolKeys};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_token_2022::extension::{
+    transfer_fee::instruction::initialize_transfer_fee_config, transfer_hook, ExtensionType,
+    StateWithExtensions,
+};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address, instruction::ExecuteInstruction,
+};
+
+use crate::bank::{Bank, BankAccount, TransactionError};
+
+/// The lamports given to the payers the fixtures create
+pub const PAYER_LAMPORTS: u64 = 1_000_000_000_000;
+
+/// Process a transaction the fixture relies on, panicking with the transaction logs when
+/// it fails
+pub fn process(bank: &mut Bank, instructions: &[Instruction], signers: &[Pubkey]) {
+    if let Err(err) = bank.process_transaction(instructions, signers) {
+        panic!("{err}\n{}", bank.logs().join("\n"));
+    }
+}
+
+/// Assert that a transaction failed with the anchor error `expected`
+pub fn assert_anchor_error(
+    result: std::result::Result<(), TransactionError>,
+    expected: impl Into<anchor_lang::error::Error>,
+) {
+    let expected = ProgramError::from(expected.into());
+    match result {
+        Ok(()) => panic!("the transaction succeeded, expected {expected}"),
+        Err(err) => assert_eq!(err.error, expected),
+    }
+}
+
+/// A new address holding `PAYER_LAMPORTS`
+pub fn new_payer(bank: &mut Bank) -> Pubkey {
+    let payer = bank.new_key();
+    bank.airdrop(&payer, PAYER_LAMPORTS);
+    payer
+}
+
+/// The token program and the extensions of a test mint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MintSetup {
+    pub decimals: u8,
+    pub token_2022: bool,
+    /// The transfer fee of a token 2022 mint, no transfer fee extension when 0
+    pub transfer_fee_basis_points: u16,
+    pub maximum_transfer_fee: u64,
+    /// Whether the mint authority is the freeze authority too
+    pub freeze_authority: bool,
+    /// The transfer hook program of a token 2022 mint, loaded in the bank by the test
+    pub transfer_hook_program: Option<Pubkey>,
+}
+
+impl MintSetup {
+    /// A mint of the spl token program
+    pub fn spl_token(decimals: u8) -> Self {
+        Self {
+            decimals,
+            token_2022: false,
+            transfer_fee_basis_points: 0,
+            maximum_transfer_fee: 0,
+            freeze_authority: false,
+            transfer_hook_program: None,
+        }
+    }
+
+    /// A mint of the token program 2022 without extensions
+    pub fn token_2022(decimals: u8) -> Self {
+        Self {
+            token_2022: true,
+            ..Self::spl_token(decimals)
+        }
+    }
+
+    /// The mint under the token program 2022 with a transfer fee
+    pub fn with_transfer_fee(self, basis_points: u16, maximum_fee: u64) -> Self {
+        Self {
+            token_2022: true,
+            transfer_fee_basis_points: basis_points,
+            maximum_transfer_fee: maximum_fee,
+            ..self
+        }
+    }
+
+    /// The mint under the token program 2022 with a transfer hook of `program_id`, which
+    /// runs without extra accounts until `set_transfer_hook_extra_accounts`
+    pub fn with_transfer_hook(self, program_id: Pubkey) -> Self {
+        Self {
+            token_2022: true,
+            transfer_hook_program: Some(program_id),
+            ..self
+        }
+    }
+
+    /// The mint with the mint authority as freeze authority
+    pub fn with_freeze_authority(self) -> Self {
+        Self {
+            freeze_authority: true,
+            ..self
+        }
+    }
+
+    pub fn token_program(&self) -> Pubkey {
+        if self.token_2022 {
+            spl_token_2022::ID
+        } else {
+            spl_token::ID
+        }
+    }
+}
+
+/// Create a mint of `setup` with `authority` as the mint authority, the transfer fee
+/// authorities, the transfer hook authority and the freeze authority. `authority` pays the
+/// mint account
+pub fn create_mint(bank: &mut Bank, authority: &Pubkey, setup: MintSetup) -> Pubkey {
+    let mint = bank.new_key();
+    let token_program = setup.token_program();
+    let with_transfer_fee = setup.transfer_fee_basis_points != 0;
+    let mut extension_types = Vec::new();
+    if with_transfer_fee {
+        extension_types.push(ExtensionType::TransferFeeConfig);
+    }
+    if setup.transfer_hook_program.is_some() {
+        extension_types.push(ExtensionType::TransferHook);
+    }
+    let space =
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extension_types)
+            .unwrap();
+    let mut instructions = vec![system_instruction::create_account(
+        authority,
+        &mint,
+        bank.rent().minimum_balance(space),
+        space as u64,
+        &token_program,
+    )];
+    if with_transfer_fee {
+        instructions.push(
+            initialize_transfer_fee_config(
+                &token_program,
+                &mint,
+                Some(authority),
+                Some(authority),
+                setup.transfer_fee_basis_points,
+                setup.maximum_transfer_fee,
+            )
+            .unwrap(),
+        );
+    }
+    if let Some(program_id) = setup.transfer_hook_program {
+        instructions.push(
+            transfer_hook::instruction::initialize(
+                &token_program,
+                &mint,
+                Some(*authority),
+                Some(program_id),
+            )
+            .unwrap(),
+        );
+    }
+    instructions.push(
+        spl_token_2022::instruction::initialize_mint2(
+            &token_program,
+            &mint,
+            authority,
+            setup.freeze_authority.then_some(authority),
+            setup.decimals,
+        )
+        .unwrap(),
+    );
+    process(bank, &instructions, &[*authority, mint]);
+
+    if let Some(program_id) = setup.transfer_hook_program {
+        set_transfer_hook_extra_accounts(bank, &mint, &program_id, &[]);
+    }
+    mint
+}
+
+/// Write the validation account of the transfer hook of `mint`, the hook program then gets
+/// `extra_accounts` on each transfer. The vault transfers need them in their remaining
+/// accounts next to `transfer_hook_accounts`
+pub fn set_transfer_hook_extra_accounts(
+    bank: &mut Bank,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+    extra_accounts: &[AccountMeta],
+) {
+    let extra_account_metas = extra_accounts
+        .iter()
+        .map(|meta| {
+            ExtraAccountMeta::new_with_pubkey(&meta.pubkey, meta.is_signer, meta.is_writable)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let mut data = vec![0; ExtraAccountMetaList::size_of(extra_account_metas.len()).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas).unwrap();
+    bank.set_account(
+        get_extra_account_metas_address(mint, program_id),
+        BankAccount {
+            lamports: bank.rent().minimum_balance(data.len()),
+            data,
+            owner: *program_id,
+            executable: false,
+        },
+    );
+}
+
+/// The remaining accounts the vault transfers of `mint` need to run its transfer hook:
+/// the validation account, the hook program and the token badge of the mint. Empty when
+/// the mint has no transfer hook
+pub fn transfer_hook_accounts(bank: &Bank, mint: &Pubkey) -> Vec<AccountMeta> {
+    let data = &bank.account(mint).expect("mint of the bank").data;
+    let program_id = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(data)
+        .ok()
+        .and_then(|mint| transfer_hook::get_program_id(&mint));
+    match program_id {
+        Some(program_id) => vec![
+            AccountMeta::new_readonly(get_extra_account_metas_address(mint, &program_id), false),
+            AccountMeta::new_readonly(program_id, false),
+            AccountMeta::new_readonly(pda::token_badge(mint).0, false),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// The token program owning `mint`
+pub fn token_program_of(bank: &Bank, mint: &Pubkey) -> Pubkey {
+    bank.account(mint).expect("mint of the bank").owner
+}
+
+/// Create the associated token account of `owner` for `mint` if missing, paid by `payer`
+pub fn create_token_account(
+    bank: &mut Bank,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Pubkey {
+    let token_program = token_program_of(bank, mint);
+    let token_account = spl_associated_token_account::get_associated_token_address_with_program_id(
+        owner,
+        mint,
+        &token_program,
+    );
+    if bank.account(&token_account).is_none() {
+        let instruction =
+            spl_associated_token_account::instruction::create_associated_token_account(
+                payer,
+                owner,
+                mint,
+                &token_program,
+            );
+        process(bank, &[instruction], &[*payer]);
+    }
+    token_account
+}
+
+/// Mint `amount` tokens of `mint` to `token_account`. The native mint has no authority,
+/// its tokens are lamports airdropped to the token account and synced
+pub fn mint_to(
+    bank: &mut Bank,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    token_account: &Pubkey,
+    amount: u64,
+) {
+    if *mint == spl_token::native_mint::ID {
+        bank.airdrop(token_account, amount);
+        let instruction =
+            spl_token::instruction::sync_native(&spl_token::ID, token_account).unwrap();
+        process(bank, &[instruction], &[]);
+        return;
+    }
+    let token_program = token_program_of(bank, mint);
+    let instruction = spl_token_2022::instruction::mint_to(
+        &token_program,
+        mint,
+        token_account,
+        authority,
+        &[],
+        amount,
+    )
+    .unwrap();
+    process(bank, &[instruction], &[*authority]);
+}
+
+/// The balance of a token account of either token program, 0 when it does not exist
+pub fn token_balance(bank: &Bank, token_account: &Pubkey) -> u64 {
+    bank.account(token_account).map_or(0, |account| {
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("token account")
+            .base
+            .amount
+    })
+}
+
+/// Assert the balance of a token account
+#[track_caller]
+pub fn assert_token_balance(bank: &Bank, token_account: &Pubkey, expected: u64) {
+    assert_eq!(
+        token_balance(bank, token_account),
+        expected,
+        "balance of the token account {token_account}"
+    );
+}
+
+/// The token balances of accounts at a point of a test, to assert how they change
+#[derive(Clone, Debug)]
+pub struct BalanceSnapshot {
+    balances: Vec<(Pubkey, u64)>,
+}
+
+impl BalanceSnapshot {
+    pub fn take(bank: &Bank, token_accounts: &[Pubkey]) -> Self {
+        Self {
+            balances: token_accounts
+                .iter()
+                .map(|token_account| (*token_account, token_balance(bank, token_account)))
+                .collect(),
+        }
+    }
+
+    /// The balance changes of the accounts since the snapshot, in the snapshot order
+    pub fn changes(&self, bank: &Bank) -> Vec<i128> {
+        self.balances
+            .iter()
+            .map(|(token_account, balance)| {
+                i128::from(token_balance(bank, token_account)) - i128::from(*balance)
+            })
+            .collect()
+    }
+
+    #[track_caller]
+    pub fn assert_changes(&self, bank: &Bank, expected: &[i128]) {
+        assert_eq!(
+            self.changes(bank),
+            expected,
+            "balance changes since the snapshot"
+        );
+    }
+}
+
+/// The amm config and the token pair of a test pool. The amounts are the amounts of
+/// `mint_a` and `mint_b`, whichever of them sorts as token_0
+#[derive(Clone, Debug)]
+pub struct PoolSetup {
+    /// The index of the amm config, created when missing with the fee rates below
+    pub config_index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub mint_a: MintSetup,
+    pub mint_b: MintSetup,
+    pub init_amount_a: u64,
+    pub init_amount_b: u64,
+    /// The pool opens right away when 0, the clock is warped past the open time
+    pub open_time: u64,
+    pub curve_type: u8,
+    pub amp: u64,
+    /// Required by the mints with a freeze authority
+    pub accept_freeze_risk: bool,
+    /// The custom authority of the pool, None for the PDA authority
+    pub custom_authority: Option<Pubkey>,
+}
+
+impl Default for PoolSetup {
+    fn default() -> Self {
+        Self {
+            config_index: 0,
+            trade_fee_rate: 2_500,
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+            mint_a: MintSetup::spl_token(9),
+            mint_b: MintSetup::spl_token(6),
+            init_amount_a: 1_000_000_000_000,
+            init_amount_b: 1_000_000_000,
+            open_time: 0,
+            curve_type: CurveType::CONSTANT_PRODUCT,
+            amp: 0,
+            accept_freeze_risk: false,
+            custom_authority: None,
+        }
+    }
+}
+
+/// A trader holding the tokens of a pool in its associated token accounts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trader {
+    pub owner: Pubkey,
+    pub token_0: Pubkey,
+    pub token_1: Pubkey,
+}
+
+impl Trader {
+    /// The input and output token accounts of a swap of `input_mint`
+    pub fn swap_accounts(&self, keys: &PoolKeys, input_mint: &Pubkey) -> (Pubkey, Pubkey) {
+        if *input_mint == keys.token_0_mint {
+            (self.token_0, self.token_1)
+        } else {
+            (self.token_1, self.token_0)
+        }
+    }
+}
+
+/// A pool created by `PoolFixture::setup` with its creator
+#[derive(Clone, Debug)]
+pub struct PoolFixture {
+    pub keys: PoolKeys,
+    /// The pool creator, holding the initial lp tokens
+    pub creator: Trader,
+    /// The mint and transfer fee authority of both mints
+    pub mint_authority: Pubkey,
+}
+
+impl PoolFixture {
+    /// Create the amm config if missing, the mints and a pool of `setup` funded by a new
+    /// creator
+    pub fn setup(bank: &mut Bank, setup: &PoolSetup) -> Self {
+        let mint_authority = new_payer(bank);
+        let mint_a = create_mint(bank, &mint_authority, setup.mint_a);
+        let mint_b = create_mint(bank, &mint_authority, setup.mint_b);
+        Self::setup_with_mints(bank, setup, mint_authority, mint_a, mint_b)
+    }
+
+    /// Create a pool of `setup` like `setup` for the existing mints `mint_a` and `mint_b` of
+    /// `mint_authority`, such as a mint of another fixture. The mint setups still give the
+    /// token programs and transfer hooks of the mints
+    pub fn setup_with_mints(
+        bank: &mut Bank,
+        setup: &PoolSetup,
+        mint_authority: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+    ) -> Self {
+        let admin = raydium_cp_swap::admin::ID;
+        bank.airdrop(&admin, PAYER_LAMPORTS);
+        let amm_config = pda::amm_config(setup.config_index).0;
+        if bank.account(&amm_config).is_none() {
+            let instruction = Instruction {
+                program_id: raydium_cp_swap::ID,
+                accounts: raydium_cp_swap::accounts::CreateAmmConfig {
+                    owner: admin,
+                    amm_config,
+                    system_program: anchor_lang::system_program::ID,
+                }
+                .to_account_metas(None),
+                data: raydium_cp_swap::instruction::CreateAmmConfig {
+                    index: setup.config_index,
+                    trade_fee_rate: setup.trade_fee_rate,
+                    protocol_fee_rate: setup.protocol_fee_rate,
+                    fund_fee_rate: setup.fund_fee_rate,
+                    create_pool_fee: 0,
+                }
+                .data(),
+            };
+            process(bank, &[instruction], &[admin]);
+        }
+
+        let keys = PoolKeys::new(
+            amm_config,
+            mint_a,
+            setup.mint_a.token_program(),
+            mint_b,
+            setup.mint_b.token_program(),
+        );
+        let (init_amount_0, init_amount_1) = if keys.token_0_mint == mint_a {
+            (setup.init_amount_a, setup.init_amount_b)
+        } else {
+            (setup.init_amount_b, setup.init_amount_a)
+        };
+        let mut fixture = Self {
+            keys,
+            creator: Trader {
+                owner: Pubkey::default(),
+                token_0: Pubkey::default(),
+                token_1: Pubkey::default(),
+            },
+            mint_authority,
+        };
+        fixture.creator = fixture.new_trader(bank, init_amount_0, init_amount_1);
+
+        let create_pool_fee = create_token_account(
+            bank,
+            &fixture.creator.owner,
+            &bank
+                .get_account::<AmmConfig>(&amm_config)
+                .unwrap()
+                .create_pool_fee_receiver,
+            &spl_token::native_mint::ID,
+        );
+        // the admin vets the transfer hooks, the pool is then created with their badges
+        for (mint, mint_setup) in [(mint_a, setup.mint_a), (mint_b, setup.mint_b)] {
+            if mint_setup.transfer_hook_program.is_some()
+                && bank.account(&pda::token_badge(&mint).0).is_none()
+            {
+                process(
+                    bank,
+                    &[instructions::create_token_badge(admin, mint)],
+                    &[admin],
+                );
+            }
+        }
+        let token_badges = [fixture.keys.token_0_mint, fixture.keys.token_1_mint]
+            .map(|mint| bank.account(&pda::token_badge(&mint).0).is_some());
+        let mut instruction = instructions::initialize(
+            &fixture.keys,
+            fixture.creator.owner,
+            fixture.creator.token_0,
+            fixture.creator.token_1,
+            create_pool_fee,
+            token_badges,
+            raydium_cp_swap::instruction::Initialize {
+                init_amount_0,
+                init_amount_1,
+                open_time: setup.open_time,
+                authority_type: setup.custom_authority.is_some().into(),
+                custom_authority: setup.custom_authority,
+                curve_type: setup.curve_type,
+                amp: setup.amp,
+                launch_mode: 0,
+                launch_duration: 0,
+                lp_mint_metadata: None,
+                accept_freeze_risk: setup.accept_freeze_risk,
+            },
+        );
+        instruction
+            .accounts
+            .extend(fixture.transfer_hook_accounts(bank));
+        process(bank, &[instruction], &[fixture.creator.owner]);
+        if setup.open_time == 0 {
+            bank.warp_forward(1);
+        }
+        fixture
+    }
+
+    /// A new trader holding `amount_0` token_0 and `amount_1` token_1 of the pool
+    pub fn new_trader(&self, bank: &mut Bank, amount_0: u64, amount_1: u64) -> Trader {
+        let owner = new_payer(bank);
+        let mut token_accounts = [Pubkey::default(); 2];
+        for (token_account, (mint, amount)) in token_accounts.iter_mut().zip([
+            (self.keys.token_0_mint, amount_0),
+            (self.keys.token_1_mint, amount_1),
+        ]) {
+            *token_account = create_token_account(bank, &owner, &owner, &mint);
+            if amount > 0 {
+                mint_to(bank, &mint, &self.mint_authority, token_account, amount);
+            }
+        }
+        Trader {
+            owner,
+            token_0: token_accounts[0],
+            token_1: token_accounts[1],
+        }
+    }
+
+    /// The lp token account of `owner`, the associated token account of the lp mint
+    pub fn lp_token_account(&self, owner: &Pubkey) -> Pubkey {
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            owner,
+            &self.keys.lp_mint,
+            &spl_token::ID,
+        )
+    }
+
+    pub fn pool_state(&self, bank: &Bank) -> PoolState {
+        bank.get_zero_copy_account(&self.keys.pool_id).unwrap()
+    }
+
+    pub fn observation_state(&self, bank: &Bank) -> ObservationState {
+        bank.get_zero_copy_account(&self.keys.observation_state)
+            .unwrap()
+    }
+
+    /// The balances of the token_0 and token_1 vaults, fees included
+    pub fn vault_amounts(&self, bank: &Bank) -> (u64, u64) {
+        (
+            token_balance(bank, &self.keys.token_0_vault),
+            token_balance(bank, &self.keys.token_1_vault),
+        )
+    }
+
+    /// The remaining accounts running the transfer hooks of both tokens of the pool
+    pub fn transfer_hook_accounts(&self, bank: &Bank) -> Vec<AccountMeta> {
+        let mut accounts = transfer_hook_accounts(bank, &self.keys.token_0_mint);
+        accounts.extend(transfer_hook_accounts(bank, &self.keys.token_1_mint));
+        accounts
+    }
+
+    /// Set the `param` of the amm config of the pool to `value` as the admin, the fee rate
+    /// changes are executed once the clock is warped past their timelock
+    pub fn update_amm_config(&self, bank: &mut Bank, param: u8, value: u64) {
+        let admin = raydium_cp_swap::admin::ID;
+        let amm_config = self.keys.amm_config;
+        let update_amm_config = instructions::update_amm_config(admin, amm_config, param, value);
+        process(bank, &[update_amm_config], &[admin]);
+        if is_fee_rate_param(param) {
+            bank.warp_forward(CONFIG_CHANGE_DELAY);
+            let execute_config_change =
+                instructions::execute_config_change(amm_config, param, admin);
+            process(bank, &[execute_config_change], &[]);
+        }
+    }
+
+    /// Swap an exact `amount_in` of `input_mint` from the tokens of `trader`
+    pub fn swap_base_input(
+        &self,
+        bank: &mut Bank,
+        trader: &Trader,
+        input_mint: &Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> std::result::Result<(), TransactionError> {
+        let (input_token_account, output_token_account) =
+            trader.swap_accounts(&self.keys, input_mint);
+        let mut instruction = instructions::swap_base_input(
+            &self.keys,
+            trader.owner,
+            input_mint,
+            input_token_account,
+            output_token_account,
+            amount_in,
+            minimum_amount_out,
+            None,
+            None,
+            None,
+        )
+        .expect("input mint of the pool");
+        instruction
+            .accounts
+            .extend(self.transfer_hook_accounts(bank));
+        bank.process_transaction(&[instruction], &[trader.owner])
+    }
+
+    /// Swap `input_mint` of `trader` for an exact `amount_out` of the other token
+    pub fn swap_base_output(
+        &self,
+        bank: &mut Bank,
+        trader: &Trader,
+        input_mint: &Pubkey,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> std::result::Result<(), TransactionError> {
+        let (input_token_account, output_token_account) =
+            trader.swap_accounts(&self.keys, input_mint);
+        let mut instruction = instructions::swap_base_output(
+            &self.keys,
+            trader.owner,
+            input_mint,
+            input_token_account,
+            output_token_account,
+            max_amount_in,
+            amount_out,
+            None,
+            None,
+        )
+        .expect("input mint of the pool");
+        instruction
+            .accounts
+            .extend(self.transfer_hook_accounts(bank));
+        bank.process_transaction(&[instruction], &[trader.owner])
+    }
+}
+
+#[cfg(test)]
+mod fixtures_test {
+    use super::*;
+    use crate::GENESIS_UNIX_TIMESTAMP;
+    use raydium_cp_swap::error::ErrorCode;
+    use raydium_cp_swap::states::SwapEvent;
+
+    #[test]
+    fn setup_pool_and_swap_base_input() {
+        let mut bank = Bank::new();
+        let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+        let keys = &fixture.keys;
+        assert!(token_balance(&bank, &fixture.lp_token_account(&fixture.creator.owner)) > 0);
+        let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+        assert!(vault_0_amount > 0 && vault_1_amount > 0);
+        assert_eq!(fixture.pool_state(&bank).amm_config, keys.amm_config);
+
+        let trader = fixture.new_trader(&mut bank, 1_000_000, 0);
+        let snapshot = BalanceSnapshot::take(
+            &bank,
+            &[
+                trader.token_0,
+                trader.token_1,
+                keys.token_0_vault,
+                keys.token_1_vault,
+            ],
+        );
+        fixture
+            .swap_base_input(&mut bank, &trader, &keys.token_0_mint, 1_000_000, 1)
+            .unwrap();
+        let swap_events = bank.events::<SwapEvent>();
+        assert_eq!(swap_events.len(), 1);
+        let amount_out = i128::from(swap_events[0].output_amount);
+        assert!(amount_out > 0);
+        snapshot.assert_changes(&bank, &[-1_000_000, amount_out, 1_000_000, -amount_out]);
+    }
+
+    #[test]
+    fn failed_transactions_are_discarded() {
+        let mut bank = Bank::new();
+        let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+        let keys = &fixture.keys;
+        let trader = fixture.new_trader(&mut bank, 0, 1_000_000);
+        let vault_amounts = fixture.vault_amounts(&bank);
+        assert_anchor_error(
+            fixture.swap_base_input(&mut bank, &trader, &keys.token_1_mint, 1_000_000, u64::MAX),
+            ErrorCode::ExceededSlippage,
+        );
+        assert_token_balance(&bank, &trader.token_1, 1_000_000);
+        assert_eq!(fixture.vault_amounts(&bank), vault_amounts);
+    }
+
+    #[test]
+    fn transfer_fee_mints_and_time_warp() {
+        let mut bank = Bank::new();
+        let open_time = GENESIS_UNIX_TIMESTAMP as u64 + 3_600;
+        let setup = PoolSetup {
+            mint_a: MintSetup::token_2022(6).with_transfer_fee(100, u64::MAX),
+            init_amount_a: 1_000_000_000,
+            open_time,
+            ..Default::default()
+        };
+        let fixture = PoolFixture::setup(&mut bank, &setup);
+        let keys = &fixture.keys;
+        let (fee_vault, fee_mint) = if keys.token_0_program == spl_token_2022::ID {
+            (keys.token_0_vault, keys.token_0_mint)
+        } else {
+            (keys.token_1_vault, keys.token_1_mint)
+        };
+        // the vault receives the initial amount without the 1% transfer fee
+        assert_token_balance(&bank, &fee_vault, 990_000_000);
+
+        let trader = fixture.new_trader(&mut bank, 1_000_000, 1_000_000);
+        assert_anchor_error(
+            fixture.swap_base_input(&mut bank, &trader, &fee_mint, 1_000_000, 1),
+            ErrorCode::NotApproved,
+        );
+        bank.warp_to_timestamp(open_time as i64);
+        let (trader_input, _) = trader.swap_accounts(keys, &fee_mint);
+        let snapshot = BalanceSnapshot::take(&bank, &[trader_input, fee_vault]);
+        fixture
+            .swap_base_input(&mut bank, &trader, &fee_mint, 1_000_000, 1)
+            .unwrap();
+        snapshot.assert_changes(&bank, &[-1_000_000, 990_000]);
+        assert_eq!(bank.events::<SwapEvent>()[0].input_transfer_fee, 10_000);
+    }
+}
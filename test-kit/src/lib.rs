@@ -0,0 +1,14 @@
+//! Deterministic test harness of the Raydium constant product AMM, for the program tests
+//! and the forks and integrators building on it.
+//!
+//! * [`Bank`] runs the cp-swap program and the SPL programs it calls in process, with a
+//!   clock that only moves when warped
+//! * [`fixtures`] creates mints with any decimals, transfer fee and transfer hook, funded
+//!   traders and pools in one call, and asserts the token balance changes
+
+mod bank;
+pub mod fixtures;
+mod runtime;
+
+pub use bank::{Bank, BankAccount, TransactionError, GENESIS_UNIX_TIMESTAMP, SLOTS_PER_SECOND};
+pub use fixtures::{BalanceSnapshot, MintSetup, PoolFixture, PoolSetup, Trader};
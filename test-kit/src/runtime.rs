@@ -0,0 +1,608 @@
+//! The in process runtime of the bank: the instructions are serialized to the input of
+//! the SBF loader and the programs run against the account infos deserialized from it,
+//! the syscalls of `solana_program` are routed to the invoke context of the thread, which
+//! charges them to its compute meter
+
+use anchor_lang::solana_program::{
+    account_info::{AccountInfo, MAX_PERMITTED_DATA_INCREASE},
+    clock::Clock,
+    ed25519_program,
+    entrypoint::{
+        self, ProcessInstruction, ProgramResult, BPF_ALIGN_OF_U128, NON_DUP_MARKER, SUCCESS,
+    },
+    epoch_schedule::EpochSchedule,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program_error::ProgramError,
+    program_stubs::{self, SyscallStubs},
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::{SystemInstruction, MAX_PERMITTED_DATA_LENGTH},
+    system_program,
+};
+use anchor_spl::{
+    associated_token::spl_associated_token_account, memo::spl_memo, token::spl_token,
+};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_token_2022::extension::{transfer_hook, StateWithExtensions};
+use spl_token_2022::instruction::TokenInstruction;
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address, instruction as transfer_hook_instruction,
+    instruction::ExecuteInstruction,
+};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Once;
+
+use crate::bank::BankAccount;
+
+/// Maximum depth of the instruction stack, the transaction instruction included
+const MAX_INSTRUCTION_STACK_DEPTH: usize = 5;
+/// The compute units of an instruction, the default limit of the compute budget
+const COMPUTE_UNIT_LIMIT: u64 = 200_000;
+/// The costs of the compute budget the bank charges, for the syscalls and the builtin
+/// system program
+const SYSCALL_BASE_COST: u64 = 100;
+const SYSVAR_BASE_COST: u64 = 100;
+const INVOKE_UNITS: u64 = 1_000;
+const CPI_BYTES_PER_UNIT: u64 = 250;
+const SYSTEM_PROGRAM_UNITS: u64 = 150;
+/// Errors of the ed25519 precompile, the `PrecompileError` codes of the runtime
+const PRECOMPILE_INVALID_PUBLIC_KEY: u32 = 0;
+const PRECOMPILE_INVALID_SIGNATURE: u32 = 2;
+const PRECOMPILE_INVALID_DATA_OFFSETS: u32 = 3;
+const PRECOMPILE_INVALID_INSTRUCTION_DATA_SIZE: u32 = 4;
+/// The signature count and a padding byte precede the signature offsets
+const ED25519_SIGNATURE_OFFSETS_START: usize = 2;
+/// Seven u16 offsets and instruction indexes per signature
+const ED25519_SIGNATURE_OFFSETS_SIZE: usize = 14;
+/// System program error of the accounts already holding lamports, data or an owner
+const ACCOUNT_ALREADY_IN_USE: u32 = 0;
+/// System program error of the transfers of more lamports than the payer holds
+const RESULT_WITH_NEGATIVE_LAMPORTS: u32 = 1;
+
+/// The state of the transaction processed by the bank on the current thread
+#[derive(Default)]
+pub(crate) struct InvokeContext {
+    pub clock: Clock,
+    pub rent: Rent,
+    /// The programs of the instruction stack, the running program last
+    pub program_ids: Vec<Pubkey>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    pub logs: Vec<String>,
+    /// The data logged by `sol_log_data`, such as the anchor events
+    pub data_logs: Vec<Vec<u8>>,
+    /// The programs of the tests loaded in the bank next to the builtin ones
+    pub native_programs: BTreeMap<Pubkey, ProcessInstruction>,
+    /// The compute units charged to the instruction of the transaction
+    pub compute_units_consumed: u64,
+}
+
+thread_local! {
+    static INVOKE_CONTEXT: RefCell<InvokeContext> = RefCell::new(InvokeContext::default());
+}
+
+pub(crate) fn with_invoke_context<R>(f: impl FnOnce(&mut InvokeContext) -> R) -> R {
+    INVOKE_CONTEXT.with(|invoke_context| f(&mut invoke_context.borrow_mut()))
+}
+
+/// Charge `units` to the instruction, an instruction beyond the limit is not aborted, its
+/// remaining units are 0
+fn consume_compute_units(units: u64) {
+    with_invoke_context(|c| {
+        c.compute_units_consumed = c.compute_units_consumed.saturating_add(units)
+    });
+}
+
+fn sysvar_cost<T>() -> u64 {
+    SYSVAR_BASE_COST + std::mem::size_of::<T>() as u64
+}
+
+struct BankSyscalls;
+
+impl SyscallStubs for BankSyscalls {
+    fn sol_log(&self, message: &str) {
+        consume_compute_units(SYSCALL_BASE_COST.max(message.len() as u64));
+        with_invoke_context(|c| c.logs.push(format!("Program log: {message}")));
+    }
+
+    fn sol_log_compute_units(&self) {
+        consume_compute_units(SYSCALL_BASE_COST);
+        with_invoke_context(|c| {
+            let remaining = COMPUTE_UNIT_LIMIT.saturating_sub(c.compute_units_consumed);
+            c.logs
+                .push(format!("Program consumption: {remaining} units remaining"))
+        });
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        consume_compute_units(SYSCALL_BASE_COST);
+        with_invoke_context(|c| COMPUTE_UNIT_LIMIT.saturating_sub(c.compute_units_consumed))
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        consume_compute_units(sysvar_cost::<Clock>());
+        let clock = with_invoke_context(|c| c.clock.clone());
+        unsafe { std::ptr::write_unaligned(var_addr as *mut Clock, clock) };
+        SUCCESS
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        consume_compute_units(sysvar_cost::<EpochSchedule>());
+        unsafe {
+            std::ptr::write_unaligned(var_addr as *mut EpochSchedule, EpochSchedule::default())
+        };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        consume_compute_units(sysvar_cost::<Rent>());
+        let rent = with_invoke_context(|c| c.rent.clone());
+        unsafe { std::ptr::write_unaligned(var_addr as *mut Rent, rent) };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        let return_data = with_invoke_context(|c| c.return_data.clone());
+        let len = return_data
+            .as_ref()
+            .map_or(0, |(_, data)| data.len() as u64);
+        consume_compute_units(SYSCALL_BASE_COST + (len + 32) / CPI_BYTES_PER_UNIT);
+        return_data
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        consume_compute_units(SYSCALL_BASE_COST + data.len() as u64 / CPI_BYTES_PER_UNIT);
+        with_invoke_context(|c| {
+            let program_id = *c.program_ids.last().unwrap();
+            c.return_data = Some((program_id, data.to_vec()));
+        });
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        consume_compute_units(
+            SYSCALL_BASE_COST
+                + fields
+                    .iter()
+                    .map(|field| SYSCALL_BASE_COST + field.len() as u64)
+                    .sum::<u64>(),
+        );
+        with_invoke_context(|c| c.data_logs.push(fields.concat()));
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        consume_compute_units(SYSCALL_BASE_COST);
+        with_invoke_context(|c| c.program_ids.len() as u64)
+    }
+}
+
+/// Route the syscalls of `solana_program` to the invoke context of the calling thread,
+/// so the banks of the tests running in parallel do not interfere
+pub(crate) fn install_syscalls() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(BankSyscalls));
+    });
+}
+
+/// An account of an instruction with its privileges
+#[derive(Clone, Debug)]
+pub(crate) struct InstructionAccount {
+    pub key: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub account: BankAccount,
+}
+
+/// Run `data` on `program_id` with `accounts`, returning the accounts after the
+/// instruction in the order of `accounts`
+pub(crate) fn execute(
+    program_id: &Pubkey,
+    accounts: &[InstructionAccount],
+    data: &[u8],
+) -> Result<Vec<BankAccount>, ProgramError> {
+    let depth = with_invoke_context(|c| {
+        c.program_ids.push(*program_id);
+        c.return_data = None;
+        c.program_ids.len()
+    });
+    with_invoke_context(|c| {
+        c.logs
+            .push(format!("Program {program_id} invoke [{depth}]"))
+    });
+    let result = if depth > MAX_INSTRUCTION_STACK_DEPTH {
+        msg!("Instruction stack depth exceeded");
+        Err(ProgramError::InvalidArgument)
+    } else {
+        run(program_id, accounts, data)
+    };
+    with_invoke_context(|c| {
+        c.program_ids.pop();
+        c.logs.push(match &result {
+            Ok(_) => format!("Program {program_id} success"),
+            Err(err) => format!("Program {program_id} failed: {err}"),
+        });
+    });
+    result
+}
+
+fn run(
+    program_id: &Pubkey,
+    accounts: &[InstructionAccount],
+    data: &[u8],
+) -> Result<Vec<BankAccount>, ProgramError> {
+    let mut input = serialize(program_id, accounts, data);
+    let after = {
+        // the account infos borrow `input`, they are dropped before it
+        let (program_id, account_infos, data) =
+            unsafe { entrypoint::deserialize(input.as_mut_ptr() as *mut u8) };
+        dispatch(program_id, &account_infos, data)?;
+        account_infos
+            .iter()
+            .zip(accounts)
+            .map(|(info, account)| BankAccount {
+                lamports: info.lamports(),
+                data: info.data.borrow().to_vec(),
+                owner: *info.owner,
+                executable: account.account.executable,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let (mut lamports_before, mut lamports_after) = (0u128, 0u128);
+    for (i, (account, account_after)) in accounts.iter().zip(&after).enumerate() {
+        if accounts[..i].iter().any(|a| a.key == account.key) {
+            continue;
+        }
+        lamports_before += u128::from(account.account.lamports);
+        lamports_after += u128::from(account_after.lamports);
+        if !is_writable(accounts, &account.key) && account.account != *account_after {
+            msg!("Instruction modified the read-only account {}", account.key);
+            return Err(ProgramError::InvalidArgument);
+        }
+    }
+    if lamports_before != lamports_after {
+        msg!("Instruction changed the total lamports of its accounts");
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(after)
+}
+
+fn is_writable(accounts: &[InstructionAccount], key: &Pubkey) -> bool {
+    accounts.iter().any(|a| a.key == *key && a.is_writable)
+}
+
+fn dispatch<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &[u8],
+) -> ProgramResult {
+    if *program_id == raydium_cp_swap::ID {
+        raydium_cp_swap::entry(program_id, accounts, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == spl_token_2022::ID {
+        spl_token_2022::processor::Processor::process(program_id, accounts, data)?;
+        execute_transfer_hook(accounts, data)
+    } else if *program_id == spl_associated_token_account::ID {
+        spl_associated_token_account::processor::process_instruction(program_id, accounts, data)
+    } else if *program_id == spl_memo::ID {
+        spl_memo::processor::process_instruction(program_id, accounts, data)
+    } else if *program_id == ed25519_program::ID {
+        // the signatures were verified before the transaction ran
+        Ok(())
+    } else if *program_id == system_program::ID {
+        consume_compute_units(SYSTEM_PROGRAM_UNITS);
+        process_system_instruction(accounts, data)
+    } else if let Some(process_instruction) =
+        with_invoke_context(|c| c.native_programs.get(program_id).copied())
+    {
+        process_instruction(program_id, accounts, data)
+    } else {
+        msg!("Program {} is not loaded in the bank", program_id);
+        Err(ProgramError::IncorrectProgramId)
+    }
+}
+
+/// Run the transfer hook of a token 2022 `TransferChecked`, which the token program calls
+/// through `solana_cpi::invoke`, a no-op off chain. The hook runs once the transfer is
+/// done, the token accounts no longer flagged as transferring
+fn execute_transfer_hook(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let Ok(TokenInstruction::TransferChecked { amount, .. }) = TokenInstruction::unpack(data)
+    else {
+        return Ok(());
+    };
+    let [source, mint, destination, authority, additional_accounts @ ..] = accounts else {
+        return Ok(());
+    };
+    let program_id = {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        transfer_hook::get_program_id(&mint_state)
+    };
+    let Some(program_id) = program_id else {
+        return Ok(());
+    };
+
+    // the accounts of `spl_transfer_hook_interface::onchain::invoke_execute`
+    let mut hook_instruction = transfer_hook_instruction::execute(
+        &program_id,
+        source.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        amount,
+    );
+    let mut hook_account_infos = vec![
+        source.clone(),
+        mint.clone(),
+        destination.clone(),
+        authority.clone(),
+    ];
+    let validation_key = get_extra_account_metas_address(mint.key, &program_id);
+    if let Some(validation_info) = additional_accounts
+        .iter()
+        .find(|info| *info.key == validation_key)
+    {
+        hook_instruction
+            .accounts
+            .push(AccountMeta::new_readonly(validation_key, false));
+        hook_account_infos.push(validation_info.clone());
+        ExtraAccountMetaList::add_to_cpi_instruction::<ExecuteInstruction>(
+            &mut hook_instruction,
+            &mut hook_account_infos,
+            &validation_info.try_borrow_data()?,
+            additional_accounts,
+        )?;
+    }
+    invoke_signed(&hook_instruction, &hook_account_infos, &[])
+}
+
+/// Serialize the instruction in the aligned input format of the SBF loader, with the
+/// room for the account data to grow by `MAX_PERMITTED_DATA_INCREASE`
+fn serialize(program_id: &Pubkey, accounts: &[InstructionAccount], data: &[u8]) -> Vec<u64> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+    for (i, account) in accounts.iter().enumerate() {
+        if let Some(position) = accounts[..i].iter().position(|a| a.key == account.key) {
+            bytes.push(position as u8);
+            bytes.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        // the privileges of an account passed more than once are merged
+        let same_accounts = accounts.iter().filter(|a| a.key == account.key);
+        let is_signer = same_accounts.clone().any(|a| a.is_signer);
+        let is_writable = same_accounts.clone().any(|a| a.is_writable);
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(u8::from(is_signer));
+        bytes.push(u8::from(is_writable));
+        bytes.push(u8::from(account.account.executable));
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(account.key.as_ref());
+        bytes.extend_from_slice(account.account.owner.as_ref());
+        bytes.extend_from_slice(&account.account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.extend_from_slice(program_id.as_ref());
+
+    // the loader input is aligned to 8 bytes
+    let mut input = vec![0u64; bytes.len().div_ceil(8)];
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len())
+    };
+    input
+}
+
+/// Cross program invocation from the running program, the accounts changed by the callee
+/// are written back to the account infos of the caller
+fn invoke_signed(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    // the instruction data and the data of the accounts are copied to the callee
+    let cpi_bytes = instruction.data.len()
+        + instruction
+            .accounts
+            .iter()
+            .filter_map(|meta| account_infos.iter().find(|info| *info.key == meta.pubkey))
+            .map(|info| info.data_len())
+            .sum::<usize>();
+    consume_compute_units(INVOKE_UNITS + cpi_bytes as u64 / CPI_BYTES_PER_UNIT);
+    let caller = with_invoke_context(|c| *c.program_ids.last().unwrap());
+    let signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    let mut callee_infos = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !info.is_signer && !signers.contains(&meta.pubkey) {
+            msg!(
+                "Cross program invocation with the unauthorized signer {}",
+                meta.pubkey
+            );
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            msg!(
+                "Cross program invocation with the unauthorized writable {}",
+                meta.pubkey
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        accounts.push(InstructionAccount {
+            key: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+            account: BankAccount {
+                lamports: info.lamports(),
+                data: info.try_borrow_data()?.to_vec(),
+                owner: *info.owner,
+                executable: info.executable,
+            },
+        });
+        callee_infos.push(info);
+    }
+
+    let after = execute(&instruction.program_id, &accounts, &instruction.data)?;
+    for ((account, info), account_after) in accounts.iter().zip(callee_infos).zip(after) {
+        if !account.is_writable {
+            continue;
+        }
+        **info.try_borrow_mut_lamports()? = account_after.lamports;
+        if info.data_len() != account_after.data.len() {
+            info.realloc(account_after.data.len(), false)?;
+        }
+        info.try_borrow_mut_data()?
+            .copy_from_slice(&account_after.data);
+        if *info.owner != account_after.owner {
+            info.assign(&account_after.owner);
+        }
+    }
+    Ok(())
+}
+
+/// Verify the signatures of an ed25519 program instruction of `instructions` like the
+/// precompile, the signed data is read from the instruction at the index of each offset
+pub(crate) fn verify_ed25519_instruction(
+    data: &[u8],
+    instructions: &[Instruction],
+) -> ProgramResult {
+    let signature_count = usize::from(*data.first().unwrap_or(&0));
+    if signature_count == 0 && data.len() > 1
+        || data.len()
+            < ED25519_SIGNATURE_OFFSETS_START + signature_count * ED25519_SIGNATURE_OFFSETS_SIZE
+    {
+        return Err(ProgramError::Custom(
+            PRECOMPILE_INVALID_INSTRUCTION_DATA_SIZE,
+        ));
+    }
+    for i in 0..signature_count {
+        let start = ED25519_SIGNATURE_OFFSETS_START + i * ED25519_SIGNATURE_OFFSETS_SIZE;
+        let offset = |j: usize| u16::from_le_bytes([data[start + 2 * j], data[start + 2 * j + 1]]);
+        let read = |instruction_index: u16, offset: u16, len: usize| {
+            let instruction_data = if instruction_index == u16::MAX {
+                data
+            } else {
+                &instructions
+                    .get(usize::from(instruction_index))
+                    .ok_or(ProgramError::Custom(PRECOMPILE_INVALID_DATA_OFFSETS))?
+                    .data
+            };
+            instruction_data
+                .get(usize::from(offset)..usize::from(offset) + len)
+                .ok_or(ProgramError::Custom(PRECOMPILE_INVALID_DATA_OFFSETS))
+        };
+        let signature = ed25519_dalek::Signature::try_from(read(offset(1), offset(0), 64)?)
+            .map_err(|_| ProgramError::Custom(PRECOMPILE_INVALID_SIGNATURE))?;
+        let public_key = ed25519_dalek::PublicKey::from_bytes(read(offset(3), offset(2), 32)?)
+            .map_err(|_| ProgramError::Custom(PRECOMPILE_INVALID_PUBLIC_KEY))?;
+        let message = read(offset(6), offset(4), usize::from(offset(5)))?;
+        public_key
+            .verify_strict(message, &signature)
+            .map_err(|_| ProgramError::Custom(PRECOMPILE_INVALID_SIGNATURE))?;
+    }
+    Ok(())
+}
+
+/// The instructions of the system program the programs and fixtures use
+fn process_system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let instruction: SystemInstruction =
+        limited_deserialize(data, 1232).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let account = |index: usize| {
+        accounts
+            .get(index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (account(0)?, account(1)?);
+            if to.lamports() > 0 {
+                msg!("Create Account: account {} already in use", to.key);
+                return Err(ProgramError::Custom(ACCOUNT_ALREADY_IN_USE));
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            assign(to, &owner)
+        }
+        SystemInstruction::Transfer { lamports } => transfer(account(0)?, account(1)?, lamports),
+        SystemInstruction::Allocate { space } => allocate(account(0)?, space),
+        SystemInstruction::Assign { owner } => assign(account(0)?, &owner),
+        _ => {
+            msg!("System instruction not supported by the bank");
+            Err(ProgramError::InvalidInstructionData)
+        }
+    }
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !from.data_is_empty() || *from.owner != system_program::ID {
+        msg!("Transfer: `from` must not carry data");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if from.lamports() < lamports {
+        msg!(
+            "Transfer: insufficient lamports {}, need {}",
+            from.lamports(),
+            lamports
+        );
+        return Err(ProgramError::Custom(RESULT_WITH_NEGATIVE_LAMPORTS));
+    }
+    **from.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !account.data_is_empty() || *account.owner != system_program::ID {
+        msg!("Allocate: account {} already in use", account.key);
+        return Err(ProgramError::Custom(ACCOUNT_ALREADY_IN_USE));
+    }
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(ProgramError::InvalidArgument);
+    }
+    account.realloc(space as usize, true)
+}
+
+fn assign(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if *account.owner == *owner {
+        return Ok(());
+    }
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    account.assign(owner);
+    Ok(())
+}
@@ -0,0 +1,379 @@
+//! The admin instructions acting on the pools
+
+use anchor_lang::prelude::Pubkey;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{
+    AdminRole, AmmConfig, BatchAdminOp, ConfigChangeQueuedEvent, CustomAuthorityChangedEvent,
+    PoolAdminOp, PoolAdminOpEvent, PoolConfigChangedEvent, CONFIG_CHANGE_DELAY,
+};
+use raydium_cp_swap_client::{instructions, pda};
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_token_account, new_payer, process,
+};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup};
+
+#[test]
+fn batch_admin_applies_all_ops_or_none() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let admin = raydium_cp_swap::admin::ID;
+    let batch_admin = |trade_fee_rate| {
+        let ops = vec![
+            BatchAdminOp {
+                pool_index: 0,
+                op: PoolAdminOp::Pause,
+            },
+            BatchAdminOp {
+                pool_index: 0,
+                op: PoolAdminOp::SetTradeFeeRate { trade_fee_rate },
+            },
+        ];
+        instructions::batch_admin(&[keys.pool_id], &[keys.amm_config], ops)
+    };
+
+    // the amm config does not allow a trade fee rate per pool, so the pause is undone
+    assert_anchor_error(
+        bank.process_transaction(&[batch_admin(Some(1_000))], &[admin]),
+        ErrorCode::InvalidTradeFeeRate,
+    );
+    assert_eq!(fixture.pool_state(&bank).status, 0);
+    process(&mut bank, &[batch_admin(None)], &[admin]);
+    assert_eq!(fixture.pool_state(&bank).status, u8::MAX);
+    let events = bank.events::<PoolAdminOpEvent>();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].op, PoolAdminOp::Pause);
+    assert_eq!(events[1].index, 1);
+}
+
+#[test]
+fn admin_proposals_of_a_former_role_holder_are_rejected() {
+    let mut bank = Bank::new();
+    let authority = new_payer(&mut bank);
+    let setup = PoolSetup {
+        custom_authority: Some(authority),
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let pool_id = fixture.keys.pool_id;
+    let role = AdminRole::PoolCustomAuthority as u8;
+    let new_admin = new_payer(&mut bank);
+    process(
+        &mut bank,
+        &[instructions::propose_new_admin(
+            authority, pool_id, role, new_admin,
+        )],
+        &[authority],
+    );
+
+    // the admin moves the role as break-glass, so the proposal of the former authority
+    // can no longer take it back
+    let admin = raydium_cp_swap::admin::ID;
+    let break_glass_authority = new_payer(&mut bank);
+    process(
+        &mut bank,
+        &[instructions::set_pool_custom_authority(
+            admin,
+            pool_id,
+            break_glass_authority,
+        )],
+        &[admin],
+    );
+    assert_anchor_error(
+        bank.process_transaction(
+            &[instructions::accept_admin(
+                new_admin, pool_id, role, authority,
+            )],
+            &[new_admin],
+        ),
+        ErrorCode::InvalidAuthority,
+    );
+
+    // a proposal of the current authority is accepted
+    process(
+        &mut bank,
+        &[instructions::propose_new_admin(
+            break_glass_authority,
+            pool_id,
+            role,
+            new_admin,
+        )],
+        &[break_glass_authority],
+    );
+    process(
+        &mut bank,
+        &[instructions::accept_admin(
+            new_admin,
+            pool_id,
+            role,
+            break_glass_authority,
+        )],
+        &[new_admin],
+    );
+    let custom_authority = fixture.pool_state(&bank).custom_authority;
+    assert_eq!(custom_authority, new_admin);
+}
+
+#[test]
+fn custom_authority_only_renounces_directly() {
+    let mut bank = Bank::new();
+    let authority = new_payer(&mut bank);
+    let setup = PoolSetup {
+        custom_authority: Some(authority),
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let pool_id = fixture.keys.pool_id;
+    let new_authority = bank.new_key();
+    assert_anchor_error(
+        bank.process_transaction(
+            &[instructions::set_pool_custom_authority(
+                authority,
+                pool_id,
+                new_authority,
+            )],
+            &[authority],
+        ),
+        ErrorCode::TransferNotProposed,
+    );
+
+    process(
+        &mut bank,
+        &[instructions::set_pool_custom_authority(
+            authority,
+            pool_id,
+            Pubkey::default(),
+        )],
+        &[authority],
+    );
+    let event = bank.events::<CustomAuthorityChangedEvent>().remove(0);
+    assert_eq!(event.old_authority, authority);
+    assert!(!event.by_admin);
+    let pool_state = fixture.pool_state(&bank);
+    assert!(!pool_state.is_custom_authority());
+}
+
+#[test]
+fn dynamic_fee_min_rate_never_exceeds_the_max_rate() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let amm_config = fixture.keys.amm_config;
+    let admin = raydium_cp_swap::admin::ID;
+    fixture.update_amm_config(&mut bank, 9, 10_000);
+    assert_anchor_error(
+        bank.process_transaction(
+            &[instructions::update_amm_config(
+                admin, amm_config, 8, 20_000,
+            )],
+            &[admin],
+        ),
+        ErrorCode::InvalidDynamicFeeBounds,
+    );
+
+    // a queued min rate is checked again against the max rate lowered in the meantime
+    process(
+        &mut bank,
+        &[instructions::update_amm_config(admin, amm_config, 8, 8_000)],
+        &[admin],
+    );
+    fixture.update_amm_config(&mut bank, 9, 6_000);
+    assert_anchor_error(
+        bank.process_transaction(
+            &[instructions::execute_config_change(amm_config, 8, admin)],
+            &[],
+        ),
+        ErrorCode::InvalidDynamicFeeBounds,
+    );
+}
+
+#[test]
+fn fee_rate_changes_wait_for_their_timelock() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let amm_config = fixture.keys.amm_config;
+    let admin = raydium_cp_swap::admin::ID;
+    let trade_fee_rate = |bank: &Bank| {
+        bank.get_account::<AmmConfig>(&amm_config)
+            .unwrap()
+            .trade_fee_rate
+    };
+    process(
+        &mut bank,
+        &[instructions::update_amm_config(admin, amm_config, 0, 3_000)],
+        &[admin],
+    );
+    let event = bank.events::<ConfigChangeQueuedEvent>().remove(0);
+    assert_eq!(
+        event.effective_timestamp,
+        bank.clock().unix_timestamp as u64 + CONFIG_CHANGE_DELAY
+    );
+    assert_eq!(trade_fee_rate(&bank), 2_500);
+    let execute = instructions::execute_config_change(amm_config, 0, admin);
+    bank.warp_forward(CONFIG_CHANGE_DELAY - 1);
+    assert_anchor_error(
+        bank.process_transaction(&[execute.clone()], &[]),
+        ErrorCode::ConfigChangeTimelocked,
+    );
+
+    bank.warp_forward(1);
+    let pending_config_change = pda::pending_config_change(&amm_config, 0).0;
+    let rent = bank.lamports(&pending_config_change);
+    let admin_lamports = bank.lamports(&admin);
+    process(&mut bank, &[execute.clone()], &[]);
+    assert_eq!(trade_fee_rate(&bank), 3_000);
+    assert!(bank.account(&pending_config_change).is_none());
+    assert_eq!(bank.lamports(&admin), admin_lamports + rent);
+    // the change is applied once
+    assert!(bank.process_transaction(&[execute], &[]).is_err());
+
+    // the other params apply right away
+    process(
+        &mut bank,
+        &[instructions::update_amm_config(admin, amm_config, 5, 1_000)],
+        &[admin],
+    );
+    assert_eq!(
+        bank.get_account::<AmmConfig>(&amm_config)
+            .unwrap()
+            .create_pool_fee,
+        1_000
+    );
+}
+
+/// A pool of the default config and another amm config to reassign it to
+fn reassignable_pool(bank: &mut Bank) -> (PoolFixture, PoolFixture) {
+    let fixture = PoolFixture::setup(bank, &PoolSetup::default());
+    let other_fixture = PoolFixture::setup(
+        bank,
+        &PoolSetup {
+            config_index: 1,
+            ..Default::default()
+        },
+    );
+    (fixture, other_fixture)
+}
+
+#[test]
+fn pool_config_reassignments_wait_for_their_timelock() {
+    let mut bank = Bank::new();
+    let (fixture, other_fixture) = reassignable_pool(&mut bank);
+    let keys = &fixture.keys;
+    let new_amm_config = other_fixture.keys.amm_config;
+    let admin = raydium_cp_swap::admin::ID;
+    let reassign = instructions::reassign_pool_config(keys, admin, new_amm_config);
+    let execute = instructions::execute_pool_config_change(keys, new_amm_config, admin);
+    process(&mut bank, &[reassign.clone()], &[admin]);
+    assert_anchor_error(
+        bank.process_transaction(&[execute.clone()], &[]),
+        ErrorCode::ConfigChangeTimelocked,
+    );
+
+    // queuing the reassignment again restarts its timelock
+    bank.warp_forward(CONFIG_CHANGE_DELAY - 10);
+    process(&mut bank, &[reassign], &[admin]);
+    bank.warp_forward(10);
+    assert_anchor_error(
+        bank.process_transaction(&[execute.clone()], &[]),
+        ErrorCode::ConfigChangeTimelocked,
+    );
+    bank.warp_forward(CONFIG_CHANGE_DELAY - 10);
+
+    // the rent of the queued reassignment is refunded to the admin that queued it
+    let pending_pool_config_change = pda::pending_pool_config_change(&keys.pool_id).0;
+    let rent = bank.lamports(&pending_pool_config_change);
+    assert!(rent > 0);
+    let admin_lamports = bank.lamports(&admin);
+    process(&mut bank, &[execute], &[]);
+    assert_eq!({ fixture.pool_state(&bank).amm_config }, new_amm_config);
+    assert!(bank.account(&pending_pool_config_change).is_none());
+    assert_eq!(bank.lamports(&admin), admin_lamports + rent);
+    let event = bank.events::<PoolConfigChangedEvent>().remove(0);
+    assert_eq!(event.old_amm_config, keys.amm_config);
+    assert_eq!(event.new_amm_config, new_amm_config);
+}
+
+#[test]
+fn pool_config_reassignments_wait_for_the_fees_and_orders_of_the_pool() {
+    let mut bank = Bank::new();
+    let (fixture, other_fixture) = reassignable_pool(&mut bank);
+    let keys = &fixture.keys;
+    // the new config charges the trade fee on the output token
+    other_fixture.update_amm_config(&mut bank, 26, 1);
+    let new_amm_config = other_fixture.keys.amm_config;
+    let admin = raydium_cp_swap::admin::ID;
+    let reassign = instructions::reassign_pool_config(keys, admin, new_amm_config);
+    let execute = instructions::execute_pool_config_change(keys, new_amm_config, admin);
+
+    let trader = fixture.new_trader(&mut bank, 10_000_000, 0);
+    fixture
+        .swap_base_input(&mut bank, &trader, &keys.token_0_mint, 1_000_000, 1)
+        .unwrap();
+    process(&mut bank, &[reassign.clone()], &[admin]);
+    // a limit order opened while the reassignment is queued
+    let open_order = instructions::open_order(
+        keys,
+        trader.owner,
+        &keys.token_0_mint,
+        trader.token_0,
+        trader.token_1,
+        raydium_cp_swap::instruction::OpenOrder {
+            order_id: 0,
+            amount_in: 1_000_000,
+            limit_price_x64: u128::MAX,
+            keeper_tip_bps: 0,
+        },
+    )
+    .unwrap();
+    process(&mut bank, &[open_order], &[trader.owner]);
+    assert_eq!({ fixture.pool_state(&bank).open_order_count }, 1);
+    bank.warp_forward(CONFIG_CHANGE_DELAY);
+
+    // the fees accrued under the old config are collected first
+    assert_anchor_error(
+        bank.process_transaction(&[execute.clone()], &[]),
+        ErrorCode::PoolFeesNotSettled,
+    );
+    let recipient_0 = create_token_account(&mut bank, &admin, &admin, &keys.token_0_mint);
+    let recipient_1 = create_token_account(&mut bank, &admin, &admin, &keys.token_1_mint);
+    process(
+        &mut bank,
+        &[
+            instructions::collect_protocol_fee(
+                keys,
+                admin,
+                recipient_0,
+                recipient_1,
+                None,
+                u64::MAX,
+                u64::MAX,
+            ),
+            instructions::collect_fund_fee(
+                keys,
+                admin,
+                recipient_0,
+                recipient_1,
+                u64::MAX,
+                u64::MAX,
+                None,
+            ),
+        ],
+        &[admin],
+    );
+    // the open order fills through the swaps the new config does not allow
+    assert_anchor_error(
+        bank.process_transaction(&[execute.clone()], &[]),
+        ErrorCode::FeeOnOutputNotSupported,
+    );
+    assert_anchor_error(
+        bank.process_transaction(&[reassign], &[admin]),
+        ErrorCode::FeeOnOutputNotSupported,
+    );
+
+    let cancel_order =
+        instructions::cancel_order(keys, trader.owner, &keys.token_0_mint, trader.token_0, 0)
+            .unwrap();
+    process(&mut bank, &[cancel_order], &[trader.owner]);
+    assert_eq!({ fixture.pool_state(&bank).open_order_count }, 0);
+    process(&mut bank, &[execute], &[]);
+    assert_eq!({ fixture.pool_state(&bank).amm_config }, new_amm_config);
+}
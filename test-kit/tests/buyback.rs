@@ -0,0 +1,125 @@
+//! Buybacks swapping the fund fees of a pool for the buyback token of its amm config and
+//! burning it, priced against the TWAP of the pool
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{BuybackEvent, SwapEvent};
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, new_payer, process, token_balance};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup, Trader};
+
+/// A pool whose token_1 is the buyback token of its amm config, with token_0 fund fees
+/// accrued by a swap and a TWAP window of a minute
+fn setup() -> (Bank, PoolFixture, Trader) {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let admin = raydium_cp_swap::admin::ID;
+    let mut set_buyback_mint = instructions::update_amm_config(admin, keys.amm_config, 17, 0);
+    set_buyback_mint
+        .accounts
+        .push(AccountMeta::new_readonly(keys.token_1_mint, false));
+    process(&mut bank, &[set_buyback_mint], &[admin]);
+    fixture.update_amm_config(&mut bank, 35, 60);
+
+    let trader = fixture.new_trader(&mut bank, 1_000_000_000_000, 1_000_000_000_000);
+    let (vault_0_amount, _) = fixture.vault_amounts(&bank);
+    fixture
+        .swap_base_input(
+            &mut bank,
+            &trader,
+            &keys.token_0_mint,
+            vault_0_amount / 100,
+            1,
+        )
+        .unwrap();
+    bank.warp_forward(120);
+    (bank, fixture, trader)
+}
+
+fn buyback_mint_supply(bank: &Bank, fixture: &PoolFixture) -> u64 {
+    bank.get_account::<Mint>(&fixture.keys.token_1_mint)
+        .unwrap()
+        .supply
+}
+
+#[test]
+fn buybacks_are_disabled_without_a_price_bound() {
+    let (mut bank, fixture, _) = setup();
+    let payer = new_payer(&mut bank);
+    let buyback =
+        instructions::execute_buyback(&fixture.keys, payer, &fixture.keys.token_0_mint).unwrap();
+    assert_anchor_error(
+        bank.process_transaction(&[buyback], &[payer]),
+        ErrorCode::NotApproved,
+    );
+}
+
+#[test]
+fn buybacks_burn_the_bought_tokens_at_the_twap_price() {
+    let (mut bank, fixture, _) = setup();
+    fixture.update_amm_config(&mut bank, 34, 100);
+    let fund_fees = { fixture.pool_state(&bank).fund_fees_token_0 };
+    assert!(fund_fees > 0);
+    let supply = buyback_mint_supply(&bank, &fixture);
+    let (_, vault_1_amount) = fixture.vault_amounts(&bank);
+
+    let payer = new_payer(&mut bank);
+    let buyback =
+        instructions::execute_buyback(&fixture.keys, payer, &fixture.keys.token_0_mint).unwrap();
+    process(&mut bank, &[buyback], &[payer]);
+    let event = bank.events::<BuybackEvent>().remove(0);
+    assert_eq!(event.amount_in, fund_fees);
+    assert!(event.amount_burned > 0);
+    // what is left of the fund fees is the fund fee of the buyback swap
+    assert_eq!(
+        { fixture.pool_state(&bank).fund_fees_token_0 },
+        bank.events::<SwapEvent>().remove(0).fund_fee
+    );
+    // the bought tokens are burned from the output vault
+    assert_eq!(
+        buyback_mint_supply(&bank, &fixture),
+        supply - event.amount_burned
+    );
+    assert_eq!(
+        fixture.vault_amounts(&bank).1,
+        vault_1_amount - event.amount_burned
+    );
+}
+
+#[test]
+fn buybacks_after_a_price_manipulation_are_rejected() {
+    let (mut bank, fixture, trader) = setup();
+    let keys = &fixture.keys;
+    fixture.update_amm_config(&mut bank, 34, 100);
+    let payer = new_payer(&mut bank);
+    let buyback = instructions::execute_buyback(keys, payer, &keys.token_0_mint).unwrap();
+
+    // the buyback token is pumped right before the buyback
+    let (vault_0_amount, _) = fixture.vault_amounts(&bank);
+    let token_1_balance = token_balance(&bank, &trader.token_1);
+    fixture
+        .swap_base_input(
+            &mut bank,
+            &trader,
+            &keys.token_0_mint,
+            vault_0_amount / 10,
+            1,
+        )
+        .unwrap();
+    let supply = buyback_mint_supply(&bank, &fixture);
+    assert_anchor_error(
+        bank.process_transaction(&[buyback.clone()], &[payer]),
+        ErrorCode::BuybackPriceDeviation,
+    );
+    assert_eq!(buyback_mint_supply(&bank, &fixture), supply);
+
+    // and goes through at the TWAP once the price is back
+    let bought = token_balance(&bank, &trader.token_1) - token_1_balance;
+    fixture
+        .swap_base_input(&mut bank, &trader, &keys.token_1_mint, bought, 1)
+        .unwrap();
+    process(&mut bank, &[buyback], &[payer]);
+    assert!(buyback_mint_supply(&bank, &fixture) < supply);
+}
@@ -0,0 +1,90 @@
+//! Protocol and fund fees swept by any caller, paid a bounty out of the swept fees
+
+use raydium_cp_swap::curve::Fees;
+use raydium_cp_swap::states::CrankProtocolFeesEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{create_token_account, process};
+use raydium_cp_swap_test_kit::{BalanceSnapshot, Bank, PoolFixture, PoolSetup};
+
+/// 1% of the swept fees
+const FEE_CRANK_BOUNTY_RATE: u64 = 10_000;
+
+fn bounty(fee: u64) -> u64 {
+    u64::try_from(Fees::crank_bounty(u128::from(fee), FEE_CRANK_BOUNTY_RATE).unwrap()).unwrap()
+}
+
+#[test]
+fn cranks_sweep_the_fees_to_their_owners_and_pay_the_caller_a_bounty() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    fixture.update_amm_config(&mut bank, 14, FEE_CRANK_BOUNTY_RATE);
+    let trader = fixture.new_trader(&mut bank, 1_000_000_000_000, 1_000_000_000);
+    for input_mint in [keys.token_0_mint, keys.token_1_mint] {
+        fixture
+            .swap_base_input(&mut bank, &trader, &input_mint, 100_000_000, 1)
+            .unwrap();
+    }
+    let pool_state = fixture.pool_state(&bank);
+    let (protocol_fee_0, protocol_fee_1) = (
+        pool_state.protocol_fees_token_0,
+        pool_state.protocol_fees_token_1,
+    );
+    let (fund_fee_0, fund_fee_1) = (pool_state.fund_fees_token_0, pool_state.fund_fees_token_1);
+    assert!(bounty(protocol_fee_0) > 0 && bounty(protocol_fee_1) > 0);
+
+    // the admin owns the protocol and fund fees of the config
+    let admin = raydium_cp_swap::admin::ID;
+    let admin_tokens = [keys.token_0_mint, keys.token_1_mint]
+        .map(|mint| create_token_account(&mut bank, &admin, &admin, &mint));
+    let caller = fixture.new_trader(&mut bank, 0, 0);
+    let balances = BalanceSnapshot::take(
+        &bank,
+        &[
+            admin_tokens[0],
+            admin_tokens[1],
+            caller.token_0,
+            caller.token_1,
+            keys.token_0_vault,
+            keys.token_1_vault,
+        ],
+    );
+    let crank = instructions::crank_protocol_fees(
+        keys,
+        caller.owner,
+        admin,
+        admin,
+        caller.token_0,
+        caller.token_1,
+    );
+    process(&mut bank, &[crank], &[caller.owner]);
+
+    let bounty_0 = bounty(protocol_fee_0) + bounty(fund_fee_0);
+    let bounty_1 = bounty(protocol_fee_1) + bounty(fund_fee_1);
+    let event = bank.events::<CrankProtocolFeesEvent>().remove(0);
+    assert_eq!(event.caller, caller.owner);
+    assert_eq!(
+        event.protocol_fee_0,
+        protocol_fee_0 - bounty(protocol_fee_0)
+    );
+    assert_eq!(event.fund_fee_1, fund_fee_1 - bounty(fund_fee_1));
+    assert_eq!((event.bounty_0, event.bounty_1), (bounty_0, bounty_1));
+    let [fees_0, fees_1] =
+        [protocol_fee_0 + fund_fee_0, protocol_fee_1 + fund_fee_1].map(i128::from);
+    balances.assert_changes(
+        &bank,
+        &[
+            fees_0 - i128::from(bounty_0),
+            fees_1 - i128::from(bounty_1),
+            i128::from(bounty_0),
+            i128::from(bounty_1),
+            -fees_0,
+            -fees_1,
+        ],
+    );
+    let pool_state = fixture.pool_state(&bank);
+    assert_eq!({ pool_state.protocol_fees_token_0 }, 0);
+    assert_eq!({ pool_state.protocol_fees_token_1 }, 0);
+    assert_eq!({ pool_state.fund_fees_token_0 }, 0);
+    assert_eq!({ pool_state.fund_fees_token_1 }, 0);
+}
@@ -0,0 +1,147 @@
+//! Deposits minting the lp tokens to a beneficiary and withdrawals paying the tokens out
+//! to a beneficiary, on behalf of an owner such as a vault program
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use raydium_cp_swap::states::{DepositEvent, WithdrawEvent};
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_token_account, new_payer, process, token_balance, BalanceSnapshot,
+};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup};
+
+#[test]
+fn deposits_for_a_beneficiary_mint_the_lp_tokens_paid_by_the_owner() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let owner = fixture.new_trader(&mut bank, 1_000_000_000, 1_000_000_000);
+    let beneficiary = new_payer(&mut bank);
+    let beneficiary_lp_token =
+        create_token_account(&mut bank, &beneficiary, &beneficiary, &keys.lp_mint);
+    let owner_lp_token = create_token_account(&mut bank, &owner.owner, &owner.owner, &keys.lp_mint);
+    let deposit_for = |beneficiary_lp_token| {
+        instructions::deposit_for(
+            keys,
+            owner.owner,
+            beneficiary,
+            beneficiary_lp_token,
+            owner.token_0,
+            owner.token_1,
+            1_000_000,
+            u64::MAX,
+            u64::MAX,
+            None,
+        )
+    };
+
+    // the lp tokens only go to a token account of the beneficiary
+    assert_anchor_error(
+        bank.process_transaction(&[deposit_for(owner_lp_token)], &[owner.owner]),
+        AnchorErrorCode::ConstraintTokenOwner,
+    );
+
+    let snapshot = BalanceSnapshot::take(
+        &bank,
+        &[
+            owner.token_0,
+            owner.token_1,
+            keys.token_0_vault,
+            keys.token_1_vault,
+        ],
+    );
+    process(
+        &mut bank,
+        &[deposit_for(beneficiary_lp_token)],
+        &[owner.owner],
+    );
+    // the liquidity is recorded as the beneficiary's
+    let event = bank.events::<DepositEvent>().remove(0);
+    assert_eq!(event.owner, beneficiary);
+    assert_eq!(event.lp_amount, 1_000_000);
+    let (token_0_amount, token_1_amount) = (
+        i128::from(event.token_0_amount),
+        i128::from(event.token_1_amount),
+    );
+    assert!(token_0_amount > 0 && token_1_amount > 0);
+    // the owner pays both tokens, the beneficiary holds the lp tokens
+    snapshot.assert_changes(
+        &bank,
+        &[
+            -token_0_amount,
+            -token_1_amount,
+            token_0_amount,
+            token_1_amount,
+        ],
+    );
+    assert_eq!(token_balance(&bank, &beneficiary_lp_token), 1_000_000);
+    assert_eq!(token_balance(&bank, &owner_lp_token), 0);
+}
+
+#[test]
+fn withdrawals_to_a_beneficiary_pay_out_the_tokens_of_the_owner_lp_tokens() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let owner = &fixture.creator;
+    let owner_lp_token = fixture.lp_token_account(&owner.owner);
+    let beneficiary = fixture.new_trader(&mut bank, 0, 0);
+    let lp_token_amount = token_balance(&bank, &owner_lp_token) / 10;
+    let withdraw_to = |token_0_account, token_1_account| {
+        instructions::withdraw_to(
+            keys,
+            owner.owner,
+            owner_lp_token,
+            beneficiary.owner,
+            token_0_account,
+            token_1_account,
+            lp_token_amount,
+            0,
+            0,
+            None,
+        )
+    };
+
+    // the tokens only go to the token accounts of the beneficiary
+    assert_anchor_error(
+        bank.process_transaction(
+            &[withdraw_to(owner.token_0, beneficiary.token_1)],
+            &[owner.owner],
+        ),
+        AnchorErrorCode::ConstraintTokenOwner,
+    );
+
+    let snapshot = BalanceSnapshot::take(
+        &bank,
+        &[
+            owner_lp_token,
+            owner.token_0,
+            owner.token_1,
+            beneficiary.token_0,
+            beneficiary.token_1,
+        ],
+    );
+    process(
+        &mut bank,
+        &[withdraw_to(beneficiary.token_0, beneficiary.token_1)],
+        &[owner.owner],
+    );
+    let event = bank.events::<WithdrawEvent>().remove(0);
+    assert_eq!(event.owner, owner.owner);
+    assert_eq!(event.lp_amount, lp_token_amount);
+    let (token_0_amount, token_1_amount) = (
+        i128::from(event.token_0_amount),
+        i128::from(event.token_1_amount),
+    );
+    assert!(token_0_amount > 0 && token_1_amount > 0);
+    // the owner lp tokens are burnt, the beneficiary receives both tokens
+    snapshot.assert_changes(
+        &bank,
+        &[
+            -i128::from(lp_token_amount),
+            0,
+            0,
+            token_0_amount,
+            token_1_amount,
+        ],
+    );
+}
@@ -0,0 +1,202 @@
+//! Deposit receipts recording the lockup of the lp tokens, compressed into the receipt tree
+//! of a pool and restored from it
+
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{
+    deposit_receipt_leaf, receipt_proof, DepositReceipt, DepositReceiptCompressedEvent,
+    EarlyExitFeeEvent, ReceiptTree,
+};
+use raydium_cp_swap_client::{instructions, pda};
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, new_payer, process};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup};
+
+#[test]
+fn compress_and_decompress_deposit_receipts() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let payer = new_payer(&mut bank);
+    process(
+        &mut bank,
+        &[instructions::create_receipt_tree(keys.pool_id, payer)],
+        &[payer],
+    );
+
+    // two owners deposit with a receipt and compress it
+    let mut owners = Vec::new();
+    let mut leaves = Vec::new();
+    for _ in 0..2 {
+        let trader = fixture.new_trader(&mut bank, 1_000_000_000, 1_000_000_000);
+        let deposit = instructions::deposit(
+            keys,
+            trader.owner,
+            fixture.lp_token_account(&trader.owner),
+            trader.token_0,
+            trader.token_1,
+            true,
+            1_000_000,
+            u64::MAX,
+            u64::MAX,
+            None,
+            None,
+        );
+        process(&mut bank, &[deposit], &[trader.owner]);
+        let deposit_receipt_key = pda::deposit_receipt(&keys.pool_id, &trader.owner).0;
+        let receipt_rent = bank.lamports(&deposit_receipt_key);
+        let lamports = bank.lamports(&trader.owner);
+        process(
+            &mut bank,
+            &[instructions::compress_deposit_receipt(
+                keys.pool_id,
+                trader.owner,
+            )],
+            &[trader.owner],
+        );
+        // the rent of the receipt is refunded
+        assert!(bank.account(&deposit_receipt_key).is_none());
+        assert_eq!(bank.lamports(&trader.owner), lamports + receipt_rent);
+        let event = bank.events::<DepositReceiptCompressedEvent>().remove(0);
+        assert_eq!(event.leaf_index, leaves.len() as u64);
+        assert_eq!(event.lp_amount, 1_000_000);
+        leaves.push(deposit_receipt_leaf(
+            &keys.pool_id,
+            &trader.owner,
+            event.lp_amount,
+            event.last_deposit_time,
+            event.leaf_index,
+        ));
+        owners.push((trader.owner, event));
+    }
+    let receipt_tree_key = pda::receipt_tree(&keys.pool_id).0;
+    let root = bank
+        .get_zero_copy_account::<ReceiptTree>(&receipt_tree_key)
+        .unwrap()
+        .root();
+    let decompress = |index: usize, lp_amount: u64| {
+        let (owner, event) = &owners[index];
+        instructions::decompress_deposit_receipt(
+            keys.pool_id,
+            *owner,
+            root,
+            event.leaf_index,
+            lp_amount,
+            event.last_deposit_time,
+            receipt_proof(&leaves, index),
+        )
+    };
+
+    // a receipt is only restored with its own data, once
+    assert_anchor_error(
+        bank.process_transaction(&[decompress(0, 2_000_000)], &[owners[0].0]),
+        ErrorCode::InvalidReceiptProof,
+    );
+    for index in [1, 0] {
+        let owner = owners[index].0;
+        process(&mut bank, &[decompress(index, 1_000_000)], &[owner]);
+        let deposit_receipt: DepositReceipt = bank
+            .get_account(&pda::deposit_receipt(&keys.pool_id, &owner).0)
+            .unwrap();
+        assert_eq!(deposit_receipt.lp_amount, 1_000_000);
+        assert_eq!(
+            deposit_receipt.last_deposit_time,
+            owners[index].1.last_deposit_time
+        );
+    }
+    assert_anchor_error(
+        bank.process_transaction(&[decompress(0, 1_000_000)], &[owners[0].0]),
+        ErrorCode::InvalidReceiptProof,
+    );
+}
+
+#[test]
+fn lockup_pools_record_every_deposit_in_a_receipt() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let admin = raydium_cp_swap::admin::ID;
+    process(
+        &mut bank,
+        &[instructions::update_pool_lockup(keys, admin, 86_400, 100)],
+        &[admin],
+    );
+    let trader = fixture.new_trader(&mut bank, 1_000_000_000, 1_000_000_000);
+    let trader_lp_token = fixture.lp_token_account(&trader.owner);
+    let deposit = |deposit_receipt: bool, lp_token_amount: u64| {
+        instructions::deposit(
+            keys,
+            trader.owner,
+            trader_lp_token,
+            trader.token_0,
+            trader.token_1,
+            deposit_receipt,
+            lp_token_amount,
+            u64::MAX,
+            u64::MAX,
+            None,
+            None,
+        )
+    };
+
+    // the lp tokens minted without a receipt would pay the early exit fee forever
+    assert_anchor_error(
+        bank.process_transaction(&[deposit(false, 1_000_000)], &[trader.owner]),
+        ErrorCode::DepositReceiptRequired,
+    );
+    let deposit_time = bank.clock().unix_timestamp as u64;
+    process(&mut bank, &[deposit(true, 1_000_000)], &[trader.owner]);
+    // and so would the lp tokens of the deposits not recording one
+    let deposit_for = instructions::deposit_for(
+        keys,
+        trader.owner,
+        trader.owner,
+        trader_lp_token,
+        trader.token_0,
+        trader.token_1,
+        1_000_000,
+        u64::MAX,
+        u64::MAX,
+        None,
+    );
+    let deposit_single_token = instructions::deposit_single_token(
+        keys,
+        trader.owner,
+        trader_lp_token,
+        &keys.token_0_mint,
+        trader.token_0,
+        1_000_000,
+        1,
+    )
+    .unwrap();
+    for instruction in [deposit_for, deposit_single_token] {
+        assert_anchor_error(
+            bank.process_transaction(&[instruction], &[trader.owner]),
+            ErrorCode::DepositReceiptRequired,
+        );
+    }
+
+    // a small deposit half a lockup later barely moves the lockup of the receipt
+    bank.warp_forward(43_200);
+    process(&mut bank, &[deposit(true, 100)], &[trader.owner]);
+    let deposit_receipt_key = pda::deposit_receipt(&keys.pool_id, &trader.owner).0;
+    let deposit_receipt: DepositReceipt = bank.get_account(&deposit_receipt_key).unwrap();
+    assert_eq!(deposit_receipt.lp_amount, 1_000_100);
+    // (1_000_000 * 0 + 100 * 43_200) / 1_000_100 rounded up
+    assert_eq!(deposit_receipt.last_deposit_time, deposit_time + 5);
+
+    bank.warp_to_timestamp((deposit_time + 5 + 86_400) as i64);
+    let withdraw = instructions::withdraw(
+        keys,
+        trader.owner,
+        trader_lp_token,
+        trader.token_0,
+        trader.token_1,
+        true,
+        1_000_100,
+        0,
+        0,
+        None,
+        None,
+    );
+    process(&mut bank, &[withdraw], &[trader.owner]);
+    assert!(bank.events::<EarlyExitFeeEvent>().is_empty());
+}
@@ -0,0 +1,226 @@
+//! Flash loans of the vault tokens, repaid with their fee later in the same transaction
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::token::spl_token;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::FlashLoanEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, mint_to, process, token_balance};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup, Trader, TransactionError};
+
+/// 0.1% of the borrowed amount
+const FLASH_LOAN_FEE_RATE: u64 = 1_000;
+
+/// A pool with a flash loan fee and a borrower holding none of its tokens
+fn setup() -> (Bank, PoolFixture, Trader) {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    fixture.update_amm_config(&mut bank, 7, FLASH_LOAN_FEE_RATE);
+    let borrower = fixture.new_trader(&mut bank, 0, 0);
+    (bank, fixture, borrower)
+}
+
+fn flash_loan(fixture: &PoolFixture, borrower: &Trader, amount_0: u64) -> Instruction {
+    instructions::flash_loan(
+        &fixture.keys,
+        borrower.owner,
+        borrower.token_0,
+        borrower.token_1,
+        amount_0,
+        0,
+    )
+}
+
+fn repay(fixture: &PoolFixture, borrower: &Trader) -> Instruction {
+    instructions::repay_flash_loan(
+        &fixture.keys,
+        borrower.owner,
+        borrower.token_0,
+        borrower.token_1,
+    )
+}
+
+/// Give `borrower` the fee of borrowing `amount_0`
+fn fund_fee(bank: &mut Bank, fixture: &PoolFixture, borrower: &Trader, amount_0: u64) -> u64 {
+    let fee = (amount_0 * FLASH_LOAN_FEE_RATE).div_ceil(1_000_000);
+    mint_to(
+        bank,
+        &fixture.keys.token_0_mint,
+        &fixture.mint_authority,
+        &borrower.token_0,
+        fee,
+    );
+    fee
+}
+
+#[test]
+fn flash_loans_accrue_their_fee_to_the_reserves() {
+    let (mut bank, fixture, borrower) = setup();
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+    let amount_0 = vault_0_amount / 10 + 1;
+    let fee = fund_fee(&mut bank, &fixture, &borrower, amount_0);
+    assert!(fee > 0);
+    process(
+        &mut bank,
+        &[
+            flash_loan(&fixture, &borrower, amount_0),
+            repay(&fixture, &borrower),
+        ],
+        &[borrower.owner],
+    );
+    let event = bank.events::<FlashLoanEvent>().remove(0);
+    assert_eq!(event.token_0_amount, amount_0);
+    assert_eq!(event.token_0_fee, fee);
+    assert_eq!(event.token_1_fee, 0);
+
+    // the fee is paid by the borrower and stays in the reserves of the liquidity providers
+    assert_eq!(token_balance(&bank, &borrower.token_0), 0);
+    assert_eq!(
+        fixture.vault_amounts(&bank),
+        (vault_0_amount + fee, vault_1_amount)
+    );
+    let pool_state = fixture.pool_state(&bank);
+    assert!(!pool_state.is_flash_loan_active());
+    assert_eq!(
+        pool_state.vault_amount_without_fee(vault_0_amount + fee, vault_1_amount),
+        (vault_0_amount + fee, vault_1_amount)
+    );
+}
+
+#[test]
+fn flash_loans_must_be_repaid_to_the_pool_later_in_the_transaction() {
+    let (mut bank, fixture, borrower) = setup();
+    let amount_0 = fixture.vault_amounts(&bank).0 / 10;
+    fund_fee(&mut bank, &fixture, &borrower, amount_0);
+    let vault_amounts = fixture.vault_amounts(&bank);
+
+    // without the repay the loan is refused
+    assert_anchor_error(
+        bank.process_transaction(
+            &[flash_loan(&fixture, &borrower, amount_0)],
+            &[borrower.owner],
+        ),
+        ErrorCode::InvalidFlashLoan,
+    );
+    // a repay before the loan does not count
+    assert_anchor_error(
+        bank.process_transaction(
+            &[
+                repay(&fixture, &borrower),
+                flash_loan(&fixture, &borrower, amount_0),
+            ],
+            &[borrower.owner],
+        ),
+        ErrorCode::InvalidFlashLoan,
+    );
+    // nor does a repay in another transaction, with no loan to repay
+    assert_anchor_error(
+        bank.process_transaction(&[repay(&fixture, &borrower)], &[borrower.owner]),
+        ErrorCode::InvalidFlashLoan,
+    );
+    // the repay must be to the pool of the loan
+    let other_fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let other_borrower = other_fixture.new_trader(&mut bank, 0, 0);
+    assert_anchor_error(
+        bank.process_transaction(
+            &[
+                flash_loan(&fixture, &borrower, amount_0),
+                repay(&other_fixture, &other_borrower),
+            ],
+            &[borrower.owner, other_borrower.owner],
+        ),
+        ErrorCode::InvalidFlashLoan,
+    );
+    assert_eq!(fixture.vault_amounts(&bank), vault_amounts);
+}
+
+#[test]
+fn flash_loans_repaid_short_of_the_fee_revert() {
+    let (mut bank, fixture, borrower) = setup();
+    let amount_0 = fixture.vault_amounts(&bank).0 / 10;
+    let fee = fund_fee(&mut bank, &fixture, &borrower, amount_0);
+    // the borrower spends part of the fee, the repay is short of it
+    let burn = spl_token::instruction::burn(
+        &spl_token::ID,
+        &borrower.token_0,
+        &fixture.keys.token_0_mint,
+        &borrower.owner,
+        &[],
+        1,
+    )
+    .unwrap();
+    process(&mut bank, &[burn], &[borrower.owner]);
+    let vault_amounts = fixture.vault_amounts(&bank);
+    assert_eq!(
+        bank.process_transaction(
+            &[
+                flash_loan(&fixture, &borrower, amount_0),
+                repay(&fixture, &borrower),
+            ],
+            &[borrower.owner],
+        ),
+        Err(TransactionError {
+            instruction_index: 1,
+            error: ProgramError::Custom(spl_token::error::TokenError::InsufficientFunds as u32),
+        })
+    );
+    assert_eq!(fixture.vault_amounts(&bank), vault_amounts);
+    assert_eq!(token_balance(&bank, &borrower.token_0), fee - 1);
+}
+
+#[test]
+fn pools_with_a_flash_loan_in_flight_refuse_trades_and_deposits() {
+    let (mut bank, fixture, borrower) = setup();
+    let keys = &fixture.keys;
+    let amount_0 = fixture.vault_amounts(&bank).0 / 10;
+    fund_fee(&mut bank, &fixture, &borrower, amount_0);
+    let trader = fixture.new_trader(&mut bank, 1_000_000_000, 1_000_000_000);
+    let swap = instructions::swap_base_input(
+        keys,
+        trader.owner,
+        &keys.token_1_mint,
+        trader.token_1,
+        trader.token_0,
+        1_000_000,
+        1,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let deposit = instructions::deposit(
+        keys,
+        trader.owner,
+        fixture.lp_token_account(&trader.owner),
+        trader.token_0,
+        trader.token_1,
+        false,
+        1_000_000,
+        u64::MAX,
+        u64::MAX,
+        None,
+        None,
+    );
+    for instruction in [swap, deposit, instructions::sync_reserves(keys)] {
+        let result = bank.process_transaction(
+            &[
+                flash_loan(&fixture, &borrower, amount_0),
+                instruction,
+                repay(&fixture, &borrower),
+            ],
+            &[borrower.owner, trader.owner],
+        );
+        assert_eq!(result.as_ref().unwrap_err().instruction_index, 1);
+        assert_anchor_error(result, ErrorCode::FlashLoanInProgress);
+    }
+    // the loan alone goes through
+    process(
+        &mut bank,
+        &[
+            flash_loan(&fixture, &borrower, amount_0),
+            repay(&fixture, &borrower),
+        ],
+        &[borrower.owner],
+    );
+}
@@ -0,0 +1,91 @@
+//! Pools of mints with a freeze authority, withdrawn from while a vault is frozen
+
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::FrozenVaultWithdrawEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, process, token_balance};
+use raydium_cp_swap_test_kit::{BalanceSnapshot, Bank, MintSetup, PoolFixture, PoolSetup};
+
+#[test]
+fn lp_tokens_withdraw_the_vault_not_frozen() {
+    let mut bank = Bank::new();
+    let setup = PoolSetup {
+        mint_a: MintSetup::spl_token(9).with_freeze_authority(),
+        mint_b: MintSetup::spl_token(6).with_freeze_authority(),
+        accept_freeze_risk: true,
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let keys = &fixture.keys;
+    let creator = fixture.creator;
+    let creator_lp_token = fixture.lp_token_account(&creator.owner);
+    let freeze_vault = spl_token_2022::instruction::freeze_account(
+        &keys.token_1_program,
+        &keys.token_1_vault,
+        &keys.token_1_mint,
+        &fixture.mint_authority,
+        &[],
+    )
+    .unwrap();
+    process(&mut bank, &[freeze_vault], &[fixture.mint_authority]);
+
+    let lp_amount = token_balance(&bank, &creator_lp_token) / 2;
+    let withdraw = instructions::withdraw(
+        keys,
+        creator.owner,
+        creator_lp_token,
+        creator.token_0,
+        creator.token_1,
+        false,
+        lp_amount,
+        0,
+        0,
+        None,
+        None,
+    );
+    assert!(bank
+        .process_transaction(&[withdraw], &[creator.owner])
+        .is_err());
+
+    let handle_frozen_vault = |minimum_out| {
+        instructions::handle_frozen_vault(
+            keys,
+            creator.owner,
+            creator_lp_token,
+            creator.token_0,
+            &keys.token_1_mint,
+            false,
+            lp_amount,
+            minimum_out,
+        )
+        .unwrap()
+    };
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+    let lp_supply = fixture.pool_state(&bank).lp_supply;
+    let output_amount =
+        (u128::from(vault_0_amount) * u128::from(lp_amount) / u128::from(lp_supply)) as u64;
+    assert_anchor_error(
+        bank.process_transaction(&[handle_frozen_vault(output_amount + 1)], &[creator.owner]),
+        ErrorCode::ExceededSlippage,
+    );
+    let snapshot = BalanceSnapshot::take(&bank, &[creator.token_0, creator_lp_token]);
+    process(
+        &mut bank,
+        &[handle_frozen_vault(output_amount)],
+        &[creator.owner],
+    );
+    snapshot.assert_changes(&bank, &[output_amount as i128, -(lp_amount as i128)]);
+    // the share of the frozen vault stays with the remaining liquidity providers
+    assert_eq!(
+        fixture.vault_amounts(&bank),
+        (vault_0_amount - output_amount, vault_1_amount)
+    );
+    let lp_supply_after = fixture.pool_state(&bank).lp_supply;
+    assert_eq!(lp_supply_after, lp_supply - lp_amount);
+    let event = bank.events::<FrozenVaultWithdrawEvent>().remove(0);
+    assert_eq!(event.frozen_vault, keys.token_1_vault);
+    assert_eq!(
+        event.forfeited_amount,
+        (u128::from(vault_1_amount) * u128::from(lp_amount) / u128::from(lp_supply)) as u64
+    );
+}
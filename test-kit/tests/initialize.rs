@@ -0,0 +1,235 @@
+//! Pool creation and the accounts it creates along the pool
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use raydium_cp_swap::curve::{CurveCalculator, CurveType, FeeRates};
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{AmmConfig, LpMintRegistry, LOCKED_LP_AMOUNT};
+use raydium_cp_swap_client::{instructions, pda, PoolKeys};
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_mint, create_token_account, mint_to, new_payer, process,
+    token_balance,
+};
+use raydium_cp_swap_test_kit::{Bank, MintSetup, PoolFixture, PoolSetup, Trader};
+
+#[test]
+fn lp_mint_registry_resolves_the_pool() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let lp_mint_registry: LpMintRegistry = bank
+        .get_account(&pda::lp_mint_registry(&keys.lp_mint).0)
+        .unwrap();
+    assert_eq!(lp_mint_registry.lp_mint, keys.lp_mint);
+    assert_eq!(lp_mint_registry.pool_id, keys.pool_id);
+}
+
+/// A pool of two new mints on the amm config of `fixture`, not created yet, with a creator
+/// holding `amount_0` token_0 and `amount_1` token_1 and its pool creation fee account
+fn new_pool(
+    bank: &mut Bank,
+    fixture: &PoolFixture,
+    amount_0: u64,
+    amount_1: u64,
+) -> (PoolFixture, Pubkey) {
+    let mint_authority = new_payer(bank);
+    let mint_a = create_mint(bank, &mint_authority, MintSetup::spl_token(9));
+    let mint_b = create_mint(bank, &mint_authority, MintSetup::spl_token(6));
+    let keys = PoolKeys::new(
+        fixture.keys.amm_config,
+        mint_a,
+        spl_token::ID,
+        mint_b,
+        spl_token::ID,
+    );
+    let mut pool = PoolFixture {
+        keys,
+        creator: fixture.creator,
+        mint_authority,
+    };
+    pool.creator = pool.new_trader(bank, amount_0, amount_1);
+    let amm_config: AmmConfig = bank.get_account(&keys.amm_config).unwrap();
+    let create_pool_fee = create_token_account(
+        bank,
+        &pool.creator.owner,
+        &amm_config.create_pool_fee_receiver,
+        &spl_token::native_mint::ID,
+    );
+    (pool, create_pool_fee)
+}
+
+#[test]
+fn initialize_and_swap_buys_from_the_new_pool() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let (init_amount_0, init_amount_1) = (1_000_000_000_000, 1_000_000_000);
+    let amount_in = 10_000_000_000;
+    let (pool, create_pool_fee) = new_pool(
+        &mut bank,
+        &fixture,
+        init_amount_0 + amount_in,
+        init_amount_1,
+    );
+    let expected = CurveCalculator::swap_base_input(
+        CurveType::ConstantProduct,
+        u128::from(amount_in),
+        u128::from(init_amount_0),
+        u128::from(init_amount_1),
+        FeeRates {
+            trade_fee_rate: 2_500,
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+        },
+    )
+    .unwrap();
+    let amount_out = u64::try_from(expected.destination_amount_swapped).unwrap();
+    let initialize_and_swap = |minimum_amount_out| {
+        instructions::initialize_and_swap(
+            &pool.keys,
+            pool.creator.owner,
+            pool.creator.token_0,
+            pool.creator.token_1,
+            create_pool_fee,
+            [false, false],
+            raydium_cp_swap::instruction::InitializeAndSwap {
+                init_amount_0,
+                init_amount_1,
+                open_time: 0,
+                authority_type: 0,
+                custom_authority: None,
+                curve_type: CurveType::CONSTANT_PRODUCT,
+                amp: 0,
+                launch_mode: 0,
+                launch_duration: 0,
+                lp_mint_metadata: None,
+                accept_freeze_risk: false,
+                zero_for_one: true,
+                amount_in,
+                minimum_amount_out,
+            },
+        )
+    };
+
+    // the slippage check of the swap reverts the pool creation with it
+    assert_anchor_error(
+        bank.process_transaction(
+            &[initialize_and_swap(amount_out + 1)],
+            &[pool.creator.owner],
+        ),
+        ErrorCode::ExceededSlippage,
+    );
+    assert!(bank.account(&pool.keys.pool_id).is_none());
+
+    process(
+        &mut bank,
+        &[initialize_and_swap(amount_out)],
+        &[pool.creator.owner],
+    );
+    assert_eq!(token_balance(&bank, &pool.creator.token_0), 0);
+    assert_eq!(token_balance(&bank, &pool.creator.token_1), amount_out);
+    assert_eq!(
+        pool.vault_amounts(&bank),
+        (init_amount_0 + amount_in, init_amount_1 - amount_out)
+    );
+    let pool_state = pool.pool_state(&bank);
+    assert_eq!(
+        { pool_state.protocol_fees_token_0 },
+        u64::try_from(expected.protocol_fee).unwrap()
+    );
+    assert_eq!(
+        { pool_state.fund_fees_token_0 },
+        u64::try_from(expected.fund_fee).unwrap()
+    );
+}
+
+#[test]
+fn donations_inflating_the_lp_token_value_cost_the_attacker_not_the_next_depositor() {
+    let mut bank = Bank::new();
+    // the attacker creates the pool with just enough liquidity for the locked lp tokens
+    let fixture = PoolFixture::setup(
+        &mut bank,
+        &PoolSetup {
+            init_amount_a: 2 * LOCKED_LP_AMOUNT,
+            init_amount_b: 2 * LOCKED_LP_AMOUNT,
+            ..PoolSetup::default()
+        },
+    );
+    let keys = &fixture.keys;
+    let attacker = &fixture.creator;
+    let attacker_lp_token = fixture.lp_token_account(&attacker.owner);
+    assert_eq!(token_balance(&bank, &attacker_lp_token), LOCKED_LP_AMOUNT);
+    let withdraw = |owner: &Trader, lp_token_amount| {
+        instructions::withdraw(
+            keys,
+            owner.owner,
+            fixture.lp_token_account(&owner.owner),
+            owner.token_0,
+            owner.token_1,
+            false,
+            lp_token_amount,
+            0,
+            0,
+            None,
+            None,
+        )
+    };
+    // down to a single lp token, then a donation to the vaults inflates its value
+    process(
+        &mut bank,
+        &[withdraw(attacker, LOCKED_LP_AMOUNT - 1)],
+        &[attacker.owner],
+    );
+    assert_eq!(
+        { fixture.pool_state(&bank).lp_supply },
+        LOCKED_LP_AMOUNT + 1
+    );
+    let donation = 1_000_000_000;
+    for (mint, vault) in [
+        (keys.token_0_mint, keys.token_0_vault),
+        (keys.token_1_mint, keys.token_1_vault),
+    ] {
+        mint_to(&mut bank, &mint, &fixture.mint_authority, &vault, donation);
+    }
+
+    // the victim deposits as much as the donation
+    let victim = fixture.new_trader(&mut bank, donation, donation);
+    process(
+        &mut bank,
+        &[instructions::deposit_exact_tokens(
+            keys,
+            victim.owner,
+            fixture.lp_token_account(&victim.owner),
+            victim.token_0,
+            victim.token_1,
+            false,
+            donation,
+            donation,
+            1,
+            None,
+        )],
+        &[victim.owner],
+    );
+    let victim_lp_amount = token_balance(&bank, &fixture.lp_token_account(&victim.owner));
+    process(
+        &mut bank,
+        &[withdraw(&victim, victim_lp_amount)],
+        &[victim.owner],
+    );
+    process(&mut bank, &[withdraw(attacker, 1)], &[attacker.owner]);
+
+    // the victim loses less than the value of one lp token, a thousandth of the deposit,
+    // while the donation mostly stays with the locked lp tokens
+    for (victim_account, attacker_account) in [
+        (victim.token_0, attacker.token_0),
+        (victim.token_1, attacker.token_1),
+    ] {
+        let victim_loss = donation - token_balance(&bank, &victim_account);
+        assert!(victim_loss <= donation / 1_000, "victim loss {victim_loss}");
+        let attacker_paid = 2 * LOCKED_LP_AMOUNT + donation;
+        let attacker_loss = attacker_paid - token_balance(&bank, &attacker_account);
+        assert!(
+            attacker_loss >= donation / 1_000 * 998,
+            "attacker loss {attacker_loss}"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! Liquidity bootstrapping pools, from their decaying weights to the constant product curve
+
+use raydium_cp_swap::curve::CurveType;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{LbpFinalizedEvent, SwapEvent, MIN_RAMP_DURATION};
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, process};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup, GENESIS_UNIX_TIMESTAMP};
+
+#[test]
+fn lbp_weights_decay_to_a_constant_product_pool() {
+    let mut bank = Bank::new();
+    let open_time = GENESIS_UNIX_TIMESTAMP as u64 + 3_600;
+    let setup = PoolSetup {
+        open_time,
+        curve_type: CurveType::WEIGHTED,
+        amp: 5_000,
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let keys = &fixture.keys;
+    let lbp_duration = MIN_RAMP_DURATION * 2;
+    process(
+        &mut bank,
+        &[instructions::configure_lbp(
+            keys,
+            fixture.creator.owner,
+            9_500,
+            lbp_duration,
+        )],
+        &[fixture.creator.owner],
+    );
+    let lbp_end_time = open_time + u64::from(lbp_duration);
+    assert_eq!(fixture.pool_state(&bank).lbp_end_time(), lbp_end_time);
+
+    // token_0 gets cheaper as its weight decays
+    let trader = fixture.new_trader(&mut bank, 0, 3_000_000);
+    let mut buy_token_0 = |bank: &mut Bank| {
+        fixture
+            .swap_base_input(bank, &trader, &keys.token_1_mint, 1_000_000, 1)
+            .unwrap();
+        bank.events::<SwapEvent>()[0].output_amount
+    };
+    bank.warp_to_timestamp(open_time as i64);
+    let amount_out_at_open = buy_token_0(&mut bank);
+    bank.warp_to_timestamp(lbp_end_time as i64 - 1);
+    assert_anchor_error(
+        bank.process_transaction(&[instructions::finalize_lbp(keys)], &[]),
+        ErrorCode::LbpNotEnded,
+    );
+    let amount_out_at_end = buy_token_0(&mut bank);
+    assert!(amount_out_at_end > amount_out_at_open * 15);
+
+    bank.warp_forward(1);
+    process(&mut bank, &[instructions::finalize_lbp(keys)], &[]);
+    assert_eq!(
+        bank.events::<LbpFinalizedEvent>()[0].lbp_end_time,
+        lbp_end_time
+    );
+    let pool_state = fixture.pool_state(&bank);
+    assert!(!pool_state.is_lbp());
+    assert_eq!(pool_state.curve_type, CurveType::CONSTANT_PRODUCT);
+    // the 50/50 weighted curve and the constant product curve trade alike, up to the
+    // price moved by the previous swap
+    let amount_out_finalized = buy_token_0(&mut bank);
+    assert!(amount_out_finalized.abs_diff(amount_out_at_end) <= amount_out_at_end / 100);
+}
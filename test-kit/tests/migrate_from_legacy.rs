@@ -0,0 +1,274 @@
+//! Liquidity of a legacy Raydium AMM v4 pool migrated into a pool in one transaction
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+};
+use anchor_spl::token::spl_token;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::instructions::LEGACY_WITHDRAW_INSTRUCTION;
+use raydium_cp_swap::states::LegacyLiquidityMigratedEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_mint, create_token_account, mint_to, new_payer, process,
+    token_balance,
+};
+use raydium_cp_swap_test_kit::{BalanceSnapshot, Bank, MintSetup, PoolFixture, PoolSetup, Trader};
+
+/// The seed of the PDA of the legacy pool, which owns its vaults
+const LEGACY_AUTHORITY_SEED: &[u8] = b"amm authority";
+
+/// A legacy pool paying out its vaults in proportion to the lp tokens withdrawn. Its
+/// accounts are the token program, the lp mint, the lp token account of the user, the
+/// user, the coin and pc vaults, the coin and pc token accounts of the user and the
+/// authority of the vaults
+fn process_legacy_amm_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let Some((&LEGACY_WITHDRAW_INSTRUCTION, amount)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let amount = u64::from_le_bytes(
+        amount
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    );
+    let [token_program, lp_mint, user_lp_token, user, coin_vault, pc_vault, user_coin, user_pc, authority] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let lp_supply = spl_token::state::Mint::unpack(&lp_mint.data.borrow())?.supply;
+    invoke(
+        &spl_token::instruction::burn(
+            token_program.key,
+            user_lp_token.key,
+            lp_mint.key,
+            user.key,
+            &[],
+            amount,
+        )?,
+        &[
+            user_lp_token.clone(),
+            lp_mint.clone(),
+            user.clone(),
+            token_program.clone(),
+        ],
+    )?;
+    let (_, bump) = Pubkey::find_program_address(&[LEGACY_AUTHORITY_SEED], program_id);
+    for (vault, user_token) in [(coin_vault, user_coin), (pc_vault, user_pc)] {
+        let vault_amount = spl_token::state::Account::unpack(&vault.data.borrow())?.amount;
+        let amount_out =
+            u64::try_from(u128::from(vault_amount) * u128::from(amount) / u128::from(lp_supply))
+                .unwrap();
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                vault.key,
+                user_token.key,
+                authority.key,
+                &[],
+                amount_out,
+            )?,
+            &[
+                vault.clone(),
+                user_token.clone(),
+                authority.clone(),
+                token_program.clone(),
+            ],
+            &[&[LEGACY_AUTHORITY_SEED, &[bump]]],
+        )?;
+    }
+    Ok(())
+}
+
+/// A pool and a legacy pool of its mints, the trader holds all the legacy lp tokens and
+/// lp token accounts of the pool
+struct Migration {
+    fixture: PoolFixture,
+    trader: Trader,
+    legacy_lp_token: Pubkey,
+    legacy_accounts: Vec<AccountMeta>,
+}
+
+impl Migration {
+    /// The legacy pool holds 1% of the token_0 and 2% of the token_1 of the pool, so the
+    /// pool price leaves half of the migrated token_1 to the trader
+    fn setup(bank: &mut Bank) -> Self {
+        let legacy_amm_program = raydium_cp_swap::legacy_amm_program::ID;
+        bank.add_native_program(legacy_amm_program, process_legacy_amm_instruction);
+        let fixture = PoolFixture::setup(bank, &PoolSetup::default());
+        let keys = &fixture.keys;
+        let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(bank);
+        let trader = fixture.new_trader(bank, 0, 0);
+        create_token_account(bank, &trader.owner, &trader.owner, &keys.lp_mint);
+
+        let payer = new_payer(bank);
+        let legacy_authority =
+            Pubkey::find_program_address(&[LEGACY_AUTHORITY_SEED], &legacy_amm_program).0;
+        let legacy_lp_mint = create_mint(bank, &payer, MintSetup::spl_token(9));
+        let legacy_lp_token = create_token_account(bank, &payer, &trader.owner, &legacy_lp_mint);
+        mint_to(
+            bank,
+            &legacy_lp_mint,
+            &payer,
+            &legacy_lp_token,
+            1_000_000_000,
+        );
+        let mut legacy_vaults = [Pubkey::default(); 2];
+        for (legacy_vault, (mint, amount)) in legacy_vaults.iter_mut().zip([
+            (keys.token_0_mint, vault_0_amount / 100),
+            (keys.token_1_mint, vault_1_amount / 50),
+        ]) {
+            *legacy_vault = create_token_account(bank, &payer, &legacy_authority, &mint);
+            mint_to(bank, &mint, &fixture.mint_authority, legacy_vault, amount);
+        }
+        let legacy_accounts = vec![
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new(legacy_lp_mint, false),
+            AccountMeta::new(legacy_lp_token, false),
+            AccountMeta::new_readonly(trader.owner, true),
+            AccountMeta::new(legacy_vaults[0], false),
+            AccountMeta::new(legacy_vaults[1], false),
+            AccountMeta::new(trader.token_0, false),
+            AccountMeta::new(trader.token_1, false),
+            AccountMeta::new_readonly(legacy_authority, false),
+        ];
+        Self {
+            fixture,
+            trader,
+            legacy_lp_token,
+            legacy_accounts,
+        }
+    }
+
+    fn migrate(&self, legacy_lp_amount: u64, minimum_lp_token_amount: u64) -> Instruction {
+        instructions::migrate_from_legacy(
+            &self.fixture.keys,
+            self.trader.owner,
+            self.legacy_lp_token,
+            self.fixture.lp_token_account(&self.trader.owner),
+            self.trader.token_0,
+            self.trader.token_1,
+            &self.legacy_accounts,
+            legacy_lp_amount,
+            minimum_lp_token_amount,
+        )
+    }
+}
+
+#[test]
+fn legacy_liquidity_is_deposited_at_the_pool_price() {
+    let mut bank = Bank::new();
+    let migration = Migration::setup(&mut bank);
+    let fixture = &migration.fixture;
+    let keys = &fixture.keys;
+    let trader = &migration.trader;
+    let lp_token = fixture.lp_token_account(&trader.owner);
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+    let lp_supply = fixture.pool_state(&bank).lp_supply;
+    let balances = BalanceSnapshot::take(
+        &bank,
+        &[
+            migration.legacy_lp_token,
+            trader.token_0,
+            trader.token_1,
+            keys.token_0_vault,
+            keys.token_1_vault,
+        ],
+    );
+
+    // half of the legacy lp tokens withdraw 0.5% of the token_0 and 1% of the token_1
+    let migrate = migration.migrate(500_000_000, 1);
+    process(&mut bank, &[migrate], &[trader.owner]);
+    let event = bank.events::<LegacyLiquidityMigratedEvent>().remove(0);
+    assert_eq!(event.pool_id, keys.pool_id);
+    assert_eq!(event.legacy_lp_amount, 500_000_000);
+    assert_eq!(event.token_0_amount, vault_0_amount / 200);
+    assert_eq!(event.token_1_amount, vault_1_amount / 100);
+    assert_eq!(token_balance(&bank, &lp_token), event.lp_amount);
+    assert_eq!(event.lp_amount, lp_supply / 200);
+
+    // the deposit takes all the token_0 and the token_1 at the pool price, the trader keeps
+    // the rest of the token_1
+    let changes = balances.changes(&bank);
+    assert_eq!(changes[0], -500_000_000);
+    assert_eq!(changes[1], 0);
+    assert!(changes[2] > 0);
+    assert_eq!(changes[3], i128::from(event.token_0_amount));
+    assert_eq!(changes[2] + changes[4], i128::from(event.token_1_amount));
+    // the lp tokens minted are backed at the pool price
+    let pool_state = fixture.pool_state(&bank);
+    assert_eq!({ pool_state.lp_supply }, lp_supply + event.lp_amount);
+    let (vault_0_amount_after, vault_1_amount_after) = fixture.vault_amounts(&bank);
+    assert!(
+        u128::from(vault_0_amount_after) * u128::from(lp_supply)
+            >= u128::from(vault_0_amount) * u128::from(pool_state.lp_supply)
+    );
+    assert!(
+        u128::from(vault_1_amount_after) * u128::from(lp_supply)
+            >= u128::from(vault_1_amount) * u128::from(pool_state.lp_supply)
+    );
+}
+
+#[test]
+fn legacy_migrations_short_of_the_minimum_lp_tokens_revert_the_withdrawal() {
+    let mut bank = Bank::new();
+    let migration = Migration::setup(&mut bank);
+    let fixture = &migration.fixture;
+    let trader = &migration.trader;
+    let lp_supply = fixture.pool_state(&bank).lp_supply;
+    let balances = BalanceSnapshot::take(
+        &bank,
+        &[migration.legacy_lp_token, trader.token_0, trader.token_1],
+    );
+    assert_anchor_error(
+        bank.process_transaction(
+            &[migration.migrate(500_000_000, lp_supply / 200 + 1)],
+            &[trader.owner],
+        ),
+        ErrorCode::ExceededSlippage,
+    );
+    // the legacy lp tokens stay in the legacy pool
+    balances.assert_changes(&bank, &[0, 0, 0]);
+    let migrate = migration.migrate(500_000_000, lp_supply / 200);
+    process(&mut bank, &[migrate], &[trader.owner]);
+}
+
+#[test]
+fn legacy_withdrawals_must_burn_the_legacy_lp_tokens_of_the_migration() {
+    let mut bank = Bank::new();
+    let migration = Migration::setup(&mut bank);
+    let trader = &migration.trader;
+
+    // the legacy withdrawal burns from another token account than the legacy lp token
+    // account of the migration, which sees no legacy lp tokens burned
+    let mut migrate = migration.migrate(500_000_000, 1);
+    let other_mint = create_mint(&mut bank, &trader.owner, MintSetup::spl_token(9));
+    let other_legacy_lp_token =
+        create_token_account(&mut bank, &trader.owner, &trader.owner, &other_mint);
+    let legacy_lp_token = migrate
+        .accounts
+        .iter_mut()
+        .find(|account| account.pubkey == migration.legacy_lp_token)
+        .unwrap();
+    legacy_lp_token.pubkey = other_legacy_lp_token;
+    assert_anchor_error(
+        bank.process_transaction(&[migrate], &[trader.owner]),
+        ErrorCode::InvalidInput,
+    );
+
+    // all the legacy withdrawal accounts must be in the remaining accounts
+    let mut migrate = migration.migrate(500_000_000, 1);
+    migrate.accounts.pop();
+    assert_anchor_error(
+        bank.process_transaction(&[migrate], &[trader.owner]),
+        ErrorCode::InvalidInput,
+    );
+}
@@ -0,0 +1,266 @@
+//! Liquidity of the admin moved between two pools of the same mints without trading
+
+use anchor_lang::solana_program::instruction::Instruction;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::PoolRebalanceEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_token_account, mint_to, process, token_balance,
+};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup, Trader};
+
+/// Two pools of the same mints in the fee tiers of different configs at slightly different
+/// prices, and the admin holding lp tokens of the first one
+struct Rebalance {
+    from: PoolFixture,
+    to: PoolFixture,
+    admin: Trader,
+}
+
+impl Rebalance {
+    fn setup(bank: &mut Bank) -> Self {
+        let from = PoolFixture::setup(bank, &PoolSetup::default());
+        let (vault_0_amount, vault_1_amount) = from.vault_amounts(bank);
+        let to = PoolFixture::setup_with_mints(
+            bank,
+            &PoolSetup {
+                config_index: 1,
+                init_amount_a: vault_0_amount / 2,
+                init_amount_b: vault_1_amount / 3,
+                ..Default::default()
+            },
+            from.mint_authority,
+            from.keys.token_0_mint,
+            from.keys.token_1_mint,
+        );
+
+        let owner = raydium_cp_swap::admin::ID;
+        let admin = Trader {
+            owner,
+            token_0: create_token_account(bank, &owner, &owner, &from.keys.token_0_mint),
+            token_1: create_token_account(bank, &owner, &owner, &from.keys.token_1_mint),
+        };
+        for (token_account, mint, amount) in [
+            (admin.token_0, from.keys.token_0_mint, vault_0_amount),
+            (admin.token_1, from.keys.token_1_mint, vault_1_amount),
+        ] {
+            mint_to(bank, &mint, &from.mint_authority, &token_account, amount);
+        }
+        let from_lp_token = create_token_account(bank, &owner, &owner, &from.keys.lp_mint);
+        create_token_account(bank, &owner, &owner, &to.keys.lp_mint);
+        let deposit = instructions::deposit(
+            &from.keys,
+            owner,
+            from_lp_token,
+            admin.token_0,
+            admin.token_1,
+            false,
+            1_000_000_000,
+            u64::MAX,
+            u64::MAX,
+            None,
+            None,
+        );
+        process(bank, &[deposit], &[owner]);
+        Self { from, to, admin }
+    }
+
+    fn rebalance(&self, lp_token_amount: u64, minimum_lp_token_amount_out: u64) -> Instruction {
+        instructions::rebalance_between_pools(
+            &self.from.keys,
+            &self.to.keys,
+            self.admin.owner,
+            self.admin.token_0,
+            self.admin.token_1,
+            lp_token_amount,
+            minimum_lp_token_amount_out,
+        )
+    }
+}
+
+/// The reserves and lp supply of a pool
+fn pool_value(bank: &Bank, fixture: &PoolFixture) -> (u128, u128, u128) {
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(bank);
+    let pool_state = fixture.pool_state(bank);
+    let (token_0_amount, token_1_amount) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount);
+    (
+        u128::from(token_0_amount),
+        u128::from(token_1_amount),
+        u128::from(pool_state.lp_supply),
+    )
+}
+
+/// Check the tokens backing each lp token of a pool did not decrease
+fn assert_lp_share_value_kept(before: (u128, u128, u128), after: (u128, u128, u128)) {
+    assert!(after.0 * before.2 >= before.0 * after.2);
+    assert!(after.1 * before.2 >= before.1 * after.2);
+}
+
+#[test]
+fn rebalances_keep_the_lp_share_value_of_both_pools() {
+    let mut bank = Bank::new();
+    let rebalance = Rebalance::setup(&mut bank);
+    let from_lp_token = rebalance.from.lp_token_account(&rebalance.admin.owner);
+    let to_lp_token = rebalance.to.lp_token_account(&rebalance.admin.owner);
+    let lp_token_amount = token_balance(&bank, &from_lp_token);
+    let from_value = pool_value(&bank, &rebalance.from);
+    let to_value = pool_value(&bank, &rebalance.to);
+    let balances = [rebalance.admin.token_0, rebalance.admin.token_1]
+        .map(|token_account| token_balance(&bank, &token_account));
+
+    process(
+        &mut bank,
+        &[rebalance.rebalance(lp_token_amount, 1)],
+        &[rebalance.admin.owner],
+    );
+    let event = bank.events::<PoolRebalanceEvent>().remove(0);
+    assert_eq!(event.from_lp_amount, lp_token_amount);
+    assert_eq!(token_balance(&bank, &from_lp_token), 0);
+    assert_eq!(token_balance(&bank, &to_lp_token), event.to_lp_amount);
+    // the ratio of the second pool leaves part of one token over, refunded to the admin
+    assert!(event.token_0_refund > 0 || event.token_1_refund > 0);
+    assert_eq!(
+        token_balance(&bank, &rebalance.admin.token_0),
+        balances[0] + event.token_0_refund
+    );
+    assert_eq!(
+        token_balance(&bank, &rebalance.admin.token_1),
+        balances[1] + event.token_1_refund
+    );
+
+    let from_value_after = pool_value(&bank, &rebalance.from);
+    let to_value_after = pool_value(&bank, &rebalance.to);
+    assert_eq!(
+        from_value_after.2,
+        from_value.2 - u128::from(lp_token_amount)
+    );
+    assert_eq!(
+        to_value_after.2,
+        to_value.2 + u128::from(event.to_lp_amount)
+    );
+    assert_lp_share_value_kept(from_value, from_value_after);
+    assert_lp_share_value_kept(to_value, to_value_after);
+}
+
+#[test]
+fn rebalances_check_the_minimum_lp_tokens_and_the_admin() {
+    let mut bank = Bank::new();
+    let rebalance = Rebalance::setup(&mut bank);
+    let lp_token_amount = token_balance(
+        &bank,
+        &rebalance.from.lp_token_account(&rebalance.admin.owner),
+    );
+    process(
+        &mut bank,
+        &[rebalance.rebalance(lp_token_amount / 2, 1)],
+        &[rebalance.admin.owner],
+    );
+    let to_lp_amount = bank.events::<PoolRebalanceEvent>().remove(0).to_lp_amount;
+    // the second half mints no more lp tokens than the first one at the same ratios
+    assert_anchor_error(
+        bank.process_transaction(
+            &[rebalance.rebalance(lp_token_amount / 2, to_lp_amount + 1)],
+            &[rebalance.admin.owner],
+        ),
+        ErrorCode::ExceededSlippage,
+    );
+
+    // the pool creator holds lp tokens of the first pool, but is not the admin
+    let trader = rebalance.from.creator;
+    create_token_account(
+        &mut bank,
+        &trader.owner,
+        &trader.owner,
+        &rebalance.to.keys.lp_mint,
+    );
+    let instruction = instructions::rebalance_between_pools(
+        &rebalance.from.keys,
+        &rebalance.to.keys,
+        trader.owner,
+        trader.token_0,
+        trader.token_1,
+        1_000,
+        1,
+    );
+    assert_anchor_error(
+        bank.process_transaction(&[instruction], &[trader.owner]),
+        ErrorCode::InvalidOwner,
+    );
+}
+
+#[test]
+fn rebalances_respect_the_lockups_and_flash_loans_of_both_pools() {
+    let mut bank = Bank::new();
+    let rebalance = Rebalance::setup(&mut bank);
+    let admin = rebalance.admin.owner;
+    let rebalance_all = rebalance.rebalance(1_000_000, 1);
+
+    for (fixture, error) in [
+        (&rebalance.from, ErrorCode::WithdrawalLockup),
+        (&rebalance.to, ErrorCode::DepositReceiptRequired),
+    ] {
+        let keys = &fixture.keys;
+        process(
+            &mut bank,
+            &[instructions::update_pool_lockup(keys, admin, 3600, 0)],
+            &[admin],
+        );
+        assert_anchor_error(
+            bank.process_transaction(&[rebalance_all.clone()], &[admin]),
+            error,
+        );
+        process(
+            &mut bank,
+            &[instructions::update_pool_lockup(keys, admin, 0, 0)],
+            &[admin],
+        );
+    }
+
+    for fixture in [&rebalance.from, &rebalance.to] {
+        let borrower = fixture.new_trader(&mut bank, 0, 0);
+        let flash_loan = instructions::flash_loan(
+            &fixture.keys,
+            borrower.owner,
+            borrower.token_0,
+            borrower.token_1,
+            1_000_000,
+            0,
+        );
+        let repay = instructions::repay_flash_loan(
+            &fixture.keys,
+            borrower.owner,
+            borrower.token_0,
+            borrower.token_1,
+        );
+        let result = bank.process_transaction(
+            &[flash_loan, rebalance_all.clone(), repay],
+            &[borrower.owner, admin],
+        );
+        assert_eq!(result.as_ref().unwrap_err().instruction_index, 1);
+        assert_anchor_error(result, ErrorCode::FlashLoanInProgress);
+    }
+    process(&mut bank, &[rebalance_all], &[admin]);
+}
+
+#[test]
+fn rebalances_between_pools_of_other_mints_are_rejected() {
+    let mut bank = Bank::new();
+    let rebalance = Rebalance::setup(&mut bank);
+    let other = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let admin = rebalance.admin.owner;
+    create_token_account(&mut bank, &admin, &admin, &other.keys.lp_mint);
+    let instruction = instructions::rebalance_between_pools(
+        &rebalance.from.keys,
+        &other.keys,
+        admin,
+        rebalance.admin.token_0,
+        rebalance.admin.token_1,
+        1_000_000,
+        1,
+    );
+    assert_anchor_error(
+        bank.process_transaction(&[instruction], &[admin]),
+        ErrorCode::InvalidInput,
+    );
+}
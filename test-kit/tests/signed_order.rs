@@ -0,0 +1,232 @@
+//! Swaps signed off-chain by the owner of the input tokens and submitted by a relayer
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::token::spl_token;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::SwapOrder;
+use raydium_cp_swap_client::{instructions, pda};
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_token_account, mint_to, new_payer, process, token_balance,
+};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup, TransactionError};
+
+const AMOUNT_IN: u64 = 1_000_000;
+
+fn keypair(seed: u8) -> (Keypair, Pubkey) {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    let key = Pubkey::new_from_array(public.to_bytes());
+    (Keypair { secret, public }, key)
+}
+
+/// The ed25519 instruction verifying the signature of `signer` over `order`
+fn verify_order(signer: &Keypair, order: &SwapOrder) -> Instruction {
+    let message = order.try_to_vec().unwrap();
+    instructions::ed25519_verify(
+        &Pubkey::new_from_array(signer.public.to_bytes()),
+        &signer.sign(&message).to_bytes(),
+        &message,
+    )
+}
+
+/// An owner holding the input token of the orders, its order authority approved up to
+/// `AMOUNT_IN`
+struct OrderOwner {
+    keypair: Keypair,
+    key: Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+}
+
+impl OrderOwner {
+    fn new(bank: &mut Bank, fixture: &PoolFixture, delegate: Pubkey) -> Self {
+        let (keypair, key) = keypair(7);
+        let payer = new_payer(bank);
+        let keys = &fixture.keys;
+        let input_token_account = create_token_account(bank, &payer, &key, &keys.token_0_mint);
+        let output_token_account = create_token_account(bank, &payer, &key, &keys.token_1_mint);
+        mint_to(
+            bank,
+            &keys.token_0_mint,
+            &fixture.mint_authority,
+            &input_token_account,
+            AMOUNT_IN,
+        );
+        let approve = spl_token::instruction::approve(
+            &spl_token::ID,
+            &input_token_account,
+            &delegate,
+            &key,
+            &[],
+            AMOUNT_IN,
+        )
+        .unwrap();
+        process(bank, &[approve], &[key]);
+        Self {
+            keypair,
+            key,
+            input_token_account,
+            output_token_account,
+        }
+    }
+
+    fn order(&self, bank: &Bank, fixture: &PoolFixture) -> SwapOrder {
+        SwapOrder {
+            owner: self.key,
+            pool_id: fixture.keys.pool_id,
+            input_mint: fixture.keys.token_0_mint,
+            output_mint: fixture.keys.token_1_mint,
+            amount_in: AMOUNT_IN,
+            minimum_amount_out: 1,
+            expiry: bank.clock().unix_timestamp as u64 + 60,
+            nonce: 0,
+        }
+    }
+
+    fn swap(&self, fixture: &PoolFixture, relayer: Pubkey, order: SwapOrder) -> Instruction {
+        instructions::swap_signed_order(
+            &fixture.keys,
+            relayer,
+            order,
+            self.input_token_account,
+            self.output_token_account,
+        )
+        .unwrap()
+    }
+}
+
+fn setup() -> (Bank, PoolFixture, OrderOwner, Pubkey) {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let (_, owner_key) = keypair(7);
+    let owner = OrderOwner::new(&mut bank, &fixture, pda::swap_order_authority(&owner_key).0);
+    let relayer = new_payer(&mut bank);
+    (bank, fixture, owner, relayer)
+}
+
+#[test]
+fn signed_orders_swap_once_through_the_order_authority_of_the_owner() {
+    let (mut bank, fixture, owner, relayer) = setup();
+    let order = owner.order(&bank, &fixture);
+    let transaction = [
+        verify_order(&owner.keypair, &order),
+        owner.swap(&fixture, relayer, order),
+    ];
+    process(&mut bank, &transaction, &[relayer]);
+    assert_eq!(token_balance(&bank, &owner.input_token_account), 0);
+    assert!(token_balance(&bank, &owner.output_token_account) > 0);
+
+    // the nonce of the owner moved on, the same order can't be replayed
+    mint_to(
+        &mut bank,
+        &fixture.keys.token_0_mint,
+        &fixture.mint_authority,
+        &owner.input_token_account,
+        AMOUNT_IN,
+    );
+    assert_anchor_error(
+        bank.process_transaction(&transaction, &[relayer]),
+        ErrorCode::InvalidOrderNonce,
+    );
+}
+
+#[test]
+fn signed_orders_do_not_pull_through_the_pool_authority() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let owner = OrderOwner::new(&mut bank, &fixture, fixture.keys.authority);
+    let relayer = new_payer(&mut bank);
+    let order = owner.order(&bank, &fixture);
+    let transaction = [
+        verify_order(&owner.keypair, &order),
+        owner.swap(&fixture, relayer, order),
+    ];
+    // the delegate signing the input transfer is the order authority of the owner
+    assert_eq!(
+        bank.process_transaction(&transaction, &[relayer]),
+        Err(TransactionError {
+            instruction_index: 1,
+            error: ProgramError::Custom(spl_token::error::TokenError::OwnerMismatch as u32),
+        })
+    );
+    assert_eq!(token_balance(&bank, &owner.input_token_account), AMOUNT_IN);
+}
+
+#[test]
+fn signed_orders_need_the_signature_of_the_owner_over_the_order() {
+    let (mut bank, fixture, owner, relayer) = setup();
+    let order = owner.order(&bank, &fixture);
+
+    // a tampered signature fails the ed25519 precompile
+    let mut verify = verify_order(&owner.keypair, &order);
+    verify.data[48] ^= 1;
+    assert_eq!(
+        bank.process_transaction(&[verify, owner.swap(&fixture, relayer, order)], &[relayer]),
+        Err(TransactionError {
+            instruction_index: 0,
+            error: ProgramError::Custom(2),
+        })
+    );
+    // the signature of the owner over another order does not execute this one
+    let signed_order = SwapOrder {
+        amount_in: 1,
+        ..order
+    };
+    assert_anchor_error(
+        bank.process_transaction(
+            &[
+                verify_order(&owner.keypair, &signed_order),
+                owner.swap(&fixture, relayer, order),
+            ],
+            &[relayer],
+        ),
+        ErrorCode::InvalidSignature,
+    );
+    // nor does the signature of another key over the order
+    let (other_keypair, _) = keypair(8);
+    assert_anchor_error(
+        bank.process_transaction(
+            &[
+                verify_order(&other_keypair, &order),
+                owner.swap(&fixture, relayer, order),
+            ],
+            &[relayer],
+        ),
+        ErrorCode::InvalidSignature,
+    );
+    // and the order is refused without the ed25519 instruction right before it
+    assert_anchor_error(
+        bank.process_transaction(&[owner.swap(&fixture, relayer, order)], &[relayer]),
+        ErrorCode::InvalidSignature,
+    );
+    assert_eq!(token_balance(&bank, &owner.input_token_account), AMOUNT_IN);
+}
+
+#[test]
+fn signed_orders_expire_and_bound_the_output() {
+    let (mut bank, fixture, owner, relayer) = setup();
+    let order = owner.order(&bank, &fixture);
+    let signed_swap = |order: SwapOrder| {
+        [
+            verify_order(&owner.keypair, &order),
+            owner.swap(&fixture, relayer, order),
+        ]
+    };
+
+    let greedy_order = SwapOrder {
+        minimum_amount_out: u64::MAX,
+        ..order
+    };
+    assert_anchor_error(
+        bank.process_transaction(&signed_swap(greedy_order), &[relayer]),
+        ErrorCode::ExceededSlippage,
+    );
+    bank.warp_forward(61);
+    assert_anchor_error(
+        bank.process_transaction(&signed_swap(order), &[relayer]),
+        ErrorCode::OrderExpired,
+    );
+    assert_eq!(token_balance(&bank, &owner.input_token_account), AMOUNT_IN);
+}
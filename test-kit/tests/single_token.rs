@@ -0,0 +1,203 @@
+//! Single token deposits and withdrawals, which swap part of the liquidity on the curve
+
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::DepositEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, process, token_balance, MintSetup};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup};
+
+#[test]
+fn deposit_single_token_creates_the_lp_token_account() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let trader = fixture.new_trader(&mut bank, 1_000_000, 0);
+    let trader_lp_token = fixture.lp_token_account(&trader.owner);
+    assert!(bank.account(&trader_lp_token).is_none());
+    let deposit = instructions::deposit_single_token(
+        keys,
+        trader.owner,
+        trader_lp_token,
+        &keys.token_0_mint,
+        trader.token_0,
+        1_000_000,
+        1,
+    )
+    .unwrap();
+    process(&mut bank, &[deposit], &[trader.owner]);
+    let event = bank.events::<DepositEvent>().remove(0);
+    assert!(event.lp_amount > 0);
+    assert_eq!(token_balance(&bank, &trader_lp_token), event.lp_amount);
+    assert_eq!(token_balance(&bank, &trader.token_0), 0);
+}
+
+#[test]
+fn single_token_liquidity_follows_the_trading_hours() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let admin = raydium_cp_swap::admin::ID;
+    let trading_start_time = bank.clock().unix_timestamp as u64 + 3_600;
+    process(
+        &mut bank,
+        &[instructions::update_pool_trading_schedule(
+            keys,
+            admin,
+            trading_start_time,
+            0,
+            0,
+            0,
+            0,
+        )],
+        &[admin],
+    );
+
+    let trader = fixture.new_trader(&mut bank, 1_000_000, 0);
+    let deposit = instructions::deposit_single_token(
+        keys,
+        trader.owner,
+        fixture.lp_token_account(&trader.owner),
+        &keys.token_0_mint,
+        trader.token_0,
+        1_000_000,
+        1,
+    )
+    .unwrap();
+    let creator = fixture.creator;
+    let withdraw = instructions::withdraw_single_token(
+        keys,
+        creator.owner,
+        fixture.lp_token_account(&creator.owner),
+        &keys.token_0_mint,
+        creator.token_0,
+        1_000_000,
+        1,
+    )
+    .unwrap();
+    let transactions = [(deposit, trader.owner), (withdraw, creator.owner)];
+    for (instruction, signer) in &transactions {
+        assert_anchor_error(
+            bank.process_transaction(&[instruction.clone()], &[*signer]),
+            ErrorCode::OutsideTradingHours,
+        );
+    }
+    bank.warp_to_timestamp(trading_start_time as i64);
+    for (instruction, signer) in &transactions {
+        process(&mut bank, &[instruction.clone()], &[*signer]);
+    }
+}
+
+#[test]
+fn single_token_liquidity_swaps_within_the_trade_size_limits() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let admin = raydium_cp_swap::admin::ID;
+    let (vault_0_amount, _) = fixture.vault_amounts(&bank);
+    let lp_supply = fixture.pool_state(&bank).lp_supply;
+    let max_trade_amount = vault_0_amount / 1_000;
+    process(
+        &mut bank,
+        &[instructions::update_pool_trade_size_limits(
+            keys,
+            admin,
+            0,
+            max_trade_amount,
+        )],
+        &[admin],
+    );
+
+    // about half of a deposit is swapped, a withdrawal swaps the lp share of token_1
+    let trader = fixture.new_trader(&mut bank, 4 * max_trade_amount, 0);
+    let trader_lp_token = fixture.lp_token_account(&trader.owner);
+    let deposit = |amount_in| {
+        instructions::deposit_single_token(
+            keys,
+            trader.owner,
+            trader_lp_token,
+            &keys.token_0_mint,
+            trader.token_0,
+            amount_in,
+            1,
+        )
+        .unwrap()
+    };
+    assert_anchor_error(
+        bank.process_transaction(&[deposit(4 * max_trade_amount)], &[trader.owner]),
+        ErrorCode::TradeSizeOutOfRange,
+    );
+    process(&mut bank, &[deposit(max_trade_amount)], &[trader.owner]);
+
+    let creator = fixture.creator;
+    let withdraw = |lp_token_amount| {
+        instructions::withdraw_single_token(
+            keys,
+            creator.owner,
+            fixture.lp_token_account(&creator.owner),
+            &keys.token_0_mint,
+            creator.token_0,
+            lp_token_amount,
+            1,
+        )
+        .unwrap()
+    };
+    assert_anchor_error(
+        bank.process_transaction(&[withdraw(lp_supply / 250)], &[creator.owner]),
+        ErrorCode::TradeSizeOutOfRange,
+    );
+    process(&mut bank, &[withdraw(lp_supply / 10_000)], &[creator.owner]);
+}
+
+#[test]
+fn single_token_liquidity_checks_the_transfer_fee_cap() {
+    let mut bank = Bank::new();
+    let setup = PoolSetup {
+        mint_a: MintSetup::token_2022(6).with_transfer_fee(100, u64::MAX),
+        init_amount_a: 1_000_000_000,
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let keys = &fixture.keys;
+    let other_mint = if keys.token_0_program == spl_token_2022::ID {
+        keys.token_1_mint
+    } else {
+        keys.token_0_mint
+    };
+    fixture.update_amm_config(&mut bank, 31, 50);
+
+    // the fee mint stops the pool even when the liquidity comes in and out in the other token
+    let trader = fixture.new_trader(&mut bank, 1_000_000, 1_000_000);
+    let deposit = instructions::deposit_single_token(
+        keys,
+        trader.owner,
+        fixture.lp_token_account(&trader.owner),
+        &other_mint,
+        trader.swap_accounts(keys, &other_mint).0,
+        1_000_000,
+        1,
+    )
+    .unwrap();
+    let creator = fixture.creator;
+    let withdraw = instructions::withdraw_single_token(
+        keys,
+        creator.owner,
+        fixture.lp_token_account(&creator.owner),
+        &other_mint,
+        creator.swap_accounts(keys, &other_mint).0,
+        1_000_000,
+        1,
+    )
+    .unwrap();
+    assert_anchor_error(
+        bank.process_transaction(&[deposit.clone()], &[trader.owner]),
+        ErrorCode::TransferFeeTooHigh,
+    );
+    assert_anchor_error(
+        bank.process_transaction(&[withdraw.clone()], &[creator.owner]),
+        ErrorCode::TransferFeeTooHigh,
+    );
+
+    fixture.update_amm_config(&mut bank, 31, 100);
+    process(&mut bank, &[deposit], &[trader.owner]);
+    process(&mut bank, &[withdraw], &[creator.owner]);
+}
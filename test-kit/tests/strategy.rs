@@ -0,0 +1,175 @@
+//! Pool reserves lent to the authorized strategy of the pool and repaid with yield
+
+use anchor_lang::solana_program::instruction::Instruction;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::StrategyRepayEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, mint_to, process, token_balance};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup, Trader};
+
+/// A pool lending up to 90% of its reserves to a strategy holding no tokens
+fn setup() -> (Bank, PoolFixture, Trader) {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let strategy = fixture.new_trader(&mut bank, 0, 0);
+    let admin = raydium_cp_swap::admin::ID;
+    process(
+        &mut bank,
+        &[instructions::update_pool_strategy(
+            &fixture.keys,
+            admin,
+            strategy.owner,
+            9000,
+        )],
+        &[admin],
+    );
+    (bank, fixture, strategy)
+}
+
+fn borrow(fixture: &PoolFixture, strategy: &Trader, amount_0: u64, amount_1: u64) -> Instruction {
+    instructions::strategy_borrow(
+        &fixture.keys,
+        strategy.owner,
+        strategy.token_0,
+        strategy.token_1,
+        amount_0,
+        amount_1,
+    )
+}
+
+/// Withdraw half of the lp tokens of the pool creator
+fn withdraw_half(bank: &Bank, fixture: &PoolFixture) -> Instruction {
+    let creator = &fixture.creator;
+    let lp_token_account = fixture.lp_token_account(&creator.owner);
+    instructions::withdraw(
+        &fixture.keys,
+        creator.owner,
+        lp_token_account,
+        creator.token_0,
+        creator.token_1,
+        false,
+        token_balance(bank, &lp_token_account) / 2,
+        0,
+        0,
+        None,
+        None,
+    )
+}
+
+#[test]
+fn strategies_borrow_within_the_limit_of_the_pool() {
+    let (mut bank, fixture, strategy) = setup();
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+    assert_anchor_error(
+        bank.process_transaction(
+            &[borrow(&fixture, &strategy, vault_0_amount / 10 * 9 + 1, 0)],
+            &[strategy.owner],
+        ),
+        ErrorCode::StrategyBorrowLimitExceeded,
+    );
+    // only the authorized strategy borrows
+    let other = fixture.new_trader(&mut bank, 0, 0);
+    assert_anchor_error(
+        bank.process_transaction(&[borrow(&fixture, &other, 1, 0)], &[other.owner]),
+        ErrorCode::InvalidAuthority,
+    );
+
+    process(
+        &mut bank,
+        &[borrow(
+            &fixture,
+            &strategy,
+            vault_0_amount / 2,
+            vault_1_amount / 2,
+        )],
+        &[strategy.owner],
+    );
+    assert_eq!(token_balance(&bank, &strategy.token_0), vault_0_amount / 2);
+    assert_eq!(token_balance(&bank, &strategy.token_1), vault_1_amount / 2);
+    let pool_state = fixture.pool_state(&bank);
+    assert_eq!({ pool_state.strategy_borrowed_0 }, vault_0_amount / 2);
+    assert_eq!({ pool_state.strategy_borrowed_1 }, vault_1_amount / 2);
+    // the borrowed reserves keep counting in the limit
+    assert_anchor_error(
+        bank.process_transaction(
+            &[borrow(&fixture, &strategy, vault_0_amount / 10 * 4 + 1, 0)],
+            &[strategy.owner],
+        ),
+        ErrorCode::StrategyBorrowLimitExceeded,
+    );
+}
+
+#[test]
+fn outflows_are_capped_at_the_vaults_while_reserves_are_lent() {
+    let (mut bank, fixture, strategy) = setup();
+    let keys = &fixture.keys;
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+    let borrowed_0 = vault_0_amount / 10 * 9;
+    process(
+        &mut bank,
+        &[borrow(&fixture, &strategy, borrowed_0, 0)],
+        &[strategy.owner],
+    );
+
+    // half of the reserves of token_0 is more than the vault has left
+    let creator = fixture.creator.owner;
+    assert_anchor_error(
+        bank.process_transaction(&[withdraw_half(&bank, &fixture)], &[creator]),
+        ErrorCode::StrategyReservesLent,
+    );
+    let trader = fixture.new_trader(&mut bank, 0, vault_1_amount);
+    assert_anchor_error(
+        fixture.swap_base_input(
+            &mut bank,
+            &trader,
+            &keys.token_1_mint,
+            vault_1_amount / 4,
+            1,
+        ),
+        ErrorCode::StrategyReservesLent,
+    );
+    // smaller outputs are paid from what is left in the vault
+    fixture
+        .swap_base_input(
+            &mut bank,
+            &trader,
+            &keys.token_1_mint,
+            vault_1_amount / 100,
+            1,
+        )
+        .unwrap();
+
+    // the strategy repays with yield, the yield stays in the reserves
+    let yield_0 = 1_000_000;
+    mint_to(
+        &mut bank,
+        &keys.token_0_mint,
+        &fixture.mint_authority,
+        &strategy.token_0,
+        yield_0,
+    );
+    let (vault_0_amount, _) = fixture.vault_amounts(&bank);
+    process(
+        &mut bank,
+        &[instructions::strategy_repay(
+            keys,
+            strategy.owner,
+            strategy.token_0,
+            strategy.token_1,
+            borrowed_0 + yield_0,
+            0,
+        )],
+        &[strategy.owner],
+    );
+    let event = bank.events::<StrategyRepayEvent>().remove(0);
+    assert_eq!(event.token_0_yield, yield_0);
+    assert_eq!(event.strategy_borrowed_0, 0);
+    assert_eq!(
+        fixture.vault_amounts(&bank).0,
+        vault_0_amount + borrowed_0 + yield_0
+    );
+    assert!(!fixture.pool_state(&bank).is_strategy_borrowed());
+
+    let withdraw = withdraw_half(&bank, &fixture);
+    process(&mut bank, &[withdraw], &[creator]);
+}
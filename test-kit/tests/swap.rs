@@ -0,0 +1,340 @@
+//! Swaps of the pool fixtures: price limits, referral fees, fee rebates, transfer fee caps,
+//! compute budget and quotes
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::AnchorDeserialize;
+use anchor_spl::token::spl_token;
+use raydium_cp_swap::curve::Fees;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{
+    LpFeeRebateEvent, PoolReserves, ReferralFeeEvent, SwapEvent, SwapQuote, SwapReceipt,
+};
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, assert_token_balance, create_token_account, process, token_balance,
+};
+use raydium_cp_swap_test_kit::{BalanceSnapshot, Bank, MintSetup, PoolFixture, PoolSetup};
+
+#[test]
+fn swap_base_input_stops_at_the_sqrt_price_limit() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+    let sqrt_price_x64 = PoolReserves::new(vault_0_amount, vault_1_amount, 0).sqrt_price_x64;
+    let sqrt_price_limit_x64 = sqrt_price_x64 / 100 * 99;
+    let amount_in = vault_0_amount / 10;
+    let trader = fixture.new_trader(&mut bank, amount_in, 0);
+    let swap = |sqrt_price_limit_x64| {
+        instructions::swap_base_input(
+            keys,
+            trader.owner,
+            &keys.token_0_mint,
+            trader.token_0,
+            trader.token_1,
+            amount_in,
+            1,
+            None,
+            Some(sqrt_price_limit_x64),
+            None,
+        )
+        .unwrap()
+    };
+
+    // selling token_0 never fills above the current price
+    assert_anchor_error(
+        bank.process_transaction(&[swap(sqrt_price_x64 + 1)], &[trader.owner]),
+        ErrorCode::SqrtPriceLimitReached,
+    );
+    bank.process_transaction(&[swap(sqrt_price_limit_x64)], &[trader.owner])
+        .unwrap();
+    let receipt = SwapReceipt::try_from_slice(&bank.return_data().unwrap().1).unwrap();
+    // a 1% lower sqrt price is about 1% more token_0 in the pool
+    assert!(receipt.amount_in > amount_in / 20 && receipt.amount_in < amount_in / 5);
+    assert!(receipt.sqrt_price_after_x64 >= sqrt_price_limit_x64);
+    assert!(receipt.sqrt_price_after_x64 - sqrt_price_limit_x64 < sqrt_price_x64 / 1_000_000);
+    // the input beyond the limit stays with the trader
+    assert_token_balance(&bank, &trader.token_0, amount_in - receipt.amount_in);
+    assert_token_balance(&bank, &trader.token_1, receipt.amount_out);
+}
+
+#[test]
+fn swaps_rebate_a_share_of_the_lp_fee_in_lp_tokens() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    // half of the liquidity provider fee is rebated, a timelocked fee rate change
+    fixture.update_amm_config(&mut bank, 32, 500_000);
+
+    let (vault_0_amount, _) = fixture.vault_amounts(&bank);
+    let amount_in = vault_0_amount / 1000;
+    let trader = fixture.new_trader(&mut bank, amount_in * 4, 0);
+    let trader_lp_token =
+        create_token_account(&mut bank, &trader.owner, &trader.owner, &keys.lp_mint);
+    let swap = |bank: &mut Bank| {
+        let instruction = instructions::swap_base_input(
+            keys,
+            trader.owner,
+            &keys.token_0_mint,
+            trader.token_0,
+            trader.token_1,
+            amount_in,
+            1,
+            None,
+            None,
+            Some((trader_lp_token, spl_token::ID)),
+        )
+        .unwrap();
+        process(bank, &[instruction], &[trader.owner]);
+        bank.events::<LpFeeRebateEvent>().pop()
+    };
+    let lp_supply = fixture.pool_state(&bank).lp_supply;
+    let event = swap(&mut bank).unwrap();
+    let swap_event = bank.events::<SwapEvent>().remove(0);
+    let lp_fee = swap_event.trade_fee - swap_event.protocol_fee - swap_event.fund_fee;
+    assert_eq!(event.rebate_fee, lp_fee / 2);
+    assert_eq!(event.mint, keys.token_0_mint);
+    assert_eq!(token_balance(&bank, &trader_lp_token), event.lp_amount);
+    let lp_supply_after = fixture.pool_state(&bank).lp_supply;
+    assert_eq!(lp_supply_after, lp_supply + event.lp_amount);
+    assert_eq!(swap_event.reserves_after.lp_supply, lp_supply_after);
+    // the lp tokens are worth the rebated fee at the pool price, a constant product
+    // pool is worth twice its token_0 reserve
+    let value =
+        u128::from(event.lp_amount) * 2 * u128::from(swap_event.reserves_after.token_0_vault)
+            / u128::from(lp_supply_after);
+    let rebate_fee = u128::from(event.rebate_fee);
+    assert!(value <= rebate_fee && value * 1000 >= rebate_fee * 999);
+
+    // the lp tokens minted per epoch are capped
+    let first_lp_amount = event.lp_amount;
+    let cap = first_lp_amount + first_lp_amount / 2;
+    fixture.update_amm_config(&mut bank, 33, cap);
+    assert_eq!(swap(&mut bank).unwrap().lp_amount, cap - first_lp_amount);
+    assert!(swap(&mut bank).is_none());
+    assert_eq!(token_balance(&bank, &trader_lp_token), cap);
+    let epoch = bank.clock().epoch;
+    bank.warp_to_epoch(epoch + 1);
+    assert!(swap(&mut bank).unwrap().lp_amount > 0);
+}
+
+#[test]
+fn swaps_pay_the_referrer_a_share_of_the_trade_fee() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    // a tenth of the trade fee goes to the referrer, a timelocked fee rate change
+    fixture.update_amm_config(&mut bank, 13, 100_000);
+    let trader = fixture.new_trader(&mut bank, 1_000_000_000, 0);
+    let referrer = fixture.new_trader(&mut bank, 0, 0);
+    let swap = |referrer_token_account: Pubkey| {
+        let mut instruction = instructions::swap_base_input(
+            keys,
+            trader.owner,
+            &keys.token_0_mint,
+            trader.token_0,
+            trader.token_1,
+            1_000_000,
+            1,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        // the first optional account of the swap is the referrer token account
+        let referrer_meta = instruction
+            .accounts
+            .iter_mut()
+            .find(|meta| meta.pubkey == raydium_cp_swap::ID)
+            .unwrap();
+        *referrer_meta = AccountMeta::new(referrer_token_account, false);
+        instruction
+    };
+
+    let balances = BalanceSnapshot::take(&bank, &[trader.token_0, keys.token_0_vault]);
+    process(&mut bank, &[swap(referrer.token_0)], &[trader.owner]);
+    let swap_event = bank.events::<SwapEvent>().remove(0);
+    let event = bank.events::<ReferralFeeEvent>().remove(0);
+    let referral_fee =
+        u64::try_from(Fees::referral_fee(u128::from(swap_event.trade_fee), 100_000).unwrap())
+            .unwrap();
+    assert!(referral_fee > 0);
+    assert_eq!(swap_event.referral_fee, referral_fee);
+    assert_eq!(event.amount, referral_fee);
+    assert_eq!(event.referrer_token_account, referrer.token_0);
+    assert_eq!(event.mint, keys.token_0_mint);
+    assert_token_balance(&bank, &referrer.token_0, referral_fee);
+    // the referral fee is sent out of the input vault, the protocol and fund fees stay
+    balances.assert_changes(&bank, &[-1_000_000, 1_000_000 - i128::from(referral_fee)]);
+    let pool_state = fixture.pool_state(&bank);
+    assert_eq!(
+        { pool_state.protocol_fees_token_0 },
+        swap_event.protocol_fee
+    );
+    assert_eq!({ pool_state.fund_fees_token_0 }, swap_event.fund_fee);
+
+    // the referrer is paid in the fee token
+    assert_anchor_error(
+        bank.process_transaction(&[swap(referrer.token_1)], &[trader.owner]),
+        ErrorCode::InvalidInput,
+    );
+}
+
+#[test]
+fn transfer_fees_beyond_the_config_cap_are_rejected() {
+    let mut bank = Bank::new();
+    let setup = PoolSetup {
+        mint_a: MintSetup::token_2022(6).with_transfer_fee(100, u64::MAX),
+        init_amount_a: 1_000_000_000,
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let keys = &fixture.keys;
+    let fee_mint = if keys.token_0_program == spl_token_2022::ID {
+        keys.token_0_mint
+    } else {
+        keys.token_1_mint
+    };
+    fixture.update_amm_config(&mut bank, 31, 50);
+
+    // the pool stops trading once its mint charges more than the cap
+    let trader = fixture.new_trader(&mut bank, 1_000_000, 1_000_000);
+    assert_anchor_error(
+        fixture.swap_base_input(&mut bank, &trader, &fee_mint, 1_000_000, 1),
+        ErrorCode::TransferFeeTooHigh,
+    );
+    let other_mint = if fee_mint == keys.token_0_mint {
+        keys.token_1_mint
+    } else {
+        keys.token_0_mint
+    };
+    assert_anchor_error(
+        fixture.swap_base_input(&mut bank, &trader, &other_mint, 1_000_000, 1),
+        ErrorCode::TransferFeeTooHigh,
+    );
+    fixture.update_amm_config(&mut bank, 31, 100);
+    fixture
+        .swap_base_input(&mut bank, &trader, &fee_mint, 1_000_000, 1)
+        .unwrap();
+}
+
+#[test]
+fn a_vanilla_swap_stays_within_its_compute_budget() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let trader = fixture.new_trader(&mut bank, 1_000_000, 0);
+    fixture
+        .swap_base_input(&mut bank, &trader, &fixture.keys.token_0_mint, 1_000_000, 1)
+        .unwrap();
+
+    // the metered calls of the swap: its two transfers, its events and its receipt
+    let compute_units = bank.compute_units_consumed();
+    assert!(compute_units > 2_000);
+    assert!(
+        compute_units < 60_000,
+        "a vanilla swap consumed {compute_units} compute units"
+    );
+}
+
+#[test]
+fn quotes_match_the_executed_swaps() {
+    let mut bank = Bank::new();
+    let setup = PoolSetup {
+        mint_a: MintSetup::token_2022(6).with_transfer_fee(100, u64::MAX),
+        init_amount_a: 1_000_000_000,
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let keys = &fixture.keys;
+    // the input token charges a transfer fee
+    let input_mint = if keys.token_0_program == spl_token_2022::ID {
+        keys.token_0_mint
+    } else {
+        keys.token_1_mint
+    };
+    let trader = fixture.new_trader(&mut bank, 100_000_000, 100_000_000);
+    let (input_token_account, output_token_account) = trader.swap_accounts(keys, &input_mint);
+    let quote = |bank: &mut Bank, instruction: Instruction| {
+        process(bank, &[instruction], &[]);
+        SwapQuote::try_from_slice(&bank.return_data().unwrap().1).unwrap()
+    };
+    let protocol_fees = |bank: &Bank| {
+        let pool_state = fixture.pool_state(bank);
+        if input_mint == keys.token_0_mint {
+            pool_state.protocol_fees_token_0
+        } else {
+            pool_state.protocol_fees_token_1
+        }
+    };
+
+    // an exact input swap sends and receives the quoted amounts, the transfer fees included
+    let amount_in = 10_000_000;
+    let quote_base_input = quote(
+        &mut bank,
+        instructions::quote_swap_base_input(keys, &input_mint, amount_in).unwrap(),
+    );
+    assert_eq!(quote_base_input.amount_in, amount_in);
+    assert!(quote_base_input.input_transfer_fee > 0);
+    assert_eq!(quote_base_input.output_transfer_fee, 0);
+    let quote_many = instructions::quote_many(&[*keys], &input_mint, amount_in).unwrap();
+    process(&mut bank, &[quote_many], &[]);
+    assert_eq!(
+        Vec::<Option<SwapQuote>>::try_from_slice(&bank.return_data().unwrap().1).unwrap(),
+        vec![Some(quote_base_input)]
+    );
+    let balances = BalanceSnapshot::take(&bank, &[input_token_account, output_token_account]);
+    let protocol_fees_before = protocol_fees(&bank);
+    fixture
+        .swap_base_input(
+            &mut bank,
+            &trader,
+            &input_mint,
+            amount_in,
+            quote_base_input.amount_out,
+        )
+        .unwrap();
+    balances.assert_changes(
+        &bank,
+        &[
+            -i128::from(amount_in),
+            i128::from(quote_base_input.amount_out),
+        ],
+    );
+    let receipt = SwapReceipt::try_from_slice(&bank.return_data().unwrap().1).unwrap();
+    assert_eq!(receipt.amount_in, quote_base_input.amount_in);
+    assert_eq!(receipt.amount_out, quote_base_input.amount_out);
+    assert_eq!(receipt.trade_fee, quote_base_input.trade_fee);
+    assert_eq!(
+        protocol_fees(&bank) - protocol_fees_before,
+        quote_base_input.protocol_fee
+    );
+
+    // an exact output swap costs the quoted input on the pool the first swap moved
+    let amount_out = 1_000_000;
+    let quote_base_output = quote(
+        &mut bank,
+        instructions::quote_swap_base_output(keys, &input_mint, amount_out).unwrap(),
+    );
+    assert_eq!(quote_base_output.amount_out, amount_out);
+    assert!(quote_base_output.input_transfer_fee > 0);
+    let balances = BalanceSnapshot::take(&bank, &[input_token_account, output_token_account]);
+    fixture
+        .swap_base_output(
+            &mut bank,
+            &trader,
+            &input_mint,
+            quote_base_output.amount_in,
+            amount_out,
+        )
+        .unwrap();
+    balances.assert_changes(
+        &bank,
+        &[
+            -i128::from(quote_base_output.amount_in),
+            i128::from(amount_out),
+        ],
+    );
+    // the lp mint is not a mint the pool trades
+    assert!(instructions::quote_swap_base_input(keys, &keys.lp_mint, amount_in).is_none());
+}
@@ -0,0 +1,247 @@
+//! Swaps routed through two pools, the intermediate token moving between their vaults
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::SwapEvent;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_mint, create_token_account, process, token_balance,
+};
+use raydium_cp_swap_test_kit::{BalanceSnapshot, Bank, MintSetup, PoolFixture, PoolSetup};
+
+/// 1% on every transfer of the intermediate mint
+const INTERMEDIATE_TRANSFER_FEE_BPS: u16 = 100;
+
+/// A route from an spl token through an intermediate token 2022 mint with a transfer fee
+/// to another spl token, and a trader holding the input token. The setups are
+/// deterministic, a route replayed on a second setup swaps the same amounts
+struct Route {
+    first: PoolFixture,
+    second: PoolFixture,
+    input_mint: Pubkey,
+    intermediate_mint: Pubkey,
+    trader: Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+}
+
+impl Route {
+    fn setup(bank: &mut Bank) -> Self {
+        let intermediate_mint_setup =
+            MintSetup::token_2022(6).with_transfer_fee(INTERMEDIATE_TRANSFER_FEE_BPS, u64::MAX);
+        let first = PoolFixture::setup(
+            bank,
+            &PoolSetup {
+                mint_b: intermediate_mint_setup,
+                ..Default::default()
+            },
+        );
+        let (input_mint, intermediate_mint) = if first.keys.token_0_program == spl_token_2022::ID {
+            (first.keys.token_1_mint, first.keys.token_0_mint)
+        } else {
+            (first.keys.token_0_mint, first.keys.token_1_mint)
+        };
+        let output_mint = create_mint(bank, &first.mint_authority, MintSetup::spl_token(9));
+        let second = PoolFixture::setup_with_mints(
+            bank,
+            &PoolSetup {
+                mint_a: intermediate_mint_setup,
+                mint_b: MintSetup::spl_token(9),
+                init_amount_a: 1_000_000_000,
+                init_amount_b: 1_000_000_000_000,
+                ..Default::default()
+            },
+            first.mint_authority,
+            intermediate_mint,
+            output_mint,
+        );
+
+        let trader = first.new_trader(bank, 100_000_000_000, 100_000_000_000);
+        let (input_token_account, _) = trader.swap_accounts(&first.keys, &input_mint);
+        let output_token_account =
+            create_token_account(bank, &trader.owner, &trader.owner, &output_mint);
+        Self {
+            first,
+            second,
+            input_mint,
+            intermediate_mint,
+            trader: trader.owner,
+            input_token_account,
+            output_token_account,
+        }
+    }
+
+    fn swap_route(&self, amount_in: u64, minimum_amount_out: u64) -> Instruction {
+        instructions::swap_route(
+            &self.first.keys,
+            &self.second.keys,
+            self.trader,
+            &self.input_mint,
+            self.input_token_account,
+            self.output_token_account,
+            amount_in,
+            minimum_amount_out,
+        )
+        .unwrap()
+    }
+
+    fn swap_route_base_output(&self, max_amount_in: u64, amount_out: u64) -> Instruction {
+        instructions::swap_route_base_output(
+            &self.first.keys,
+            &self.second.keys,
+            self.trader,
+            &self.input_mint,
+            self.input_token_account,
+            self.output_token_account,
+            max_amount_in,
+            amount_out,
+        )
+        .unwrap()
+    }
+
+    /// The token accounts of the trader and the intermediate vaults of the pools
+    fn balances(&self, bank: &Bank) -> BalanceSnapshot {
+        let first_trade = self.first.keys.trade_keys(&self.input_mint).unwrap();
+        let second_trade = self
+            .second
+            .keys
+            .trade_keys(&self.intermediate_mint)
+            .unwrap();
+        BalanceSnapshot::take(
+            bank,
+            &[
+                self.input_token_account,
+                first_trade.output_vault,
+                second_trade.input_vault,
+                self.output_token_account,
+            ],
+        )
+    }
+}
+
+/// Check the intermediate token sent by the first pool reached the second pool less a
+/// single transfer fee
+fn assert_intermediate_transfer_fee_charged_once(changes: &[i128]) {
+    let sent = u64::try_from(-changes[1]).unwrap();
+    let transfer_fee = (sent * u64::from(INTERMEDIATE_TRANSFER_FEE_BPS)).div_ceil(10_000);
+    assert!(transfer_fee > 0);
+    assert_eq!(changes[2], i128::from(sent - transfer_fee));
+}
+
+#[test]
+fn routes_charge_the_transfer_fee_of_the_intermediate_mint_once() {
+    let mut bank = Bank::new();
+    let route = Route::setup(&mut bank);
+    let balances = route.balances(&bank);
+    let swap_route = route.swap_route(1_000_000_000, 1);
+    process(&mut bank, &[swap_route], &[route.trader]);
+    let changes = balances.changes(&bank);
+    assert_eq!(changes[0], -1_000_000_000);
+    assert_intermediate_transfer_fee_charged_once(&changes);
+    assert!(changes[3] > 0);
+    // each hop is priced on the amount its input vault received
+    let events = bank.events::<SwapEvent>();
+    assert_eq!(events.len(), 2);
+    assert_eq!(i128::from(events[0].output_amount), -changes[1]);
+    assert_eq!(i128::from(events[1].input_amount), changes[2]);
+    assert_eq!(i128::from(events[1].output_amount), changes[3]);
+
+    let balances = route.balances(&bank);
+    let swap_route_base_output = route.swap_route_base_output(u64::MAX, 1_000_000);
+    process(&mut bank, &[swap_route_base_output], &[route.trader]);
+    let changes = balances.changes(&bank);
+    assert!(changes[0] < 0);
+    assert_intermediate_transfer_fee_charged_once(&changes);
+    assert_eq!(changes[3], 1_000_000);
+}
+
+#[test]
+fn route_slippage_is_checked_on_the_output_of_the_last_hop() {
+    let mut bank = Bank::new();
+    let route = Route::setup(&mut bank);
+    let swap_route = route.swap_route(1_000_000_000, 1);
+    process(&mut bank, &[swap_route], &[route.trader]);
+    let amount_received = token_balance(&bank, &route.output_token_account);
+
+    // the same route on the same pools receives the same amount
+    let mut bank = Bank::new();
+    let route = Route::setup(&mut bank);
+    assert_anchor_error(
+        bank.process_transaction(
+            &[route.swap_route(1_000_000_000, amount_received + 1)],
+            &[route.trader],
+        ),
+        ErrorCode::ExceededSlippage,
+    );
+    let swap_route = route.swap_route(1_000_000_000, amount_received);
+    process(&mut bank, &[swap_route], &[route.trader]);
+    assert_eq!(
+        token_balance(&bank, &route.output_token_account),
+        amount_received
+    );
+}
+
+#[test]
+fn route_slippage_is_checked_on_the_input_of_the_first_hop() {
+    let mut bank = Bank::new();
+    let route = Route::setup(&mut bank);
+    let balances = route.balances(&bank);
+    let swap_route_base_output = route.swap_route_base_output(u64::MAX, 1_000_000);
+    process(&mut bank, &[swap_route_base_output], &[route.trader]);
+    let amount_in = u64::try_from(-balances.changes(&bank)[0]).unwrap();
+
+    let mut bank = Bank::new();
+    let route = Route::setup(&mut bank);
+    let balances = route.balances(&bank);
+    assert_anchor_error(
+        bank.process_transaction(
+            &[route.swap_route_base_output(amount_in - 1, 1_000_000)],
+            &[route.trader],
+        ),
+        ErrorCode::ExceededSlippage,
+    );
+    let swap_route_base_output = route.swap_route_base_output(amount_in, 1_000_000);
+    process(&mut bank, &[swap_route_base_output], &[route.trader]);
+    let changes = balances.changes(&bank);
+    assert_eq!(changes[0], -i128::from(amount_in));
+    assert_eq!(changes[3], 1_000_000);
+}
+
+#[test]
+fn routes_through_the_same_pool_twice_are_rejected() {
+    let mut bank = Bank::new();
+    let route = Route::setup(&mut bank);
+    let keys = &route.first.keys;
+    // the second hop swaps the intermediate token back to the input token in the first pool
+    let swap_route = instructions::swap_route(
+        keys,
+        keys,
+        route.trader,
+        &route.input_mint,
+        route.input_token_account,
+        route.input_token_account,
+        1_000_000_000,
+        1,
+    )
+    .unwrap();
+    let swap_route_base_output = instructions::swap_route_base_output(
+        keys,
+        keys,
+        route.trader,
+        &route.input_mint,
+        route.input_token_account,
+        route.input_token_account,
+        u64::MAX,
+        1_000_000,
+    )
+    .unwrap();
+    let balances = route.balances(&bank);
+    for instruction in [swap_route, swap_route_base_output] {
+        assert_anchor_error(
+            bank.process_transaction(&[instruction], &[route.trader]),
+            ErrorCode::InvalidInput,
+        );
+    }
+    balances.assert_changes(&bank, &[0, 0, 0, 0]);
+}
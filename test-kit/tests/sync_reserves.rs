@@ -0,0 +1,102 @@
+//! Tokens sent directly to the vaults of a pool, synced into its reserves and oracle
+
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{PoolState, SyncReservesEvent};
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{assert_anchor_error, mint_to, process};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup};
+
+/// Overwrite the pool state of `fixture` with `update` applied to it
+fn update_pool_state(bank: &mut Bank, fixture: &PoolFixture, update: impl FnOnce(&mut PoolState)) {
+    let mut pool_state = fixture.pool_state(bank);
+    update(&mut pool_state);
+    let mut account = bank.account(&fixture.keys.pool_id).unwrap().clone();
+    account.data[8..PoolState::LEN].copy_from_slice(bytemuck::bytes_of(&pool_state));
+    bank.set_account(fixture.keys.pool_id, account);
+}
+
+#[test]
+fn donations_are_synced_into_the_reserves_of_the_lp_tokens() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let keys = &fixture.keys;
+    let trader = fixture.new_trader(&mut bank, 1_000_000_000, 0);
+    fixture
+        .swap_base_input(&mut bank, &trader, &keys.token_0_mint, 1_000_000_000, 1)
+        .unwrap();
+    let pool_state = fixture.pool_state(&bank);
+    let accrued_fees_0 = pool_state.protocol_fees_token_0
+        + pool_state.fund_fees_token_0
+        + pool_state.creator_fees_token_0;
+    assert!(accrued_fees_0 > 0);
+
+    let donation = 5_000_000;
+    mint_to(
+        &mut bank,
+        &keys.token_0_mint,
+        &fixture.mint_authority,
+        &keys.token_0_vault,
+        donation,
+    );
+    let (vault_0_amount, vault_1_amount) = fixture.vault_amounts(&bank);
+    bank.warp_forward(3600);
+    let observation_index = fixture.observation_state(&bank).observation_index;
+    process(&mut bank, &[instructions::sync_reserves(keys)], &[]);
+
+    // the donation is part of the reserves, not of the accrued fees
+    let event = bank.events::<SyncReservesEvent>().remove(0);
+    assert_eq!(event.pool_id, keys.pool_id);
+    assert_eq!(
+        event.reserves.token_0_vault,
+        vault_0_amount - accrued_fees_0
+    );
+    assert_eq!(
+        fixture
+            .pool_state(&bank)
+            .vault_amount_without_fee(vault_0_amount, vault_1_amount),
+        (event.reserves.token_0_vault, event.reserves.token_1_vault)
+    );
+    assert_eq!({ fixture.pool_state(&bank).protocol_fees_token_0 }, {
+        pool_state.protocol_fees_token_0
+    });
+    // and its price is recorded right away
+    assert_ne!(
+        { fixture.observation_state(&bank).observation_index },
+        observation_index
+    );
+}
+
+#[test]
+fn syncing_vaults_short_of_the_accrued_fees_is_rejected() {
+    let mut bank = Bank::new();
+    let fixture = PoolFixture::setup(&mut bank, &PoolSetup::default());
+    let sync_reserves = instructions::sync_reserves(&fixture.keys);
+    let (_, vault_1_amount) = fixture.vault_amounts(&bank);
+
+    update_pool_state(&mut bank, &fixture, |pool_state| {
+        pool_state.protocol_fees_token_1 = vault_1_amount;
+        pool_state.fund_fees_token_1 = 1;
+    });
+    assert_anchor_error(
+        bank.process_transaction(&[sync_reserves.clone()], &[]),
+        ErrorCode::InvalidVault,
+    );
+    // fees summing past u64::MAX are an error rather than a panic
+    update_pool_state(&mut bank, &fixture, |pool_state| {
+        pool_state.protocol_fees_token_1 = u64::MAX;
+    });
+    assert_anchor_error(
+        bank.process_transaction(&[sync_reserves.clone()], &[]),
+        ErrorCode::MathOverflow,
+    );
+    update_pool_state(&mut bank, &fixture, |pool_state| {
+        pool_state.protocol_fees_token_1 = 0;
+        pool_state.fund_fees_token_1 = 0;
+        pool_state.creator_fees_token_0 = u64::MAX;
+        pool_state.fund_fees_token_0 = 1;
+    });
+    assert_anchor_error(
+        bank.process_transaction(&[sync_reserves], &[]),
+        ErrorCode::MathOverflow,
+    );
+}
@@ -0,0 +1,329 @@
+//! Pools of token 2022 mints with a transfer hook, which the vault transfers run through
+//! the remaining accounts
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    program_error::ProgramError,
+};
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap_client::{instructions, pda};
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_mint, create_token_account, mint_to, new_payer, process,
+    set_transfer_hook_extra_accounts, token_balance,
+};
+use raydium_cp_swap_test_kit::{Bank, MintSetup, PoolFixture, PoolSetup};
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+use std::cell::RefCell;
+
+const HOOK_PROGRAM_ID: Pubkey = pubkey!("HookTest11111111111111111111111111111111111");
+const REENTRANT_HOOK_PROGRAM_ID: Pubkey = pubkey!("HookReentrant111111111111111111111111111111");
+/// The seed of the PDA of the reentrant hook, which signs the swaps of the hook
+const HOOK_TRADER_SEED: &[u8] = b"hook_trader";
+
+thread_local! {
+    /// The amounts of the transfers the hook ran on, the bank runs it on the test thread
+    static HOOK_EXECUTIONS: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A transfer hook recording the amount of each transfer of its mints
+fn process_hook_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    match TransferHookInstruction::unpack(data)? {
+        TransferHookInstruction::Execute { amount } => {
+            HOOK_EXECUTIONS.with(|executions| executions.borrow_mut().push(amount));
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+thread_local! {
+    /// The instruction the reentrant hook sends back into the program, none while disarmed
+    static REENTRANT_INSTRUCTION: RefCell<Option<Instruction>> = const { RefCell::new(None) };
+}
+
+/// A transfer hook calling back into the program whose vault transfer runs it
+fn process_reentrant_hook_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let TransferHookInstruction::Execute { .. } = TransferHookInstruction::unpack(data)? else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some(instruction) = REENTRANT_INSTRUCTION.with(|reentrant| reentrant.borrow().clone())
+    else {
+        return Ok(());
+    };
+    let (_, bump) = Pubkey::find_program_address(&[HOOK_TRADER_SEED], program_id);
+    invoke_signed(&instruction, accounts, &[&[HOOK_TRADER_SEED, &[bump]]])
+}
+
+/// The amounts the hook ran on since the last call
+fn take_hook_executions() -> Vec<u64> {
+    HOOK_EXECUTIONS.with(|executions| executions.take())
+}
+
+#[test]
+fn swaps_run_the_transfer_hook_vetted_by_the_token_badge() {
+    let mut bank = Bank::new();
+    bank.add_native_program(HOOK_PROGRAM_ID, process_hook_instruction);
+    let setup = PoolSetup {
+        mint_a: MintSetup::token_2022(9).with_transfer_hook(HOOK_PROGRAM_ID),
+        ..Default::default()
+    };
+    let fixture = PoolFixture::setup(&mut bank, &setup);
+    let keys = &fixture.keys;
+    let hook_mint = if keys.token_0_program == spl_token_2022::ID {
+        keys.token_0_mint
+    } else {
+        keys.token_1_mint
+    };
+    let other_mint = if hook_mint == keys.token_0_mint {
+        keys.token_1_mint
+    } else {
+        keys.token_0_mint
+    };
+
+    // the hook runs on the transfers into and out of its vault
+    let trader = fixture.new_trader(&mut bank, 1_000_000, 1_000_000);
+    take_hook_executions();
+    fixture
+        .swap_base_input(&mut bank, &trader, &hook_mint, 1_000_000, 1)
+        .unwrap();
+    assert_eq!(take_hook_executions(), [1_000_000]);
+    let (hook_token_account, _) = trader.swap_accounts(keys, &hook_mint);
+    let hook_balance = token_balance(&bank, &hook_token_account);
+    fixture
+        .swap_base_input(&mut bank, &trader, &other_mint, 1_000_000, 1)
+        .unwrap();
+    assert_eq!(
+        take_hook_executions(),
+        [token_balance(&bank, &hook_token_account) - hook_balance]
+    );
+
+    // without the badge in the remaining accounts the hook is not vetted
+    let (input_token_account, output_token_account) = trader.swap_accounts(keys, &hook_mint);
+    let mut swap = instructions::swap_base_input(
+        keys,
+        trader.owner,
+        &hook_mint,
+        input_token_account,
+        output_token_account,
+        1_000_000,
+        1,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let token_badge = pda::token_badge(&hook_mint).0;
+    swap.accounts.extend(
+        fixture
+            .transfer_hook_accounts(&bank)
+            .into_iter()
+            .filter(|account| account.pubkey != token_badge),
+    );
+    assert_anchor_error(
+        bank.process_transaction(&[swap], &[trader.owner]),
+        ErrorCode::NotSupportMint,
+    );
+
+    // the vault transfers stop once the admin revokes the badge
+    let admin = raydium_cp_swap::admin::ID;
+    process(
+        &mut bank,
+        &[instructions::close_token_badge(admin, hook_mint)],
+        &[admin],
+    );
+    assert_anchor_error(
+        fixture.swap_base_input(&mut bank, &trader, &hook_mint, 1_000_000, 1),
+        ErrorCode::NotSupportMint,
+    );
+}
+
+/// A route from the hook mint of its first pool through a second pool, the input transfer of
+/// the route runs the reentrant hook
+struct ReentrantRoute {
+    first: PoolFixture,
+    second: PoolFixture,
+    hook_mint: Pubkey,
+    intermediate_mint: Pubkey,
+    output_mint: Pubkey,
+    /// The PDA of the reentrant hook
+    hook_trader: Pubkey,
+}
+
+impl ReentrantRoute {
+    fn setup(bank: &mut Bank) -> Self {
+        bank.add_native_program(
+            REENTRANT_HOOK_PROGRAM_ID,
+            process_reentrant_hook_instruction,
+        );
+        let first = PoolFixture::setup(
+            bank,
+            &PoolSetup {
+                mint_a: MintSetup::token_2022(9).with_transfer_hook(REENTRANT_HOOK_PROGRAM_ID),
+                ..Default::default()
+            },
+        );
+        let hook_mint = if first.keys.token_0_program == spl_token_2022::ID {
+            first.keys.token_0_mint
+        } else {
+            first.keys.token_1_mint
+        };
+        let intermediate_mint = if first.keys.token_0_mint == hook_mint {
+            first.keys.token_1_mint
+        } else {
+            first.keys.token_0_mint
+        };
+        let output_mint = create_mint(bank, &first.mint_authority, MintSetup::spl_token(9));
+        let second = PoolFixture::setup_with_mints(
+            bank,
+            &PoolSetup::default(),
+            first.mint_authority,
+            intermediate_mint,
+            output_mint,
+        );
+        Self {
+            first,
+            second,
+            hook_mint,
+            intermediate_mint,
+            output_mint,
+            hook_trader: Pubkey::find_program_address(
+                &[HOOK_TRADER_SEED],
+                &REENTRANT_HOOK_PROGRAM_ID,
+            )
+            .0,
+        }
+    }
+
+    /// The route of a new trader, the trader and its output token account. The accounts of
+    /// `reentrant` are the extra accounts of the hook
+    fn route(&self, bank: &mut Bank, reentrant: &Instruction) -> (Instruction, Pubkey, Pubkey) {
+        let mut extra_accounts = reentrant
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                is_signer: false,
+                ..meta.clone()
+            })
+            .collect::<Vec<_>>();
+        extra_accounts.push(AccountMeta::new_readonly(raydium_cp_swap::ID, false));
+        set_transfer_hook_extra_accounts(
+            bank,
+            &self.hook_mint,
+            &REENTRANT_HOOK_PROGRAM_ID,
+            &extra_accounts,
+        );
+
+        let payer = new_payer(bank);
+        let trader = self.first.new_trader(bank, 1_000_000_000, 1_000_000_000);
+        let (input_token_account, _) = trader.swap_accounts(&self.first.keys, &self.hook_mint);
+        let output_token_account =
+            create_token_account(bank, &payer, &trader.owner, &self.output_mint);
+        let mut route = instructions::swap_route(
+            &self.first.keys,
+            &self.second.keys,
+            trader.owner,
+            &self.hook_mint,
+            input_token_account,
+            output_token_account,
+            1_000_000_000,
+            1,
+        )
+        .unwrap();
+        route
+            .accounts
+            .extend(self.first.transfer_hook_accounts(bank));
+        route.accounts.extend(extra_accounts);
+        (route, trader.owner, output_token_account)
+    }
+}
+
+#[test]
+fn a_transfer_hook_swapping_in_a_pool_of_the_route_is_rejected() {
+    let mut bank = Bank::new();
+    let route = ReentrantRoute::setup(&mut bank);
+    let second = &route.second;
+
+    // the vaults of a transfer reach its hook read only, the hook swaps in the other pool
+    // of the route from the token accounts of its PDA
+    let payer = new_payer(&mut bank);
+    let hook_input = create_token_account(
+        &mut bank,
+        &payer,
+        &route.hook_trader,
+        &route.intermediate_mint,
+    );
+    let hook_output =
+        create_token_account(&mut bank, &payer, &route.hook_trader, &route.output_mint);
+    mint_to(
+        &mut bank,
+        &route.intermediate_mint,
+        &route.first.mint_authority,
+        &hook_input,
+        1_000_000,
+    );
+    let reentrant_swap = instructions::swap_base_input(
+        &second.keys,
+        route.hook_trader,
+        &route.intermediate_mint,
+        hook_input,
+        hook_output,
+        1_000_000,
+        1,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let (swap_route, trader, output_token_account) = route.route(&mut bank, &reentrant_swap);
+
+    // both pools are held from the settlement of the route until its checks
+    REENTRANT_INSTRUCTION.with(|reentrant| *reentrant.borrow_mut() = Some(reentrant_swap));
+    let vault_amounts = second.vault_amounts(&bank);
+    assert_anchor_error(
+        bank.process_transaction(&[swap_route.clone()], &[trader]),
+        ErrorCode::ReentrancyDetected,
+    );
+    assert_eq!(second.vault_amounts(&bank), vault_amounts);
+
+    // the same route goes through once the hook no longer swaps
+    REENTRANT_INSTRUCTION.with(|reentrant| *reentrant.borrow_mut() = None);
+    process(&mut bank, &[swap_route], &[trader]);
+    assert!(token_balance(&bank, &output_token_account) > 0);
+}
+
+#[test]
+fn a_transfer_hook_syncing_the_reserves_of_a_pool_of_the_route_is_rejected() {
+    let mut bank = Bank::new();
+    let route = ReentrantRoute::setup(&mut bank);
+    let sync_reserves = instructions::sync_reserves(&route.second.keys);
+    let (swap_route, trader, output_token_account) = route.route(&mut bank, &sync_reserves);
+
+    // the vaults of the second pool are mid route, their price is not recorded
+    REENTRANT_INSTRUCTION.with(|reentrant| *reentrant.borrow_mut() = Some(sync_reserves.clone()));
+    let observation_index = route.second.observation_state(&bank).observation_index;
+    assert_anchor_error(
+        bank.process_transaction(&[swap_route.clone()], &[trader]),
+        ErrorCode::ReentrancyDetected,
+    );
+    assert_eq!(
+        { route.second.observation_state(&bank).observation_index },
+        observation_index
+    );
+
+    REENTRANT_INSTRUCTION.with(|reentrant| *reentrant.borrow_mut() = None);
+    process(&mut bank, &[swap_route], &[trader]);
+    assert!(token_balance(&bank, &output_token_account) > 0);
+    // and are synced once the route released the pool
+    process(&mut bank, &[sync_reserves], &[]);
+}
@@ -0,0 +1,228 @@
+//! Native SOL wrapped into the WSOL side of a pool by the swaps and deposits, and
+//! unwrapped back to the owner by the swaps and withdrawals
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::InstructionData;
+use anchor_spl::token::spl_token;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap_client::instructions;
+use raydium_cp_swap_test_kit::fixtures::{
+    assert_anchor_error, create_mint, create_token_account, mint_to, new_payer, process,
+    token_balance,
+};
+use raydium_cp_swap_test_kit::{Bank, PoolFixture, PoolSetup, Trader};
+
+/// The position of the optional system program in the accounts of a swap
+const SWAP_SYSTEM_PROGRAM_INDEX: usize = 14;
+
+/// A pool of WSOL and a token of the default setup
+fn setup() -> (Bank, PoolFixture) {
+    let mut bank = Bank::new();
+    let setup = PoolSetup::default();
+    let mint_authority = new_payer(&mut bank);
+    let mint_b = create_mint(&mut bank, &mint_authority, setup.mint_b);
+    let fixture = PoolFixture::setup_with_mints(
+        &mut bank,
+        &setup,
+        mint_authority,
+        spl_token::native_mint::ID,
+        mint_b,
+    );
+    (bank, fixture)
+}
+
+/// The WSOL token account and the vault of the WSOL side of the pool, then the mint,
+/// token account and vault of the other side
+fn sides(fixture: &PoolFixture, trader: &Trader) -> ((Pubkey, Pubkey), (Pubkey, Pubkey, Pubkey)) {
+    let keys = &fixture.keys;
+    if keys.token_0_mint == spl_token::native_mint::ID {
+        (
+            (trader.token_0, keys.token_0_vault),
+            (keys.token_1_mint, trader.token_1, keys.token_1_vault),
+        )
+    } else {
+        (
+            (trader.token_1, keys.token_1_vault),
+            (keys.token_0_mint, trader.token_0, keys.token_0_vault),
+        )
+    }
+}
+
+/// A base input swap of the sdk wrapping or unwrapping the WSOL side, passed the system
+/// program to wrap with
+fn swap_wrapping_sol(
+    fixture: &PoolFixture,
+    trader: &Trader,
+    input_mint: &Pubkey,
+    amount_in: u64,
+) -> Instruction {
+    let (input_token_account, output_token_account) =
+        trader.swap_accounts(&fixture.keys, input_mint);
+    let mut instruction = instructions::swap_base_input(
+        &fixture.keys,
+        trader.owner,
+        input_mint,
+        input_token_account,
+        output_token_account,
+        amount_in,
+        1,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    instruction.data = raydium_cp_swap::instruction::SwapBaseInput {
+        amount_in,
+        minimum_amount_out: 1,
+        wrap_sol: true,
+        deadline: None,
+        sqrt_price_limit_x64: None,
+    }
+    .data();
+    instruction.accounts[SWAP_SYSTEM_PROGRAM_INDEX] =
+        AccountMeta::new_readonly(anchor_lang::system_program::ID, false);
+    instruction
+}
+
+#[test]
+fn swaps_wrap_the_native_sol_input_and_unwrap_the_native_sol_output() {
+    let (mut bank, fixture) = setup();
+    let sol_amount = 1_000_000_000;
+    let trader = fixture.new_trader(&mut bank, 0, 0);
+    let ((wsol_account, wsol_vault), (other_mint, other_account, other_vault)) =
+        sides(&fixture, &trader);
+
+    // native SOL in, the WSOL account only passes it through to the vault
+    let lamports = bank.lamports(&trader.owner);
+    let wsol_vault_amount = token_balance(&bank, &wsol_vault);
+    let other_vault_amount = token_balance(&bank, &other_vault);
+    let swap = swap_wrapping_sol(&fixture, &trader, &spl_token::native_mint::ID, sol_amount);
+    process(&mut bank, &[swap], &[trader.owner]);
+    assert_eq!(bank.lamports(&trader.owner), lamports - sol_amount);
+    assert_eq!(token_balance(&bank, &wsol_account), 0);
+    assert_eq!(
+        token_balance(&bank, &wsol_vault),
+        wsol_vault_amount + sol_amount
+    );
+    let amount_out = token_balance(&bank, &other_account);
+    assert!(amount_out > 0);
+    assert_eq!(
+        token_balance(&bank, &other_vault),
+        other_vault_amount - amount_out
+    );
+
+    // native SOL out, the WSOL account is closed to the trader with its rent
+    let lamports = bank.lamports(&trader.owner);
+    let rent = bank.lamports(&wsol_account);
+    let wsol_vault_amount = token_balance(&bank, &wsol_vault);
+    let swap = swap_wrapping_sol(&fixture, &trader, &other_mint, amount_out);
+    process(&mut bank, &[swap], &[trader.owner]);
+    let sol_out = wsol_vault_amount - token_balance(&bank, &wsol_vault);
+    assert!(sol_out > 0);
+    assert!(bank.account(&wsol_account).is_none());
+    assert_eq!(bank.lamports(&trader.owner), lamports + rent + sol_out);
+    assert_eq!(token_balance(&bank, &other_account), 0);
+}
+
+#[test]
+fn swaps_wrapping_sol_require_the_system_program() {
+    let (mut bank, fixture) = setup();
+    let trader = fixture.new_trader(&mut bank, 0, 0);
+    let mut swap = swap_wrapping_sol(&fixture, &trader, &spl_token::native_mint::ID, 1_000_000);
+    swap.accounts[SWAP_SYSTEM_PROGRAM_INDEX] =
+        AccountMeta::new_readonly(raydium_cp_swap::ID, false);
+    assert_anchor_error(
+        bank.process_transaction(&[swap], &[trader.owner]),
+        ErrorCode::InvalidInput,
+    );
+}
+
+#[test]
+fn deposits_wrap_and_withdrawals_unwrap_the_native_sol() {
+    let (mut bank, fixture) = setup();
+    let keys = &fixture.keys;
+    let trader = fixture.new_trader(&mut bank, 0, 0);
+    let ((wsol_account, wsol_vault), (other_mint, other_account, other_vault)) =
+        sides(&fixture, &trader);
+    // the lp token account is paid by the creator, the trader lamports only pay the deposit
+    let lp_token_account = create_token_account(
+        &mut bank,
+        &fixture.creator.owner,
+        &trader.owner,
+        &keys.lp_mint,
+    );
+    let lp_token_amount =
+        token_balance(&bank, &fixture.lp_token_account(&fixture.creator.owner)) / 1_000;
+    let other_amount = token_balance(&bank, &other_vault) / 100;
+    mint_to(
+        &mut bank,
+        &other_mint,
+        &fixture.mint_authority,
+        &other_account,
+        other_amount,
+    );
+
+    let mut deposit = instructions::deposit(
+        keys,
+        trader.owner,
+        lp_token_account,
+        trader.token_0,
+        trader.token_1,
+        false,
+        lp_token_amount,
+        u64::MAX,
+        u64::MAX,
+        None,
+        None,
+    );
+    deposit.data = raydium_cp_swap::instruction::Deposit {
+        lp_token_amount,
+        maximum_token_0_amount: u64::MAX,
+        maximum_token_1_amount: u64::MAX,
+        wrap_sol: true,
+        deadline: None,
+        price_slippage: None,
+    }
+    .data();
+    let lamports = bank.lamports(&trader.owner);
+    let wsol_vault_amount = token_balance(&bank, &wsol_vault);
+    process(&mut bank, &[deposit], &[trader.owner]);
+    let sol_in = token_balance(&bank, &wsol_vault) - wsol_vault_amount;
+    assert!(sol_in > 0);
+    assert_eq!(bank.lamports(&trader.owner), lamports - sol_in);
+    assert_eq!(token_balance(&bank, &wsol_account), 0);
+    assert_eq!(token_balance(&bank, &lp_token_account), lp_token_amount);
+
+    let mut withdraw = instructions::withdraw(
+        keys,
+        trader.owner,
+        lp_token_account,
+        trader.token_0,
+        trader.token_1,
+        false,
+        lp_token_amount,
+        0,
+        0,
+        None,
+        None,
+    );
+    withdraw.data = raydium_cp_swap::instruction::Withdraw {
+        lp_token_amount,
+        minimum_token_0_amount: 0,
+        minimum_token_1_amount: 0,
+        wrap_sol: true,
+        deadline: None,
+        price_slippage: None,
+    }
+    .data();
+    let lamports = bank.lamports(&trader.owner);
+    let rent = bank.lamports(&wsol_account);
+    let wsol_vault_amount = token_balance(&bank, &wsol_vault);
+    process(&mut bank, &[withdraw], &[trader.owner]);
+    let sol_out = wsol_vault_amount - token_balance(&bank, &wsol_vault);
+    assert!(sol_out > 0);
+    assert!(bank.account(&wsol_account).is_none());
+    assert_eq!(bank.lamports(&trader.owner), lamports + rent + sol_out);
+    assert_eq!(token_balance(&bank, &lp_token_account), 0);
+}
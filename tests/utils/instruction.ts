@@ -344,7 +344,15 @@ export async function initialize(
   }
   
   await program.methods
-    .initialize(initAmount.initAmount0, initAmount.initAmount1, new BN(0), 0, null)
+    .initialize(
+      initAmount.initAmount0,
+      initAmount.initAmount1,
+      new BN(0),
+      0,
+      null,
+      0,
+      new BN(0)
+    )
     .accountsPartial({
       creator: creator.publicKey,
       ammConfig: configAddress,